@@ -0,0 +1,47 @@
+//! 本地扫描基准测试
+//!
+//! 在临时目录中生成 100 个子目录、共 20,000 个文件，测量 `scan_local` 的吞吐量，
+//! 用于防止大文件夹首次扫描的性能退化。运行：`cargo bench --bench scan`
+use std::fs;
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use lightsync_lib::sync::filter::SyncFilter;
+use lightsync_lib::sync::scanner::scan_local;
+use lightsync_lib::sync::symlinks::SymlinkPolicy;
+
+const DIRS: usize = 100;
+const FILES_PER_DIR: usize = 200;
+
+/// 生成测试目录树（两层目录，每个叶子目录 FILES_PER_DIR 个小文件）
+fn create_tree() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("lightsync_bench_{}", uuid::Uuid::new_v4()));
+    for d in 0..DIRS {
+        let dir = root
+            .join(format!("group-{}", d % 10))
+            .join(format!("dir-{}", d));
+        fs::create_dir_all(&dir).unwrap();
+        for f in 0..FILES_PER_DIR {
+            fs::write(dir.join(format!("file-{}.txt", f)), b"lightsync").unwrap();
+        }
+    }
+    root
+}
+
+fn bench_scan(c: &mut Criterion) {
+    let root = create_tree();
+    let filter = SyncFilter::new(vec!["*.tmp".to_string()], Vec::new());
+
+    let mut group = c.benchmark_group("scan_local");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements((DIRS * FILES_PER_DIR) as u64));
+    group.bench_function("20k_files", |b| {
+        b.iter(|| scan_local(&root, &filter, SymlinkPolicy::Skip).unwrap())
+    });
+    group.finish();
+
+    let _ = fs::remove_dir_all(root);
+}
+
+criterion_group!(benches, bench_scan);
+criterion_main!(benches);
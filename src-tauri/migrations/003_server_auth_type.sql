@@ -0,0 +1,6 @@
+-- WebDAV 服务器认证方式
+-- 区分账户密码（basic）和通过 Nextcloud 登录流程获取的应用密码（app_password）
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN auth_type TEXT NOT NULL DEFAULT 'basic';
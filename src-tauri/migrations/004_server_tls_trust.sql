@@ -0,0 +1,9 @@
+-- WebDAV 服务器证书信任设置
+-- 支持自签名证书：接受无效证书，或固定证书 SHA-256 指纹
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN accept_invalid_certs INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE webdav_servers
+    ADD COLUMN pinned_cert_fingerprint TEXT;
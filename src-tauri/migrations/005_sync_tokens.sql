@@ -0,0 +1,9 @@
+-- 远程增量同步令牌（RFC 6578 sync-collection）
+-- 每个同步文件夹保存最近一次 REPORT 返回的 sync-token
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS remote_sync_tokens (
+    folder_id TEXT PRIMARY KEY,              -- 同步文件夹 ID（SyncFolderConfig.id）
+    sync_token TEXT NOT NULL,                -- 服务器返回的 sync-token
+    updated_at INTEGER NOT NULL              -- 最后更新时间
+);
@@ -0,0 +1,16 @@
+-- 本地回收站
+-- 同步引擎因远程删除而删除本地文件时，文件会被移动到应用管理的回收站目录
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS trash_items (
+    id TEXT PRIMARY KEY,                     -- 回收站条目 ID（UUID，同时作为回收站内的文件名）
+    folder_id TEXT NOT NULL,                 -- 同步文件夹 ID（SyncFolderConfig.id）
+    original_path TEXT NOT NULL,             -- 原始绝对路径
+    trash_path TEXT NOT NULL,                -- 回收站中的绝对路径
+    size INTEGER NOT NULL DEFAULT 0,         -- 大小（字节，目录为 0）
+    is_directory INTEGER NOT NULL DEFAULT 0, -- 是否为目录
+    deleted_at INTEGER NOT NULL              -- 删除时间
+);
+
+CREATE INDEX IF NOT EXISTS idx_trash_items_folder ON trash_items(folder_id);
+CREATE INDEX IF NOT EXISTS idx_trash_items_deleted_at ON trash_items(deleted_at);
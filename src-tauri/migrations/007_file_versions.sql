@@ -0,0 +1,15 @@
+-- 本地文件历史版本
+-- 下载覆盖本地已修改的文件前保存旧版本，内容按 SHA-256 存放在应用数据目录的 versions 目录中
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS file_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    folder_id TEXT NOT NULL,                 -- 同步文件夹 ID（SyncFolderConfig.id）
+    file_path TEXT NOT NULL,                 -- 文件绝对路径
+    hash TEXT NOT NULL,                      -- 内容 SHA-256（同时作为存储文件名）
+    size INTEGER NOT NULL,                   -- 文件大小
+    created_at INTEGER NOT NULL              -- 保存时间
+);
+
+CREATE INDEX IF NOT EXISTS idx_file_versions_path ON file_versions(folder_id, file_path);
+CREATE INDEX IF NOT EXISTS idx_file_versions_hash ON file_versions(hash);
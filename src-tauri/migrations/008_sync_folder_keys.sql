@@ -0,0 +1,10 @@
+-- 同步文件夹数值 ID 映射
+-- 同步文件夹配置使用 UUID 作为 ID，而 file_metadata / sync_logs / sync_sessions
+-- 使用整数 sync_folder_id，此表为每个 UUID 分配一个稳定的整数 ID
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS sync_folder_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,    -- 整数 ID（即 sync_folder_id）
+    folder_id TEXT NOT NULL UNIQUE,          -- 同步文件夹 ID（SyncFolderConfig.id）
+    created_at INTEGER NOT NULL DEFAULT (STRFTIME('%s', 'now'))
+);
@@ -0,0 +1,14 @@
+-- 服务器健康检查历史
+-- 后台任务定期对已启用的服务器执行连接测试，记录结果与延迟，每个服务器只保留最近的若干条
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS server_health (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id TEXT NOT NULL,                 -- 服务器 ID（webdav_servers.id）
+    checked_at INTEGER NOT NULL,             -- 检查时间
+    success INTEGER NOT NULL,                -- 是否连接成功
+    latency_ms INTEGER,                      -- 连接测试耗时（毫秒，失败时也记录）
+    error TEXT                               -- 失败原因
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_health_server ON server_health(server_id, checked_at);
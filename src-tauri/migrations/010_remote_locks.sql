@@ -0,0 +1,13 @@
+-- 远程文件锁（RFC 4918 LOCK）
+-- 开启上传加锁时，同步引擎在上传前锁定远程文件并保存锁令牌，
+-- 上传结束后释放；应用异常退出后，下次同步该文件夹时释放遗留的锁
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS remote_locks (
+    folder_id TEXT NOT NULL,                 -- 同步文件夹 ID（SyncFolderConfig.id）
+    remote_path TEXT NOT NULL,               -- 被锁定的远程路径
+    lock_token TEXT NOT NULL,                -- 服务器返回的锁令牌
+    owner TEXT NOT NULL,                     -- 锁的所有者描述
+    expires_at INTEGER NOT NULL,             -- 锁的过期时间
+    PRIMARY KEY (folder_id, remote_path)
+);
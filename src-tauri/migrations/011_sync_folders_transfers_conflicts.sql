@@ -0,0 +1,119 @@
+-- 同步文件夹、传输队列和冲突表
+-- sync_folders 镜像配置文件中的同步文件夹（配置仍是唯一来源），便于与传输、冲突记录建立外键；
+-- transfers 记录传输队列状态与断点续传信息；conflicts 记录等待用户处理的冲突
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS sync_folders
+(
+    -- 同步文件夹 ID（SyncFolderConfig.id）
+    id                  TEXT PRIMARY KEY NOT NULL,
+
+    -- 文件夹名称
+    name                TEXT             NOT NULL,
+
+    -- 本地路径
+    local_path          TEXT             NOT NULL,
+
+    -- 远程路径
+    remote_path         TEXT             NOT NULL,
+
+    -- 关联的服务器 ID
+    server_id           TEXT             NOT NULL REFERENCES webdav_servers (id) ON DELETE CASCADE,
+
+    -- 同步方向（bidirectional, upload-only, download-only）
+    sync_direction      TEXT             NOT NULL DEFAULT 'bidirectional',
+
+    -- 冲突解决策略（ask, local-wins, remote-wins, newer-wins）
+    conflict_resolution TEXT             NOT NULL DEFAULT 'newer-wins',
+
+    -- 是否启用自动同步（0: 否, 1: 是）
+    auto_sync           INTEGER          NOT NULL DEFAULT 1,
+
+    -- 记录创建时间（Unix 时间戳，秒）
+    created_at          INTEGER          NOT NULL DEFAULT (STRFTIME('%s', 'now')),
+
+    -- 记录更新时间（Unix 时间戳，秒）
+    updated_at          INTEGER          NOT NULL DEFAULT (STRFTIME('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_folders_server
+    ON sync_folders (server_id);
+
+CREATE TABLE IF NOT EXISTS transfers
+(
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- 所属同步文件夹（手动传输为 NULL）
+    sync_folder_id TEXT REFERENCES sync_folders (id) ON DELETE CASCADE,
+
+    -- 服务器 ID
+    server_id      TEXT    NOT NULL REFERENCES webdav_servers (id) ON DELETE CASCADE,
+
+    -- 传输方向（upload, download）
+    direction      TEXT    NOT NULL,
+
+    -- 本地路径
+    local_path     TEXT    NOT NULL,
+
+    -- 远程路径
+    remote_path    TEXT    NOT NULL,
+
+    -- 队列状态（queued, running, paused, completed, failed）
+    status         TEXT    NOT NULL DEFAULT 'queued',
+
+    -- 总字节数（未知时为 NULL）
+    bytes_total    INTEGER,
+
+    -- 已传输字节数
+    bytes_done     INTEGER NOT NULL DEFAULT 0,
+
+    -- 断点续传信息（如分块上传会话 ID 或下载的 ETag）
+    resume_token   TEXT,
+
+    -- 失败原因
+    error_message  TEXT,
+
+    created_at     INTEGER NOT NULL DEFAULT (STRFTIME('%s', 'now')),
+    updated_at     INTEGER NOT NULL DEFAULT (STRFTIME('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_transfers_status
+    ON transfers (status);
+
+CREATE INDEX IF NOT EXISTS idx_transfers_sync_folder
+    ON transfers (sync_folder_id);
+
+CREATE TABLE IF NOT EXISTS conflicts
+(
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- 所属同步文件夹
+    sync_folder_id  TEXT    NOT NULL REFERENCES sync_folders (id) ON DELETE CASCADE,
+
+    -- 相对于同步根目录的路径
+    file_path       TEXT    NOT NULL,
+
+    -- 本地文件大小与修改时间
+    local_size      INTEGER,
+    local_modified  INTEGER,
+
+    -- 远程文件大小与修改时间
+    remote_size     INTEGER,
+    remote_modified INTEGER,
+
+    -- 处理状态（pending, resolved）
+    status          TEXT    NOT NULL DEFAULT 'pending',
+
+    -- 采用的解决方式（keep-local, keep-remote, keep-both）
+    resolution      TEXT,
+
+    -- 发现时间与解决时间（Unix 时间戳，秒）
+    detected_at     INTEGER NOT NULL DEFAULT (STRFTIME('%s', 'now')),
+    resolved_at     INTEGER
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_conflicts_folder_path
+    ON conflicts (sync_folder_id, file_path);
+
+CREATE INDEX IF NOT EXISTS idx_conflicts_status
+    ON conflicts (status);
@@ -0,0 +1,7 @@
+-- 文件元数据的远程大小
+-- 上传时压缩的文件在服务器上的大小与本地不同，记录同步时的远程大小用于判断远程文件是否变化；
+-- 为空时远程大小与本地大小相同
+-- SQLite 版本
+
+ALTER TABLE file_metadata
+    ADD COLUMN remote_size INTEGER;
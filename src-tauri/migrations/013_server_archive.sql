@@ -0,0 +1,7 @@
+-- WebDAV 服务器归档（软删除）
+-- 归档的服务器被禁用并记录归档时间，保留期内可以恢复，连接测试与健康检查历史也一并保留；
+-- 超过保留期后由后台清理任务永久删除
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN deleted_at INTEGER;
@@ -0,0 +1,11 @@
+-- 远程目录快照指纹
+-- 不支持 sync-collection 的只下载文件夹定期轮询远程目录，
+-- 比对 ETag / getlastmodified 计算的指纹，只有发生变化时才触发同步
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS remote_snapshots (
+    folder_id TEXT PRIMARY KEY,              -- 同步文件夹 ID（SyncFolderConfig.id）
+    fingerprint TEXT,                        -- 上次同步成功时的远程目录指纹（尚未同步成功时为空）
+    entry_count INTEGER NOT NULL DEFAULT 0,  -- 指纹包含的远程条目数
+    checked_at INTEGER NOT NULL              -- 最后一次轮询时间
+);
@@ -0,0 +1,7 @@
+-- 文件元数据的本地文件标识
+-- 记录同步时本地文件的 inode（仅 Unix），用于识别本地重命名或移动的文件；
+-- 为空时表示平台不支持或尚未记录
+-- SQLite 版本
+
+ALTER TABLE file_metadata
+    ADD COLUMN inode INTEGER;
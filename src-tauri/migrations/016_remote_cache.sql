@@ -0,0 +1,19 @@
+-- 远程目录树缓存
+-- 保存每个同步文件夹最近一次列出的远程目录树（路径为解密后的真实相对路径），
+-- 每次同步生成计划时整体替换，同步过程中随上传、移动和远程删除更新，
+-- 前端浏览远程目录时直接读取，无需等待 PROPFIND
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS remote_cache (
+    folder_id TEXT NOT NULL,                 -- 同步文件夹 ID（SyncFolderConfig.id）
+    path TEXT NOT NULL,                      -- 相对于同步根目录的路径
+    parent TEXT NOT NULL,                    -- 父目录的相对路径（根目录下的条目为空字符串）
+    is_directory INTEGER NOT NULL DEFAULT 0, -- 是否为目录
+    size INTEGER NOT NULL DEFAULT 0,         -- 文件大小
+    modified INTEGER,                        -- 最后修改时间（Unix 时间戳，秒）
+    etag TEXT,                               -- ETag（服务器未返回或本地更新的条目为空）
+    cached_at INTEGER NOT NULL,              -- 写入缓存的时间
+    PRIMARY KEY (folder_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_remote_cache_parent ON remote_cache(folder_id, parent);
@@ -0,0 +1,6 @@
+-- WebDAV 服务器自定义请求头
+-- 部分服务商要求每个请求都携带额外的请求头（如 X-API-Key），以 JSON 对象保存（请求头名称 -> 值）
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN custom_headers TEXT NOT NULL DEFAULT '{}';
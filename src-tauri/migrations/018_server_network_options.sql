@@ -0,0 +1,10 @@
+-- WebDAV 服务器网络选项
+-- 部分 NAS 的 DNS 或 IPv6（AAAA 记录）配置有问题，允许按服务器强制使用 IPv4/IPv6，
+-- 或为服务器主机名指定固定的 IP 地址（跳过 DNS 解析）
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN ip_version TEXT NOT NULL DEFAULT 'any';
+
+ALTER TABLE webdav_servers
+    ADD COLUMN resolve_ip TEXT;
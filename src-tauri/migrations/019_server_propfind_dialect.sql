@@ -0,0 +1,7 @@
+-- WebDAV 服务器的 PROPFIND 请求方式
+-- 部分精简的 WebDAV 服务器（如某些路由器）无法处理指定属性的 PROPFIND 请求体，
+-- 客户端在 400/422 时依次改用 allprop 和空请求体重试，并记住可用的方式
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN propfind_dialect TEXT NOT NULL DEFAULT 'standard';
@@ -0,0 +1,10 @@
+-- WebDAV 服务器的连接超时与读取超时
+-- timeout 只限制单个非流式请求的总时长；上传和下载不限制总时长，
+-- 只在建立连接超时或连续 read_timeout 秒没有收发数据时中止
+-- SQLite 版本
+
+ALTER TABLE webdav_servers
+    ADD COLUMN connect_timeout INTEGER NOT NULL DEFAULT 10;
+
+ALTER TABLE webdav_servers
+    ADD COLUMN read_timeout INTEGER NOT NULL DEFAULT 60;
@@ -0,0 +1,6 @@
+-- 同步日志记录传输的平均速度（字节/秒）
+-- 只有完成的上传、下载记录速度，其他操作为 NULL
+-- SQLite 版本
+
+ALTER TABLE sync_logs
+    ADD COLUMN bytes_per_sec INTEGER;
@@ -0,0 +1,13 @@
+-- 文件名映射
+-- 远程文件名在本地不合法（按 reserved_name_policy 替换或编码）或大小写冲突（以副本保存）时，
+-- 记录本地文件名对应的原始远程路径。每次同步生成计划时整体替换，
+-- 远程文件被删除或移走后，本地的重命名文件仍然以原来的远程文件名上传
+-- SQLite 版本
+
+CREATE TABLE IF NOT EXISTS name_mappings (
+    folder_id TEXT NOT NULL,    -- 同步文件夹 ID（SyncFolderConfig.id）
+    local_path TEXT NOT NULL,   -- 本地相对路径（清理后的文件名）
+    remote_path TEXT NOT NULL,  -- 对应的远程相对路径（原始文件名）
+    created_at INTEGER NOT NULL, -- 写入时间
+    PRIMARY KEY (folder_id, local_path)
+);
@@ -0,0 +1,376 @@
+/// 主密码应用锁模块
+///
+/// 可选功能：设置主密码后，查看服务器、导出配置等敏感命令在调用 `unlock_app` 之前返回
+/// `SyncError::Locked`，一段时间内没有访问敏感命令时自动重新锁定。
+///
+/// 主密码本身不保存，应用数据目录下的 `app_lock.json` 只记录：
+/// - Argon2 密码哈希（PHC 格式），用于校验主密码
+/// - 密钥派生盐，解锁时用 Argon2 从主密码派生密钥，加密本地缓存的服务器密码（见 `credential_store`）
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::constants::APP_LOCK_FILE;
+use crate::{Result, SyncError};
+
+/// 盐长度（字节）
+const SALT_LEN: usize = 16;
+
+/// 派生密钥长度（字节）
+const KEY_LEN: usize = 32;
+
+/// 主密码最短长度
+const MIN_PASSWORD_LEN: usize = 8;
+
+/// 持久化的主密码信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MasterPassword {
+    /// Argon2 密码哈希（PHC 格式）
+    password_hash: String,
+    /// 密钥派生盐（Base64）
+    key_salt: String,
+}
+
+/// 应用锁状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    /// 是否设置了主密码
+    pub enabled: bool,
+    /// 当前是否处于锁定状态
+    pub locked: bool,
+}
+
+/// 运行时状态
+struct LockState {
+    /// 主密码信息（未设置时为 None）
+    master: Option<MasterPassword>,
+    /// 最近一次访问敏感命令的时间（锁定时为 None）
+    last_activity: Option<Instant>,
+}
+
+/// 主密码应用锁
+pub struct AppLock {
+    /// 主密码信息文件路径
+    path: PathBuf,
+    /// 运行时状态
+    state: Mutex<LockState>,
+}
+
+impl AppLock {
+    /// 打开应用锁，设置了主密码时初始为锁定状态
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let master = match std::fs::read_to_string(&path) {
+            Ok(content) => Some(
+                serde_json::from_str(&content)
+                    .map_err(|e| SyncError::ConfigError(format!("Invalid app lock file: {}", e)))?,
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(LockState {
+                master,
+                last_activity: None,
+            }),
+        })
+    }
+
+    /// 在应用数据目录下打开应用锁
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        Self::open(app_dir.join(APP_LOCK_FILE))
+    }
+
+    /// 是否设置了主密码
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().unwrap().master.is_some()
+    }
+
+    /// 获取锁状态
+    ///
+    /// # 参数
+    /// - auto_lock: 自动锁定时间（None 表示不自动锁定），超时后先锁定再返回状态
+    pub fn status(&self, auto_lock: Option<Duration>) -> AppLockStatus {
+        let mut state = self.state.lock().unwrap();
+        Self::expire(&mut state, auto_lock);
+
+        AppLockStatus {
+            enabled: state.master.is_some(),
+            locked: state.master.is_some() && state.last_activity.is_none(),
+        }
+    }
+
+    /// 检查应用是否已解锁，并刷新最近访问时间
+    ///
+    /// # 返回
+    /// - Ok(()): 未设置主密码，或已解锁
+    /// - Err(SyncError::Locked): 已锁定（包括超时自动锁定）
+    pub fn ensure_unlocked(&self, auto_lock: Option<Duration>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.master.is_none() {
+            return Ok(());
+        }
+
+        Self::expire(&mut state, auto_lock);
+        match state.last_activity {
+            Some(_) => {
+                state.last_activity = Some(Instant::now());
+                Ok(())
+            }
+            None => Err(SyncError::Locked(
+                "Unlock the application with the master password first".to_string(),
+            )),
+        }
+    }
+
+    /// 使用主密码解锁
+    ///
+    /// # 返回
+    /// - Ok(String): 从主密码派生的密钥（Base64），用于解密本地缓存的凭据
+    /// - Err(SyncError::AuthError): 主密码错误
+    /// - Err(SyncError::ValidationError): 未设置主密码
+    pub fn unlock(&self, password: &str) -> Result<String> {
+        let mut state = self.state.lock().unwrap();
+        let master = state
+            .master
+            .as_ref()
+            .ok_or_else(|| SyncError::ValidationError("Master password is not set".to_string()))?;
+
+        verify_password(&master.password_hash, password)?;
+        let key = derive_key(password, &master.key_salt)?;
+        state.last_activity = Some(Instant::now());
+        Ok(key)
+    }
+
+    /// 立即锁定
+    pub fn lock(&self) {
+        self.state.lock().unwrap().last_activity = None;
+    }
+
+    /// 设置或修改主密码
+    ///
+    /// 设置成功后保持解锁状态
+    ///
+    /// # 参数
+    /// - current: 当前主密码（首次设置时为 None）
+    /// - new_password: 新主密码（至少 8 个字符）
+    ///
+    /// # 返回
+    /// - Ok(String): 从新主密码派生的密钥（Base64）
+    /// - Err(SyncError::AuthError): 当前主密码错误
+    /// - Err(SyncError::ValidationError): 新主密码太短，或修改时未提供当前主密码
+    pub fn set_password(&self, current: Option<&str>, new_password: &str) -> Result<String> {
+        if new_password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(SyncError::ValidationError(format!(
+                "Master password must be at least {} characters",
+                MIN_PASSWORD_LEN
+            )));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(master) = &state.master {
+            let current = current.ok_or_else(|| {
+                SyncError::ValidationError("Current master password is required".to_string())
+            })?;
+            verify_password(&master.password_hash, current)?;
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let master = MasterPassword {
+            password_hash: hash_password(new_password)?,
+            key_salt: BASE64.encode(salt),
+        };
+        let key = derive_key(new_password, &master.key_salt)?;
+
+        write_file(&self.path, &master)?;
+        state.master = Some(master);
+        state.last_activity = Some(Instant::now());
+        Ok(key)
+    }
+
+    /// 移除主密码
+    ///
+    /// # 返回
+    /// - Ok(()): 已移除
+    /// - Err(SyncError::AuthError): 主密码错误
+    /// - Err(SyncError::ValidationError): 未设置主密码
+    pub fn remove_password(&self, password: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let master = state
+            .master
+            .as_ref()
+            .ok_or_else(|| SyncError::ValidationError("Master password is not set".to_string()))?;
+        verify_password(&master.password_hash, password)?;
+
+        std::fs::remove_file(&self.path)?;
+        state.master = None;
+        state.last_activity = None;
+        Ok(())
+    }
+
+    /// 超过自动锁定时间没有访问时锁定
+    fn expire(state: &mut LockState, auto_lock: Option<Duration>) {
+        if let (Some(last_activity), Some(auto_lock)) = (state.last_activity, auto_lock) {
+            if last_activity.elapsed() >= auto_lock {
+                state.last_activity = None;
+            }
+        }
+    }
+}
+
+/// 计算主密码的 Argon2 哈希
+fn hash_password(password: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to encode salt: {}", e)))?;
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| SyncError::ConfigError(format!("Failed to hash master password: {}", e)))
+}
+
+/// 校验主密码
+fn verify_password(password_hash: &str, password: &str) -> Result<()> {
+    let hash = PasswordHash::new(password_hash)
+        .map_err(|e| SyncError::ConfigError(format!("Invalid master password hash: {}", e)))?;
+
+    Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .map_err(|_| SyncError::AuthError("Wrong master password".to_string()))
+}
+
+/// 从主密码派生加密密钥
+fn derive_key(password: &str, key_salt: &str) -> Result<String> {
+    let salt = BASE64
+        .decode(key_salt)
+        .map_err(|e| SyncError::ConfigError(format!("Invalid key salt: {}", e)))?;
+
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), &salt, &mut key)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to derive key: {}", e)))?;
+    Ok(BASE64.encode(key))
+}
+
+/// 写入主密码信息文件
+fn write_file(path: &Path, master: &MasterPassword) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(master)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn create_test_path() -> (PathBuf, PathBuf) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        let path = test_dir.join("app_lock.json");
+        (test_dir, path)
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let (test_dir, path) = create_test_path();
+
+        let lock = AppLock::open(&path).unwrap();
+        assert!(!lock.is_enabled());
+        assert!(lock.ensure_unlocked(Some(Duration::ZERO)).is_ok());
+        assert!(matches!(
+            lock.unlock("password"),
+            Err(SyncError::ValidationError(_))
+        ));
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_set_unlock_and_remove() {
+        let (test_dir, path) = create_test_path();
+
+        let lock = AppLock::open(&path).unwrap();
+        assert!(matches!(
+            lock.set_password(None, "short"),
+            Err(SyncError::ValidationError(_))
+        ));
+        let key = lock.set_password(None, "correct horse").unwrap();
+        assert!(lock.ensure_unlocked(None).is_ok());
+
+        // 重新打开后处于锁定状态
+        let lock = AppLock::open(&path).unwrap();
+        assert_eq!(
+            lock.status(None),
+            AppLockStatus {
+                enabled: true,
+                locked: true
+            }
+        );
+        assert!(matches!(
+            lock.ensure_unlocked(None),
+            Err(SyncError::Locked(_))
+        ));
+        assert!(matches!(
+            lock.unlock("wrong password"),
+            Err(SyncError::AuthError(_))
+        ));
+
+        // 同一主密码派生的密钥不变
+        assert_eq!(lock.unlock("correct horse").unwrap(), key);
+        assert!(lock.ensure_unlocked(None).is_ok());
+
+        // 修改主密码需要当前主密码
+        assert!(lock.set_password(None, "battery staple").is_err());
+        assert_ne!(
+            lock.set_password(Some("correct horse"), "battery staple")
+                .unwrap(),
+            key
+        );
+
+        lock.remove_password("battery staple").unwrap();
+        assert!(!path.exists());
+        assert!(!lock.is_enabled());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_auto_lock() {
+        let (test_dir, path) = create_test_path();
+
+        let lock = AppLock::open(&path).unwrap();
+        lock.set_password(None, "correct horse").unwrap();
+        assert!(lock.ensure_unlocked(Some(Duration::from_secs(60))).is_ok());
+        assert!(matches!(
+            lock.ensure_unlocked(Some(Duration::ZERO)),
+            Err(SyncError::Locked(_))
+        ));
+        assert!(lock.status(None).locked);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
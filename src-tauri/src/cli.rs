@@ -0,0 +1,333 @@
+/// 命令行参数模块
+///
+/// 支持通过命令行触发一次性同步，便于在 cron 或任务计划程序中定时执行：
+///
+/// ```text
+/// lightsync --sync <folder-id|all> [--headless]
+/// ```
+///
+/// - `--sync`: 启动后同步指定文件夹（`all` 表示全部文件夹）
+/// - `--headless`: 不打开窗口，同步结束后向 stdout 输出 JSON 汇总并以对应的退出码退出
+///
+/// LightSync 已在运行时，参数会转发给已运行的实例执行同步，当前进程直接以 0 退出；
+/// 无界面模式下已运行的实例无法返回同步结果，因此不转发，输出失败的汇总并以退出码 3 退出
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::database::SyncSession;
+use crate::error::ErrorPayload;
+use crate::{Result, SyncError};
+
+/// 命令行用法说明
+pub const USAGE: &str = "Usage: lightsync [--sync <folder-id|all>] [--headless]
+
+Options:
+  --sync <folder-id|all>  Sync the given folder (or all folders) after startup
+  --headless              Run the sync without opening a window, print a JSON
+                          summary to stdout and exit (requires --sync)
+  -h, --help              Print this help
+
+Exit codes:
+  0  All folders synced successfully
+  1  At least one folder failed to sync
+  2  Invalid arguments
+  3  LightSync is already running (--headless only)";
+
+/// 退出码：全部同步成功
+pub const EXIT_SUCCESS: i32 = 0;
+
+/// 退出码：有文件夹同步失败
+pub const EXIT_SYNC_FAILED: i32 = 1;
+
+/// 退出码：参数错误
+pub const EXIT_USAGE: i32 = 2;
+
+/// 退出码：无界面模式下已有实例在运行
+pub const EXIT_ALREADY_RUNNING: i32 = 3;
+
+/// 要同步的文件夹
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncTarget {
+    /// 全部同步文件夹
+    All,
+    /// 指定 ID 的同步文件夹
+    Folder(String),
+}
+
+impl SyncTarget {
+    fn parse(value: &str) -> Self {
+        if value == "all" {
+            SyncTarget::All
+        } else {
+            SyncTarget::Folder(value.to_string())
+        }
+    }
+}
+
+/// 命令行选项
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliOptions {
+    /// 启动后要同步的文件夹
+    pub sync: Option<SyncTarget>,
+    /// 是否以无界面模式运行
+    pub headless: bool,
+    /// 是否只输出用法说明
+    pub help: bool,
+}
+
+impl CliOptions {
+    /// 解析命令行参数
+    ///
+    /// # 参数
+    /// - args: 命令行参数（不含程序路径）
+    ///
+    /// # 返回
+    /// - Ok(CliOptions): 解析后的选项
+    /// - Err(SyncError::ValidationError): 未知参数、缺少参数值，或 `--headless` 未指定 `--sync`
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut options = CliOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--sync" => {
+                    let value = args.next().filter(|v| !v.starts_with('-')).ok_or_else(|| {
+                        SyncError::ValidationError("--sync requires a folder id or 'all'".into())
+                    })?;
+                    options.sync = Some(SyncTarget::parse(&value));
+                }
+                "--headless" => options.headless = true,
+                "-h" | "--help" => options.help = true,
+                // macOS 从 Finder 启动时附加的进程序列号
+                arg if arg.starts_with("-psn_") => {}
+                arg => {
+                    if let Some(value) = arg.strip_prefix("--sync=") {
+                        options.sync = Some(SyncTarget::parse(value));
+                    } else {
+                        return Err(SyncError::ValidationError(format!(
+                            "Unknown argument: {}",
+                            arg
+                        )));
+                    }
+                }
+            }
+        }
+
+        if options.headless && options.sync.is_none() {
+            return Err(SyncError::ValidationError(
+                "--headless requires --sync".to_string(),
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+/// 单个文件夹的同步结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncResult {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 同步会话（同步失败时为 None）
+    pub session: Option<SyncSession>,
+    /// 错误信息（同步成功时为 None）
+    pub error: Option<ErrorPayload>,
+}
+
+/// 一次命令行同步的汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+    /// 是否全部同步成功
+    pub success: bool,
+    /// 各文件夹的同步结果
+    pub folders: Vec<FolderSyncResult>,
+}
+
+impl SyncSummary {
+    /// 根据各文件夹的结果生成汇总
+    pub fn new(folders: Vec<FolderSyncResult>) -> Self {
+        Self {
+            success: folders.iter().all(|f| f.error.is_none()),
+            folders,
+        }
+    }
+
+    /// 对应的进程退出码
+    pub fn exit_code(&self) -> i32 {
+        if self.success {
+            EXIT_SUCCESS
+        } else {
+            EXIT_SYNC_FAILED
+        }
+    }
+}
+
+/// 输出 JSON 汇总（无界面模式）
+pub fn print_summary(summary: &SyncSummary) {
+    match serde_json::to_string_pretty(summary) {
+        Ok(json) => println!("{}", json),
+        Err(e) => tracing::error!(error = %e, "序列化同步汇总失败"),
+    }
+}
+
+/// 无界面模式下已有实例在运行：不执行同步，输出失败的汇总
+///
+/// # 返回
+/// 进程退出码（`EXIT_ALREADY_RUNNING`）
+pub fn report_already_running(target: &SyncTarget) -> i32 {
+    let folder_id = match target {
+        SyncTarget::All => "all".to_string(),
+        SyncTarget::Folder(id) => id.clone(),
+    };
+    let error = SyncError::Interrupted(
+        "LightSync is already running; quit it or sync from the running app".to_string(),
+    );
+    print_summary(&SyncSummary::new(vec![FolderSyncResult {
+        folder_id,
+        session: None,
+        error: Some(ErrorPayload::from(&error)),
+    }]));
+    EXIT_ALREADY_RUNNING
+}
+
+/// 附加到父进程的控制台
+///
+/// Windows 发布版本使用 GUI 子系统，从终端或任务计划程序启动时没有控制台，
+/// 附加后用法说明与 JSON 汇总才能输出到终端
+#[cfg(windows)]
+pub fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // SAFETY: 没有参数指针；没有父进程控制台（如从资源管理器启动）时调用失败，不影响后续运行
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// 附加到父进程的控制台（只有 Windows 需要）
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+/// 依次同步指定的文件夹
+///
+/// 单个文件夹失败不影响其他文件夹
+pub async fn run_sync(app: &AppHandle, target: &SyncTarget) -> SyncSummary {
+    let folder_ids = match target {
+        SyncTarget::Folder(id) => vec![id.clone()],
+        SyncTarget::All => match crate::config::get_config(app.clone()).await {
+            Ok(config) => config.sync_folders.into_iter().map(|f| f.id).collect(),
+            Err(e) => {
+                return SyncSummary::new(vec![FolderSyncResult {
+                    folder_id: "all".to_string(),
+                    session: None,
+                    error: Some(ErrorPayload::from(&e)),
+                }])
+            }
+        },
+    };
+
+    let mut folders = Vec::with_capacity(folder_ids.len());
+    for folder_id in folder_ids {
+        let result = crate::commands::sync::run_manual_sync(app, &folder_id).await;
+        if let Err(e) = &result {
+            tracing::warn!(folder_id = %folder_id, error = %e, "命令行同步失败");
+        }
+        folders.push(FolderSyncResult {
+            folder_id,
+            error: result.as_ref().err().map(ErrorPayload::from),
+            session: result.ok(),
+        });
+    }
+
+    SyncSummary::new(folders)
+}
+
+/// 在后台执行命令行请求的同步
+///
+/// 先等待启动恢复完成，避免恢复清理临时文件时与新的同步冲突。
+/// 无界面模式下同步结束后输出 JSON 汇总并退出应用
+///
+/// # 参数
+/// - options: 命令行选项（未指定 `--sync` 时不执行任何操作）
+/// - recovery: 启动恢复任务
+pub fn spawn_sync(
+    app: AppHandle,
+    options: CliOptions,
+    recovery: tauri::async_runtime::JoinHandle<()>,
+) {
+    let Some(target) = options.sync else {
+        return;
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let _ = recovery.await;
+        let summary = run_sync(&app, &target).await;
+
+        if options.headless {
+            print_summary(&summary);
+            app.exit(summary.exit_code());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOptions> {
+        CliOptions::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(parse(&[]).unwrap(), CliOptions::default());
+
+        let options = parse(&["--sync", "all", "--headless"]).unwrap();
+        assert_eq!(options.sync, Some(SyncTarget::All));
+        assert!(options.headless);
+
+        let options = parse(&["--sync=folder-1", "-psn_0_12345"]).unwrap();
+        assert_eq!(
+            options.sync,
+            Some(SyncTarget::Folder("folder-1".to_string()))
+        );
+        assert!(!options.headless);
+
+        assert!(parse(&["--help"]).unwrap().help);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--headless"]).is_err());
+        assert!(parse(&["--sync"]).is_err());
+        assert!(parse(&["--sync", "--headless"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_summary_exit_code() {
+        let ok = FolderSyncResult {
+            folder_id: "folder-1".to_string(),
+            session: None,
+            error: None,
+        };
+        let failed = FolderSyncResult {
+            folder_id: "folder-2".to_string(),
+            session: None,
+            error: Some(ErrorPayload::from(&SyncError::NotFound(
+                "folder-2".to_string(),
+            ))),
+        };
+
+        assert_eq!(SyncSummary::new(vec![]).exit_code(), EXIT_SUCCESS);
+        assert_eq!(SyncSummary::new(vec![ok.clone()]).exit_code(), EXIT_SUCCESS);
+
+        let summary = SyncSummary::new(vec![ok, failed]);
+        assert_eq!(summary.exit_code(), EXIT_SYNC_FAILED);
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(json.contains("\"success\":false"));
+        assert!(json.contains("\"errorCode\":\"NOT_FOUND\""));
+    }
+}
@@ -0,0 +1,29 @@
+/// 服务器活动命令模块
+///
+/// 提供 Nextcloud 服务器活动流的查询命令
+use tauri::State;
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::webdav::activity::{self, ServerActivityFeed};
+use crate::webdav::factory::WebDavClientFactory;
+
+/// 获取服务器上的文件活动（谁在何时修改了哪个文件）
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - since: 只返回该活动 ID 之后的活动（可选，通常传入上次返回的 lastActivityId）
+///
+/// # 返回
+/// - 成功：返回活动列表；服务器不是 Nextcloud 或未启用 activity 应用时 supported 为 false
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_server_activity(
+    server_id: String,
+    since: Option<i64>,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<ServerActivityFeed> {
+    let (server, client) = clients.get(&db, &server_id).await?;
+    activity::fetch(&client, &server.server_type, since).await
+}
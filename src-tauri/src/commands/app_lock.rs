@@ -0,0 +1,154 @@
+/// 主密码应用锁命令模块
+///
+/// 设置、修改、移除主密码以及锁定/解锁应用。
+/// 主密码派生的密钥同时用作加密凭据文件（见 `crate::webdav::credential_store`）的口令：
+/// 设置或移除主密码时重新加密已保存的密码，锁定时清除内存中已解密的密码
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_lock::{AppLock, AppLockStatus};
+use crate::config::get_config;
+use crate::error::Result;
+use crate::webdav::credential_store;
+use crate::webdav::keyring::KeyringManager;
+
+/// 读取配置中的自动锁定时间
+async fn auto_lock_duration(app: &AppHandle) -> Result<Option<Duration>> {
+    let minutes = get_config(app.clone()).await?.auto_lock_minutes;
+    Ok((minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60)))
+}
+
+/// 锁定加密凭据文件
+fn lock_credentials() {
+    if let Some(store) = KeyringManager::fallback() {
+        store.lock();
+    }
+}
+
+/// 检查应用是否已解锁，供敏感命令在执行前调用
+///
+/// 超过自动锁定时间没有访问时同时锁定加密凭据文件
+///
+/// # 返回
+/// - Ok(()): 未设置主密码，或已解锁
+/// - Err(SyncError::Locked): 已锁定
+pub async fn ensure_unlocked(app: &AppHandle) -> Result<()> {
+    let auto_lock = auto_lock_duration(app).await?;
+    let result = app.state::<AppLock>().ensure_unlocked(auto_lock);
+    if result.is_err() {
+        lock_credentials();
+    }
+    result
+}
+
+/// 获取应用锁状态
+///
+/// # 返回
+/// - 成功：返回是否设置了主密码以及是否已锁定
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_app_lock_status(
+    app: AppHandle,
+    app_lock: State<'_, AppLock>,
+) -> Result<AppLockStatus> {
+    let status = app_lock.status(auto_lock_duration(&app).await?);
+    if status.locked {
+        lock_credentials();
+    }
+    Ok(status)
+}
+
+/// 使用主密码解锁应用
+///
+/// # 参数
+/// - password: 主密码
+///
+/// # 返回
+/// - 成功：返回解锁后的状态
+/// - 失败：主密码错误或未设置主密码
+#[tauri::command]
+pub async fn unlock_app(password: String, app_lock: State<'_, AppLock>) -> Result<AppLockStatus> {
+    let key = app_lock.unlock(&password)?;
+    if let Some(store) = KeyringManager::fallback() {
+        store.unlock(key);
+    }
+
+    tracing::info!("应用已解锁");
+    Ok(app_lock.status(None))
+}
+
+/// 立即锁定应用
+///
+/// # 返回
+/// - 成功：返回锁定后的状态
+#[tauri::command]
+pub async fn lock_app(app_lock: State<'_, AppLock>) -> Result<AppLockStatus> {
+    app_lock.lock();
+    lock_credentials();
+
+    tracing::info!("应用已锁定");
+    Ok(app_lock.status(None))
+}
+
+/// 设置或修改主密码
+///
+/// # 参数
+/// - current_password: 当前主密码（首次设置时为 None）
+/// - new_password: 新主密码（至少 8 个字符）
+///
+/// # 返回
+/// - 成功：返回设置后的状态（保持解锁）
+/// - 失败：当前主密码错误、新主密码太短或重新加密凭据失败
+#[tauri::command]
+pub async fn set_master_password(
+    current_password: Option<String>,
+    new_password: String,
+    app_lock: State<'_, AppLock>,
+) -> Result<AppLockStatus> {
+    let store = KeyringManager::fallback();
+
+    // 修改主密码时先用当前主密码解锁凭据文件，才能使用新密钥重新加密
+    if let Some(current) = current_password.as_deref() {
+        if app_lock.is_enabled() {
+            let current_key = app_lock.unlock(current)?;
+            if let Some(store) = store {
+                store.unlock(current_key);
+            }
+        }
+    }
+
+    let key = app_lock.set_password(current_password.as_deref(), &new_password)?;
+    if let Some(store) = store {
+        store.rekey(key)?;
+    }
+
+    tracing::info!("已设置主密码");
+    Ok(app_lock.status(None))
+}
+
+/// 移除主密码
+///
+/// 加密凭据文件改回使用默认口令加密
+///
+/// # 参数
+/// - password: 当前主密码
+///
+/// # 返回
+/// - 成功：返回移除后的状态
+/// - 失败：主密码错误或未设置主密码
+#[tauri::command]
+pub async fn remove_master_password(
+    password: String,
+    app_lock: State<'_, AppLock>,
+) -> Result<AppLockStatus> {
+    let key = app_lock.unlock(&password)?;
+    if let Some(store) = KeyringManager::fallback() {
+        store.unlock(key);
+        store.rekey(credential_store::default_passphrase())?;
+    }
+    app_lock.remove_password(&password)?;
+
+    tracing::info!("已移除主密码");
+    Ok(app_lock.status(None))
+}
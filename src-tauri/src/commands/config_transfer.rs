@@ -0,0 +1,367 @@
+/// 配置导入导出命令模块
+///
+/// 将 AppConfig、webdav_servers 表中的服务器配置以及（可选的）Keyring 密码打包为单个 JSON 文件，
+/// 用于把 LightSync 迁移到另一台机器。
+///
+/// - 未提供口令时导出为明文 JSON，此时不允许包含密码
+/// - 提供口令时整个配置包使用口令加密（见 `crate::crypto`）
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::config::{get_config, update_config, AppConfig};
+use crate::constants::{APP_VERSION, CONFIG_EXPORT_FORMAT, CONFIG_EXPORT_VERSION};
+use crate::crypto::{self, EncryptedBlob};
+use crate::database::{Database, WebDavServerConfig};
+use crate::error::{Result, SyncError};
+use crate::webdav::db;
+use crate::webdav::keyring::KeyringManager;
+
+// ========== 数据结构 ==========
+
+/// 导出的配置内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundle {
+    /// 应用配置
+    pub app_config: AppConfig,
+
+    /// WebDAV 服务器配置（webdav_servers 表）
+    pub webdav_servers: Vec<WebDavServerConfig>,
+
+    /// 服务器密码（服务器 ID -> 密码），仅在加密导出时包含
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub passwords: HashMap<String, String>,
+}
+
+/// 导出文件结构
+///
+/// `bundle` 与 `encrypted` 二者只有一个存在
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigExportFile {
+    /// 文件格式标识
+    pub format: String,
+
+    /// 文件格式版本
+    pub version: u32,
+
+    /// 导出时的应用版本
+    pub app_version: String,
+
+    /// 导出时间（Unix 时间戳，秒）
+    pub exported_at: i64,
+
+    /// 明文配置内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<ConfigBundle>,
+
+    /// 加密后的配置内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<EncryptedBlob>,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigExportSummary {
+    /// 导出文件路径
+    pub path: String,
+
+    /// 导出的服务器数量
+    pub servers: usize,
+
+    /// 导出的密码数量
+    pub passwords: usize,
+
+    /// 是否已加密
+    pub encrypted: bool,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImportSummary {
+    /// 新增的服务器数量
+    pub servers_added: usize,
+
+    /// 覆盖更新的服务器数量
+    pub servers_updated: usize,
+
+    /// 写入 Keyring 的密码数量
+    pub passwords_imported: usize,
+
+    /// 导入的同步文件夹数量
+    pub sync_folders: usize,
+}
+
+// ========== 打包与解包 ==========
+
+/// 将配置内容封装为导出文件
+///
+/// # 参数
+/// - bundle: 配置内容
+/// - passphrase: 加密口令（None 表示明文导出）
+///
+/// # 返回
+/// - Ok(ConfigExportFile): 导出文件
+/// - Err(SyncError::ConfigError): 明文导出时包含密码，或加密失败
+pub fn seal_bundle(bundle: ConfigBundle, passphrase: Option<&str>) -> Result<ConfigExportFile> {
+    let mut file = ConfigExportFile {
+        format: CONFIG_EXPORT_FORMAT.to_string(),
+        version: CONFIG_EXPORT_VERSION,
+        app_version: APP_VERSION.to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        bundle: None,
+        encrypted: None,
+    };
+
+    match passphrase {
+        Some(passphrase) => {
+            let plaintext = serde_json::to_vec(&bundle)?;
+            file.encrypted = Some(crypto::encrypt(&plaintext, passphrase)?);
+        }
+        None => {
+            if !bundle.passwords.is_empty() {
+                return Err(SyncError::ConfigError(
+                    "Passwords can only be exported with a passphrase".to_string(),
+                ));
+            }
+            file.bundle = Some(bundle);
+        }
+    }
+
+    Ok(file)
+}
+
+/// 从导出文件中取出配置内容
+///
+/// # 参数
+/// - file: 导出文件
+/// - passphrase: 解密口令（加密导出时必须提供）
+///
+/// # 返回
+/// - Ok(ConfigBundle): 配置内容
+/// - Err(SyncError::ConfigError): 文件格式或版本不受支持、缺少口令
+/// - Err(SyncError::AuthError): 口令错误
+pub fn open_bundle(file: ConfigExportFile, passphrase: Option<&str>) -> Result<ConfigBundle> {
+    if file.format != CONFIG_EXPORT_FORMAT {
+        return Err(SyncError::ConfigError(format!(
+            "Not a LightSync config export: {}",
+            file.format
+        )));
+    }
+
+    if file.version > CONFIG_EXPORT_VERSION {
+        return Err(SyncError::ConfigError(format!(
+            "Unsupported config export version: {}",
+            file.version
+        )));
+    }
+
+    match (file.bundle, file.encrypted) {
+        (_, Some(encrypted)) => {
+            let passphrase = passphrase.ok_or_else(|| {
+                SyncError::ConfigError(
+                    "This config export is encrypted, passphrase required".to_string(),
+                )
+            })?;
+            let plaintext = crypto::decrypt(&encrypted, passphrase)?;
+            Ok(serde_json::from_slice(&plaintext)?)
+        }
+        (Some(bundle), None) => Ok(bundle),
+        (None, None) => Err(SyncError::ConfigError(
+            "Config export contains no data".to_string(),
+        )),
+    }
+}
+
+// ========== Tauri 命令 ==========
+
+/// 导出配置到文件
+///
+/// # 参数
+/// - path: 导出文件路径
+/// - include_passwords: 是否包含 Keyring 中的服务器密码（需要提供口令）
+/// - passphrase: 加密口令（可选，提供后整个文件加密）
+///
+/// # 返回
+/// - 成功：返回导出结果
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn export_config(
+    path: PathBuf,
+    include_passwords: bool,
+    passphrase: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ConfigExportSummary> {
+    // 设置了主密码时需要先解锁
+    super::app_lock::ensure_unlocked(&app).await?;
+
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    if include_passwords && passphrase.is_none() {
+        return Err(SyncError::ConfigError(
+            "A passphrase is required to export passwords".to_string(),
+        ));
+    }
+
+    let app_config = get_config(app).await?;
+    let webdav_servers = db::get_webdav_servers(&db, false).await?;
+
+    let mut passwords = HashMap::new();
+    if include_passwords {
+        for server in &webdav_servers {
+            match KeyringManager::get_account_password(server) {
+                Ok(password) => {
+                    passwords.insert(server.id.clone(), password);
+                }
+                Err(SyncError::NotFound(_)) => {
+                    tracing::warn!(server_id = %server.id, "服务器没有保存的密码，跳过");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let summary = ConfigExportSummary {
+        path: path.to_string_lossy().into_owned(),
+        servers: webdav_servers.len(),
+        passwords: passwords.len(),
+        encrypted: passphrase.is_some(),
+    };
+
+    let bundle = ConfigBundle {
+        app_config,
+        webdav_servers,
+        passwords,
+    };
+    let file = seal_bundle(bundle, passphrase.as_deref())?;
+
+    tokio::fs::write(&path, serde_json::to_vec_pretty(&file)?).await?;
+
+    Ok(summary)
+}
+
+/// 从文件导入配置
+///
+/// 覆盖当前的应用配置；服务器按 ID 合并（已存在则更新，否则新增），
+/// 文件中包含的密码写入系统 Keyring
+///
+/// # 参数
+/// - path: 导出文件路径
+/// - passphrase: 解密口令（导入加密文件时必须提供）
+///
+/// # 返回
+/// - 成功：返回导入结果
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn import_config(
+    path: PathBuf,
+    passphrase: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ConfigImportSummary> {
+    let content = tokio::fs::read(&path).await?;
+    let file: ConfigExportFile = serde_json::from_slice(&content)
+        .map_err(|e| SyncError::ConfigError(format!("Invalid config export file: {}", e)))?;
+    let bundle = open_bundle(file, passphrase.as_deref().filter(|p| !p.is_empty()))?;
+
+    let mut summary = ConfigImportSummary {
+        servers_added: 0,
+        servers_updated: 0,
+        passwords_imported: 0,
+        sync_folders: bundle.app_config.sync_folders.len(),
+    };
+
+    for server in &bundle.webdav_servers {
+        match db::get_webdav_server_by_id(&db, &server.id).await {
+            Ok(_) => {
+                db::update_webdav_server(&db, &server.id, server.clone()).await?;
+                summary.servers_updated += 1;
+            }
+            Err(SyncError::NotFound(_)) => {
+                db::insert_webdav_server(&db, server.clone()).await?;
+                summary.servers_added += 1;
+            }
+            Err(e) => return Err(e),
+        }
+
+        if let Some(password) = bundle.passwords.get(&server.id) {
+            KeyringManager::save_account_password(server, password)?;
+            summary.passwords_imported += 1;
+        }
+    }
+
+    let mut app_config = bundle.app_config;
+    app_config.version = APP_VERSION.to_string();
+    update_config(app, app_config).await?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_bundle(passwords: HashMap<String, String>) -> ConfigBundle {
+        ConfigBundle {
+            app_config: AppConfig::default(),
+            webdav_servers: vec![],
+            passwords,
+        }
+    }
+
+    #[test]
+    fn test_plain_export_round_trip() {
+        let file = seal_bundle(create_test_bundle(HashMap::new()), None).unwrap();
+        assert!(file.bundle.is_some());
+        assert!(file.encrypted.is_none());
+
+        let json = serde_json::to_string(&file).unwrap();
+        assert!(json.contains("appConfig"));
+        assert!(json.contains("webdavServers"));
+
+        let parsed: ConfigExportFile = serde_json::from_str(&json).unwrap();
+        let bundle = open_bundle(parsed, None).unwrap();
+        assert_eq!(bundle.app_config.language, AppConfig::default().language);
+    }
+
+    #[test]
+    fn test_plain_export_rejects_passwords() {
+        let passwords = HashMap::from([("server1".to_string(), "secret".to_string())]);
+        assert!(seal_bundle(create_test_bundle(passwords), None).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_export_round_trip() {
+        let passwords = HashMap::from([("server1".to_string(), "secret".to_string())]);
+        let file = seal_bundle(create_test_bundle(passwords), Some("passphrase")).unwrap();
+        assert!(file.bundle.is_none());
+
+        let json = serde_json::to_string(&file).unwrap();
+        assert!(!json.contains("secret"));
+
+        let parsed: ConfigExportFile = serde_json::from_str(&json).unwrap();
+        assert!(open_bundle(parsed.clone(), None).is_err());
+        assert!(matches!(
+            open_bundle(parsed.clone(), Some("wrong")),
+            Err(SyncError::AuthError(_))
+        ));
+
+        let bundle = open_bundle(parsed, Some("passphrase")).unwrap();
+        assert_eq!(
+            bundle.passwords.get("server1").map(String::as_str),
+            Some("secret")
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_unknown_format() {
+        let mut file = seal_bundle(create_test_bundle(HashMap::new()), None).unwrap();
+        file.format = "something-else".to_string();
+        assert!(open_bundle(file, None).is_err());
+    }
+}
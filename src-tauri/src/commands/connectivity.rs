@@ -0,0 +1,123 @@
+/// 网络连通性命令模块
+///
+/// 提供连通性状态查询与立即检测命令，并在启动时开启后台检测任务
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::config::get_config;
+use crate::constants::{CONNECTIVITY_CHECK_INTERVAL, CONNECTIVITY_PROBE_TIMEOUT};
+use crate::database::Database;
+use crate::error::Result;
+use crate::sync::connectivity::{
+    probe_server, ConnectivityMonitor, ConnectivityState, CONNECTIVITY_CHANGED_EVENT,
+};
+use crate::webdav::db;
+
+/// 获取当前连通性状态
+#[tauri::command]
+pub async fn get_connectivity_state(
+    connectivity: State<'_, ConnectivityMonitor>,
+) -> Result<ConnectivityState> {
+    Ok(connectivity.state())
+}
+
+/// 立即检测所有已启用服务器的连通性
+///
+/// 服务器恢复在线后会自动同步排队的文件夹
+///
+/// # 返回
+/// - 成功：返回检测后的连通性状态
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn check_connectivity(app: AppHandle) -> Result<ConnectivityState> {
+    run_check(&app).await
+}
+
+/// 推送连通性变化事件
+pub fn emit_state(app: &AppHandle, state: &ConnectivityState) {
+    if let Err(e) = app.emit(CONNECTIVITY_CHANGED_EVENT, state.clone()) {
+        tracing::warn!(error = %e, "发送连通性事件失败");
+    }
+}
+
+/// 在后台定期检测服务器连通性
+///
+/// 在应用启动时调用，检测失败只记录日志
+pub fn spawn_connectivity_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(CONNECTIVITY_CHECK_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_check(&app).await {
+                tracing::warn!(error = %e, "连通性检测失败");
+            }
+        }
+    });
+}
+
+/// 执行一轮检测，并同步服务器已恢复在线的排队文件夹
+async fn run_check(app: &AppHandle) -> Result<ConnectivityState> {
+    let servers = {
+        let database = app.state::<Database>();
+        db::get_webdav_servers(&database, true).await?
+    };
+
+    let timeout = Duration::from_secs(CONNECTIVITY_PROBE_TIMEOUT);
+    let mut results = BTreeMap::new();
+    for server in &servers {
+        results.insert(server.id.clone(), probe_server(server, timeout).await);
+    }
+
+    let connectivity = app.state::<ConnectivityMonitor>();
+    if connectivity.record_check(&results) {
+        let state = connectivity.state();
+        tracing::info!(
+            online = state.online,
+            offline_servers = ?state.offline_servers,
+            "网络连通性发生变化"
+        );
+        emit_state(app, &state);
+    }
+
+    flush_queue(app).await?;
+    Ok(connectivity.state())
+}
+
+/// 同步服务器已恢复在线的排队文件夹
+async fn flush_queue(app: &AppHandle) -> Result<()> {
+    let connectivity = app.state::<ConnectivityMonitor>();
+    if connectivity.state().queued_folders.is_empty() {
+        return Ok(());
+    }
+
+    let config = get_config(app.clone()).await?;
+    let folder_servers: HashMap<String, String> = config
+        .sync_folders
+        .iter()
+        .map(|folder| (folder.id.clone(), folder.server_id.clone()))
+        .collect();
+
+    let ready = connectivity.take_ready(&folder_servers);
+    if ready.is_empty() {
+        return Ok(());
+    }
+
+    emit_state(app, &connectivity.state());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for folder_id in ready {
+            tracing::info!(folder_id = %folder_id, "连接已恢复，开始同步排队的文件夹");
+            match super::sync::run_scheduled_sync(&app, &folder_id).await {
+                Ok(Some(_)) => {}
+                // 不满足同步策略时重新排队，下次检测时再检查
+                Ok(None) => app.state::<ConnectivityMonitor>().enqueue(&folder_id),
+                Err(e) => tracing::warn!(folder_id = %folder_id, error = %e, "排队文件夹同步失败"),
+            }
+        }
+    });
+
+    Ok(())
+}
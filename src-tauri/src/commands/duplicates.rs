@@ -0,0 +1,56 @@
+/// 重复文件命令模块
+///
+/// 检测同步文件夹中内容相同的文件，并支持通过服务器端复制同步尚未上传的重复文件
+use tauri::{AppHandle, State};
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::sync::duplicates::{self, CopyDuplicatesResult, DuplicateReport};
+use crate::webdav::factory::WebDavClientFactory;
+
+/// 检测同步文件夹中的重复文件
+///
+/// 本地文件未修改时使用缓存的哈希，只对大小相同的文件计算哈希
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：返回重复文件组及浪费的空间
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn find_duplicates(
+    folder_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<DuplicateReport> {
+    let folder = super::sync_folders::find_folder(app, &folder_id).await?;
+    duplicates::find_duplicates(&db, &folder).await
+}
+
+/// 从已同步的规范文件复制出尚未上传的重复文件
+///
+/// 在服务器端复制规范文件，代替逐个上传内容相同的文件
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - canonical_path: 规范文件的相对路径（必须已同步）
+/// - duplicate_paths: 要复制的重复文件的相对路径
+///
+/// # 返回
+/// - 成功：返回已复制和跳过的文件
+/// - 失败：返回错误信息（加密文件夹、规范文件未同步等）
+#[tauri::command]
+pub async fn copy_duplicates_from_canonical(
+    folder_id: String,
+    canonical_path: String,
+    duplicate_paths: Vec<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<CopyDuplicatesResult> {
+    let folder = super::sync_folders::find_folder(app, &folder_id).await?;
+    let (_, client) = clients.get(&db, &folder.server_id).await?;
+
+    duplicates::copy_from_canonical(&db, &client, &folder, &canonical_path, &duplicate_paths).await
+}
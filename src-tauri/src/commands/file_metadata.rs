@@ -0,0 +1,143 @@
+/// 文件元数据命令模块
+///
+/// 提供查询本地同步状态的 Tauri 命令，供前端文件浏览器展示每个文件的同步状态，
+/// 以及为单个文件设置忽略（ignored）或始终保留本地（pinned）
+use tauri::{AppHandle, State};
+
+use crate::commands::sync_folders::find_folder;
+use crate::constants::file_status;
+use crate::database::{file_metadata, folder_keys, Database, FileMetadata};
+use crate::error::{Result, SyncError};
+
+/// 恢复正常同步的状态值
+const NORMAL_STATE: &str = "normal";
+
+/// 获取同步文件夹下所有文件的元数据
+///
+/// # 参数
+/// - sync_folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：返回按路径排序的文件元数据列表
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_folder_file_metadata(
+    sync_folder_id: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<FileMetadata>> {
+    file_metadata::get_by_folder(&db, sync_folder_id).await
+}
+
+/// 获取单个文件的元数据
+///
+/// # 参数
+/// - sync_folder_id: 同步文件夹 ID
+/// - path: 文件路径（相对于同步文件夹根目录）
+///
+/// # 返回
+/// - 成功：返回文件元数据
+/// - 失败：返回错误信息（记录不存在时返回 NotFound）
+#[tauri::command]
+pub async fn get_file_metadata(
+    sync_folder_id: i64,
+    path: String,
+    db: State<'_, Database>,
+) -> Result<FileMetadata> {
+    file_metadata::get_by_path(&db, sync_folder_id, &path).await
+}
+
+/// 设置单个文件的同步状态
+///
+/// 下次同步时生效：ignored 的文件不做任何同步操作；pinned 的文件始终保留本地副本，
+/// 远程删除时重新上传；normal 恢复正常同步
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID（SyncFolderConfig.id）
+/// - relative_path: 文件路径（相对于同步文件夹根目录，`/` 分隔）
+/// - state: 同步状态（ignored, pinned, normal）
+///
+/// # 返回
+/// - 成功：返回 ()
+/// - 失败：文件夹不存在、路径或状态不合法时返回错误信息
+#[tauri::command]
+pub async fn set_file_sync_state(
+    folder_id: String,
+    relative_path: String,
+    state: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<()> {
+    find_folder(app, &folder_id).await?;
+    let path = normalize_relative_path(&relative_path)?;
+    let state = match state.as_str() {
+        NORMAL_STATE => None,
+        file_status::IGNORED | file_status::PINNED => Some(state.as_str()),
+        _ => {
+            return Err(SyncError::ValidationError(format!(
+                "Invalid file sync state: {}",
+                state
+            )))
+        }
+    };
+
+    let sync_folder_id = folder_keys::resolve(&db, &folder_id).await?;
+    file_metadata::set_sync_state(&db, sync_folder_id, &path, state).await?;
+    tracing::info!(
+        folder_id = %folder_id,
+        path = %path,
+        state = state.unwrap_or(NORMAL_STATE),
+        "已设置文件同步状态"
+    );
+    Ok(())
+}
+
+/// 获取同步文件夹下设置了同步状态（ignored、pinned）的文件，供前端显示标记
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID（SyncFolderConfig.id）
+///
+/// # 返回
+/// - 成功：返回按路径排序的文件元数据列表（status 为 ignored 或 pinned）
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_file_sync_states(
+    folder_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Vec<FileMetadata>> {
+    find_folder(app, &folder_id).await?;
+    let sync_folder_id = folder_keys::resolve(&db, &folder_id).await?;
+    file_metadata::get_with_sync_state(&db, sync_folder_id).await
+}
+
+/// 规范化相对路径：统一使用 `/` 分隔并去掉首尾的分隔符，拒绝空路径和 `.`、`..`
+fn normalize_relative_path(path: &str) -> Result<String> {
+    let segments: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() || segments.iter().any(|s| *s == "." || *s == "..") {
+        return Err(SyncError::ValidationError(format!(
+            "Invalid relative path: {}",
+            path
+        )));
+    }
+    Ok(segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_relative_path() {
+        assert_eq!(
+            normalize_relative_path("/docs\\a.txt/").unwrap(),
+            "docs/a.txt"
+        );
+        assert_eq!(normalize_relative_path("a.txt").unwrap(), "a.txt");
+        assert!(normalize_relative_path("").is_err());
+        assert!(normalize_relative_path("/").is_err());
+        assert!(normalize_relative_path("docs/../a.txt").is_err());
+    }
+}
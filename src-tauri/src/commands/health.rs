@@ -0,0 +1,221 @@
+/// 服务器健康检查命令模块
+///
+/// 后台任务定期对已启用的服务器执行连接测试，将结果与延迟写入 server_health 表，
+/// 前端通过 `get_server_health` 获取历史记录绘制可用性与延迟曲线；
+/// 开启 `keep_alive` 时另一个后台任务定期向最近使用的服务器发送 OPTIONS 请求，避免空闲连接被 NAT 断开
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::get_config;
+use crate::constants::{
+    KEEP_ALIVE_INTERVAL, KEEP_ALIVE_JITTER, SERVER_HEALTH_CHECK_INTERVAL,
+    SERVER_HEALTH_HISTORY_LIMIT,
+};
+use crate::database::{server_health, Database, ServerHealthCheck, WebDavServerConfig};
+use crate::error::Result;
+use crate::webdav::db;
+use crate::webdav::factory::WebDavClientFactory;
+
+/// 服务器健康状况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerHealthReport {
+    /// 服务器 ID
+    pub server_id: String,
+
+    /// 健康检查记录（最新的在前）
+    pub checks: Vec<ServerHealthCheck>,
+
+    /// 可用率（0.0 - 1.0，没有记录时为 None）
+    pub availability: Option<f64>,
+
+    /// 成功检查的平均延迟（毫秒）
+    pub average_latency_ms: Option<f64>,
+}
+
+impl ServerHealthReport {
+    /// 根据检查记录计算可用率与平均延迟
+    pub fn from_checks(server_id: &str, checks: Vec<ServerHealthCheck>) -> Self {
+        let availability = if checks.is_empty() {
+            None
+        } else {
+            let succeeded = checks.iter().filter(|c| c.success).count();
+            Some(succeeded as f64 / checks.len() as f64)
+        };
+
+        let latencies: Vec<i64> = checks
+            .iter()
+            .filter(|c| c.success)
+            .filter_map(|c| c.latency_ms)
+            .collect();
+        let average_latency_ms = if latencies.is_empty() {
+            None
+        } else {
+            Some(latencies.iter().sum::<i64>() as f64 / latencies.len() as f64)
+        };
+
+        Self {
+            server_id: server_id.to_string(),
+            checks,
+            availability,
+            average_latency_ms,
+        }
+    }
+}
+
+/// 获取服务器健康检查历史
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - limit: 最多返回的记录数（可选，默认返回全部保留的记录）
+///
+/// # 返回
+/// - 成功：返回健康检查记录及统计
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_server_health(
+    server_id: String,
+    limit: Option<i64>,
+    db: State<'_, Database>,
+) -> Result<ServerHealthReport> {
+    let limit = limit
+        .unwrap_or(SERVER_HEALTH_HISTORY_LIMIT)
+        .clamp(1, SERVER_HEALTH_HISTORY_LIMIT);
+    let checks = server_health::list(&db, &server_id, limit).await?;
+
+    Ok(ServerHealthReport::from_checks(&server_id, checks))
+}
+
+/// 在后台定期执行服务器健康检查
+///
+/// 在应用启动时调用，检查失败只记录日志
+pub fn spawn_health_checks(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(SERVER_HEALTH_CHECK_INTERVAL));
+        loop {
+            interval.tick().await;
+            let database = app.state::<Database>();
+            let clients = app.state::<WebDavClientFactory>();
+            if let Err(e) = run_health_checks(&database, &clients).await {
+                tracing::warn!(error = %e, "服务器健康检查失败");
+            }
+        }
+    });
+}
+
+/// 在后台定期向最近使用的服务器发送保持连接请求
+///
+/// 在应用启动时调用；只在配置项 `keep_alive` 开启时发送，每次间隔加入随机抖动
+pub fn spawn_keep_alive(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let jitter = fastrand::u64(0..=KEEP_ALIVE_JITTER);
+            tokio::time::sleep(Duration::from_secs(KEEP_ALIVE_INTERVAL + jitter)).await;
+
+            match get_config(app.clone()).await {
+                Ok(config) if config.keep_alive => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::warn!(error = %e, "读取配置失败，跳过保持连接请求");
+                    continue;
+                }
+            }
+
+            let alive = app.state::<WebDavClientFactory>().keep_alive().await;
+            tracing::trace!(alive, "已发送保持连接请求");
+        }
+    });
+}
+
+/// 对所有已启用的服务器执行一轮健康检查
+async fn run_health_checks(database: &Database, clients: &WebDavClientFactory) -> Result<()> {
+    let servers = db::get_webdav_servers(database, true).await?;
+    for server in &servers {
+        check_server(database, clients, server).await?;
+    }
+    Ok(())
+}
+
+/// 检查单个服务器并记录结果
+async fn check_server(
+    database: &Database,
+    clients: &WebDavClientFactory,
+    server: &WebDavServerConfig,
+) -> Result<()> {
+    let started = Instant::now();
+    let outcome = match clients.get(database, &server.id).await {
+        Ok((_, client)) => client.test_connection().await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    let latency_ms = started.elapsed().as_millis() as i64;
+    let checked_at = chrono::Utc::now().timestamp();
+
+    match &outcome {
+        Ok(()) => {
+            tracing::debug!(server_id = %server.id, latency_ms, "服务器健康检查成功");
+        }
+        Err(e) => {
+            tracing::debug!(server_id = %server.id, error = %e, "服务器健康检查失败");
+        }
+    }
+
+    let error = outcome.err().map(|e| e.to_string());
+    server_health::insert(
+        database,
+        &server.id,
+        checked_at,
+        error.is_none(),
+        Some(latency_ms),
+        error.as_deref(),
+    )
+    .await?;
+    server_health::prune(database, &server.id, SERVER_HEALTH_HISTORY_LIMIT).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(success: bool, latency_ms: Option<i64>) -> ServerHealthCheck {
+        ServerHealthCheck {
+            id: 0,
+            server_id: "server-1".to_string(),
+            checked_at: 0,
+            success,
+            latency_ms,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_report_statistics() {
+        let report = ServerHealthReport::from_checks(
+            "server-1",
+            vec![
+                check(true, Some(100)),
+                check(true, Some(300)),
+                check(false, Some(5000)),
+                check(true, None),
+            ],
+        );
+
+        assert_eq!(report.availability, Some(0.75));
+        // 失败检查的耗时不计入平均延迟
+        assert_eq!(report.average_latency_ms, Some(200.0));
+    }
+
+    #[test]
+    fn test_report_without_checks() {
+        let report = ServerHealthReport::from_checks("server-1", vec![]);
+        assert_eq!(report.availability, None);
+        assert_eq!(report.average_latency_ms, None);
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("serverId"));
+        assert!(json.contains("averageLatencyMs"));
+    }
+}
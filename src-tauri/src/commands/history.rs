@@ -0,0 +1,65 @@
+/// 同步历史命令模块
+///
+/// 提供同步日志和同步会话的分页查询命令以及同步统计，供前端活动历史页面使用
+use tauri::State;
+
+use crate::database::{
+    folder_keys, sync_logs, sync_sessions, sync_statistics, Database, PagedResult, QueryFilter,
+    SyncLog, SyncSession, SyncStatistics,
+};
+use crate::error::Result;
+
+/// 分页查询同步日志
+///
+/// # 参数
+/// - filter: 查询过滤器（sync_folder_id、status、start_time、end_time、limit、offset）
+///
+/// # 返回
+/// - 成功：返回当前页日志和满足条件的总数
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_sync_logs(
+    filter: QueryFilter,
+    db: State<'_, Database>,
+) -> Result<PagedResult<SyncLog>> {
+    sync_logs::query(&db, &filter).await
+}
+
+/// 分页查询同步会话
+///
+/// # 参数
+/// - filter: 查询过滤器（sync_folder_id、status、start_time、end_time、limit、offset）
+///
+/// # 返回
+/// - 成功：返回当前页会话和满足条件的总数
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_sync_sessions(
+    filter: QueryFilter,
+    db: State<'_, Database>,
+) -> Result<PagedResult<SyncSession>> {
+    sync_sessions::query(&db, &filter).await
+}
+
+/// 获取同步统计
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID（可选，不传表示所有文件夹）
+/// - period: 时间范围（week, month, quarter, year）
+///
+/// # 返回
+/// - 成功：返回每日传输量、文件数、平均同步耗时和错误率
+/// - 失败：返回错误信息（未知的时间范围等）
+#[tauri::command]
+pub async fn get_sync_statistics(
+    folder_id: Option<String>,
+    period: String,
+    db: State<'_, Database>,
+) -> Result<SyncStatistics> {
+    let sync_folder_id = match folder_id {
+        Some(id) => Some(folder_keys::resolve(&db, &id).await?),
+        None => None,
+    };
+
+    sync_statistics::collect(&db, sync_folder_id, &period, chrono::Utc::now().timestamp()).await
+}
@@ -0,0 +1,69 @@
+/// 数据库维护命令模块
+///
+/// 后台任务定期按配置的保留天数清理同步历史并回收数据库空间，
+/// 前端也可以通过 `run_db_maintenance` 手动触发，并通过 `get_database_stats` 查看统计信息
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::get_config;
+use crate::constants::{DB_MAINTENANCE_DELAY, DB_MAINTENANCE_INTERVAL};
+use crate::database::{maintenance, stats, Database, DatabaseStats, MaintenanceReport};
+use crate::error::Result;
+
+/// 获取数据库统计信息
+///
+/// # 返回
+/// - 成功：返回文件、日志、会话数量与数据库文件大小
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_database_stats(db: State<'_, Database>) -> Result<DatabaseStats> {
+    stats::collect(&db).await
+}
+
+/// 立即执行数据库维护
+///
+/// # 返回
+/// - 成功：返回删除的记录数与回收的空间
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn run_db_maintenance(
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<MaintenanceReport> {
+    let retention_days = get_config(app).await?.log_retention_days;
+    maintenance::run(&db, retention_days).await
+}
+
+/// 在后台定期执行数据库维护
+///
+/// 在应用启动时调用，首次维护延迟执行以免与启动时的迁移和同步争用数据库，维护失败只记录日志
+pub fn spawn_db_maintenance(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(DB_MAINTENANCE_DELAY),
+            Duration::from_secs(DB_MAINTENANCE_INTERVAL),
+        );
+        loop {
+            interval.tick().await;
+            let retention_days = match get_config(app.clone()).await {
+                Ok(config) => config.log_retention_days,
+                Err(e) => {
+                    tracing::warn!(error = %e, "读取配置失败，跳过数据库维护");
+                    continue;
+                }
+            };
+
+            let db = app.state::<Database>();
+            match maintenance::run(&db, retention_days).await {
+                Ok(report) => tracing::info!(
+                    logs_deleted = report.logs_deleted,
+                    sessions_deleted = report.sessions_deleted,
+                    reclaimed_bytes = report.reclaimed_bytes,
+                    "数据库维护完成"
+                ),
+                Err(e) => tracing::warn!(error = %e, "数据库维护失败"),
+            }
+        }
+    });
+}
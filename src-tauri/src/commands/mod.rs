@@ -1,4 +1,25 @@
 /// Tauri 命令模块
 ///
 /// 组织所有暴露给前端的 Tauri 命令
+pub mod activity;
+pub mod app_lock;
+pub mod config_transfer;
+pub mod connectivity;
+pub mod diagnostics;
+pub mod duplicates;
+pub mod file_metadata;
+pub mod health;
+pub mod history;
+pub mod maintenance;
+pub mod remote_poll;
+pub mod settings;
+pub mod shares;
+pub mod sso;
+pub mod sync;
+pub mod sync_folders;
+pub mod thumbnails;
+pub mod transfer;
+pub mod trash;
+pub mod versions;
+pub mod watcher;
 pub mod webdav;
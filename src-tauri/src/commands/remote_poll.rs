@@ -0,0 +1,126 @@
+/// 远程变更轮询命令模块
+///
+/// 后台任务按同步间隔轮询启用自动同步的只下载文件夹，远程目录指纹发生变化时才触发同步，
+/// 不满足同步策略的文件夹不轮询；
+/// 同时提供立即轮询指定文件夹的命令
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::{get_config, SyncFolderConfig};
+use crate::config_bus::{ConfigBus, ConfigUpdate};
+use crate::constants::{session_status, REMOTE_POLL_TICK};
+use crate::database::{remote_snapshots, Database};
+use crate::error::{Result, SyncError};
+use crate::sync::connectivity::ConnectivityMonitor;
+use crate::sync::control::SyncControl;
+use crate::sync::remote_poll::{self, PollOutcome};
+use crate::sync::state::SyncStateManager;
+use crate::webdav::factory::WebDavClientFactory;
+
+/// 立即轮询指定文件夹的远程目录，有变化时同步
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：返回是否触发了同步（服务器支持 sync-collection 或远程没有变化时为 false）
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn poll_remote_changes(folder_id: String, app: AppHandle) -> Result<bool> {
+    let config = get_config(app.clone()).await?;
+    let folder = config
+        .sync_folders
+        .iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder_id)))?;
+
+    poll_and_sync(&app, folder).await
+}
+
+/// 在后台定期轮询只下载文件夹的远程变更
+///
+/// 在应用启动时调用，每个检查周期只轮询已到同步间隔、未暂停、未在同步、服务器在线且满足同步策略的文件夹，
+/// 文件夹的同步间隔、自动同步等设置修改后立即开始一轮检查。轮询失败只记录日志
+pub fn spawn_remote_polling(app: AppHandle) {
+    let mut updates = app.state::<ConfigBus>().subscribe();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(REMOTE_POLL_TICK));
+        loop {
+            // 文件夹的调度设置变化后立即重新检查，不等待下一个检查周期
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = ConfigBus::wait_for(&mut updates, ConfigUpdate::reschedules_polling) => {}
+            }
+            if let Err(e) = run_poll(&app).await {
+                tracing::warn!(error = %e, "远程变更轮询失败");
+            }
+        }
+    });
+}
+
+/// 执行一轮轮询
+async fn run_poll(app: &AppHandle) -> Result<()> {
+    let config = get_config(app.clone()).await?;
+    let now = chrono::Utc::now().timestamp();
+
+    for folder in config
+        .sync_folders
+        .iter()
+        .filter(|f| remote_poll::is_eligible(f))
+    {
+        let snapshot = {
+            let db = app.state::<Database>();
+            remote_snapshots::get(&db, &folder.id).await?
+        };
+        if !remote_poll::is_due(snapshot.as_ref(), folder.sync_interval, now)
+            || app.state::<SyncControl>().is_paused(&folder.id)
+            || !app
+                .state::<ConnectivityMonitor>()
+                .is_server_online(&folder.server_id)
+            || app.state::<SyncStateManager>().get(&folder.id).is_active()
+        {
+            continue;
+        }
+        if super::sync::check_policy(app, &config, folder)
+            .await
+            .is_some()
+        {
+            continue;
+        }
+
+        if let Err(e) = poll_and_sync(app, folder).await {
+            tracing::warn!(folder_id = %folder.id, error = %e, "轮询远程变更失败");
+        }
+    }
+
+    Ok(())
+}
+
+/// 轮询文件夹，远程有变化时同步，同步成功后保存新的目录指纹
+async fn poll_and_sync(app: &AppHandle, folder: &SyncFolderConfig) -> Result<bool> {
+    let db = app.state::<Database>();
+    let (_, client) = app
+        .state::<WebDavClientFactory>()
+        .get(&db, &folder.server_id)
+        .await?;
+    let now = chrono::Utc::now().timestamp();
+
+    let (fingerprint, entry_count) =
+        match remote_poll::poll_folder(&db, &client, folder, now).await? {
+            PollOutcome::SyncCollection | PollOutcome::Unchanged => return Ok(false),
+            PollOutcome::Changed {
+                fingerprint,
+                entry_count,
+            } => (fingerprint, entry_count),
+        };
+
+    tracing::info!(folder_id = %folder.id, entry_count, "远程目录发生变化，开始同步");
+    let session = super::sync::run_folder_sync(app, &folder.id).await?;
+    // 同步未完成时不保存指纹，下次轮询会再次触发同步
+    if session.status == session_status::COMPLETED {
+        remote_snapshots::save(&db, &folder.id, &fingerprint, entry_count, now).await?;
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,254 @@
+/// 设置命令模块
+///
+/// 按配置分组提供类型化的读取与修改命令，代替按名称读写任意配置项的 `get_config_value`/`set_config_value`。
+/// 修改后的完整配置经过校验才会保存，保存后通过配置变更总线通知运行中的子系统；
+/// 同步文件夹的设置通过 `update_sync_folder_settings` 修改
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::config::{
+    get_config, update_config, validate_language, validate_theme, AppConfig, NotificationConfig,
+    SyncPolicyConfig,
+};
+use crate::config_crypto;
+use crate::constants::CONFIG_STORE_FILE;
+use crate::error::{Result, SyncError};
+
+/// 保留期限设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// 回收站保留天数（0 表示永久保留）
+    pub trash_retention_days: u32,
+    /// 每个文件最多保留的历史版本数（0 表示不保存历史版本）
+    pub max_versions_per_file: u32,
+    /// 同步历史保留天数（0 表示永久保留）
+    pub log_retention_days: u32,
+    /// 归档的服务器保留天数（0 表示永久保留）
+    pub server_archive_retention_days: u32,
+}
+
+impl RetentionSettings {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            trash_retention_days: config.trash_retention_days,
+            max_versions_per_file: config.max_versions_per_file,
+            log_retention_days: config.log_retention_days,
+            server_archive_retention_days: config.server_archive_retention_days,
+        }
+    }
+
+    fn apply_to(self, config: &mut AppConfig) {
+        config.trash_retention_days = self.trash_retention_days;
+        config.max_versions_per_file = self.max_versions_per_file;
+        config.log_retention_days = self.log_retention_days;
+        config.server_archive_retention_days = self.server_archive_retention_days;
+    }
+}
+
+/// 传输设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TransferSettings {
+    /// 传输完成后是否校验完整性
+    pub verify_transfers: bool,
+    /// 上传前是否锁定远程文件
+    pub lock_uploads: bool,
+    /// 并行分段下载阈值（MB，0 表示不启用）
+    pub parallel_download_threshold_mb: u32,
+    /// 是否定期保持与最近使用的服务器的连接
+    pub keep_alive: bool,
+    /// 是否记录 WebDAV 请求跟踪
+    pub webdav_trace: bool,
+    /// 本地文件最后修改后需要保持不变的时间（秒）
+    pub file_quiet_period_secs: u32,
+    /// 传输优先窗口（分钟，0 表示不调整顺序）
+    pub priority_window_mins: u32,
+    /// 服务器上的文件名在本地不合法时的处理策略（rename, skip）
+    pub reserved_name_policy: String,
+    /// 仅大小写不同的远程文件在本地冲突时的处理策略（rename, skip）
+    pub case_collision_policy: String,
+}
+
+impl TransferSettings {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            verify_transfers: config.verify_transfers,
+            lock_uploads: config.lock_uploads,
+            parallel_download_threshold_mb: config.parallel_download_threshold_mb,
+            keep_alive: config.keep_alive,
+            webdav_trace: config.webdav_trace,
+            file_quiet_period_secs: config.file_quiet_period_secs,
+            priority_window_mins: config.priority_window_mins,
+            reserved_name_policy: config.reserved_name_policy.clone(),
+            case_collision_policy: config.case_collision_policy.clone(),
+        }
+    }
+
+    fn apply_to(self, config: &mut AppConfig) {
+        config.verify_transfers = self.verify_transfers;
+        config.lock_uploads = self.lock_uploads;
+        config.parallel_download_threshold_mb = self.parallel_download_threshold_mb;
+        config.keep_alive = self.keep_alive;
+        config.webdav_trace = self.webdav_trace;
+        config.file_quiet_period_secs = self.file_quiet_period_secs;
+        config.priority_window_mins = self.priority_window_mins;
+        config.reserved_name_policy = self.reserved_name_policy;
+        config.case_collision_policy = self.case_collision_policy;
+    }
+}
+
+/// 设置主题
+///
+/// # 参数
+/// - theme: 主题（light, dark, system）
+#[tauri::command]
+pub async fn set_theme(theme: String, app: AppHandle) -> Result<()> {
+    validate_theme(&theme)?;
+    modify(app, |config| config.theme = theme).await
+}
+
+/// 设置界面语言
+///
+/// # 参数
+/// - language: 语言代码（如 zh-CN、en-US）
+#[tauri::command]
+pub async fn set_language(language: String, app: AppHandle) -> Result<()> {
+    validate_language(&language)?;
+    modify(app, |config| config.language = language).await
+}
+
+/// 获取保留期限设置
+#[tauri::command]
+pub async fn get_retention_settings(app: AppHandle) -> Result<RetentionSettings> {
+    Ok(RetentionSettings::from_config(&get_config(app).await?))
+}
+
+/// 修改保留期限设置
+#[tauri::command]
+pub async fn set_retention_settings(settings: RetentionSettings, app: AppHandle) -> Result<()> {
+    modify(app, |config| settings.apply_to(config)).await
+}
+
+/// 获取传输设置
+#[tauri::command]
+pub async fn get_transfer_settings(app: AppHandle) -> Result<TransferSettings> {
+    Ok(TransferSettings::from_config(&get_config(app).await?))
+}
+
+/// 修改传输设置
+///
+/// # 返回
+/// - 成功：Ok(())
+/// - 失败：返回错误信息（处理策略无效等）
+#[tauri::command]
+pub async fn set_transfer_settings(settings: TransferSettings, app: AppHandle) -> Result<()> {
+    modify(app, |config| settings.apply_to(config)).await
+}
+
+/// 获取桌面通知设置
+#[tauri::command]
+pub async fn get_notification_settings(app: AppHandle) -> Result<NotificationConfig> {
+    Ok(get_config(app).await?.notifications)
+}
+
+/// 修改桌面通知设置
+#[tauri::command]
+pub async fn set_notification_settings(settings: NotificationConfig, app: AppHandle) -> Result<()> {
+    modify(app, |config| config.notifications = settings).await
+}
+
+/// 获取全局自动同步策略
+#[tauri::command]
+pub async fn get_sync_policy(app: AppHandle) -> Result<SyncPolicyConfig> {
+    Ok(get_config(app).await?.sync_policy)
+}
+
+/// 修改全局自动同步策略
+///
+/// # 返回
+/// - 成功：Ok(())
+/// - 失败：返回错误信息（电量百分比超出范围、静默时段格式无效等）
+#[tauri::command]
+pub async fn set_sync_policy(policy: SyncPolicyConfig, app: AppHandle) -> Result<()> {
+    modify(app, |config| config.sync_policy = policy).await
+}
+
+/// 配置文件加密状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigEncryptionStatus {
+    /// 是否开启了配置文件加密
+    pub enabled: bool,
+    /// 配置文件当前是否已加密保存
+    pub encrypted: bool,
+}
+
+/// 获取配置文件加密状态
+#[tauri::command]
+pub async fn get_config_encryption(app: AppHandle) -> Result<ConfigEncryptionStatus> {
+    let enabled = get_config(app.clone()).await?.encrypt_config;
+    let path = tauri_plugin_store::resolve_store_path(&app, CONFIG_STORE_FILE)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to resolve config path: {}", e)))?;
+    Ok(ConfigEncryptionStatus {
+        enabled,
+        encrypted: config_crypto::is_file_encrypted(&path),
+    })
+}
+
+/// 开启或关闭配置文件加密
+///
+/// 开启时先在系统 Keyring 中准备密钥，再以加密形式重新保存配置文件；关闭时以明文重新保存
+///
+/// # 返回
+/// - 成功：Ok(())
+/// - 失败：返回错误信息（Keyring 无法保存密钥等）
+#[tauri::command]
+pub async fn set_config_encryption(enabled: bool, app: AppHandle) -> Result<()> {
+    if enabled {
+        config_crypto::encryption_key(true)?;
+    }
+    modify(app, |config| config.encrypt_config = enabled).await
+}
+
+/// 读取配置，修改后校验并保存
+async fn modify(app: AppHandle, change: impl FnOnce(&mut AppConfig)) -> Result<()> {
+    let mut config = get_config(app.clone()).await?;
+    change(&mut config);
+    update_config(app, config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_round_trip() {
+        let mut config = AppConfig::default();
+
+        let mut transfer = TransferSettings::from_config(&config);
+        transfer.keep_alive = true;
+        transfer.reserved_name_policy = "skip".to_string();
+        transfer.clone().apply_to(&mut config);
+        assert!(config.keep_alive);
+        assert_eq!(TransferSettings::from_config(&config), transfer);
+
+        let mut retention = RetentionSettings::from_config(&config);
+        retention.trash_retention_days = 7;
+        retention.clone().apply_to(&mut config);
+        assert_eq!(config.trash_retention_days, 7);
+        assert_eq!(RetentionSettings::from_config(&config), retention);
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let json = serde_json::json!({
+            "trashRetentionDays": 30,
+            "maxVersionsPerFile": 10,
+            "logRetentionDays": 90,
+            "serverArchiveRetentionDays": 30,
+            "trashRetentionDay": 1
+        });
+        assert!(serde_json::from_value::<RetentionSettings>(json).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+/// 分享链接命令模块
+///
+/// 提供 Nextcloud / ownCloud 公开分享链接的创建、查询和删除命令
+use tauri::State;
+
+use crate::database::Database;
+use crate::error::{Result, SyncError};
+use crate::webdav::client::WebDavClient;
+use crate::webdav::factory::WebDavClientFactory;
+use crate::webdav::shares::{self, PublicShare, ShareLinkOptions};
+
+/// 获取支持 OCS 分享接口的服务器客户端
+async fn ocs_client(
+    db: &Database,
+    clients: &WebDavClientFactory,
+    server_id: &str,
+) -> Result<WebDavClient> {
+    let (server, client) = clients.get(db, server_id).await?;
+    if !shares::supports_ocs(&server.server_type) {
+        return Err(SyncError::ValidationError(format!(
+            "Share links are not supported by {} servers",
+            server.server_type
+        ))
+        .with_context("serverId", server_id));
+    }
+    Ok(client)
+}
+
+/// 为远程文件或文件夹创建公开分享链接
+///
+/// # 参数
+/// - server_id: 服务器 ID（服务器类型必须为 Nextcloud 或 ownCloud）
+/// - remote_path: 远程路径（相对于服务器根路径）
+/// - options: 访问密码与过期日期（可选）
+///
+/// # 返回
+/// - 成功：返回创建的分享链接
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn create_share_link(
+    server_id: String,
+    remote_path: String,
+    options: Option<ShareLinkOptions>,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<PublicShare> {
+    let client = ocs_client(&db, &clients, &server_id).await?;
+    let share = shares::create_link(&client, &remote_path, &options.unwrap_or_default()).await?;
+
+    tracing::info!(server_id = %server_id, path = %remote_path, share_id = %share.id, "已创建分享链接");
+    Ok(share)
+}
+
+/// 列出公开分享链接
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - remote_path: 只列出该路径的分享（可选，默认列出全部）
+///
+/// # 返回
+/// - 成功：返回分享链接列表
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn list_shares(
+    server_id: String,
+    remote_path: Option<String>,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<Vec<PublicShare>> {
+    let client = ocs_client(&db, &clients, &server_id).await?;
+    shares::list_links(&client, remote_path.as_deref()).await
+}
+
+/// 删除分享链接
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - share_id: 分享 ID
+///
+/// # 返回
+/// - 成功：返回空
+/// - 失败：返回错误信息（分享不存在时返回 NotFound）
+#[tauri::command]
+pub async fn delete_share(
+    server_id: String,
+    share_id: String,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<()> {
+    let client = ocs_client(&db, &clients, &server_id).await?;
+    shares::delete(&client, &share_id).await?;
+
+    tracing::info!(server_id = %server_id, share_id = %share_id, "已删除分享链接");
+    Ok(())
+}
@@ -0,0 +1,135 @@
+/// SSO 登录命令模块
+///
+/// 服务器位于基于 Cookie 的 SSO 网关之后时，在内嵌浏览器窗口中打开服务器地址，
+/// 用户完成登录后读取窗口中的会话 Cookie，保存到服务器的 Cookie 罐供后续 WebDAV 请求使用
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use url::Url;
+
+use crate::database::{Database, WebDavServerConfig};
+use crate::error::{Result, SyncError};
+use crate::webdav::cookies::{CookieStore, StoredCookie};
+use crate::webdav::db;
+
+/// 登录窗口标签前缀（后接服务器 ID）
+const SSO_WINDOW_PREFIX: &str = "sso-login-";
+
+/// SSO 登录结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SsoSession {
+    /// 服务器 ID
+    pub server_id: String,
+    /// 本次从登录窗口读取的 Cookie 数
+    pub captured: usize,
+    /// 服务器当前有效的 Cookie 数
+    pub cookie_count: usize,
+}
+
+fn window_label(server_id: &str) -> String {
+    format!("{}{}", SSO_WINDOW_PREFIX, server_id)
+}
+
+fn server_url(server: &WebDavServerConfig) -> Result<Url> {
+    Url::parse(&server.resolved_url())
+        .map_err(|e| SyncError::ValidationError(format!("Invalid server URL: {}", e)))
+}
+
+/// 打开内嵌浏览器窗口登录服务器（窗口已打开时切换到该窗口）
+///
+/// # 参数
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - 成功：返回空
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn open_sso_login(
+    server_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<()> {
+    let server = db::get_webdav_server_by_id(&db, &server_id).await?;
+    let url = server_url(&server)?;
+    let label = window_label(&server_id);
+
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(&app, &label, WebviewUrl::External(url))
+        .title(format!("{} - Sign in", server.name))
+        .inner_size(960.0, 720.0)
+        .build()
+        .map_err(|e| SyncError::Unknown(format!("Failed to open login window: {}", e)))?;
+
+    tracing::info!(server_id = %server_id, "已打开 SSO 登录窗口");
+    Ok(())
+}
+
+/// 完成 SSO 登录：读取登录窗口中服务器地址的 Cookie 并保存，然后关闭窗口
+///
+/// # 参数
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - 成功：返回读取和保存的 Cookie 数
+/// - 失败：返回错误信息（登录窗口未打开时返回 NotFound）
+#[tauri::command]
+pub async fn complete_sso_login(
+    server_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+    cookies: State<'_, CookieStore>,
+) -> Result<SsoSession> {
+    let server = db::get_webdav_server_by_id(&db, &server_id).await?;
+    let url = server_url(&server)?;
+    let window = app
+        .get_webview_window(&window_label(&server_id))
+        .ok_or_else(|| {
+            SyncError::NotFound("Login window is not open".to_string())
+                .with_context("serverId", &server_id)
+        })?;
+
+    let now = chrono::Utc::now().timestamp();
+    let captured: Vec<StoredCookie> = window
+        .cookies_for_url(url.clone())
+        .map_err(|e| SyncError::Unknown(format!("Failed to read login cookies: {}", e)))?
+        .iter()
+        .filter_map(|cookie| StoredCookie::from_cookie(cookie, &url, now))
+        .filter(|cookie| !cookie.is_expired(now))
+        .collect();
+    if let Err(e) = window.close() {
+        tracing::warn!(server_id = %server_id, error = %e, "关闭 SSO 登录窗口失败");
+    }
+
+    let captured_count = captured.len();
+    let cookie_count = cookies.jar(&server_id)?.import(captured)?;
+
+    tracing::info!(server_id = %server_id, captured = captured_count, "已保存 SSO 会话 Cookie");
+    Ok(SsoSession {
+        server_id,
+        captured: captured_count,
+        cookie_count,
+    })
+}
+
+/// 清除服务器保存的 Cookie（退出 SSO 会话）
+///
+/// # 参数
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - 成功：返回空
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn clear_server_cookies(
+    server_id: String,
+    cookies: State<'_, CookieStore>,
+) -> Result<()> {
+    cookies.clear(&server_id)?;
+
+    tracing::info!(server_id = %server_id, "已清除服务器 Cookie");
+    Ok(())
+}
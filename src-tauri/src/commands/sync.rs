@@ -0,0 +1,516 @@
+/// 同步命令模块
+///
+/// 提供手动同步（包括批量触发）、同步预览、确认大量删除的同步计划、暂停/恢复同步、取消同步会话以及查询文件夹同步状态的命令，
+/// 以及启动时的崩溃恢复、推迟上传的文件夹的重新同步和自动同步前的同步策略检查
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::{get_config, update_config, AppConfig, SyncFolderConfig};
+use crate::constants::STABILITY_RESYNC_TICK;
+use crate::database::log_writer::SyncLogWriter;
+use crate::database::{Database, SyncSession};
+use crate::error::{Result, SyncError};
+use crate::notifications;
+use crate::sync::connectivity::ConnectivityMonitor;
+use crate::sync::control::{PauseState, SyncControl};
+use crate::sync::encryption::FolderCipher;
+use crate::sync::engine::{self, SyncContext};
+use crate::sync::events::{SyncSkippedEvent, SyncTriggeredEvent};
+use crate::sync::folders;
+use crate::sync::incremental::ScanCache;
+use crate::sync::marker::ClientId;
+use crate::sync::paths::{CaseCollisionPolicy, PathNormalizer};
+use crate::sync::planner::{self, SyncPlan};
+use crate::sync::policy::{self, PolicyConditions, PolicySkips, SkipReason};
+use crate::sync::recovery;
+use crate::sync::safety::DeletionGuard;
+use crate::sync::sanitize::ReservedNamePolicy;
+use crate::sync::stability::StabilityQueue;
+use crate::sync::state::{FolderSyncState, SyncStateManager};
+use crate::sync::throughput::TransferStats;
+use crate::sync::trash::Trash;
+use crate::sync::versions::VersionStore;
+use crate::sync::SyncEventEmitter;
+use crate::webdav::factory::WebDavClientFactory;
+
+/// 立即同步指定文件夹
+///
+/// 同步进度通过 `sync://progress`、`sync://completed`、`sync://error` 事件推送，
+/// 文件夹状态变化通过 `sync://state-changed` 事件推送
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：返回同步会话（暂停时状态为 paused）
+/// - 失败：返回错误信息（文件夹已暂停或正在同步，或服务器离线、同步已加入队列时返回 Interrupted 错误）
+#[tauri::command]
+pub async fn sync_now(folder_id: String, app: AppHandle) -> Result<SyncSession> {
+    run_manual_sync(&app, &folder_id).await
+}
+
+/// 批量触发多个文件夹的同步
+///
+/// 先校验全部文件夹 ID，随后在后台依次同步（单个文件夹失败不影响其他文件夹），
+/// 并发送一次 `sync://triggered` 事件；各文件夹的进度与结果仍通过同步事件推送
+///
+/// # 参数
+/// - folder_ids: 同步文件夹 ID（重复的 ID 只同步一次）
+///
+/// # 返回
+/// - 成功：返回加入同步队列的文件夹 ID
+/// - 失败：返回错误信息（任一文件夹不存在时不会开始同步）
+#[tauri::command]
+pub async fn trigger_sync(folder_ids: Vec<String>, app: AppHandle) -> Result<Vec<String>> {
+    let config = get_config(app.clone()).await?;
+
+    let mut queued: Vec<String> = Vec::with_capacity(folder_ids.len());
+    for folder_id in folder_ids {
+        if queued.contains(&folder_id) {
+            continue;
+        }
+        if !config.sync_folders.iter().any(|f| f.id == folder_id) {
+            return Err(SyncError::NotFound(format!(
+                "Sync folder not found: {}",
+                folder_id
+            )));
+        }
+        queued.push(folder_id);
+    }
+    if queued.is_empty() {
+        return Ok(queued);
+    }
+
+    SyncEventEmitter::new(app.clone()).triggered(&SyncTriggeredEvent {
+        folder_ids: queued.clone(),
+    });
+
+    let folder_ids = queued.clone();
+    tauri::async_runtime::spawn(async move {
+        for folder_id in folder_ids {
+            if let Err(e) = run_manual_sync(&app, &folder_id).await {
+                tracing::warn!(folder_id = %folder_id, error = %e, "批量同步中的文件夹同步失败");
+            }
+        }
+    });
+
+    tracing::info!(folders = queued.len(), "已批量触发同步");
+    Ok(queued)
+}
+
+/// 执行手动触发的同步，总是完整扫描本地目录
+pub(crate) async fn run_manual_sync(app: &AppHandle, folder_id: &str) -> Result<SyncSession> {
+    app.state::<ScanCache>().request_full_scan(folder_id);
+    run_folder_sync(app, folder_id).await
+}
+
+/// 同步指定文件夹
+///
+/// 服务器离线时不发起请求，而是将文件夹加入待同步队列，恢复连接后自动同步；
+/// 同步过程中出现网络错误时将服务器标记为离线并加入队列；
+/// 本地根目录不可用（如可移动磁盘已断开）时暂停该文件夹。会话结束后按配置发送桌面通知
+pub async fn run_folder_sync(app: &AppHandle, folder_id: &str) -> Result<SyncSession> {
+    let config = get_config(app.clone()).await?;
+    let folder = config
+        .sync_folders
+        .iter()
+        .find(|f| f.id == folder_id)
+        .cloned()
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder_id)))?;
+
+    let connectivity = app.state::<ConnectivityMonitor>();
+    if !connectivity.is_server_online(&folder.server_id) {
+        connectivity.enqueue(&folder.id);
+        return Err(SyncError::Interrupted(format!(
+            "Server is offline, sync queued for folder: {}",
+            folder.id
+        )));
+    }
+
+    let db = app.state::<Database>();
+    let logs = app.state::<SyncLogWriter>();
+    let control = app.state::<SyncControl>();
+    let states = app.state::<SyncStateManager>();
+    let trash = app.state::<Trash>();
+    let versions = app.state::<VersionStore>();
+    let stability = app.state::<StabilityQueue>();
+    let scans = app.state::<ScanCache>();
+    let deletion_guard = app.state::<DeletionGuard>();
+    let client_id = app.state::<ClientId>();
+    let transfer_stats = app.state::<TransferStats>();
+    let (_, client) = app
+        .state::<WebDavClientFactory>()
+        .get(&db, &folder.server_id)
+        .await?;
+
+    let ctx = SyncContext {
+        db: &db,
+        client,
+        logs: &logs,
+        control: &control,
+        states: &states,
+        trash: &trash,
+        versions: &versions,
+        emitter: SyncEventEmitter::new(app.clone()),
+        max_versions: config.max_versions_per_file,
+        verify_transfers: config.verify_transfers,
+        lock_uploads: config.lock_uploads,
+        parallel_download_threshold: u64::from(config.parallel_download_threshold_mb) * 1024 * 1024,
+        reserved_name_policy: ReservedNamePolicy::from_config(&config.reserved_name_policy),
+        case_collision_policy: CaseCollisionPolicy::from_config(&config.case_collision_policy),
+        file_quiet_period: config.file_quiet_period_secs,
+        priority_window: config.priority_window_mins.saturating_mul(60),
+        stability: &stability,
+        scans: &scans,
+        deletion_guard: &deletion_guard,
+        client_id: &client_id,
+        transfer_stats: &transfer_stats,
+    };
+
+    let result = engine::sync_folder(&ctx, &folder).await;
+    notifications::notify_sync_result(app, &config, &folder, &result);
+    if let Err(SyncError::SyncRootUnavailable(_)) = &result {
+        let state = control.pause(Some(&folder.id));
+        if let Err(e) = save_pause_state(app.clone(), &state).await {
+            tracing::warn!(folder_id = %folder.id, error = %e, "保存暂停状态失败");
+        }
+        tracing::warn!(folder_id = %folder.id, "同步文件夹本地根目录不可用，已暂停同步");
+    }
+    if let Err(SyncError::Network(_)) = &result {
+        connectivity.enqueue(&folder.id);
+        if connectivity.mark_offline(&folder.server_id) {
+            super::connectivity::emit_state(app, &connectivity.state());
+        }
+    }
+
+    result
+}
+
+/// 预览同步文件夹的同步计划（dry-run）
+///
+/// 执行完整的对比阶段但不进行任何传输，也不会推进远程同步令牌。
+/// 文件夹尚未同步过时，可以传入首次同步策略预览不同策略的结果
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - first_sync_strategy: 预览使用的首次同步策略（可选，默认使用文件夹的设置）
+///
+/// # 返回
+/// - 成功：返回同步计划（上传、下载、删除、冲突、两侧保留的文件及涉及的字节数）
+/// - 失败：返回错误信息（策略无效时返回 ConfigError）
+#[tauri::command]
+pub async fn preview_sync(
+    folder_id: String,
+    first_sync_strategy: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<SyncPlan> {
+    let config = get_config(app.clone()).await?;
+    let mut folder = super::sync_folders::find_folder(app, &folder_id).await?;
+    if let Some(strategy) = first_sync_strategy {
+        folder.first_sync_strategy = strategy;
+        folders::validate_options(&folder)?;
+    }
+    let (_, client) = clients.get(&db, &folder.server_id).await?;
+    let mut normalizer = PathNormalizer::new(ReservedNamePolicy::from_config(
+        &config.reserved_name_policy,
+    ))
+    .with_case_collisions(CaseCollisionPolicy::from_config(
+        &config.case_collision_policy,
+    ));
+    let cipher = if folder.encryption.enabled {
+        Some(FolderCipher::open(&client, &folder).await?)
+    } else {
+        None
+    };
+
+    planner::plan_folder(
+        &db,
+        &client,
+        &folder,
+        &mut normalizer,
+        cipher.as_ref(),
+        None,
+        false,
+        config.file_quiet_period_secs,
+        config.priority_window_mins.saturating_mul(60),
+    )
+    .await
+}
+
+/// 确认因删除文件过多而中止的同步计划，并立即重新同步
+///
+/// 重新生成的计划删除的文件都在已确认的计划范围内时才会执行，
+/// 否则会再次中止并返回新的计划 ID
+///
+/// # 参数
+/// - plan_id: `MassDeletionProtection` 错误上下文中的计划 ID（planId）
+///
+/// # 返回
+/// - 成功：返回同步会话
+/// - 失败：返回错误信息（计划不存在或已确认时返回 NotFound）
+#[tauri::command]
+pub async fn confirm_sync_plan(
+    plan_id: String,
+    app: AppHandle,
+    guard: State<'_, DeletionGuard>,
+) -> Result<SyncSession> {
+    let folder_id = guard.confirm(&plan_id)?;
+
+    tracing::info!(folder_id = %folder_id, plan_id = %plan_id, "已确认大量删除的同步计划");
+    run_manual_sync(&app, &folder_id).await
+}
+
+/// 暂停同步
+///
+/// 正在进行的传输会在当前数据块完成后停止，暂停状态会保存到配置中，重启后保持
+///
+/// # 参数
+/// - folder_id: 要暂停的文件夹 ID（可选，不传表示全局暂停）
+///
+/// # 返回
+/// - 成功：返回更新后的暂停状态
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn pause_sync(
+    folder_id: Option<String>,
+    app: AppHandle,
+    control: State<'_, SyncControl>,
+) -> Result<PauseState> {
+    if let Some(id) = &folder_id {
+        super::sync_folders::find_folder(app.clone(), id).await?;
+    }
+
+    let state = control.pause(folder_id.as_deref());
+    save_pause_state(app, &state).await?;
+
+    tracing::info!(folder_id = ?folder_id, "已暂停同步");
+    Ok(state)
+}
+
+/// 恢复同步
+///
+/// # 参数
+/// - folder_id: 要恢复的文件夹 ID（可选，不传表示解除全局暂停并恢复所有文件夹）
+///
+/// # 返回
+/// - 成功：返回更新后的暂停状态
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn resume_sync(
+    folder_id: Option<String>,
+    app: AppHandle,
+    control: State<'_, SyncControl>,
+) -> Result<PauseState> {
+    let state = control.resume(folder_id.as_deref());
+    save_pause_state(app, &state).await?;
+
+    tracing::info!(folder_id = ?folder_id, "已恢复同步");
+    Ok(state)
+}
+
+/// 取消运行中的同步会话
+///
+/// 进行中的 WebDAV 请求立即中止，未完成的下载临时文件会被清理，
+/// 会话以 cancelled 状态结束
+///
+/// # 参数
+/// - session_id: 同步会话 ID
+///
+/// # 返回
+/// - 成功：返回 ()
+/// - 失败：会话不存在或已结束时返回 NotFound 错误
+#[tauri::command]
+pub async fn cancel_sync_session(session_id: i64, control: State<'_, SyncControl>) -> Result<()> {
+    if !control.cancel_session(session_id) {
+        return Err(SyncError::NotFound(format!(
+            "Running sync session not found: {}",
+            session_id
+        )));
+    }
+
+    tracing::info!(session_id, "已请求取消同步会话");
+    Ok(())
+}
+
+/// 获取当前暂停状态
+#[tauri::command]
+pub async fn get_sync_pause_state(control: State<'_, SyncControl>) -> Result<PauseState> {
+    Ok(control.state())
+}
+
+/// 获取所有同步文件夹的当前同步状态
+///
+/// # 返回
+/// - 成功：返回文件夹 ID 到同步状态的映射（空闲、扫描、传输、暂停、出错）
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_sync_status(
+    app: AppHandle,
+    states: State<'_, SyncStateManager>,
+) -> Result<BTreeMap<String, FolderSyncState>> {
+    let config = get_config(app).await?;
+    Ok(config
+        .sync_folders
+        .iter()
+        .map(|folder| (folder.id.clone(), states.get(&folder.id)))
+        .collect())
+}
+
+/// 获取当前因同步策略跳过自动同步的文件夹
+///
+/// # 返回
+/// 文件夹 ID -> 跳过原因（metered-network, low-battery, quiet-hours）
+#[tauri::command]
+pub async fn get_policy_skips(
+    skips: State<'_, PolicySkips>,
+) -> Result<BTreeMap<String, SkipReason>> {
+    Ok(skips.snapshot())
+}
+
+/// 在后台执行启动恢复
+///
+/// 在应用启动时调用，整理上次异常退出遗留的会话、传输和下载临时文件，恢复失败只记录日志。
+/// 传输队列中还有排队中传输的文件夹随后在后台重新同步，从断点继续未完成的下载
+///
+/// # 返回
+/// 恢复任务句柄，需要在恢复完成后才开始的任务（如命令行同步）可以等待该句柄
+pub fn spawn_startup_recovery(app: AppHandle) -> tauri::async_runtime::JoinHandle<()> {
+    let launched_at = chrono::Utc::now().timestamp();
+    tauri::async_runtime::spawn(async move {
+        let sync_folders = match get_config(app.clone()).await {
+            Ok(config) => config.sync_folders,
+            Err(e) => {
+                tracing::warn!(error = %e, "读取配置失败，跳过启动恢复");
+                return;
+            }
+        };
+
+        let db = app.state::<Database>();
+        match recovery::run(&db, &sync_folders, launched_at).await {
+            Ok(report) if report.is_empty() => {}
+            Ok(report) => {
+                tracing::info!(
+                    sessions_interrupted = report.sessions_interrupted,
+                    transfers_requeued = report.transfers_requeued,
+                    partial_files_removed = report.partial_files_removed,
+                    partial_files_kept = report.partial_files_kept,
+                    pending_folders = report.pending_folders.len(),
+                    "已恢复上次异常退出遗留的同步状态"
+                );
+                resume_queued_transfers(app.clone(), report.pending_folders);
+            }
+            Err(e) => tracing::warn!(error = %e, "启动恢复失败"),
+        }
+    })
+}
+
+/// 在后台重新同步传输队列中还有未完成传输的文件夹
+///
+/// 不满足同步策略的文件夹跳过，由定时同步稍后处理；同步失败只记录日志
+fn resume_queued_transfers(app: AppHandle, folder_ids: Vec<String>) {
+    if folder_ids.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        for folder_id in folder_ids {
+            tracing::info!(folder_id = %folder_id, "继续上次未完成的传输");
+            if let Err(e) = run_scheduled_sync(&app, &folder_id).await {
+                tracing::warn!(folder_id = %folder_id, error = %e, "继续未完成的传输失败");
+            }
+        }
+    });
+}
+
+/// 在后台重新同步因文件仍在写入而推迟上传的文件夹
+///
+/// 在应用启动时调用，每个检查周期同步已到期的文件夹，同步失败只记录日志
+pub fn spawn_deferred_sync(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(STABILITY_RESYNC_TICK));
+        loop {
+            interval.tick().await;
+            let due = app
+                .state::<StabilityQueue>()
+                .take_due(chrono::Utc::now().timestamp());
+            for folder_id in due {
+                match run_scheduled_sync(&app, &folder_id).await {
+                    Ok(Some(_)) => {}
+                    // 不满足同步策略时在下个检查周期重试
+                    Ok(None) => app.state::<StabilityQueue>().requeue(
+                        &folder_id,
+                        chrono::Utc::now().timestamp() + STABILITY_RESYNC_TICK as i64,
+                    ),
+                    Err(e) => {
+                        tracing::warn!(folder_id = %folder_id, error = %e, "重新同步推迟的文件夹失败")
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 执行自动触发的同步，不满足同步策略时跳过
+///
+/// # 返回
+/// - Ok(Some(session)): 已同步
+/// - Ok(None): 因同步策略跳过
+/// - Err(SyncError): 同步失败
+pub(crate) async fn run_scheduled_sync(
+    app: &AppHandle,
+    folder_id: &str,
+) -> Result<Option<SyncSession>> {
+    let config = get_config(app.clone()).await?;
+    if let Some(folder) = config.sync_folders.iter().find(|f| f.id == folder_id) {
+        if check_policy(app, &config, folder).await.is_some() {
+            return Ok(None);
+        }
+    }
+    run_folder_sync(app, folder_id).await.map(Some)
+}
+
+/// 检查文件夹生效的同步策略，并记录跳过原因
+///
+/// 跳过原因变化时记录日志并推送 `sync://skipped` 事件，避免每个调度周期重复推送
+///
+/// # 返回
+/// 满足策略时返回 None，否则返回跳过原因
+pub(crate) async fn check_policy(
+    app: &AppHandle,
+    config: &AppConfig,
+    folder: &SyncFolderConfig,
+) -> Option<SkipReason> {
+    let sync_policy = policy::effective(&config.sync_policy, folder);
+    let reason = policy::evaluate(sync_policy, &PolicyConditions::current(sync_policy).await);
+
+    if app.state::<PolicySkips>().record(&folder.id, reason) {
+        match reason {
+            Some(reason) => {
+                tracing::info!(folder_id = %folder.id, reason = ?reason, "不满足同步策略，跳过自动同步");
+                SyncEventEmitter::new(app.clone()).skipped(&SyncSkippedEvent {
+                    folder_id: folder.id.clone(),
+                    reason,
+                });
+            }
+            None => tracing::info!(folder_id = %folder.id, "已满足同步策略，恢复自动同步"),
+        }
+    }
+    reason
+}
+
+/// 将暂停状态写入配置，并更新各文件夹的同步状态
+pub(crate) async fn save_pause_state(app: AppHandle, state: &PauseState) -> Result<()> {
+    let mut config = get_config(app.clone()).await?;
+    state.apply_to(&mut config);
+
+    let states = app.state::<SyncStateManager>();
+    for folder in &config.sync_folders {
+        states.apply_pause(&folder.id, state.is_paused(&folder.id));
+    }
+
+    update_config(app, config).await
+}
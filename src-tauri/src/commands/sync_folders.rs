@@ -0,0 +1,738 @@
+/// 同步文件夹命令模块
+///
+/// 提供同步文件夹的增删改查、用量统计与远程目录浏览命令。同步文件夹保存在配置文件的 sync_folders 中，
+/// 写入前会校验本地路径、检查重叠、确认服务器存在并在远程创建目标目录，
+/// 写入后同步到数据库的 sync_folders 表
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::config::{
+    get_config, update_config, FolderCompressionConfig, FolderEncryptionConfig, SyncFolderConfig,
+    SyncPolicyConfig,
+};
+use crate::constants::{
+    DEFAULT_CONFLICT_RESOLUTION, DEFAULT_FIRST_SYNC_STRATEGY, DEFAULT_MAX_DELETE_RATIO,
+    DEFAULT_SYNC_INTERVAL, SELECTIVE_SYNC_TREE_DEPTH,
+};
+use crate::database::{
+    file_metadata, folder_keys, folder_records, name_mappings, remote_cache, remote_snapshots,
+    sync_tokens, Database,
+};
+use crate::error::{Result, SyncError};
+use crate::file_watcher::FileWatcherManager;
+use crate::sync::browse::{
+    self, CachedRemoteListing, RemoteCacheRefreshes, REMOTE_CACHE_UPDATED_EVENT,
+};
+use crate::sync::control::SyncControl;
+use crate::sync::delta::list_recursive;
+use crate::sync::filter::SyncFilter;
+use crate::sync::incremental::ScanCache;
+use crate::sync::scanner::{self, FolderUsage};
+use crate::sync::state::SyncStateManager;
+use crate::sync::{encryption, folders, selective};
+use crate::webdav::factory::WebDavClientFactory;
+use crate::webdav::keyring::KeyringManager;
+
+// ========== 输入数据结构 ==========
+
+/// 添加同步文件夹时的输入数据（不包含自动生成的 id）
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddSyncFolderInput {
+    /// 文件夹名称
+    pub name: String,
+    /// 本地路径
+    pub local_path: PathBuf,
+    /// 远程路径
+    pub remote_path: String,
+    /// 关联的服务器 ID
+    pub server_id: String,
+    /// 同步方向（可选，默认 bidirectional）
+    #[serde(default = "default_sync_direction")]
+    pub sync_direction: String,
+    /// 同步间隔（分钟，可选）
+    #[serde(default = "default_sync_interval")]
+    pub sync_interval: u32,
+    /// 是否启用自动同步（可选，默认 true）
+    #[serde(default = "default_auto_sync")]
+    pub auto_sync: bool,
+    /// 忽略规则（可选）
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// 冲突解决策略（可选）
+    #[serde(default = "default_conflict_resolution")]
+    pub conflict_resolution: String,
+    /// 选择性同步排除的远程子文件夹（可选）
+    #[serde(default)]
+    pub selective_exclusions: Vec<String>,
+    /// 符号链接处理策略（可选，默认 skip）
+    #[serde(default = "default_symlink_policy")]
+    pub symlink_policy: String,
+    /// 端到端加密设置（可选，默认不加密）
+    #[serde(default)]
+    pub encryption: FolderEncryptionConfig,
+    /// 上传压缩设置（可选，默认不压缩）
+    #[serde(default)]
+    pub compression: FolderCompressionConfig,
+    /// 单次同步最多删除的文件比例（可选，默认 0.2）
+    #[serde(default = "default_max_delete_ratio")]
+    pub max_delete_ratio: f64,
+    /// 首次同步策略（可选，默认 merge-keep-both）
+    #[serde(default = "default_first_sync_strategy")]
+    pub first_sync_strategy: String,
+    /// 单个文件的大小上限（字节，可选，默认不限制）
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    /// 只同步这些扩展名的文件（可选）
+    #[serde(default)]
+    pub include_extensions: Vec<String>,
+    /// 不同步这些扩展名的文件（可选）
+    #[serde(default)]
+    pub exclude_extensions: Vec<String>,
+    /// 是否忽略操作系统生成的元数据文件（可选，默认忽略）
+    #[serde(default = "default_ignore_system_files")]
+    pub ignore_system_files: bool,
+    /// 单独设置的自动同步策略（可选，默认使用全局策略）
+    #[serde(default)]
+    pub sync_policy: Option<SyncPolicyConfig>,
+}
+
+fn default_sync_direction() -> String {
+    crate::constants::sync_direction::BIDIRECTIONAL.to_string()
+}
+
+fn default_sync_interval() -> u32 {
+    DEFAULT_SYNC_INTERVAL
+}
+
+fn default_auto_sync() -> bool {
+    true
+}
+
+fn default_ignore_system_files() -> bool {
+    true
+}
+
+fn default_conflict_resolution() -> String {
+    DEFAULT_CONFLICT_RESOLUTION.to_string()
+}
+
+fn default_symlink_policy() -> String {
+    crate::constants::symlink_policy::SKIP.to_string()
+}
+
+fn default_max_delete_ratio() -> f64 {
+    DEFAULT_MAX_DELETE_RATIO
+}
+
+fn default_first_sync_strategy() -> String {
+    DEFAULT_FIRST_SYNC_STRATEGY.to_string()
+}
+
+/// 同步文件夹设置的部分更新
+///
+/// 只包含不需要重新准备文件夹的设置（本地路径、服务器、远程路径与加密通过 `update_sync_folder` 修改），
+/// 未提供的字段保持不变；可为空的字段传入 null 表示清除
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct SyncFolderSettings {
+    /// 文件夹名称
+    pub name: Option<String>,
+    /// 同步方向
+    pub sync_direction: Option<String>,
+    /// 同步间隔（分钟）
+    pub sync_interval: Option<u32>,
+    /// 是否启用自动同步
+    pub auto_sync: Option<bool>,
+    /// 忽略规则
+    pub ignore_patterns: Option<Vec<String>>,
+    /// 冲突解决策略
+    pub conflict_resolution: Option<String>,
+    /// 符号链接处理策略
+    pub symlink_policy: Option<String>,
+    /// 上传压缩设置
+    pub compression: Option<FolderCompressionConfig>,
+    /// 单次同步最多删除的文件比例
+    pub max_delete_ratio: Option<f64>,
+    /// 单个文件的大小上限（null 表示不限制）
+    #[serde(deserialize_with = "nullable")]
+    pub max_file_size: Option<Option<u64>>,
+    /// 只同步这些扩展名的文件
+    pub include_extensions: Option<Vec<String>>,
+    /// 不同步这些扩展名的文件
+    pub exclude_extensions: Option<Vec<String>>,
+    /// 是否忽略操作系统生成的元数据文件
+    pub ignore_system_files: Option<bool>,
+    /// 单独设置的自动同步策略（null 表示使用全局策略）
+    #[serde(deserialize_with = "nullable")]
+    pub sync_policy: Option<Option<SyncPolicyConfig>>,
+}
+
+impl SyncFolderSettings {
+    /// 将提供的字段写入同步文件夹配置
+    pub fn apply_to(self, folder: &mut SyncFolderConfig) {
+        fn set<T>(target: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *target = value;
+            }
+        }
+
+        set(&mut folder.name, self.name);
+        set(&mut folder.sync_direction, self.sync_direction);
+        set(&mut folder.sync_interval, self.sync_interval);
+        set(&mut folder.auto_sync, self.auto_sync);
+        set(&mut folder.ignore_patterns, self.ignore_patterns);
+        set(&mut folder.conflict_resolution, self.conflict_resolution);
+        set(&mut folder.symlink_policy, self.symlink_policy);
+        set(&mut folder.compression, self.compression);
+        set(&mut folder.max_delete_ratio, self.max_delete_ratio);
+        set(&mut folder.max_file_size, self.max_file_size);
+        set(&mut folder.include_extensions, self.include_extensions);
+        set(&mut folder.exclude_extensions, self.exclude_extensions);
+        set(&mut folder.ignore_system_files, self.ignore_system_files);
+        set(&mut folder.sync_policy, self.sync_policy);
+    }
+}
+
+/// 区分缺少的字段（None）与 null（Some(None)）
+fn nullable<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    <Option<T> as serde::Deserialize>::deserialize(deserializer).map(Some)
+}
+
+/// 同步文件夹的本地与远程用量
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderStats {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 本地文件数与总大小（不含被忽略的文件）
+    pub local: FolderUsage,
+    /// 远程文件数与总大小
+    pub remote: FolderUsage,
+    /// 远程用量是否来自已同步文件的元数据缓存（否则为实时遍历服务器的结果）
+    pub remote_cached: bool,
+    /// 统计时间（Unix 时间戳，秒）
+    pub computed_at: i64,
+}
+
+// ========== 同步文件夹 CRUD 操作 ==========
+
+/// 列出所有同步文件夹
+#[tauri::command]
+pub async fn list_sync_folders(app: AppHandle) -> Result<Vec<SyncFolderConfig>> {
+    Ok(get_config(app).await?.sync_folders)
+}
+
+/// 添加同步文件夹
+///
+/// # 参数
+/// - input: 同步文件夹配置（不包含 id）
+///
+/// # 返回
+/// - 成功：返回包含生成 ID 的同步文件夹配置
+/// - 失败：返回错误信息（路径无效、与已有文件夹重叠、服务器不存在等）
+#[tauri::command]
+pub async fn add_sync_folder(
+    input: AddSyncFolderInput,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+    control: State<'_, SyncControl>,
+    states: State<'_, SyncStateManager>,
+) -> Result<SyncFolderConfig> {
+    let mut config = get_config(app.clone()).await?;
+
+    let folder = SyncFolderConfig {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: input.name,
+        local_path: input.local_path,
+        remote_path: input.remote_path,
+        server_id: input.server_id,
+        sync_direction: input.sync_direction,
+        sync_interval: input.sync_interval,
+        auto_sync: input.auto_sync,
+        ignore_patterns: input.ignore_patterns,
+        conflict_resolution: input.conflict_resolution,
+        selective_exclusions: input.selective_exclusions,
+        symlink_policy: input.symlink_policy,
+        encryption: input.encryption,
+        compression: input.compression,
+        max_delete_ratio: input.max_delete_ratio,
+        first_sync_strategy: input.first_sync_strategy,
+        max_file_size: input.max_file_size,
+        include_extensions: input.include_extensions,
+        exclude_extensions: input.exclude_extensions,
+        ignore_system_files: input.ignore_system_files,
+        sync_policy: input.sync_policy,
+    };
+
+    let folder = prepare_folder(folder, &config.sync_folders, &db, &clients).await?;
+
+    config.sync_folders.push(folder.clone());
+    update_config(app, config).await?;
+    folder_records::upsert(&db, &folder).await?;
+    states.apply_pause(&folder.id, control.is_paused(&folder.id));
+
+    tracing::info!(folder_id = %folder.id, local_path = %folder.local_path.display(), "已添加同步文件夹");
+    Ok(folder)
+}
+
+/// 更新同步文件夹
+///
+/// # 参数
+/// - folder: 更新后的同步文件夹配置（id 必须已存在）
+///
+/// # 返回
+/// - 成功：返回更新后的同步文件夹配置
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn update_sync_folder(
+    folder: SyncFolderConfig,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<SyncFolderConfig> {
+    let mut config = get_config(app.clone()).await?;
+
+    let index = config
+        .sync_folders
+        .iter()
+        .position(|f| f.id == folder.id)
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder.id)))?;
+
+    let folder = prepare_folder(folder, &config.sync_folders, &db, &clients).await?;
+
+    // 服务器或远程路径变化后，原有的增量同步令牌不再适用
+    let previous = &config.sync_folders[index];
+    if previous.server_id != folder.server_id || previous.remote_path != folder.remote_path {
+        sync_tokens::clear(&db, &folder.id).await?;
+    }
+
+    config.sync_folders[index] = folder.clone();
+    update_config(app, config).await?;
+    folder_records::upsert(&db, &folder).await?;
+
+    Ok(folder)
+}
+
+/// 修改同步文件夹的设置
+///
+/// 与 `update_sync_folder` 不同，只修改提供的设置字段，不访问服务器
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - settings: 要修改的设置（未提供的字段保持不变，未知字段会被拒绝）
+///
+/// # 返回
+/// - 成功：返回更新后的同步文件夹配置
+/// - 失败：返回错误信息（文件夹不存在、设置无效等）
+#[tauri::command]
+pub async fn update_sync_folder_settings(
+    folder_id: String,
+    settings: SyncFolderSettings,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<SyncFolderConfig> {
+    let mut config = get_config(app.clone()).await?;
+
+    let folder = config
+        .sync_folders
+        .iter_mut()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder_id)))?;
+    settings.apply_to(folder);
+    folders::validate_options(folder)?;
+    let folder = folder.clone();
+
+    update_config(app, config).await?;
+    folder_records::upsert(&db, &folder).await?;
+
+    Ok(folder)
+}
+
+/// 删除同步文件夹
+///
+/// 只删除同步配置，不会删除本地或远程的任何文件
+#[tauri::command]
+pub async fn remove_sync_folder(
+    folder_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+    control: State<'_, SyncControl>,
+    states: State<'_, SyncStateManager>,
+    scans: State<'_, ScanCache>,
+    watchers: State<'_, FileWatcherManager>,
+) -> Result<()> {
+    let mut config = get_config(app.clone()).await?;
+
+    let before = config.sync_folders.len();
+    config.sync_folders.retain(|f| f.id != folder_id);
+    if config.sync_folders.len() == before {
+        return Err(SyncError::NotFound(format!(
+            "Sync folder not found: {}",
+            folder_id
+        )));
+    }
+
+    control.resume(Some(&folder_id)).apply_to(&mut config);
+
+    update_config(app.clone(), config).await?;
+    sync_tokens::clear(&db, &folder_id).await?;
+    remote_snapshots::clear(&db, &folder_id).await?;
+    remote_cache::clear(&db, &folder_id).await?;
+    name_mappings::clear(&db, &folder_id).await?;
+    folder_records::delete(&db, &folder_id).await?;
+    states.remove(&folder_id);
+    watchers.stop(&app, &folder_id);
+    scans.invalidate(&folder_id);
+    match KeyringManager::delete_password(&encryption::passphrase_entry(&folder_id)) {
+        Ok(()) | Err(SyncError::NotFound(_)) => {}
+        Err(e) => tracing::warn!(folder_id = %folder_id, error = %e, "删除加密口令失败"),
+    }
+
+    tracing::info!(folder_id = %folder_id, "已删除同步文件夹");
+    Ok(())
+}
+
+// ========== 用量统计 ==========
+
+/// 统计同步文件夹本地与远程的文件数和总大小
+///
+/// 本地目录并行遍历；远程用量优先使用已同步文件的元数据缓存，
+/// 尚未同步过或要求刷新时通过 PROPFIND 递归遍历服务器
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - refresh: 是否忽略缓存、实时遍历服务器（可选，默认 false）
+///
+/// # 返回
+/// - 成功：返回本地与远程的用量
+/// - 失败：返回错误信息（本地目录不可读或服务器请求失败）
+#[tauri::command]
+pub async fn get_folder_stats(
+    folder_id: String,
+    refresh: Option<bool>,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<FolderStats> {
+    let folder = find_folder(app, &folder_id).await?;
+    let filter = SyncFilter::from_folder(&folder);
+
+    let root = folder.local_path.clone();
+    let local_filter = filter.clone();
+    let local = tokio::task::spawn_blocking(move || scanner::local_usage(&root, &local_filter))
+        .await
+        .map_err(|e| SyncError::Unknown(format!("Folder stats task failed: {}", e)))??;
+
+    let sync_folder_id = folder_keys::resolve(&db, &folder.id).await?;
+    let cached =
+        !refresh.unwrap_or(false) && file_metadata::has_synced(&db, sync_folder_id).await?;
+    let remote = if cached {
+        let (file_count, total_bytes) = file_metadata::synced_usage(&db, sync_folder_id).await?;
+        FolderUsage {
+            file_count,
+            total_bytes,
+        }
+    } else {
+        let (_, client) = clients.get(&db, &folder.server_id).await?;
+        // 混淆后的文件名无法匹配忽略规则
+        let list_filter = if folder.encryption.enabled {
+            SyncFilter::default()
+        } else {
+            filter
+        };
+        let entries = list_recursive(&client, &folder.remote_path, &list_filter).await?;
+        entries.iter().filter(|info| !info.is_directory).fold(
+            FolderUsage::default(),
+            |usage, info| FolderUsage {
+                file_count: usage.file_count + 1,
+                total_bytes: usage.total_bytes + info.size,
+            },
+        )
+    };
+
+    Ok(FolderStats {
+        folder_id,
+        local,
+        remote,
+        remote_cached: cached,
+        computed_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+// ========== 远程目录浏览 ==========
+
+/// 从远程目录树缓存中列出目录内容
+///
+/// 立即返回缓存中的子条目和缓存时间；缓存已过期或尚未缓存时在后台重新列出远程目录，
+/// 完成后发送 `sync://remote-cache-updated` 事件（加密文件夹只由同步更新缓存）
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - path: 目录的相对路径（空字符串或 `/` 表示同步根目录）
+///
+/// # 返回
+/// - 成功：返回缓存中的目录内容
+/// - 失败：返回错误信息（文件夹不存在或读取数据库失败）
+#[tauri::command]
+pub async fn browse_cached_remote(
+    folder_id: String,
+    path: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+    refreshes: State<'_, RemoteCacheRefreshes>,
+) -> Result<CachedRemoteListing> {
+    let folder = find_folder(app.clone(), &folder_id).await?;
+    let path = browse::normalize_path(&path);
+
+    let entries = remote_cache::list_children(&db, &folder.id, &path).await?;
+    let cached_at = remote_cache::cached_at(&db, &folder.id).await?;
+    let stale = browse::is_stale(cached_at, chrono::Utc::now().timestamp());
+
+    let refreshing = if stale && browse::can_refresh(&folder) && refreshes.begin(&folder.id) {
+        spawn_cache_refresh(app, folder);
+        true
+    } else {
+        refreshes.is_running(&folder_id)
+    };
+
+    Ok(CachedRemoteListing {
+        folder_id,
+        path,
+        entries,
+        cached_at,
+        stale,
+        refreshing,
+    })
+}
+
+/// 在后台刷新文件夹的远程目录树缓存，刷新失败只记录日志
+fn spawn_cache_refresh(app: AppHandle, folder: SyncFolderConfig) {
+    tauri::async_runtime::spawn(async move {
+        let result = async {
+            let db = app.state::<Database>();
+            let (_, client) = app
+                .state::<WebDavClientFactory>()
+                .get(&db, &folder.server_id)
+                .await?;
+            browse::refresh(&db, &client, &folder).await
+        }
+        .await;
+        app.state::<RemoteCacheRefreshes>().finish(&folder.id);
+
+        match result {
+            Ok(event) => {
+                if let Err(e) = app.emit(REMOTE_CACHE_UPDATED_EVENT, event) {
+                    tracing::warn!(error = %e, "发送远程目录缓存更新事件失败");
+                }
+            }
+            Err(e) => tracing::warn!(folder_id = %folder.id, error = %e, "刷新远程目录缓存失败"),
+        }
+    });
+}
+
+// ========== 选择性同步 ==========
+
+/// 获取同步文件夹的远程目录树，供用户选择要排除的子文件夹
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - max_depth: 最大展开深度（可选，默认 SELECTIVE_SYNC_TREE_DEPTH）
+///
+/// # 返回
+/// - 成功：返回文件夹树，已排除的节点 `excluded` 为 true
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_selective_sync_tree(
+    folder_id: String,
+    max_depth: Option<u32>,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<Vec<selective::RemoteFolderNode>> {
+    let folder = find_folder(app, &folder_id).await?;
+    let (_, client) = clients.get(&db, &folder.server_id).await?;
+
+    selective::fetch_folder_tree(
+        &client,
+        &folder.remote_path,
+        &folder.selective_exclusions,
+        max_depth.unwrap_or(SELECTIVE_SYNC_TREE_DEPTH),
+    )
+    .await
+}
+
+/// 设置同步文件夹的选择性同步排除列表
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - exclusions: 排除的远程子文件夹（相对于 remote_path）
+///
+/// # 返回
+/// - 成功：返回更新后的同步文件夹配置
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn set_selective_exclusions(
+    folder_id: String,
+    exclusions: Vec<String>,
+    app: AppHandle,
+) -> Result<SyncFolderConfig> {
+    let mut config = get_config(app.clone()).await?;
+
+    let folder = config
+        .sync_folders
+        .iter_mut()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder_id)))?;
+    folder.selective_exclusions = selective::normalize_exclusions(&exclusions)?;
+    let folder = folder.clone();
+
+    update_config(app, config).await?;
+
+    tracing::info!(folder_id = %folder_id, count = folder.selective_exclusions.len(), "已更新选择性同步排除列表");
+    Ok(folder)
+}
+
+// ========== 端到端加密 ==========
+
+/// 设置同步文件夹的加密口令
+///
+/// 口令保存在系统 Keyring 中。远程已有同步清单时，口令必须与创建清单时使用的口令一致，
+/// 否则下次同步会因无法解密清单而失败
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - passphrase: 加密口令
+///
+/// # 返回
+/// - 成功：返回 ()
+/// - 失败：文件夹不存在、口令为空或 Keyring 写入失败
+#[tauri::command]
+pub async fn set_folder_encryption_passphrase(
+    folder_id: String,
+    passphrase: String,
+    app: AppHandle,
+) -> Result<()> {
+    find_folder(app, &folder_id).await?;
+    if passphrase.is_empty() {
+        return Err(SyncError::ValidationError(
+            "Encryption passphrase cannot be empty".to_string(),
+        ));
+    }
+
+    KeyringManager::save_password(&encryption::passphrase_entry(&folder_id), &passphrase)?;
+
+    tracing::info!(folder_id = %folder_id, "已设置加密口令");
+    Ok(())
+}
+
+/// 检查同步文件夹是否已设置加密口令
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：返回是否已设置
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn has_folder_encryption_passphrase(folder_id: String) -> Result<bool> {
+    match encryption::load_passphrase(&folder_id) {
+        Ok(_) => Ok(true),
+        Err(SyncError::ValidationError(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// ========== 辅助函数 ==========
+
+/// 根据 ID 查找同步文件夹
+pub(crate) async fn find_folder(app: AppHandle, folder_id: &str) -> Result<SyncFolderConfig> {
+    get_config(app)
+        .await?
+        .sync_folders
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder_id)))
+}
+
+/// 校验同步文件夹并准备远程目录
+///
+/// 1. 校验基础字段
+/// 2. 校验本地路径并规范化
+/// 3. 检查与其他同步文件夹是否重叠
+/// 4. 确认服务器存在，并在远程创建缺失的目录
+/// 5. 在本地根目录写入标记文件
+async fn prepare_folder(
+    mut folder: SyncFolderConfig,
+    existing: &[SyncFolderConfig],
+    db: &Database,
+    clients: &WebDavClientFactory,
+) -> Result<SyncFolderConfig> {
+    folders::validate_options(&folder)?;
+    folder.selective_exclusions = selective::normalize_exclusions(&folder.selective_exclusions)?;
+
+    folder.local_path = folders::validate_local_path(&folder.local_path)?;
+
+    if let Some(other) = folders::find_overlap(existing, &folder.local_path, Some(&folder.id)) {
+        return Err(SyncError::ConfigError(format!(
+            "Local path overlaps with existing sync folder '{}' ({})",
+            other.name,
+            other.local_path.display()
+        )));
+    }
+
+    let (_, client) = clients.get(db, &folder.server_id).await?;
+    client.mkdir_all(&folder.remote_path).await?;
+    folders::write_root_marker(&folder.local_path, &folder.id)?;
+
+    Ok(folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folder_settings_apply() {
+        let mut folder: SyncFolderConfig = serde_json::from_value(serde_json::json!({
+            "id": "folder-1",
+            "name": "Documents",
+            "localPath": "/tmp/documents",
+            "remotePath": "/documents",
+            "serverId": "server-1",
+            "syncDirection": "bidirectional",
+            "syncInterval": 30,
+            "autoSync": true,
+            "ignorePatterns": [],
+            "conflictResolution": "ask",
+            "maxFileSize": 1024
+        }))
+        .unwrap();
+
+        let settings: SyncFolderSettings = serde_json::from_value(serde_json::json!({
+            "syncInterval": 5,
+            "ignorePatterns": ["*.tmp"]
+        }))
+        .unwrap();
+        settings.apply_to(&mut folder);
+        assert_eq!(folder.sync_interval, 5);
+        assert_eq!(folder.ignore_patterns, vec!["*.tmp"]);
+        assert_eq!(folder.max_file_size, Some(1024));
+        assert!(folder.auto_sync);
+
+        let settings: SyncFolderSettings =
+            serde_json::from_value(serde_json::json!({ "maxFileSize": null })).unwrap();
+        settings.apply_to(&mut folder);
+        assert_eq!(folder.max_file_size, None);
+
+        assert!(serde_json::from_value::<SyncFolderSettings>(
+            serde_json::json!({ "syncIntervall": 5 })
+        )
+        .is_err());
+    }
+}
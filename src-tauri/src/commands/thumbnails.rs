@@ -0,0 +1,43 @@
+/// 远程缩略图命令模块
+///
+/// 提供远程图片缩略图的获取命令，供远程文件浏览器显示预览
+use tauri::State;
+
+use crate::constants::THUMBNAIL_MAX_SIZE;
+use crate::database::Database;
+use crate::error::{Result, SyncError};
+use crate::webdav::factory::WebDavClientFactory;
+use crate::webdav::thumbnails::{self, RemoteThumbnail, ThumbnailCache};
+
+/// 获取远程图片的缩略图
+///
+/// Nextcloud 服务器使用预览接口生成缩略图，其他服务器下载较小的图片后在本地缩小；
+/// 结果缓存在应用数据目录中
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - path: 远程文件路径（相对于服务器根路径）
+/// - size: 缩略图的最大边长（像素，1 ~ THUMBNAIL_MAX_SIZE）
+///
+/// # 返回
+/// - 成功：返回 PNG 缩略图（Base64 编码）
+/// - 失败：返回错误信息（文件不存在、不是支持的图片格式或原图过大）
+#[tauri::command]
+pub async fn get_remote_thumbnail(
+    server_id: String,
+    path: String,
+    size: u32,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<RemoteThumbnail> {
+    if size == 0 || size > THUMBNAIL_MAX_SIZE {
+        return Err(SyncError::ValidationError(format!(
+            "Thumbnail size must be between 1 and {}",
+            THUMBNAIL_MAX_SIZE
+        )));
+    }
+
+    let (server, client) = clients.get(&db, &server_id).await?;
+    thumbnails::fetch(&cache, &server, &client, &path, size).await
+}
@@ -0,0 +1,457 @@
+/// 手动传输命令模块
+///
+/// 在文件夹同步之外，提供单个文件的一次性上传、下载。
+/// 传输过程通过 `transfer://progress` 事件推送进度，前端可传入 `transfer_id` 关联事件。
+/// 手动传输与文件夹同步的传输都登记到传输速度统计，通过 `get_transfer_stats` 查询实时速度。
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::constants::TRANSFER_PROGRESS_INTERVAL_MS;
+use crate::database::Database;
+use crate::error::{ErrorPayload, Result, SyncError};
+use crate::sync::throughput::{TransferMeter, TransferStats, TransferStatsSnapshot};
+use crate::sync::transfer::TransferKind;
+use crate::webdav::factory::WebDavClientFactory;
+
+/// 手动传输进度事件名称
+pub const TRANSFER_PROGRESS_EVENT: &str = "transfer://progress";
+
+/// 手动传输状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    /// 传输中
+    Running,
+    /// 已完成
+    Completed,
+    /// 失败
+    Failed,
+}
+
+/// 手动传输进度事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgressEvent {
+    /// 传输 ID
+    pub transfer_id: String,
+    /// 服务器 ID
+    pub server_id: String,
+    /// 传输方向
+    pub kind: TransferKind,
+    /// 本地路径
+    pub local_path: String,
+    /// 远程路径
+    pub remote_path: String,
+    /// 已传输字节数
+    pub bytes_transferred: u64,
+    /// 总字节数（下载时服务器未返回长度则为 None）
+    pub total_bytes: Option<u64>,
+    /// 传输状态
+    pub status: TransferStatus,
+    /// 失败原因
+    pub error: Option<ErrorPayload>,
+}
+
+/// 手动传输结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualTransferResult {
+    /// 传输 ID
+    pub transfer_id: String,
+    /// 规范化后的本地路径
+    pub local_path: String,
+    /// 规范化后的远程路径
+    pub remote_path: String,
+    /// 传输字节数
+    pub bytes: u64,
+    /// 耗时（毫秒）
+    pub duration_ms: i64,
+}
+
+// ========== 路径校验 ==========
+
+/// 规范化远程文件路径
+///
+/// 合并重复的 `/`，去除 `.` 分段，结果以 `/` 开头
+///
+/// # 返回
+/// - Ok(String): 规范化后的路径
+/// - Err(SyncError::ValidationError): 路径为空（指向根目录），或包含 `..`、反斜杠、控制字符
+pub fn sanitize_remote_path(path: &str) -> Result<String> {
+    let mut segments = Vec::new();
+
+    for segment in path.trim().split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => {
+                return Err(SyncError::ValidationError(format!(
+                    "Remote path must not contain '..': {}",
+                    path
+                )))
+            }
+            _ if segment.contains('\\') || segment.chars().any(char::is_control) => {
+                return Err(SyncError::ValidationError(format!(
+                    "Remote path contains invalid characters: {}",
+                    path
+                )))
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(SyncError::ValidationError(
+            "Remote path must point to a file".to_string(),
+        ));
+    }
+
+    Ok(format!("/{}", segments.join("/")))
+}
+
+/// 规范化本地文件路径
+///
+/// # 返回
+/// - Ok(PathBuf): 去除 `.` 分段后的绝对路径
+/// - Err(SyncError::ValidationError): 不是绝对路径，或包含 `..`
+pub fn sanitize_local_path(path: &Path) -> Result<PathBuf> {
+    if !path.is_absolute() {
+        return Err(SyncError::ValidationError(format!(
+            "Local path must be absolute: {}",
+            path.display()
+        )));
+    }
+
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(SyncError::ValidationError(format!(
+            "Local path must not contain '..': {}",
+            path.display()
+        )));
+    }
+
+    Ok(path
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect())
+}
+
+// ========== 进度推送 ==========
+
+/// 进度事件发送器（传输中的进度按时间间隔节流，速度统计每个数据块都更新）
+#[derive(Clone)]
+struct ProgressReporter {
+    app: AppHandle,
+    event: TransferProgressEvent,
+    last_emit: Arc<Mutex<Option<Instant>>>,
+    meter: Arc<TransferMeter>,
+}
+
+impl ProgressReporter {
+    fn new(app: AppHandle, event: TransferProgressEvent, stats: &TransferStats) -> Self {
+        let meter = stats.begin(
+            &event.server_id,
+            None,
+            &event.remote_path,
+            event.kind,
+            event.total_bytes,
+        );
+        Self {
+            app,
+            event,
+            last_emit: Arc::new(Mutex::new(None)),
+            meter: Arc::new(meter),
+        }
+    }
+
+    /// 推送传输中的进度
+    fn progress(&self, bytes_transferred: u64, total_bytes: Option<u64>) {
+        self.meter.update(bytes_transferred, total_bytes);
+        {
+            let mut last_emit = self.last_emit.lock().unwrap();
+            let interval = Duration::from_millis(TRANSFER_PROGRESS_INTERVAL_MS);
+            if last_emit.is_some_and(|at| at.elapsed() < interval) {
+                return;
+            }
+            *last_emit = Some(Instant::now());
+        }
+
+        let mut event = self.event.clone();
+        event.bytes_transferred = bytes_transferred;
+        event.total_bytes = total_bytes.or(event.total_bytes);
+        self.emit(&event);
+    }
+
+    /// 推送最终结果
+    fn finish(&self, result: &Result<u64>) {
+        let mut event = self.event.clone();
+        match result {
+            Ok(bytes) => {
+                event.status = TransferStatus::Completed;
+                event.bytes_transferred = *bytes;
+                event.total_bytes = Some(*bytes);
+            }
+            Err(e) => {
+                event.status = TransferStatus::Failed;
+                event.error = Some(ErrorPayload::from(e));
+            }
+        }
+        self.emit(&event);
+    }
+
+    fn emit(&self, event: &TransferProgressEvent) {
+        if let Err(e) = self.app.emit(TRANSFER_PROGRESS_EVENT, event) {
+            tracing::warn!(error = %e, "发送传输进度事件失败");
+        }
+    }
+}
+
+// ========== Tauri 命令 ==========
+
+/// 上传单个本地文件到服务器
+///
+/// 远程父目录不存在时自动创建
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - local_path: 本地文件绝对路径
+/// - remote_path: 远程文件路径（相对于服务器根路径）
+/// - transfer_id: 传输 ID（可选，用于关联进度事件，默认自动生成）
+///
+/// # 返回
+/// - 成功：返回传输结果
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn webdav_upload_file(
+    server_id: String,
+    local_path: PathBuf,
+    remote_path: String,
+    transfer_id: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<ManualTransferResult> {
+    let local_path = sanitize_local_path(&local_path)?;
+    let remote_path = sanitize_remote_path(&remote_path)?;
+
+    let metadata = tokio::fs::metadata(&local_path)
+        .await
+        .map_err(|_| SyncError::FileNotFound(local_path.display().to_string()))?;
+    if !metadata.is_file() {
+        return Err(SyncError::ValidationError(format!(
+            "Local path is not a file: {}",
+            local_path.display()
+        )));
+    }
+
+    let (_, client) = clients.get(&db, &server_id).await?;
+    if let Some((parent, _)) = remote_path.rsplit_once('/') {
+        if !parent.is_empty() {
+            client.mkdir_all(parent).await?;
+        }
+    }
+
+    let stats = app.state::<TransferStats>().inner().clone();
+    let reporter = ProgressReporter::new(
+        app,
+        new_event(
+            transfer_id,
+            &server_id,
+            TransferKind::Upload,
+            &local_path,
+            &remote_path,
+            Some(metadata.len()),
+        ),
+        &stats,
+    );
+
+    tracing::info!(
+        server_id = %server_id,
+        local = %local_path.display(),
+        remote = %remote_path,
+        "开始手动上传"
+    );
+    let started = Instant::now();
+    let progress = reporter.clone();
+    let result = client
+        .upload_with_progress(&local_path, &remote_path, move |sent, total| {
+            progress.progress(sent, total)
+        })
+        .await;
+    reporter.finish(&result);
+
+    Ok(ManualTransferResult {
+        transfer_id: reporter.event.transfer_id.clone(),
+        local_path: local_path.display().to_string(),
+        remote_path,
+        bytes: result?,
+        duration_ms: started.elapsed().as_millis() as i64,
+    })
+}
+
+/// 从服务器下载单个文件到本地
+///
+/// 本地父目录不存在时自动创建；目标文件已存在时会被覆盖
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - remote_path: 远程文件路径（相对于服务器根路径）
+/// - local_path: 本地文件绝对路径
+/// - transfer_id: 传输 ID（可选，用于关联进度事件，默认自动生成）
+///
+/// # 返回
+/// - 成功：返回传输结果
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn webdav_download_file(
+    server_id: String,
+    remote_path: String,
+    local_path: PathBuf,
+    transfer_id: Option<String>,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<ManualTransferResult> {
+    let local_path = sanitize_local_path(&local_path)?;
+    let remote_path = sanitize_remote_path(&remote_path)?;
+
+    if local_path.is_dir() {
+        return Err(SyncError::ValidationError(format!(
+            "Local path is a directory: {}",
+            local_path.display()
+        )));
+    }
+    if let Some(parent) = local_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let (_, client) = clients.get(&db, &server_id).await?;
+
+    let stats = app.state::<TransferStats>().inner().clone();
+    let reporter = ProgressReporter::new(
+        app,
+        new_event(
+            transfer_id,
+            &server_id,
+            TransferKind::Download,
+            &local_path,
+            &remote_path,
+            None,
+        ),
+        &stats,
+    );
+
+    tracing::info!(
+        server_id = %server_id,
+        remote = %remote_path,
+        local = %local_path.display(),
+        "开始手动下载"
+    );
+    let started = Instant::now();
+    let result = client
+        .download_with_progress(&remote_path, &local_path, |written, total| {
+            reporter.progress(written, total)
+        })
+        .await;
+    reporter.finish(&result);
+
+    Ok(ManualTransferResult {
+        transfer_id: reporter.event.transfer_id.clone(),
+        local_path: local_path.display().to_string(),
+        remote_path,
+        bytes: result?,
+        duration_ms: started.elapsed().as_millis() as i64,
+    })
+}
+
+/// 获取实时传输速度
+///
+/// # 返回
+/// 进行中的传输（文件夹同步与手动传输）及各服务器在滑动窗口内的速度
+#[tauri::command]
+pub async fn get_transfer_stats(stats: State<'_, TransferStats>) -> Result<TransferStatsSnapshot> {
+    Ok(stats.snapshot())
+}
+
+/// 构建传输开始时的进度事件
+fn new_event(
+    transfer_id: Option<String>,
+    server_id: &str,
+    kind: TransferKind,
+    local_path: &Path,
+    remote_path: &str,
+    total_bytes: Option<u64>,
+) -> TransferProgressEvent {
+    TransferProgressEvent {
+        transfer_id: transfer_id
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        server_id: server_id.to_string(),
+        kind,
+        local_path: local_path.display().to_string(),
+        remote_path: remote_path.to_string(),
+        bytes_transferred: 0,
+        total_bytes,
+        status: TransferStatus::Running,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_remote_path() {
+        assert_eq!(sanitize_remote_path("docs/a.txt").unwrap(), "/docs/a.txt");
+        assert_eq!(
+            sanitize_remote_path(" //docs/./sub//a.txt ").unwrap(),
+            "/docs/sub/a.txt"
+        );
+        assert_eq!(
+            sanitize_remote_path("/文档/报告 1.pdf").unwrap(),
+            "/文档/报告 1.pdf"
+        );
+
+        assert!(sanitize_remote_path("/").is_err());
+        assert!(sanitize_remote_path("/docs/../etc/passwd").is_err());
+        assert!(sanitize_remote_path("/docs\\a.txt").is_err());
+        assert!(matches!(
+            sanitize_remote_path("/docs/a\n.txt"),
+            Err(SyncError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_sanitize_local_path() {
+        let base = std::env::temp_dir();
+
+        assert_eq!(
+            sanitize_local_path(&base.join(".").join("a.txt")).unwrap(),
+            base.join("a.txt")
+        );
+        assert!(sanitize_local_path(Path::new("relative/a.txt")).is_err());
+        assert!(sanitize_local_path(&base.join("..").join("a.txt")).is_err());
+    }
+
+    #[test]
+    fn test_progress_event_serialization() {
+        let event = new_event(
+            None,
+            "server-1",
+            TransferKind::Upload,
+            Path::new("/tmp/a.txt"),
+            "/a.txt",
+            Some(10),
+        );
+        assert!(!event.transfer_id.is_empty());
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["kind"], "upload");
+        assert_eq!(json["status"], "running");
+        assert_eq!(json["totalBytes"], 10);
+        assert!(json["error"].is_null());
+    }
+}
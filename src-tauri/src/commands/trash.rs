@@ -0,0 +1,94 @@
+/// 回收站命令模块
+///
+/// 提供回收站条目的列表、恢复和永久删除命令，并在启动时按保留策略清理过期条目
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::get_config;
+use crate::database::{trash as trash_db, Database, TrashItem};
+use crate::error::Result;
+use crate::sync::trash::Trash;
+
+/// 获取回收站条目
+///
+/// # 参数
+/// - folder_id: 只返回指定同步文件夹的条目（可选）
+///
+/// # 返回
+/// - 成功：按删除时间倒序的条目列表
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn list_trash_items(
+    folder_id: Option<String>,
+    db: State<'_, Database>,
+) -> Result<Vec<TrashItem>> {
+    trash_db::list(&db, folder_id.as_deref()).await
+}
+
+/// 恢复回收站条目到原始位置
+///
+/// # 参数
+/// - id: 回收站条目 ID
+///
+/// # 返回
+/// - 成功：返回恢复后的路径
+/// - 失败：返回错误信息（原始位置已存在同名文件时返回冲突错误）
+#[tauri::command]
+pub async fn restore_trash_item(
+    id: String,
+    db: State<'_, Database>,
+    trash: State<'_, Trash>,
+) -> Result<String> {
+    let path = trash.restore(&db, &id).await?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// 永久删除回收站条目
+///
+/// # 参数
+/// - id: 回收站条目 ID
+#[tauri::command]
+pub async fn purge_trash_item(
+    id: String,
+    db: State<'_, Database>,
+    trash: State<'_, Trash>,
+) -> Result<()> {
+    trash.purge(&db, &id).await
+}
+
+/// 清空回收站
+///
+/// # 参数
+/// - folder_id: 只清空指定同步文件夹的条目（可选，默认全部）
+///
+/// # 返回
+/// - 成功：返回被删除的条目数量
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn empty_trash(
+    folder_id: Option<String>,
+    db: State<'_, Database>,
+    trash: State<'_, Trash>,
+) -> Result<usize> {
+    trash.empty(&db, folder_id.as_deref()).await
+}
+
+/// 在后台按配置的保留天数清理过期条目
+///
+/// 在应用启动时调用，清理失败只记录日志，不影响启动
+pub fn spawn_retention_cleanup(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let retention_days = match get_config(app.clone()).await {
+            Ok(config) => config.trash_retention_days,
+            Err(e) => {
+                tracing::warn!(error = %e, "读取配置失败，跳过回收站清理");
+                return;
+            }
+        };
+
+        let db = app.state::<Database>();
+        let trash = app.state::<Trash>();
+        if let Err(e) = trash.purge_expired(&db, retention_days).await {
+            tracing::warn!(error = %e, "清理过期回收站条目失败");
+        }
+    });
+}
@@ -0,0 +1,55 @@
+/// 文件历史版本命令模块
+///
+/// 提供文件历史版本的查询和恢复命令
+use std::path::PathBuf;
+
+use tauri::{AppHandle, State};
+
+use crate::config::get_config;
+use crate::database::{Database, FileVersion};
+use crate::error::Result;
+use crate::sync::versions::VersionStore;
+
+/// 获取文件的历史版本
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+/// - path: 文件绝对路径
+///
+/// # 返回
+/// - 成功：返回版本列表（最新的在前）
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn list_file_versions(
+    folder_id: String,
+    path: PathBuf,
+    db: State<'_, Database>,
+    versions: State<'_, VersionStore>,
+) -> Result<Vec<FileVersion>> {
+    versions.list(&db, &folder_id, &path).await
+}
+
+/// 将文件恢复到指定的历史版本
+///
+/// 恢复前会先保存文件的当前内容作为新版本
+///
+/// # 参数
+/// - version_id: 版本 ID
+///
+/// # 返回
+/// - 成功：返回被恢复的文件路径
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn restore_file_version(
+    version_id: i64,
+    app: AppHandle,
+    db: State<'_, Database>,
+    versions: State<'_, VersionStore>,
+) -> Result<String> {
+    let config = get_config(app).await?;
+    let path = versions
+        .restore(&db, version_id, config.max_versions_per_file)
+        .await?;
+
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,66 @@
+/// 文件监控命令模块
+///
+/// 提供启动、停止同步文件夹的本地文件监控以及查询监控事件队列统计的命令
+use tauri::{AppHandle, State};
+
+use crate::config::get_config;
+use crate::error::{Result, SyncError};
+use crate::file_watcher::{EventMetrics, FileWatcherManager};
+
+/// 开始监控同步文件夹的本地文件变化
+///
+/// 文件变化停止后自动同步该文件夹，之后的同步增量扫描本地目录
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：Ok(())
+/// - 失败：返回错误信息（文件夹不存在时返回 NotFound，已在监控或无法监控时返回 WatcherError）
+#[tauri::command]
+pub async fn start_folder_watcher(
+    folder_id: String,
+    app: AppHandle,
+    watchers: State<'_, FileWatcherManager>,
+) -> Result<()> {
+    let config = get_config(app.clone()).await?;
+    let folder = config
+        .sync_folders
+        .iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| SyncError::NotFound(format!("Sync folder not found: {}", folder_id)))?;
+
+    watchers.start(&app, &folder.id, &folder.local_path)
+}
+
+/// 停止监控同步文件夹（未在监控时不做任何事）
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+#[tauri::command]
+pub async fn stop_folder_watcher(
+    folder_id: String,
+    app: AppHandle,
+    watchers: State<'_, FileWatcherManager>,
+) -> Result<()> {
+    watchers.stop(&app, &folder_id);
+    Ok(())
+}
+
+/// 获取同步文件夹监控事件队列的统计
+///
+/// # 参数
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - 成功：接收、合并、丢弃的事件数与溢出次数
+/// - 失败：返回错误信息（文件夹未在监控时返回 NotFound）
+#[tauri::command]
+pub async fn get_watcher_metrics(
+    folder_id: String,
+    watchers: State<'_, FileWatcherManager>,
+) -> Result<EventMetrics> {
+    watchers
+        .metrics(&folder_id)
+        .ok_or_else(|| SyncError::NotFound(format!("Folder watcher not running: {}", folder_id)))
+}
@@ -1,10 +1,11 @@
 /// WebDAV 命令模块
 ///
-/// 提供 WebDAV 服务器配置管理和连接测试的 Tauri 命令
-use tauri::AppHandle;
+/// 提供 WebDAV 服务器配置管理、服务器归档和连接测试（包括分阶段连接测试）的 Tauri 命令
+use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::database::WebDavServerConfig;
+use crate::database::{ArchivedWebDavServer, CustomHeaders, Database, WebDavServerConfig};
 use crate::error::Result;
+use crate::webdav::factory::WebDavClientFactory;
 
 // ========== 输入数据结构 ==========
 
@@ -20,7 +21,7 @@ pub struct AddServerInput {
     pub username: String,
     /// 是否使用 HTTPS
     pub use_https: bool,
-    /// 连接超时时间（秒）:
+    /// 请求超时时间（秒）:
     pub timeout: u32,
     /// 最后连接测试状态（可选，默认 "unknown"）
     #[serde(default)]
@@ -31,12 +32,45 @@ pub struct AddServerInput {
     /// 是否启用（可选，默认 true）
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// 是否接受无效证书（可选，默认 false）
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// 固定的证书 SHA-256 指纹（可选）
+    #[serde(default)]
+    pub pinned_cert_fingerprint: Option<String>,
+    /// 每个请求附带的自定义请求头（可选）
+    #[serde(default)]
+    pub custom_headers: CustomHeaders,
+    /// 连接时使用的 IP 协议版本（可选，默认 "any"）
+    #[serde(default = "default_ip_version")]
+    pub ip_version: String,
+    /// 服务器主机名固定解析到的 IP 地址（可选）
+    #[serde(default)]
+    pub resolve_ip: Option<String>,
+    /// 建立连接超时时间（秒，可选）
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout: u32,
+    /// 读取超时时间（秒，可选）
+    #[serde(default = "default_read_timeout")]
+    pub read_timeout: u32,
 }
 
 fn default_enabled() -> bool {
     true
 }
 
+fn default_ip_version() -> String {
+    crate::constants::ip_version::ANY.to_string()
+}
+
+fn default_connect_timeout() -> u32 {
+    crate::constants::DEFAULT_CONNECT_TIMEOUT
+}
+
+fn default_read_timeout() -> u32 {
+    crate::constants::DEFAULT_READ_TIMEOUT
+}
+
 // ========== 服务器配置 CRUD 操作 ==========
 
 /// 添加 WebDAV 服务器配置
@@ -47,12 +81,13 @@ fn default_enabled() -> bool {
 ///
 /// # 返回
 /// - 成功：返回包含生成 ID 的服务器配置
-/// - 失败：返回错误信息
+/// - 失败：返回错误信息；相同地址和用户名的服务器已存在时返回 `DuplicateServer`，
+///   上下文 `serverId` 为已有服务器的 ID，前端可据此改为编辑已有服务器
 #[tauri::command]
 pub async fn add_webdav_server(
     input: AddServerInput,
     password: String,
-    app: AppHandle,
+    db: State<'_, Database>,
 ) -> Result<WebDavServerConfig> {
     use crate::webdav::db;
     use crate::webdav::keyring::KeyringManager;
@@ -79,6 +114,14 @@ pub async fn add_webdav_server(
             input.last_test_status
         },
         last_test_error: None,
+        auth_type: crate::constants::auth_type::BASIC.to_string(),
+        accept_invalid_certs: input.accept_invalid_certs,
+        pinned_cert_fingerprint: input.pinned_cert_fingerprint,
+        custom_headers: input.custom_headers,
+        ip_version: input.ip_version,
+        resolve_ip: input.resolve_ip,
+        connect_timeout: input.connect_timeout,
+        read_timeout: input.read_timeout,
         server_type: if input.server_type.is_empty() {
             "generic".to_string()
         } else {
@@ -91,10 +134,10 @@ pub async fn add_webdav_server(
 
     // 4. 验证配置（会在 insert_webdav_server 中执行）
     // 5. 插入数据库
-    let inserted_config = db::insert_webdav_server(app.clone(), config).await?;
+    let inserted_config = db::insert_webdav_server(&db, config).await?;
 
-    // 6. 保存密码到 Keyring
-    KeyringManager::save_password(&server_id, &password)?;
+    // 6. 保存账户密码到 Keyring
+    KeyringManager::save_account_password(&inserted_config, &password)?;
 
     Ok(inserted_config)
 }
@@ -111,11 +154,15 @@ pub async fn add_webdav_server(
 pub async fn get_webdav_servers(
     enabled_only: bool,
     app: AppHandle,
+    db: State<'_, Database>,
 ) -> Result<Vec<WebDavServerConfig>> {
     use crate::webdav::db;
 
+    // 设置了主密码时需要先解锁
+    super::app_lock::ensure_unlocked(&app).await?;
+
     // 从数据库查询服务器配置
-    db::get_webdav_servers(app, enabled_only).await
+    db::get_webdav_servers(&db, enabled_only).await
 }
 
 /// 获取单个 WebDAV 服务器配置
@@ -127,15 +174,24 @@ pub async fn get_webdav_servers(
 /// - 成功：返回服务器配置
 /// - 失败：返回错误信息
 #[tauri::command]
-pub async fn get_webdav_server(server_id: String, app: AppHandle) -> Result<WebDavServerConfig> {
+pub async fn get_webdav_server(
+    server_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<WebDavServerConfig> {
     use crate::webdav::db;
 
+    // 设置了主密码时需要先解锁
+    super::app_lock::ensure_unlocked(&app).await?;
+
     // 从数据库查询指定 ID 的服务器配置
-    db::get_webdav_server_by_id(app, &server_id).await
+    db::get_webdav_server_by_id(&db, &server_id).await
 }
 
 /// 更新 WebDAV 服务器配置
 ///
+/// 密码按账户保存，修改用户名时必须同时提供新账户的密码，旧账户的密码会被删除
+///
 /// # 参数
 /// - server_id: 服务器 ID
 /// - config: 更新后的服务器配置
@@ -149,19 +205,36 @@ pub async fn update_webdav_server(
     server_id: String,
     config: WebDavServerConfig,
     password: Option<String>,
-    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
 ) -> Result<WebDavServerConfig> {
     use crate::webdav::db;
     use crate::webdav::keyring::KeyringManager;
 
+    let current = db::get_webdav_server_by_id(&db, &server_id).await?;
+    let username_changed = current.username.trim() != config.username.trim();
+    if username_changed && password.is_none() {
+        return Err(crate::SyncError::ValidationError(
+            "A password is required when changing the username".to_string(),
+        ));
+    }
+
     // 1. 验证配置并更新数据库（会在 update_webdav_server 中验证）
-    let updated_config = db::update_webdav_server(app, &server_id, config).await?;
+    let mut updated_config = db::update_webdav_server(&db, &server_id, config).await?;
+    // 数据库中的 ID 以 server_id 为准
+    updated_config.id = server_id;
 
     // 2. 如果提供了新密码，更新 Keyring
     if let Some(new_password) = password {
-        KeyringManager::save_password(&server_id, &new_password)?;
+        KeyringManager::save_account_password(&updated_config, &new_password)?;
     }
 
+    // 3. 用户名变化后旧账户的密码不再使用
+    if username_changed {
+        remove_account_password(&current);
+    }
+    clients.invalidate(&updated_config.id);
+
     Ok(updated_config)
 }
 
@@ -174,19 +247,35 @@ pub async fn update_webdav_server(
 /// - 成功：返回 ()
 /// - 失败：返回错误信息（如果服务器正在被使用）
 #[tauri::command]
-pub async fn delete_webdav_server(server_id: String, app: AppHandle) -> Result<()> {
+pub async fn delete_webdav_server(
+    server_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<()> {
     use crate::webdav::db;
-    use crate::webdav::keyring::KeyringManager;
 
     // 1. 检查服务器是否被 sync_folders 使用
-    check_server_in_use(&server_id, app.clone()).await?;
+    check_server_in_use(&server_id, app).await?;
 
     // 2. 从数据库删除记录
-    db::delete_webdav_server(app, &server_id).await?;
+    let config = db::get_webdav_server_by_id(&db, &server_id).await?;
+    db::delete_webdav_server(&db, &server_id).await?;
+    crate::database::server_health::delete_by_server(&db, &server_id).await?;
+    clients.invalidate(&server_id);
 
     // 3. 从 Keyring 删除密码
     // 注意：即使密码不存在也不应该失败，因为数据库删除已成功
-    match KeyringManager::delete_password(&server_id) {
+    remove_account_password(&config);
+
+    Ok(())
+}
+
+/// 从 Keyring 删除账户密码，密码不存在或删除失败时只记录日志
+fn remove_account_password(config: &WebDavServerConfig) {
+    use crate::webdav::keyring::KeyringManager;
+
+    match KeyringManager::delete_account_password(config) {
         Ok(_) => {}
         Err(crate::SyncError::NotFound(_)) => {
             // 密码不存在，忽略错误
@@ -196,8 +285,6 @@ pub async fn delete_webdav_server(server_id: String, app: AppHandle) -> Result<(
             eprintln!("Warning: Failed to delete password from keyring: {}", e);
         }
     }
-
-    Ok(())
 }
 
 /// 检查服务器是否被 sync_folders 使用
@@ -238,6 +325,194 @@ pub async fn check_server_in_use(server_id: &str, app: AppHandle) -> Result<()>
     Ok(())
 }
 
+/// 批量启用或禁用 WebDAV 服务器
+///
+/// 所有更新在同一个数据库事务中完成，完成后发送一次 `webdav://servers-changed` 事件
+///
+/// # 参数
+/// - server_ids: 服务器 ID 列表
+/// - enabled: 是否启用
+///
+/// # 返回
+/// - 成功：返回更新后的服务器配置
+/// - 失败：返回错误信息（任一服务器不存在时不会修改任何服务器）
+#[tauri::command]
+pub async fn set_servers_enabled(
+    server_ids: Vec<String>,
+    enabled: bool,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Vec<WebDavServerConfig>> {
+    if server_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let servers = crate::webdav::db::set_servers_enabled(&db, &server_ids, enabled).await?;
+
+    let event = ServersChangedEvent {
+        server_ids,
+        enabled,
+    };
+    if let Err(e) = app.emit(SERVERS_CHANGED_EVENT, event) {
+        tracing::warn!(error = %e, "发送服务器变化事件失败");
+    }
+
+    Ok(servers)
+}
+
+// ========== 服务器归档 ==========
+
+/// 归档 WebDAV 服务器（软删除）
+///
+/// 服务器被禁用，使用该服务器的同步文件夹被暂停（正在进行的传输在当前数据块完成后停止）；
+/// 配置、密码与历史记录保留 `server_archive_retention_days` 天，期间可以恢复
+///
+/// # 参数
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - 成功：返回归档后的服务器与被暂停的同步文件夹
+/// - 失败：返回错误信息（服务器不存在或已归档时返回 NotFound）
+#[tauri::command]
+pub async fn archive_webdav_server(
+    server_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ArchiveServerResult> {
+    use crate::config::get_config;
+    use crate::sync::control::SyncControl;
+    use crate::webdav::db;
+
+    let deleted_at = chrono::Utc::now().timestamp();
+    let server = db::archive_webdav_server(&db, &server_id, deleted_at).await?;
+    app.state::<WebDavClientFactory>().invalidate(&server_id);
+
+    let paused_folders: Vec<String> = get_config(app.clone())
+        .await?
+        .sync_folders
+        .into_iter()
+        .filter(|folder| folder.server_id == server_id)
+        .map(|folder| folder.id)
+        .collect();
+    if !paused_folders.is_empty() {
+        let control = app.state::<SyncControl>();
+        for folder_id in &paused_folders {
+            control.pause(Some(folder_id));
+        }
+        super::sync::save_pause_state(app.clone(), &control.state()).await?;
+    }
+
+    tracing::info!(server_id = %server_id, paused_folders = paused_folders.len(), "已归档服务器");
+    Ok(ArchiveServerResult {
+        server: ArchivedWebDavServer { server, deleted_at },
+        paused_folders,
+    })
+}
+
+/// 恢复已归档的 WebDAV 服务器
+///
+/// 恢复后服务器仍处于禁用状态，归档时暂停的同步文件夹也保持暂停，由用户确认后重新启用
+///
+/// # 参数
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - 成功：返回恢复后的服务器配置
+/// - 失败：返回错误信息（归档期间添加了相同账户时返回 DuplicateServer）
+#[tauri::command]
+pub async fn restore_webdav_server(
+    server_id: String,
+    db: State<'_, Database>,
+) -> Result<WebDavServerConfig> {
+    crate::webdav::db::restore_webdav_server(&db, &server_id).await
+}
+
+/// 获取已归档的 WebDAV 服务器列表
+///
+/// # 返回
+/// - 成功：返回已归档的服务器（最近归档的在前）
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn get_archived_webdav_servers(
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Vec<ArchivedWebDavServer>> {
+    // 设置了主密码时需要先解锁
+    super::app_lock::ensure_unlocked(&app).await?;
+
+    crate::webdav::db::get_archived_webdav_servers(&db, None).await
+}
+
+/// 永久删除超过保留期的归档服务器
+///
+/// 删除数据库记录、健康检查历史与 Keyring 中的密码；仍被同步文件夹使用的服务器暂不删除
+///
+/// # 参数
+/// - app: Tauri 应用句柄
+/// - db: 共享数据库连接
+/// - retention_days: 保留天数，0 表示永久保留
+///
+/// # 返回
+/// - Ok(usize): 删除的服务器数量
+/// - Err(SyncError): 读取配置或删除失败
+pub async fn purge_archived_servers(
+    app: &AppHandle,
+    db: &Database,
+    retention_days: u32,
+) -> Result<usize> {
+    use crate::config::get_config;
+    use crate::webdav::db;
+
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = chrono::Utc::now().timestamp() - i64::from(retention_days) * 24 * 60 * 60;
+    let sync_folders = get_config(app.clone()).await?.sync_folders;
+
+    let mut purged = 0;
+    for archived in db::get_archived_webdav_servers(db, Some(cutoff)).await? {
+        let server = archived.server;
+        if sync_folders
+            .iter()
+            .any(|folder| folder.server_id == server.id)
+        {
+            tracing::info!(server_id = %server.id, "归档的服务器仍被同步文件夹使用，暂不删除");
+            continue;
+        }
+
+        db::delete_webdav_server(db, &server.id).await?;
+        crate::database::server_health::delete_by_server(db, &server.id).await?;
+        app.state::<WebDavClientFactory>().invalidate(&server.id);
+        remove_account_password(&server);
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
+/// 在后台永久删除超过保留期的归档服务器
+///
+/// 在应用启动时调用，清理失败只记录日志，不影响启动
+pub fn spawn_archive_purge(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let retention_days = match crate::config::get_config(app.clone()).await {
+            Ok(config) => config.server_archive_retention_days,
+            Err(e) => {
+                tracing::warn!(error = %e, "读取配置失败，跳过归档服务器清理");
+                return;
+            }
+        };
+
+        let db = app.state::<Database>();
+        match purge_archived_servers(&app, &db, retention_days).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!(purged, "已删除过期的归档服务器"),
+            Err(e) => tracing::warn!(error = %e, "清理过期的归档服务器失败"),
+        }
+    });
+}
+
 // ========== 连接测试 ==========
 
 /// 测试 WebDAV 服务器连接
@@ -251,7 +526,7 @@ pub async fn check_server_in_use(server_id: &str, app: AppHandle) -> Result<()>
 #[tauri::command]
 pub async fn test_webdav_connection(
     server_id: String,
-    app: AppHandle,
+    db: State<'_, Database>,
 ) -> Result<ConnectionTestResult> {
     use crate::webdav::client::WebDavClient;
     use crate::webdav::db;
@@ -260,20 +535,94 @@ pub async fn test_webdav_connection(
     tracing::info!(server_id = %server_id, "开始测试 WebDAV 连接");
 
     // 1. 从数据库读取服务器配置
-    let config = db::get_webdav_server_by_id(app.clone(), &server_id).await?;
+    let config = db::get_webdav_server_by_id(&db, &server_id).await?;
     tracing::debug!(url = %config.url, username = %config.username, "已加载服务器配置");
 
     // 2. 从 Keyring 读取密码
-    let password = KeyringManager::get_password(&server_id)?;
+    let password = KeyringManager::get_account_password(&config)?;
     tracing::debug!("已从 Keyring 读取密码");
 
     // 3. 创建 WebDavClient
     let client = WebDavClient::new(&config, password)?;
     tracing::debug!("已创建 WebDavClient 实例");
 
-    // 4. 执行连接测试
+    // 4. 执行连接测试（固定了证书指纹时先校验证书）
+    let outcome = match client.verify_certificate().await {
+        Ok(()) => client.test_connection().await,
+        Err(e) => Err(e),
+    };
+
+    record_test_result(&db, config, outcome, None, &client).await
+}
+
+/// 分阶段测试 WebDAV 服务器连接
+///
+/// 与 `test_webdav_connection` 相同，但每个阶段（DNS 解析、TCP 连接、TLS 握手、认证、
+/// WebDAV 支持、服务器类型、存储配额）结束后立即通过 `webdav://connection-test-stage`
+/// 事件推送结果，供前端展示逐步的连接向导。所有阶段结束后才返回
+///
+/// # 参数
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - 成功：返回连接测试结果（包含可用空间）
+/// - 失败：返回错误信息（服务器或密码不存在等，连接失败时返回 success 为 false 的结果）
+#[tauri::command]
+pub async fn test_webdav_connection_staged(
+    server_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<ConnectionTestResult> {
+    use crate::webdav::client::WebDavClient;
+    use crate::webdav::connection_test::{self, CONNECTION_TEST_STAGE_EVENT};
+    use crate::webdav::db;
+    use crate::webdav::keyring::KeyringManager;
+    use tauri::Emitter;
+
+    tracing::info!(server_id = %server_id, "开始分阶段测试 WebDAV 连接");
+
+    let config = db::get_webdav_server_by_id(&db, &server_id).await?;
+    let password = KeyringManager::get_account_password(&config)?;
+    let client = WebDavClient::new(&config, password)?;
+
+    let outcome = connection_test::run(&server_id, &client, config.accept_invalid_certs, |event| {
+        if let Err(e) = app.emit(CONNECTION_TEST_STAGE_EVENT, event) {
+            tracing::warn!(error = %e, "发送连接测试阶段事件失败");
+        }
+    })
+    .await;
+
+    let available_space = outcome
+        .as_ref()
+        .ok()
+        .and_then(|outcome| outcome.quota.as_ref())
+        .and_then(|quota| quota.available_bytes);
+    let outcome = outcome.map(|outcome| outcome.server_type);
+
+    record_test_result(&db, config, outcome, available_space, &client).await
+}
+
+/// 将连接测试结果写入数据库并构建返回值
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - config: 被测试的服务器配置
+/// - outcome: 测试结果（成功时为检测到的服务器类型）
+/// - available_space: 可用空间（字节，未查询时为 None）
+/// - client: 执行测试的客户端（读取永久重定向后的新服务器 URL 和可用的 PROPFIND 请求方式）
+async fn record_test_result(
+    db: &Database,
+    config: WebDavServerConfig,
+    outcome: Result<String>,
+    available_space: Option<u64>,
+    client: &crate::webdav::client::WebDavClient,
+) -> Result<ConnectionTestResult> {
+    use crate::webdav::db;
+
+    let server_id = config.id.clone();
+    let redirected_url = client.redirected_url();
     let now = chrono::Utc::now().timestamp();
-    let test_result = match client.test_connection().await {
+    let test_result = match outcome {
         Ok(server_type) => {
             // 连接成功
             tracing::info!(
@@ -282,14 +631,15 @@ pub async fn test_webdav_connection(
                 "连接测试成功"
             );
 
-            let mut updated_config = config.clone();
+            let mut updated_config = config;
             updated_config.last_test_at = Some(now);
             updated_config.last_test_status = "success".to_string();
             updated_config.last_test_error = None;
             updated_config.server_type = server_type.clone();
 
-            // 5. 更新数据库中的测试状态
-            db::update_webdav_server(app, &server_id, updated_config).await?;
+            // 5. 更新数据库中的测试状态与可用的 PROPFIND 请求方式
+            db::update_webdav_server(db, &server_id, updated_config).await?;
+            db::set_propfind_dialect(db, &server_id, client.propfind_dialect().as_str()).await?;
             tracing::debug!("已更新数据库测试状态");
 
             // 6. 返回测试结果
@@ -298,8 +648,9 @@ pub async fn test_webdav_connection(
                 message: format!("Successfully connected to {} server", server_type),
                 server_info: Some(ServerInfo {
                     server_type,
-                    available_space: None, // TODO: 实现空间查询（可选功能）
+                    available_space,
                 }),
+                redirected_url,
             }
         }
         Err(e) => {
@@ -311,13 +662,13 @@ pub async fn test_webdav_connection(
                 "连接测试失败"
             );
 
-            let mut updated_config = config.clone();
+            let mut updated_config = config;
             updated_config.last_test_at = Some(now);
             updated_config.last_test_status = "failed".to_string();
             updated_config.last_test_error = Some(error_message.clone());
 
             // 5. 更新数据库中的测试状态
-            db::update_webdav_server(app, &server_id, updated_config).await?;
+            db::update_webdav_server(db, &server_id, updated_config).await?;
             tracing::debug!("已更新数据库测试状态");
 
             // 6. 返回测试结果
@@ -325,6 +676,7 @@ pub async fn test_webdav_connection(
                 success: false,
                 message: error_message,
                 server_info: None,
+                redirected_url,
             }
         }
     };
@@ -332,8 +684,265 @@ pub async fn test_webdav_connection(
     Ok(test_result)
 }
 
+/// 将连接测试发现的重定向地址保存为服务器 URL
+///
+/// 原 URL 使用了 `{username}` 占位符时，新地址中的用户名路径段会替换回占位符
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - url: 新的服务器 URL（通常为连接测试结果中的 redirectedUrl）
+///
+/// # 返回
+/// - 成功：返回更新后的服务器配置
+/// - 失败：返回错误信息（URL 无效时返回 ValidationError）
+#[tauri::command]
+pub async fn apply_redirected_url(
+    server_id: String,
+    url: String,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<WebDavServerConfig> {
+    use crate::constants::USERNAME_PLACEHOLDER;
+    use crate::webdav::db;
+    use crate::webdav::path::encode_segment;
+
+    let mut config = db::get_webdav_server_by_id(&db, &server_id).await?;
+    let previous_url = config.url.clone();
+    config.url = if config.url.contains(USERNAME_PLACEHOLDER) {
+        let username = format!("/{}", encode_segment(config.username.trim()));
+        url.replacen(&username, &format!("/{}", USERNAME_PLACEHOLDER), 1)
+    } else {
+        url
+    };
+
+    let updated = db::update_webdav_server(&db, &server_id, config).await?;
+    clients.invalidate(&server_id);
+
+    tracing::info!(
+        server_id = %server_id,
+        previous_url = %previous_url,
+        url = %updated.url,
+        "已保存重定向后的服务器 URL"
+    );
+    Ok(updated)
+}
+
+// ========== 远程目录浏览 ==========
+
+/// 浏览远程目录
+///
+/// 用于设置同步文件夹时选择 remote_path
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - path: 远程路径（相对于服务器根路径）
+///
+/// # 返回
+/// - 成功：返回目录内容，文件夹在前，同类按名称排序（忽略大小写）
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn browse_webdav_directory(
+    server_id: String,
+    path: String,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<Vec<crate::webdav::client::FileInfo>> {
+    let (_, client) = clients.get(&db, &server_id).await?;
+
+    let mut entries = client.list(&path).await?;
+    sort_entries(&mut entries);
+
+    Ok(entries)
+}
+
+/// 在远程目录下新建文件夹
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - parent_path: 父目录路径
+/// - name: 新文件夹名称（不能包含 `/`）
+///
+/// # 返回
+/// - 成功：返回新文件夹的完整路径
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn create_webdav_directory(
+    server_id: String,
+    parent_path: String,
+    name: String,
+    db: State<'_, Database>,
+    clients: State<'_, WebDavClientFactory>,
+) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+        return Err(crate::SyncError::ConfigError(format!(
+            "Invalid folder name: {}",
+            name
+        )));
+    }
+
+    let (_, client) = clients.get(&db, &server_id).await?;
+
+    let path = format!("{}/{}", parent_path.trim_end_matches('/'), name);
+    client.mkdir(&path).await?;
+
+    Ok(path)
+}
+
+/// 排序目录内容：文件夹在前，同类按名称排序（忽略大小写）
+fn sort_entries(entries: &mut [crate::webdav::client::FileInfo]) {
+    entries.sort_by(|a, b| {
+        b.is_directory
+            .cmp(&a.is_directory)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+// ========== 证书信任 ==========
+
+/// 获取服务器证书，供用户确认是否信任
+///
+/// 用户确认后，前端将返回的 `fingerprintSha256` 写入服务器配置的
+/// `pinnedCertFingerprint` 并调用 `update_webdav_server` 保存
+///
+/// # 参数
+/// - url: 服务器地址（必须是 https）
+///
+/// # 返回
+/// - 成功：返回证书指纹与 PEM 内容
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn fetch_server_certificate(url: String) -> Result<crate::webdav::tls::CertificateInfo> {
+    use crate::constants::DEFAULT_TIMEOUT;
+    use std::time::Duration;
+
+    crate::webdav::tls::fetch_certificate(&url, Duration::from_secs(DEFAULT_TIMEOUT as u64)).await
+}
+
+// ========== 地址发现 ==========
+
+/// 从主机名自动发现 WebDAV 地址
+///
+/// 用户只输入 `cloud.example.com` 时，探测 Nextcloud / ownCloud 的 `remote.php` 端点以及
+/// `/dav`、`/webdav` 等常见路径，返回最合适的地址与服务器类型
+///
+/// # 参数
+/// - host: 主机名或地址
+/// - username: 用户名（可选，用于拼接 Nextcloud / ownCloud 的用户目录地址）
+///
+/// # 返回
+/// - 成功：返回推荐的地址与服务器类型
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn discover_webdav_url(
+    host: String,
+    username: Option<String>,
+) -> Result<crate::webdav::discovery::DiscoveryResult> {
+    use crate::constants::DEFAULT_TIMEOUT;
+    use std::time::Duration;
+
+    crate::webdav::discovery::discover(
+        &host,
+        username.as_deref(),
+        Duration::from_secs(DEFAULT_TIMEOUT as u64),
+    )
+    .await
+}
+
+// ========== Nextcloud 登录流程 ==========
+
+/// 发起 Nextcloud Login Flow v2
+///
+/// 前端拿到 `login_url` 后在浏览器中打开，随后调用 `complete_nextcloud_login`
+///
+/// # 参数
+/// - server_url: 服务器地址
+///
+/// # 返回
+/// - 成功：返回登录页 URL 与轮询信息
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn start_nextcloud_login(
+    server_url: String,
+) -> Result<crate::webdav::login_flow::LoginFlowInit> {
+    crate::webdav::login_flow::start(&server_url).await
+}
+
+/// 等待 Nextcloud 登录流程完成并保存应用密码
+///
+/// 授权完成后将应用密码写入 Keyring，并将服务器的用户名更新为登录名、
+/// 认证方式标记为 `app_password`
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - init: `start_nextcloud_login` 返回的轮询信息
+///
+/// # 返回
+/// - 成功：返回更新后的服务器配置
+/// - 失败：返回错误信息（包括等待超时）
+#[tauri::command]
+pub async fn complete_nextcloud_login(
+    server_id: String,
+    init: crate::webdav::login_flow::LoginFlowInit,
+    db: State<'_, Database>,
+) -> Result<WebDavServerConfig> {
+    use crate::constants::{auth_type, LOGIN_FLOW_POLL_INTERVAL, LOGIN_FLOW_TIMEOUT};
+    use crate::webdav::db;
+    use crate::webdav::keyring::KeyringManager;
+    use crate::webdav::login_flow;
+    use std::time::Duration;
+
+    let mut config = db::get_webdav_server_by_id(&db, &server_id).await?;
+
+    let credentials = login_flow::wait_for_credentials(
+        &init,
+        Duration::from_secs(LOGIN_FLOW_POLL_INTERVAL),
+        Duration::from_secs(LOGIN_FLOW_TIMEOUT),
+    )
+    .await?;
+
+    tracing::info!(server_id = %server_id, login_name = %credentials.login_name, "Nextcloud 登录流程完成");
+
+    let previous = config.clone();
+    config.username = credentials.login_name;
+    config.auth_type = auth_type::APP_PASSWORD.to_string();
+    let updated = db::update_webdav_server(&db, &server_id, config).await?;
+
+    KeyringManager::save_account_password(&updated, &credentials.app_password)?;
+    if previous.username.trim() != updated.username.trim() {
+        remove_account_password(&previous);
+    }
+
+    Ok(updated)
+}
+
 // ========== 辅助数据结构 ==========
 
+/// 服务器批量启用/禁用事件名称
+pub const SERVERS_CHANGED_EVENT: &str = "webdav://servers-changed";
+
+/// 服务器批量启用/禁用事件负载
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServersChangedEvent {
+    /// 被修改的服务器 ID
+    pub server_ids: Vec<String>,
+
+    /// 修改后是否启用
+    pub enabled: bool,
+}
+
+/// 归档服务器的结果
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveServerResult {
+    /// 归档后的服务器
+    pub server: ArchivedWebDavServer,
+
+    /// 因服务器归档而暂停的同步文件夹 ID
+    pub paused_folders: Vec<String>,
+}
+
 /// 连接测试结果
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -346,6 +955,9 @@ pub struct ConnectionTestResult {
 
     /// 服务器信息（仅在成功时返回）
     pub server_info: Option<ServerInfo>,
+
+    /// 服务器永久重定向到的新服务器 URL，可通过 `apply_redirected_url` 保存到服务器配置
+    pub redirected_url: Option<String>,
 }
 
 /// 服务器信息
@@ -365,10 +977,36 @@ pub struct ServerInfo {
 mod tests {
     use crate::database::WebDavServerConfig;
     use crate::webdav::keyring::KeyringManager;
+    use crate::webdav::mock_server_config;
     use std::fs;
     use std::path::PathBuf;
     use uuid::Uuid;
 
+    #[test]
+    fn test_sort_entries_directories_first() {
+        use crate::webdav::client::FileInfo;
+
+        let entry = |name: &str, is_directory: bool| FileInfo {
+            path: format!("/{}", name),
+            name: name.to_string(),
+            is_directory,
+            size: 0,
+            modified: None,
+            etag: None,
+        };
+        let mut entries = vec![
+            entry("b.txt", false),
+            entry("Zeta", true),
+            entry("A.txt", false),
+            entry("alpha", true),
+        ];
+
+        super::sort_entries(&mut entries);
+
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "Zeta", "A.txt", "b.txt"]);
+    }
+
     /// 创建测试用的临时数据库
     fn create_test_db() -> (PathBuf, PathBuf) {
         let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
@@ -399,18 +1037,11 @@ mod tests {
         let unique_id = format!("test-{}", Uuid::new_v4());
         WebDavServerConfig {
             id: unique_id,
-            name: "Test Server".to_string(),
-            url: "https://example.com/webdav".to_string(),
-            username: "testuser".to_string(),
             use_https: true,
             timeout: 30,
-            last_test_at: None,
-            last_test_status: "unknown".to_string(),
-            last_test_error: None,
-            server_type: "generic".to_string(),
-            enabled: true,
             created_at: now,
             updated_at: now,
+            ..mock_server_config("https://example.com/webdav")
         }
     }
 
@@ -430,24 +1061,17 @@ mod tests {
             WebDavServerConfig {
                 id: Uuid::new_v4().to_string(),
                 name: "Simple Server".to_string(),
-                url: "https://simple.com/webdav".to_string(),
                 username: "user1".to_string(),
                 use_https: true,
                 timeout: 30,
-                last_test_at: None,
-                last_test_status: "unknown".to_string(),
-                last_test_error: None,
-                server_type: "generic".to_string(),
-                enabled: true,
                 created_at: chrono::Utc::now().timestamp(),
                 updated_at: chrono::Utc::now().timestamp(),
+                ..mock_server_config("https://simple.com/webdav")
             },
             WebDavServerConfig {
                 id: Uuid::new_v4().to_string(),
                 name: "Complex Server with 中文".to_string(),
-                url: "http://complex.example.com:8080/dav/files".to_string(),
                 username: "user-with-special-chars-!@#".to_string(),
-                use_https: false,
                 timeout: 120,
                 last_test_at: Some(1234567890),
                 last_test_status: "success".to_string(),
@@ -456,6 +1080,7 @@ mod tests {
                 enabled: false,
                 created_at: chrono::Utc::now().timestamp(),
                 updated_at: chrono::Utc::now().timestamp(),
+                ..mock_server_config("http://complex.example.com:8080/dav/files")
             },
         ];
 
@@ -522,6 +1147,7 @@ mod tests {
                             enabled: row.get::<_, i32>(10)? != 0,
                             created_at: row.get(11)?,
                             updated_at: row.get(12)?,
+                            ..mock_server_config("")
                         })
                     },
                 )
@@ -765,6 +1391,7 @@ mod tests {
                         enabled: row.get::<_, i32>(10)? != 0,
                         created_at: row.get(11)?,
                         updated_at: row.get(12)?,
+                        ..mock_server_config("")
                     })
                 },
             )
@@ -815,6 +1442,7 @@ mod tests {
                         enabled: row.get::<_, i32>(10)? != 0,
                         created_at: row.get(11)?,
                         updated_at: row.get(12)?,
+                        ..mock_server_config("")
                     })
                 },
             )
@@ -914,6 +1542,7 @@ mod tests {
                         enabled: row.get::<_, i32>(10)? != 0,
                         created_at: row.get(11)?,
                         updated_at: row.get(12)?,
+                        ..mock_server_config("")
                     })
                 },
             )
@@ -1034,8 +1663,26 @@ mod tests {
                 theme: "system".to_string(),
                 auto_start: false,
                 minimize_to_tray: true,
+                trash_retention_days: 30,
+                max_versions_per_file: 10,
+                log_retention_days: 90,
+                server_archive_retention_days: 30,
+                sync_paused: false,
+                paused_folders: vec![],
+                verify_transfers: false,
+                lock_uploads: false,
+                parallel_download_threshold_mb: 64,
+                keep_alive: false,
+                webdav_trace: false,
+                file_quiet_period_secs: 10,
+                priority_window_mins: 15,
+                reserved_name_policy: "rename".to_string(),
+                case_collision_policy: "rename".to_string(),
+                notifications: Default::default(),
+                sync_policy: Default::default(),
+                auto_lock_minutes: 0,
+                encrypt_config: false,
                 sync_folders: vec![], // 没有同步文件夹
-                webdav_servers: vec![],
             };
 
             // 检查是否有文件夹使用该服务器
@@ -1067,6 +1714,17 @@ mod tests {
                 auto_sync: true,
                 ignore_patterns: vec![],
                 conflict_resolution: "newer-wins".to_string(),
+                selective_exclusions: vec![],
+                symlink_policy: "skip".to_string(),
+                encryption: Default::default(),
+                compression: Default::default(),
+                max_delete_ratio: 0.2,
+                first_sync_strategy: "merge-keep-both".to_string(),
+                max_file_size: None,
+                include_extensions: vec![],
+                exclude_extensions: vec![],
+                ignore_system_files: true,
+                sync_policy: None,
             };
 
             let config = AppConfig {
@@ -1075,8 +1733,26 @@ mod tests {
                 theme: "system".to_string(),
                 auto_start: false,
                 minimize_to_tray: true,
+                trash_retention_days: 30,
+                max_versions_per_file: 10,
+                log_retention_days: 90,
+                server_archive_retention_days: 30,
+                sync_paused: false,
+                paused_folders: vec![],
+                verify_transfers: false,
+                lock_uploads: false,
+                parallel_download_threshold_mb: 64,
+                keep_alive: false,
+                webdav_trace: false,
+                file_quiet_period_secs: 10,
+                priority_window_mins: 15,
+                reserved_name_policy: "rename".to_string(),
+                case_collision_policy: "rename".to_string(),
+                notifications: Default::default(),
+                sync_policy: Default::default(),
+                auto_lock_minutes: 0,
+                encrypt_config: false,
                 sync_folders: vec![sync_folder],
-                webdav_servers: vec![],
             };
 
             // 检查是否有文件夹使用该服务器
@@ -1137,6 +1813,17 @@ mod tests {
                 auto_sync: true,
                 ignore_patterns: vec![],
                 conflict_resolution: "newer-wins".to_string(),
+                selective_exclusions: vec![],
+                symlink_policy: "skip".to_string(),
+                encryption: Default::default(),
+                compression: Default::default(),
+                max_delete_ratio: 0.2,
+                first_sync_strategy: "merge-keep-both".to_string(),
+                max_file_size: None,
+                include_extensions: vec![],
+                exclude_extensions: vec![],
+                ignore_system_files: true,
+                sync_policy: None,
             };
 
             let sync_folder2 = SyncFolderConfig {
@@ -1150,6 +1837,17 @@ mod tests {
                 auto_sync: false,
                 ignore_patterns: vec![],
                 conflict_resolution: "local-wins".to_string(),
+                selective_exclusions: vec![],
+                symlink_policy: "skip".to_string(),
+                encryption: Default::default(),
+                compression: Default::default(),
+                max_delete_ratio: 0.2,
+                first_sync_strategy: "merge-keep-both".to_string(),
+                max_file_size: None,
+                include_extensions: vec![],
+                exclude_extensions: vec![],
+                ignore_system_files: true,
+                sync_policy: None,
             };
 
             let sync_folder3 = SyncFolderConfig {
@@ -1163,6 +1861,17 @@ mod tests {
                 auto_sync: true,
                 ignore_patterns: vec!["*.tmp".to_string()],
                 conflict_resolution: "remote-wins".to_string(),
+                selective_exclusions: vec![],
+                symlink_policy: "skip".to_string(),
+                encryption: Default::default(),
+                compression: Default::default(),
+                max_delete_ratio: 0.2,
+                first_sync_strategy: "merge-keep-both".to_string(),
+                max_file_size: None,
+                include_extensions: vec![],
+                exclude_extensions: vec![],
+                ignore_system_files: true,
+                sync_policy: None,
             };
 
             let config = AppConfig {
@@ -1171,8 +1880,26 @@ mod tests {
                 theme: "system".to_string(),
                 auto_start: false,
                 minimize_to_tray: true,
+                trash_retention_days: 30,
+                max_versions_per_file: 10,
+                log_retention_days: 90,
+                server_archive_retention_days: 30,
+                sync_paused: false,
+                paused_folders: vec![],
+                verify_transfers: false,
+                lock_uploads: false,
+                parallel_download_threshold_mb: 64,
+                keep_alive: false,
+                webdav_trace: false,
+                file_quiet_period_secs: 10,
+                priority_window_mins: 15,
+                reserved_name_policy: "rename".to_string(),
+                case_collision_policy: "rename".to_string(),
+                notifications: Default::default(),
+                sync_policy: Default::default(),
+                auto_lock_minutes: 0,
+                encrypt_config: false,
                 sync_folders: vec![sync_folder1, sync_folder2, sync_folder3],
-                webdav_servers: vec![],
             };
 
             // 检查是否有文件夹使用该服务器
@@ -1229,6 +1956,17 @@ mod tests {
                 auto_sync: true,
                 ignore_patterns: vec![],
                 conflict_resolution: "newer-wins".to_string(),
+                selective_exclusions: vec![],
+                symlink_policy: "skip".to_string(),
+                encryption: Default::default(),
+                compression: Default::default(),
+                max_delete_ratio: 0.2,
+                first_sync_strategy: "merge-keep-both".to_string(),
+                max_file_size: None,
+                include_extensions: vec![],
+                exclude_extensions: vec![],
+                ignore_system_files: true,
+                sync_policy: None,
             };
 
             let config = AppConfig {
@@ -1237,8 +1975,26 @@ mod tests {
                 theme: "system".to_string(),
                 auto_start: false,
                 minimize_to_tray: true,
+                trash_retention_days: 30,
+                max_versions_per_file: 10,
+                log_retention_days: 90,
+                server_archive_retention_days: 30,
+                sync_paused: false,
+                paused_folders: vec![],
+                verify_transfers: false,
+                lock_uploads: false,
+                parallel_download_threshold_mb: 64,
+                keep_alive: false,
+                webdav_trace: false,
+                file_quiet_period_secs: 10,
+                priority_window_mins: 15,
+                reserved_name_policy: "rename".to_string(),
+                case_collision_policy: "rename".to_string(),
+                notifications: Default::default(),
+                sync_policy: Default::default(),
+                auto_lock_minutes: 0,
+                encrypt_config: false,
                 sync_folders: vec![sync_folder],
-                webdav_servers: vec![],
             };
 
             // 检查被使用的服务器
@@ -1281,18 +2037,14 @@ mod tests {
 
         let config = WebDavServerConfig {
             id: "test-123".to_string(),
-            name: "Test Server".to_string(),
-            url: "https://example.com/webdav".to_string(),
-            username: "testuser".to_string(),
             use_https: true,
             timeout: 30,
             last_test_at: Some(1234567890),
             last_test_status: "success".to_string(),
-            last_test_error: None,
             server_type: "nextcloud".to_string(),
-            enabled: true,
             created_at: 1234567890,
             updated_at: 1234567890,
+            ..mock_server_config("https://example.com/webdav")
         };
 
         println!("原始配置:");
@@ -1493,8 +2245,10 @@ mod tests {
             // 验证 JSON 包含错误信息
             assert!(json.len() > 0, "序列化后的 JSON 不应该为空");
 
-            // 注意：SyncError 没有实现 Deserialize，所以我们只验证序列化
-            // 前端会将错误作为字符串处理
+            // 验证错误以 { errorCode, message, context } 结构传递到前端
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(value["errorCode"], error.code().as_str());
+            assert_eq!(value["message"], error.to_string());
             println!("  ✓ 序列化成功\n");
         }
 
@@ -1521,6 +2275,7 @@ mod tests {
                 server_type: "nextcloud".to_string(),
                 available_space: Some(1024 * 1024 * 1024), // 1GB
             }),
+            redirected_url: None,
         };
 
         println!("成功结果:");
@@ -1542,6 +2297,7 @@ mod tests {
             success: false,
             message: "Authentication failed".to_string(),
             server_info: None,
+            redirected_url: None,
         };
 
         println!("失败结果:");
@@ -1728,6 +2484,7 @@ mod tests {
                         enabled: row.get::<_, i32>(10)? != 0,
                         created_at: row.get(11)?,
                         updated_at: row.get(12)?,
+                        ..mock_server_config("")
                     })
                 },
             );
@@ -28,11 +28,84 @@ pub struct AppConfig {
     /// 是否最小化到系统托盘
     pub minimize_to_tray: bool,
     
+    /// 回收站保留天数（0 表示永久保留）
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    
+    /// 每个文件最多保留的历史版本数（0 表示不保存历史版本）
+    #[serde(default = "default_max_versions_per_file")]
+    pub max_versions_per_file: u32,
+    
+    /// 同步历史（日志与会话）保留天数（0 表示永久保留）
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32,
+    
+    /// 归档的服务器保留天数，超过后永久删除（0 表示永久保留）
+    #[serde(default = "default_server_archive_retention_days")]
+    pub server_archive_retention_days: u32,
+    
+    /// 是否全局暂停同步
+    #[serde(default)]
+    pub sync_paused: bool,
+    
+    /// 已暂停同步的文件夹 ID
+    #[serde(default)]
+    pub paused_folders: Vec<String>,
+    
+    /// 传输完成后是否校验完整性（比对远程校验和与大小）
+    #[serde(default)]
+    pub verify_transfers: bool,
+    
+    /// 上传前是否锁定远程文件（WebDAV LOCK），避免多个客户端同时修改同一文件
+    #[serde(default)]
+    pub lock_uploads: bool,
+    
+    /// 并行分段下载阈值（MB），不小于该大小且服务器支持范围请求的文件使用多个连接下载（0 表示不启用）
+    #[serde(default = "default_parallel_download_threshold_mb")]
+    pub parallel_download_threshold_mb: u32,
+    
+    /// 是否定期向最近使用的服务器发送 OPTIONS 请求保持连接，避免路由器 NAT 超时断开空闲连接
+    #[serde(default)]
+    pub keep_alive: bool,
+    
+    /// 是否记录 WebDAV 请求跟踪（方法、URL、状态、耗时与脱敏后的请求头），用于排查服务器兼容问题
+    #[serde(default)]
+    pub webdav_trace: bool,
+    
+    /// 本地文件最后修改后需要保持不变的时间（秒），未满足时推迟上传
+    #[serde(default = "default_file_quiet_period_secs")]
+    pub file_quiet_period_secs: u32,
+    
+    /// 传输优先窗口（分钟），最后修改时间在窗口内的文件排在其他待传输文件之前（0 表示不调整顺序）
+    #[serde(default = "default_priority_window_mins")]
+    pub priority_window_mins: u32,
+    
+    /// 服务器上的文件名在本地不合法时的处理策略（rename, skip）
+    #[serde(default = "default_reserved_name_policy")]
+    pub reserved_name_policy: String,
+    
+    /// 仅大小写不同的远程文件在本地冲突时的处理策略（rename, skip）
+    #[serde(default = "default_case_collision_policy")]
+    pub case_collision_policy: String,
+    
+    /// 桌面通知设置
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    
+    /// 自动同步策略（未单独设置策略的同步文件夹使用）
+    #[serde(default)]
+    pub sync_policy: SyncPolicyConfig,
+    
+    /// 设置主密码后，无操作多久自动锁定（分钟，0 表示不自动锁定）
+    #[serde(default = "default_auto_lock_minutes")]
+    pub auto_lock_minutes: u32,
+    
+    /// 是否加密保存配置文件（密钥保存在系统 Keyring，见 `config_crypto`）
+    #[serde(default)]
+    pub encrypt_config: bool,
+    
     /// 同步文件夹配置列表
     pub sync_folders: Vec<SyncFolderConfig>,
-    
-    /// WebDAV 服务器配置列表
-    pub webdav_servers: Vec<WebDavServerConfig>,
 }
 
 /// 同步文件夹配置
@@ -68,29 +141,215 @@ pub struct SyncFolderConfig {
     
     /// 冲突解决策略（ask, local-wins, remote-wins, newer-wins）
     pub conflict_resolution: String,
+    
+    /// 选择性同步：排除的远程子文件夹（相对于 remote_path，如 `photos/2019`）
+    #[serde(default)]
+    pub selective_exclusions: Vec<String>,
+    
+    /// 符号链接处理策略（skip, follow, sync-as-placeholder）
+    #[serde(default = "default_symlink_policy")]
+    pub symlink_policy: String,
+    
+    /// 端到端加密设置
+    #[serde(default)]
+    pub encryption: FolderEncryptionConfig,
+    
+    /// 上传压缩设置
+    #[serde(default)]
+    pub compression: FolderCompressionConfig,
+    
+    /// 单次同步最多删除的文件比例（0~1，任一侧超过时需要用户确认，1 表示不限制）
+    #[serde(default = "default_max_delete_ratio")]
+    pub max_delete_ratio: f64,
+    
+    /// 首次同步策略（merge-keep-both, prefer-local, prefer-remote, mirror）
+    #[serde(default = "default_first_sync_strategy")]
+    pub first_sync_strategy: String,
+    
+    /// 单个文件的大小上限（字节，超过的文件不上传也不下载，为空表示不限制）
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    
+    /// 只同步这些扩展名的文件（不含点、不区分大小写，为空表示不限制）
+    #[serde(default)]
+    pub include_extensions: Vec<String>,
+    
+    /// 不同步这些扩展名的文件（不含点、不区分大小写）
+    #[serde(default)]
+    pub exclude_extensions: Vec<String>,
+    
+    /// 是否忽略操作系统生成的元数据文件（`.DS_Store`、`Thumbs.db`、AppleDouble `._*` 附属文件等）
+    #[serde(default = "default_ignore_system_files")]
+    pub ignore_system_files: bool,
+    
+    /// 文件夹单独设置的自动同步策略（为空时使用全局策略）
+    #[serde(default)]
+    pub sync_policy: Option<SyncPolicyConfig>,
 }
 
-/// WebDAV 服务器配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WebDavServerConfig {
-    /// 服务器 ID
-    pub id: String,
+/// 同步文件夹的端到端加密设置
+///
+/// 加密口令保存在系统 Keyring 中，不写入配置文件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FolderEncryptionConfig {
+    /// 是否在上传前加密文件
+    pub enabled: bool,
     
-    /// 服务器名称
-    pub name: String,
+    /// 是否混淆远程文件名（仅在首次创建远程同步清单时生效）
+    pub obfuscate_filenames: bool,
+}
+
+/// 同步文件夹的上传压缩设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FolderCompressionConfig {
+    /// 是否在上传前压缩匹配的文件
+    pub enabled: bool,
+    
+    /// 压缩算法（zstd, gzip）
+    pub algorithm: String,
+    
+    /// 要压缩的扩展名分组（text, code, data, document）
+    pub groups: Vec<String>,
+    
+    /// 分组之外额外要压缩的扩展名
+    pub extensions: Vec<String>,
     
-    /// 服务器 URL
-    pub url: String,
+    /// 最小文件大小（字节），更小的文件不压缩
+    pub min_size: u64,
+}
+
+impl Default for FolderCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            algorithm: compression_algorithm::ZSTD.to_string(),
+            groups: vec![
+                compression_group::TEXT.to_string(),
+                compression_group::CODE.to_string(),
+                compression_group::DATA.to_string(),
+            ],
+            extensions: Vec::new(),
+            min_size: DEFAULT_COMPRESSION_MIN_SIZE,
+        }
+    }
+}
+
+/// 桌面通知设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationConfig {
+    /// 是否启用桌面通知
+    pub enabled: bool,
+    
+    /// 同步完成时是否通知（无变更的同步不通知）
+    pub sync_completed: bool,
+    
+    /// 同步失败时是否通知（认证失败、存储空间不足）
+    pub errors: bool,
     
-    /// 用户名
-    pub username: String,
+    /// 检测到冲突时是否通知
+    pub conflicts: bool,
+    
+    /// 不发送通知的同步文件夹 ID
+    pub muted_folders: Vec<String>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sync_completed: true,
+            errors: true,
+            conflicts: true,
+            muted_folders: Vec::new(),
+        }
+    }
+}
+
+/// 自动同步策略
+///
+/// 只限制自动触发的同步（远程变更轮询、推迟上传的重新同步、恢复连接后的排队同步），
+/// 手动同步不受影响
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncPolicyConfig {
+    /// 按流量计费的网络上是否暂停自动同步
+    pub pause_on_metered: bool,
     
-    /// 是否使用 HTTPS
-    pub use_https: bool,
+    /// 使用电池供电且电量低于该百分比时暂停自动同步（为空表示不限制）
+    pub min_battery_percent: Option<u8>,
     
-    /// 连接超时（秒）
-    pub timeout: u32,
+    /// 暂停自动同步的时段（本地时间，为空表示不限制）
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// 暂停自动同步的时段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    /// 开始时间（HH:MM）
+    pub start: String,
+    
+    /// 结束时间（HH:MM，早于开始时间表示跨越午夜）
+    pub end: String,
+}
+
+fn default_trash_retention_days() -> u32 {
+    DEFAULT_TRASH_RETENTION_DAYS
+}
+
+fn default_max_versions_per_file() -> u32 {
+    DEFAULT_MAX_VERSIONS_PER_FILE
+}
+
+fn default_log_retention_days() -> u32 {
+    DEFAULT_LOG_RETENTION_DAYS
+}
+
+fn default_server_archive_retention_days() -> u32 {
+    DEFAULT_SERVER_ARCHIVE_RETENTION_DAYS
+}
+
+fn default_auto_lock_minutes() -> u32 {
+    DEFAULT_AUTO_LOCK_MINUTES
+}
+
+fn default_file_quiet_period_secs() -> u32 {
+    DEFAULT_FILE_QUIET_PERIOD_SECS
+}
+
+fn default_priority_window_mins() -> u32 {
+    DEFAULT_PRIORITY_WINDOW_MINS
+}
+
+fn default_parallel_download_threshold_mb() -> u32 {
+    DEFAULT_PARALLEL_DOWNLOAD_THRESHOLD_MB
+}
+
+fn default_max_delete_ratio() -> f64 {
+    DEFAULT_MAX_DELETE_RATIO
+}
+
+fn default_first_sync_strategy() -> String {
+    DEFAULT_FIRST_SYNC_STRATEGY.to_string()
+}
+
+fn default_ignore_system_files() -> bool {
+    true
+}
+
+fn default_symlink_policy() -> String {
+    symlink_policy::SKIP.to_string()
+}
+
+fn default_reserved_name_policy() -> String {
+    reserved_name_policy::RENAME.to_string()
+}
+
+fn default_case_collision_policy() -> String {
+    case_collision_policy::RENAME.to_string()
 }
 
 impl Default for AppConfig {
@@ -101,12 +360,99 @@ impl Default for AppConfig {
             theme: DEFAULT_THEME.to_string(),
             auto_start: false,
             minimize_to_tray: true,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            max_versions_per_file: DEFAULT_MAX_VERSIONS_PER_FILE,
+            log_retention_days: DEFAULT_LOG_RETENTION_DAYS,
+            server_archive_retention_days: DEFAULT_SERVER_ARCHIVE_RETENTION_DAYS,
+            sync_paused: false,
+            paused_folders: Vec::new(),
+            verify_transfers: false,
+            lock_uploads: false,
+            parallel_download_threshold_mb: DEFAULT_PARALLEL_DOWNLOAD_THRESHOLD_MB,
+            keep_alive: false,
+            webdav_trace: false,
+            file_quiet_period_secs: DEFAULT_FILE_QUIET_PERIOD_SECS,
+            priority_window_mins: DEFAULT_PRIORITY_WINDOW_MINS,
+            reserved_name_policy: reserved_name_policy::RENAME.to_string(),
+            case_collision_policy: case_collision_policy::RENAME.to_string(),
+            notifications: NotificationConfig::default(),
+            sync_policy: SyncPolicyConfig::default(),
+            auto_lock_minutes: DEFAULT_AUTO_LOCK_MINUTES,
+            encrypt_config: false,
             sync_folders: Vec::new(),
-            webdav_servers: Vec::new(),
         }
     }
 }
 
+/// 校验配置
+///
+/// # 返回
+/// - Ok(()): 所有配置项有效
+/// - Err(SyncError::ConfigError): 任一配置项无效
+pub fn validate(config: &AppConfig) -> Result<()> {
+    validate_theme(&config.theme)?;
+    validate_language(&config.language)?;
+
+    let policies = [
+        reserved_name_policy::RENAME,
+        reserved_name_policy::PERCENT_ENCODE,
+        reserved_name_policy::SKIP,
+    ];
+    if !policies.contains(&config.reserved_name_policy.as_str()) {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid reserved name policy: {}",
+            config.reserved_name_policy
+        )));
+    }
+    let policies = [case_collision_policy::RENAME, case_collision_policy::SKIP];
+    if !policies.contains(&config.case_collision_policy.as_str()) {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid case collision policy: {}",
+            config.case_collision_policy
+        )));
+    }
+
+    crate::sync::policy::validate(&config.sync_policy)?;
+    for folder in &config.sync_folders {
+        crate::sync::folders::validate_options(folder)?;
+    }
+    Ok(())
+}
+
+/// 校验主题（light, dark, system）
+pub fn validate_theme(value: &str) -> Result<()> {
+    if [theme::LIGHT, theme::DARK, theme::SYSTEM].contains(&value) {
+        Ok(())
+    } else {
+        Err(SyncError::ConfigError(format!("Invalid theme: {}", value)))
+    }
+}
+
+/// 校验语言代码格式（如 zh-CN、en-US，兼容前端使用的 zh_cn、en）
+pub fn validate_language(value: &str) -> Result<()> {
+    let mut subtags = value.split(['-', '_']);
+    let primary = subtags.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|tag| {
+            (2..=8).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(SyncError::ConfigError(format!("Invalid language: {}", value)))
+    }
+}
+
+/// 配置的顶层配置项（camelCase 名称 -> 值）
+fn to_config_map(config: &AppConfig) -> Result<serde_json::Map<String, serde_json::Value>> {
+    match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(map)) => Ok(map),
+        Ok(_) => Err(SyncError::ConfigError("Config is not an object".to_string())),
+        Err(e) => Err(SyncError::ConfigError(format!("Failed to serialize config: {}", e))),
+    }
+}
+
 /// 初始化配置
 ///
 /// 如果配置文件不存在，创建默认配置
@@ -157,6 +503,9 @@ pub async fn get_config(app: AppHandle) -> Result<AppConfig> {
 /// 更新配置
 #[tauri::command]
 pub async fn update_config(app: AppHandle, config: AppConfig) -> Result<()> {
+    validate(&config)?;
+    let previous = get_config(app.clone()).await.ok();
+
     let store = app.store(CONFIG_STORE_FILE).map_err(|e| {
         SyncError::ConfigError(format!("Failed to access store: {}", e))
     })?;
@@ -171,61 +520,57 @@ pub async fn update_config(app: AppHandle, config: AppConfig) -> Result<()> {
         SyncError::ConfigError(format!("Failed to save config: {}", e))
     })?;
 
+    crate::config_bus::publish(&app, previous, config);
+
     Ok(())
 }
 
 /// 获取指定配置项
+///
+/// # 参数
+/// - key: 顶层配置项名称（camelCase，如 `theme`）
+///
+/// # 返回
+/// - 成功：配置项的值（尚未保存过的配置项返回默认值）
+/// - 失败：配置项不存在
 #[tauri::command]
 pub async fn get_config_value(app: AppHandle, key: String) -> Result<serde_json::Value> {
-    let store = app.store(CONFIG_STORE_FILE).map_err(|e| {
-        SyncError::ConfigError(format!("Failed to access store: {}", e))
-    })?;
-
-    if let Some(config_value) = store.get("app_config") {
-        let config: serde_json::Map<String, serde_json::Value> =
-            serde_json::from_value(config_value.clone())
-                .map_err(|e| SyncError::ConfigError(format!("Failed to parse config: {}", e)))?;
-
-        if let Some(value) = config.get(&key) {
-            return Ok(value.clone());
-        }
-    }
-
-    Err(SyncError::ConfigError(format!("Config key '{}' not found", key)))
+    let config = to_config_map(&get_config(app).await?)?;
+    config
+        .get(&key)
+        .cloned()
+        .ok_or_else(|| SyncError::ConfigError(format!("Unknown config key '{}'", key)))
 }
 
 /// 设置指定配置项
+///
+/// 只接受已有的顶层配置项，值的类型必须与配置项一致，并与完整配置一起校验后保存
+///
+/// # 参数
+/// - key: 顶层配置项名称（camelCase，如 `theme`）
+/// - value: 新的值
+///
+/// # 返回
+/// - 成功：Ok(())
+/// - 失败：配置项不存在、值的类型不匹配或未通过校验
 #[tauri::command]
 pub async fn set_config_value(
     app: AppHandle,
     key: String,
     value: serde_json::Value,
 ) -> Result<()> {
-    let store = app.store(CONFIG_STORE_FILE).map_err(|e| {
-        SyncError::ConfigError(format!("Failed to access store: {}", e))
-    })?;
-
-    // 获取当前配置
-    let mut config: serde_json::Map<String, serde_json::Value> =
-        if let Some(config_value) = store.get("app_config") {
-            serde_json::from_value(config_value.clone())
-                .map_err(|e| SyncError::ConfigError(format!("Failed to parse config: {}", e)))?
-        } else {
-            serde_json::to_value(AppConfig::default())
-                .and_then(|v| serde_json::from_value(v))
-                .map_err(|e| SyncError::ConfigError(format!("Failed to create default config: {}", e)))?
-        };
-
-    // 更新配置项
-    config.insert(key, value);
+    let mut config = to_config_map(&get_config(app.clone()).await?)?;
+    if !config.contains_key(&key) {
+        return Err(SyncError::ConfigError(format!("Unknown config key '{}'", key)));
+    }
 
-    // 保存配置
-    store.set("app_config", serde_json::Value::Object(config));
-    store.save().map_err(|e| {
-        SyncError::ConfigError(format!("Failed to save config: {}", e))
-    })?;
+    config.insert(key.clone(), value);
+    let config: AppConfig = serde_json::from_value(serde_json::Value::Object(config))
+        .map_err(|e| {
+            SyncError::ConfigError(format!("Invalid value for config key '{}': {}", key, e))
+        })?;
 
-    Ok(())
+    update_config(app, config).await
 }
 
 /// 重置配置为默认值
@@ -241,6 +586,39 @@ mod tests {
     use super::*;
     use serde_json;
 
+    #[test]
+    fn test_validate() {
+        let mut config = AppConfig::default();
+        assert!(validate(&config).is_ok());
+
+        config.theme = "blue".to_string();
+        assert!(validate(&config).is_err());
+        config.theme = "dark".to_string();
+
+        for language in ["en-US", "zh_cn", "en", "pt-BR"] {
+            assert!(validate_language(language).is_ok(), "{}", language);
+        }
+        for language in ["", "e", "english!", "zh-", "en-verylongsubtag"] {
+            assert!(validate_language(language).is_err(), "{}", language);
+        }
+
+        config.reserved_name_policy = "ignore".to_string();
+        assert!(validate(&config).is_err());
+        config.reserved_name_policy = "percent-encode".to_string();
+        assert!(validate(&config).is_ok());
+        config.reserved_name_policy = "skip".to_string();
+        config.case_collision_policy = "merge".to_string();
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_config_keys() {
+        let keys = to_config_map(&AppConfig::default()).unwrap();
+        assert!(keys.contains_key("theme"));
+        assert!(keys.contains_key("syncPolicy"));
+        assert!(!keys.contains_key("them"));
+    }
+
     #[test]
     fn test_default_config() {
         let config = AppConfig::default();
@@ -259,6 +637,25 @@ mod tests {
             theme: "dark".to_string(),
             auto_start: true,
             minimize_to_tray: false,
+            trash_retention_days: 30,
+            max_versions_per_file: 10,
+            log_retention_days: 90,
+            server_archive_retention_days: 30,
+            sync_paused: false,
+            paused_folders: vec![],
+            verify_transfers: false,
+            lock_uploads: false,
+            parallel_download_threshold_mb: DEFAULT_PARALLEL_DOWNLOAD_THRESHOLD_MB,
+            keep_alive: false,
+            webdav_trace: false,
+            file_quiet_period_secs: DEFAULT_FILE_QUIET_PERIOD_SECS,
+            priority_window_mins: DEFAULT_PRIORITY_WINDOW_MINS,
+            reserved_name_policy: "rename".to_string(),
+            case_collision_policy: "skip".to_string(),
+            notifications: NotificationConfig::default(),
+            sync_policy: SyncPolicyConfig::default(),
+            auto_lock_minutes: 15,
+            encrypt_config: true,
             sync_folders: vec![
                 SyncFolderConfig {
                     id: "folder1".to_string(),
@@ -271,16 +668,17 @@ mod tests {
                     auto_sync: true,
                     ignore_patterns: vec!["*.tmp".to_string(), ".git".to_string()],
                     conflict_resolution: "newer-wins".to_string(),
-                }
-            ],
-            webdav_servers: vec![
-                WebDavServerConfig {
-                    id: "server1".to_string(),
-                    name: "我的服务器".to_string(),
-                    url: "https://cloud.example.com".to_string(),
-                    username: "user".to_string(),
-                    use_https: true,
-                    timeout: 30,
+                    selective_exclusions: vec![],
+                    symlink_policy: "skip".to_string(),
+                    encryption: Default::default(),
+                    compression: Default::default(),
+                    max_delete_ratio: DEFAULT_MAX_DELETE_RATIO,
+                    first_sync_strategy: DEFAULT_FIRST_SYNC_STRATEGY.to_string(),
+                    max_file_size: None,
+                    include_extensions: vec![],
+                    exclude_extensions: vec![],
+                    ignore_system_files: true,
+                    sync_policy: None,
                 }
             ],
         };
@@ -292,6 +690,7 @@ mod tests {
         assert!(json.contains("syncInterval"));
         assert!(json.contains("conflictResolution"));
         assert!(json.contains("minimizeToTray"));
+        assert!(json.contains("encryptConfig"));
 
         // 反序列化
         let deserialized: AppConfig = serde_json::from_str(&json).unwrap();
@@ -302,8 +701,8 @@ mod tests {
         assert_eq!(original.theme, deserialized.theme);
         assert_eq!(original.auto_start, deserialized.auto_start);
         assert_eq!(original.minimize_to_tray, deserialized.minimize_to_tray);
+        assert_eq!(original.encrypt_config, deserialized.encrypt_config);
         assert_eq!(original.sync_folders.len(), deserialized.sync_folders.len());
-        assert_eq!(original.webdav_servers.len(), deserialized.webdav_servers.len());
 
         // 验证嵌套结构体 - SyncFolderConfig
         assert_eq!(
@@ -314,16 +713,6 @@ mod tests {
             original.sync_folders[0].conflict_resolution,
             deserialized.sync_folders[0].conflict_resolution
         );
-
-        // 验证嵌套结构体 - WebDavServerConfig
-        assert_eq!(
-            original.webdav_servers[0].timeout,
-            deserialized.webdav_servers[0].timeout
-        );
-        assert_eq!(
-            original.webdav_servers[0].use_https,
-            deserialized.webdav_servers[0].use_https
-        );
     }
 
     #[test]
@@ -339,6 +728,17 @@ mod tests {
             auto_sync: false,
             ignore_patterns: vec!["node_modules".to_string()],
             conflict_resolution: "local-wins".to_string(),
+            selective_exclusions: vec![],
+            symlink_policy: "skip".to_string(),
+            encryption: Default::default(),
+            compression: Default::default(),
+            max_delete_ratio: DEFAULT_MAX_DELETE_RATIO,
+            first_sync_strategy: DEFAULT_FIRST_SYNC_STRATEGY.to_string(),
+            max_file_size: None,
+            include_extensions: vec![],
+            exclude_extensions: vec![],
+            ignore_system_files: true,
+            sync_policy: None,
         };
 
         let json = serde_json::to_string(&folder).unwrap();
@@ -358,27 +758,22 @@ mod tests {
     }
 
     #[test]
-    fn test_webdav_server_config_serialization() {
-        let server = WebDavServerConfig {
-            id: "server-test".to_string(),
-            name: "测试服务器".to_string(),
-            url: "https://test.example.com".to_string(),
-            username: "testuser".to_string(),
-            use_https: true,
-            timeout: 45,
-        };
-
-        let json = serde_json::to_string(&server).unwrap();
-        let deserialized: WebDavServerConfig = serde_json::from_str(&json).unwrap();
-
-        // 验证 JSON 包含正确的驼峰命名字段
-        assert!(json.contains("useHttps"));
-        assert!(json.contains("timeout"));
-
-        // 验证反序列化后的数据
-        assert_eq!(server.use_https, deserialized.use_https);
-        assert_eq!(server.timeout, deserialized.timeout);
-        assert_eq!(server.url, deserialized.url);
+    fn test_legacy_webdav_servers_ignored() {
+        // 旧版本在配置中保存了服务器列表，服务器配置现在只保存在数据库中
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["webdavServers"] = serde_json::json!([{
+            "id": "server-test",
+            "name": "测试服务器",
+            "url": "https://test.example.com",
+            "username": "testuser",
+            "useHttps": true,
+            "timeout": 45
+        }]);
+
+        let config: AppConfig = serde_json::from_value(value).unwrap();
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("webdavServers"));
+        assert!(!json.contains("test.example.com"));
     }
 
     #[test]
@@ -392,13 +787,11 @@ mod tests {
         assert!(json.contains("autoStart"));
         assert!(json.contains("minimizeToTray"));
         assert!(json.contains("syncFolders"));
-        assert!(json.contains("webdavServers"));
 
         // 确保没有蛇形命名的字段
         assert!(!json.contains("auto_start"));
         assert!(!json.contains("minimize_to_tray"));
         assert!(!json.contains("sync_folders"));
-        assert!(!json.contains("webdav_servers"));
     }
 
     #[test]
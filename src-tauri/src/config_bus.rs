@@ -0,0 +1,368 @@
+/// 配置变更总线
+///
+/// 配置保存（`update_config`、`set_config_value`）或配置文件被外部修改并重新加载后，
+/// 向订阅者广播类型化的配置变更，运行中的子系统就地调整而不需要重启应用：
+/// - 远程变更轮询：文件夹新增或同步间隔、自动同步、同步方向等调度字段变化时立即重新检查
+/// - 同步控制：暂停状态（syncPaused、pausedFolders）变化时更新暂停控制与文件夹状态
+/// - WebDAV 请求跟踪：按 webdavTrace 开启或关闭
+/// - 同步策略：策略变化后清除记录的跳过原因，下次检查时重新推送
+///
+/// 忽略规则、扩展名与大小过滤在每次同步开始时从配置构建，保存后下次同步即生效；
+/// 服务器配置保存在数据库中，由服务器命令直接使缓存的 WebDAV 客户端失效
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::config::AppConfig;
+use crate::constants::CONFIG_BUS_CAPACITY;
+use crate::sync::control::{PauseState, SyncControl};
+use crate::sync::policy::PolicySkips;
+use crate::sync::state::SyncStateManager;
+
+/// 影响远程变更轮询调度的同步文件夹字段
+const SCHEDULE_FIELDS: &[&str] = &[
+    "autoSync",
+    "syncInterval",
+    "syncDirection",
+    "serverId",
+    "remotePath",
+    "syncPolicy",
+];
+
+/// 单项配置变更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// 顶层配置项变化（camelCase 名称，不包括同步文件夹列表）
+    Setting(String),
+    /// 新增同步文件夹
+    FolderAdded(String),
+    /// 删除同步文件夹
+    FolderRemoved(String),
+    /// 同步文件夹的字段变化
+    FolderUpdated {
+        /// 同步文件夹 ID
+        folder_id: String,
+        /// 发生变化的字段（camelCase，按字母排序）
+        fields: Vec<String>,
+    },
+}
+
+/// 一次配置更新
+#[derive(Debug, Clone)]
+pub struct ConfigUpdate {
+    /// 更新后的配置
+    pub current: AppConfig,
+    /// 配置变更
+    pub changes: Vec<ConfigChange>,
+}
+
+impl ConfigUpdate {
+    /// 比较两份配置，生成配置更新
+    pub fn new(previous: &AppConfig, current: AppConfig) -> Self {
+        let changes = diff(previous, &current);
+        Self { current, changes }
+    }
+
+    /// 指定的顶层配置项是否变化
+    pub fn setting_changed(&self, key: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|change| matches!(change, ConfigChange::Setting(k) if k == key))
+    }
+
+    /// 是否需要立即重新检查远程变更轮询的调度
+    pub fn reschedules_polling(&self) -> bool {
+        self.setting_changed("syncPolicy")
+            || self.changes.iter().any(|change| match change {
+                ConfigChange::FolderAdded(_) => true,
+                ConfigChange::FolderUpdated { fields, .. } => fields
+                    .iter()
+                    .any(|field| SCHEDULE_FIELDS.contains(&field.as_str())),
+                _ => false,
+            })
+    }
+}
+
+/// 比较两份配置
+///
+/// # 返回
+/// 配置变更：顶层配置项按名称排序，其后是同步文件夹的变更（按 ID 排序）
+pub fn diff(previous: &AppConfig, current: &AppConfig) -> Vec<ConfigChange> {
+    let previous = to_object(previous);
+    let current = to_object(current);
+
+    let mut changes: Vec<ConfigChange> = changed_fields(&previous, &current)
+        .into_iter()
+        .filter(|key| key != "syncFolders")
+        .map(ConfigChange::Setting)
+        .collect();
+
+    let previous_folders = by_id(previous.get("syncFolders"));
+    let current_folders = by_id(current.get("syncFolders"));
+    for id in previous_folders
+        .keys()
+        .chain(current_folders.keys())
+        .collect::<BTreeSet<_>>()
+    {
+        match (previous_folders.get(id), current_folders.get(id)) {
+            (Some(_), None) => changes.push(ConfigChange::FolderRemoved(id.clone())),
+            (None, Some(_)) => changes.push(ConfigChange::FolderAdded(id.clone())),
+            (Some(before), Some(after)) => {
+                let fields = changed_fields(before, after);
+                if !fields.is_empty() {
+                    changes.push(ConfigChange::FolderUpdated {
+                        folder_id: id.clone(),
+                        fields,
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    changes
+}
+
+/// 两个 JSON 对象中值不同的字段（按名称排序）
+pub fn changed_fields(previous: &Map<String, Value>, current: &Map<String, Value>) -> Vec<String> {
+    previous
+        .keys()
+        .chain(current.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|key| previous.get(*key) != current.get(*key))
+        .cloned()
+        .collect()
+}
+
+fn to_object(config: &AppConfig) -> Map<String, Value> {
+    match serde_json::to_value(config) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// 按 id 字段索引 JSON 对象数组
+fn by_id(list: Option<&Value>) -> BTreeMap<String, Map<String, Value>> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let object = item.as_object()?;
+            let id = object.get("id")?.as_str()?.to_string();
+            Some((id, object.clone()))
+        })
+        .collect()
+}
+
+/// 配置变更总线（作为 Tauri State 管理）
+#[derive(Debug)]
+pub struct ConfigBus {
+    sender: broadcast::Sender<Arc<ConfigUpdate>>,
+}
+
+impl Default for ConfigBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CONFIG_BUS_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl ConfigBus {
+    /// 订阅配置更新
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<ConfigUpdate>> {
+        self.sender.subscribe()
+    }
+
+    /// 比较配置并向订阅者广播（没有变化时不广播）
+    ///
+    /// # 返回
+    /// 是否广播了配置更新
+    pub fn publish(&self, previous: AppConfig, current: AppConfig) -> bool {
+        let update = ConfigUpdate::new(&previous, current);
+        if update.changes.is_empty() {
+            return false;
+        }
+        tracing::debug!(changes = ?update.changes, "配置已更新");
+        // 没有订阅者时发送失败，不影响配置保存
+        let _ = self.sender.send(Arc::new(update));
+        true
+    }
+
+    /// 等待下一次满足条件的配置更新
+    ///
+    /// 订阅者落后而错过部分更新时视为满足条件；总线关闭后不再返回
+    pub async fn wait_for(
+        receiver: &mut broadcast::Receiver<Arc<ConfigUpdate>>,
+        matches: impl Fn(&ConfigUpdate) -> bool,
+    ) {
+        loop {
+            match receiver.recv().await {
+                Ok(update) if matches(&update) => return,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => return,
+                Err(broadcast::error::RecvError::Closed) => std::future::pending().await,
+            }
+        }
+    }
+}
+
+/// 发布配置更新（总线尚未创建时忽略）
+///
+/// # 参数
+/// - previous: 更新前的配置（读取失败时为 None，按默认配置比较）
+/// - current: 更新后的配置
+pub fn publish(app: &AppHandle, previous: Option<AppConfig>, current: AppConfig) {
+    if let Some(bus) = app.try_state::<ConfigBus>() {
+        bus.publish(previous.unwrap_or_default(), current);
+    }
+}
+
+/// 在后台把配置更新应用到同步控制、请求跟踪与同步策略
+///
+/// 在应用启动时调用，需要在相关的 Tauri State 管理之后
+pub fn spawn_apply(app: AppHandle) {
+    let mut updates = app.state::<ConfigBus>().subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match updates.recv().await {
+                Ok(update) => apply(&app, &update),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "配置更新处理落后，已跳过部分更新");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// 应用一次配置更新
+fn apply(app: &AppHandle, update: &ConfigUpdate) {
+    let current = &update.current;
+
+    if update.setting_changed("webdavTrace") {
+        crate::webdav::trace::trace().set_enabled(current.webdav_trace);
+    }
+
+    if update.setting_changed("syncPaused") || update.setting_changed("pausedFolders") {
+        let pause_state = PauseState::from_config(current);
+        if app.state::<SyncControl>().set_state(pause_state.clone()) {
+            let states = app.state::<SyncStateManager>();
+            for folder in &current.sync_folders {
+                states.apply_pause(&folder.id, pause_state.is_paused(&folder.id));
+            }
+        }
+    }
+
+    let skips = app.state::<PolicySkips>();
+    if update.setting_changed("syncPolicy") {
+        skips.clear();
+    }
+
+    for change in &update.changes {
+        match change {
+            ConfigChange::FolderRemoved(folder_id) => {
+                skips.record(folder_id, None);
+            }
+            ConfigChange::FolderUpdated { folder_id, fields }
+                if fields.iter().any(|field| field == "syncPolicy") =>
+            {
+                skips.record(folder_id, None);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SyncFolderConfig;
+
+    fn folder(id: &str) -> SyncFolderConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": id,
+            "localPath": format!("/tmp/{}", id),
+            "remotePath": "/remote",
+            "serverId": "server-1",
+            "syncDirection": "bidirectional",
+            "syncInterval": 30,
+            "autoSync": true,
+            "ignorePatterns": [],
+            "conflictResolution": "ask"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = AppConfig {
+            sync_folders: vec![folder("a"), folder("b")],
+            ..Default::default()
+        };
+
+        let mut current = previous.clone();
+        current.theme = "dark".to_string();
+        current.sync_paused = true;
+        current.sync_folders[0].sync_interval = 5;
+        current.sync_folders[0].ignore_patterns = vec!["*.tmp".to_string()];
+        current.sync_folders.remove(1);
+        current.sync_folders.push(folder("c"));
+
+        assert_eq!(
+            diff(&previous, &current),
+            vec![
+                ConfigChange::Setting("syncPaused".to_string()),
+                ConfigChange::Setting("theme".to_string()),
+                ConfigChange::FolderUpdated {
+                    folder_id: "a".to_string(),
+                    fields: vec!["ignorePatterns".to_string(), "syncInterval".to_string()],
+                },
+                ConfigChange::FolderRemoved("b".to_string()),
+                ConfigChange::FolderAdded("c".to_string()),
+            ]
+        );
+        assert!(diff(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_reschedules_polling() {
+        let previous = AppConfig {
+            sync_folders: vec![folder("a")],
+            ..Default::default()
+        };
+
+        let mut ignored = previous.clone();
+        ignored.sync_folders[0].ignore_patterns = vec!["*.log".to_string()];
+        assert!(!ConfigUpdate::new(&previous, ignored).reschedules_polling());
+
+        let mut interval = previous.clone();
+        interval.sync_folders[0].sync_interval = 1;
+        assert!(ConfigUpdate::new(&previous, interval).reschedules_polling());
+
+        let mut policy = previous.clone();
+        policy.sync_policy.pause_on_metered = true;
+        assert!(ConfigUpdate::new(&previous, policy).reschedules_polling());
+    }
+
+    #[tokio::test]
+    async fn test_publish() {
+        let bus = ConfigBus::default();
+        let mut receiver = bus.subscribe();
+
+        let previous = AppConfig::default();
+        assert!(!bus.publish(previous.clone(), previous.clone()));
+
+        let mut current = previous.clone();
+        current.webdav_trace = true;
+        assert!(bus.publish(previous, current));
+
+        let update = receiver.recv().await.unwrap();
+        assert!(update.setting_changed("webdavTrace"));
+        assert!(receiver.try_recv().is_err());
+    }
+}
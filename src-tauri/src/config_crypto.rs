@@ -0,0 +1,296 @@
+/// 配置文件静态加密
+///
+/// 配置文件中包含服务器地址和用户名。开启 `encryptConfig` 后，配置存储保存时整体使用
+/// AES-256-GCM 加密（见 `crypto::encrypt_with_key`），文件内容只有一个 `encryptedConfig` 字段：
+/// - 密钥为随机生成的 256 位密钥，Base64 编码后保存在系统 Keyring
+///   （Keyring 不可用时保存在加密凭据文件，见 `credential_store`），首次加密时生成
+/// - 加载时自动识别加密文件与明文文件：旧版本的明文配置可以直接读取，开启后下次保存时加密；
+///   关闭后下次保存恢复为明文
+/// - 加密文件无法解密时（密钥丢失或 Keyring 无法访问），拒绝保存配置，避免默认配置覆盖加密文件
+///
+/// Store 插件的序列化函数只能是函数指针，因此密钥与解密状态保存在全局状态中
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::Value as JsonValue;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{CONFIG_KEY_ENTRY, CONFIG_STORE_FILE};
+use crate::crypto::{self, EncryptedBlob, KEY_LEN};
+use crate::webdav::keyring::KeyringManager;
+use crate::{Result, SyncError};
+
+/// 加密配置文件中保存密文的字段
+const ENCRYPTED_FIELD: &str = "encryptedConfig";
+
+/// 配置存储中保存应用配置的键
+const APP_CONFIG_KEY: &str = "app_config";
+
+/// 应用配置中表示开启加密的字段
+const ENABLED_FIELD: &str = "encryptConfig";
+
+/// 已从 Keyring 读取的密钥
+static KEY: Mutex<Option<[u8; KEY_LEN]>> = Mutex::new(None);
+
+/// 最近一次加载的加密配置文件是否无法解密
+static UNREADABLE: AtomicBool = AtomicBool::new(false);
+
+/// Store 插件序列化函数返回的错误
+type StoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 读取配置加密密钥
+///
+/// # 参数
+/// - create: Keyring 中没有密钥时是否生成并保存新密钥
+///
+/// # 返回
+/// - Ok(key): 密钥
+/// - Err(SyncError::NotFound): 没有密钥且 create 为 false
+/// - Err(SyncError): Keyring 无法访问或保存的密钥无效
+pub fn encryption_key(create: bool) -> Result<[u8; KEY_LEN]> {
+    let mut cached = KEY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(key) = *cached {
+        return Ok(key);
+    }
+
+    let key = match KeyringManager::get_password(CONFIG_KEY_ENTRY) {
+        Ok(encoded) => decode_key(&encoded)?,
+        Err(SyncError::NotFound(_)) if create => {
+            let key = crypto::generate_key();
+            KeyringManager::save_password(CONFIG_KEY_ENTRY, &BASE64.encode(key))?;
+            tracing::info!("已生成配置文件加密密钥");
+            key
+        }
+        Err(e) => return Err(e),
+    };
+    *cached = Some(key);
+    Ok(key)
+}
+
+/// 解码 Keyring 中保存的密钥
+fn decode_key(encoded: &str) -> Result<[u8; KEY_LEN]> {
+    BASE64
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+        .ok_or_else(|| {
+            SyncError::ConfigError("Invalid config encryption key in keyring".to_string())
+        })
+}
+
+/// 配置文件内容是否已加密
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    serde_json::from_slice::<HashMap<String, JsonValue>>(bytes)
+        .map(|entries| entries.len() == 1 && entries.contains_key(ENCRYPTED_FIELD))
+        .unwrap_or(false)
+}
+
+/// 配置文件是否已加密（文件不存在或无法读取时返回 false）
+pub fn is_file_encrypted(path: &Path) -> bool {
+    std::fs::read(path)
+        .map(|bytes| is_encrypted(&bytes))
+        .unwrap_or(false)
+}
+
+/// 存储内容中的应用配置是否开启了加密
+fn encryption_enabled(entries: &HashMap<String, JsonValue>) -> bool {
+    entries
+        .get(APP_CONFIG_KEY)
+        .and_then(|config| config.get(ENABLED_FIELD))
+        .and_then(JsonValue::as_bool)
+        .unwrap_or(false)
+}
+
+/// 序列化配置存储内容
+///
+/// # 参数
+/// - entries: 存储内容
+/// - key: 开启加密时使用的密钥
+///
+/// # 返回
+/// 提供密钥时返回加密文件内容，否则返回格式化的明文 JSON
+pub fn encode(
+    entries: &HashMap<String, JsonValue>,
+    key: Option<&[u8; KEY_LEN]>,
+) -> Result<Vec<u8>> {
+    let plaintext = serde_json::to_vec_pretty(entries)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to serialize config: {}", e)))?;
+    let Some(key) = key else {
+        return Ok(plaintext);
+    };
+
+    let blob = crypto::encrypt_with_key(&plaintext, key)?;
+    let mut envelope = serde_json::Map::new();
+    envelope.insert(
+        ENCRYPTED_FIELD.to_string(),
+        serde_json::to_value(blob)
+            .map_err(|e| SyncError::ConfigError(format!("Failed to serialize config: {}", e)))?,
+    );
+    serde_json::to_vec_pretty(&envelope)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to serialize config: {}", e)))
+}
+
+/// 解析配置文件内容（加密或明文）
+///
+/// # 参数
+/// - bytes: 文件内容
+/// - key: 读取密钥的函数，只有文件已加密时才调用
+///
+/// # 返回
+/// - Ok(entries): 存储内容
+/// - Err(SyncError::ConfigError): 不是有效的配置文件
+/// - Err(SyncError::AuthError): 密钥错误或文件已被篡改
+pub fn decode(
+    bytes: &[u8],
+    key: impl FnOnce() -> Result<[u8; KEY_LEN]>,
+) -> Result<HashMap<String, JsonValue>> {
+    let mut entries: HashMap<String, JsonValue> = serde_json::from_slice(bytes)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to parse config file: {}", e)))?;
+    if entries.len() != 1 {
+        return Ok(entries);
+    }
+    let Some(blob) = entries.remove(ENCRYPTED_FIELD) else {
+        return Ok(entries);
+    };
+
+    let blob: EncryptedBlob = serde_json::from_value(blob)
+        .map_err(|e| SyncError::ConfigError(format!("Invalid encrypted config: {}", e)))?;
+    let plaintext = crypto::decrypt_with_key(&blob, &key()?)?;
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to parse config file: {}", e)))
+}
+
+/// Store 插件的序列化函数
+///
+/// 应用配置开启了加密时加密保存，需要时生成密钥
+pub fn serialize_store(
+    entries: &HashMap<String, JsonValue>,
+) -> std::result::Result<Vec<u8>, StoreError> {
+    if UNREADABLE.load(Ordering::SeqCst) {
+        return Err(SyncError::ConfigError(
+            "Refusing to overwrite an encrypted config file that could not be decrypted"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let key = if encryption_enabled(entries) {
+        Some(encryption_key(true)?)
+    } else {
+        None
+    };
+    Ok(encode(entries, key.as_ref())?)
+}
+
+/// Store 插件的反序列化函数
+///
+/// 同时接受加密文件与明文文件，明文文件在开启加密后的下次保存时加密
+pub fn deserialize_store(
+    bytes: &[u8],
+) -> std::result::Result<HashMap<String, JsonValue>, StoreError> {
+    let encrypted = is_encrypted(bytes);
+    match decode(bytes, || encryption_key(false)) {
+        Ok(entries) => {
+            if encrypted {
+                UNREADABLE.store(false, Ordering::SeqCst);
+            }
+            Ok(entries)
+        }
+        Err(e) => {
+            if encrypted {
+                tracing::error!(error = %e, "无法解密配置文件，在恢复密钥之前不会保存配置");
+                UNREADABLE.store(true, Ordering::SeqCst);
+            }
+            Err(e.into())
+        }
+    }
+}
+
+/// 开启了加密但配置文件仍是明文时（旧版本的配置或外部写入的明文配置），立即加密保存
+///
+/// # 返回
+/// - Ok(true): 已重新加密保存
+/// - Ok(false): 未开启加密或文件已加密
+pub fn ensure_encrypted(app: &AppHandle) -> Result<bool> {
+    let store = app
+        .store(CONFIG_STORE_FILE)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to access store: {}", e)))?;
+    let enabled = store
+        .get(APP_CONFIG_KEY)
+        .and_then(|config| config.get(ENABLED_FIELD).and_then(JsonValue::as_bool))
+        .unwrap_or(false);
+    let path = tauri_plugin_store::resolve_store_path(app, CONFIG_STORE_FILE)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to resolve config path: {}", e)))?;
+    if !enabled || is_file_encrypted(&path) {
+        return Ok(false);
+    }
+
+    store
+        .save()
+        .map_err(|e| SyncError::ConfigError(format!("Failed to save config: {}", e)))?;
+    tracing::info!("明文配置文件已加密保存");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(encrypt: bool) -> HashMap<String, JsonValue> {
+        HashMap::from([(
+            APP_CONFIG_KEY.to_string(),
+            serde_json::json!({
+                "encryptConfig": encrypt,
+                "webdavServers": [{ "url": "https://dav.example.com", "username": "alice" }]
+            }),
+        )])
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let key = crypto::generate_key();
+        let bytes = encode(&entries(true), Some(&key)).unwrap();
+
+        assert!(is_encrypted(&bytes));
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert!(!text.contains("dav.example.com"));
+        assert!(!text.contains("alice"));
+
+        assert_eq!(decode(&bytes, || Ok(key)).unwrap(), entries(true));
+        assert!(matches!(
+            decode(&bytes, || Ok(crypto::generate_key())),
+            Err(SyncError::AuthError(_))
+        ));
+        assert!(decode(&bytes, || Err(SyncError::NotFound("key".to_string()))).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_is_read_without_key() {
+        let bytes = encode(&entries(false), None).unwrap();
+
+        assert!(!is_encrypted(&bytes));
+        let decoded = decode(&bytes, || panic!("key is not needed for plaintext")).unwrap();
+        assert_eq!(decoded, entries(false));
+        assert!(decode(b"not json", || Ok(crypto::generate_key())).is_err());
+    }
+
+    #[test]
+    fn test_encryption_enabled() {
+        assert!(encryption_enabled(&entries(true)));
+        assert!(!encryption_enabled(&entries(false)));
+        assert!(!encryption_enabled(&HashMap::new()));
+    }
+
+    #[test]
+    fn test_decode_key() {
+        let key = crypto::generate_key();
+        assert_eq!(decode_key(&BASE64.encode(key)).unwrap(), key);
+        assert!(decode_key("c2hvcnQ=").is_err());
+        assert!(decode_key("not base64!").is_err());
+    }
+}
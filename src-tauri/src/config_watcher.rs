@@ -1,22 +1,50 @@
 /// LightSync 配置文件监听模块
 ///
-/// 监听配置文件变化，当配置文件被外部程序修改时通知前端
+/// 监听配置文件变化，当配置文件被外部程序修改时重新加载：
+/// - 防抖：写入停止 `CONFIG_WATCH_DEBOUNCE_MS` 毫秒后才读取，编辑器的多次写入只加载一次
+/// - 校验：新配置解析并通过校验后才替换内存中的配置，不会只应用一部分
+/// - 回滚：解析或校验失败时把当前配置写回配置文件，并推送 `config://reload-failed` 事件
+/// - 通知：配置发生变化时推送 `config://changed` 事件，包含发生变化的顶层配置项
+/// - 加密：加密的配置文件解密后加载；开启加密时外部写入的明文配置加载后重新加密保存
+///
+/// 后台任务（远程轮询、同步策略检查等）每次执行时都重新读取配置，重新加载后即按新配置运行
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::PathBuf;
-use std::sync::mpsc::channel;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::sync::Mutex;
+use tauri_plugin_store::StoreExt;
+use tokio::sync::{mpsc, Mutex};
 
+use crate::config::{self, AppConfig};
+use crate::config_bus;
+use crate::config_crypto;
+use crate::constants::{CONFIG_STORE_FILE, CONFIG_WATCH_DEBOUNCE_MS};
 use crate::error::{Result, SyncError};
 
+/// 配置变化事件名称
+pub const CONFIG_CHANGED_EVENT: &str = "config://changed";
+
+/// 配置重新加载失败事件名称
+pub const CONFIG_RELOAD_FAILED_EVENT: &str = "config://reload-failed";
+
+/// 配置存储中保存应用配置的键
+const APP_CONFIG_KEY: &str = "app_config";
+
+/// 配置变化事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangedEvent {
+    /// 发生变化的顶层配置项（camelCase，按字母排序）
+    pub changed_keys: Vec<String>,
+}
+
 /// 配置文件监听器
 #[derive(Clone)]
 pub struct ConfigWatcher {
     watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
     app_handle: AppHandle,
-    last_event_time: Arc<Mutex<Option<Instant>>>,
 }
 
 impl ConfigWatcher {
@@ -25,28 +53,41 @@ impl ConfigWatcher {
         Self {
             watcher: Arc::new(Mutex::new(None)),
             app_handle,
-            last_event_time: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// 是否正在监听
+    pub async fn is_running(&self) -> bool {
+        self.watcher.lock().await.is_some()
+    }
+
     /// 开始监听配置文件
+    ///
+    /// 监听配置文件所在的目录而不是文件本身，编辑器通过重命名临时文件保存时也能收到事件
     pub async fn start(&self, config_path: PathBuf) -> Result<()> {
-        let (tx, rx) = channel();
-        let app_handle = self.app_handle.clone();
-        let last_event_time = self.last_event_time.clone();
+        let dir = config_path
+            .parent()
+            .ok_or_else(|| {
+                SyncError::WatcherError(format!(
+                    "Config file has no parent directory: {}",
+                    config_path.display()
+                ))
+            })?
+            .to_path_buf();
+        let (tx, mut rx) = mpsc::unbounded_channel();
 
         // 创建文件监听器，使用更宽松的配置以减少 macOS 上的事件频率
+        let watched = config_path.clone();
         let mut watcher = RecommendedWatcher::new(
             move |res: notify::Result<Event>| {
                 if let Ok(event) = res {
-                    // 过滤掉不相关的事件，减少 macOS 上的事件噪音
-                    match event.kind {
-                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
-                            let _ = tx.send(event);
-                        }
-                        _ => {
-                            // 忽略其他类型的事件（如权限变更等）
-                        }
+                    // 只处理配置文件的创建、修改和删除事件
+                    let relevant = matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) && event.paths.iter().any(|path| path == &watched);
+                    if relevant {
+                        let _ = tx.send(());
                     }
                 }
             },
@@ -56,54 +97,26 @@ impl ConfigWatcher {
         )
         .map_err(|e| SyncError::WatcherError(format!("Failed to create watcher: {}", e)))?;
 
-        // 监听配置文件
         watcher
-            .watch(&config_path, RecursiveMode::NonRecursive)
+            .watch(&dir, RecursiveMode::NonRecursive)
             .map_err(|e| SyncError::WatcherError(format!("Failed to watch config file: {}", e)))?;
 
-        // 保存 watcher 实例
-        let mut watcher_lock = self.watcher.lock().await;
-        *watcher_lock = Some(watcher);
+        // 保存 watcher 实例，停止监听时释放 watcher 会关闭通道并结束处理任务
+        *self.watcher.lock().await = Some(watcher);
 
-        // 启动事件处理任务
-        let app_handle_clone = app_handle.clone();
+        let app = self.app_handle.clone();
         tokio::spawn(async move {
-            loop {
-                match rx.recv() {
-                    Ok(event) => {
-                        // 防抖处理：检查距离上次事件的时间
-                        let now = Instant::now();
-                        let should_notify = {
-                            let mut last_time = last_event_time.lock().await;
-                            match *last_time {
-                                None => {
-                                    *last_time = Some(now);
-                                    true
-                                }
-                                Some(last) => {
-                                    if now.duration_since(last) > Duration::from_millis(500) {
-                                        *last_time = Some(now);
-                                        true
-                                    } else {
-                                        false
-                                    }
-                                }
-                            }
-                        };
-
-                        if should_notify {
-                            // 当配置文件发生变化时，发送通知到前端
-                            let event_type = format!("{:?}", event.kind);
-                            if let Err(e) = app_handle_clone.emit("config-changed", event_type) {
-                                eprintln!("Failed to emit config-changed event: {}", e);
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // 通道关闭，退出循环
-                        break;
+            let debounce = Duration::from_millis(CONFIG_WATCH_DEBOUNCE_MS);
+            while rx.recv().await.is_some() {
+                // 防抖处理：等待写入停止
+                loop {
+                    match tokio::time::timeout(debounce, rx.recv()).await {
+                        Ok(Some(())) => continue,
+                        Ok(None) => return,
+                        Err(_) => break,
                     }
                 }
+                reload(&app, &config_path);
             }
         });
 
@@ -112,29 +125,105 @@ impl ConfigWatcher {
 
     /// 停止监听
     pub async fn stop(&self) {
-        let mut watcher_lock = self.watcher.lock().await;
-        *watcher_lock = None;
+        *self.watcher.lock().await = None;
+    }
+}
+
+/// 重新加载配置文件，校验失败时回滚
+fn reload(app: &AppHandle, config_path: &Path) {
+    let store = match app.store(CONFIG_STORE_FILE) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!(error = %e, "访问配置存储失败，跳过重新加载");
+            return;
+        }
+    };
+    let previous = store
+        .get(APP_CONFIG_KEY)
+        .unwrap_or_else(|| serde_json::to_value(AppConfig::default()).unwrap_or_default());
+
+    let loaded = std::fs::read(config_path)
+        .map_err(SyncError::from)
+        .and_then(|bytes| {
+            let (config, value) = parse_config(&bytes)?;
+            Ok((config, value, config_crypto::is_encrypted(&bytes)))
+        });
+    let (config, value, encrypted) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::warn!(path = %config_path.display(), error = %e, "配置文件无效，已恢复为当前配置");
+            // 内存中仍是修改前的配置，写回文件即可回滚
+            if let Err(save_error) = store.save() {
+                tracing::error!(error = %save_error, "回滚配置文件失败");
+            }
+            let _ = app.emit(CONFIG_RELOAD_FAILED_EVENT, e.to_string());
+            return;
+        }
+    };
+
+    let changed_keys = changed_keys(&previous, &value);
+    if config.encrypt_config && !encrypted {
+        // 开启加密时外部写入了明文配置，加载后立即重新加密保存
+        store.set(APP_CONFIG_KEY, value.clone());
+        if let Err(e) = store.save() {
+            tracing::warn!(error = %e, "重新加密配置文件失败");
+        }
+    }
+    if changed_keys.is_empty() {
+        // 应用自身保存配置时也会触发文件事件，此时内容与内存一致
+        return;
+    }
 
-        // 重置最后事件时间
-        let mut last_time = self.last_event_time.lock().await;
-        *last_time = None;
+    store.set(APP_CONFIG_KEY, value);
+    config_bus::publish(app, serde_json::from_value(previous).ok(), config);
+
+    tracing::info!(changed_keys = ?changed_keys, "配置文件已被外部修改，已重新加载");
+    if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, ConfigChangedEvent { changed_keys }) {
+        tracing::warn!(error = %e, "发送配置变化事件失败");
     }
 }
 
+/// 解析并校验配置文件内容（加密的配置文件使用 Keyring 中的密钥解密）
+///
+/// # 返回
+/// - Ok((config, value)): 应用配置及其 JSON 值（文件中没有应用配置时为默认配置）
+/// - Err(SyncError::ConfigError): 不是有效的 JSON、无法解析为应用配置或未通过校验
+fn parse_config(bytes: &[u8]) -> Result<(AppConfig, serde_json::Value)> {
+    let mut entries = config_crypto::decode(bytes, || config_crypto::encryption_key(false))?;
+
+    let config: AppConfig = match entries.remove(APP_CONFIG_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| SyncError::ConfigError(format!("Failed to parse config: {}", e)))?,
+        None => AppConfig::default(),
+    };
+
+    config::validate(&config)?;
+
+    // 重新序列化以补全缺省字段，与内存中的配置格式一致
+    let value = serde_json::to_value(&config)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to serialize config: {}", e)))?;
+    Ok((config, value))
+}
+
+/// 比较两份配置，返回发生变化的顶层配置项
+fn changed_keys(previous: &serde_json::Value, current: &serde_json::Value) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    config_bus::changed_fields(
+        previous.as_object().unwrap_or(&empty),
+        current.as_object().unwrap_or(&empty),
+    )
+}
+
 /// 启动配置文件监听
 #[tauri::command]
 pub async fn start_config_watcher(app: AppHandle) -> Result<()> {
-    // 获取配置文件路径
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| SyncError::ConfigError(format!("Failed to get config dir: {}", e)))?;
-
-    let config_path = config_dir.join("config.json");
+    // 获取配置存储文件路径
+    let config_path = tauri_plugin_store::resolve_store_path(&app, CONFIG_STORE_FILE)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to get config path: {}", e)))?;
 
     // 创建配置目录（如果不存在）
-    if !config_dir.exists() {
-        std::fs::create_dir_all(&config_dir)
+    if let Some(config_dir) = config_path.parent() {
+        std::fs::create_dir_all(config_dir)
             .map_err(|e| SyncError::ConfigError(format!("Failed to create config dir: {}", e)))?;
     }
 
@@ -144,36 +233,29 @@ pub async fn start_config_watcher(app: AppHandle) -> Result<()> {
             .map_err(|e| SyncError::ConfigError(format!("Failed to create config file: {}", e)))?;
     }
 
-    // 检查是否已经存在监听器，避免重复创建
-    if app.try_state::<ConfigWatcher>().is_some() {
-        return Err(SyncError::ConfigError(
-            "Config watcher already running".to_string(),
-        ));
-    }
-
-    // 创建监听器并存储到应用状态中
-    let watcher = ConfigWatcher::new(app.clone());
-    let watcher_clone = watcher.clone();
-    app.manage(watcher);
-
-    // 启动监听器
-    let config_path_clone = config_path.clone();
-    let app_handle = app.clone();
-
-    // 使用 tokio::spawn 创建任务，并处理可能的错误
-    tokio::spawn(async move {
-        match watcher_clone.start(config_path_clone).await {
-            Ok(_) => {
-                println!("Config watcher started successfully");
-            }
-            Err(e) => {
-                eprintln!("Failed to start config watcher: {}", e);
-                // 发送错误事件到前端
-                let _ = app_handle.emit("config-watcher-error", e.to_string());
-            }
+    // 检查是否已经在监听，避免重复创建
+    let watcher = match app.try_state::<ConfigWatcher>() {
+        Some(watcher) if watcher.is_running().await => {
+            return Err(SyncError::ConfigError(
+                "Config watcher already running".to_string(),
+            ));
+        }
+        Some(watcher) => watcher.inner().clone(),
+        None => {
+            let watcher = ConfigWatcher::new(app.clone());
+            app.manage(watcher.clone());
+            watcher
         }
-    });
+    };
+
+    if let Err(e) = watcher.start(config_path).await {
+        tracing::error!(error = %e, "启动配置文件监听失败");
+        // 发送错误事件到前端
+        let _ = app.emit("config-watcher-error", e.to_string());
+        return Err(e);
+    }
 
+    tracing::info!("配置文件监听已启动");
     Ok(())
 }
 
@@ -191,9 +273,38 @@ pub async fn stop_config_watcher(app: AppHandle) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_changed_keys() {
+        let previous = json!({ "theme": "light", "language": "zh-CN", "keepAlive": false });
+        let current = json!({ "theme": "dark", "language": "zh-CN", "webdavTrace": true });
+
+        assert_eq!(
+            changed_keys(&previous, &current),
+            vec!["keepAlive", "theme", "webdavTrace"]
+        );
+        assert!(changed_keys(&current, &current).is_empty());
+    }
+
     #[test]
-    fn test_config_watcher_creation() {
-        // 测试配置文件监听器创建
-        // 注意: 这个测试需要在 Tauri 环境中运行
+    fn test_parse_config() {
+        let config = AppConfig::default();
+        let file = json!({ "app_config": config });
+        let (parsed, value) = parse_config(file.to_string().as_bytes()).unwrap();
+        assert_eq!(parsed.language, config.language);
+        assert_eq!(value, serde_json::to_value(&config).unwrap());
+
+        // 没有应用配置时使用默认配置
+        let (parsed, _) = parse_config(b"{}").unwrap();
+        assert_eq!(parsed.theme, AppConfig::default().theme);
+
+        assert!(parse_config(b"{ \"app_config\": ").is_err());
+        assert!(parse_config(b"{ \"app_config\": { \"theme\": 1 } }").is_err());
+
+        let mut invalid = serde_json::to_value(&config).unwrap();
+        invalid["syncPolicy"]["minBatteryPercent"] = json!(150);
+        assert!(parse_config(json!({ "app_config": invalid }).to_string().as_bytes()).is_err());
     }
 }
@@ -14,12 +14,42 @@
 /// 配置存储文件名
 pub const CONFIG_STORE_FILE: &str = "config.json";
 
+/// 配置文件加密密钥在系统 Keyring 中的条目名
+pub const CONFIG_KEY_ENTRY: &str = "config-encryption-key";
+
+/// 配置文件被外部修改后，等待写入停止的时间（毫秒）
+pub const CONFIG_WATCH_DEBOUNCE_MS: u64 = 500;
+
+/// 文件监控事件队列中最多积压的路径数，超出时丢弃积压的事件并重新扫描整个文件夹
+pub const WATCHER_QUEUE_CAPACITY: usize = 10_000;
+
+/// 同步文件夹的本地文件变化后，等待事件停止的时间（毫秒），之后触发一次自动同步
+pub const FOLDER_WATCH_DEBOUNCE_MS: u64 = 2000;
+
+/// 配置变更总线的缓冲容量（订阅者落后超过该数量时只会收到最新的变更）
+pub const CONFIG_BUS_CAPACITY: usize = 16;
+
 /// 数据库文件名
 pub const DATABASE_FILE: &str = "lightsync.db";
 
 /// 日志文件名
 pub const LOG_FILE: &str = "lightsync.log";
 
+/// 加密凭据文件名（系统 Keyring 不可用时使用）
+pub const CREDENTIALS_FILE: &str = "credentials.enc.json";
+
+/// 加密凭据文件口令环境变量（未设置时使用本机标识派生密钥）
+pub const CREDENTIAL_PASSPHRASE_ENV: &str = "LIGHTSYNC_CREDENTIAL_PASSPHRASE";
+
+/// 服务器 Cookie 会话加密文件名（位于应用数据目录）
+pub const COOKIES_FILE: &str = "cookies.enc.json";
+
+/// 主密码信息文件名
+pub const APP_LOCK_FILE: &str = "app_lock.json";
+
+/// 客户端标识文件名（每个安装随机生成，写入远程同步标记）
+pub const CLIENT_ID_FILE: &str = "client_id";
+
 // ============================================================================
 // 目录名常量
 // ============================================================================
@@ -33,6 +63,15 @@ pub const LOG_DIR: &str = "logs";
 /// 临时文件目录名
 pub const TEMP_DIR: &str = "temp";
 
+/// 回收站目录名
+pub const TRASH_DIR: &str = "trash";
+
+/// 文件历史版本目录名
+pub const VERSIONS_DIR: &str = "versions";
+
+/// 远程图片缩略图缓存目录名
+pub const THUMBNAILS_DIR: &str = "thumbnails";
+
 // ============================================================================
 // 配置默认值
 // ============================================================================
@@ -43,15 +82,110 @@ pub const DEFAULT_LANGUAGE: &str = "zh-CN";
 /// 默认主题
 pub const DEFAULT_THEME: &str = "system";
 
+/// 主题选项
+pub mod theme {
+    pub const LIGHT: &str = "light";
+    pub const DARK: &str = "dark";
+    pub const SYSTEM: &str = "system";
+}
+
 /// 默认同步间隔（分钟）
 pub const DEFAULT_SYNC_INTERVAL: u32 = 30;
 
-/// 默认连接超时（秒）
+/// 默认请求超时（秒，单个非流式请求的总时长）
 pub const DEFAULT_TIMEOUT: u32 = 30;
 
+/// 默认建立连接超时（秒）
+pub const DEFAULT_CONNECT_TIMEOUT: u32 = 10;
+
+/// 默认读取超时（秒，流式传输中连续没有收发数据的最长时间）
+pub const DEFAULT_READ_TIMEOUT: u32 = 60;
+
+/// 服务器超时设置的有效范围（秒）
+pub mod timeout_range {
+    /// 请求超时
+    pub const REQUEST: (u32, u32) = (1, 300);
+    /// 建立连接超时
+    pub const CONNECT: (u32, u32) = (1, 120);
+    /// 读取超时
+    pub const READ: (u32, u32) = (5, 3600);
+}
+
 /// 默认冲突解决策略
 pub const DEFAULT_CONFLICT_RESOLUTION: &str = "newer-wins";
 
+/// 默认首次同步策略
+pub const DEFAULT_FIRST_SYNC_STRATEGY: &str = first_sync_strategy::MERGE_KEEP_BOTH;
+
+/// 默认回收站保留天数
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// 默认每个文件最多保留的历史版本数
+pub const DEFAULT_MAX_VERSIONS_PER_FILE: u32 = 10;
+
+/// 默认同步历史（日志与会话）保留天数
+pub const DEFAULT_LOG_RETENTION_DAYS: u32 = 90;
+
+/// 默认归档服务器保留天数
+pub const DEFAULT_SERVER_ARCHIVE_RETENTION_DAYS: u32 = 30;
+
+/// 默认自动锁定时间（分钟，0 表示不自动锁定）
+pub const DEFAULT_AUTO_LOCK_MINUTES: u32 = 15;
+
+/// 默认文件静默期（秒），本地文件最后修改后需保持不变这么久才会上传
+pub const DEFAULT_FILE_QUIET_PERIOD_SECS: u32 = 10;
+
+/// 默认传输优先窗口（分钟），最后修改时间在窗口内的文件排在积压的传输之前（0 表示不调整顺序）
+pub const DEFAULT_PRIORITY_WINDOW_MINS: u32 = 15;
+
+/// 默认并行分段下载阈值（MB），不小于该大小的文件使用多个连接按范围下载（0 表示不启用）
+pub const DEFAULT_PARALLEL_DOWNLOAD_THRESHOLD_MB: u32 = 64;
+
+/// 默认单次同步最多删除的文件比例（任一侧，相对于上次同步的文件数）
+pub const DEFAULT_MAX_DELETE_RATIO: f64 = 0.2;
+
+/// 删除文件数不超过该值时不触发大量删除保护
+pub const MASS_DELETION_MIN_FILES: usize = 10;
+
+/// 统计本地文件夹大小时最多使用的并行线程数
+pub const FOLDER_STATS_MAX_THREADS: usize = 8;
+
+/// 扫描本地文件夹时最多使用的并行线程数
+pub const LOCAL_SCAN_MAX_THREADS: usize = 8;
+
+/// 增量扫描本地文件夹时，距上次完整扫描超过该时间（秒）后重新完整扫描
+pub const LOCAL_FULL_SCAN_INTERVAL_SECS: i64 = 3600;
+
+/// 同步过程中写入快照（file_metadata）时每个事务包含的最多条目数
+pub const METADATA_BATCH_SIZE: usize = 1000;
+
+/// 快照记录在内存中最多等待的时间（毫秒），超过后即使未满一批也写入
+pub const METADATA_FLUSH_INTERVAL_MS: u64 = 2000;
+
+/// 同步日志批量写入时每个事务包含的最多条目数
+pub const SYNC_LOG_BATCH_SIZE: usize = 500;
+
+/// 同步日志在内存中最多等待的时间（毫秒），超过后即使未满一批也写入
+pub const SYNC_LOG_FLUSH_INTERVAL_MS: u64 = 1000;
+
+/// 同步日志写入通道的容量（条），已满时同步等待写入
+pub const SYNC_LOG_CHANNEL_CAPACITY: usize = 4096;
+
+/// 下载前检查本地剩余空间时额外保留的安全余量（字节）
+pub const LOCAL_FREE_SPACE_MARGIN: u64 = 256 * 1024 * 1024;
+
+/// 缩略图的最大边长（像素）
+pub const THUMBNAIL_MAX_SIZE: u32 = 1024;
+
+/// 本地生成缩略图时允许下载的原图最大大小（字节）
+pub const THUMBNAIL_MAX_SOURCE_SIZE: u64 = 20 * 1024 * 1024;
+
+/// 缩略图磁盘缓存的最大总大小（字节），超出时淘汰最久未使用的缩略图
+pub const THUMBNAIL_CACHE_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 可以在本地生成缩略图的图片扩展名
+pub const THUMBNAIL_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
 // ============================================================================
 // 应用程序信息
 // ============================================================================
@@ -75,12 +209,103 @@ pub const MAX_RETRY_COUNT: u32 = 3;
 /// 重试延迟（毫秒）
 pub const RETRY_DELAY_MS: u64 = 1000;
 
+/// 安全请求（PROPFIND、GET 等）最多跟随的重定向次数
+pub const MAX_REDIRECTS: usize = 5;
+
+/// 保持连接请求的间隔（秒），开启 `keep_alive` 后生效
+pub const KEEP_ALIVE_INTERVAL: u64 = 60;
+
+/// 保持连接请求间隔的最大随机抖动（秒），避免多个客户端同时发送
+pub const KEEP_ALIVE_JITTER: u64 = 15;
+
 /// 最大并发上传数
 pub const MAX_CONCURRENT_UPLOADS: usize = 5;
 
 /// 最大并发下载数
 pub const MAX_CONCURRENT_DOWNLOADS: usize = 5;
 
+/// 并行分段下载单个文件时的最大连接数
+pub const PARALLEL_DOWNLOAD_CONNECTIONS: usize = 4;
+
+/// 传输数据块大小（256KB），暂停时在当前数据块完成后停止
+pub const TRANSFER_CHUNK_SIZE: usize = 256 * 1024;
+
+/// 手动传输进度事件的最小推送间隔（毫秒）
+pub const TRANSFER_PROGRESS_INTERVAL_MS: u64 = 200;
+
+/// 同步传输阶段字节进度事件的推送间隔（毫秒）
+pub const SYNC_PROGRESS_INTERVAL_MS: u64 = 500;
+
+/// 传输速度统计的滑动窗口长度（秒）
+pub const TRANSFER_SPEED_WINDOW_SECS: u64 = 5;
+
+/// 下载临时文件后缀，下载完成后重命名为目标文件
+pub const PARTIAL_DOWNLOAD_SUFFIX: &str = ".lightsync-part";
+
+/// 端到端加密的同步清单文件名（位于同步文件夹的远程根目录）
+pub const ENCRYPTION_MANIFEST_FILE: &str = ".lightsync-manifest.json";
+
+/// 同步文件夹本地根目录下的标记文件（内容为同步文件夹 ID），用于确认根目录可用
+pub const SYNC_ROOT_MARKER: &str = ".lightsync";
+
+/// 端到端加密的明文数据块大小（64KB），每块单独加密并附带认证标签
+pub const ENCRYPTION_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 压缩上传的默认最小文件大小（4KB），更小的文件压缩收益不明显
+pub const DEFAULT_COMPRESSION_MIN_SIZE: u64 = 4 * 1024;
+
+/// 传输完整性校验失败后的最大重传次数
+pub const MAX_VERIFY_RETRIES: u32 = 2;
+
+/// 上传时远程文件锁的有效期（秒），锁在一批上传全部结束后才释放
+pub const REMOTE_LOCK_TIMEOUT: u64 = 60 * 60;
+
+/// Windows 路径长度上限（MAX_PATH），超过时本地路径需要添加 `\\?\` 前缀
+pub const WINDOWS_MAX_PATH: usize = 260;
+
+/// Nextcloud 登录流程轮询间隔（秒）
+pub const LOGIN_FLOW_POLL_INTERVAL: u64 = 2;
+
+/// Nextcloud 登录流程最长等待时间（秒，Nextcloud 的 token 有效期为 20 分钟）
+pub const LOGIN_FLOW_TIMEOUT: u64 = 20 * 60;
+
+/// 连通性检测间隔（秒）
+pub const CONNECTIVITY_CHECK_INTERVAL: u64 = 30;
+
+/// 远程变更轮询的检查间隔（秒），各文件夹按自身同步间隔决定是否轮询
+pub const REMOTE_POLL_TICK: u64 = 60;
+
+/// 远程目录树缓存的过期时间（秒），浏览已过期的缓存时在后台重新列出远程目录
+pub const REMOTE_CACHE_STALE_SECS: i64 = 300;
+
+/// 检查因文件仍在写入而推迟的文件夹是否到期重新同步的间隔（秒）
+pub const STABILITY_RESYNC_TICK: u64 = 5;
+
+/// 连通性检测单次请求超时（秒）
+pub const CONNECTIVITY_PROBE_TIMEOUT: u64 = 5;
+
+/// 服务器健康检查间隔（秒）
+pub const SERVER_HEALTH_CHECK_INTERVAL: u64 = 5 * 60;
+
+/// 每个服务器保留的健康检查记录数（按 5 分钟间隔约 24 小时）
+pub const SERVER_HEALTH_HISTORY_LIMIT: i64 = 288;
+
+/// 数据库维护间隔（秒）
+pub const DB_MAINTENANCE_INTERVAL: u64 = 24 * 60 * 60;
+
+/// 应用启动后首次数据库维护的延迟（秒）
+pub const DB_MAINTENANCE_DELAY: u64 = 10 * 60;
+
+// ============================================================================
+// 配置导入导出
+// ============================================================================
+
+/// 配置导出文件格式标识
+pub const CONFIG_EXPORT_FORMAT: &str = "lightsync-config";
+
+/// 配置导出文件格式版本
+pub const CONFIG_EXPORT_VERSION: u32 = 1;
+
 // ============================================================================
 // 文件大小限制
 // ============================================================================
@@ -103,13 +328,42 @@ pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
     ".git",
     ".svn",
     "node_modules",
-    ".DS_Store",
-    "Thumbs.db",
     "*.tmp",
     "*.temp",
     "~*",
+    "~$*",
+    "*.crdownload",
+    "*.part",
+    "*.lightsync-part",
+    ".lightsync-manifest.json",
+    ".lightsync",
+];
+
+/// 操作系统生成的元数据文件（`ignore_system_files` 开启时忽略）
+///
+/// 包括 macOS 的 Finder 和 Spotlight 文件以及 Windows 资源管理器的缩略图缓存，
+/// 同步文件夹可能被其他平台的客户端同步到服务器，因此在所有平台上都忽略两组文件。
+/// AppleDouble 文件（`._name`）由 `SyncFilter::apple_double_files` 按附属关系检测
+pub const SYSTEM_FILE_PATTERNS: &[&str] = &[
+    // macOS
+    ".DS_Store",
+    ".AppleDouble",
+    ".LSOverride",
+    ".Spotlight-V100",
+    ".Trashes",
+    ".fseventsd",
+    ".TemporaryItems",
+    ".DocumentRevisions-V100",
+    "Icon\r",
+    // Windows
+    "Thumbs.db",
+    "ehthumbs.db",
+    "desktop.ini",
 ];
 
+/// 选择性同步目录树的默认展开深度
+pub const SELECTIVE_SYNC_TREE_DEPTH: u32 = 3;
+
 /// 同步方向选项
 pub mod sync_direction {
     pub const BIDIRECTIONAL: &str = "bidirectional";
@@ -125,6 +379,191 @@ pub mod conflict_resolution {
     pub const NEWER_WINS: &str = "newer-wins";
 }
 
+/// 首次同步策略（本地与远程都已有文件时如何合并）
+///
+/// - merge-keep-both: 合并两侧，同名但内容不同的文件两份都保留（本地文件改名为冲突副本）
+/// - prefer-local: 合并两侧，同名文件以本地为准
+/// - prefer-remote: 合并两侧，同名文件以远程为准
+/// - mirror: 以同步方向的源端为准（仅下载时为远程，否则为本地），删除另一侧多余的文件
+pub mod first_sync_strategy {
+    pub const MERGE_KEEP_BOTH: &str = "merge-keep-both";
+    pub const PREFER_LOCAL: &str = "prefer-local";
+    pub const PREFER_REMOTE: &str = "prefer-remote";
+    pub const MIRROR: &str = "mirror";
+}
+
+/// 同步文件夹中符号链接的处理策略
+pub mod symlink_policy {
+    pub const SKIP: &str = "skip";
+    pub const FOLLOW: &str = "follow";
+    pub const PLACEHOLDER: &str = "sync-as-placeholder";
+}
+
+/// 上传压缩算法
+pub mod compression_algorithm {
+    pub const ZSTD: &str = "zstd";
+    pub const GZIP: &str = "gzip";
+}
+
+/// 上传压缩的扩展名分组（各分组包含的扩展名见 `sync::compression::group_extensions`）
+pub mod compression_group {
+    pub const TEXT: &str = "text";
+    pub const CODE: &str = "code";
+    pub const DATA: &str = "data";
+    pub const DOCUMENT: &str = "document";
+}
+
+/// 服务器上的文件名在本地不合法时的处理策略
+pub mod reserved_name_policy {
+    /// 不合法的字符替换为 `_`
+    pub const RENAME: &str = "rename";
+    /// 不合法的字符编码为 `%XX`
+    pub const PERCENT_ENCODE: &str = "percent-encode";
+    pub const SKIP: &str = "skip";
+}
+
+/// 仅大小写不同的远程文件在不区分大小写的本地文件系统上的处理策略
+pub mod case_collision_policy {
+    /// 保留一个，其余以重命名的副本保存
+    pub const RENAME: &str = "rename";
+    pub const SKIP: &str = "skip";
+}
+
+/// 同步会话状态
+pub mod session_status {
+    pub const RUNNING: &str = "running";
+    pub const COMPLETED: &str = "completed";
+    pub const PAUSED: &str = "paused";
+    pub const CANCELLED: &str = "cancelled";
+    pub const FAILED: &str = "failed";
+    /// 应用在会话结束前退出，启动恢复时标记
+    pub const INTERRUPTED: &str = "interrupted";
+}
+
+/// 同步日志操作类型
+pub mod sync_action {
+    pub const UPLOAD: &str = "upload";
+    pub const DOWNLOAD: &str = "download";
+    pub const MKDIR_LOCAL: &str = "mkdir_local";
+    pub const MKDIR_REMOTE: &str = "mkdir_remote";
+    pub const DELETE_LOCAL: &str = "delete_local";
+    pub const DELETE_REMOTE: &str = "delete_remote";
+    /// 本地重命名的文件在服务器端移动（MOVE），不重新上传
+    pub const MOVE: &str = "move";
+    /// 首次同步时两侧内容不同，本地文件改名为冲突副本
+    pub const KEEP_BOTH: &str = "keep_both";
+    /// 远程已存在内容相同的文件，跳过上传
+    pub const SKIPPED_IDENTICAL: &str = "skipped-identical";
+    pub const CONFLICT: &str = "conflict";
+}
+
+/// 文件同步状态（file_metadata.status）
+pub mod file_status {
+    pub const PENDING: &str = "pending";
+    pub const SYNCED: &str = "synced";
+    /// 用户设置为忽略，不做任何同步操作
+    pub const IGNORED: &str = "ignored";
+    /// 用户设置为始终保留本地文件，远程删除时重新上传
+    pub const PINNED: &str = "pinned";
+}
+
+/// 同步日志状态
+pub mod log_status {
+    pub const SUCCESS: &str = "success";
+    pub const FAILED: &str = "failed";
+    pub const CONFLICT: &str = "conflict";
+    pub const VERIFIED: &str = "verified";
+    pub const CORRUPT: &str = "corrupt";
+    pub const SKIPPED: &str = "skipped";
+}
+
+/// 同步统计的时间范围（最近 7、30、90、365 天）
+pub mod statistics_period {
+    pub const WEEK: &str = "week";
+    pub const MONTH: &str = "month";
+    pub const QUARTER: &str = "quarter";
+    pub const YEAR: &str = "year";
+}
+
+/// 传输方向
+pub mod transfer_direction {
+    pub const UPLOAD: &str = "upload";
+    pub const DOWNLOAD: &str = "download";
+}
+
+/// 传输队列状态
+pub mod transfer_status {
+    pub const QUEUED: &str = "queued";
+    pub const RUNNING: &str = "running";
+    pub const PAUSED: &str = "paused";
+    pub const COMPLETED: &str = "completed";
+    pub const FAILED: &str = "failed";
+}
+
+/// 冲突处理状态
+pub mod conflict_status {
+    pub const PENDING: &str = "pending";
+    pub const RESOLVED: &str = "resolved";
+}
+
+/// WebDAV 服务器认证方式
+pub mod auth_type {
+    /// 账户用户名 + 密码
+    pub const BASIC: &str = "basic";
+    /// Nextcloud 登录流程获取的应用密码
+    pub const APP_PASSWORD: &str = "app_password";
+}
+
+/// 连接 WebDAV 服务器时使用的 IP 协议版本
+pub mod ip_version {
+    /// 由系统决定（同时尝试 IPv4 与 IPv6）
+    pub const ANY: &str = "any";
+    /// 只使用 IPv4
+    pub const IPV4: &str = "ipv4";
+    /// 只使用 IPv6
+    pub const IPV6: &str = "ipv6";
+}
+
+/// PROPFIND 请求方式（部分精简的 WebDAV 服务器无法处理指定属性的请求体）
+pub mod propfind_dialect {
+    /// 请求体中列出需要的属性（默认）
+    pub const STANDARD: &str = "standard";
+    /// 请求体为 `<D:allprop/>`
+    pub const ALLPROP: &str = "allprop";
+    /// 不带请求体（RFC 4918 中等同于 allprop）
+    pub const EMPTY: &str = "empty";
+}
+
+/// 同步文件夹远程根目录上的同步标记（WebDAV 自定义属性）
+pub mod remote_marker {
+    /// 属性命名空间
+    pub const NAMESPACE: &str = "urn:lightsync:dav";
+    /// 管理该目录的应用名称
+    pub const MANAGED_BY: &str = "managed-by";
+    /// 最近一次完成同步的客户端标识
+    pub const CLIENT_ID: &str = "client-id";
+    /// 最近一次完成同步的时间（Unix 时间戳，秒）
+    pub const LAST_SYNC: &str = "last-sync";
+}
+
+/// 服务器 URL 中的用户名占位符
+///
+/// 同一主机上的多个账户可以使用 `https://cloud.example.com/remote.php/dav/files/{username}/`
+/// 这样的地址，连接时替换为各自的用户名
+pub const USERNAME_PLACEHOLDER: &str = "{username}";
+
+/// 不允许通过服务器自定义请求头覆盖的请求头（由客户端自身管理）
+pub const RESERVED_CUSTOM_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "host",
+    "content-length",
+    "content-type",
+    "transfer-encoding",
+    "connection",
+    "range",
+];
+
 // ============================================================================
 // 数据库相关常量
 // ============================================================================
@@ -135,6 +574,12 @@ pub const DB_POOL_SIZE: u32 = 5;
 /// 数据库查询超时（秒）
 pub const DB_QUERY_TIMEOUT: u64 = 30;
 
+/// 分页查询默认每页条数
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// 分页查询每页最大条数
+pub const MAX_PAGE_SIZE: i64 = 500;
+
 // ============================================================================
 // 日志相关常量
 // ============================================================================
@@ -145,6 +590,52 @@ pub const LOG_FILE_MAX_SIZE: u64 = 10 * 1024 * 1024;
 /// 日志文件保留数量
 pub const LOG_FILE_RETENTION: usize = 5;
 
+/// 内存中保留的最近日志条数（供诊断面板查看）
+pub const LOG_BUFFER_CAPACITY: usize = 2000;
+
+/// 诊断面板单次获取日志的默认条数
+pub const DEFAULT_RECENT_LOGS_LIMIT: usize = 200;
+
+/// 内存中保留的最近 WebDAV 请求跟踪条数
+pub const WEBDAV_TRACE_CAPACITY: usize = 500;
+
+/// WebDAV 请求跟踪中单个请求头值的最大长度（字符），超出部分截断
+pub const WEBDAV_TRACE_HEADER_MAX_LEN: usize = 256;
+
+/// 诊断包中每个服务器包含的健康检查记录数
+pub const DIAGNOSTICS_HEALTH_CHECKS: i64 = 50;
+
+// ============================================================================
+// 系统状态相关常量
+// ============================================================================
+
+/// 查询电源与网络状态时外部命令（pmset、PowerShell、nmcli）的超时时间（秒）
+pub const SYSTEM_QUERY_TIMEOUT: u64 = 5;
+
+/// 电源类型
+pub mod power_source {
+    /// 外接电源（包括没有电池的台式机）
+    pub const AC: &str = "ac";
+    /// 电池供电
+    pub const BATTERY: &str = "battery";
+    /// 无法确定
+    pub const UNKNOWN: &str = "unknown";
+}
+
+/// 网络连接类型
+pub mod network_type {
+    /// 有线网络
+    pub const ETHERNET: &str = "ethernet";
+    /// 无线网络
+    pub const WIFI: &str = "wifi";
+    /// 蜂窝网络（包括手机网络共享）
+    pub const CELLULAR: &str = "cellular";
+    /// 没有网络连接
+    pub const NONE: &str = "none";
+    /// 无法确定
+    pub const UNKNOWN: &str = "unknown";
+}
+
 // ============================================================================
 // 测试相关常量（仅在测试时可用）
 // ============================================================================
@@ -188,4 +679,3 @@ mod tests {
         assert_eq!(conflict_resolution::NEWER_WINS, "newer-wins");
     }
 }
-
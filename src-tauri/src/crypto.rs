@@ -0,0 +1,274 @@
+/// 口令加密模块
+///
+/// 使用 PBKDF2-HMAC-SHA256 从用户口令派生密钥，再用 AES-256-GCM 加密数据。
+/// 每次加密都生成新的随机盐和随机数，密文中包含认证标签，口令错误或数据被篡改时解密失败。
+///
+/// 密钥本身已是随机生成的 256 位密钥时（如保存在系统 Keyring 中的密钥），
+/// 使用 `encrypt_with_key` / `decrypt_with_key` 直接加密，跳过密钥派生
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::{Result, SyncError};
+
+/// PBKDF2 迭代次数
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// 盐长度（字节）
+const SALT_LEN: usize = 16;
+
+/// 密钥长度（字节，AES-256）
+pub const KEY_LEN: usize = 32;
+
+/// 口令加密后的数据（二进制字段使用 Base64 编码，便于写入 JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedBlob {
+    /// 加密算法（固定为 aes-256-gcm）
+    pub algorithm: String,
+
+    /// 密钥派生迭代次数（直接使用密钥加密时为 0）
+    pub iterations: u32,
+
+    /// 密钥派生盐（直接使用密钥加密时为空）
+    pub salt: String,
+
+    /// AES-GCM 随机数
+    pub nonce: String,
+
+    /// 密文（含认证标签）
+    pub ciphertext: String,
+}
+
+/// 加密算法名称
+const ALGORITHM: &str = "aes-256-gcm";
+
+/// 从口令派生密钥
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+/// 生成随机密钥
+pub fn generate_key() -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// 使用密钥加密，返回 Base64 编码的随机数与密文
+fn seal(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<(String, String)> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| SyncError::ConfigError(format!("Failed to encrypt data: {}", e)))?;
+
+    Ok((BASE64.encode(nonce), BASE64.encode(ciphertext)))
+}
+
+/// 解码加密数据中 Base64 编码的字段
+fn decode_field(field: &str, value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| SyncError::ConfigError(format!("Invalid encrypted {}: {}", field, e)))
+}
+
+/// 使用密钥解密
+fn open(key: &[u8; KEY_LEN], blob: &EncryptedBlob) -> Result<Vec<u8>> {
+    let nonce = decode_field("nonce", &blob.nonce)?;
+    let ciphertext = decode_field("ciphertext", &blob.ciphertext)?;
+
+    if nonce.len() != 12 {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid encrypted nonce length: {}",
+            nonce.len()
+        )));
+    }
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| {
+            SyncError::AuthError("Wrong passphrase or corrupted encrypted data".to_string())
+        })
+}
+
+/// 检查加密算法
+fn check_algorithm(blob: &EncryptedBlob) -> Result<()> {
+    if blob.algorithm != ALGORITHM {
+        return Err(SyncError::ConfigError(format!(
+            "Unsupported encryption algorithm: {}",
+            blob.algorithm
+        )));
+    }
+    Ok(())
+}
+
+/// 使用口令加密数据
+///
+/// # 参数
+/// - plaintext: 明文
+/// - passphrase: 用户口令（不能为空）
+///
+/// # 返回
+/// - Ok(EncryptedBlob): 加密结果
+/// - Err(SyncError::ConfigError): 口令为空或加密失败
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<EncryptedBlob> {
+    if passphrase.is_empty() {
+        return Err(SyncError::ConfigError(
+            "Passphrase cannot be empty".to_string(),
+        ));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS);
+    let (nonce, ciphertext) = seal(&key, plaintext)?;
+
+    Ok(EncryptedBlob {
+        algorithm: ALGORITHM.to_string(),
+        iterations: PBKDF2_ITERATIONS,
+        salt: BASE64.encode(salt),
+        nonce,
+        ciphertext,
+    })
+}
+
+/// 使用口令解密数据
+///
+/// # 参数
+/// - blob: 加密数据
+/// - passphrase: 用户口令
+///
+/// # 返回
+/// - Ok(Vec<u8>): 明文
+/// - Err(SyncError::AuthError): 口令错误或数据已被篡改
+/// - Err(SyncError::ConfigError): 数据格式无效
+pub fn decrypt(blob: &EncryptedBlob, passphrase: &str) -> Result<Vec<u8>> {
+    check_algorithm(blob)?;
+    if blob.iterations == 0 {
+        return Err(SyncError::ConfigError(
+            "Data was encrypted with a key, not a passphrase".to_string(),
+        ));
+    }
+
+    let salt = decode_field("salt", &blob.salt)?;
+    let key = derive_key(passphrase, &salt, blob.iterations);
+    open(&key, blob)
+}
+
+/// 使用随机密钥加密数据（不派生密钥）
+///
+/// # 参数
+/// - plaintext: 明文
+/// - key: 256 位密钥（见 `generate_key`）
+///
+/// # 返回
+/// - Ok(EncryptedBlob): 加密结果（iterations 为 0，salt 为空）
+/// - Err(SyncError::ConfigError): 加密失败
+pub fn encrypt_with_key(plaintext: &[u8], key: &[u8; KEY_LEN]) -> Result<EncryptedBlob> {
+    let (nonce, ciphertext) = seal(key, plaintext)?;
+
+    Ok(EncryptedBlob {
+        algorithm: ALGORITHM.to_string(),
+        iterations: 0,
+        salt: String::new(),
+        nonce,
+        ciphertext,
+    })
+}
+
+/// 使用随机密钥解密数据
+///
+/// # 参数
+/// - blob: `encrypt_with_key` 加密的数据
+/// - key: 加密时使用的密钥
+///
+/// # 返回
+/// - Ok(Vec<u8>): 明文
+/// - Err(SyncError::AuthError): 密钥错误或数据已被篡改
+/// - Err(SyncError::ConfigError): 数据格式无效或数据使用口令加密
+pub fn decrypt_with_key(blob: &EncryptedBlob, key: &[u8; KEY_LEN]) -> Result<Vec<u8>> {
+    check_algorithm(blob)?;
+    if blob.iterations != 0 {
+        return Err(SyncError::ConfigError(
+            "Data was encrypted with a passphrase, not a key".to_string(),
+        ));
+    }
+
+    open(key, blob)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let blob = encrypt(b"secret data", "correct horse").unwrap();
+        assert_eq!(blob.algorithm, "aes-256-gcm");
+        assert_eq!(decrypt(&blob, "correct horse").unwrap(), b"secret data");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails() {
+        let blob = encrypt(b"secret data", "correct horse").unwrap();
+        let result = decrypt(&blob, "battery staple");
+        assert!(matches!(result, Err(SyncError::AuthError(_))));
+    }
+
+    #[test]
+    fn test_each_encryption_uses_fresh_salt_and_nonce() {
+        let first = encrypt(b"same", "pass").unwrap();
+        let second = encrypt(b"same", "pass").unwrap();
+        assert_ne!(first.salt, second.salt);
+        assert_ne!(first.nonce, second.nonce);
+        assert_ne!(first.ciphertext, second.ciphertext);
+    }
+
+    #[test]
+    fn test_empty_passphrase_rejected() {
+        assert!(encrypt(b"data", "").is_err());
+    }
+
+    #[test]
+    fn test_key_round_trip() {
+        let key = generate_key();
+        let blob = encrypt_with_key(b"secret data", &key).unwrap();
+        assert_eq!(blob.iterations, 0);
+        assert!(blob.salt.is_empty());
+        assert_eq!(decrypt_with_key(&blob, &key).unwrap(), b"secret data");
+
+        let other = generate_key();
+        assert_ne!(key, other);
+        assert!(matches!(
+            decrypt_with_key(&blob, &other),
+            Err(SyncError::AuthError(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_and_passphrase_blobs_are_not_interchangeable() {
+        let key = generate_key();
+        let keyed = encrypt_with_key(b"data", &key).unwrap();
+        assert!(matches!(
+            decrypt(&keyed, "pass"),
+            Err(SyncError::ConfigError(_))
+        ));
+
+        let passphrase = encrypt(b"data", "pass").unwrap();
+        assert!(matches!(
+            decrypt_with_key(&passphrase, &key),
+            Err(SyncError::ConfigError(_))
+        ));
+    }
+}
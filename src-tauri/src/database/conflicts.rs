@@ -0,0 +1,185 @@
+/// 同步冲突数据库操作模块
+///
+/// 冲突策略为 ask 时，同步引擎把无法自动处理的冲突记录到 conflicts 表，
+/// 等待用户选择保留哪一方
+use crate::constants::conflict_status;
+use crate::database::{Conflict, Database};
+use crate::{Result, SyncError};
+
+/// conflicts 表的查询列（顺序与 `row_to_conflict` 对应）
+const CONFLICT_COLUMNS: &str = "id, sync_folder_id, file_path, local_size, local_modified, \
+     remote_size, remote_modified, status, resolution, detected_at, resolved_at";
+
+/// 将查询结果行转换为冲突记录
+fn row_to_conflict(row: &rusqlite::Row<'_>) -> rusqlite::Result<Conflict> {
+    Ok(Conflict {
+        id: row.get(0)?,
+        sync_folder_id: row.get(1)?,
+        file_path: row.get(2)?,
+        local_size: row.get(3)?,
+        local_modified: row.get(4)?,
+        remote_size: row.get(5)?,
+        remote_modified: row.get(6)?,
+        status: row.get(7)?,
+        resolution: row.get(8)?,
+        detected_at: row.get(9)?,
+        resolved_at: row.get(10)?,
+    })
+}
+
+/// 记录冲突
+///
+/// 同一文件已有冲突记录时更新双方的大小与修改时间，并重新置为待处理
+///
+/// # 参数
+/// - sync_folder_id: 同步文件夹 ID
+/// - file_path: 相对于同步根目录的路径
+/// - local: 本地文件的 (大小, 修改时间)，本地已删除时为 None
+/// - remote: 远程文件的 (大小, 修改时间)，远程已删除时为 None
+///
+/// # 返回
+/// - Ok(i64): 冲突记录 ID
+pub async fn record(
+    db: &Database,
+    sync_folder_id: &str,
+    file_path: &str,
+    local: Option<(i64, i64)>,
+    remote: Option<(i64, i64)>,
+) -> Result<i64> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "INSERT INTO conflicts (
+             sync_folder_id, file_path, local_size, local_modified,
+             remote_size, remote_modified, status
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(sync_folder_id, file_path) DO UPDATE SET
+             local_size = excluded.local_size,
+             local_modified = excluded.local_modified,
+             remote_size = excluded.remote_size,
+             remote_modified = excluded.remote_modified,
+             status = excluded.status,
+             resolution = NULL,
+             detected_at = STRFTIME('%s', 'now'),
+             resolved_at = NULL
+         RETURNING id",
+        rusqlite::params![
+            sync_folder_id,
+            file_path,
+            local.map(|(size, _)| size),
+            local.map(|(_, modified)| modified),
+            remote.map(|(size, _)| size),
+            remote.map(|(_, modified)| modified),
+            conflict_status::PENDING
+        ],
+        |row| row.get(0),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to record conflict: {}", e)))
+}
+
+/// 获取待处理的冲突（按发现顺序）
+///
+/// # 参数
+/// - sync_folder_id: 只返回指定文件夹的冲突（None 表示全部）
+pub async fn list_pending(db: &Database, sync_folder_id: Option<&str>) -> Result<Vec<Conflict>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM conflicts
+             WHERE status = ?1 AND (?2 IS NULL OR sync_folder_id = ?2)
+             ORDER BY detected_at, id",
+            CONFLICT_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let conflicts = stmt
+        .query_map(
+            rusqlite::params![conflict_status::PENDING, sync_folder_id],
+            row_to_conflict,
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query conflicts: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read conflict: {}", e)))?;
+
+    Ok(conflicts)
+}
+
+/// 标记冲突已解决
+///
+/// # 参数
+/// - id: 冲突记录 ID
+/// - resolution: 采用的解决方式（keep-local, keep-remote, keep-both）
+///
+/// # 返回
+/// - Ok(()): 标记成功
+/// - Err(SyncError::NotFound): 冲突记录不存在
+pub async fn resolve(db: &Database, id: i64, resolution: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    let updated = conn
+        .execute(
+            "UPDATE conflicts SET
+                 status = ?2,
+                 resolution = ?3,
+                 resolved_at = STRFTIME('%s', 'now')
+             WHERE id = ?1",
+            rusqlite::params![id, conflict_status::RESOLVED, resolution],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to resolve conflict: {}", e)))?;
+
+    if updated == 0 {
+        return Err(SyncError::NotFound(format!("Conflict not found: {}", id)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::folder_records::{self, tests::create_folder, tests::create_test_db};
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_record_and_resolve() {
+        let (test_dir, db) = create_test_db();
+        folder_records::upsert(&db, &create_folder("folder-1"))
+            .await
+            .unwrap();
+        folder_records::upsert(&db, &create_folder("folder-2"))
+            .await
+            .unwrap();
+
+        let id = record(&db, "folder-1", "a.txt", Some((10, 100)), Some((20, 200)))
+            .await
+            .unwrap();
+        record(&db, "folder-2", "b.txt", None, Some((5, 50)))
+            .await
+            .unwrap();
+
+        assert_eq!(list_pending(&db, None).await.unwrap().len(), 2);
+        let pending = list_pending(&db, Some("folder-1")).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].remote_size, Some(20));
+
+        resolve(&db, id, "keep-local").await.unwrap();
+        assert!(list_pending(&db, Some("folder-1"))
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(resolve(&db, 9999, "keep-local").await.is_err());
+
+        // 同一文件再次冲突时复用记录并重新置为待处理
+        let again = record(&db, "folder-1", "a.txt", Some((11, 110)), Some((20, 200)))
+            .await
+            .unwrap();
+        assert_eq!(again, id);
+        let pending = list_pending(&db, Some("folder-1")).await.unwrap();
+        assert_eq!(pending[0].local_size, Some(11));
+        assert_eq!(pending[0].resolution, None);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
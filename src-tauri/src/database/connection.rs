@@ -0,0 +1,147 @@
+/// 共享数据库连接
+///
+/// 应用启动时打开一次 SQLite 连接，并通过 `tauri::State<Database>` 在各个命令间共享，
+/// 避免每次操作都重新解析路径、重新打开连接。
+///
+/// 打开连接时统一设置：
+/// - `journal_mode = WAL`：允许前端 plugin-sql 连接与后端连接并发读写
+/// - `foreign_keys = ON`：启用外键约束
+/// - `busy_timeout`：遇到锁时等待而不是立即失败
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::constants::{DATABASE_FILE, DB_QUERY_TIMEOUT};
+use crate::database::migrations;
+use crate::{Result, SyncError};
+
+/// 共享数据库连接
+///
+/// 内部使用 `Mutex` 包装单个连接，SQLite 写操作本身是串行的，单连接足以满足需求
+#[derive(Debug)]
+pub struct Database {
+    /// 数据库文件路径
+    path: PathBuf,
+
+    /// 数据库连接
+    conn: Mutex<Connection>,
+}
+
+impl Database {
+    /// 打开指定路径的数据库并设置连接参数
+    ///
+    /// # 参数
+    /// - path: 数据库文件路径
+    ///
+    /// # 返回
+    /// - Ok(Database): 打开成功
+    /// - Err(SyncError::DatabaseError): 打开或设置参数失败
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let conn = Connection::open(&path)
+            .map_err(|e| SyncError::DatabaseError(format!("Failed to open database: {}", e)))?;
+
+        Self::configure(&conn)?;
+
+        Ok(Self {
+            path,
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// 打开应用数据目录下的数据库，并执行尚未应用的迁移
+    ///
+    /// 数据库文件与前端 `sqlite:lightsync.db` 指向同一文件，
+    /// 无界面模式下前端不会加载数据库，迁移由后端执行（见 `migrations`）
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::DatabaseError(format!("Failed to get app data dir: {}", e)))?;
+
+        std::fs::create_dir_all(&app_dir).map_err(|e| {
+            SyncError::DatabaseError(format!("Failed to create app data dir: {}", e))
+        })?;
+
+        let db = Self::open(app_dir.join(DATABASE_FILE))?;
+        migrations::run(&*db.conn()?)?;
+        Ok(db)
+    }
+
+    /// 获取数据库文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 获取数据库连接
+    ///
+    /// 返回的锁在作用域结束时释放，注意不要跨 `.await` 持有
+    pub fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| SyncError::DatabaseError(format!("Database connection poisoned: {}", e)))
+    }
+
+    /// 设置连接参数（WAL、外键、忙等待超时）
+    fn configure(conn: &Connection) -> Result<()> {
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .map_err(|e| SyncError::DatabaseError(format!("Failed to enable WAL mode: {}", e)))?;
+
+        conn.pragma_update(None, "foreign_keys", "ON")
+            .map_err(|e| {
+                SyncError::DatabaseError(format!("Failed to enable foreign keys: {}", e))
+            })?;
+
+        conn.busy_timeout(Duration::from_secs(DB_QUERY_TIMEOUT))
+            .map_err(|e| SyncError::DatabaseError(format!("Failed to set busy timeout: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn create_test_dir() -> PathBuf {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        test_dir
+    }
+
+    #[test]
+    fn test_open_enables_wal_and_foreign_keys() {
+        let test_dir = create_test_dir();
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let conn = db.conn().unwrap();
+        let journal_mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        let foreign_keys: i64 = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+        assert_eq!(foreign_keys, 1);
+
+        drop(conn);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_path_is_preserved() {
+        let test_dir = create_test_dir();
+        let db_path = test_dir.join("lightsync.db");
+        let db = Database::open(&db_path).unwrap();
+
+        assert_eq!(db.path(), db_path.as_path());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,755 @@
+/// 文件元数据数据库操作模块
+///
+/// 提供对 file_metadata 表的操作，记录每个同步文件在本地的最新状态，
+/// 供同步引擎对比快照以及前端文件浏览器展示同步状态使用
+///
+/// 用户为单个文件设置的同步状态（ignored、pinned）同样保存在 status 列中，
+/// 同步引擎写入快照时保留这两种状态，只能通过 `set_sync_state` 修改
+///
+/// 注意: 删除采用软删除（is_delete = 1），便于后续追溯
+use crate::constants::file_status;
+use crate::database::{Database, FileMetadata};
+use crate::{Result, SyncError};
+
+/// file_metadata 表的查询列（顺序与 `row_to_metadata` 对应）
+const METADATA_COLUMNS: &str = "id, path, hash, size, modified_at, synced_at, sync_folder_id,
+                is_directory, status, created_at, updated_at, remote_size, inode";
+
+/// 将查询结果行转换为文件元数据
+fn row_to_metadata(row: &rusqlite::Row<'_>) -> rusqlite::Result<FileMetadata> {
+    Ok(FileMetadata {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        hash: row.get(2)?,
+        size: row.get(3)?,
+        modified_at: row.get(4)?,
+        synced_at: row.get(5)?,
+        sync_folder_id: row.get(6)?,
+        is_directory: row.get::<_, i32>(7)? != 0,
+        status: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        remote_size: row.get(11)?,
+        inode: row.get(12)?,
+    })
+}
+
+/// `upsert` 与 `upsert_batch` 共用的写入语句（参数见 `upsert_params`）
+const UPSERT_SQL: &str = "INSERT INTO file_metadata (
+        path, hash, size, modified_at, synced_at, sync_folder_id,
+        is_directory, status, created_at, updated_at, is_delete, remote_size, inode
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9, 0, ?10, ?11)
+    ON CONFLICT (sync_folder_id, path) DO UPDATE SET
+        hash = excluded.hash,
+        size = excluded.size,
+        modified_at = excluded.modified_at,
+        synced_at = COALESCE(excluded.synced_at, file_metadata.synced_at),
+        is_directory = excluded.is_directory,
+        status = CASE
+            WHEN file_metadata.is_delete = 0 AND file_metadata.status IN (?12, ?13)
+            THEN file_metadata.status ELSE excluded.status
+        END,
+        updated_at = excluded.updated_at,
+        is_delete = 0,
+        remote_size = excluded.remote_size,
+        inode = excluded.inode";
+
+/// `UPSERT_SQL` 的参数
+fn upsert_params<'a>(metadata: &'a FileMetadata, now: &'a i64) -> [&'a dyn rusqlite::ToSql; 13] {
+    [
+        &metadata.path,
+        &metadata.hash,
+        &metadata.size,
+        &metadata.modified_at,
+        &metadata.synced_at,
+        &metadata.sync_folder_id,
+        &metadata.is_directory,
+        &metadata.status,
+        now,
+        &metadata.remote_size,
+        &metadata.inode,
+        &file_status::IGNORED,
+        &file_status::PINNED,
+    ]
+}
+
+/// 插入或更新文件元数据
+///
+/// 以 (sync_folder_id, path) 为唯一键，已存在时更新哈希、大小、修改时间、状态、远程大小和 inode，
+/// 并清除软删除标记。未删除的记录已由用户设置为 ignored 或 pinned 时保留原状态
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - metadata: 文件元数据（id、created_at、updated_at 会被忽略）
+///
+/// # 返回
+/// - Ok(i64): 记录 ID
+/// - Err(SyncError::DatabaseError): 写入失败
+pub async fn upsert(db: &Database, metadata: &FileMetadata) -> Result<i64> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.query_row(
+        &format!("{} RETURNING id", UPSERT_SQL),
+        upsert_params(metadata, &now).as_slice(),
+        |row| row.get(0),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to upsert file metadata: {}", e)))
+}
+
+/// 在一个事务中插入或更新多条文件元数据
+///
+/// 与逐条调用 `upsert` 的效果相同，大量文件同步完成时避免每条记录单独提交
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - rows: 文件元数据（id、created_at、updated_at 会被忽略）
+pub async fn upsert_batch(db: &Database, rows: &[FileMetadata]) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+    let db_err = |e: rusqlite::Error| {
+        SyncError::DatabaseError(format!("Failed to upsert file metadata: {}", e))
+    };
+
+    let tx = conn.unchecked_transaction().map_err(db_err)?;
+    {
+        let mut stmt = tx.prepare(UPSERT_SQL).map_err(db_err)?;
+        for metadata in rows {
+            stmt.execute(upsert_params(metadata, &now).as_slice())
+                .map_err(db_err)?;
+        }
+    }
+    tx.commit().map_err(db_err)?;
+
+    Ok(())
+}
+
+/// 查询同步文件夹下的所有文件元数据
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - Ok(Vec<FileMetadata>): 按路径排序的文件元数据（不包含已软删除的记录）
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn get_by_folder(db: &Database, sync_folder_id: i64) -> Result<Vec<FileMetadata>> {
+    let conn = db.conn()?;
+
+    let query = format!(
+        "SELECT {} FROM file_metadata
+         WHERE sync_folder_id = ?1 AND is_delete = 0
+         ORDER BY path",
+        METADATA_COLUMNS
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let files = stmt
+        .query_map(rusqlite::params![sync_folder_id], row_to_metadata)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query file metadata: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
+
+    Ok(files)
+}
+
+/// 查询单个文件的元数据
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+/// - path: 文件路径（相对于同步文件夹根目录）
+///
+/// # 返回
+/// - Ok(FileMetadata): 查询成功
+/// - Err(SyncError::NotFound): 记录不存在或已被软删除
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn get_by_path(db: &Database, sync_folder_id: i64, path: &str) -> Result<FileMetadata> {
+    let conn = db.conn()?;
+
+    let query = format!(
+        "SELECT {} FROM file_metadata
+         WHERE sync_folder_id = ?1 AND path = ?2 AND is_delete = 0
+         LIMIT 1",
+        METADATA_COLUMNS
+    );
+
+    conn.query_row(
+        &query,
+        rusqlite::params![sync_folder_id, path],
+        row_to_metadata,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            SyncError::NotFound(format!("File metadata not found: {}", path))
+        }
+        _ => SyncError::DatabaseError(format!("Failed to query file metadata: {}", e)),
+    })
+}
+
+/// 将文件标记为已同步（ignored、pinned 状态保持不变）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+/// - path: 文件路径（相对于同步文件夹根目录）
+/// - hash: 同步后的文件哈希（None 表示保留原值）
+///
+/// # 返回
+/// - Ok(()): 更新成功
+/// - Err(SyncError::NotFound): 记录不存在
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn mark_synced(
+    db: &Database,
+    sync_folder_id: i64,
+    path: &str,
+    hash: Option<&str>,
+) -> Result<()> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    let affected = conn
+        .execute(
+            "UPDATE file_metadata
+             SET status = CASE WHEN status IN (?5, ?6) THEN status ELSE ?7 END,
+                 synced_at = ?1, hash = COALESCE(?2, hash), updated_at = ?1
+             WHERE sync_folder_id = ?3 AND path = ?4 AND is_delete = 0",
+            rusqlite::params![
+                now,
+                hash,
+                sync_folder_id,
+                path,
+                file_status::IGNORED,
+                file_status::PINNED,
+                file_status::SYNCED
+            ],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to mark file synced: {}", e)))?;
+
+    if affected == 0 {
+        return Err(SyncError::NotFound(format!(
+            "File metadata not found: {}",
+            path
+        )));
+    }
+
+    Ok(())
+}
+
+/// 更新文件的内容哈希缓存
+///
+/// 只更新哈希，不改变同步状态和修改时间，调用方需确认本地文件与快照记录一致
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+/// - path: 文件路径
+/// - hash: 文件内容的 SHA-256 哈希
+///
+/// # 返回
+/// - Ok(bool): 是否存在对应记录
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn update_hash(
+    db: &Database,
+    sync_folder_id: i64,
+    path: &str,
+    hash: &str,
+) -> Result<bool> {
+    let conn = db.conn()?;
+
+    let affected = conn
+        .execute(
+            "UPDATE file_metadata SET hash = ?1
+             WHERE sync_folder_id = ?2 AND path = ?3 AND is_delete = 0",
+            rusqlite::params![hash, sync_folder_id, path],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to update file hash: {}", e)))?;
+
+    Ok(affected > 0)
+}
+
+/// 设置单个文件的同步状态
+///
+/// state 为 ignored 或 pinned 时写入该状态，文件还没有记录时新建一条未同步的记录；
+/// 已软删除的记录恢复时清除同步时间，避免过期的快照被当作上次同步的结果。
+/// state 为 None 时恢复正常同步：已同步过的记录改回 synced，从未同步的记录直接删除
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+/// - path: 文件路径（相对于同步文件夹根目录）
+/// - state: `file_status::IGNORED`、`file_status::PINNED` 或 None
+///
+/// # 返回
+/// - Ok(()): 更新成功
+/// - Err(SyncError::ValidationError): state 不是 ignored 或 pinned
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn set_sync_state(
+    db: &Database,
+    sync_folder_id: i64,
+    path: &str,
+    state: Option<&str>,
+) -> Result<()> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    let result = match state {
+        Some(state @ (file_status::IGNORED | file_status::PINNED)) => conn.execute(
+            "INSERT INTO file_metadata (
+                path, size, modified_at, sync_folder_id, is_directory, status,
+                created_at, updated_at, is_delete
+            ) VALUES (?1, 0, 0, ?2, 0, ?3, ?4, ?4, 0)
+            ON CONFLICT (sync_folder_id, path) DO UPDATE SET
+                status = excluded.status,
+                synced_at = CASE WHEN file_metadata.is_delete = 1 THEN NULL
+                                 ELSE file_metadata.synced_at END,
+                is_delete = 0,
+                updated_at = excluded.updated_at",
+            rusqlite::params![path, sync_folder_id, state, now],
+        ),
+        Some(state) => {
+            return Err(SyncError::ValidationError(format!(
+                "Invalid file sync state: {}",
+                state
+            )))
+        }
+        None => conn
+            .execute(
+                "DELETE FROM file_metadata
+                 WHERE sync_folder_id = ?1 AND path = ?2 AND synced_at IS NULL
+                   AND status IN (?3, ?4)",
+                rusqlite::params![
+                    sync_folder_id,
+                    path,
+                    file_status::IGNORED,
+                    file_status::PINNED
+                ],
+            )
+            .and_then(|_| {
+                conn.execute(
+                    "UPDATE file_metadata SET status = ?1, updated_at = ?2
+                     WHERE sync_folder_id = ?3 AND path = ?4 AND status IN (?5, ?6)",
+                    rusqlite::params![
+                        file_status::SYNCED,
+                        now,
+                        sync_folder_id,
+                        path,
+                        file_status::IGNORED,
+                        file_status::PINNED
+                    ],
+                )
+            }),
+    };
+
+    result
+        .map(|_| ())
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to set file sync state: {}", e)))
+}
+
+/// 查询同步文件夹下用户设置了同步状态（ignored、pinned）的文件
+///
+/// # 返回
+/// - Ok(Vec<FileMetadata>): 按路径排序的文件元数据
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn get_with_sync_state(db: &Database, sync_folder_id: i64) -> Result<Vec<FileMetadata>> {
+    let conn = db.conn()?;
+
+    let query = format!(
+        "SELECT {} FROM file_metadata
+         WHERE sync_folder_id = ?1 AND is_delete = 0 AND status IN (?2, ?3)
+         ORDER BY path",
+        METADATA_COLUMNS
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let files = stmt
+        .query_map(
+            rusqlite::params![sync_folder_id, file_status::IGNORED, file_status::PINNED],
+            row_to_metadata,
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query file metadata: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
+
+    Ok(files)
+}
+
+/// 软删除过期的文件元数据
+///
+/// 同步引擎在一次完整扫描前记录开始时间，扫描过程中对每个文件调用 `upsert`，
+/// 扫描结束后调用本函数：`updated_at` 早于扫描开始时间的记录即为本地已不存在的文件
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+/// - seen_before: 扫描开始时间（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(usize): 被软删除的记录数
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn delete_stale(db: &Database, sync_folder_id: i64, seen_before: i64) -> Result<usize> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "UPDATE file_metadata
+         SET is_delete = 1, updated_at = ?1
+         WHERE sync_folder_id = ?2 AND updated_at < ?3 AND is_delete = 0",
+        rusqlite::params![now, sync_folder_id, seen_before],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete stale file metadata: {}", e)))
+}
+
+/// 同步文件夹是否有已同步的文件
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - Ok(bool): 存在未删除且已同步的记录时为 true
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn has_synced(db: &Database, sync_folder_id: i64) -> Result<bool> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM file_metadata
+            WHERE sync_folder_id = ?1 AND is_delete = 0 AND synced_at IS NOT NULL
+        )",
+        rusqlite::params![sync_folder_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query file metadata: {}", e)))
+}
+
+/// 统计已同步文件的数量和远程总大小
+///
+/// 远程大小未记录时（未压缩、未加密）按本地大小计算，不包含目录
+///
+/// # 返回
+/// - Ok((u64, u64)): (文件数, 总字节数)
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn synced_usage(db: &Database, sync_folder_id: i64) -> Result<(u64, u64)> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(COALESCE(remote_size, size)), 0)
+         FROM file_metadata
+         WHERE sync_folder_id = ?1 AND is_delete = 0 AND is_directory = 0
+           AND synced_at IS NOT NULL",
+        rusqlite::params![sync_folder_id],
+        |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64)),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query file metadata: {}", e)))
+}
+
+/// 软删除文件（或目录及其下所有条目）的元数据
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+/// - path: 文件路径（相对于同步文件夹根目录）
+///
+/// # 返回
+/// - Ok(usize): 被软删除的记录数
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn mark_deleted(db: &Database, sync_folder_id: i64, path: &str) -> Result<usize> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "UPDATE file_metadata
+         SET is_delete = 1, updated_at = ?1
+         WHERE sync_folder_id = ?2 AND is_delete = 0
+           AND (path = ?3 OR substr(path, 1, length(?3) + 1) = ?3 || '/')",
+        rusqlite::params![now, sync_folder_id, path],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete file metadata: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .expect("Failed to run migration 001");
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!(
+                "../../migrations/012_file_metadata_remote_size.sql"
+            ))
+            .expect("Failed to run migration 012");
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/015_file_metadata_inode.sql"))
+            .expect("Failed to run migration 015");
+
+        (test_dir, db)
+    }
+
+    fn create_metadata(folder_id: i64, path: &str) -> FileMetadata {
+        FileMetadata {
+            id: None,
+            path: path.to_string(),
+            hash: Some("hash-1".to_string()),
+            size: 1024,
+            modified_at: 1234567890,
+            synced_at: None,
+            sync_folder_id: folder_id,
+            is_directory: false,
+            status: "pending".to_string(),
+            created_at: None,
+            updated_at: None,
+            remote_size: None,
+            inode: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_inserts_and_updates() {
+        let (test_dir, db) = create_test_db();
+
+        let mut metadata = create_metadata(1, "docs/a.txt");
+        let id1 = upsert(&db, &metadata).await.unwrap();
+
+        metadata.size = 2048;
+        metadata.hash = Some("hash-2".to_string());
+        metadata.remote_size = Some(512);
+        metadata.inode = Some(42);
+        let id2 = upsert(&db, &metadata).await.unwrap();
+
+        assert_eq!(id1, id2);
+        let fetched = get_by_path(&db, 1, "docs/a.txt").await.unwrap();
+        assert_eq!(fetched.size, 2048);
+        assert_eq!(fetched.hash.as_deref(), Some("hash-2"));
+        assert_eq!(fetched.remote_size, Some(512));
+        assert_eq!(fetched.inode, Some(42));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_batch() {
+        let (test_dir, db) = create_test_db();
+
+        upsert(&db, &create_metadata(1, "a.txt")).await.unwrap();
+        set_sync_state(&db, 1, "a.txt", Some(file_status::PINNED))
+            .await
+            .unwrap();
+
+        let rows: Vec<FileMetadata> = (0..2500)
+            .map(|i| {
+                let mut metadata = create_metadata(1, &format!("dir/{:04}.txt", i));
+                metadata.is_directory = i == 0;
+                metadata
+            })
+            .chain(std::iter::once(create_metadata(1, "a.txt")))
+            .collect();
+        upsert_batch(&db, &rows).await.unwrap();
+        upsert_batch(&db, &[]).await.unwrap();
+
+        let all = get_by_folder(&db, 1).await.unwrap();
+        assert_eq!(all.len(), 2501);
+        assert!(all
+            .iter()
+            .any(|m| m.path == "dir/0000.txt" && m.is_directory));
+        // 与 upsert 相同，保留用户设置的状态
+        let pinned = get_by_path(&db, 1, "a.txt").await.unwrap();
+        assert_eq!(pinned.status, file_status::PINNED);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_folder_filters_by_folder() {
+        let (test_dir, db) = create_test_db();
+
+        upsert(&db, &create_metadata(1, "b.txt")).await.unwrap();
+        upsert(&db, &create_metadata(1, "a.txt")).await.unwrap();
+        upsert(&db, &create_metadata(2, "c.txt")).await.unwrap();
+
+        let files = get_by_folder(&db, 1).await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.txt");
+        assert_eq!(files[1].path, "b.txt");
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_mark_synced() {
+        let (test_dir, db) = create_test_db();
+
+        upsert(&db, &create_metadata(1, "a.txt")).await.unwrap();
+        assert!(!has_synced(&db, 1).await.unwrap());
+        mark_synced(&db, 1, "a.txt", Some("synced-hash"))
+            .await
+            .unwrap();
+        assert!(has_synced(&db, 1).await.unwrap());
+
+        let mut compressed = create_metadata(1, "b.txt");
+        compressed.remote_size = Some(100);
+        upsert(&db, &compressed).await.unwrap();
+        mark_synced(&db, 1, "b.txt", None).await.unwrap();
+        upsert(&db, &create_metadata(1, "pending.txt"))
+            .await
+            .unwrap();
+        assert_eq!(synced_usage(&db, 1).await.unwrap(), (2, 1024 + 100));
+
+        let fetched = get_by_path(&db, 1, "a.txt").await.unwrap();
+        assert_eq!(fetched.status, "synced");
+        assert!(fetched.synced_at.is_some());
+        assert_eq!(fetched.hash.as_deref(), Some("synced-hash"));
+
+        let missing = mark_synced(&db, 1, "missing.txt", None).await;
+        assert!(matches!(missing, Err(SyncError::NotFound(_))));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_update_hash() {
+        let (test_dir, db) = create_test_db();
+
+        upsert(&db, &create_metadata(1, "a.txt")).await.unwrap();
+        assert!(update_hash(&db, 1, "a.txt", "hash-2").await.unwrap());
+        assert!(!update_hash(&db, 1, "missing.txt", "hash-2").await.unwrap());
+
+        let fetched = get_by_path(&db, 1, "a.txt").await.unwrap();
+        assert_eq!(fetched.hash.as_deref(), Some("hash-2"));
+        assert_eq!(fetched.status, "pending");
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_set_sync_state() {
+        let (test_dir, db) = create_test_db();
+
+        // 已同步的文件设置为 pinned 后，重新写入快照不会覆盖该状态
+        upsert(&db, &create_metadata(1, "a.txt")).await.unwrap();
+        mark_synced(&db, 1, "a.txt", None).await.unwrap();
+        set_sync_state(&db, 1, "a.txt", Some(file_status::PINNED))
+            .await
+            .unwrap();
+        upsert(&db, &create_metadata(1, "a.txt")).await.unwrap();
+        mark_synced(&db, 1, "a.txt", None).await.unwrap();
+        assert_eq!(
+            get_by_path(&db, 1, "a.txt").await.unwrap().status,
+            file_status::PINNED
+        );
+
+        // 没有记录的文件新建一条未同步的记录
+        set_sync_state(&db, 1, "b.txt", Some(file_status::IGNORED))
+            .await
+            .unwrap();
+        let ignored = get_by_path(&db, 1, "b.txt").await.unwrap();
+        assert_eq!(ignored.status, file_status::IGNORED);
+        assert!(ignored.synced_at.is_none());
+
+        let paths: Vec<String> = get_with_sync_state(&db, 1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.path)
+            .collect();
+        assert_eq!(paths, vec!["a.txt", "b.txt"]);
+
+        // 恢复正常同步
+        set_sync_state(&db, 1, "a.txt", None).await.unwrap();
+        set_sync_state(&db, 1, "b.txt", None).await.unwrap();
+        assert_eq!(
+            get_by_path(&db, 1, "a.txt").await.unwrap().status,
+            file_status::SYNCED
+        );
+        assert!(matches!(
+            get_by_path(&db, 1, "b.txt").await,
+            Err(SyncError::NotFound(_))
+        ));
+
+        let invalid = set_sync_state(&db, 1, "a.txt", Some("bogus")).await;
+        assert!(matches!(invalid, Err(SyncError::ValidationError(_))));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_delete_stale() {
+        let (test_dir, db) = create_test_db();
+
+        upsert(&db, &create_metadata(1, "old.txt")).await.unwrap();
+        // 模拟旧记录：将 updated_at 改到过去
+        db.conn()
+            .unwrap()
+            .execute(
+                "UPDATE file_metadata SET updated_at = 100 WHERE path = 'old.txt'",
+                [],
+            )
+            .unwrap();
+        upsert(&db, &create_metadata(1, "new.txt")).await.unwrap();
+
+        let removed = delete_stale(&db, 1, 1000).await.unwrap();
+        assert_eq!(removed, 1);
+
+        let files = get_by_folder(&db, 1).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "new.txt");
+
+        // 重新 upsert 会恢复软删除的记录
+        upsert(&db, &create_metadata(1, "old.txt")).await.unwrap();
+        assert_eq!(get_by_folder(&db, 1).await.unwrap().len(), 2);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_mark_deleted_includes_children() {
+        let (test_dir, db) = create_test_db();
+
+        upsert(&db, &create_metadata(1, "photos")).await.unwrap();
+        upsert(&db, &create_metadata(1, "photos/a.jpg"))
+            .await
+            .unwrap();
+        upsert(&db, &create_metadata(1, "photos-old/b.jpg"))
+            .await
+            .unwrap();
+
+        let removed = mark_deleted(&db, 1, "photos").await.unwrap();
+        assert_eq!(removed, 2);
+
+        let files = get_by_folder(&db, 1).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "photos-old/b.jpg");
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,115 @@
+/// 文件历史版本数据库操作模块
+///
+/// 记录每个文件保存过的历史版本，版本内容由 `sync::versions` 按哈希存储
+use crate::database::{Database, FileVersion};
+use crate::{Result, SyncError};
+use rusqlite::OptionalExtension;
+
+/// file_versions 表的查询列（顺序与 `row_to_version` 对应）
+const VERSION_COLUMNS: &str = "id, folder_id, file_path, hash, size, created_at";
+
+/// 将查询结果行转换为文件版本
+fn row_to_version(row: &rusqlite::Row<'_>) -> rusqlite::Result<FileVersion> {
+    Ok(FileVersion {
+        id: row.get(0)?,
+        folder_id: row.get(1)?,
+        file_path: row.get(2)?,
+        hash: row.get(3)?,
+        size: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// 插入版本记录
+///
+/// # 返回
+/// - Ok(i64): 新记录 ID
+pub async fn insert(
+    db: &Database,
+    folder_id: &str,
+    file_path: &str,
+    hash: &str,
+    size: i64,
+) -> Result<i64> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO file_versions (folder_id, file_path, hash, size, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![folder_id, file_path, hash, size, now],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to insert file version: {}", e)))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// 获取文件的所有版本（最新的在前）
+pub async fn list(db: &Database, folder_id: &str, file_path: &str) -> Result<Vec<FileVersion>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM file_versions
+             WHERE folder_id = ?1 AND file_path = ?2
+             ORDER BY created_at DESC, id DESC",
+            VERSION_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let versions = stmt
+        .query_map(rusqlite::params![folder_id, file_path], row_to_version)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query file versions: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read file version: {}", e)))?;
+
+    Ok(versions)
+}
+
+/// 根据 ID 获取版本
+///
+/// # 返回
+/// - Ok(FileVersion): 找到版本
+/// - Err(SyncError::NotFound): 版本不存在
+pub async fn get_by_id(db: &Database, id: i64) -> Result<FileVersion> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM file_versions WHERE id = ?1",
+            VERSION_COLUMNS
+        ),
+        rusqlite::params![id],
+        row_to_version,
+    )
+    .optional()
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query file version: {}", e)))?
+    .ok_or_else(|| SyncError::NotFound(format!("File version not found: {}", id)))
+}
+
+/// 删除版本记录
+pub async fn delete(db: &Database, id: i64) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM file_versions WHERE id = ?1",
+        rusqlite::params![id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete file version: {}", e)))?;
+
+    Ok(())
+}
+
+/// 统计引用指定哈希的版本数量
+///
+/// 用于判断内容文件是否还能被删除
+pub async fn count_by_hash(db: &Database, hash: &str) -> Result<i64> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT COUNT(*) FROM file_versions WHERE hash = ?1",
+        rusqlite::params![hash],
+        |row| row.get(0),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to count file versions: {}", e)))
+}
@@ -0,0 +1,55 @@
+/// 同步文件夹数值 ID 映射模块
+///
+/// 同步文件夹配置使用 UUID，而 file_metadata、sync_logs、sync_sessions 使用整数
+/// sync_folder_id。通过 sync_folder_keys 表为每个 UUID 分配稳定的整数 ID。
+use crate::database::Database;
+use crate::{Result, SyncError};
+
+/// 获取同步文件夹对应的整数 ID（不存在时自动分配）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID（SyncFolderConfig.id）
+///
+/// # 返回
+/// - Ok(i64): 整数 sync_folder_id
+pub async fn resolve(db: &Database, folder_id: &str) -> Result<i64> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "INSERT INTO sync_folder_keys (folder_id) VALUES (?1)
+         ON CONFLICT(folder_id) DO UPDATE SET folder_id = excluded.folder_id
+         RETURNING id",
+        rusqlite::params![folder_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to resolve sync folder key: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_resolve_is_stable() {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/008_sync_folder_keys.sql"))
+            .expect("Failed to run migration 008");
+
+        let a1 = resolve(&db, "folder-a").await.unwrap();
+        let b = resolve(&db, "folder-b").await.unwrap();
+        let a2 = resolve(&db, "folder-a").await.unwrap();
+
+        assert_eq!(a1, a2);
+        assert_ne!(a1, b);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
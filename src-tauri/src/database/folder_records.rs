@@ -0,0 +1,224 @@
+/// 同步文件夹记录数据库操作模块
+///
+/// 同步文件夹以配置文件为准，sync_folders 表只是其镜像，
+/// 供 transfers、conflicts 表建立外键，并随服务器删除级联清理
+use crate::config::SyncFolderConfig;
+use crate::database::{Database, SyncFolderRecord};
+use crate::{Result, SyncError};
+
+/// sync_folders 表的查询列（顺序与 `row_to_record` 对应）
+const FOLDER_COLUMNS: &str = "id, name, local_path, remote_path, server_id, sync_direction, \
+     conflict_resolution, auto_sync, created_at, updated_at";
+
+/// 将查询结果行转换为同步文件夹记录
+fn row_to_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<SyncFolderRecord> {
+    Ok(SyncFolderRecord {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        local_path: row.get(2)?,
+        remote_path: row.get(3)?,
+        server_id: row.get(4)?,
+        sync_direction: row.get(5)?,
+        conflict_resolution: row.get(6)?,
+        auto_sync: row.get::<_, i32>(7)? != 0,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// 写入同步文件夹记录（已存在时更新）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder: 同步文件夹配置
+pub async fn upsert(db: &Database, folder: &SyncFolderConfig) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO sync_folders (
+             id, name, local_path, remote_path, server_id,
+             sync_direction, conflict_resolution, auto_sync
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(id) DO UPDATE SET
+             name = excluded.name,
+             local_path = excluded.local_path,
+             remote_path = excluded.remote_path,
+             server_id = excluded.server_id,
+             sync_direction = excluded.sync_direction,
+             conflict_resolution = excluded.conflict_resolution,
+             auto_sync = excluded.auto_sync,
+             updated_at = STRFTIME('%s', 'now')",
+        rusqlite::params![
+            folder.id,
+            folder.name,
+            folder.local_path.to_string_lossy(),
+            folder.remote_path,
+            folder.server_id,
+            folder.sync_direction,
+            folder.conflict_resolution,
+            folder.auto_sync as i32,
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to save sync folder: {}", e)))?;
+
+    Ok(())
+}
+
+/// 获取同步文件夹记录
+///
+/// # 返回
+/// - Ok(Some(SyncFolderRecord)): 记录存在
+/// - Ok(None): 记录不存在
+pub async fn get(db: &Database, folder_id: &str) -> Result<Option<SyncFolderRecord>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM sync_folders WHERE id = ?1 LIMIT 1",
+            FOLDER_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let mut rows = stmt
+        .query_map(rusqlite::params![folder_id], row_to_record)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync folder: {}", e)))?;
+
+    rows.next()
+        .transpose()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read sync folder: {}", e)))
+}
+
+/// 获取所有同步文件夹记录（按创建顺序）
+pub async fn list(db: &Database) -> Result<Vec<SyncFolderRecord>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM sync_folders ORDER BY created_at, id",
+            FOLDER_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let records = stmt
+        .query_map([], row_to_record)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync folders: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read sync folder: {}", e)))?;
+
+    Ok(records)
+}
+
+/// 删除同步文件夹记录（关联的传输与冲突记录级联删除）
+pub async fn delete(db: &Database, folder_id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM sync_folders WHERE id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete sync folder: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库，并插入一个服务器
+    pub(crate) fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        {
+            let conn = db.conn().unwrap();
+            conn.execute_batch(include_str!("../../migrations/002_webdav_servers.sql"))
+                .expect("Failed to run migration 002");
+            conn.execute_batch(include_str!(
+                "../../migrations/011_sync_folders_transfers_conflicts.sql"
+            ))
+            .expect("Failed to run migration 011");
+            conn.execute(
+                "INSERT INTO webdav_servers (id, name, url, username)
+                 VALUES ('server-1', 'Test', 'https://dav.example.com', 'user')",
+                [],
+            )
+            .unwrap();
+        }
+
+        (test_dir, db)
+    }
+
+    pub(crate) fn create_folder(id: &str) -> SyncFolderConfig {
+        SyncFolderConfig {
+            id: id.to_string(),
+            name: "Documents".to_string(),
+            local_path: PathBuf::from("/home/user/Documents"),
+            remote_path: "/Documents".to_string(),
+            server_id: "server-1".to_string(),
+            sync_direction: "bidirectional".to_string(),
+            sync_interval: 30,
+            auto_sync: true,
+            ignore_patterns: vec![],
+            conflict_resolution: "newer-wins".to_string(),
+            selective_exclusions: vec![],
+            symlink_policy: "skip".to_string(),
+            encryption: Default::default(),
+            compression: Default::default(),
+            max_delete_ratio: 0.2,
+            first_sync_strategy: "merge-keep-both".to_string(),
+            max_file_size: None,
+            include_extensions: vec![],
+            exclude_extensions: vec![],
+            ignore_system_files: true,
+            sync_policy: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_get_delete() {
+        let (test_dir, db) = create_test_db();
+
+        let mut folder = create_folder("folder-1");
+        upsert(&db, &folder).await.unwrap();
+
+        folder.remote_path = "/Backup".to_string();
+        folder.auto_sync = false;
+        upsert(&db, &folder).await.unwrap();
+
+        let record = get(&db, "folder-1").await.unwrap().unwrap();
+        assert_eq!(record.remote_path, "/Backup");
+        assert_eq!(record.local_path, "/home/user/Documents");
+        assert!(!record.auto_sync);
+        assert_eq!(list(&db).await.unwrap().len(), 1);
+
+        delete(&db, "folder-1").await.unwrap();
+        assert!(get(&db, "folder-1").await.unwrap().is_none());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_server_foreign_key() {
+        let (test_dir, db) = create_test_db();
+
+        let mut folder = create_folder("folder-1");
+        folder.server_id = "missing".to_string();
+        assert!(upsert(&db, &folder).await.is_err());
+
+        upsert(&db, &create_folder("folder-2")).await.unwrap();
+        db.conn()
+            .unwrap()
+            .execute("DELETE FROM webdav_servers WHERE id = 'server-1'", [])
+            .unwrap();
+        assert!(list(&db).await.unwrap().is_empty());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,240 @@
+/// 同步日志批量写入
+///
+/// 同步过程中每个文件都会产生一条日志，大型同步逐条写入时每条都要获取连接并单独提交。
+/// `SyncLogWriter` 通过有界通道把日志交给后台任务，后台任务累积到 `SYNC_LOG_BATCH_SIZE` 条
+/// 或距第一条未写入的日志超过 `SYNC_LOG_FLUSH_INTERVAL_MS` 时在一个事务中写入（见 `sync_logs::insert_batch`）。
+///
+/// 通道已满时发送方等待，写入速度跟不上时同步随之放慢而不会无限占用内存。
+/// 会话结束时调用 `flush` 等待已发送的日志全部写入，并取得该文件夹期间的写入错误。
+/// 写入错误按同步文件夹记录（每个文件夹同一时间只有一个同步会话），
+/// 一个会话的批次写入失败不会影响同时结束的其他会话
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+use crate::constants::{
+    SYNC_LOG_BATCH_SIZE, SYNC_LOG_CHANNEL_CAPACITY, SYNC_LOG_FLUSH_INTERVAL_MS,
+};
+use crate::database::{sync_logs, Database, SyncLog};
+use crate::{Result, SyncError};
+
+/// 发送给后台任务的消息
+#[derive(Debug)]
+enum LogMessage {
+    /// 待写入的日志
+    Row(SyncLog),
+    /// 立即写入缓冲的日志，完成后返回该文件夹上次 flush 以来的第一个写入错误
+    Flush(i64, oneshot::Sender<Result<()>>),
+}
+
+/// 同步日志写入器
+///
+/// 作为 Tauri State 管理，可以廉价克隆
+#[derive(Debug, Clone)]
+pub struct SyncLogWriter {
+    tx: mpsc::Sender<LogMessage>,
+}
+
+impl SyncLogWriter {
+    /// 启动后台写入任务（使用应用管理的共享数据库连接）
+    pub fn spawn(app: AppHandle) -> Self {
+        let (writer, rx) = Self::channel();
+        tauri::async_runtime::spawn(async move {
+            let db = app.state::<Database>();
+            run(&db, rx).await;
+        });
+        writer
+    }
+
+    /// 创建写入器与后台任务使用的接收端
+    fn channel() -> (Self, mpsc::Receiver<LogMessage>) {
+        let (tx, rx) = mpsc::channel(SYNC_LOG_CHANNEL_CAPACITY);
+        (Self { tx }, rx)
+    }
+
+    /// 提交一条日志（写入在后台批量进行）
+    ///
+    /// # 返回
+    /// - Err(SyncError::Unknown): 后台任务已停止
+    pub async fn write(&self, log: SyncLog) -> Result<()> {
+        self.tx
+            .send(LogMessage::Row(log))
+            .await
+            .map_err(|_| stopped())
+    }
+
+    /// 等待已提交的日志全部写入
+    ///
+    /// # 参数
+    /// - sync_folder_id: 同步文件夹数据库 ID，只返回该文件夹的日志的写入错误
+    ///
+    /// # 返回
+    /// - Ok(()): 全部写入
+    /// - Err(SyncError::DatabaseError): 上次 flush 以来该文件夹的日志有批次写入失败
+    /// - Err(SyncError::Unknown): 后台任务已停止
+    pub async fn flush(&self, sync_folder_id: i64) -> Result<()> {
+        let (done, result) = oneshot::channel();
+        self.tx
+            .send(LogMessage::Flush(sync_folder_id, done))
+            .await
+            .map_err(|_| stopped())?;
+        result.await.map_err(|_| stopped())?
+    }
+}
+
+fn stopped() -> SyncError {
+    SyncError::Unknown("Sync log writer stopped".to_string())
+}
+
+/// 后台写入循环，所有写入器都已释放时写入剩余的日志后结束
+async fn run(db: &Database, mut rx: mpsc::Receiver<LogMessage>) {
+    let interval = Duration::from_millis(SYNC_LOG_FLUSH_INTERVAL_MS);
+    let mut buffer: Vec<SyncLog> = Vec::with_capacity(SYNC_LOG_BATCH_SIZE);
+    let mut deadline = Instant::now();
+    let mut errors: HashMap<i64, SyncError> = HashMap::new();
+
+    loop {
+        let message = if buffer.is_empty() {
+            rx.recv().await
+        } else {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(message) => message,
+                Err(_) => {
+                    write_batch(db, &mut buffer, &mut errors).await;
+                    continue;
+                }
+            }
+        };
+
+        match message {
+            Some(LogMessage::Row(log)) => {
+                if buffer.is_empty() {
+                    deadline = Instant::now() + interval;
+                }
+                buffer.push(log);
+                if buffer.len() >= SYNC_LOG_BATCH_SIZE {
+                    write_batch(db, &mut buffer, &mut errors).await;
+                }
+            }
+            Some(LogMessage::Flush(sync_folder_id, done)) => {
+                write_batch(db, &mut buffer, &mut errors).await;
+                let _ = done.send(errors.remove(&sync_folder_id).map_or(Ok(()), Err));
+            }
+            None => {
+                write_batch(db, &mut buffer, &mut errors).await;
+                break;
+            }
+        }
+    }
+}
+
+/// 写入缓冲的日志，失败时记录警告，并为批次中的每个文件夹保留第一个错误
+async fn write_batch(
+    db: &Database,
+    buffer: &mut Vec<SyncLog>,
+    errors: &mut HashMap<i64, SyncError>,
+) {
+    if let Err(e) = sync_logs::insert_batch(db, buffer).await {
+        tracing::warn!(count = buffer.len(), error = %e, "批量写入同步日志失败");
+        let message = match &e {
+            SyncError::DatabaseError(message) => message.clone(),
+            other => other.to_string(),
+        };
+        let folders: HashSet<i64> = buffer.iter().map(|log| log.sync_folder_id).collect();
+        for sync_folder_id in folders {
+            errors
+                .entry(sync_folder_id)
+                .or_insert_with(|| SyncError::DatabaseError(message.clone()));
+        }
+    }
+    buffer.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::QueryFilter;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn log(i: i64) -> SyncLog {
+        folder_log(1, i)
+    }
+
+    fn folder_log(sync_folder_id: i64, i: i64) -> SyncLog {
+        SyncLog {
+            id: None,
+            sync_folder_id,
+            file_path: format!("file-{}.txt", i),
+            action: "upload".to_string(),
+            status: "success".to_string(),
+            error_message: None,
+            file_size: Some(10),
+            duration_ms: Some(5),
+            bytes_per_sec: None,
+            created_at: Some(i),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writer_batches_and_flushes() {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/021_sync_log_speed.sql"))
+            .unwrap();
+
+        let (writer, rx) = SyncLogWriter::channel();
+        let count = SYNC_LOG_BATCH_SIZE as i64 + 5;
+        let producer = async {
+            for i in 0..count {
+                writer.write(log(i)).await.unwrap();
+            }
+            writer.flush(1).await.unwrap();
+
+            let page = sync_logs::query(&db, &QueryFilter::default())
+                .await
+                .unwrap();
+            assert_eq!(page.total, count);
+            drop(writer);
+        };
+        tokio::join!(run(&db, rx), producer);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_flush_reports_errors_per_folder() {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        // 没有建表，所有批次都写入失败
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let (writer, rx) = SyncLogWriter::channel();
+        let producer = async {
+            writer.write(folder_log(2, 1)).await.unwrap();
+            assert!(matches!(
+                writer.flush(2).await,
+                Err(SyncError::DatabaseError(_))
+            ));
+            // 其他文件夹的会话不受影响，错误只返回一次
+            assert!(writer.flush(1).await.is_ok());
+            assert!(writer.flush(2).await.is_ok());
+            drop(writer);
+        };
+        tokio::join!(run(&db, rx), producer);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,182 @@
+/// 数据库维护模块
+///
+/// sync_logs 和 sync_sessions 会随同步次数不断增长，维护任务按保留天数删除旧记录，
+/// 再执行 VACUUM 回收空闲页，使数据库文件随之缩小
+use crate::constants::session_status;
+use crate::database::{Database, MaintenanceReport};
+use crate::{Result, SyncError};
+
+/// 获取数据库大小（字节）
+///
+/// 按 `page_count * page_size` 计算，WAL 中尚未检查点的页不计入
+pub fn database_size(conn: &rusqlite::Connection) -> Result<i64> {
+    conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to get database size: {}", e)))
+}
+
+/// 删除早于指定时间的同步日志和已结束的同步会话
+///
+/// # 参数
+/// - before: 截止时间（Unix 时间戳，秒），早于该时间的记录被删除
+///
+/// # 返回
+/// - Ok((usize, usize)): 删除的日志数和会话数
+pub async fn prune_history(db: &Database, before: i64) -> Result<(usize, usize)> {
+    let conn = db.conn()?;
+
+    let logs = conn
+        .execute(
+            "DELETE FROM sync_logs WHERE created_at < ?1",
+            rusqlite::params![before],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prune sync logs: {}", e)))?;
+
+    // 仍在运行的会话保留，避免结束时找不到记录
+    let sessions = conn
+        .execute(
+            "DELETE FROM sync_sessions WHERE started_at < ?1 AND status != ?2",
+            rusqlite::params![before, session_status::RUNNING],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prune sync sessions: {}", e)))?;
+
+    Ok((logs, sessions))
+}
+
+/// 回收数据库空闲页
+///
+/// 先执行 VACUUM，再将 WAL 检查点写回主文件并截断，使磁盘占用真正减少
+pub async fn vacuum(db: &Database) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute_batch("VACUUM")
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to vacuum database: {}", e)))?;
+
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to checkpoint database: {}", e)))?;
+
+    Ok(())
+}
+
+/// 执行一次完整维护：清理旧记录并回收空间
+///
+/// # 参数
+/// - retention_days: 同步历史保留天数（0 表示永久保留，只执行 VACUUM）
+///
+/// # 返回
+/// - Ok(MaintenanceReport): 删除的记录数与回收的空间
+pub async fn run(db: &Database, retention_days: u32) -> Result<MaintenanceReport> {
+    let size_before_bytes = database_size(&*db.conn()?)?;
+
+    let (logs_deleted, sessions_deleted) = if retention_days > 0 {
+        let before = chrono::Utc::now().timestamp() - i64::from(retention_days) * 24 * 60 * 60;
+        prune_history(db, before).await?
+    } else {
+        (0, 0)
+    };
+
+    vacuum(db).await?;
+    let size_after_bytes = database_size(&*db.conn()?)?;
+
+    Ok(MaintenanceReport {
+        logs_deleted,
+        sessions_deleted,
+        size_before_bytes,
+        size_after_bytes,
+        reclaimed_bytes: (size_before_bytes - size_after_bytes).max(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .expect("Failed to run migration 001");
+
+        (test_dir, db)
+    }
+
+    fn insert_log(db: &Database, created_at: i64) {
+        db.conn()
+            .unwrap()
+            .execute(
+                "INSERT INTO sync_logs (sync_folder_id, file_path, action, status, error_message, created_at)
+                 VALUES (1, 'a.txt', 'upload', 'success', ?1, ?2)",
+                rusqlite::params!["x".repeat(4096), created_at],
+            )
+            .unwrap();
+    }
+
+    fn insert_session(db: &Database, status: &str, started_at: i64) {
+        db.conn()
+            .unwrap()
+            .execute(
+                "INSERT INTO sync_sessions (sync_folder_id, status, started_at) VALUES (1, ?1, ?2)",
+                rusqlite::params![status, started_at],
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prune_history() {
+        let (test_dir, db) = create_test_db();
+
+        insert_log(&db, 100);
+        insert_log(&db, 200);
+        insert_log(&db, 300);
+        insert_session(&db, "completed", 100);
+        insert_session(&db, "running", 100);
+        insert_session(&db, "failed", 300);
+
+        assert_eq!(prune_history(&db, 250).await.unwrap(), (2, 1));
+
+        let conn = db.conn().unwrap();
+        let sessions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sync_sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(sessions, 2);
+
+        drop(conn);
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_reclaims_space() {
+        let (test_dir, db) = create_test_db();
+
+        for _ in 0..200 {
+            insert_log(&db, 100);
+        }
+        insert_log(&db, chrono::Utc::now().timestamp());
+
+        let report = run(&db, 30).await.unwrap();
+        assert_eq!(report.logs_deleted, 200);
+        assert!(report.reclaimed_bytes > 0);
+        assert_eq!(
+            report.reclaimed_bytes,
+            report.size_before_bytes - report.size_after_bytes
+        );
+
+        // 保留天数为 0 时不删除记录
+        let report = run(&db, 0).await.unwrap();
+        assert_eq!(report.logs_deleted, 0);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,274 @@
+/// 数据库迁移
+///
+/// 表结构迁移原本只由前端 plugin-sql 在加载数据库时执行，无界面模式下不会加载前端，
+/// 后端的共享连接可能读写尚不存在的表。后端打开应用数据库时先执行尚未应用的迁移：
+/// - 两侧共用 `MIGRATIONS`（plugin-sql 的迁移列表也由它生成）
+/// - 已应用的迁移按 plugin-sql（sqlx）的格式记录在 `_sqlx_migrations` 表中，
+///   校验和为脚本的 SHA-384，之后前端加载数据库时视为已应用，不会重复执行
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha384};
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::{Result, SyncError};
+
+/// 一个迁移脚本
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// 版本号（按顺序执行）
+    pub version: i64,
+    /// 说明
+    pub description: &'static str,
+    /// SQL 脚本
+    pub sql: &'static str,
+}
+
+/// 全部迁移（按版本号排序）
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial database schema",
+        sql: include_str!("../../migrations/001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "add webdav_servers table",
+        sql: include_str!("../../migrations/002_webdav_servers.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "add auth_type to webdav_servers",
+        sql: include_str!("../../migrations/003_server_auth_type.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "add tls trust settings to webdav_servers",
+        sql: include_str!("../../migrations/004_server_tls_trust.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "add remote_sync_tokens table",
+        sql: include_str!("../../migrations/005_sync_tokens.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "add trash_items table",
+        sql: include_str!("../../migrations/006_trash_items.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "add file_versions table",
+        sql: include_str!("../../migrations/007_file_versions.sql"),
+    },
+    Migration {
+        version: 8,
+        description: "add sync_folder_keys table",
+        sql: include_str!("../../migrations/008_sync_folder_keys.sql"),
+    },
+    Migration {
+        version: 9,
+        description: "add server_health table",
+        sql: include_str!("../../migrations/009_server_health.sql"),
+    },
+    Migration {
+        version: 10,
+        description: "add remote_locks table",
+        sql: include_str!("../../migrations/010_remote_locks.sql"),
+    },
+    Migration {
+        version: 11,
+        description: "add sync_folders, transfers and conflicts tables",
+        sql: include_str!("../../migrations/011_sync_folders_transfers_conflicts.sql"),
+    },
+    Migration {
+        version: 12,
+        description: "add remote_size to file_metadata",
+        sql: include_str!("../../migrations/012_file_metadata_remote_size.sql"),
+    },
+    Migration {
+        version: 13,
+        description: "add deleted_at to webdav_servers",
+        sql: include_str!("../../migrations/013_server_archive.sql"),
+    },
+    Migration {
+        version: 14,
+        description: "add remote_snapshots table",
+        sql: include_str!("../../migrations/014_remote_snapshots.sql"),
+    },
+    Migration {
+        version: 15,
+        description: "add inode to file_metadata",
+        sql: include_str!("../../migrations/015_file_metadata_inode.sql"),
+    },
+    Migration {
+        version: 16,
+        description: "add remote_cache table",
+        sql: include_str!("../../migrations/016_remote_cache.sql"),
+    },
+    Migration {
+        version: 17,
+        description: "add custom_headers to webdav_servers",
+        sql: include_str!("../../migrations/017_server_custom_headers.sql"),
+    },
+    Migration {
+        version: 18,
+        description: "add network options to webdav_servers",
+        sql: include_str!("../../migrations/018_server_network_options.sql"),
+    },
+    Migration {
+        version: 19,
+        description: "add propfind_dialect to webdav_servers",
+        sql: include_str!("../../migrations/019_server_propfind_dialect.sql"),
+    },
+    Migration {
+        version: 20,
+        description: "add connect and read timeouts to webdav_servers",
+        sql: include_str!("../../migrations/020_server_timeouts.sql"),
+    },
+    Migration {
+        version: 21,
+        description: "add average transfer speed to sync_logs",
+        sql: include_str!("../../migrations/021_sync_log_speed.sql"),
+    },
+    Migration {
+        version: 22,
+        description: "create name_mappings table",
+        sql: include_str!("../../migrations/022_name_mappings.sql"),
+    },
+];
+
+/// 执行尚未应用的迁移
+///
+/// 每个迁移与其记录在同一个事务中写入
+///
+/// # 返回
+/// - Ok(usize): 本次执行的迁移数
+/// - Err(SyncError::DatabaseError): 有未完成的迁移记录，或执行迁移失败
+pub fn run(conn: &Connection) -> Result<usize> {
+    let db_err =
+        |e: rusqlite::Error| SyncError::DatabaseError(format!("Failed to migrate database: {}", e));
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+            version BIGINT PRIMARY KEY,
+            description TEXT NOT NULL,
+            installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            success BOOLEAN NOT NULL,
+            checksum BLOB NOT NULL,
+            execution_time BIGINT NOT NULL
+        );",
+    )
+    .map_err(db_err)?;
+
+    let mut applied = HashSet::new();
+    {
+        let mut stmt = conn
+            .prepare("SELECT version, success FROM _sqlx_migrations")
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?))
+            })
+            .map_err(db_err)?;
+        for row in rows {
+            let (version, success) = row.map_err(db_err)?;
+            if !success {
+                return Err(SyncError::DatabaseError(format!(
+                    "Migration {} is partially applied",
+                    version
+                )));
+            }
+            applied.insert(version);
+        }
+    }
+
+    let mut count = 0;
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+        let started = Instant::now();
+        let tx = conn.unchecked_transaction().map_err(db_err)?;
+        tx.execute_batch(migration.sql).map_err(db_err)?;
+        tx.execute(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (?1, ?2, TRUE, ?3, ?4)",
+            params![
+                migration.version,
+                migration.description,
+                checksum(migration.sql),
+                started.elapsed().as_nanos() as i64
+            ],
+        )
+        .map_err(db_err)?;
+        tx.commit().map_err(db_err)?;
+        tracing::info!(
+            version = migration.version,
+            description = migration.description,
+            "已执行数据库迁移"
+        );
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// 迁移脚本的校验和（与 sqlx 相同）
+fn checksum(sql: &str) -> Vec<u8> {
+    Sha384::digest(sql.as_bytes()).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_applies_pending_migrations_once() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        // 模拟前端已执行第一个迁移
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE _sqlx_migrations (
+                version BIGINT PRIMARY KEY,
+                description TEXT NOT NULL,
+                installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                success BOOLEAN NOT NULL,
+                checksum BLOB NOT NULL,
+                execution_time BIGINT NOT NULL
+            );",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (1, 'initial database schema', TRUE, ?1, 0)",
+            params![checksum(MIGRATIONS[0].sql)],
+        )
+        .unwrap();
+
+        assert_eq!(run(&conn).unwrap(), MIGRATIONS.len() - 1);
+        assert_eq!(run(&conn).unwrap(), 0);
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM name_mappings", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+        // 校验和与 sqlx 计算的一致（SHA-384）
+        let stored: Vec<u8> = conn
+            .query_row(
+                "SELECT checksum FROM _sqlx_migrations WHERE version = 22",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored.len(), 48);
+        assert_eq!(stored, checksum(MIGRATIONS[21].sql));
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as i64 + 1);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+/// 数据库模块
+///
+/// 提供数据库表对应的数据结构，以及后端共享的数据库连接
+///
+/// 模块结构:
+/// - types: 数据库表对应的数据结构
+/// - connection: 共享数据库连接（作为 Tauri State 管理）
+/// - conflicts: conflicts 表操作（等待用户处理的同步冲突）
+/// - file_metadata: file_metadata 表操作
+/// - file_versions: file_versions 表操作（文件历史版本）
+/// - folder_keys: sync_folder_keys 表操作（同步文件夹 UUID 与整数 ID 映射）
+/// - folder_records: sync_folders 表操作（配置中同步文件夹的镜像）
+/// - log_writer: 同步日志批量写入（通过通道交给后台任务，在事务中成批写入）
+/// - maintenance: 数据库维护（清理旧的同步历史、VACUUM）
+/// - migrations: 表结构迁移（与前端 plugin-sql 共用，后端打开应用数据库时执行尚未应用的迁移）
+/// - name_mappings: name_mappings 表操作（本地清理后的文件名与原始远程路径的映射）
+/// - remote_cache: remote_cache 表操作（远程目录树缓存）
+/// - remote_locks: remote_locks 表操作（上传时持有的远程文件锁）
+/// - remote_snapshots: remote_snapshots 表操作（远程变更轮询的目录指纹）
+/// - server_health: server_health 表操作（服务器健康检查历史）
+/// - stats: 数据库统计信息
+/// - sync_logs: sync_logs 表操作
+/// - sync_sessions: sync_sessions 表操作
+/// - sync_statistics: 同步统计（按日汇总的传输量、会话耗时与错误率）
+/// - sync_tokens: remote_sync_tokens 表操作（增量同步令牌）
+/// - transfers: transfers 表操作（传输队列与断点续传信息）
+/// - trash: trash_items 表操作（回收站条目）
+pub mod conflicts;
+pub mod connection;
+pub mod file_metadata;
+pub mod file_versions;
+pub mod folder_keys;
+pub mod folder_records;
+pub mod log_writer;
+pub mod maintenance;
+pub mod migrations;
+pub mod name_mappings;
+pub mod remote_cache;
+pub mod remote_locks;
+pub mod remote_snapshots;
+pub mod server_health;
+pub mod stats;
+pub mod sync_logs;
+pub mod sync_sessions;
+pub mod sync_statistics;
+pub mod sync_tokens;
+pub mod transfers;
+pub mod trash;
+pub mod types;
+
+pub use connection::Database;
+pub use types::*;
@@ -0,0 +1,146 @@
+/// 文件名映射数据库操作模块
+///
+/// 保存本地清理后的文件名与原始远程路径的对应关系（见 `sync::sanitize`），
+/// 每次同步生成计划时用当前生效的映射替换文件夹的记录
+use crate::database::Database;
+use crate::{Result, SyncError};
+
+/// 用当前生效的映射替换文件夹的记录
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - mappings: (本地相对路径, 远程相对路径)
+pub async fn replace(db: &Database, folder_id: &str, mappings: &[(String, String)]) -> Result<()> {
+    let conn = db.conn()?;
+    let db_err = |e: rusqlite::Error| {
+        SyncError::DatabaseError(format!("Failed to replace name mappings: {}", e))
+    };
+    let now = chrono::Utc::now().timestamp();
+
+    let tx = conn.unchecked_transaction().map_err(db_err)?;
+    tx.execute(
+        "DELETE FROM name_mappings WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(db_err)?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO name_mappings (folder_id, local_path, remote_path, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .map_err(db_err)?;
+        for (local_path, remote_path) in mappings {
+            stmt.execute(rusqlite::params![folder_id, local_path, remote_path, now])
+                .map_err(db_err)?;
+        }
+    }
+    tx.commit().map_err(db_err)?;
+
+    Ok(())
+}
+
+/// 列出文件夹的映射（按本地路径排序）
+///
+/// # 返回
+/// (本地相对路径, 远程相对路径)
+pub async fn list(db: &Database, folder_id: &str) -> Result<Vec<(String, String)>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT local_path, remote_path FROM name_mappings
+             WHERE folder_id = ?1 ORDER BY local_path",
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let mappings = stmt
+        .query_map(rusqlite::params![folder_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query name mappings: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read name mappings: {}", e)))?;
+
+    Ok(mappings)
+}
+
+/// 清除文件夹的映射
+///
+/// 文件夹被移除时调用
+pub async fn clear(db: &Database, folder_id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM name_mappings WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to clear name mappings: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/022_name_mappings.sql"))
+            .expect("Failed to run migration 022");
+
+        (test_dir, db)
+    }
+
+    fn mapping(local: &str, remote: &str) -> (String, String) {
+        (local.to_string(), remote.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_replace_list_and_clear() {
+        let (test_dir, db) = create_test_db();
+
+        assert!(list(&db, "folder-1").await.unwrap().is_empty());
+        replace(
+            &db,
+            "folder-1",
+            &[mapping("b_c.txt", "b:c.txt"), mapping("CON_", "CON")],
+        )
+        .await
+        .unwrap();
+        replace(&db, "folder-2", &[mapping("x_.md", "x?.md")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            list(&db, "folder-1").await.unwrap(),
+            vec![mapping("CON_", "CON"), mapping("b_c.txt", "b:c.txt")]
+        );
+
+        // 整体替换时丢弃旧映射，不影响其他文件夹
+        replace(&db, "folder-1", &[mapping("a%3F.txt", "a?.txt")])
+            .await
+            .unwrap();
+        assert_eq!(
+            list(&db, "folder-1").await.unwrap(),
+            vec![mapping("a%3F.txt", "a?.txt")]
+        );
+
+        clear(&db, "folder-1").await.unwrap();
+        assert!(list(&db, "folder-1").await.unwrap().is_empty());
+        assert_eq!(list(&db, "folder-2").await.unwrap().len(), 1);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,321 @@
+/// 远程目录树缓存数据库操作模块
+///
+/// 每次同步生成计划时用完整的远程列表替换文件夹的缓存，
+/// 同步过程中的上传、移动和远程删除逐条更新缓存，浏览远程目录时直接读取
+use std::borrow::Borrow;
+
+use crate::database::{Database, RemoteCacheEntry};
+use crate::{Result, SyncError};
+use rusqlite::OptionalExtension;
+
+/// 相对路径的父目录（根目录下的条目为空字符串）
+fn parent_of(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// 用完整的远程列表替换文件夹的缓存
+///
+/// 条目逐个写入同一事务，调用方可以边转换边写入，不需要先收集为列表
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - entries: 远程条目（路径相对于同步根目录）
+pub async fn replace(
+    db: &Database,
+    folder_id: &str,
+    entries: impl IntoIterator<Item = impl Borrow<RemoteCacheEntry>>,
+) -> Result<()> {
+    let conn = db.conn()?;
+    let db_err = |e: rusqlite::Error| {
+        SyncError::DatabaseError(format!("Failed to replace remote cache: {}", e))
+    };
+
+    let tx = conn.unchecked_transaction().map_err(db_err)?;
+    tx.execute(
+        "DELETE FROM remote_cache WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(db_err)?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO remote_cache
+                     (folder_id, path, parent, is_directory, size, modified, etag, cached_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )
+            .map_err(db_err)?;
+        for entry in entries {
+            let entry = entry.borrow();
+            stmt.execute(rusqlite::params![
+                folder_id,
+                entry.path,
+                parent_of(&entry.path),
+                entry.is_directory,
+                entry.size as i64,
+                entry.modified,
+                entry.etag,
+                entry.cached_at,
+            ])
+            .map_err(db_err)?;
+        }
+    }
+    tx.commit().map_err(db_err)?;
+
+    Ok(())
+}
+
+/// 写入或更新单个条目
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - entry: 远程条目
+pub async fn upsert(db: &Database, folder_id: &str, entry: &RemoteCacheEntry) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO remote_cache
+             (folder_id, path, parent, is_directory, size, modified, etag, cached_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            folder_id,
+            entry.path,
+            parent_of(&entry.path),
+            entry.is_directory,
+            entry.size as i64,
+            entry.modified,
+            entry.etag,
+            entry.cached_at,
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to update remote cache: {}", e)))?;
+
+    Ok(())
+}
+
+/// 删除条目及其下的所有子条目
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - path: 相对于同步根目录的路径
+pub async fn remove(db: &Database, folder_id: &str, path: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM remote_cache
+         WHERE folder_id = ?1 AND (path = ?2 OR substr(path, 1, length(?2) + 1) = ?2 || '/')",
+        rusqlite::params![folder_id, path],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to update remote cache: {}", e)))?;
+
+    Ok(())
+}
+
+/// 列出缓存中目录的直接子条目（目录在前，按路径排序）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - parent: 目录的相对路径（空字符串表示同步根目录）
+pub async fn list_children(
+    db: &Database,
+    folder_id: &str,
+    parent: &str,
+) -> Result<Vec<RemoteCacheEntry>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, is_directory, size, modified, etag, cached_at
+             FROM remote_cache WHERE folder_id = ?1 AND parent = ?2
+             ORDER BY is_directory DESC, path",
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![folder_id, parent], |row| {
+            Ok(RemoteCacheEntry {
+                path: row.get(0)?,
+                is_directory: row.get::<_, i32>(1)? != 0,
+                size: row.get::<_, i64>(2)? as u64,
+                modified: row.get(3)?,
+                etag: row.get(4)?,
+                cached_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query remote cache: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read remote cache: {}", e)))?;
+
+    Ok(entries)
+}
+
+/// 获取缓存中文件的 ETag
+///
+/// # 返回
+/// - Ok(Some(etag)): 条目存在且服务器返回了 ETag
+/// - Ok(None): 条目不存在或没有 ETag
+pub async fn etag(db: &Database, folder_id: &str, path: &str) -> Result<Option<String>> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT etag FROM remote_cache WHERE folder_id = ?1 AND path = ?2",
+        rusqlite::params![folder_id, path],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query remote cache: {}", e)))
+}
+
+/// 获取文件夹缓存最早的写入时间
+///
+/// 缓存由整体替换和逐条更新组成，最早的写入时间代表整个缓存的新鲜程度
+///
+/// # 返回
+/// - Ok(Some(i64)): 最早的写入时间（Unix 时间戳，秒）
+/// - Ok(None): 尚未缓存
+pub async fn cached_at(db: &Database, folder_id: &str) -> Result<Option<i64>> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT MIN(cached_at) FROM remote_cache WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+        |row| row.get::<_, Option<i64>>(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query remote cache: {}", e)))
+}
+
+/// 清除文件夹的缓存
+///
+/// 文件夹被移除时调用
+pub async fn clear(db: &Database, folder_id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM remote_cache WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to clear remote cache: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/016_remote_cache.sql"))
+            .expect("Failed to run migration 016");
+
+        (test_dir, db)
+    }
+
+    fn entry(path: &str, is_directory: bool, cached_at: i64) -> RemoteCacheEntry {
+        RemoteCacheEntry {
+            path: path.to_string(),
+            is_directory,
+            size: if is_directory { 0 } else { 10 },
+            modified: Some(100),
+            etag: None,
+            cached_at,
+        }
+    }
+
+    fn paths(entries: &[RemoteCacheEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_etag() {
+        let (test_dir, db) = create_test_db();
+
+        let mut tagged = entry("a.txt", false, 100);
+        tagged.etag = Some("\"v1\"".to_string());
+        replace(&db, "folder-1", &[tagged, entry("b.txt", false, 100)])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            etag(&db, "folder-1", "a.txt").await.unwrap().as_deref(),
+            Some("\"v1\"")
+        );
+        assert_eq!(etag(&db, "folder-1", "b.txt").await.unwrap(), None);
+        assert_eq!(etag(&db, "folder-2", "a.txt").await.unwrap(), None);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_replace_and_update() {
+        let (test_dir, db) = create_test_db();
+
+        assert_eq!(cached_at(&db, "folder-1").await.unwrap(), None);
+        replace(
+            &db,
+            "folder-1",
+            &[
+                entry("b.txt", false, 100),
+                entry("docs", true, 100),
+                entry("docs/a.txt", false, 100),
+                entry("docs-old.txt", false, 100),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let root = list_children(&db, "folder-1", "").await.unwrap();
+        assert_eq!(paths(&root), vec!["docs", "b.txt", "docs-old.txt"]);
+        assert_eq!(
+            paths(&list_children(&db, "folder-1", "docs").await.unwrap()),
+            vec!["docs/a.txt"]
+        );
+        assert_eq!(cached_at(&db, "folder-1").await.unwrap(), Some(100));
+
+        // 删除目录时同时删除子条目，不影响前缀相同的兄弟条目
+        remove(&db, "folder-1", "docs").await.unwrap();
+        upsert(&db, "folder-1", &entry("c.txt", false, 200))
+            .await
+            .unwrap();
+        assert_eq!(
+            paths(&list_children(&db, "folder-1", "").await.unwrap()),
+            vec!["b.txt", "c.txt", "docs-old.txt"]
+        );
+        assert!(list_children(&db, "folder-1", "docs")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // 整体替换时丢弃旧条目
+        replace(&db, "folder-1", &[entry("d.txt", false, 300)])
+            .await
+            .unwrap();
+        assert_eq!(
+            paths(&list_children(&db, "folder-1", "").await.unwrap()),
+            vec!["d.txt"]
+        );
+        assert_eq!(cached_at(&db, "folder-1").await.unwrap(), Some(300));
+
+        clear(&db, "folder-1").await.unwrap();
+        assert_eq!(cached_at(&db, "folder-1").await.unwrap(), None);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,142 @@
+/// 远程文件锁数据库操作模块
+///
+/// 保存同步引擎上传时持有的锁令牌，应用异常退出后可据此释放遗留的锁
+use crate::database::Database;
+use crate::webdav::client::RemoteLock;
+use crate::{Result, SyncError};
+
+/// 保存锁（同一路径已存在时覆盖）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - lock: 服务器返回的锁
+pub async fn save(db: &Database, folder_id: &str, lock: &RemoteLock) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO remote_locks (folder_id, remote_path, lock_token, owner, expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(folder_id, remote_path) DO UPDATE SET
+             lock_token = excluded.lock_token,
+             owner = excluded.owner,
+             expires_at = excluded.expires_at",
+        rusqlite::params![
+            folder_id,
+            lock.path,
+            lock.token,
+            lock.owner,
+            lock.expires_at
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to save remote lock: {}", e)))?;
+
+    Ok(())
+}
+
+/// 获取文件夹持有的所有锁
+pub async fn list_by_folder(db: &Database, folder_id: &str) -> Result<Vec<RemoteLock>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT remote_path, lock_token, owner, expires_at FROM remote_locks
+             WHERE folder_id = ?1
+             ORDER BY remote_path",
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let locks = stmt
+        .query_map(rusqlite::params![folder_id], |row| {
+            Ok(RemoteLock {
+                path: row.get(0)?,
+                token: row.get(1)?,
+                owner: row.get(2)?,
+                expires_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query remote locks: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read remote lock: {}", e)))?;
+
+    Ok(locks)
+}
+
+/// 删除锁记录
+pub async fn delete(db: &Database, folder_id: &str, remote_path: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM remote_locks WHERE folder_id = ?1 AND remote_path = ?2",
+        rusqlite::params![folder_id, remote_path],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete remote lock: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/010_remote_locks.sql"))
+            .expect("Failed to run migration 010");
+
+        (test_dir, db)
+    }
+
+    fn create_lock(path: &str, token: &str) -> RemoteLock {
+        RemoteLock {
+            path: path.to_string(),
+            token: token.to_string(),
+            owner: "LightSync".to_string(),
+            expires_at: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_list_delete() {
+        let (test_dir, db) = create_test_db();
+
+        save(&db, "folder-1", &create_lock("/docs/b.txt", "token-1"))
+            .await
+            .unwrap();
+        save(&db, "folder-1", &create_lock("/docs/a.txt", "token-2"))
+            .await
+            .unwrap();
+        // 同一路径重新加锁时覆盖令牌
+        save(&db, "folder-1", &create_lock("/docs/b.txt", "token-3"))
+            .await
+            .unwrap();
+        save(&db, "folder-2", &create_lock("/other.txt", "token-4"))
+            .await
+            .unwrap();
+
+        let locks = list_by_folder(&db, "folder-1").await.unwrap();
+        assert_eq!(
+            locks,
+            vec![
+                create_lock("/docs/a.txt", "token-2"),
+                create_lock("/docs/b.txt", "token-3"),
+            ]
+        );
+
+        delete(&db, "folder-1", "/docs/a.txt").await.unwrap();
+        assert_eq!(list_by_folder(&db, "folder-1").await.unwrap().len(), 1);
+        assert_eq!(list_by_folder(&db, "folder-2").await.unwrap().len(), 1);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
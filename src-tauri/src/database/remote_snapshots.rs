@@ -0,0 +1,145 @@
+/// 远程目录快照数据库操作模块
+///
+/// 保存只下载文件夹轮询远程目录时使用的指纹：每次轮询更新检查时间，
+/// 同步成功后才写入新的指纹，同步失败时下次轮询会再次触发同步
+use crate::database::{Database, RemoteSnapshot};
+use crate::{Result, SyncError};
+use rusqlite::OptionalExtension;
+
+/// 获取文件夹的远程目录快照
+///
+/// # 返回
+/// - Ok(Some(RemoteSnapshot)): 已轮询过
+/// - Ok(None): 尚未轮询过
+pub async fn get(db: &Database, folder_id: &str) -> Result<Option<RemoteSnapshot>> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT folder_id, fingerprint, entry_count, checked_at
+         FROM remote_snapshots WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+        |row| {
+            Ok(RemoteSnapshot {
+                folder_id: row.get(0)?,
+                fingerprint: row.get(1)?,
+                entry_count: row.get(2)?,
+                checked_at: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query remote snapshot: {}", e)))
+}
+
+/// 记录一次轮询（不修改已保存的指纹）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - checked_at: 轮询时间（Unix 时间戳，秒）
+pub async fn touch(db: &Database, folder_id: &str, checked_at: i64) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO remote_snapshots (folder_id, checked_at)
+         VALUES (?1, ?2)
+         ON CONFLICT(folder_id) DO UPDATE SET checked_at = excluded.checked_at",
+        rusqlite::params![folder_id, checked_at],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to update remote snapshot: {}", e)))?;
+
+    Ok(())
+}
+
+/// 保存同步成功后的远程目录指纹（已存在时覆盖）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - fingerprint: 远程目录指纹
+/// - entry_count: 指纹包含的远程条目数
+/// - checked_at: 计算指纹的轮询时间（Unix 时间戳，秒）
+pub async fn save(
+    db: &Database,
+    folder_id: &str,
+    fingerprint: &str,
+    entry_count: i64,
+    checked_at: i64,
+) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO remote_snapshots (folder_id, fingerprint, entry_count, checked_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(folder_id) DO UPDATE SET
+             fingerprint = excluded.fingerprint,
+             entry_count = excluded.entry_count,
+             checked_at = excluded.checked_at",
+        rusqlite::params![folder_id, fingerprint, entry_count, checked_at],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to save remote snapshot: {}", e)))?;
+
+    Ok(())
+}
+
+/// 清除文件夹的远程目录快照
+///
+/// 文件夹被移除时调用
+pub async fn clear(db: &Database, folder_id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM remote_snapshots WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to clear remote snapshot: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/014_remote_snapshots.sql"))
+            .expect("Failed to run migration 014");
+
+        (test_dir, db)
+    }
+
+    #[tokio::test]
+    async fn test_touch_save_clear() {
+        let (test_dir, db) = create_test_db();
+
+        assert_eq!(get(&db, "folder-1").await.unwrap(), None);
+
+        touch(&db, "folder-1", 100).await.unwrap();
+        let snapshot = get(&db, "folder-1").await.unwrap().unwrap();
+        assert_eq!(snapshot.fingerprint, None);
+        assert_eq!(snapshot.checked_at, 100);
+
+        save(&db, "folder-1", "abc", 3, 200).await.unwrap();
+        touch(&db, "folder-1", 300).await.unwrap();
+        let snapshot = get(&db, "folder-1").await.unwrap().unwrap();
+        assert_eq!(snapshot.fingerprint.as_deref(), Some("abc"));
+        assert_eq!(snapshot.entry_count, 3);
+        assert_eq!(snapshot.checked_at, 300);
+
+        clear(&db, "folder-1").await.unwrap();
+        assert_eq!(get(&db, "folder-1").await.unwrap(), None);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
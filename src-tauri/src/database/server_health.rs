@@ -0,0 +1,173 @@
+/// 服务器健康检查数据库操作模块
+///
+/// 记录后台健康检查的结果与延迟，每个服务器只保留最近的若干条
+use crate::database::{Database, ServerHealthCheck};
+use crate::{Result, SyncError};
+
+/// server_health 表的查询列（顺序与 `row_to_check` 对应）
+const HEALTH_COLUMNS: &str = "id, server_id, checked_at, success, latency_ms, error";
+
+/// 将查询结果行转换为健康检查记录
+fn row_to_check(row: &rusqlite::Row<'_>) -> rusqlite::Result<ServerHealthCheck> {
+    Ok(ServerHealthCheck {
+        id: row.get(0)?,
+        server_id: row.get(1)?,
+        checked_at: row.get(2)?,
+        success: row.get::<_, i32>(3)? != 0,
+        latency_ms: row.get(4)?,
+        error: row.get(5)?,
+    })
+}
+
+/// 插入健康检查记录
+///
+/// # 返回
+/// - Ok(i64): 新记录 ID
+pub async fn insert(
+    db: &Database,
+    server_id: &str,
+    checked_at: i64,
+    success: bool,
+    latency_ms: Option<i64>,
+    error: Option<&str>,
+) -> Result<i64> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO server_health (server_id, checked_at, success, latency_ms, error)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![server_id, checked_at, success as i32, latency_ms, error],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to insert server health: {}", e)))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// 获取服务器最近的健康检查记录（最新的在前）
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - limit: 最多返回的记录数
+pub async fn list(db: &Database, server_id: &str, limit: i64) -> Result<Vec<ServerHealthCheck>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM server_health
+             WHERE server_id = ?1
+             ORDER BY checked_at DESC, id DESC
+             LIMIT ?2",
+            HEALTH_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let checks = stmt
+        .query_map(rusqlite::params![server_id, limit], row_to_check)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query server health: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read server health: {}", e)))?;
+
+    Ok(checks)
+}
+
+/// 删除超出保留数量的旧记录
+///
+/// # 参数
+/// - server_id: 服务器 ID
+/// - keep: 保留最近的记录数
+///
+/// # 返回
+/// - Ok(usize): 删除的记录数
+pub async fn prune(db: &Database, server_id: &str, keep: i64) -> Result<usize> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM server_health
+         WHERE server_id = ?1 AND id NOT IN (
+             SELECT id FROM server_health
+             WHERE server_id = ?1
+             ORDER BY checked_at DESC, id DESC
+             LIMIT ?2
+         )",
+        rusqlite::params![server_id, keep],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to prune server health: {}", e)))
+}
+
+/// 删除服务器的全部健康检查记录
+pub async fn delete_by_server(db: &Database, server_id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM server_health WHERE server_id = ?1",
+        rusqlite::params![server_id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete server health: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/009_server_health.sql"))
+            .expect("Failed to run migration 009");
+
+        (test_dir, db)
+    }
+
+    #[tokio::test]
+    async fn test_insert_list_prune() {
+        let (test_dir, db) = create_test_db();
+
+        for checked_at in 1..=5 {
+            insert(
+                &db,
+                "server-1",
+                checked_at,
+                true,
+                Some(checked_at * 10),
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        insert(&db, "server-2", 3, false, None, Some("timeout"))
+            .await
+            .unwrap();
+
+        let checks = list(&db, "server-1", 3).await.unwrap();
+        assert_eq!(
+            checks.iter().map(|c| c.checked_at).collect::<Vec<_>>(),
+            vec![5, 4, 3]
+        );
+        assert_eq!(checks[0].latency_ms, Some(50));
+
+        assert_eq!(prune(&db, "server-1", 2).await.unwrap(), 3);
+        assert_eq!(list(&db, "server-1", 10).await.unwrap().len(), 2);
+
+        // 其他服务器的记录不受影响
+        let other = list(&db, "server-2", 10).await.unwrap();
+        assert_eq!(other.len(), 1);
+        assert!(!other[0].success);
+        assert_eq!(other[0].error.as_deref(), Some("timeout"));
+
+        delete_by_server(&db, "server-2").await.unwrap();
+        assert!(list(&db, "server-2", 10).await.unwrap().is_empty());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,102 @@
+/// 数据库统计模块
+///
+/// 汇总文件元数据、同步日志和同步会话的数量以及数据库文件大小，供设置/诊断页面展示
+use std::path::Path;
+
+use crate::database::{Database, DatabaseStats};
+use crate::{Result, SyncError};
+
+/// 获取数据库在磁盘上占用的大小（字节）
+///
+/// 包括主文件以及 WAL 模式下的 `-wal`、`-shm` 文件，不存在的文件按 0 计算
+pub fn disk_size(path: &Path) -> i64 {
+    ["", "-wal", "-shm"]
+        .iter()
+        .map(|suffix| {
+            let mut file = path.as_os_str().to_owned();
+            file.push(suffix);
+            std::fs::metadata(file).map(|m| m.len() as i64).unwrap_or(0)
+        })
+        .sum()
+}
+
+/// 统计数据库记录数与大小
+///
+/// 文件数量只统计未被软删除的文件元数据
+pub async fn collect(db: &Database) -> Result<DatabaseStats> {
+    let conn = db.conn()?;
+
+    let (total_files, pending_files, synced_files, conflict_files) = conn
+        .query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(status = 'pending'), 0),
+                    COALESCE(SUM(status = 'synced'), 0),
+                    COALESCE(SUM(status = 'conflict'), 0)
+             FROM file_metadata
+             WHERE is_delete = 0",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to count file metadata: {}", e)))?;
+
+    let total_logs = conn
+        .query_row("SELECT COUNT(*) FROM sync_logs", [], |row| row.get(0))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to count sync logs: {}", e)))?;
+
+    let total_sessions = conn
+        .query_row("SELECT COUNT(*) FROM sync_sessions", [], |row| row.get(0))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to count sync sessions: {}", e)))?;
+
+    Ok(DatabaseStats {
+        total_files,
+        total_logs,
+        total_sessions,
+        pending_files,
+        synced_files,
+        conflict_files,
+        database_size_bytes: disk_size(db.path()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_collect() {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        {
+            let conn = db.conn().unwrap();
+            conn.execute_batch(include_str!("../../migrations/001_initial.sql"))
+                .expect("Failed to run migration 001");
+            conn.execute_batch(
+                "INSERT INTO file_metadata (path, modified_at, sync_folder_id, status, is_delete) VALUES
+                     ('a', 0, 1, 'pending', 0),
+                     ('b', 0, 1, 'synced', 0),
+                     ('c', 0, 1, 'synced', 0),
+                     ('d', 0, 1, 'conflict', 0),
+                     ('e', 0, 1, 'synced', 1);
+                 INSERT INTO sync_logs (sync_folder_id, file_path, action) VALUES (1, 'a', 'upload');
+                 INSERT INTO sync_sessions (sync_folder_id) VALUES (1), (1);",
+            )
+            .unwrap();
+        }
+
+        let stats = collect(&db).await.unwrap();
+        assert_eq!(stats.total_files, 4);
+        assert_eq!(stats.pending_files, 1);
+        assert_eq!(stats.synced_files, 2);
+        assert_eq!(stats.conflict_files, 1);
+        assert_eq!(stats.total_logs, 1);
+        assert_eq!(stats.total_sessions, 2);
+        assert!(stats.database_size_bytes > 0);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,229 @@
+/// 同步日志数据库操作模块
+///
+/// 提供对 sync_logs 表的写入和分页查询。
+/// 插入语句通过连接的语句缓存复用，同步过程中的日志由 `log_writer` 在事务中批量写入
+use crate::database::{Database, PagedResult, QueryFilter, SyncLog};
+use crate::{Result, SyncError};
+
+/// sync_logs 表的查询列（顺序与 `row_to_log` 对应）
+const LOG_COLUMNS: &str =
+    "id, sync_folder_id, file_path, action, status, error_message, file_size, duration_ms, created_at, bytes_per_sec";
+
+/// 将查询结果行转换为同步日志
+fn row_to_log(row: &rusqlite::Row<'_>) -> rusqlite::Result<SyncLog> {
+    Ok(SyncLog {
+        id: row.get(0)?,
+        sync_folder_id: row.get(1)?,
+        file_path: row.get(2)?,
+        action: row.get(3)?,
+        status: row.get(4)?,
+        error_message: row.get(5)?,
+        file_size: row.get(6)?,
+        duration_ms: row.get(7)?,
+        created_at: row.get(8)?,
+        bytes_per_sec: row.get(9)?,
+    })
+}
+
+/// sync_logs 表的插入语句
+const INSERT_SQL: &str = "INSERT INTO sync_logs (
+        sync_folder_id, file_path, action, status, error_message,
+        file_size, duration_ms, created_at, bytes_per_sec
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)";
+
+/// 使用缓存的插入语句写入一条日志
+fn insert_with(conn: &rusqlite::Connection, log: &SyncLog, now: i64) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare_cached(INSERT_SQL)?;
+    stmt.execute(rusqlite::params![
+        log.sync_folder_id,
+        log.file_path,
+        log.action,
+        log.status,
+        log.error_message,
+        log.file_size,
+        log.duration_ms,
+        log.created_at.unwrap_or(now),
+        log.bytes_per_sec,
+    ])?;
+    Ok(())
+}
+
+/// 插入同步日志
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - log: 同步日志（id 会被忽略，created_at 为空时使用当前时间）
+///
+/// # 返回
+/// - Ok(i64): 新记录 ID
+/// - Err(SyncError::DatabaseError): 写入失败
+pub async fn insert(db: &Database, log: &SyncLog) -> Result<i64> {
+    let conn = db.conn()?;
+    insert_with(&conn, log, chrono::Utc::now().timestamp())
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to insert sync log: {}", e)))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// 在一个事务中批量插入同步日志
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - logs: 同步日志（id 会被忽略，created_at 为空时使用当前时间）
+///
+/// # 返回
+/// - Ok(()): 全部写入
+/// - Err(SyncError::DatabaseError): 写入失败（事务回滚，不写入任何日志）
+pub async fn insert_batch(db: &Database, logs: &[SyncLog]) -> Result<()> {
+    if logs.is_empty() {
+        return Ok(());
+    }
+    let conn = db.conn()?;
+    let db_err =
+        |e: rusqlite::Error| SyncError::DatabaseError(format!("Failed to insert sync logs: {}", e));
+    let now = chrono::Utc::now().timestamp();
+
+    let tx = conn.unchecked_transaction().map_err(db_err)?;
+    for log in logs {
+        insert_with(&tx, log, now).map_err(db_err)?;
+    }
+    tx.commit().map_err(db_err)?;
+
+    Ok(())
+}
+
+/// 分页查询同步日志
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - filter: 查询过滤器（文件夹、状态、时间范围、分页）
+///
+/// # 返回
+/// - Ok(PagedResult<SyncLog>): 按时间倒序的当前页日志及总数
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn query(db: &Database, filter: &QueryFilter) -> Result<PagedResult<SyncLog>> {
+    let conn = db.conn()?;
+    let (where_clause, params) = filter.where_clause("created_at");
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM sync_logs {}", where_clause),
+            rusqlite::params_from_iter(params.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to count sync logs: {}", e)))?;
+
+    let query = format!(
+        "SELECT {} FROM sync_logs {} ORDER BY created_at DESC, id DESC {}",
+        LOG_COLUMNS,
+        where_clause,
+        filter.limit_clause()
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let items = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), row_to_log)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync logs: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
+
+    Ok(PagedResult { items, total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .expect("Failed to run migration 001");
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/021_sync_log_speed.sql"))
+            .expect("Failed to run migration 021");
+
+        (test_dir, db)
+    }
+
+    fn create_log(folder_id: i64, status: &str, created_at: i64) -> SyncLog {
+        SyncLog {
+            id: None,
+            sync_folder_id: folder_id,
+            file_path: format!("file-{}.txt", created_at),
+            action: "upload".to_string(),
+            status: status.to_string(),
+            error_message: None,
+            file_size: Some(10),
+            duration_ms: Some(5),
+            bytes_per_sec: Some(2),
+            created_at: Some(created_at),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_with_filters_and_pagination() {
+        let (test_dir, db) = create_test_db();
+
+        for i in 0..5 {
+            insert(&db, &create_log(1, "success", 100 + i))
+                .await
+                .unwrap();
+        }
+        insert(&db, &create_log(1, "failed", 200)).await.unwrap();
+        insert(&db, &create_log(2, "success", 300)).await.unwrap();
+
+        // 文件夹 + 状态过滤，分页
+        let filter = QueryFilter {
+            sync_folder_id: Some(1),
+            status: Some("success".to_string()),
+            limit: Some(2),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let page = query(&db, &filter).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].created_at, Some(103));
+
+        // 时间范围过滤
+        let filter = QueryFilter {
+            start_time: Some(150),
+            end_time: Some(250),
+            ..Default::default()
+        };
+        let page = query(&db, &filter).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].status, "failed");
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_insert_batch() {
+        let (test_dir, db) = create_test_db();
+
+        let logs: Vec<SyncLog> = (0..3).map(|i| create_log(1, "success", 100 + i)).collect();
+        insert_batch(&db, &logs).await.unwrap();
+        insert_batch(&db, &[]).await.unwrap();
+
+        let page = query(&db, &QueryFilter::default()).await.unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0].file_path, "file-102.txt");
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,259 @@
+/// 同步会话数据库操作模块
+///
+/// 提供对 sync_sessions 表的创建、结束和分页查询
+use crate::constants::session_status;
+use crate::database::{Database, PagedResult, QueryFilter, SyncSession};
+use crate::{Result, SyncError};
+
+/// sync_sessions 表的查询列（顺序与 `row_to_session` 对应）
+const SESSION_COLUMNS: &str = "id, sync_folder_id, status, started_at, completed_at, files_uploaded,
+                files_downloaded, files_deleted, files_conflict, errors_count, total_bytes, error_message";
+
+/// 将查询结果行转换为同步会话
+fn row_to_session(row: &rusqlite::Row<'_>) -> rusqlite::Result<SyncSession> {
+    Ok(SyncSession {
+        id: row.get(0)?,
+        sync_folder_id: row.get(1)?,
+        status: row.get(2)?,
+        started_at: row.get(3)?,
+        completed_at: row.get(4)?,
+        files_uploaded: row.get(5)?,
+        files_downloaded: row.get(6)?,
+        files_deleted: row.get(7)?,
+        files_conflict: row.get(8)?,
+        errors_count: row.get(9)?,
+        total_bytes: row.get(10)?,
+        error_message: row.get(11)?,
+    })
+}
+
+/// 创建同步会话（状态为 running）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - Ok(i64): 新会话 ID
+/// - Err(SyncError::DatabaseError): 写入失败
+pub async fn create(db: &Database, sync_folder_id: i64) -> Result<i64> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO sync_sessions (sync_folder_id, status, started_at) VALUES (?1, 'running', ?2)",
+        rusqlite::params![sync_folder_id, now],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to create sync session: {}", e)))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// 结束同步会话，写回最终状态和计数
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - session: 同步会话（必须包含 id）
+///
+/// # 返回
+/// - Ok(()): 更新成功
+/// - Err(SyncError::NotFound): 会话不存在
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn finish(db: &Database, session: &SyncSession) -> Result<()> {
+    let session_id = session
+        .id
+        .ok_or_else(|| SyncError::DatabaseError("Sync session id is missing".to_string()))?;
+    let conn = db.conn()?;
+    let completed_at = session
+        .completed_at
+        .unwrap_or_else(|| chrono::Utc::now().timestamp());
+
+    let affected = conn
+        .execute(
+            "UPDATE sync_sessions
+             SET status = ?1, completed_at = ?2, files_uploaded = ?3, files_downloaded = ?4,
+                 files_deleted = ?5, files_conflict = ?6, errors_count = ?7, total_bytes = ?8,
+                 error_message = ?9
+             WHERE id = ?10",
+            rusqlite::params![
+                session.status,
+                completed_at,
+                session.files_uploaded,
+                session.files_downloaded,
+                session.files_deleted,
+                session.files_conflict,
+                session.errors_count,
+                session.total_bytes,
+                session.error_message,
+                session_id,
+            ],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to finish sync session: {}", e)))?;
+
+    if affected == 0 {
+        return Err(SyncError::NotFound(format!(
+            "Sync session not found: {}",
+            session_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// 将仍处于运行状态的会话标记为已中断
+///
+/// 在应用启动时调用：本次启动前开始且仍在运行的会话都是上次异常退出遗留的
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - started_before: 只处理早于该时间开始的会话（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(usize): 标记的会话数
+pub async fn mark_interrupted(db: &Database, started_before: i64) -> Result<usize> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "UPDATE sync_sessions
+         SET status = ?1, completed_at = ?2,
+             error_message = COALESCE(error_message, 'Application exited before the session finished')
+         WHERE status = ?3 AND started_at < ?4",
+        rusqlite::params![
+            session_status::INTERRUPTED,
+            now,
+            session_status::RUNNING,
+            started_before
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to mark interrupted sessions: {}", e)))
+}
+
+/// 根据 ID 查询同步会话
+pub async fn get_by_id(db: &Database, session_id: i64) -> Result<SyncSession> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM sync_sessions WHERE id = ?1 AND is_delete = 0",
+            SESSION_COLUMNS
+        ),
+        rusqlite::params![session_id],
+        row_to_session,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            SyncError::NotFound(format!("Sync session not found: {}", session_id))
+        }
+        _ => SyncError::DatabaseError(format!("Failed to query sync session: {}", e)),
+    })
+}
+
+/// 分页查询同步会话
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - filter: 查询过滤器（文件夹、状态、时间范围、分页），时间范围作用于 started_at
+///
+/// # 返回
+/// - Ok(PagedResult<SyncSession>): 按开始时间倒序的当前页会话及总数
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn query(db: &Database, filter: &QueryFilter) -> Result<PagedResult<SyncSession>> {
+    let conn = db.conn()?;
+    let (where_clause, params) = filter.where_clause("started_at");
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM sync_sessions {}", where_clause),
+            rusqlite::params_from_iter(params.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to count sync sessions: {}", e)))?;
+
+    let query = format!(
+        "SELECT {} FROM sync_sessions {} ORDER BY started_at DESC, id DESC {}",
+        SESSION_COLUMNS,
+        where_clause,
+        filter.limit_clause()
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let items = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), row_to_session)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync sessions: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
+
+    Ok(PagedResult { items, total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .expect("Failed to run migration 001");
+
+        (test_dir, db)
+    }
+
+    #[tokio::test]
+    async fn test_create_finish_and_query() {
+        let (test_dir, db) = create_test_db();
+
+        let id = create(&db, 1).await.unwrap();
+        create(&db, 2).await.unwrap();
+
+        let mut session = get_by_id(&db, id).await.unwrap();
+        assert_eq!(session.status, "running");
+
+        session.status = "completed".to_string();
+        session.files_uploaded = 3;
+        session.total_bytes = 4096;
+        finish(&db, &session).await.unwrap();
+
+        let filter = QueryFilter {
+            sync_folder_id: Some(1),
+            ..Default::default()
+        };
+        let page = query(&db, &filter).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].status, "completed");
+        assert_eq!(page.items[0].files_uploaded, 3);
+        assert!(page.items[0].completed_at.is_some());
+
+        let filter = QueryFilter {
+            status: Some("running".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query(&db, &filter).await.unwrap().total, 1);
+
+        // 启动恢复时遗留的运行中会话被标记为已中断
+        let started_before = chrono::Utc::now().timestamp() + 1;
+        assert_eq!(mark_interrupted(&db, started_before).await.unwrap(), 1);
+        let filter = QueryFilter {
+            status: Some("interrupted".to_string()),
+            ..Default::default()
+        };
+        let page = query(&db, &filter).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert!(page.items[0].completed_at.is_some());
+        assert!(page.items[0].error_message.is_some());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
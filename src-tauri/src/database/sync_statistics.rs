@@ -0,0 +1,292 @@
+/// 同步统计模块
+///
+/// 从 sync_logs 和 sync_sessions 汇总指定时间范围内的同步数据，供前端绘制图表：
+/// - 每日上传/下载字节数与文件数、错误数和会话数（没有数据的日期补 0）
+/// - 会话数、失败会话数、平均同步耗时
+/// - 文件操作数与错误率
+///
+/// 日期按 UTC 划分
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration};
+
+use crate::constants::statistics_period;
+use crate::database::{DailySyncStats, Database, SyncStatistics};
+use crate::{Result, SyncError};
+
+/// 统计时间范围包含的天数
+///
+/// # 返回
+/// - Ok(i64): 天数
+/// - Err(SyncError::ValidationError): 未知的时间范围
+pub fn period_days(period: &str) -> Result<i64> {
+    match period {
+        statistics_period::WEEK => Ok(7),
+        statistics_period::MONTH => Ok(30),
+        statistics_period::QUARTER => Ok(90),
+        statistics_period::YEAR => Ok(365),
+        other => Err(SyncError::ValidationError(format!(
+            "Invalid statistics period: {}",
+            other
+        ))),
+    }
+}
+
+/// 汇总同步统计
+///
+/// 时间范围从 `now` 所在日期往前推 `period_days - 1` 天的 0 点开始，到 `now` 为止
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folder_id: 同步文件夹 ID（None 表示所有文件夹）
+/// - period: 时间范围（week, month, quarter, year）
+/// - now: 当前时间（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(SyncStatistics): 统计结果
+/// - Err(SyncError::ValidationError): 未知的时间范围
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn collect(
+    db: &Database,
+    sync_folder_id: Option<i64>,
+    period: &str,
+    now: i64,
+) -> Result<SyncStatistics> {
+    let days = period_days(period)?;
+    let today = DateTime::from_timestamp(now, 0)
+        .ok_or_else(|| SyncError::ValidationError(format!("Invalid timestamp: {}", now)))?
+        .date_naive();
+    let first_day = today - Duration::days(days - 1);
+    let start_time = first_day
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is valid")
+        .and_utc()
+        .timestamp();
+    let until = now + 1;
+
+    let conn = db.conn()?;
+    let params = rusqlite::params![start_time, until, sync_folder_id];
+
+    let mut daily: HashMap<String, DailySyncStats> = HashMap::new();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT DATE(created_at, 'unixepoch'),
+                    COALESCE(SUM(CASE WHEN action = 'upload' AND status IN ('success', 'verified')
+                                      THEN file_size END), 0),
+                    COALESCE(SUM(CASE WHEN action = 'download' AND status IN ('success', 'verified')
+                                      THEN file_size END), 0),
+                    COALESCE(SUM(action = 'upload' AND status IN ('success', 'verified')), 0),
+                    COALESCE(SUM(action = 'download' AND status IN ('success', 'verified')), 0),
+                    COALESCE(SUM(status IN ('failed', 'corrupt')), 0)
+             FROM sync_logs
+             WHERE is_delete = 0 AND created_at >= ?1 AND created_at < ?2
+               AND (?3 IS NULL OR sync_folder_id = ?3)
+             GROUP BY 1",
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok(DailySyncStats {
+                date: row.get(0)?,
+                bytes_uploaded: row.get(1)?,
+                bytes_downloaded: row.get(2)?,
+                files_uploaded: row.get(3)?,
+                files_downloaded: row.get(4)?,
+                errors: row.get(5)?,
+                sessions: 0,
+            })
+        })
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query daily transfers: {}", e)))?;
+    for row in rows {
+        let row = row.map_err(|e| {
+            SyncError::DatabaseError(format!("Failed to parse query results: {}", e))
+        })?;
+        daily.insert(row.date.clone(), row);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT DATE(started_at, 'unixepoch'), COUNT(*)
+             FROM sync_sessions
+             WHERE is_delete = 0 AND started_at >= ?1 AND started_at < ?2
+               AND (?3 IS NULL OR sync_folder_id = ?3)
+             GROUP BY 1",
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query daily sessions: {}", e)))?;
+    for row in rows {
+        let (date, sessions) = row.map_err(|e| {
+            SyncError::DatabaseError(format!("Failed to parse query results: {}", e))
+        })?;
+        daily
+            .entry(date.clone())
+            .or_insert_with(|| empty_day(date))
+            .sessions = sessions;
+    }
+
+    let mut statistics = conn
+        .query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(status = 'failed'), 0),
+                    COALESCE(SUM(files_uploaded), 0),
+                    COALESCE(SUM(files_downloaded), 0),
+                    COALESCE(SUM(files_deleted), 0),
+                    COALESCE(SUM(files_conflict), 0),
+                    COALESCE(SUM(total_bytes), 0),
+                    AVG(CASE WHEN completed_at IS NOT NULL THEN completed_at - started_at END)
+             FROM sync_sessions
+             WHERE is_delete = 0 AND started_at >= ?1 AND started_at < ?2
+               AND (?3 IS NULL OR sync_folder_id = ?3)",
+            params,
+            |row| {
+                Ok(SyncStatistics {
+                    period: period.to_string(),
+                    start_time,
+                    end_time: now,
+                    total_sessions: row.get(0)?,
+                    failed_sessions: row.get(1)?,
+                    files_uploaded: row.get(2)?,
+                    files_downloaded: row.get(3)?,
+                    files_deleted: row.get(4)?,
+                    files_conflict: row.get(5)?,
+                    bytes_transferred: row.get(6)?,
+                    average_duration_secs: row.get(7)?,
+                    ..Default::default()
+                })
+            },
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync sessions: {}", e)))?;
+
+    let (total_operations, failed_operations): (i64, i64) = conn
+        .query_row(
+            "SELECT COALESCE(SUM(status != 'skipped'), 0),
+                    COALESCE(SUM(status IN ('failed', 'corrupt')), 0)
+             FROM sync_logs
+             WHERE is_delete = 0 AND created_at >= ?1 AND created_at < ?2
+               AND (?3 IS NULL OR sync_folder_id = ?3)",
+            params,
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync logs: {}", e)))?;
+
+    statistics.total_operations = total_operations;
+    statistics.failed_operations = failed_operations;
+    statistics.error_rate = if total_operations > 0 {
+        failed_operations as f64 / total_operations as f64
+    } else {
+        0.0
+    };
+    statistics.daily = first_day
+        .iter_days()
+        .take(days as usize)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            daily.remove(&date).unwrap_or_else(|| empty_day(date))
+        })
+        .collect();
+
+    Ok(statistics)
+}
+
+fn empty_day(date: String) -> DailySyncStats {
+    DailySyncStats {
+        date,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::fs;
+    use uuid::Uuid;
+
+    /// 日期当天 0 点的 Unix 时间戳
+    fn midnight(date: &str) -> i64 {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    }
+
+    #[tokio::test]
+    async fn test_collect() {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let day1 = midnight("2024-03-01");
+        let day3 = midnight("2024-03-03");
+        let before = midnight("2024-02-20");
+        {
+            let conn = db.conn().unwrap();
+            conn.execute_batch(include_str!("../../migrations/001_initial.sql"))
+                .expect("Failed to run migration 001");
+            conn.execute(
+                "INSERT INTO sync_logs (sync_folder_id, file_path, action, status, file_size, created_at) VALUES
+                     (1, 'a', 'upload', 'success', 100, ?1),
+                     (1, 'b', 'upload', 'verified', 50, ?1),
+                     (1, 'c', 'download', 'success', 30, ?2),
+                     (1, 'd', 'download', 'failed', 70, ?2),
+                     (1, 'e', 'upload', 'skipped', NULL, ?2),
+                     (2, 'f', 'upload', 'success', 999, ?1),
+                     (1, 'g', 'upload', 'success', 999, ?3)",
+                rusqlite::params![day1 + 60, day3 + 60, before],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO sync_sessions (sync_folder_id, status, started_at, completed_at, files_uploaded, total_bytes) VALUES
+                     (1, 'completed', ?1, ?1 + 10, 2, 150),
+                     (1, 'failed', ?2, ?2 + 30, 0, 30),
+                     (1, 'running', ?2, NULL, 0, 0)",
+                rusqlite::params![day1 + 60, day3 + 60],
+            )
+            .unwrap();
+        }
+
+        let stats = collect(&db, Some(1), statistics_period::WEEK, day3 + 3600)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.daily.len(), 7);
+        assert_eq!(stats.daily[0].date, "2024-02-26");
+        assert_eq!(stats.daily[4].date, "2024-03-01");
+        assert_eq!(stats.daily[4].bytes_uploaded, 150);
+        assert_eq!(stats.daily[4].files_uploaded, 2);
+        assert_eq!(stats.daily[4].sessions, 1);
+        assert_eq!(stats.daily[5].sessions, 0);
+        assert_eq!(stats.daily[6].bytes_downloaded, 30);
+        assert_eq!(stats.daily[6].errors, 1);
+        assert_eq!(stats.daily[6].sessions, 2);
+
+        assert_eq!(stats.total_sessions, 3);
+        assert_eq!(stats.failed_sessions, 1);
+        assert_eq!(stats.files_uploaded, 2);
+        assert_eq!(stats.bytes_transferred, 180);
+        assert_eq!(stats.average_duration_secs, Some(20.0));
+        assert_eq!(stats.total_operations, 4);
+        assert_eq!(stats.failed_operations, 1);
+        assert!((stats.error_rate - 0.25).abs() < f64::EPSILON);
+
+        let all = collect(&db, None, statistics_period::WEEK, day3 + 3600)
+            .await
+            .unwrap();
+        assert_eq!(all.daily[4].bytes_uploaded, 1149);
+
+        assert!(matches!(
+            collect(&db, None, "decade", day3).await,
+            Err(SyncError::ValidationError(_))
+        ));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
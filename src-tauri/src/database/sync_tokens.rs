@@ -0,0 +1,108 @@
+/// 远程同步令牌数据库操作模块
+///
+/// 保存每个同步文件夹最近一次 sync-collection REPORT 返回的 sync-token，
+/// 下次同步时只需请求该令牌之后的变更
+use crate::database::Database;
+use crate::{Result, SyncError};
+use rusqlite::OptionalExtension;
+
+/// 获取文件夹的同步令牌
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+///
+/// # 返回
+/// - Ok(Some(token)): 已有令牌
+/// - Ok(None): 尚未同步过或令牌已被清除
+pub async fn get(db: &Database, folder_id: &str) -> Result<Option<String>> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        "SELECT sync_token FROM remote_sync_tokens WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query sync token: {}", e)))
+}
+
+/// 保存文件夹的同步令牌（已存在时覆盖）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID
+/// - token: 服务器返回的 sync-token
+pub async fn save(db: &Database, folder_id: &str, token: &str) -> Result<()> {
+    let conn = db.conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO remote_sync_tokens (folder_id, sync_token, updated_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(folder_id) DO UPDATE SET
+             sync_token = excluded.sync_token,
+             updated_at = excluded.updated_at",
+        rusqlite::params![folder_id, token, now],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to save sync token: {}", e)))?;
+
+    Ok(())
+}
+
+/// 清除文件夹的同步令牌
+///
+/// 令牌失效或文件夹被移除时调用，下次同步将执行完整扫描
+pub async fn clear(db: &Database, folder_id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM remote_sync_tokens WHERE folder_id = ?1",
+        rusqlite::params![folder_id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to clear sync token: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/005_sync_tokens.sql"))
+            .expect("Failed to run migration 005");
+
+        (test_dir, db)
+    }
+
+    #[tokio::test]
+    async fn test_save_get_clear() {
+        let (test_dir, db) = create_test_db();
+
+        assert_eq!(get(&db, "folder-1").await.unwrap(), None);
+
+        save(&db, "folder-1", "token-1").await.unwrap();
+        save(&db, "folder-1", "token-2").await.unwrap();
+        assert_eq!(
+            get(&db, "folder-1").await.unwrap().as_deref(),
+            Some("token-2")
+        );
+
+        clear(&db, "folder-1").await.unwrap();
+        assert_eq!(get(&db, "folder-1").await.unwrap(), None);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,439 @@
+/// 传输队列数据库操作模块
+///
+/// 记录上传/下载任务的排队状态、已传输字节数和断点续传信息，
+/// 应用重启后可据此恢复未完成的传输
+use crate::constants::transfer_status;
+use crate::database::{Database, Transfer};
+use crate::{Result, SyncError};
+
+/// transfers 表的查询列（顺序与 `row_to_transfer` 对应）
+const TRANSFER_COLUMNS: &str = "id, sync_folder_id, server_id, direction, local_path, \
+     remote_path, status, bytes_total, bytes_done, resume_token, error_message, \
+     created_at, updated_at";
+
+/// 将查询结果行转换为传输记录
+fn row_to_transfer(row: &rusqlite::Row<'_>) -> rusqlite::Result<Transfer> {
+    Ok(Transfer {
+        id: row.get(0)?,
+        sync_folder_id: row.get(1)?,
+        server_id: row.get(2)?,
+        direction: row.get(3)?,
+        local_path: row.get(4)?,
+        remote_path: row.get(5)?,
+        status: row.get(6)?,
+        bytes_total: row.get(7)?,
+        bytes_done: row.get(8)?,
+        resume_token: row.get(9)?,
+        error_message: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+/// 将传输加入队列
+///
+/// # 参数
+/// - sync_folder_id: 所属同步文件夹 ID（手动传输为 None）
+/// - server_id: 服务器 ID
+/// - direction: 传输方向（upload, download）
+/// - local_path: 本地路径
+/// - remote_path: 远程路径
+/// - bytes_total: 总字节数（未知时为 None）
+///
+/// # 返回
+/// - Ok(i64): 新记录 ID
+pub async fn enqueue(
+    db: &Database,
+    sync_folder_id: Option<&str>,
+    server_id: &str,
+    direction: &str,
+    local_path: &str,
+    remote_path: &str,
+    bytes_total: Option<i64>,
+) -> Result<i64> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO transfers (
+             sync_folder_id, server_id, direction, local_path, remote_path, status, bytes_total
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            sync_folder_id,
+            server_id,
+            direction,
+            local_path,
+            remote_path,
+            transfer_status::QUEUED,
+            bytes_total
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to enqueue transfer: {}", e)))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// 获取传输记录
+pub async fn get(db: &Database, id: i64) -> Result<Option<Transfer>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM transfers WHERE id = ?1 LIMIT 1",
+            TRANSFER_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let mut rows = stmt
+        .query_map(rusqlite::params![id], row_to_transfer)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query transfer: {}", e)))?;
+
+    rows.next()
+        .transpose()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read transfer: {}", e)))
+}
+
+/// 获取传输列表（按入队顺序）
+///
+/// # 参数
+/// - status: 只返回指定状态的传输（None 表示全部）
+pub async fn list(db: &Database, status: Option<&str>) -> Result<Vec<Transfer>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM transfers
+             WHERE ?1 IS NULL OR status = ?1
+             ORDER BY id",
+            TRANSFER_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let transfers = stmt
+        .query_map(rusqlite::params![status], row_to_transfer)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query transfers: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read transfer: {}", e)))?;
+
+    Ok(transfers)
+}
+
+/// 获取同步文件夹的传输列表（按入队顺序）
+///
+/// # 参数
+/// - sync_folder_id: 同步文件夹 ID
+pub async fn list_for_folder(db: &Database, sync_folder_id: &str) -> Result<Vec<Transfer>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM transfers WHERE sync_folder_id = ?1 ORDER BY id",
+            TRANSFER_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let transfers = stmt
+        .query_map(rusqlite::params![sync_folder_id], row_to_transfer)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query transfers: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read transfer: {}", e)))?;
+
+    Ok(transfers)
+}
+
+/// 获取有排队中传输的同步文件夹 ID
+pub async fn folders_with_queued(db: &Database) -> Result<Vec<String>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT sync_folder_id FROM transfers
+             WHERE sync_folder_id IS NOT NULL AND status = ?1
+             ORDER BY sync_folder_id",
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let folders = stmt
+        .query_map(rusqlite::params![transfer_status::QUEUED], |row| row.get(0))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query transfers: {}", e)))?
+        .collect::<rusqlite::Result<Vec<String>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read transfer: {}", e)))?;
+
+    Ok(folders)
+}
+
+/// 更新传输进度
+///
+/// # 参数
+/// - id: 传输记录 ID
+/// - bytes_done: 已传输字节数
+/// - resume_token: 断点续传信息（None 时保留原值）
+pub async fn update_progress(
+    db: &Database,
+    id: i64,
+    bytes_done: i64,
+    resume_token: Option<&str>,
+) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "UPDATE transfers SET
+             bytes_done = ?2,
+             resume_token = COALESCE(?3, resume_token),
+             updated_at = STRFTIME('%s', 'now')
+         WHERE id = ?1",
+        rusqlite::params![id, bytes_done, resume_token],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to update transfer: {}", e)))?;
+
+    Ok(())
+}
+
+/// 更新传输状态
+///
+/// # 参数
+/// - id: 传输记录 ID
+/// - status: 新状态（transfer_status 常量）
+/// - error_message: 失败原因（非失败状态时传 None 以清除）
+pub async fn set_status(
+    db: &Database,
+    id: i64,
+    status: &str,
+    error_message: Option<&str>,
+) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "UPDATE transfers SET
+             status = ?2,
+             error_message = ?3,
+             updated_at = STRFTIME('%s', 'now')
+         WHERE id = ?1",
+        rusqlite::params![id, status, error_message],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to update transfer: {}", e)))?;
+
+    Ok(())
+}
+
+/// 将运行中的传输重新放回队列
+///
+/// 在应用启动时调用，上次异常退出时仍在运行的传输保留已传输字节数与断点续传信息
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - updated_before: 只处理早于该时间更新的传输（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(usize): 重新入队的传输数
+pub async fn requeue_interrupted(db: &Database, updated_before: i64) -> Result<usize> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "UPDATE transfers SET status = ?1, updated_at = STRFTIME('%s', 'now')
+         WHERE status = ?2 AND updated_at < ?3",
+        rusqlite::params![
+            transfer_status::QUEUED,
+            transfer_status::RUNNING,
+            updated_before
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to requeue transfers: {}", e)))
+}
+
+/// 删除传输记录
+pub async fn delete(db: &Database, id: i64) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute("DELETE FROM transfers WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to delete transfer: {}", e)))?;
+
+    Ok(())
+}
+
+/// 删除同步文件夹中早于指定时间更新的传输记录
+///
+/// 同步会话完成时调用，清理之前会话遗留、本次不再需要的传输
+///
+/// # 参数
+/// - sync_folder_id: 同步文件夹 ID
+/// - updated_before: 只删除早于该时间更新的传输（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(usize): 删除的记录数
+pub async fn delete_stale(
+    db: &Database,
+    sync_folder_id: &str,
+    updated_before: i64,
+) -> Result<usize> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM transfers WHERE sync_folder_id = ?1 AND updated_at < ?2",
+        rusqlite::params![sync_folder_id, updated_before],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete transfers: {}", e)))
+}
+
+/// 删除已完成的传输记录
+///
+/// # 返回
+/// - Ok(usize): 删除的记录数
+pub async fn delete_completed(db: &Database) -> Result<usize> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM transfers WHERE status = ?1",
+        rusqlite::params![transfer_status::COMPLETED],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete transfers: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::transfer_direction;
+    use crate::database::folder_records::{self, tests::create_folder, tests::create_test_db};
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_transfer_lifecycle() {
+        let (test_dir, db) = create_test_db();
+        folder_records::upsert(&db, &create_folder("folder-1"))
+            .await
+            .unwrap();
+
+        let id = enqueue(
+            &db,
+            Some("folder-1"),
+            "server-1",
+            transfer_direction::UPLOAD,
+            "/home/user/Documents/a.bin",
+            "/Documents/a.bin",
+            Some(1000),
+        )
+        .await
+        .unwrap();
+        let manual = enqueue(
+            &db,
+            None,
+            "server-1",
+            transfer_direction::DOWNLOAD,
+            "/tmp/b.bin",
+            "/b.bin",
+            None,
+        )
+        .await
+        .unwrap();
+
+        set_status(&db, id, transfer_status::RUNNING, None)
+            .await
+            .unwrap();
+        update_progress(&db, id, 400, Some("session-1"))
+            .await
+            .unwrap();
+        update_progress(&db, id, 600, None).await.unwrap();
+
+        let transfer = get(&db, id).await.unwrap().unwrap();
+        assert_eq!(transfer.status, transfer_status::RUNNING);
+        assert_eq!(transfer.bytes_done, 600);
+        assert_eq!(transfer.resume_token.as_deref(), Some("session-1"));
+
+        let queued = list(&db, Some(transfer_status::QUEUED)).await.unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, manual);
+        assert_eq!(queued[0].sync_folder_id, None);
+
+        // 异常退出后重新入队，保留进度
+        let updated_before = chrono::Utc::now().timestamp() + 1;
+        assert_eq!(requeue_interrupted(&db, updated_before).await.unwrap(), 1);
+        let transfer = get(&db, id).await.unwrap().unwrap();
+        assert_eq!(transfer.status, transfer_status::QUEUED);
+        assert_eq!(transfer.bytes_done, 600);
+
+        set_status(&db, id, transfer_status::COMPLETED, None)
+            .await
+            .unwrap();
+        assert_eq!(delete_completed(&db).await.unwrap(), 1);
+        assert_eq!(list(&db, None).await.unwrap().len(), 1);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_folder_queue() {
+        let (test_dir, db) = create_test_db();
+        for id in ["folder-1", "folder-2"] {
+            folder_records::upsert(&db, &create_folder(id))
+                .await
+                .unwrap();
+        }
+
+        let enqueue_in = |folder: &'static str, path: &'static str| {
+            enqueue(
+                &db,
+                Some(folder),
+                "server-1",
+                transfer_direction::DOWNLOAD,
+                path,
+                path,
+                Some(10),
+            )
+        };
+        let a = enqueue_in("folder-1", "/a").await.unwrap();
+        let b = enqueue_in("folder-1", "/b").await.unwrap();
+        let c = enqueue_in("folder-2", "/c").await.unwrap();
+        set_status(&db, c, transfer_status::FAILED, Some("boom"))
+            .await
+            .unwrap();
+
+        let ids: Vec<i64> = list_for_folder(&db, "folder-1")
+            .await
+            .unwrap()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![a, b]);
+        assert_eq!(folders_with_queued(&db).await.unwrap(), vec!["folder-1"]);
+
+        delete(&db, a).await.unwrap();
+        assert!(get(&db, a).await.unwrap().is_none());
+
+        let updated_before = chrono::Utc::now().timestamp() + 1;
+        assert_eq!(
+            delete_stale(&db, "folder-1", updated_before).await.unwrap(),
+            1
+        );
+        assert!(folders_with_queued(&db).await.unwrap().is_empty());
+        assert_eq!(list(&db, None).await.unwrap().len(), 1);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_delete_folder_cascades() {
+        let (test_dir, db) = create_test_db();
+        folder_records::upsert(&db, &create_folder("folder-1"))
+            .await
+            .unwrap();
+
+        enqueue(
+            &db,
+            Some("folder-1"),
+            "server-1",
+            transfer_direction::UPLOAD,
+            "/a",
+            "/a",
+            None,
+        )
+        .await
+        .unwrap();
+
+        folder_records::delete(&db, "folder-1").await.unwrap();
+        assert!(list(&db, None).await.unwrap().is_empty());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
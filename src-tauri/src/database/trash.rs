@@ -0,0 +1,193 @@
+/// 回收站数据库操作模块
+///
+/// 记录被移动到回收站的文件的原始路径、删除时间和所属文件夹
+use crate::database::{Database, TrashItem};
+use crate::{Result, SyncError};
+use rusqlite::OptionalExtension;
+
+/// trash_items 表的查询列（顺序与 `row_to_item` 对应）
+const TRASH_COLUMNS: &str =
+    "id, folder_id, original_path, trash_path, size, is_directory, deleted_at";
+
+/// 将查询结果行转换为回收站条目
+fn row_to_item(row: &rusqlite::Row<'_>) -> rusqlite::Result<TrashItem> {
+    Ok(TrashItem {
+        id: row.get(0)?,
+        folder_id: row.get(1)?,
+        original_path: row.get(2)?,
+        trash_path: row.get(3)?,
+        size: row.get(4)?,
+        is_directory: row.get::<_, i32>(5)? != 0,
+        deleted_at: row.get(6)?,
+    })
+}
+
+/// 插入回收站条目
+pub async fn insert(db: &Database, item: &TrashItem) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        &format!(
+            "INSERT INTO trash_items ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            TRASH_COLUMNS
+        ),
+        rusqlite::params![
+            item.id,
+            item.folder_id,
+            item.original_path,
+            item.trash_path,
+            item.size,
+            item.is_directory as i32,
+            item.deleted_at,
+        ],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to insert trash item: {}", e)))?;
+
+    Ok(())
+}
+
+/// 查询回收站条目
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder_id: 同步文件夹 ID（None 表示所有文件夹）
+///
+/// # 返回
+/// 按删除时间倒序排列的条目列表
+pub async fn list(db: &Database, folder_id: Option<&str>) -> Result<Vec<TrashItem>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM trash_items
+             WHERE ?1 IS NULL OR folder_id = ?1
+             ORDER BY deleted_at DESC",
+            TRASH_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let items = stmt
+        .query_map(rusqlite::params![folder_id], row_to_item)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query trash items: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read trash item: {}", e)))?;
+
+    Ok(items)
+}
+
+/// 根据 ID 获取回收站条目
+///
+/// # 返回
+/// - Ok(TrashItem): 找到条目
+/// - Err(SyncError::NotFound): 条目不存在
+pub async fn get_by_id(db: &Database, id: &str) -> Result<TrashItem> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM trash_items WHERE id = ?1", TRASH_COLUMNS),
+        rusqlite::params![id],
+        row_to_item,
+    )
+    .optional()
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to query trash item: {}", e)))?
+    .ok_or_else(|| SyncError::NotFound(format!("Trash item not found: {}", id)))
+}
+
+/// 查询删除时间早于指定时间的条目
+pub async fn list_expired(db: &Database, deleted_before: i64) -> Result<Vec<TrashItem>> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM trash_items WHERE deleted_at < ?1",
+            TRASH_COLUMNS
+        ))
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let items = stmt
+        .query_map(rusqlite::params![deleted_before], row_to_item)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query trash items: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to read trash item: {}", e)))?;
+
+    Ok(items)
+}
+
+/// 删除回收站条目记录
+pub async fn delete(db: &Database, id: &str) -> Result<()> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM trash_items WHERE id = ?1",
+        rusqlite::params![id],
+    )
+    .map_err(|e| SyncError::DatabaseError(format!("Failed to delete trash item: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// 创建测试用的临时数据库
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/006_trash_items.sql"))
+            .expect("Failed to run migration 006");
+
+        (test_dir, db)
+    }
+
+    fn create_item(folder_id: &str, deleted_at: i64) -> TrashItem {
+        let id = Uuid::new_v4().to_string();
+        TrashItem {
+            trash_path: format!("/trash/{}", id),
+            id,
+            folder_id: folder_id.to_string(),
+            original_path: "/sync/a.txt".to_string(),
+            size: 10,
+            is_directory: false,
+            deleted_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_list_delete() {
+        let (test_dir, db) = create_test_db();
+
+        let old = create_item("folder-1", 100);
+        let new = create_item("folder-1", 200);
+        let other = create_item("folder-2", 300);
+        for item in [&old, &new, &other] {
+            insert(&db, item).await.unwrap();
+        }
+
+        let all = list(&db, None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0], other);
+
+        let folder = list(&db, Some("folder-1")).await.unwrap();
+        assert_eq!(folder, vec![new.clone(), old.clone()]);
+
+        let expired = list_expired(&db, 150).await.unwrap();
+        assert_eq!(expired, vec![old.clone()]);
+
+        delete(&db, &old.id).await.unwrap();
+        assert!(matches!(
+            get_by_id(&db, &old.id).await,
+            Err(SyncError::NotFound(_))
+        ));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
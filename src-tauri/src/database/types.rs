@@ -0,0 +1,1253 @@
+/// LightSync 数据库类型定义模块
+///
+/// 提供数据库表对应的数据结构
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    auth_type, ip_version, timeout_range, DEFAULT_CONNECT_TIMEOUT, DEFAULT_PAGE_SIZE,
+    DEFAULT_READ_TIMEOUT, MAX_PAGE_SIZE, RESERVED_CUSTOM_HEADERS, USERNAME_PLACEHOLDER,
+};
+
+/// 文件元数据结构体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub id: Option<i64>,
+    pub path: String,
+    pub hash: Option<String>,
+    pub size: i64,
+    pub modified_at: i64,
+    pub synced_at: Option<i64>,
+    pub sync_folder_id: i64,
+    pub is_directory: bool,
+    pub status: String,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    /// 同步时远程文件的大小（与本地大小相同时为 None，例如未压缩上传）
+    #[serde(default)]
+    pub remote_size: Option<i64>,
+    /// 同步时本地文件的 inode（仅 Unix，用于识别重命名）
+    #[serde(default)]
+    pub inode: Option<i64>,
+}
+
+/// 同步日志结构体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncLog {
+    pub id: Option<i64>,
+    pub sync_folder_id: i64,
+    pub file_path: String,
+    pub action: String,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub file_size: Option<i64>,
+    pub duration_ms: Option<i64>,
+    /// 传输的平均速度（字节/秒），仅完成的上传、下载记录
+    pub bytes_per_sec: Option<i64>,
+    pub created_at: Option<i64>,
+}
+
+/// 同步会话结构体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncSession {
+    pub id: Option<i64>,
+    pub sync_folder_id: i64,
+    pub status: String,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+    pub files_uploaded: i32,
+    pub files_downloaded: i32,
+    pub files_deleted: i32,
+    pub files_conflict: i32,
+    pub errors_count: i32,
+    pub total_bytes: i64,
+    pub error_message: Option<String>,
+}
+
+/// 回收站条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    /// 条目 ID（UUID）
+    pub id: String,
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 原始绝对路径
+    pub original_path: String,
+    /// 回收站中的绝对路径
+    pub trash_path: String,
+    /// 大小（字节，目录为 0）
+    pub size: i64,
+    /// 是否为目录
+    pub is_directory: bool,
+    /// 删除时间（Unix 时间戳，秒）
+    pub deleted_at: i64,
+}
+
+/// 文件历史版本
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersion {
+    /// 版本 ID
+    pub id: i64,
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 文件绝对路径
+    pub file_path: String,
+    /// 内容 SHA-256
+    pub hash: String,
+    /// 文件大小（字节）
+    pub size: i64,
+    /// 保存时间（Unix 时间戳，秒）
+    pub created_at: i64,
+}
+
+/// 服务器健康检查记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerHealthCheck {
+    /// 记录 ID
+    pub id: i64,
+    /// 服务器 ID
+    pub server_id: String,
+    /// 检查时间（Unix 时间戳，秒）
+    pub checked_at: i64,
+    /// 是否连接成功
+    pub success: bool,
+    /// 连接测试耗时（毫秒）
+    pub latency_ms: Option<i64>,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+/// 远程目录快照
+///
+/// 对应数据库中的 remote_snapshots 表，用于轮询判断远程目录是否发生变化
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSnapshot {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 上次同步成功时的远程目录指纹（尚未同步成功时为 None）
+    pub fingerprint: Option<String>,
+    /// 指纹包含的远程条目数
+    pub entry_count: i64,
+    /// 最后一次轮询时间（Unix 时间戳，秒）
+    pub checked_at: i64,
+}
+
+/// 远程目录树缓存条目
+///
+/// 对应数据库中的 remote_cache 表，用于浏览远程目录时立即返回上次已知的内容
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCacheEntry {
+    /// 相对于同步根目录的路径
+    pub path: String,
+    /// 是否为目录
+    pub is_directory: bool,
+    /// 文件大小
+    pub size: u64,
+    /// 最后修改时间（Unix 时间戳，秒）
+    pub modified: Option<i64>,
+    /// ETag（服务器未返回或由同步更新的条目为 None）
+    pub etag: Option<String>,
+    /// 写入缓存的时间（Unix 时间戳，秒）
+    pub cached_at: i64,
+}
+
+/// 同步文件夹记录
+///
+/// 对应数据库中的 sync_folders 表，由配置文件中的同步文件夹镜像而来
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFolderRecord {
+    /// 同步文件夹 ID（SyncFolderConfig.id）
+    pub id: String,
+    /// 文件夹名称
+    pub name: String,
+    /// 本地路径
+    pub local_path: String,
+    /// 远程路径
+    pub remote_path: String,
+    /// 关联的服务器 ID
+    pub server_id: String,
+    /// 同步方向
+    pub sync_direction: String,
+    /// 冲突解决策略
+    pub conflict_resolution: String,
+    /// 是否启用自动同步
+    pub auto_sync: bool,
+    /// 创建时间（Unix 时间戳，秒）
+    pub created_at: i64,
+    /// 更新时间（Unix 时间戳，秒）
+    pub updated_at: i64,
+}
+
+/// 传输队列条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    /// 记录 ID
+    pub id: i64,
+    /// 所属同步文件夹 ID（手动传输为 None）
+    pub sync_folder_id: Option<String>,
+    /// 服务器 ID
+    pub server_id: String,
+    /// 传输方向（upload, download）
+    pub direction: String,
+    /// 本地路径
+    pub local_path: String,
+    /// 远程路径
+    pub remote_path: String,
+    /// 队列状态（queued, running, paused, completed, failed）
+    pub status: String,
+    /// 总字节数（未知时为 None）
+    pub bytes_total: Option<i64>,
+    /// 已传输字节数
+    pub bytes_done: i64,
+    /// 断点续传信息
+    pub resume_token: Option<String>,
+    /// 失败原因
+    pub error_message: Option<String>,
+    /// 创建时间（Unix 时间戳，秒）
+    pub created_at: i64,
+    /// 更新时间（Unix 时间戳，秒）
+    pub updated_at: i64,
+}
+
+/// 同步冲突记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Conflict {
+    /// 记录 ID
+    pub id: i64,
+    /// 所属同步文件夹 ID
+    pub sync_folder_id: String,
+    /// 相对于同步根目录的路径
+    pub file_path: String,
+    /// 本地文件大小
+    pub local_size: Option<i64>,
+    /// 本地修改时间（Unix 时间戳，秒）
+    pub local_modified: Option<i64>,
+    /// 远程文件大小
+    pub remote_size: Option<i64>,
+    /// 远程修改时间（Unix 时间戳，秒）
+    pub remote_modified: Option<i64>,
+    /// 处理状态（pending, resolved）
+    pub status: String,
+    /// 采用的解决方式（keep-local, keep-remote, keep-both）
+    pub resolution: Option<String>,
+    /// 发现时间（Unix 时间戳，秒）
+    pub detected_at: i64,
+    /// 解决时间（Unix 时间戳，秒）
+    pub resolved_at: Option<i64>,
+}
+
+/// 查询过滤器
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryFilter {
+    pub sync_folder_id: Option<i64>,
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// 起始时间（Unix 时间戳，秒，包含）
+    pub start_time: Option<i64>,
+    /// 结束时间（Unix 时间戳，秒，包含）
+    pub end_time: Option<i64>,
+}
+
+impl QueryFilter {
+    /// 生成 WHERE 子句及其参数
+    ///
+    /// # 参数
+    /// - time_column: 用于日期范围过滤的列名（如 created_at、started_at）
+    ///
+    /// # 返回
+    /// (WHERE 子句, 参数列表)，子句始终包含软删除过滤条件
+    pub fn where_clause(&self, time_column: &str) -> (String, Vec<rusqlite::types::Value>) {
+        let mut conditions = vec!["is_delete = 0".to_string()];
+        let mut params: Vec<rusqlite::types::Value> = Vec::new();
+
+        if let Some(folder_id) = self.sync_folder_id {
+            params.push(folder_id.into());
+            conditions.push(format!("sync_folder_id = ?{}", params.len()));
+        }
+        if let Some(status) = &self.status {
+            params.push(status.clone().into());
+            conditions.push(format!("status = ?{}", params.len()));
+        }
+        if let Some(start_time) = self.start_time {
+            params.push(start_time.into());
+            conditions.push(format!("{} >= ?{}", time_column, params.len()));
+        }
+        if let Some(end_time) = self.end_time {
+            params.push(end_time.into());
+            conditions.push(format!("{} <= ?{}", time_column, params.len()));
+        }
+
+        (format!("WHERE {}", conditions.join(" AND ")), params)
+    }
+
+    /// 生成 LIMIT/OFFSET 子句
+    ///
+    /// 未指定 limit 时使用默认分页大小，limit 超过上限时截断
+    pub fn limit_clause(&self) -> String {
+        let limit = self
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let offset = self.offset.unwrap_or(0).max(0);
+        format!("LIMIT {} OFFSET {}", limit, offset)
+    }
+}
+
+/// 分页查询结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PagedResult<T> {
+    /// 当前页数据
+    pub items: Vec<T>,
+    /// 满足过滤条件的总记录数
+    pub total: i64,
+}
+
+/// 数据库统计信息结构体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseStats {
+    /// 文件元数据总数（不含已删除）
+    pub total_files: i64,
+    /// 同步日志总数
+    pub total_logs: i64,
+    /// 同步会话总数
+    pub total_sessions: i64,
+    /// 待同步的文件数
+    pub pending_files: i64,
+    /// 已同步的文件数
+    pub synced_files: i64,
+    /// 冲突的文件数
+    pub conflict_files: i64,
+    /// 数据库文件在磁盘上的大小（字节，含 WAL）
+    pub database_size_bytes: i64,
+}
+
+/// 每日同步统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySyncStats {
+    /// 日期（UTC，YYYY-MM-DD）
+    pub date: String,
+    /// 上传的字节数
+    pub bytes_uploaded: i64,
+    /// 下载的字节数
+    pub bytes_downloaded: i64,
+    /// 上传的文件数
+    pub files_uploaded: i64,
+    /// 下载的文件数
+    pub files_downloaded: i64,
+    /// 失败的文件操作数
+    pub errors: i64,
+    /// 开始的同步会话数
+    pub sessions: i64,
+}
+
+/// 同步统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatistics {
+    /// 时间范围（week, month, quarter, year）
+    pub period: String,
+    /// 统计开始时间（Unix 时间戳，秒）
+    pub start_time: i64,
+    /// 统计结束时间（Unix 时间戳，秒）
+    pub end_time: i64,
+    /// 每日统计（按日期排序，没有数据的日期为 0）
+    pub daily: Vec<DailySyncStats>,
+    /// 同步会话数
+    pub total_sessions: i64,
+    /// 失败的同步会话数
+    pub failed_sessions: i64,
+    /// 上传的文件数
+    pub files_uploaded: i64,
+    /// 下载的文件数
+    pub files_downloaded: i64,
+    /// 删除的文件数
+    pub files_deleted: i64,
+    /// 产生冲突的文件数
+    pub files_conflict: i64,
+    /// 传输的字节数
+    pub bytes_transferred: i64,
+    /// 已结束会话的平均耗时（秒，没有已结束的会话时为 None）
+    pub average_duration_secs: Option<f64>,
+    /// 文件操作数（不含跳过的操作）
+    pub total_operations: i64,
+    /// 失败的文件操作数（含校验失败）
+    pub failed_operations: i64,
+    /// 文件操作错误率（0.0 ~ 1.0）
+    pub error_rate: f64,
+}
+
+/// 数据库维护结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    /// 删除的同步日志数
+    pub logs_deleted: usize,
+    /// 删除的同步会话数
+    pub sessions_deleted: usize,
+    /// 维护前的数据库大小（字节）
+    pub size_before_bytes: i64,
+    /// 维护后的数据库大小（字节）
+    pub size_after_bytes: i64,
+    /// 回收的空间（字节）
+    pub reclaimed_bytes: i64,
+}
+
+/// WebDAV 服务器配置结构体
+///
+/// 对应数据库中的 webdav_servers 表，是服务器配置的唯一来源（应用配置中不保存服务器列表）
+/// 密码不存储在此结构中，而是存储在系统 Keyring 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavServerConfig {
+    /// 服务器唯一标识符 (UUID)
+    pub id: String,
+
+    /// 服务器名称
+    pub name: String,
+
+    /// WebDAV 服务器 URL
+    pub url: String,
+
+    /// 用户名
+    pub username: String,
+
+    /// 是否使用 HTTPS
+    pub use_https: bool,
+
+    /// 请求超时时间（秒）
+    ///
+    /// 限制单个非流式请求（PROPFIND、DELETE 等）的总时长；上传和下载不受总时长限制，
+    /// 只受 `connect_timeout` 与 `read_timeout` 限制
+    pub timeout: u32,
+
+    /// 建立连接（包括 TLS 握手）的超时时间（秒）
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout: u32,
+
+    /// 读取超时时间（秒）：上传和下载中连续没有收发数据超过该时间时中止，避免在失效的连接上无限等待
+    #[serde(default = "default_read_timeout")]
+    pub read_timeout: u32,
+
+    /// 最后连接测试时间（Unix 时间戳，秒）
+    pub last_test_at: Option<i64>,
+
+    /// 最后连接测试状态（success, failed, unknown）
+    pub last_test_status: String,
+
+    /// 最后连接测试错误信息
+    pub last_test_error: Option<String>,
+
+    /// 服务器类型（自动检测，如 nextcloud, owncloud, generic）
+    pub server_type: String,
+
+    /// 认证方式（basic: 账户密码, app_password: 通过 Nextcloud 登录流程获取的应用密码）
+    #[serde(default = "default_auth_type")]
+    pub auth_type: String,
+
+    /// 是否接受无效证书（自签名证书等），开启后不再校验证书链
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+
+    /// 固定的服务器证书 SHA-256 指纹（冒号分隔的十六进制）
+    ///
+    /// 设置后仅信任指纹匹配的证书，用于自签名证书的安全连接
+    #[serde(default)]
+    pub pinned_cert_fingerprint: Option<String>,
+
+    /// 每个请求附带的自定义请求头（如服务商要求的 `X-API-Key`）
+    #[serde(default)]
+    pub custom_headers: CustomHeaders,
+
+    /// 连接时使用的 IP 协议版本（any, ipv4, ipv6）
+    #[serde(default = "default_ip_version")]
+    pub ip_version: String,
+
+    /// 服务器主机名固定解析到的 IP 地址（跳过 DNS 解析，用于 DNS 或 AAAA 记录有问题的 NAS）
+    #[serde(default)]
+    pub resolve_ip: Option<String>,
+
+    /// 是否启用
+    pub enabled: bool,
+
+    /// 创建时间（Unix 时间戳，秒）
+    pub created_at: i64,
+
+    /// 更新时间（Unix 时间戳，秒）
+    pub updated_at: i64,
+}
+
+fn default_auth_type() -> String {
+    auth_type::BASIC.to_string()
+}
+
+fn default_ip_version() -> String {
+    ip_version::ANY.to_string()
+}
+
+fn default_connect_timeout() -> u32 {
+    DEFAULT_CONNECT_TIMEOUT
+}
+
+fn default_read_timeout() -> u32 {
+    DEFAULT_READ_TIMEOUT
+}
+
+/// 服务器自定义请求头（请求头名称 -> 值）
+///
+/// 值可能包含 API 密钥，`Debug` 输出与 `redacted` 只保留请求头名称
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CustomHeaders(pub BTreeMap<String, String>);
+
+impl CustomHeaders {
+    /// 是否没有自定义请求头
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 遍历请求头名称与值
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+
+    /// 值替换为 `[REDACTED]` 的副本，用于日志与诊断包
+    pub fn redacted(&self) -> Self {
+        Self(
+            self.0
+                .keys()
+                .map(|name| (name.clone(), "[REDACTED]".to_string()))
+                .collect(),
+        )
+    }
+}
+
+impl std::fmt::Debug for CustomHeaders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.redacted().0).finish()
+    }
+}
+
+/// 已归档的 WebDAV 服务器
+///
+/// 归档后服务器被禁用，配置、密码与历史记录保留到保留期结束，期间可以恢复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedWebDavServer {
+    /// 服务器配置
+    #[serde(flatten)]
+    pub server: WebDavServerConfig,
+
+    /// 归档时间（Unix 时间戳，秒）
+    pub deleted_at: i64,
+}
+
+impl WebDavServerConfig {
+    /// 替换用户名占位符后的服务器 URL
+    ///
+    /// 用户名按路径段进行百分号编码；URL 中没有 `{username}` 时原样返回
+    pub fn resolved_url(&self) -> String {
+        self.url.replace(
+            USERNAME_PLACEHOLDER,
+            &crate::webdav::path::encode_segment(self.username.trim()),
+        )
+    }
+
+    /// 用于比较的规范化服务器 URL
+    ///
+    /// 在 `resolved_url` 的基础上统一协议与主机名的大小写、去掉默认端口和末尾的 `/`，
+    /// 用于判断两个配置是否指向同一服务器；无法解析时只去掉末尾的 `/`
+    pub fn normalized_url(&self) -> String {
+        let resolved = self.resolved_url();
+        let normalized = match url::Url::parse(resolved.trim()) {
+            Ok(parsed) => parsed.to_string(),
+            Err(_) => resolved.trim().to_string(),
+        };
+        normalized.trim_end_matches('/').to_string()
+    }
+
+    /// 验证 URL 格式是否有效
+    ///
+    /// 要求：
+    /// - URL 必须包含协议（http 或 https）
+    /// - URL 必须包含主机名
+    /// - `{username}` 占位符只能出现在路径中
+    ///
+    /// # 返回
+    /// - Ok(()) 如果 URL 有效
+    /// - Err(String) 如果 URL 无效，包含错误描述
+    pub fn validate_url(&self) -> Result<(), String> {
+        // 检查 URL 是否为空
+        if self.url.trim().is_empty() {
+            return Err("URL cannot be empty".to_string());
+        }
+
+        // 用户名占位符只能出现在路径中
+        if let Some(idx) = self.url.find(USERNAME_PLACEHOLDER) {
+            let path_start = self.url.find("://").and_then(|scheme_end| {
+                self.url[scheme_end + 3..]
+                    .find('/')
+                    .map(|idx| idx + scheme_end + 3)
+            });
+            if path_start.is_none_or(|start| idx < start) {
+                return Err(format!(
+                    "{} can only be used in the URL path",
+                    USERNAME_PLACEHOLDER
+                ));
+            }
+        }
+
+        // 尝试解析 URL
+        match url::Url::parse(&self.resolved_url()) {
+            Ok(parsed_url) => {
+                // 检查是否有协议
+                let scheme = parsed_url.scheme();
+                if scheme != "http" && scheme != "https" {
+                    return Err(format!(
+                        "URL must use http or https protocol, found: {}",
+                        scheme
+                    ));
+                }
+
+                // 检查是否有主机名
+                if parsed_url.host_str().is_none() {
+                    return Err("URL must contain a valid host".to_string());
+                }
+
+                Ok(())
+            }
+            Err(e) => Err(format!("Invalid URL format: {}", e)),
+        }
+    }
+
+    /// 验证服务器名称是否有效
+    ///
+    /// 要求：
+    /// - 名称不能为空
+    /// - 名称不能只包含空白字符
+    ///
+    /// # 返回
+    /// - Ok(()) 如果名称有效
+    /// - Err(String) 如果名称无效，包含错误描述
+    pub fn validate_name(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Server name cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// 验证用户名是否有效
+    ///
+    /// 要求：
+    /// - 用户名不能为空
+    /// - 用户名不能只包含空白字符
+    ///
+    /// # 返回
+    /// - Ok(()) 如果用户名有效
+    /// - Err(String) 如果用户名无效，包含错误描述
+    pub fn validate_username(&self) -> Result<(), String> {
+        if self.username.trim().is_empty() {
+            return Err("Username cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// 验证超时时间是否在有效范围内
+    ///
+    /// 要求：
+    /// - 请求超时必须在 1-300 秒之间
+    /// - 建立连接超时必须在 1-120 秒之间
+    /// - 读取超时必须在 5-3600 秒之间
+    ///
+    /// # 返回
+    /// - Ok(()) 如果超时时间有效
+    /// - Err(String) 如果超时时间无效，包含错误描述
+    pub fn validate_timeout(&self) -> Result<(), String> {
+        let checks = [
+            ("Timeout", self.timeout, timeout_range::REQUEST),
+            (
+                "Connect timeout",
+                self.connect_timeout,
+                timeout_range::CONNECT,
+            ),
+            ("Read timeout", self.read_timeout, timeout_range::READ),
+        ];
+        for (name, value, (min, max)) in checks {
+            if value < min || value > max {
+                return Err(format!(
+                    "{} must be between {} and {} seconds, got: {}",
+                    name, min, max, value
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 验证固定证书指纹格式
+    ///
+    /// 要求：
+    /// - 未设置时视为有效
+    /// - 设置时必须是 32 字节的 SHA-256 指纹（可带冒号或空格分隔）
+    ///
+    /// # 返回
+    /// - Ok(()) 如果指纹有效
+    /// - Err(String) 如果指纹无效，包含错误描述
+    pub fn validate_pinned_fingerprint(&self) -> Result<(), String> {
+        match &self.pinned_cert_fingerprint {
+            Some(fingerprint) => crate::webdav::tls::normalize_fingerprint(fingerprint)
+                .map(|_| ())
+                .ok_or_else(|| format!("Invalid SHA-256 certificate fingerprint: {}", fingerprint)),
+            None => Ok(()),
+        }
+    }
+
+    /// 验证自定义请求头
+    ///
+    /// 要求：
+    /// - 名称必须是合法的 HTTP 请求头名称，且不能是客户端自身管理的请求头（见 `RESERVED_CUSTOM_HEADERS`）
+    /// - 值不能包含换行等控制字符
+    ///
+    /// # 返回
+    /// - Ok(()) 如果请求头有效
+    /// - Err(String) 如果请求头无效，包含错误描述（不包含请求头的值）
+    pub fn validate_custom_headers(&self) -> Result<(), String> {
+        for (name, value) in self.custom_headers.iter() {
+            let header = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|_| format!("Invalid custom header name: {}", name))?;
+            if RESERVED_CUSTOM_HEADERS.contains(&header.as_str()) {
+                return Err(format!("Custom header '{}' is managed by the client", name));
+            }
+            reqwest::header::HeaderValue::from_str(value)
+                .map_err(|_| format!("Invalid value for custom header '{}'", name))?;
+        }
+        Ok(())
+    }
+
+    /// 验证网络选项
+    ///
+    /// 要求：
+    /// - IP 协议版本必须是 any、ipv4 或 ipv6
+    /// - 固定 IP 必须是有效的 IP 地址，且与强制使用的 IP 协议版本一致
+    ///
+    /// # 返回
+    /// - Ok(()) 如果网络选项有效
+    /// - Err(String) 如果网络选项无效，包含错误描述
+    pub fn validate_network_options(&self) -> Result<(), String> {
+        let version = self.ip_version.as_str();
+        if ![ip_version::ANY, ip_version::IPV4, ip_version::IPV6].contains(&version) {
+            return Err(format!("Invalid IP version: {}", self.ip_version));
+        }
+
+        if let Some(ip) = &self.resolve_ip {
+            let ip: std::net::IpAddr = ip
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid IP address: {}", ip))?;
+            if (version == ip_version::IPV4 && ip.is_ipv6())
+                || (version == ip_version::IPV6 && ip.is_ipv4())
+            {
+                return Err(format!(
+                    "IP address {} does not match the forced IP version {}",
+                    ip, version
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 验证所有字段
+    ///
+    /// 执行所有验证检查，返回第一个遇到的错误
+    ///
+    /// # 返回
+    /// - Ok(()) 如果所有字段都有效
+    /// - Err(String) 如果任何字段无效，包含错误描述
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_name()?;
+        self.validate_url()?;
+        self.validate_username()?;
+        self.validate_timeout()?;
+        self.validate_pinned_fingerprint()?;
+        self.validate_custom_headers()?;
+        self.validate_network_options()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webdav::mock_server_config;
+
+    #[test]
+    fn test_file_metadata_serialization() {
+        let metadata = FileMetadata {
+            id: Some(1),
+            path: "/test/file.txt".to_string(),
+            hash: Some("abc123".to_string()),
+            size: 1024,
+            modified_at: 1234567890,
+            synced_at: Some(1234567891),
+            sync_folder_id: 1,
+            is_directory: false,
+            status: "synced".to_string(),
+            created_at: Some(1234567889),
+            updated_at: Some(1234567891),
+            remote_size: None,
+            inode: None,
+        };
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert!(json.contains("test/file.txt"));
+    }
+
+    #[test]
+    fn test_sync_log_creation() {
+        let log = SyncLog {
+            id: None,
+            sync_folder_id: 1,
+            file_path: "/test/file.txt".to_string(),
+            action: "upload".to_string(),
+            status: "success".to_string(),
+            error_message: None,
+            file_size: Some(1024),
+            duration_ms: Some(500),
+            bytes_per_sec: Some(2048),
+            created_at: None,
+        };
+
+        assert_eq!(log.action, "upload");
+        assert_eq!(log.status, "success");
+    }
+
+    #[test]
+    fn test_query_filter_where_clause() {
+        let filter = QueryFilter {
+            sync_folder_id: Some(1),
+            status: Some("success".to_string()),
+            start_time: Some(100),
+            end_time: Some(200),
+            ..Default::default()
+        };
+
+        let (clause, params) = filter.where_clause("created_at");
+        assert_eq!(
+            clause,
+            "WHERE is_delete = 0 AND sync_folder_id = ?1 AND status = ?2 AND created_at >= ?3 AND created_at <= ?4"
+        );
+        assert_eq!(params.len(), 4);
+
+        let (clause, params) = QueryFilter::default().where_clause("created_at");
+        assert_eq!(clause, "WHERE is_delete = 0");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_query_filter_limit_clause() {
+        assert_eq!(QueryFilter::default().limit_clause(), "LIMIT 50 OFFSET 0");
+
+        let filter = QueryFilter {
+            limit: Some(100000),
+            offset: Some(-5),
+            ..Default::default()
+        };
+        assert_eq!(filter.limit_clause(), "LIMIT 500 OFFSET 0");
+    }
+
+    // ========== WebDavServerConfig Tests ==========
+
+    fn create_valid_config() -> WebDavServerConfig {
+        WebDavServerConfig {
+            id: "test-uuid-123".to_string(),
+            use_https: true,
+            timeout: 30,
+            created_at: 1234567890,
+            updated_at: 1234567890,
+            ..mock_server_config("https://example.com/webdav")
+        }
+    }
+
+    #[test]
+    fn test_webdav_config_serialization() {
+        let config = create_valid_config();
+        let json = serde_json::to_string(&config).unwrap();
+
+        // Verify camelCase serialization
+        assert!(json.contains("\"useHttps\":true"));
+        assert!(json.contains("\"lastTestStatus\":\"unknown\""));
+        assert!(json.contains("\"serverType\":\"generic\""));
+        assert!(json.contains("\"createdAt\":1234567890"));
+        assert!(json.contains("\"updatedAt\":1234567890"));
+    }
+
+    #[test]
+    fn test_webdav_config_deserialization() {
+        let json = r#"{
+            "id": "test-uuid-456",
+            "name": "My Server",
+            "url": "https://cloud.example.com",
+            "username": "user123",
+            "useHttps": true,
+            "timeout": 60,
+            "lastTestAt": null,
+            "lastTestStatus": "success",
+            "lastTestError": null,
+            "serverType": "nextcloud",
+            "enabled": false,
+            "createdAt": 1234567890,
+            "updatedAt": 1234567891
+        }"#;
+
+        let config: WebDavServerConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.id, "test-uuid-456");
+        assert_eq!(config.name, "My Server");
+        assert_eq!(config.url, "https://cloud.example.com");
+        assert_eq!(config.username, "user123");
+        assert_eq!(config.use_https, true);
+        assert_eq!(config.timeout, 60);
+        assert_eq!(config.last_test_status, "success");
+        assert_eq!(config.server_type, "nextcloud");
+        assert_eq!(config.auth_type, "basic");
+        assert!(!config.accept_invalid_certs);
+        assert_eq!(config.pinned_cert_fingerprint, None);
+        assert_eq!(config.enabled, false);
+    }
+
+    #[test]
+    fn test_validate_url_valid_https() {
+        let config = create_valid_config();
+        assert!(config.validate_url().is_ok());
+    }
+
+    #[test]
+    fn test_validate_url_valid_http() {
+        let mut config = create_valid_config();
+        config.url = "http://example.com/webdav".to_string();
+        assert!(config.validate_url().is_ok());
+    }
+
+    #[test]
+    fn test_validate_url_empty() {
+        let mut config = create_valid_config();
+        config.url = "".to_string();
+        let result = config.validate_url();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_url_whitespace_only() {
+        let mut config = create_valid_config();
+        config.url = "   ".to_string();
+        let result = config.validate_url();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_url_missing_protocol() {
+        let mut config = create_valid_config();
+        config.url = "example.com/webdav".to_string();
+        let result = config.validate_url();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Invalid URL format"));
+    }
+
+    #[test]
+    fn test_validate_url_invalid_protocol() {
+        let mut config = create_valid_config();
+        config.url = "ftp://example.com/webdav".to_string();
+        let result = config.validate_url();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("must use http or https"));
+    }
+
+    #[test]
+    fn test_validate_url_no_host() {
+        let mut config = create_valid_config();
+        config.url = "https://".to_string();
+        let result = config.validate_url();
+        assert!(result.is_err());
+        // The url crate returns "Invalid URL format" for this case
+        let err = result.unwrap_err();
+        assert!(err.contains("Invalid URL format") || err.contains("valid host"));
+    }
+
+    #[test]
+    fn test_resolved_url_username_placeholder() {
+        let mut config = create_valid_config();
+        assert_eq!(config.resolved_url(), config.url);
+
+        config.url = "https://cloud.example.com/remote.php/dav/files/{username}/".to_string();
+        config.username = "alice smith".to_string();
+        assert_eq!(
+            config.resolved_url(),
+            "https://cloud.example.com/remote.php/dav/files/alice%20smith/"
+        );
+        assert!(config.validate_url().is_ok());
+
+        config.url = "https://{username}.example.com/dav".to_string();
+        let result = config.validate_url();
+        assert!(result.unwrap_err().contains("only be used in the URL path"));
+    }
+
+    #[test]
+    fn test_normalized_url() {
+        let mut config = create_valid_config();
+        config.url = "HTTPS://Cloud.Example.com:443/remote.php/dav/files/{username}/".to_string();
+        config.username = "alice".to_string();
+        assert_eq!(
+            config.normalized_url(),
+            "https://cloud.example.com/remote.php/dav/files/alice"
+        );
+
+        config.url = "http://nas.local:80".to_string();
+        assert_eq!(config.normalized_url(), "http://nas.local");
+
+        // 非默认端口与路径大小写保留
+        config.url = "http://nas.local:5005/WebDAV/".to_string();
+        assert_eq!(config.normalized_url(), "http://nas.local:5005/WebDAV");
+    }
+
+    #[test]
+    fn test_validate_name_valid() {
+        let config = create_valid_config();
+        assert!(config.validate_name().is_ok());
+    }
+
+    #[test]
+    fn test_validate_name_empty() {
+        let mut config = create_valid_config();
+        config.name = "".to_string();
+        let result = config.validate_name();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_name_whitespace_only() {
+        let mut config = create_valid_config();
+        config.name = "   ".to_string();
+        let result = config.validate_name();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_username_valid() {
+        let config = create_valid_config();
+        assert!(config.validate_username().is_ok());
+    }
+
+    #[test]
+    fn test_validate_username_empty() {
+        let mut config = create_valid_config();
+        config.username = "".to_string();
+        let result = config.validate_username();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_username_whitespace_only() {
+        let mut config = create_valid_config();
+        config.username = "   ".to_string();
+        let result = config.validate_username();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_timeout_valid() {
+        let config = create_valid_config();
+        assert!(config.validate_timeout().is_ok());
+    }
+
+    #[test]
+    fn test_validate_timeout_minimum() {
+        let mut config = create_valid_config();
+        config.timeout = 1;
+        assert!(config.validate_timeout().is_ok());
+    }
+
+    #[test]
+    fn test_validate_timeout_maximum() {
+        let mut config = create_valid_config();
+        config.timeout = 300;
+        assert!(config.validate_timeout().is_ok());
+    }
+
+    #[test]
+    fn test_validate_timeout_too_small() {
+        let mut config = create_valid_config();
+        config.timeout = 0;
+        let result = config.validate_timeout();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("between 1 and 300"));
+    }
+
+    #[test]
+    fn test_validate_timeout_too_large() {
+        let mut config = create_valid_config();
+        config.timeout = 301;
+        let result = config.validate_timeout();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("between 1 and 300"));
+    }
+
+    #[test]
+    fn test_validate_connect_and_read_timeout() {
+        let mut config = create_valid_config();
+        config.connect_timeout = 0;
+        assert!(config
+            .validate_timeout()
+            .unwrap_err()
+            .contains("Connect timeout must be between 1 and 120"));
+
+        config.connect_timeout = 120;
+        config.read_timeout = 4;
+        assert!(config
+            .validate_timeout()
+            .unwrap_err()
+            .contains("Read timeout must be between 5 and 3600"));
+
+        config.read_timeout = 3600;
+        assert!(config.validate_timeout().is_ok());
+    }
+
+    #[test]
+    fn test_validate_all_fields_valid() {
+        let config = create_valid_config();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_all_fields_invalid_name() {
+        let mut config = create_valid_config();
+        config.name = "".to_string();
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("name"));
+    }
+
+    #[test]
+    fn test_validate_all_fields_invalid_url() {
+        let mut config = create_valid_config();
+        config.url = "invalid-url".to_string();
+        let result = config.validate();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_all_fields_invalid_username() {
+        let mut config = create_valid_config();
+        config.username = "".to_string();
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Username"));
+    }
+
+    #[test]
+    fn test_validate_all_fields_invalid_timeout() {
+        let mut config = create_valid_config();
+        config.timeout = 500;
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Timeout"));
+    }
+
+    #[test]
+    fn test_validate_custom_headers() {
+        let mut config = create_valid_config();
+        config
+            .custom_headers
+            .0
+            .insert("X-API-Key".to_string(), "secret-key".to_string());
+        assert!(config.validate().is_ok());
+
+        config
+            .custom_headers
+            .0
+            .insert("Bad Header".to_string(), "value".to_string());
+        assert!(config.validate().unwrap_err().contains("Bad Header"));
+        config.custom_headers.0.remove("Bad Header");
+
+        // 由客户端管理的请求头不能覆盖
+        config
+            .custom_headers
+            .0
+            .insert("Authorization".to_string(), "Bearer token".to_string());
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("managed by the client"));
+        config.custom_headers.0.remove("Authorization");
+
+        config
+            .custom_headers
+            .0
+            .insert("X-Other".to_string(), "line\nbreak".to_string());
+        let error = config.validate().unwrap_err();
+        assert!(error.contains("X-Other"));
+        assert!(!error.contains("secret-key"));
+    }
+
+    #[test]
+    fn test_validate_network_options() {
+        let mut config = create_valid_config();
+        assert_eq!(config.ip_version, "any");
+        assert!(config.validate().is_ok());
+
+        config.resolve_ip = Some("192.168.1.10".to_string());
+        assert!(config.validate().is_ok());
+        config.ip_version = "ipv4".to_string();
+        assert!(config.validate().is_ok());
+
+        // 固定 IP 与强制的协议版本不一致
+        config.ip_version = "ipv6".to_string();
+        assert!(config.validate().unwrap_err().contains("does not match"));
+        config.resolve_ip = Some("fd00::10".to_string());
+        assert!(config.validate().is_ok());
+
+        config.resolve_ip = Some("nas.local".to_string());
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("Invalid IP address"));
+
+        config.resolve_ip = None;
+        config.ip_version = "ipv5".to_string();
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("Invalid IP version"));
+    }
+
+    #[test]
+    fn test_custom_headers_redacted_in_debug() {
+        let mut config = create_valid_config();
+        config
+            .custom_headers
+            .0
+            .insert("X-API-Key".to_string(), "secret-key".to_string());
+
+        let debug = format!("{:?}", config);
+        assert!(debug.contains("X-API-Key"));
+        assert!(!debug.contains("secret-key"));
+
+        // 序列化保留原值，供数据库与前端使用
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""customHeaders":{"X-API-Key":"secret-key"}"#));
+    }
+}
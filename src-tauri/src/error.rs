@@ -1,7 +1,112 @@
 /// LightSync 统一错误类型定义
 ///
 /// 使用 thiserror 提供统一的错误处理机制，支持错误传播和序列化
-use serde::{Serialize, Serializer};
+///
+/// 错误序列化到前端时使用稳定的 JSON 结构：
+///
+/// ```json
+/// {
+///   "errorCode": "NOT_FOUND",
+///   "message": "Not found: ...",
+///   "context": { "serverId": "..." },
+///   "messageKey": "errors.NOT_FOUND",
+///   "params": { "detail": "...", "serverId": "..." }
+/// }
+/// ```
+///
+/// 前端应根据 `errorCode` 判断错误类型；`message` 为英文描述，
+/// 本地化文本通过 `messageKey` 与 `params` 查表生成（见 `crate::i18n`）
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+/// 稳定的错误码
+///
+/// 序列化为大写蛇形命名（如 `NOT_FOUND`），新增错误码只能追加，不能修改已有的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// 文件读写错误
+    IoError,
+    /// WebDAV 协议错误
+    WebdavError,
+    /// 网络错误
+    NetworkError,
+    /// 序列化错误
+    SerializationError,
+    /// Tauri 框架错误
+    TauriError,
+    /// 同步冲突
+    SyncConflict,
+    /// 认证失败
+    AuthFailed,
+    /// 本地文件不存在
+    FileNotFound,
+    /// 资源不存在
+    NotFound,
+    /// 配置错误
+    ConfigError,
+    /// 输入校验失败
+    ValidationError,
+    /// 数据库错误
+    DatabaseError,
+    /// 系统 Keyring 错误
+    KeyringError,
+    /// 文件系统监控错误
+    WatcherError,
+    /// 传输完整性校验失败
+    IntegrityError,
+    /// 操作被中断
+    Interrupted,
+    /// 操作已取消
+    Cancelled,
+    /// 应用已锁定（需要输入主密码解锁）
+    AppLocked,
+    /// 服务器配置已存在
+    DuplicateServer,
+    /// 同步文件夹本地根目录不可用（如可移动磁盘已断开）
+    SyncRootUnavailable,
+    /// 同步计划删除的文件过多，需要用户确认
+    MassDeletionProtection,
+    /// 服务器剩余空间不足以完成本次上传
+    InsufficientRemoteStorage,
+    /// 本地磁盘剩余空间不足以完成本次下载
+    InsufficientLocalStorage,
+    /// 未知错误
+    Unknown,
+}
+
+impl ErrorCode {
+    /// 错误码字符串（与序列化结果一致）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::IoError => "IO_ERROR",
+            ErrorCode::WebdavError => "WEBDAV_ERROR",
+            ErrorCode::NetworkError => "NETWORK_ERROR",
+            ErrorCode::SerializationError => "SERIALIZATION_ERROR",
+            ErrorCode::TauriError => "TAURI_ERROR",
+            ErrorCode::SyncConflict => "SYNC_CONFLICT",
+            ErrorCode::AuthFailed => "AUTH_FAILED",
+            ErrorCode::FileNotFound => "FILE_NOT_FOUND",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::ConfigError => "CONFIG_ERROR",
+            ErrorCode::ValidationError => "VALIDATION_ERROR",
+            ErrorCode::DatabaseError => "DATABASE_ERROR",
+            ErrorCode::KeyringError => "KEYRING_ERROR",
+            ErrorCode::WatcherError => "WATCHER_ERROR",
+            ErrorCode::IntegrityError => "INTEGRITY_ERROR",
+            ErrorCode::Interrupted => "INTERRUPTED",
+            ErrorCode::Cancelled => "CANCELLED",
+            ErrorCode::AppLocked => "APP_LOCKED",
+            ErrorCode::DuplicateServer => "DUPLICATE_SERVER",
+            ErrorCode::SyncRootUnavailable => "SYNC_ROOT_UNAVAILABLE",
+            ErrorCode::MassDeletionProtection => "MASS_DELETION_PROTECTION",
+            ErrorCode::InsufficientRemoteStorage => "INSUFFICIENT_REMOTE_STORAGE",
+            ErrorCode::InsufficientLocalStorage => "INSUFFICIENT_LOCAL_STORAGE",
+            ErrorCode::Unknown => "UNKNOWN",
+        }
+    }
+}
 
 /// 同步错误的主要类型枚举
 #[derive(Debug, thiserror::Error)]
@@ -46,26 +151,249 @@ pub enum SyncError {
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
+    /// 输入校验错误
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
     /// 数据库错误
     #[error("Database error: {0}")]
     DatabaseError(String),
 
+    /// 系统 Keyring 错误
+    #[error("Keyring error: {0}")]
+    KeyringError(String),
+
     /// 文件系统监控错误
     #[error("File watcher error: {0}")]
     WatcherError(String),
 
+    /// 传输完整性校验失败
+    #[error("Integrity check failed: {0}")]
+    IntegrityError(String),
+
+    /// 操作被中断（同步已暂停）
+    #[error("Interrupted: {0}")]
+    Interrupted(String),
+
+    /// 操作已取消
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    /// 应用已锁定
+    #[error("Application is locked: {0}")]
+    Locked(String),
+
+    /// 相同地址和用户名的服务器已存在（内容为已有服务器的名称）
+    #[error("Server already exists: {0}")]
+    DuplicateServer(String),
+
+    /// 同步文件夹本地根目录不存在或缺少标记文件，为避免误删远程文件已停止同步
+    #[error("Sync root unavailable: {0}")]
+    SyncRootUnavailable(String),
+
+    /// 同步计划删除的文件超过文件夹设置的比例，需要用户确认
+    #[error("Mass deletion protection: {0}")]
+    MassDeletionProtection(String),
+
+    /// 同步前检查到服务器配额的剩余空间小于计划上传的字节数（内容包含缺少的字节数）
+    #[error("Insufficient remote storage: {0}")]
+    InsufficientRemoteStorage(String),
+
+    /// 同步前检查到本地磁盘剩余空间小于计划下载的字节数加安全余量（内容包含缺少的字节数）
+    #[error("Insufficient local storage: {0}")]
+    InsufficientLocalStorage(String),
+
+    /// 附带上下文信息的错误
+    ///
+    /// 错误码与消息沿用内部错误，只在序列化时附加上下文。
+    /// 仅在错误直接返回给前端的位置使用，避免影响调用方对错误变体的匹配
+    #[error("{source}")]
+    WithContext {
+        /// 原始错误
+        source: Box<SyncError>,
+        /// 上下文信息（如 serverId、path）
+        context: BTreeMap<String, String>,
+    },
+
     /// 未知错误
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
-/// 实现 Serialize trait，使错误可以序列化传递到前端
+impl SyncError {
+    /// 获取错误码
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SyncError::Io(_) => ErrorCode::IoError,
+            SyncError::WebDav(_) => ErrorCode::WebdavError,
+            SyncError::Network(_) => ErrorCode::NetworkError,
+            SyncError::Serde(_) => ErrorCode::SerializationError,
+            SyncError::Tauri(_) => ErrorCode::TauriError,
+            SyncError::Conflict(_) => ErrorCode::SyncConflict,
+            SyncError::AuthError(_) => ErrorCode::AuthFailed,
+            SyncError::FileNotFound(_) => ErrorCode::FileNotFound,
+            SyncError::NotFound(_) => ErrorCode::NotFound,
+            SyncError::ConfigError(_) => ErrorCode::ConfigError,
+            SyncError::ValidationError(_) => ErrorCode::ValidationError,
+            SyncError::DatabaseError(_) => ErrorCode::DatabaseError,
+            SyncError::KeyringError(_) => ErrorCode::KeyringError,
+            SyncError::WatcherError(_) => ErrorCode::WatcherError,
+            SyncError::IntegrityError(_) => ErrorCode::IntegrityError,
+            SyncError::Interrupted(_) => ErrorCode::Interrupted,
+            SyncError::Cancelled(_) => ErrorCode::Cancelled,
+            SyncError::Locked(_) => ErrorCode::AppLocked,
+            SyncError::DuplicateServer(_) => ErrorCode::DuplicateServer,
+            SyncError::SyncRootUnavailable(_) => ErrorCode::SyncRootUnavailable,
+            SyncError::MassDeletionProtection(_) => ErrorCode::MassDeletionProtection,
+            SyncError::InsufficientRemoteStorage(_) => ErrorCode::InsufficientRemoteStorage,
+            SyncError::InsufficientLocalStorage(_) => ErrorCode::InsufficientLocalStorage,
+            SyncError::WithContext { source, .. } => source.code(),
+            SyncError::Unknown(_) => ErrorCode::Unknown,
+        }
+    }
+
+    /// 获取去除上下文包装后的原始错误
+    pub fn root(&self) -> &SyncError {
+        match self {
+            SyncError::WithContext { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// 是否为存储空间不足（本地磁盘已满、服务器返回 507 Insufficient Storage，或同步前检查到服务器或本地空间不足）
+    pub fn is_out_of_space(&self) -> bool {
+        match self.root() {
+            SyncError::Io(e) => e.kind() == std::io::ErrorKind::StorageFull,
+            SyncError::InsufficientRemoteStorage(_) | SyncError::InsufficientLocalStorage(_) => {
+                true
+            }
+            SyncError::WebDav(msg) => msg.starts_with("HTTP 507"),
+            _ => false,
+        }
+    }
+
+    /// 附加一条上下文信息
+    ///
+    /// 多次调用会合并到同一个上下文中，相同的键以最后一次为准
+    pub fn with_context(self, key: impl Into<String>, value: impl ToString) -> Self {
+        match self {
+            SyncError::WithContext {
+                source,
+                mut context,
+            } => {
+                context.insert(key.into(), value.to_string());
+                SyncError::WithContext { source, context }
+            }
+            other => SyncError::WithContext {
+                source: Box::new(other),
+                context: BTreeMap::from([(key.into(), value.to_string())]),
+            },
+        }
+    }
+
+    /// 获取错误详情（不含错误类型前缀的原始描述）
+    pub fn detail(&self) -> String {
+        match self {
+            SyncError::WebDav(msg)
+            | SyncError::Network(msg)
+            | SyncError::Conflict(msg)
+            | SyncError::AuthError(msg)
+            | SyncError::FileNotFound(msg)
+            | SyncError::NotFound(msg)
+            | SyncError::ConfigError(msg)
+            | SyncError::ValidationError(msg)
+            | SyncError::DatabaseError(msg)
+            | SyncError::KeyringError(msg)
+            | SyncError::WatcherError(msg)
+            | SyncError::IntegrityError(msg)
+            | SyncError::Interrupted(msg)
+            | SyncError::Cancelled(msg)
+            | SyncError::Locked(msg)
+            | SyncError::DuplicateServer(msg)
+            | SyncError::SyncRootUnavailable(msg)
+            | SyncError::MassDeletionProtection(msg)
+            | SyncError::InsufficientRemoteStorage(msg)
+            | SyncError::InsufficientLocalStorage(msg)
+            | SyncError::Unknown(msg) => msg.clone(),
+            SyncError::Io(e) => e.to_string(),
+            SyncError::Serde(e) => e.to_string(),
+            SyncError::Tauri(e) => e.to_string(),
+            SyncError::WithContext { source, .. } => source.detail(),
+        }
+    }
+
+    /// 获取本地化消息键（见 `crate::i18n`）
+    pub fn message_key(&self) -> String {
+        format!("errors.{}", self.code().as_str())
+    }
+
+    /// 获取本地化消息参数
+    ///
+    /// 包含 `detail`（错误详情）以及全部上下文信息
+    pub fn message_params(&self) -> BTreeMap<String, String> {
+        let mut params = self.context();
+        params.insert("detail".to_string(), self.detail());
+        params
+    }
+
+    /// 获取上下文信息
+    ///
+    /// 包含通过 `with_context` 附加的信息，以及部分变体自带的信息（如 I/O 错误类型）
+    pub fn context(&self) -> BTreeMap<String, String> {
+        match self {
+            SyncError::WithContext { source, context } => {
+                let mut merged = source.context();
+                merged.extend(context.iter().map(|(k, v)| (k.clone(), v.clone())));
+                merged
+            }
+            SyncError::Io(e) => BTreeMap::from([("ioKind".to_string(), format!("{:?}", e.kind()))]),
+            _ => BTreeMap::new(),
+        }
+    }
+}
+
+/// 序列化到前端的错误结构
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorPayload {
+    /// 错误码
+    pub error_code: ErrorCode,
+
+    /// 错误消息（英文，用于展示和日志）
+    pub message: String,
+
+    /// 上下文信息（没有时为空对象）
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
+
+    /// 本地化消息键（如 `errors.NOT_FOUND`）
+    #[serde(default)]
+    pub message_key: String,
+
+    /// 本地化消息参数
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+}
+
+impl From<&SyncError> for ErrorPayload {
+    fn from(error: &SyncError) -> Self {
+        Self {
+            error_code: error.code(),
+            message: error.to_string(),
+            context: error.context(),
+            message_key: error.message_key(),
+            params: error.message_params(),
+        }
+    }
+}
+
+/// 实现 Serialize trait，使错误以 `ErrorPayload` 结构传递到前端
 impl Serialize for SyncError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        ErrorPayload::from(self).serialize(serializer)
     }
 }
 
@@ -90,6 +418,88 @@ mod tests {
         assert!(json.contains("Configuration error"));
     }
 
+    #[test]
+    fn test_error_serialization_shape() {
+        let error = SyncError::NotFound("server-1".to_string());
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["errorCode"], "NOT_FOUND");
+        assert_eq!(value["message"], "Not found: server-1");
+        assert!(value["context"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_error_code_as_str_matches_serialization() {
+        let codes = [
+            ErrorCode::IoError,
+            ErrorCode::WebdavError,
+            ErrorCode::AuthFailed,
+            ErrorCode::ValidationError,
+            ErrorCode::KeyringError,
+            ErrorCode::AppLocked,
+            ErrorCode::DuplicateServer,
+            ErrorCode::SyncRootUnavailable,
+            ErrorCode::MassDeletionProtection,
+            ErrorCode::InsufficientRemoteStorage,
+            ErrorCode::InsufficientLocalStorage,
+            ErrorCode::Unknown,
+        ];
+        for code in codes {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json, code.as_str());
+        }
+    }
+
+    #[test]
+    fn test_with_context_keeps_code_and_message() {
+        let error = SyncError::AuthError("bad password".to_string())
+            .with_context("serverId", "server-1")
+            .with_context("path", "/docs");
+
+        assert_eq!(error.code(), ErrorCode::AuthFailed);
+        assert_eq!(error.to_string(), "Authentication failed: bad password");
+        assert!(matches!(error.root(), SyncError::AuthError(_)));
+
+        let payload = ErrorPayload::from(&error);
+        assert_eq!(payload.context.get("serverId").unwrap(), "server-1");
+        assert_eq!(payload.context.get("path").unwrap(), "/docs");
+    }
+
+    #[test]
+    fn test_message_key_and_params() {
+        let error = SyncError::NotFound("server-1".to_string()).with_context("serverId", "abc");
+
+        assert_eq!(error.message_key(), "errors.NOT_FOUND");
+        let params = error.message_params();
+        assert_eq!(params.get("detail").unwrap(), "server-1");
+        assert_eq!(params.get("serverId").unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_io_error_context() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let error: SyncError = io_error.into();
+
+        assert_eq!(error.code(), ErrorCode::IoError);
+        assert_eq!(error.context().get("ioKind").unwrap(), "PermissionDenied");
+    }
+
+    #[test]
+    fn test_is_out_of_space() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::StorageFull, "disk full");
+        assert!(SyncError::from(io_error).is_out_of_space());
+        assert!(SyncError::WebDav(
+            "HTTP 507 Insufficient Storage: The server has insufficient storage.".to_string()
+        )
+        .with_context("path", "/a.txt")
+        .is_out_of_space());
+        assert!(
+            SyncError::InsufficientRemoteStorage("short by 10 bytes".to_string()).is_out_of_space()
+        );
+        assert!(!SyncError::WebDav("HTTP 500 Internal Server Error".to_string()).is_out_of_space());
+        assert!(!SyncError::AuthError("bad password".to_string()).is_out_of_space());
+    }
+
     #[test]
     fn test_error_from_io() {
         let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -0,0 +1,236 @@
+/// 事件批处理器
+///
+/// 批量操作（如 `git checkout`、解压归档）会在短时间内产生数千个监控事件，
+/// 监控器把事件放入 `EventBatcher`，同步一侧按批取出，两者之间的积压有上限：
+/// - 同一路径只保留最新的事件（合并），重命名后的路径再次变化时保留重命名的旧路径
+/// - 待处理的路径数达到容量时视为溢出：丢弃已积压和之后的事件，
+///   取出的批次标记为需要重新扫描整个文件夹，之后重新开始积压
+/// - 累计的接收、合并、丢弃数与溢出次数通过 `metrics` 读取
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use super::types::FileEvent;
+use crate::constants::WATCHER_QUEUE_CAPACITY;
+
+/// 事件队列统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventMetrics {
+    /// 接收的事件数
+    pub received: u64,
+    /// 与同一路径的事件合并的事件数
+    pub coalesced: u64,
+    /// 因溢出丢弃的事件数
+    pub dropped: u64,
+    /// 溢出次数（每次溢出需要重新扫描一次）
+    pub overflows: u64,
+}
+
+/// 一批待处理的事件
+#[derive(Debug, Default)]
+pub struct EventBatch {
+    /// 合并后的事件（按首次到达的顺序）
+    pub events: Vec<FileEvent>,
+    /// 有事件被丢弃，需要重新扫描整个文件夹
+    pub rescan: bool,
+}
+
+impl EventBatch {
+    /// 是否没有任何需要处理的内容
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && !self.rescan
+    }
+}
+
+#[derive(Debug, Default)]
+struct BatcherState {
+    /// 路径 -> (到达顺序, 该路径最新的事件)
+    pending: HashMap<PathBuf, (u64, FileEvent)>,
+    /// 下一个事件的到达顺序
+    next_seq: u64,
+    /// 已溢出，取出下一批前丢弃所有事件
+    overflowed: bool,
+    metrics: EventMetrics,
+}
+
+/// 监控事件的合并队列（有界）
+#[derive(Debug)]
+pub struct EventBatcher {
+    /// 最多积压的路径数
+    capacity: usize,
+    state: Mutex<BatcherState>,
+    /// 有新事件或溢出时通知等待的消费方
+    ready: Notify,
+}
+
+impl EventBatcher {
+    /// 创建队列
+    ///
+    /// # 参数
+    /// - capacity: 最多积压的路径数（至少为 1）
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(BatcherState::default()),
+            ready: Notify::new(),
+        }
+    }
+
+    /// 放入一个事件
+    ///
+    /// # 返回
+    /// 事件是否被保留（已溢出时返回 false）
+    pub fn push(&self, mut event: FileEvent) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.metrics.received += 1;
+        if state.overflowed {
+            state.metrics.dropped += 1;
+            return false;
+        }
+
+        if let Some((seq, previous)) = state.pending.remove(&event.path) {
+            if event.old_path.is_none() {
+                event.old_path = previous.old_path;
+            }
+            state.metrics.coalesced += 1;
+            state.pending.insert(event.path.clone(), (seq, event));
+            return true;
+        }
+
+        if state.pending.len() >= self.capacity {
+            state.metrics.dropped += state.pending.len() as u64 + 1;
+            state.metrics.overflows += 1;
+            state.pending.clear();
+            state.overflowed = true;
+            drop(state);
+            tracing::warn!(
+                capacity = self.capacity,
+                "文件监控事件过多，改为重新扫描整个文件夹"
+            );
+            self.ready.notify_one();
+            return false;
+        }
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.pending.insert(event.path.clone(), (seq, event));
+        drop(state);
+        self.ready.notify_one();
+        true
+    }
+
+    /// 取出当前积压的事件（没有时返回空批次）
+    pub fn take(&self) -> EventBatch {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut events: Vec<(u64, FileEvent)> = state.pending.drain().map(|(_, e)| e).collect();
+        events.sort_by_key(|(seq, _)| *seq);
+        let rescan = std::mem::take(&mut state.overflowed);
+        EventBatch {
+            events: events.into_iter().map(|(_, event)| event).collect(),
+            rescan,
+        }
+    }
+
+    /// 等待并取出下一批事件
+    pub async fn next_batch(&self) -> EventBatch {
+        loop {
+            let batch = self.take();
+            if !batch.is_empty() {
+                return batch;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// 累计统计
+    pub fn metrics(&self) -> EventMetrics {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).metrics
+    }
+}
+
+impl Default for EventBatcher {
+    fn default() -> Self {
+        Self::new(WATCHER_QUEUE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_watcher::FileEventType;
+
+    fn event(event_type: FileEventType, path: &str) -> FileEvent {
+        FileEvent::new(event_type, PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_coalesces_events_per_path() {
+        let batcher = EventBatcher::new(10);
+        batcher.push(event(FileEventType::Create, "/sync/a.txt"));
+        batcher.push(FileEvent::new_rename(
+            PathBuf::from("/sync/old.txt"),
+            PathBuf::from("/sync/b.txt"),
+        ));
+        batcher.push(event(FileEventType::Modify, "/sync/a.txt"));
+        batcher.push(event(FileEventType::Modify, "/sync/b.txt"));
+
+        let batch = batcher.take();
+        assert!(!batch.rescan);
+        assert_eq!(batch.events.len(), 2);
+        assert_eq!(batch.events[0].path, PathBuf::from("/sync/a.txt"));
+        assert_eq!(batch.events[0].event_type, FileEventType::Modify);
+        // 重命名后再次修改时保留旧路径
+        assert_eq!(
+            batch.events[1].old_path,
+            Some(PathBuf::from("/sync/old.txt"))
+        );
+        assert!(batcher.take().is_empty());
+
+        let metrics = batcher.metrics();
+        assert_eq!(metrics.received, 4);
+        assert_eq!(metrics.coalesced, 2);
+    }
+
+    #[test]
+    fn test_overflow_requests_rescan() {
+        let batcher = EventBatcher::new(2);
+        assert!(batcher.push(event(FileEventType::Create, "/sync/1")));
+        assert!(batcher.push(event(FileEventType::Create, "/sync/2")));
+        assert!(!batcher.push(event(FileEventType::Create, "/sync/3")));
+        assert!(!batcher.push(event(FileEventType::Create, "/sync/1")));
+
+        let batch = batcher.take();
+        assert!(batch.rescan);
+        assert!(batch.events.is_empty());
+        assert_eq!(
+            batcher.metrics(),
+            EventMetrics {
+                received: 4,
+                coalesced: 0,
+                dropped: 4,
+                overflows: 1,
+            }
+        );
+
+        // 取出溢出的批次后重新开始积压
+        assert!(batcher.push(event(FileEventType::Modify, "/sync/4")));
+        assert_eq!(batcher.take().events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_waits_for_events() {
+        let batcher = std::sync::Arc::new(EventBatcher::new(10));
+        let consumer = {
+            let batcher = batcher.clone();
+            tokio::spawn(async move { batcher.next_batch().await })
+        };
+        batcher.push(event(FileEventType::Delete, "/sync/a.txt"));
+
+        let batch = consumer.await.unwrap();
+        assert_eq!(batch.events.len(), 1);
+    }
+}
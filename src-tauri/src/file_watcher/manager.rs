@@ -0,0 +1,224 @@
+/// 监控器管理器
+///
+/// 为同步文件夹启动本地文件监控，监控事件与同步之间通过 `EventBatcher` 限流：
+/// - notify 的事件转换为 `FileEvent` 放入该文件夹的事件队列
+/// - 后台任务按批取出事件并标记 `ScanCache` 的脏路径（溢出的批次改为下次完整扫描），
+///   事件停止 `FOLDER_WATCH_DEBOUNCE_MS` 毫秒后触发一次自动同步
+/// - 监控中的文件夹在 `ScanCache` 中注册，之后的自动同步增量扫描本地目录
+///
+/// 停止监控时只通知后台任务在取下一批事件前退出，正在进行的同步照常完成（或通过 `SyncControl` 取消），
+/// 会话记录、文件夹状态与远程锁都由同步自身正常收尾
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use super::{EventBatcher, EventMetrics, FileEvent, FileEventType};
+use crate::commands::sync::run_scheduled_sync;
+use crate::constants::FOLDER_WATCH_DEBOUNCE_MS;
+use crate::sync::incremental::ScanCache;
+use crate::{Result, SyncError};
+
+/// 运行中的文件夹监控器
+struct FolderWatcher {
+    /// 释放时停止监控
+    _watcher: RecommendedWatcher,
+    /// 监控事件队列
+    batcher: Arc<EventBatcher>,
+    /// 通知后台任务退出
+    shutdown: CancellationToken,
+}
+
+/// 文件夹监控器管理器
+///
+/// 作为 Tauri State 管理，每个文件夹最多一个监控器
+#[derive(Default)]
+pub struct FileWatcherManager {
+    /// 文件夹 ID -> 运行中的监控器
+    watchers: Mutex<HashMap<String, FolderWatcher>>,
+}
+
+impl FileWatcherManager {
+    /// 开始监控文件夹
+    ///
+    /// # 参数
+    /// - app: 应用句柄（标记脏路径并触发同步）
+    /// - folder_id: 同步文件夹 ID
+    /// - root: 同步文件夹本地根目录
+    ///
+    /// # 返回
+    /// - Err(SyncError::WatcherError): 已在监控或无法监控该目录
+    pub fn start(&self, app: &AppHandle, folder_id: &str, root: &Path) -> Result<()> {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|e| e.into_inner());
+        if watchers.contains_key(folder_id) {
+            return Err(SyncError::WatcherError(format!(
+                "Folder watcher already running: {}",
+                folder_id
+            )));
+        }
+
+        let batcher = Arc::new(EventBatcher::default());
+        let sink = batcher.clone();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: notify::Result<Event>| match res {
+                Ok(event) => {
+                    for event in file_events(event) {
+                        sink.push(event);
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "文件监控出错"),
+            },
+            Config::default(),
+        )
+        .map_err(|e| SyncError::WatcherError(format!("Failed to create watcher: {}", e)))?;
+        watcher.watch(root, RecursiveMode::Recursive).map_err(|e| {
+            SyncError::WatcherError(format!("Failed to watch {}: {}", root.display(), e))
+        })?;
+
+        app.state::<ScanCache>().set_watched(folder_id, true);
+        let shutdown = CancellationToken::new();
+        tauri::async_runtime::spawn(forward(
+            app.clone(),
+            folder_id.to_string(),
+            root.to_path_buf(),
+            batcher.clone(),
+            shutdown.clone(),
+        ));
+        watchers.insert(
+            folder_id.to_string(),
+            FolderWatcher {
+                _watcher: watcher,
+                batcher,
+                shutdown,
+            },
+        );
+
+        tracing::info!(folder_id = %folder_id, root = %root.display(), "已开始监控同步文件夹");
+        Ok(())
+    }
+
+    /// 停止监控文件夹（没有运行中的监控器时不做任何事）
+    ///
+    /// 后台任务正在同步时等同步结束后退出，不会中断同步
+    ///
+    /// # 返回
+    /// 是否停止了运行中的监控器
+    pub fn stop(&self, app: &AppHandle, folder_id: &str) -> bool {
+        let Some(watcher) = self
+            .watchers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(folder_id)
+        else {
+            return false;
+        };
+        watcher.shutdown.cancel();
+        app.state::<ScanCache>().set_watched(folder_id, false);
+
+        tracing::info!(folder_id = %folder_id, "已停止监控同步文件夹");
+        true
+    }
+
+    /// 文件夹监控事件队列的统计（没有运行中的监控器时返回 None）
+    pub fn metrics(&self, folder_id: &str) -> Option<EventMetrics> {
+        self.watchers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(folder_id)
+            .map(|watcher| watcher.batcher.metrics())
+    }
+}
+
+/// 按批取出事件并标记脏路径，事件停止后触发自动同步
+///
+/// 收到退出通知时在两次同步之间退出
+async fn forward(
+    app: AppHandle,
+    folder_id: String,
+    root: PathBuf,
+    batcher: Arc<EventBatcher>,
+    shutdown: CancellationToken,
+) {
+    let debounce = Duration::from_millis(FOLDER_WATCH_DEBOUNCE_MS);
+    loop {
+        let mut batch = tokio::select! {
+            batch = batcher.next_batch() => batch,
+            _ = shutdown.cancelled() => return,
+        };
+        // 防抖处理：等待事件停止
+        while !batch.is_empty() {
+            app.state::<ScanCache>()
+                .apply_batch(&folder_id, &root, &batch);
+            tokio::select! {
+                _ = tokio::time::sleep(debounce) => {}
+                _ = shutdown.cancelled() => return,
+            }
+            batch = batcher.take();
+        }
+
+        if let Err(e) = run_scheduled_sync(&app, &folder_id).await {
+            tracing::warn!(folder_id = %folder_id, error = %e, "本地文件变化触发的同步失败");
+        }
+    }
+}
+
+/// 将 notify 事件转换为文件事件（访问等其他事件忽略）
+fn file_events(event: Event) -> Vec<FileEvent> {
+    let event_type = match event.kind {
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            let mut paths = event.paths.into_iter();
+            let (from, to) = (paths.next().unwrap(), paths.next().unwrap());
+            return vec![FileEvent::new_rename(from, to)];
+        }
+        EventKind::Create(_) => FileEventType::Create,
+        EventKind::Modify(_) => FileEventType::Modify,
+        EventKind::Remove(_) => FileEventType::Delete,
+        _ => return Vec::new(),
+    };
+    event
+        .paths
+        .into_iter()
+        .map(|path| FileEvent::new(event_type, path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange};
+
+    #[test]
+    fn test_file_events() {
+        let created = file_events(
+            Event::new(EventKind::Create(CreateKind::File)).add_path(PathBuf::from("/sync/a.txt")),
+        );
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].event_type, FileEventType::Create);
+
+        let renamed = file_events(
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+                .add_path(PathBuf::from("/sync/old.txt"))
+                .add_path(PathBuf::from("/sync/new.txt")),
+        );
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(renamed[0].event_type, FileEventType::Rename);
+        assert_eq!(renamed[0].path, PathBuf::from("/sync/new.txt"));
+        assert_eq!(renamed[0].old_path, Some(PathBuf::from("/sync/old.txt")));
+
+        let modified = file_events(
+            Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                .add_path(PathBuf::from("/sync/b.txt")),
+        );
+        assert_eq!(modified[0].event_type, FileEventType::Modify);
+
+        assert!(file_events(
+            Event::new(EventKind::Access(AccessKind::Any)).add_path(PathBuf::from("/sync/c.txt"))
+        )
+        .is_empty());
+    }
+}
@@ -1,6 +1,15 @@
 /// 文件系统监控模块
 ///
 /// 负责实时监控本地同步文件夹的文件变更事件，并触发相应的同步操作。
+///
+/// 模块结构:
+/// - types: 核心数据结构定义
+/// - event_batcher: 事件批处理器（按路径合并事件，积压过多时改为重新扫描）
+/// - manager: 监控器管理器（每个同步文件夹一个监控器，事件停止后触发同步）
+pub mod event_batcher;
+pub mod manager;
 pub mod types;
 
+pub use event_batcher::{EventBatch, EventBatcher, EventMetrics};
+pub use manager::FileWatcherManager;
 pub use types::{FileEvent, FileEventType, FileState, WatcherState};
@@ -0,0 +1,280 @@
+/// 后端消息本地化模块
+///
+/// 后端生成的错误消息默认是英文，而界面默认是中文。错误序列化时携带消息键和参数
+/// （见 `ErrorPayload`），本模块按配置中的 `language` 查表生成对应语言的文本。
+/// 桌面通知的标题与正文也通过本模块生成（消息键以 `notifications.` 开头）。
+///
+/// 模板中使用 `{name}` 引用参数，缺失的参数保持原样；
+/// 未收录的语言回退到 en-US，未收录的消息键回退到错误的英文描述。
+use std::collections::BTreeMap;
+
+use tauri::AppHandle;
+
+use crate::config::get_config;
+use crate::error::{ErrorPayload, Result};
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// 简体中文
+    ZhCn,
+    /// 英文
+    EnUs,
+}
+
+impl Language {
+    /// 从语言代码解析（如 `zh-CN`、`en-US`，同时兼容 `zh_CN` 与 `zh`）
+    ///
+    /// 无法识别的语言回退到英文
+    pub fn from_code(code: &str) -> Self {
+        let normalized = code.trim().replace('_', "-").to_ascii_lowercase();
+        if normalized == "zh" || normalized == "zh-cn" || normalized == "zh-hans" {
+            Language::ZhCn
+        } else {
+            Language::EnUs
+        }
+    }
+}
+
+/// 查找消息模板
+fn template(language: Language, key: &str) -> Option<&'static str> {
+    match language {
+        Language::ZhCn => zh_cn(key),
+        Language::EnUs => en_us(key),
+    }
+}
+
+/// 简体中文消息表
+fn zh_cn(key: &str) -> Option<&'static str> {
+    let text = match key {
+        "errors.IO_ERROR" => "文件读写错误：{detail}",
+        "errors.WEBDAV_ERROR" => "WebDAV 错误：{detail}",
+        "errors.NETWORK_ERROR" => "网络错误：{detail}",
+        "errors.SERIALIZATION_ERROR" => "数据格式错误：{detail}",
+        "errors.TAURI_ERROR" => "应用内部错误：{detail}",
+        "errors.SYNC_CONFLICT" => "同步冲突：{detail}",
+        "errors.AUTH_FAILED" => "认证失败：{detail}",
+        "errors.FILE_NOT_FOUND" => "文件不存在：{detail}",
+        "errors.NOT_FOUND" => "未找到：{detail}",
+        "errors.CONFIG_ERROR" => "配置错误：{detail}",
+        "errors.VALIDATION_ERROR" => "输入无效：{detail}",
+        "errors.DATABASE_ERROR" => "数据库错误：{detail}",
+        "errors.KEYRING_ERROR" => "系统密钥环错误：{detail}",
+        "errors.WATCHER_ERROR" => "文件监控错误：{detail}",
+        "errors.INTEGRITY_ERROR" => "完整性校验失败：{detail}",
+        "errors.INTERRUPTED" => "操作已中断：{detail}",
+        "errors.CANCELLED" => "操作已取消：{detail}",
+        "errors.APP_LOCKED" => "应用已锁定，请输入主密码解锁",
+        "errors.DUPLICATE_SERVER" => "相同的服务器账户已存在：{detail}",
+        "errors.SYNC_ROOT_UNAVAILABLE" => "同步文件夹不可用，已暂停同步：{detail}",
+        "errors.MASS_DELETION_PROTECTION" => "本次同步将删除大量文件，需要确认后继续：{detail}",
+        "errors.INSUFFICIENT_REMOTE_STORAGE" => "服务器剩余空间不足：{detail}",
+        "errors.INSUFFICIENT_LOCAL_STORAGE" => "本地磁盘剩余空间不足：{detail}",
+        "errors.UNKNOWN" => "未知错误：{detail}",
+        "notifications.SYNC_COMPLETED_TITLE" => "同步完成：{folder}",
+        "notifications.SYNC_COMPLETED_BODY" => {
+            "上传 {uploaded} 个，下载 {downloaded} 个，删除 {deleted} 个文件，{errors} 个错误"
+        }
+        "notifications.CONFLICT_TITLE" => "检测到同步冲突：{folder}",
+        "notifications.CONFLICT_BODY" => "{count} 个文件存在冲突，请在冲突列表中处理",
+        "notifications.SYNC_FAILED_TITLE" => "同步失败：{folder}",
+        "notifications.AUTH_FAILED_BODY" => "服务器认证失败，请检查用户名和密码",
+        "notifications.OUT_OF_SPACE_BODY" => "存储空间不足：{detail}",
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// 英文消息表
+fn en_us(key: &str) -> Option<&'static str> {
+    let text = match key {
+        "errors.IO_ERROR" => "I/O error: {detail}",
+        "errors.WEBDAV_ERROR" => "WebDAV error: {detail}",
+        "errors.NETWORK_ERROR" => "Network error: {detail}",
+        "errors.SERIALIZATION_ERROR" => "Invalid data format: {detail}",
+        "errors.TAURI_ERROR" => "Internal application error: {detail}",
+        "errors.SYNC_CONFLICT" => "Sync conflict: {detail}",
+        "errors.AUTH_FAILED" => "Authentication failed: {detail}",
+        "errors.FILE_NOT_FOUND" => "File not found: {detail}",
+        "errors.NOT_FOUND" => "Not found: {detail}",
+        "errors.CONFIG_ERROR" => "Configuration error: {detail}",
+        "errors.VALIDATION_ERROR" => "Validation error: {detail}",
+        "errors.DATABASE_ERROR" => "Database error: {detail}",
+        "errors.KEYRING_ERROR" => "Keyring error: {detail}",
+        "errors.WATCHER_ERROR" => "File watcher error: {detail}",
+        "errors.INTEGRITY_ERROR" => "Integrity check failed: {detail}",
+        "errors.INTERRUPTED" => "Interrupted: {detail}",
+        "errors.CANCELLED" => "Cancelled: {detail}",
+        "errors.APP_LOCKED" => "LightSync is locked, please enter the master password",
+        "errors.DUPLICATE_SERVER" => "This server account already exists: {detail}",
+        "errors.SYNC_ROOT_UNAVAILABLE" => {
+            "Sync folder is unavailable, sync has been paused: {detail}"
+        }
+        "errors.MASS_DELETION_PROTECTION" => {
+            "Sync would delete many files and needs confirmation: {detail}"
+        }
+        "errors.INSUFFICIENT_REMOTE_STORAGE" => "Not enough space on the server: {detail}",
+        "errors.INSUFFICIENT_LOCAL_STORAGE" => "Not enough space on the local disk: {detail}",
+        "errors.UNKNOWN" => "Unknown error: {detail}",
+        "notifications.SYNC_COMPLETED_TITLE" => "Sync completed: {folder}",
+        "notifications.SYNC_COMPLETED_BODY" => {
+            "{uploaded} uploaded, {downloaded} downloaded, {deleted} deleted, {errors} errors"
+        }
+        "notifications.CONFLICT_TITLE" => "Sync conflicts detected: {folder}",
+        "notifications.CONFLICT_BODY" => "{count} files have conflicts and need to be resolved",
+        "notifications.SYNC_FAILED_TITLE" => "Sync failed: {folder}",
+        "notifications.AUTH_FAILED_BODY" => {
+            "Authentication failed, please check your username and password"
+        }
+        "notifications.OUT_OF_SPACE_BODY" => "Out of storage space: {detail}",
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// 替换模板中的 `{name}` 参数
+fn render(template: &str, params: &BTreeMap<String, String>) -> String {
+    params
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// 按消息键生成本地化文本
+///
+/// # 返回
+/// - Some(String): 找到模板（当前语言或 en-US 回退）
+/// - None: 两种语言都未收录该消息键
+pub fn translate(
+    language: Language,
+    key: &str,
+    params: &BTreeMap<String, String>,
+) -> Option<String> {
+    template(language, key)
+        .or_else(|| template(Language::EnUs, key))
+        .map(|template| render(template, params))
+}
+
+/// 生成错误的本地化文本
+///
+/// 消息键未收录时返回错误的英文描述
+pub fn translate_error(language: Language, payload: &ErrorPayload) -> String {
+    let key = if payload.message_key.is_empty() {
+        format!("errors.{}", payload.error_code.as_str())
+    } else {
+        payload.message_key.clone()
+    };
+
+    translate(language, &key, &payload.params).unwrap_or_else(|| payload.message.clone())
+}
+
+/// 将后端返回的错误翻译为当前界面语言
+///
+/// # 参数
+/// - payload: 后端命令返回的错误对象
+/// - language: 目标语言（可选，默认使用配置中的 language）
+///
+/// # 返回
+/// - 成功：返回本地化后的错误文本
+/// - 失败：返回错误信息
+#[tauri::command]
+pub async fn translate_error_message(
+    payload: ErrorPayload,
+    language: Option<String>,
+    app: AppHandle,
+) -> Result<String> {
+    let language = match language {
+        Some(language) => language,
+        None => get_config(app).await?.language,
+    };
+
+    Ok(translate_error(Language::from_code(&language), &payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ErrorCode, SyncError};
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("zh-CN"), Language::ZhCn);
+        assert_eq!(Language::from_code("zh_CN"), Language::ZhCn);
+        assert_eq!(Language::from_code("en-US"), Language::EnUs);
+        assert_eq!(Language::from_code("fr-FR"), Language::EnUs);
+    }
+
+    #[test]
+    fn test_translate_error_zh_cn() {
+        let error = SyncError::NotFound("server-1".to_string());
+        let payload = ErrorPayload::from(&error);
+
+        assert_eq!(
+            translate_error(Language::ZhCn, &payload),
+            "未找到：server-1"
+        );
+        assert_eq!(
+            translate_error(Language::EnUs, &payload),
+            "Not found: server-1"
+        );
+    }
+
+    #[test]
+    fn test_every_error_code_has_templates() {
+        let codes = [
+            ErrorCode::IoError,
+            ErrorCode::WebdavError,
+            ErrorCode::NetworkError,
+            ErrorCode::SerializationError,
+            ErrorCode::TauriError,
+            ErrorCode::SyncConflict,
+            ErrorCode::AuthFailed,
+            ErrorCode::FileNotFound,
+            ErrorCode::NotFound,
+            ErrorCode::ConfigError,
+            ErrorCode::ValidationError,
+            ErrorCode::DatabaseError,
+            ErrorCode::KeyringError,
+            ErrorCode::WatcherError,
+            ErrorCode::IntegrityError,
+            ErrorCode::Interrupted,
+            ErrorCode::Cancelled,
+            ErrorCode::AppLocked,
+            ErrorCode::DuplicateServer,
+            ErrorCode::SyncRootUnavailable,
+            ErrorCode::MassDeletionProtection,
+            ErrorCode::InsufficientRemoteStorage,
+            ErrorCode::InsufficientLocalStorage,
+            ErrorCode::Unknown,
+        ];
+
+        for code in codes {
+            let key = format!("errors.{}", code.as_str());
+            assert!(zh_cn(&key).is_some(), "missing zh-CN template: {}", key);
+            assert!(en_us(&key).is_some(), "missing en-US template: {}", key);
+        }
+    }
+
+    #[test]
+    fn test_unknown_key_falls_back_to_message() {
+        let payload = ErrorPayload {
+            error_code: ErrorCode::Unknown,
+            message: "Something happened".to_string(),
+            context: BTreeMap::new(),
+            message_key: "errors.SOMETHING_NEW".to_string(),
+            params: BTreeMap::new(),
+        };
+
+        assert_eq!(
+            translate_error(Language::ZhCn, &payload),
+            "Something happened"
+        );
+    }
+
+    #[test]
+    fn test_render_leaves_missing_params() {
+        let params = BTreeMap::from([("name".to_string(), "docs".to_string())]);
+        assert_eq!(render("{name}: {detail}", &params), "docs: {detail}");
+    }
+}
@@ -0,0 +1,145 @@
+/// 单实例模块
+///
+/// 同时运行两个 LightSync 实例会争用同一个数据库和同步文件夹，可能破坏同步状态。
+/// 通过 single-instance 插件保证只有一个实例运行：再次启动时新进程直接退出，
+/// 已运行的实例把主窗口显示并聚焦，同时通过 `app://second-instance` 事件转发新进程的命令行参数。
+/// 新进程指定了 `--sync` 时由已运行的实例执行同步。
+///
+/// 插件检测到已运行的实例时直接以 0 退出，无法返回同步结果。每个实例启动时还会取得
+/// 应用数据目录下的实例锁（`try_lock`），无界面同步在取不到锁时不转发，而是以失败的退出码退出。
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::cli::{self, CliOptions};
+use crate::Result;
+
+/// 实例锁文件名（应用数据目录下）
+const LOCK_FILE: &str = "lightsync.lock";
+
+/// 再次启动事件名称
+pub const SECOND_INSTANCE_EVENT: &str = "app://second-instance";
+
+/// 再次启动时转发的启动信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondInstancePayload {
+    /// 命令行参数（不含程序路径）
+    pub args: Vec<String>,
+    /// 新进程的工作目录
+    pub cwd: String,
+}
+
+impl SecondInstancePayload {
+    /// 从新进程的 argv 构建，第一个参数是程序路径，不转发
+    pub fn new(argv: Vec<String>, cwd: String) -> Self {
+        Self {
+            args: argv.into_iter().skip(1).collect(),
+            cwd,
+        }
+    }
+}
+
+/// 实例锁，进程退出时由系统释放
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+/// 尝试取得实例锁
+///
+/// # 参数
+/// - app_dir: 应用数据目录
+///
+/// # 返回
+/// - Ok(Some(lock)): 取得实例锁（没有其他实例在运行）
+/// - Ok(None): 已有实例持有锁
+/// - Err(SyncError::Io): 无法创建或锁定锁文件
+pub fn try_lock(app_dir: &Path) -> Result<Option<InstanceLock>> {
+    std::fs::create_dir_all(app_dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(app_dir.join(LOCK_FILE))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(InstanceLock { _file: file })),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+/// 创建单实例插件
+///
+/// 必须作为第一个插件注册，以便在其他插件初始化前检测到已运行的实例
+pub fn plugin() -> tauri::plugin::TauriPlugin<Wry> {
+    tauri_plugin_single_instance::init(activate)
+}
+
+/// 激活已运行的实例
+///
+/// 主窗口可能已被最小化或隐藏，依次恢复、显示并聚焦
+fn activate(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let payload = SecondInstancePayload::new(argv, cwd);
+    tracing::info!(args = ?payload.args, "检测到再次启动，激活已运行的实例");
+
+    let options = CliOptions::parse(payload.args.clone()).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "忽略无法解析的启动参数");
+        CliOptions::default()
+    });
+
+    if !options.headless {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    if let Some(target) = options.sync {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            cli::run_sync(&app, &target).await;
+        });
+    }
+
+    if let Err(e) = app.emit(SECOND_INSTANCE_EVENT, payload) {
+        tracing::warn!(error = %e, "转发启动参数失败");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_skips_program_path() {
+        let payload = SecondInstancePayload::new(
+            vec![
+                "/usr/bin/lightsync".to_string(),
+                "--sync".to_string(),
+                "all".to_string(),
+            ],
+            "/home/user".to_string(),
+        );
+        assert_eq!(payload.args, vec!["--sync", "all"]);
+        assert_eq!(payload.cwd, "/home/user");
+
+        let payload = SecondInstancePayload::new(Vec::new(), String::new());
+        assert!(payload.args.is_empty());
+    }
+
+    #[test]
+    fn test_try_lock() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", uuid::Uuid::new_v4()));
+
+        let lock = try_lock(&dir).unwrap();
+        assert!(lock.is_some());
+        assert!(try_lock(&dir).unwrap().is_none());
+        drop(lock);
+        assert!(try_lock(&dir).unwrap().is_some());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
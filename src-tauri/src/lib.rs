@@ -4,16 +4,36 @@ mod error;
 mod config;
 // 配置文件监听模块
 mod config_watcher;
+// 配置变更总线模块
+mod config_bus;
+// 配置文件加密模块
+mod config_crypto;
+// 口令加密模块
+mod crypto;
+// 主密码应用锁模块
+mod app_lock;
 // 常量定义模块
 mod constants;
 // 数据库操作模块（公开以供测试使用）
 pub mod database;
 // 系统信息模块
 mod system;
+// 后端消息本地化模块
+mod i18n;
+// 后端日志缓冲模块（供 main 注册 tracing Layer）
+pub mod log_buffer;
+// 命令行参数模块（供 main 解析启动参数）
+pub mod cli;
+// 单实例模块
+mod instance;
+// 桌面通知模块
+mod notifications;
 // WebDAV 模块（公开以供测试使用）
 pub mod webdav;
 // 文件系统监控模块
 pub mod file_watcher;
+// 同步引擎模块
+pub mod sync;
 // Tauri 命令模块（导入宏）
 #[macro_use]
 pub mod commands;
@@ -43,34 +63,204 @@ fn test_error_failure() -> Result<String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    run_with_options(cli::CliOptions::default());
+}
+
+/// 按命令行选项运行应用
+///
+/// # 返回
+/// 进程退出码（无界面模式下为同步结果对应的退出码）
+pub fn run_with_options(options: cli::CliOptions) -> i32 {
+    let mut context = tauri::generate_context!();
+
+    // 实例锁持有到应用退出；无界面同步时已有实例在运行则不转发给它，直接返回失败
+    let app_dir = dirs::data_dir().map(|dir| dir.join(&context.config().identifier));
+    let _instance_lock = match app_dir.as_deref().map(instance::try_lock) {
+        Some(Ok(Some(lock))) => Some(lock),
+        Some(Ok(None)) => match &options.sync {
+            Some(target) if options.headless => return cli::report_already_running(target),
+            _ => None,
+        },
+        Some(Err(e)) => {
+            tracing::warn!(error = %e, "获取实例锁失败");
+            None
+        }
+        None => None,
+    };
+
+    if options.headless {
+        // 无界面模式不创建任何窗口
+        context.config_mut().app.windows.clear();
+    }
+
     tauri::Builder::default()
+        // 单实例插件必须最先注册
+        .plugin(instance::plugin())
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_store::Builder::new().build())
+        // 配置存储按配置项 encryptConfig 加密保存，加载时同时接受加密与明文文件
+        .plugin(
+            tauri_plugin_store::Builder::new()
+                .default_serialize_fn(config_crypto::serialize_store)
+                .default_deserialize_fn(config_crypto::deserialize_store)
+                .build(),
+        )
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_sql::Builder::new()
                 .add_migrations(
                     "sqlite:lightsync.db",
-                    vec![
-                        tauri_plugin_sql::Migration {
-                            version: 1,
-                            description: "initial database schema",
-                            sql: include_str!("../migrations/001_initial.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 2,
-                            description: "add webdav_servers table",
-                            sql: include_str!("../migrations/002_webdav_servers.sql"),
+                    // 与后端打开数据库时执行的迁移共用同一份列表
+                    database::migrations::MIGRATIONS
+                        .iter()
+                        .map(|m| tauri_plugin_sql::Migration {
+                            version: m.version,
+                            description: m.description,
+                            sql: m.sql,
                             kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                    ],
+                        })
+                        .collect(),
                 )
                 .build(),
         )
-        .setup(|app| {
+        .setup(move |app| {
             use tauri::Manager;
 
+            #[cfg(target_os = "macos")]
+            if options.headless {
+                app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+            }
+
+            // 之后的后端日志实时推送给前端诊断面板
+            log_buffer::attach(app.handle().clone());
+
+            // 主密码应用锁，设置了主密码时启动后处于锁定状态
+            let app_lock = app_lock::AppLock::open_in_app_dir(app.handle())?;
+            let locked = app_lock.is_enabled();
+            app.manage(app_lock);
+
+            // 系统 Keyring 不可用时改用加密文件保存服务器密码（锁定期间无法读取）
+            webdav::keyring::KeyringManager::init_fallback(app.handle(), locked)?;
+
+            // 打开共享数据库连接，供后端命令复用
+            let database = database::Database::open_in_app_dir(app.handle())?;
+            app.manage(database);
+            // 同步日志批量写入
+            app.manage(database::log_writer::SyncLogWriter::spawn(
+                app.handle().clone(),
+            ));
+
+            // 服务器 Cookie 会话（SSO 网关），加密保存在应用数据目录
+            let cookies = webdav::cookies::CookieStore::open_in_app_dir(app.handle())?;
+            app.manage(cookies.clone());
+
+            // WebDAV 客户端缓存，同一服务器的命令与同步共享连接池，并附带服务器的 Cookie 会话
+            app.manage(webdav::factory::WebDavClientFactory::with_cookie_store(
+                cookies,
+            ));
+
+            // 本地回收站，并在启动时按保留策略清理过期条目
+            let trash = sync::trash::Trash::open_in_app_dir(app.handle())?;
+            app.manage(trash);
+            commands::trash::spawn_retention_cleanup(app.handle().clone());
+
+            // 恢复上次异常退出遗留的会话、传输和下载临时文件
+            let recovery = commands::sync::spawn_startup_recovery(app.handle().clone());
+
+            // 本地文件历史版本库
+            let versions = sync::versions::VersionStore::open_in_app_dir(app.handle())?;
+            app.manage(versions);
+
+            // 远程图片缩略图缓存
+            let thumbnails = webdav::thumbnails::ThumbnailCache::open_in_app_dir(app.handle())?;
+            app.manage(thumbnails);
+
+            // 配置变更总线，配置保存或被外部修改后通知运行中的子系统
+            app.manage(config_bus::ConfigBus::default());
+
+            let app_config =
+                tauri::async_runtime::block_on(config::get_config(app.handle().clone())).ok();
+
+            // 开启了配置文件加密时，把旧版本留下的明文配置文件加密保存
+            if let Err(e) = config_crypto::ensure_encrypted(app.handle()) {
+                tracing::warn!(error = %e, "加密保存配置文件失败");
+            }
+
+            // WebDAV 请求跟踪，按配置开启（默认关闭）
+            let webdav_trace = app_config
+                .as_ref()
+                .is_some_and(|config| config.webdav_trace);
+            webdav::trace::trace().set_enabled(webdav_trace);
+
+            // 同步暂停控制，从配置中恢复上次的暂停状态
+            let pause_state = app_config
+                .as_ref()
+                .map(sync::control::PauseState::from_config)
+                .unwrap_or_default();
+            app.manage(sync::control::SyncControl::new(pause_state.clone()));
+
+            // 文件夹同步状态，已暂停的文件夹启动后处于 Paused 状态
+            let sync_states = sync::state::SyncStateManager::new(sync::SyncEventEmitter::new(
+                app.handle().clone(),
+            ));
+            for folder in app_config.iter().flat_map(|config| &config.sync_folders) {
+                sync_states.apply_pause(&folder.id, pause_state.is_paused(&folder.id));
+            }
+            app.manage(sync_states);
+
+            // 网络连通性监控，服务器离线时将同步加入队列，恢复后自动同步
+            app.manage(sync::connectivity::ConnectivityMonitor::default());
+            commands::connectivity::spawn_connectivity_monitor(app.handle().clone());
+
+            // 只下载文件夹的远程变更轮询，远程目录指纹变化时才同步
+            commands::remote_poll::spawn_remote_polling(app.handle().clone());
+
+            // 文件仍在写入时推迟上传，静默期过后重新同步所在的文件夹
+            app.manage(sync::stability::StabilityQueue::default());
+            // 上次扫描的结果与脏路径，之后的同步增量扫描本地目录
+            app.manage(sync::incremental::ScanCache::default());
+            // 同步文件夹的本地文件监控，文件变化停止后自动同步
+            app.manage(file_watcher::FileWatcherManager::default());
+            app.manage(sync::policy::PolicySkips::default());
+            app.manage(sync::safety::DeletionGuard::default());
+            // 客户端标识，同步完成后写入远程根目录的同步标记
+            app.manage(sync::marker::ClientId::open_in_app_dir(app.handle())?);
+            app.manage(sync::browse::RemoteCacheRefreshes::default());
+            // 传输速度统计（文件夹同步与手动传输）
+            app.manage(sync::throughput::TransferStats::default());
+            // 开启自动同步的文件夹启动本地文件监控（无界面模式同步一次后退出，不需要监控）
+            if !options.headless {
+                let watchers = app.state::<file_watcher::FileWatcherManager>();
+                for folder in app_config
+                    .iter()
+                    .flat_map(|config| &config.sync_folders)
+                    .filter(|folder| folder.auto_sync)
+                {
+                    if let Err(e) = watchers.start(app.handle(), &folder.id, &folder.local_path) {
+                        tracing::warn!(folder_id = %folder.id, error = %e, "启动文件夹监控失败");
+                    }
+                }
+            }
+            commands::sync::spawn_deferred_sync(app.handle().clone());
+
+            // 配置更新后就地调整暂停状态、请求跟踪与同步策略
+            config_bus::spawn_apply(app.handle().clone());
+
+            // 服务器健康检查，定期记录连接测试结果与延迟
+            commands::health::spawn_health_checks(app.handle().clone());
+
+            // 定期保持与最近使用的服务器的连接（配置项 keep_alive 开启时）
+            commands::health::spawn_keep_alive(app.handle().clone());
+
+            // 永久删除超过保留期的归档服务器
+            commands::webdav::spawn_archive_purge(app.handle().clone());
+
+            // 数据库维护，定期清理旧的同步历史并回收空间
+            commands::maintenance::spawn_db_maintenance(app.handle().clone());
+
+            // 命令行请求的同步（--sync），在启动恢复完成后执行
+            cli::spawn_sync(app.handle().clone(), options, recovery);
+
             if let Some(window) = app.get_webview_window("main") {
                 #[cfg(target_os = "macos")]
                 {
@@ -96,6 +286,28 @@ pub fn run() {
             config::get_config_value,
             config::set_config_value,
             config::reset_config,
+            // 类型化设置命令
+            commands::settings::set_theme,
+            commands::settings::set_language,
+            commands::settings::get_retention_settings,
+            commands::settings::set_retention_settings,
+            commands::settings::get_transfer_settings,
+            commands::settings::set_transfer_settings,
+            commands::settings::get_notification_settings,
+            commands::settings::set_notification_settings,
+            commands::settings::get_sync_policy,
+            commands::settings::set_sync_policy,
+            commands::settings::get_config_encryption,
+            commands::settings::set_config_encryption,
+            // 主密码应用锁命令
+            commands::app_lock::get_app_lock_status,
+            commands::app_lock::unlock_app,
+            commands::app_lock::lock_app,
+            commands::app_lock::set_master_password,
+            commands::app_lock::remove_master_password,
+            // 配置导入导出命令
+            commands::config_transfer::export_config,
+            commands::config_transfer::import_config,
             // 配置文件监听命令
             config_watcher::start_config_watcher,
             config_watcher::stop_config_watcher,
@@ -103,14 +315,108 @@ pub fn run() {
             system::get_runtime_environment,
             system::get_environment_mode,
             system::get_os_type,
+            system::get_disk_usage,
+            system::get_power_state,
+            system::get_network_type,
+            // 消息本地化命令
+            i18n::translate_error_message,
             // WebDAV 命令（由宏统一管理）
             commands::webdav::add_webdav_server,
             commands::webdav::get_webdav_servers,
             commands::webdav::get_webdav_server,
             commands::webdav::update_webdav_server,
             commands::webdav::delete_webdav_server,
-            commands::webdav::test_webdav_connection
+            commands::webdav::set_servers_enabled,
+            commands::webdav::archive_webdav_server,
+            commands::webdav::restore_webdav_server,
+            commands::webdav::get_archived_webdav_servers,
+            commands::webdav::test_webdav_connection,
+            commands::webdav::test_webdav_connection_staged,
+            commands::webdav::apply_redirected_url,
+            commands::webdav::browse_webdav_directory,
+            commands::webdav::create_webdav_directory,
+            commands::webdav::start_nextcloud_login,
+            commands::webdav::complete_nextcloud_login,
+            commands::webdav::fetch_server_certificate,
+            commands::webdav::discover_webdav_url,
+            // 手动传输命令
+            commands::transfer::webdav_upload_file,
+            commands::transfer::webdav_download_file,
+            commands::transfer::get_transfer_stats,
+            // 服务器健康检查命令
+            commands::health::get_server_health,
+            // 文件元数据命令
+            commands::file_metadata::get_folder_file_metadata,
+            commands::file_metadata::get_file_metadata,
+            commands::file_metadata::set_file_sync_state,
+            commands::file_metadata::get_file_sync_states,
+            // 同步历史命令
+            commands::history::get_sync_logs,
+            commands::history::get_sync_sessions,
+            commands::history::get_sync_statistics,
+            // 数据库统计与维护命令
+            commands::maintenance::get_database_stats,
+            commands::maintenance::run_db_maintenance,
+            // 诊断命令
+            commands::diagnostics::get_recent_logs,
+            commands::diagnostics::get_webdav_trace,
+            commands::diagnostics::export_diagnostics,
+            // 同步文件夹命令
+            commands::sync_folders::list_sync_folders,
+            commands::sync_folders::add_sync_folder,
+            commands::sync_folders::update_sync_folder,
+            commands::sync_folders::update_sync_folder_settings,
+            commands::sync_folders::remove_sync_folder,
+            commands::sync_folders::get_folder_stats,
+            commands::sync_folders::browse_cached_remote,
+            commands::sync_folders::get_selective_sync_tree,
+            commands::sync_folders::set_selective_exclusions,
+            commands::sync_folders::set_folder_encryption_passphrase,
+            commands::sync_folders::has_folder_encryption_passphrase,
+            // 重复文件命令
+            commands::duplicates::find_duplicates,
+            commands::duplicates::copy_duplicates_from_canonical,
+            // 同步命令
+            commands::sync::sync_now,
+            commands::sync::trigger_sync,
+            commands::sync::preview_sync,
+            commands::sync::confirm_sync_plan,
+            commands::sync::pause_sync,
+            commands::sync::resume_sync,
+            commands::sync::cancel_sync_session,
+            commands::sync::get_sync_pause_state,
+            commands::sync::get_sync_status,
+            commands::sync::get_policy_skips,
+            // 网络连通性命令
+            commands::connectivity::get_connectivity_state,
+            commands::connectivity::check_connectivity,
+            commands::remote_poll::poll_remote_changes,
+            // 回收站命令
+            commands::trash::list_trash_items,
+            commands::trash::restore_trash_item,
+            commands::trash::purge_trash_item,
+            commands::trash::empty_trash,
+            // 文件历史版本命令
+            commands::versions::list_file_versions,
+            commands::versions::restore_file_version,
+            // 本地文件监控命令
+            commands::watcher::start_folder_watcher,
+            commands::watcher::stop_folder_watcher,
+            commands::watcher::get_watcher_metrics,
+            // 远程缩略图命令
+            commands::thumbnails::get_remote_thumbnail,
+            // 分享链接命令
+            commands::shares::create_share_link,
+            commands::shares::list_shares,
+            commands::shares::delete_share,
+            // 服务器活动命令
+            commands::activity::get_server_activity,
+            // SSO 登录命令
+            commands::sso::open_sso_login,
+            commands::sso::complete_sso_login,
+            commands::sso::clear_server_cookies
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run_return(|_, _| {})
 }
@@ -0,0 +1,253 @@
+/// 后端日志缓冲模块
+///
+/// 提供一个 tracing Layer，把后端日志记录保存到固定容量的环形缓冲区，
+/// 并在应用启动后通过 `log://event` 事件实时推送给前端的诊断面板，
+/// 用户无需查找日志文件即可查看后端日志。
+///
+/// Layer 在 `main` 中初始化日志系统时注册，早于 Tauri 应用创建，
+/// 因此缓冲区是全局单例，AppHandle 在应用 setup 阶段通过 `attach` 注入。
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use crate::constants::LOG_BUFFER_CAPACITY;
+
+/// 日志事件名称
+pub const LOG_EVENT: &str = "log://event";
+
+/// 一条后端日志记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    /// 记录时间（Unix 时间戳，毫秒）
+    pub timestamp: i64,
+    /// 日志级别（ERROR, WARN, INFO, DEBUG, TRACE）
+    pub level: String,
+    /// 日志来源模块
+    pub target: String,
+    /// 日志内容
+    pub message: String,
+    /// 结构化字段
+    pub fields: BTreeMap<String, String>,
+}
+
+/// 日志环形缓冲区
+pub struct LogBuffer {
+    /// 最多保留的记录数
+    capacity: usize,
+    /// 记录（最旧的在前）
+    records: Mutex<VecDeque<(Level, LogRecord)>>,
+    /// 用于推送事件的应用句柄（应用启动前为 None）
+    app: Mutex<Option<AppHandle>>,
+}
+
+impl LogBuffer {
+    /// 创建指定容量的缓冲区
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+            app: Mutex::new(None),
+        }
+    }
+
+    /// 追加一条记录，超出容量时丢弃最旧的记录
+    pub fn push(&self, level: Level, record: LogRecord) {
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() >= self.capacity {
+                records.pop_front();
+            }
+            records.push_back((level, record));
+        }
+    }
+
+    /// 获取最近的日志记录
+    ///
+    /// # 参数
+    /// - level: 最低级别（如 WARN 返回 WARN 和 ERROR），None 表示全部
+    /// - limit: 最多返回的记录数
+    ///
+    /// # 返回
+    /// 按时间顺序排列（最旧的在前）的最近记录
+    pub fn recent(&self, level: Option<Level>, limit: usize) -> Vec<LogRecord> {
+        let Ok(records) = self.records.lock() else {
+            return Vec::new();
+        };
+
+        let mut recent: Vec<LogRecord> = records
+            .iter()
+            .rev()
+            .filter(|(record_level, _)| level.is_none_or(|level| *record_level <= level))
+            .take(limit)
+            .map(|(_, record)| record.clone())
+            .collect();
+        recent.reverse();
+        recent
+    }
+}
+
+/// 全局日志缓冲区
+pub fn buffer() -> &'static LogBuffer {
+    static BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+    BUFFER.get_or_init(|| LogBuffer::new(LOG_BUFFER_CAPACITY))
+}
+
+/// 注入应用句柄，之后的日志记录会通过 `log://event` 推送给前端
+pub fn attach(app: AppHandle) {
+    if let Ok(mut handle) = buffer().app.lock() {
+        *handle = Some(app);
+    }
+}
+
+/// 创建写入全局缓冲区的 tracing Layer
+pub fn layer() -> LogBufferLayer {
+    LogBufferLayer
+}
+
+thread_local! {
+    /// 是否正在处理日志事件（推送事件时 Tauri 内部产生的日志不再重复处理）
+    static IN_LAYER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 把日志记录写入全局缓冲区的 tracing Layer
+pub struct LogBufferLayer;
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if IN_LAYER.with(|flag| flag.replace(true)) {
+            return;
+        }
+
+        let metadata = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let record = LogRecord {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+
+        let buffer = buffer();
+        let app = buffer.app.lock().ok().and_then(|app| app.clone());
+        if let Some(app) = app {
+            let _ = app.emit(LOG_EVENT, record.clone());
+        }
+        buffer.push(*metadata.level(), record);
+
+        IN_LAYER.with(|flag| flag.set(false));
+    }
+}
+
+/// 收集日志事件的 message 与其他字段
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_record(message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: 0,
+            level: String::new(),
+            target: "lightsync_lib".to_string(),
+            message: message.to_string(),
+            fields: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest() {
+        let buffer = LogBuffer::new(2);
+        buffer.push(Level::INFO, create_record("a"));
+        buffer.push(Level::INFO, create_record("b"));
+        buffer.push(Level::INFO, create_record("c"));
+
+        let messages: Vec<_> = buffer
+            .recent(None, 10)
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(messages, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_recent_filters_level_and_limit() {
+        let buffer = LogBuffer::new(10);
+        buffer.push(Level::ERROR, create_record("error"));
+        buffer.push(Level::DEBUG, create_record("debug"));
+        buffer.push(Level::WARN, create_record("warn"));
+        buffer.push(Level::INFO, create_record("info"));
+
+        let messages: Vec<_> = buffer
+            .recent(Some(Level::WARN), 10)
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(messages, vec!["error", "warn"]);
+
+        let messages: Vec<_> = buffer
+            .recent(None, 2)
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(messages, vec!["warn", "info"]);
+    }
+
+    #[test]
+    fn test_layer_captures_fields() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let subscriber = tracing_subscriber::registry().with(layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(folder_id = "f1", count = 3, "log buffer test");
+        });
+
+        let record = buffer()
+            .recent(None, LOG_BUFFER_CAPACITY)
+            .into_iter()
+            .rev()
+            .find(|r| r.message == "log buffer test")
+            .unwrap();
+        assert_eq!(record.level, "WARN");
+        assert_eq!(
+            record.fields.get("folder_id").map(String::as_str),
+            Some("f1")
+        );
+        assert_eq!(record.fields.get("count").map(String::as_str), Some("3"));
+    }
+}
@@ -3,18 +3,37 @@
 
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+use lightsync_lib::cli::{self, CliOptions};
+
 fn main() {
+    cli::attach_console();
+
+    // 解析命令行参数
+    let options = match CliOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
     // 初始化日志系统
     init_logging();
 
     // 启动应用
-    lightsync_lib::run()
+    let exit_code = lightsync_lib::run_with_options(options);
+    std::process::exit(exit_code);
 }
 
 /// 初始化日志系统
 ///
-/// 开发环境：输出到控制台，级别为 debug
+/// 开发环境：输出到控制台（stderr，避免与无界面模式的 JSON 汇总混在一起），级别为 debug
 /// 生产环境：输出到文件，级别为 info
+/// 两种环境下日志都会写入内存缓冲区，供前端诊断面板查看
 fn init_logging() {
     #[cfg(debug_assertions)]
     {
@@ -22,10 +41,12 @@ fn init_logging() {
         tracing_subscriber::registry()
             .with(
                 fmt::layer()
+                    .with_writer(std::io::stderr)
                     .with_target(true)
                     .with_thread_ids(true)
                     .with_line_number(true),
             )
+            .with(lightsync_lib::log_buffer::layer())
             .with(
                 EnvFilter::from_default_env()
                     .add_directive("lightsync=debug".parse().unwrap())
@@ -57,6 +78,7 @@ fn init_logging() {
 
         tracing_subscriber::registry()
             .with(fmt::layer().with_writer(non_blocking).with_ansi(false))
+            .with(lightsync_lib::log_buffer::layer())
             .with(EnvFilter::new("lightsync=info,lightsync_lib=info"))
             .init();
 
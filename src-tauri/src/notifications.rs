@@ -0,0 +1,248 @@
+/// 桌面通知模块
+///
+/// 同步会话结束后按配置发送系统通知：
+/// - 同步完成：汇总上传、下载、删除和错误数量（没有任何变更的同步不通知）
+/// - 同步失败：认证失败或存储空间不足（网络错误会自动重试，不通知）
+/// - 检测到冲突：提示需要处理的冲突文件数
+///
+/// 通知可以全局关闭，也可以按同步文件夹静音；标题与正文按配置的语言生成
+use std::collections::BTreeMap;
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::{AppConfig, SyncFolderConfig};
+use crate::constants::session_status;
+use crate::database::SyncSession;
+use crate::i18n::{self, Language};
+use crate::{Result, SyncError};
+
+/// 一条待发送的桌面通知
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// 标题
+    pub title: String,
+    /// 正文
+    pub body: String,
+}
+
+/// 按消息键生成通知文本
+fn text(language: Language, key: &str, params: &BTreeMap<String, String>) -> String {
+    i18n::translate(language, key, params).unwrap_or_else(|| key.to_string())
+}
+
+/// 生成同步结果对应的通知
+///
+/// # 参数
+/// - config: 应用配置（通知开关与语言）
+/// - folder: 同步文件夹配置
+/// - result: 同步会话结果
+///
+/// # 返回
+/// 需要发送的通知（可能为空）
+pub fn for_sync_result(
+    config: &AppConfig,
+    folder: &SyncFolderConfig,
+    result: &Result<SyncSession>,
+) -> Vec<Notification> {
+    let settings = &config.notifications;
+    if !settings.enabled || settings.muted_folders.contains(&folder.id) {
+        return Vec::new();
+    }
+
+    let language = Language::from_code(&config.language);
+    let mut params = BTreeMap::from([("folder".to_string(), folder.name.clone())]);
+    let mut notifications = Vec::new();
+
+    match result {
+        Ok(session) => {
+            let changed = session.files_uploaded
+                + session.files_downloaded
+                + session.files_deleted
+                + session.errors_count;
+            if settings.sync_completed && session.status == session_status::COMPLETED && changed > 0
+            {
+                params.insert("uploaded".to_string(), session.files_uploaded.to_string());
+                params.insert(
+                    "downloaded".to_string(),
+                    session.files_downloaded.to_string(),
+                );
+                params.insert("deleted".to_string(), session.files_deleted.to_string());
+                params.insert("errors".to_string(), session.errors_count.to_string());
+                notifications.push(Notification {
+                    title: text(language, "notifications.SYNC_COMPLETED_TITLE", &params),
+                    body: text(language, "notifications.SYNC_COMPLETED_BODY", &params),
+                });
+            }
+
+            if settings.conflicts && session.files_conflict > 0 {
+                params.insert("count".to_string(), session.files_conflict.to_string());
+                notifications.push(Notification {
+                    title: text(language, "notifications.CONFLICT_TITLE", &params),
+                    body: text(language, "notifications.CONFLICT_BODY", &params),
+                });
+            }
+        }
+        Err(e) if settings.errors => {
+            let body_key = if matches!(e.root(), SyncError::AuthError(_)) {
+                "notifications.AUTH_FAILED_BODY"
+            } else if e.is_out_of_space() {
+                "notifications.OUT_OF_SPACE_BODY"
+            } else {
+                return notifications;
+            };
+
+            params.insert("detail".to_string(), e.detail());
+            notifications.push(Notification {
+                title: text(language, "notifications.SYNC_FAILED_TITLE", &params),
+                body: text(language, body_key, &params),
+            });
+        }
+        Err(_) => {}
+    }
+
+    notifications
+}
+
+/// 发送一条桌面通知，失败只记录日志
+pub fn send(app: &AppHandle, notification: &Notification) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(&notification.title)
+        .body(&notification.body)
+        .show()
+    {
+        tracing::warn!(error = %e, "发送桌面通知失败");
+    }
+}
+
+/// 按配置发送同步结果通知
+pub fn notify_sync_result(
+    app: &AppHandle,
+    config: &AppConfig,
+    folder: &SyncFolderConfig,
+    result: &Result<SyncSession>,
+) {
+    for notification in for_sync_result(config, folder, result) {
+        send(app, &notification);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn create_folder() -> SyncFolderConfig {
+        SyncFolderConfig {
+            id: "folder-1".to_string(),
+            name: "Documents".to_string(),
+            local_path: PathBuf::from("/home/user/documents"),
+            remote_path: "/documents".to_string(),
+            server_id: "server-1".to_string(),
+            sync_direction: "bidirectional".to_string(),
+            sync_interval: 30,
+            auto_sync: true,
+            ignore_patterns: vec![],
+            conflict_resolution: "ask".to_string(),
+            selective_exclusions: vec![],
+            symlink_policy: "skip".to_string(),
+            encryption: Default::default(),
+            compression: Default::default(),
+            max_delete_ratio: 0.2,
+            first_sync_strategy: "merge-keep-both".to_string(),
+            max_file_size: None,
+            include_extensions: vec![],
+            exclude_extensions: vec![],
+            ignore_system_files: true,
+            sync_policy: None,
+        }
+    }
+
+    fn create_session(uploaded: i32, conflicts: i32) -> SyncSession {
+        SyncSession {
+            id: Some(1),
+            sync_folder_id: 1,
+            status: session_status::COMPLETED.to_string(),
+            started_at: 0,
+            completed_at: Some(1),
+            files_uploaded: uploaded,
+            files_downloaded: 2,
+            files_deleted: 0,
+            files_conflict: conflicts,
+            errors_count: 0,
+            total_bytes: 0,
+            error_message: None,
+        }
+    }
+
+    fn create_config() -> AppConfig {
+        AppConfig {
+            language: "en-US".to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_completed_and_conflict_notifications() {
+        let config = create_config();
+        let notifications = for_sync_result(&config, &create_folder(), &Ok(create_session(3, 1)));
+
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].title, "Sync completed: Documents");
+        assert_eq!(
+            notifications[0].body,
+            "3 uploaded, 2 downloaded, 0 deleted, 0 errors"
+        );
+        assert_eq!(notifications[1].title, "Sync conflicts detected: Documents");
+
+        // 没有变更的同步不通知
+        let mut session = create_session(0, 0);
+        session.files_downloaded = 0;
+        assert!(for_sync_result(&config, &create_folder(), &Ok(session)).is_empty());
+    }
+
+    #[test]
+    fn test_error_notifications() {
+        let config = create_config();
+        let folder = create_folder();
+
+        let auth = Err(SyncError::AuthError("401".to_string()));
+        let notifications = for_sync_result(&config, &folder, &auth);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].title, "Sync failed: Documents");
+
+        let full = Err(SyncError::WebDav(
+            "HTTP 507 Insufficient Storage".to_string(),
+        ));
+        let notifications = for_sync_result(&config, &folder, &full);
+        assert_eq!(
+            notifications[0].body,
+            "Out of storage space: HTTP 507 Insufficient Storage"
+        );
+
+        let network = Err(SyncError::Network("timeout".to_string()));
+        assert!(for_sync_result(&config, &folder, &network).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_and_muted() {
+        let folder = create_folder();
+        let result = Ok(create_session(1, 1));
+
+        let mut config = create_config();
+        config.notifications.muted_folders = vec![folder.id.clone()];
+        assert!(for_sync_result(&config, &folder, &result).is_empty());
+
+        let mut config = create_config();
+        config.notifications.enabled = false;
+        assert!(for_sync_result(&config, &folder, &result).is_empty());
+
+        let mut config = create_config();
+        config.notifications.sync_completed = false;
+        let notifications = for_sync_result(&config, &folder, &result);
+        assert_eq!(notifications.len(), 1);
+        assert!(notifications[0].title.starts_with("Sync conflicts"));
+    }
+}
@@ -0,0 +1,164 @@
+/// 远程目录浏览缓存
+///
+/// 浏览远程目录时直接返回远程目录树缓存（`database::remote_cache`）中的内容，
+/// 缓存写入时间超过 `REMOTE_CACHE_STALE_SECS` 时标记为过期。
+/// 缓存过期或尚未缓存时在后台重新列出远程目录，完成后通过 `sync://remote-cache-updated`
+/// 事件通知前端重新读取。
+///
+/// 加密文件夹的远程文件名可能已混淆、文件大小为密文大小，缓存只能由同步更新
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::REMOTE_CACHE_STALE_SECS;
+use crate::database::{remote_cache, Database, RemoteCacheEntry};
+use crate::sync::delta::list_recursive;
+use crate::sync::filter::SyncFilter;
+use crate::sync::planner::RemoteEntry;
+use crate::webdav::client::WebDavClient;
+use crate::Result;
+
+/// 远程目录树缓存更新事件名称
+pub const REMOTE_CACHE_UPDATED_EVENT: &str = "sync://remote-cache-updated";
+
+/// 从缓存读取的远程目录内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedRemoteListing {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 目录的相对路径（空字符串表示同步根目录）
+    pub path: String,
+    /// 目录的直接子条目
+    pub entries: Vec<RemoteCacheEntry>,
+    /// 缓存时间（Unix 时间戳，秒；尚未缓存时为 None）
+    pub cached_at: Option<i64>,
+    /// 缓存是否已过期（尚未缓存时为 true）
+    pub stale: bool,
+    /// 是否正在后台刷新
+    pub refreshing: bool,
+}
+
+/// 远程目录树缓存更新事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCacheUpdatedEvent {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 缓存的条目数
+    pub entry_count: usize,
+    /// 缓存时间（Unix 时间戳，秒）
+    pub cached_at: i64,
+}
+
+/// 缓存是否已过期
+///
+/// # 参数
+/// - cached_at: 缓存时间（尚未缓存时为 None）
+/// - now: 当前时间（Unix 时间戳，秒）
+pub fn is_stale(cached_at: Option<i64>, now: i64) -> bool {
+    cached_at.is_none_or(|at| now - at >= REMOTE_CACHE_STALE_SECS)
+}
+
+/// 将浏览路径规范化为缓存中的相对路径（去掉首尾的 `/`）
+pub fn normalize_path(path: &str) -> String {
+    path.trim_matches('/').to_string()
+}
+
+/// 加密文件夹的缓存是否可以在后台刷新
+pub fn can_refresh(folder: &SyncFolderConfig) -> bool {
+    !folder.encryption.enabled
+}
+
+/// 正在后台刷新缓存的文件夹
+///
+/// 作为 Tauri State 管理，避免同一文件夹同时发起多次刷新
+#[derive(Debug, Default)]
+pub struct RemoteCacheRefreshes {
+    running: Mutex<HashSet<String>>,
+}
+
+impl RemoteCacheRefreshes {
+    /// 开始刷新文件夹（已在刷新时返回 false）
+    pub fn begin(&self, folder_id: &str) -> bool {
+        self.running.lock().unwrap().insert(folder_id.to_string())
+    }
+
+    /// 刷新结束
+    pub fn finish(&self, folder_id: &str) {
+        self.running.lock().unwrap().remove(folder_id);
+    }
+
+    /// 文件夹是否正在刷新
+    pub fn is_running(&self, folder_id: &str) -> bool {
+        self.running.lock().unwrap().contains(folder_id)
+    }
+}
+
+/// 重新列出远程目录并替换文件夹的缓存
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - client: WebDAV 客户端
+/// - folder: 同步文件夹配置（不能是加密文件夹）
+///
+/// # 返回
+/// - Ok(RemoteCacheUpdatedEvent): 刷新后的缓存信息
+/// - Err(SyncError): 列出远程目录或写入数据库失败
+pub async fn refresh(
+    db: &Database,
+    client: &WebDavClient,
+    folder: &SyncFolderConfig,
+) -> Result<RemoteCacheUpdatedEvent> {
+    let filter = SyncFilter::from_folder(folder);
+    let cached_at = chrono::Utc::now().timestamp();
+
+    let entries: Vec<RemoteCacheEntry> = list_recursive(client, &folder.remote_path, &filter)
+        .await?
+        .iter()
+        .filter_map(|info| RemoteEntry::from_file_info(client, &folder.remote_path, info))
+        .map(|entry| entry.to_cache_entry(cached_at))
+        .collect();
+    remote_cache::replace(db, &folder.id, &entries).await?;
+
+    Ok(RemoteCacheUpdatedEvent {
+        folder_id: folder.id.clone(),
+        entry_count: entries.len(),
+        cached_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stale() {
+        assert!(is_stale(None, 1_000));
+        assert!(!is_stale(Some(1_000), 1_000 + REMOTE_CACHE_STALE_SECS - 1));
+        assert!(is_stale(Some(1_000), 1_000 + REMOTE_CACHE_STALE_SECS));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/"), "");
+        assert_eq!(normalize_path("/docs/reports/"), "docs/reports");
+        assert_eq!(normalize_path("docs"), "docs");
+    }
+
+    #[test]
+    fn test_refreshes_are_exclusive() {
+        let refreshes = RemoteCacheRefreshes::default();
+
+        assert!(refreshes.begin("folder-1"));
+        assert!(!refreshes.begin("folder-1"));
+        assert!(refreshes.is_running("folder-1"));
+        assert!(refreshes.begin("folder-2"));
+
+        refreshes.finish("folder-1");
+        assert!(!refreshes.is_running("folder-1"));
+        assert!(refreshes.begin("folder-1"));
+    }
+}
@@ -0,0 +1,383 @@
+/// 透明压缩
+///
+/// 同步文件夹启用压缩后，匹配扩展名分组且不小于最小阈值的文件在上传前压缩，
+/// 压缩后不比原文件小时仍上传原文件。下载时根据文件头的压缩标记自动解压，
+/// 因此关闭压缩后远程已有的压缩文件仍能正常下载。
+///
+/// # 文件格式
+///
+/// 8 字节魔数 + 1 字节算法标识 + 8 字节原始大小（小端序），之后是压缩数据。
+/// 与加密同时启用时先压缩再加密，解密后再解压
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::config::FolderCompressionConfig;
+use crate::constants::{compression_algorithm, compression_group};
+use crate::{Result, SyncError};
+
+/// 压缩文件魔数（参照 PNG 文件头，包含不可打印字节和换行符以避免与文本文件混淆）
+const MAGIC: &[u8; 8] = b"\x89LSZ\r\n\x1a\n";
+
+/// 压缩文件头长度（魔数 + 算法标识 + 原始大小）
+const HEADER_LEN: usize = MAGIC.len() + 1 + 8;
+
+/// zstd 压缩级别
+const ZSTD_LEVEL: i32 = 3;
+
+/// 压缩算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Zstd,
+    Gzip,
+}
+
+impl Algorithm {
+    /// 解析配置中的算法名称
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            compression_algorithm::ZSTD => Ok(Self::Zstd),
+            compression_algorithm::GZIP => Ok(Self::Gzip),
+            other => Err(SyncError::ConfigError(format!(
+                "Invalid compression algorithm: {}",
+                other
+            ))),
+        }
+    }
+
+    /// 文件头中的算法标识
+    fn id(self) -> u8 {
+        match self {
+            Self::Zstd => 1,
+            Self::Gzip => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self> {
+        match id {
+            1 => Ok(Self::Zstd),
+            2 => Ok(Self::Gzip),
+            other => Err(SyncError::IntegrityError(format!(
+                "Unknown compression algorithm id: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 扩展名分组包含的扩展名（小写，不含点）
+///
+/// # 返回
+/// 未知分组返回 None
+pub fn group_extensions(group: &str) -> Option<&'static [&'static str]> {
+    match group {
+        compression_group::TEXT => Some(&["txt", "md", "markdown", "rst", "log", "csv", "tsv"]),
+        compression_group::CODE => Some(&[
+            "rs", "ts", "tsx", "js", "jsx", "mjs", "vue", "py", "java", "kt", "go", "c", "h",
+            "cpp", "hpp", "cs", "rb", "php", "sh", "ps1", "css", "scss", "html", "htm",
+        ]),
+        compression_group::DATA => Some(&[
+            "json", "xml", "yaml", "yml", "toml", "ini", "sql", "ndjson", "geojson",
+        ]),
+        compression_group::DOCUMENT => Some(&["svg", "rtf", "tex", "ps", "eps"]),
+        _ => None,
+    }
+}
+
+/// 校验压缩设置
+///
+/// # 返回
+/// - Ok(()): 算法和扩展名分组合法
+/// - Err(SyncError::ConfigError): 未知的算法或分组
+pub fn validate(config: &FolderCompressionConfig) -> Result<()> {
+    Algorithm::parse(&config.algorithm)?;
+    if let Some(group) = config
+        .groups
+        .iter()
+        .find(|group| group_extensions(group).is_none())
+    {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid compression group: {}",
+            group
+        )));
+    }
+    Ok(())
+}
+
+/// 同步文件夹的压缩策略
+#[derive(Debug)]
+pub struct CompressionPolicy {
+    algorithm: Algorithm,
+    extensions: HashSet<String>,
+    min_size: u64,
+}
+
+impl CompressionPolicy {
+    /// 根据文件夹的压缩设置创建压缩策略
+    ///
+    /// # 返回
+    /// - Ok(Some(policy)): 已启用压缩
+    /// - Ok(None): 未启用压缩
+    /// - Err(SyncError::ConfigError): 设置不合法
+    pub fn from_config(config: &FolderCompressionConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        validate(config)?;
+
+        let extensions = config
+            .groups
+            .iter()
+            .filter_map(|group| group_extensions(group))
+            .flat_map(|extensions| extensions.iter().map(|ext| ext.to_string()))
+            .chain(
+                config
+                    .extensions
+                    .iter()
+                    .map(|ext| ext.trim_start_matches('.').to_lowercase()),
+            )
+            .filter(|ext| !ext.is_empty())
+            .collect();
+
+        Ok(Some(Self {
+            algorithm: Algorithm::parse(&config.algorithm)?,
+            extensions,
+            min_size: config.min_size,
+        }))
+    }
+
+    /// 文件是否应在上传前压缩
+    ///
+    /// # 参数
+    /// - rel_path: 相对于同步根目录的路径
+    /// - size: 文件大小（字节）
+    pub fn should_compress(&self, rel_path: &str, size: u64) -> bool {
+        if size < self.min_size {
+            return false;
+        }
+        Path::new(rel_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| self.extensions.contains(&ext.to_lowercase()))
+            .unwrap_or(false)
+    }
+
+    /// 压缩文件
+    ///
+    /// # 参数
+    /// - src: 原始文件
+    /// - dst: 压缩后的输出文件
+    ///
+    /// # 返回
+    /// - Ok(u64): 输出文件大小（含文件头）
+    /// - Err(SyncError::Io): 读写失败
+    pub fn compress_file(&self, src: &Path, dst: &Path) -> Result<u64> {
+        let input = File::open(src)?;
+        let original_size = input.metadata()?.len();
+        let mut input = BufReader::new(input);
+        let mut output = BufWriter::new(File::create(dst)?);
+
+        output.write_all(MAGIC)?;
+        output.write_all(&[self.algorithm.id()])?;
+        output.write_all(&original_size.to_le_bytes())?;
+
+        match self.algorithm {
+            Algorithm::Zstd => zstd::stream::copy_encode(&mut input, &mut output, ZSTD_LEVEL)?,
+            Algorithm::Gzip => {
+                let mut encoder = GzEncoder::new(&mut output, flate2::Compression::default());
+                std::io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+        output.flush()?;
+
+        Ok(std::fs::metadata(dst)?.len())
+    }
+}
+
+/// 文件是否带有压缩标记
+pub fn is_compressed(path: &Path) -> Result<bool> {
+    let mut header = [0u8; MAGIC.len()];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == MAGIC),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 带压缩标记的文件原地解压
+///
+/// 先解压到临时文件，校验大小与文件头记录的原始大小一致后替换原文件
+///
+/// # 返回
+/// - Ok(Some(u64)): 已解压，返回解压前的文件大小
+/// - Ok(None): 文件没有压缩标记，未做修改
+/// - Err(SyncError::IntegrityError): 压缩数据损坏
+pub fn decompress_in_place(path: &Path) -> Result<Option<u64>> {
+    if !is_compressed(path)? {
+        return Ok(None);
+    }
+    let compressed_size = std::fs::metadata(path)?.len();
+
+    let partial = crate::webdav::client::partial_download_path(path);
+    match decompress_file(path, &partial) {
+        Ok(_) => {
+            std::fs::rename(&partial, path)?;
+            Ok(Some(compressed_size))
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+/// 解压文件
+///
+/// # 返回
+/// - Ok(u64): 解压后的大小
+/// - Err(SyncError::IntegrityError): 不是压缩文件或压缩数据损坏
+fn decompress_file(src: &Path, dst: &Path) -> Result<u64> {
+    let mut input = BufReader::new(File::open(src)?);
+    let mut header = [0u8; HEADER_LEN];
+    input
+        .read_exact(&mut header)
+        .map_err(|_| SyncError::IntegrityError("Not a compressed LightSync file".to_string()))?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(SyncError::IntegrityError(
+            "Not a compressed LightSync file".to_string(),
+        ));
+    }
+    let algorithm = Algorithm::from_id(header[MAGIC.len()])?;
+    let original_size = u64::from_le_bytes(
+        header[MAGIC.len() + 1..]
+            .try_into()
+            .expect("header length checked"),
+    );
+
+    let mut output = BufWriter::new(File::create(dst)?);
+    let corrupt =
+        |e: std::io::Error| SyncError::IntegrityError(format!("Failed to decompress file: {}", e));
+    let written = match algorithm {
+        Algorithm::Zstd => {
+            let mut decoder = zstd::stream::read::Decoder::new(input).map_err(corrupt)?;
+            std::io::copy(&mut decoder, &mut output).map_err(corrupt)?
+        }
+        Algorithm::Gzip => {
+            std::io::copy(&mut GzDecoder::new(input), &mut output).map_err(corrupt)?
+        }
+    };
+    output.flush()?;
+
+    if written != original_size {
+        return Err(SyncError::IntegrityError(format!(
+            "Decompressed size mismatch: expected {}, got {}",
+            original_size, written
+        )));
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("lightsync-compression-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn config(algorithm: &str) -> FolderCompressionConfig {
+        FolderCompressionConfig {
+            enabled: true,
+            algorithm: algorithm.to_string(),
+            groups: vec![compression_group::TEXT.to_string()],
+            extensions: vec![".Dat".to_string()],
+            min_size: 16,
+        }
+    }
+
+    #[test]
+    fn test_should_compress_matches_groups_extensions_and_threshold() {
+        let policy = CompressionPolicy::from_config(&config("zstd"))
+            .unwrap()
+            .unwrap();
+
+        assert!(policy.should_compress("docs/notes.TXT", 1024));
+        assert!(policy.should_compress("raw/sample.dat", 1024));
+        assert!(!policy.should_compress("docs/notes.txt", 8));
+        assert!(!policy.should_compress("photos/cat.jpg", 1024));
+        assert!(!policy.should_compress("Makefile", 1024));
+
+        let disabled = FolderCompressionConfig::default();
+        assert!(CompressionPolicy::from_config(&disabled).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_round_trip_for_each_algorithm() {
+        let dir = temp_dir();
+        let src = dir.join("notes.txt");
+        let content = "lightsync compression\n".repeat(500);
+        fs::write(&src, &content).unwrap();
+
+        for algorithm in [compression_algorithm::ZSTD, compression_algorithm::GZIP] {
+            let policy = CompressionPolicy::from_config(&config(algorithm))
+                .unwrap()
+                .unwrap();
+            let compressed = dir.join(format!("notes.{}", algorithm));
+            let size = policy.compress_file(&src, &compressed).unwrap();
+            assert!(size < content.len() as u64);
+            assert!(is_compressed(&compressed).unwrap());
+
+            assert_eq!(decompress_in_place(&compressed).unwrap(), Some(size));
+            assert_eq!(fs::read_to_string(&compressed).unwrap(), content);
+        }
+
+        // 没有压缩标记的文件保持原样
+        assert_eq!(decompress_in_place(&src).unwrap(), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_data_is_rejected_and_file_untouched() {
+        let dir = temp_dir();
+        let src = dir.join("notes.txt");
+        fs::write(&src, "abc".repeat(1000)).unwrap();
+        let compressed = dir.join("notes.lsz");
+        let policy = CompressionPolicy::from_config(&config("gzip"))
+            .unwrap()
+            .unwrap();
+        policy.compress_file(&src, &compressed).unwrap();
+
+        let mut bytes = fs::read(&compressed).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        fs::write(&compressed, &bytes).unwrap();
+
+        assert!(matches!(
+            decompress_in_place(&compressed),
+            Err(SyncError::IntegrityError(_))
+        ));
+        assert_eq!(fs::read(&compressed).unwrap(), bytes);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_values() {
+        let mut invalid = config("brotli");
+        assert!(matches!(validate(&invalid), Err(SyncError::ConfigError(_))));
+
+        invalid.algorithm = compression_algorithm::ZSTD.to_string();
+        invalid.groups.push("video".to_string());
+        assert!(matches!(validate(&invalid), Err(SyncError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,231 @@
+/// 网络连通性监控与离线模式
+///
+/// 后台任务定期向已启用的服务器发送轻量的 OPTIONS 请求（不携带认证信息，
+/// 收到任何 HTTP 响应即视为可达），按服务器记录在线状态：
+/// - 服务器离线时，针对该服务器的同步请求不再发起网络请求，而是加入待同步队列
+/// - 同步过程中出现网络错误时，立即将服务器标记为离线
+/// - 服务器恢复可达后，自动同步队列中的文件夹
+///
+/// 状态变化通过 `connectivity://changed` 事件推送到前端。
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::WebDavServerConfig;
+
+/// 连通性变化事件名称
+pub const CONNECTIVITY_CHANGED_EVENT: &str = "connectivity://changed";
+
+/// 连通性状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityState {
+    /// 是否在线（没有服务器或至少一个服务器可达时为 true）
+    pub online: bool,
+    /// 不可达的服务器 ID
+    pub offline_servers: BTreeSet<String>,
+    /// 等待恢复连接后同步的文件夹 ID
+    pub queued_folders: BTreeSet<String>,
+    /// 最后一次检测时间（Unix 时间戳，秒）
+    pub last_checked_at: Option<i64>,
+}
+
+/// 连通性监控器
+///
+/// 作为 Tauri State 管理，克隆后共享同一个状态
+#[derive(Debug, Clone)]
+pub struct ConnectivityMonitor {
+    state: Arc<Mutex<ConnectivityState>>,
+}
+
+impl Default for ConnectivityMonitor {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ConnectivityState {
+                online: true,
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+impl ConnectivityMonitor {
+    /// 当前连通性状态
+    pub fn state(&self) -> ConnectivityState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// 指定服务器是否在线
+    pub fn is_server_online(&self, server_id: &str) -> bool {
+        !self
+            .state
+            .lock()
+            .unwrap()
+            .offline_servers
+            .contains(server_id)
+    }
+
+    /// 将文件夹加入待同步队列
+    pub fn enqueue(&self, folder_id: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .queued_folders
+            .insert(folder_id.to_string());
+    }
+
+    /// 同步时遇到网络错误，将服务器标记为离线
+    ///
+    /// # 返回
+    /// 状态是否发生变化
+    pub fn mark_offline(&self, server_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.offline_servers.insert(server_id.to_string())
+    }
+
+    /// 记录一轮检测结果
+    ///
+    /// # 参数
+    /// - results: 服务器 ID -> 是否可达（未包含的服务器视为已删除或已禁用，从离线列表中移除）
+    ///
+    /// # 返回
+    /// 状态是否发生变化
+    pub fn record_check(&self, results: &BTreeMap<String, bool>) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let before = (state.online, state.offline_servers.clone());
+
+        state.offline_servers = results
+            .iter()
+            .filter(|(_, reachable)| !**reachable)
+            .map(|(id, _)| id.clone())
+            .collect();
+        state.online = results.is_empty() || results.values().any(|reachable| *reachable);
+        state.last_checked_at = Some(chrono::Utc::now().timestamp());
+
+        before != (state.online, state.offline_servers.clone())
+    }
+
+    /// 取出服务器已恢复在线的排队文件夹
+    ///
+    /// # 参数
+    /// - folder_servers: 文件夹 ID -> 服务器 ID（已不存在的文件夹会被直接移出队列）
+    ///
+    /// # 返回
+    /// 可以开始同步的文件夹 ID
+    pub fn take_ready(&self, folder_servers: &HashMap<String, String>) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        let ready: Vec<String> = state
+            .queued_folders
+            .iter()
+            .filter(|folder_id| match folder_servers.get(*folder_id) {
+                Some(server_id) => !state.offline_servers.contains(server_id),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        for folder_id in &ready {
+            state.queued_folders.remove(folder_id);
+        }
+
+        ready
+            .into_iter()
+            .filter(|folder_id| folder_servers.contains_key(folder_id))
+            .collect()
+    }
+}
+
+/// 检测服务器是否可达
+///
+/// 发送不带认证信息的 OPTIONS 请求，收到任何 HTTP 响应（包括 401）即视为可达
+pub async fn probe_server(server: &WebDavServerConfig, timeout: Duration) -> bool {
+    let mut builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .danger_accept_invalid_certs(server.accept_invalid_certs);
+    if let Some(pinned) = &server.pinned_cert_fingerprint {
+        match crate::webdav::tls::pinned_tls_config(pinned) {
+            Ok(config) => builder = builder.use_preconfigured_tls(config),
+            Err(e) => {
+                tracing::warn!(server_id = %server.id, error = %e, "创建连通性检测客户端失败");
+                return false;
+            }
+        }
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(server_id = %server.id, error = %e, "创建连通性检测客户端失败");
+            return false;
+        }
+    };
+
+    client
+        .request(reqwest::Method::OPTIONS, server.resolved_url())
+        .send()
+        .await
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(entries: &[(&str, bool)]) -> BTreeMap<String, bool> {
+        entries
+            .iter()
+            .map(|(id, reachable)| (id.to_string(), *reachable))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_state_is_online() {
+        let monitor = ConnectivityMonitor::default();
+        assert!(monitor.state().online);
+        assert!(monitor.is_server_online("server-1"));
+    }
+
+    #[test]
+    fn test_record_check_transitions() {
+        let monitor = ConnectivityMonitor::default();
+
+        assert!(monitor.record_check(&results(&[("s1", false), ("s2", false)])));
+        let state = monitor.state();
+        assert!(!state.online);
+        assert!(!monitor.is_server_online("s1"));
+
+        assert!(monitor.record_check(&results(&[("s1", true), ("s2", false)])));
+        assert!(monitor.state().online);
+        assert!(monitor.is_server_online("s1"));
+        assert!(!monitor.is_server_online("s2"));
+
+        // 状态不变时不报告变化
+        assert!(!monitor.record_check(&results(&[("s1", true), ("s2", false)])));
+    }
+
+    #[test]
+    fn test_take_ready_only_returns_online_folders() {
+        let monitor = ConnectivityMonitor::default();
+        monitor.mark_offline("s1");
+        monitor.mark_offline("s2");
+        monitor.enqueue("f1");
+        monitor.enqueue("f2");
+        monitor.enqueue("removed");
+
+        let folder_servers = HashMap::from([
+            ("f1".to_string(), "s1".to_string()),
+            ("f2".to_string(), "s2".to_string()),
+        ]);
+
+        monitor.record_check(&results(&[("s1", true), ("s2", false)]));
+        assert_eq!(monitor.take_ready(&folder_servers), vec!["f1".to_string()]);
+
+        // 已删除的文件夹被移出队列，离线服务器的文件夹保留
+        let queued = monitor.state().queued_folders;
+        assert_eq!(
+            queued.into_iter().collect::<Vec<_>>(),
+            vec!["f2".to_string()]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+/// 同步控制（暂停与取消）
+///
+/// 支持全局暂停和按文件夹暂停。暂停状态通过 `tokio::sync::watch` 广播，
+/// 传输池在每个数据块之间检查状态，暂停时在当前数据块完成后停止；
+/// 暂停状态同时保存在配置文件中，应用重启后恢复。
+///
+/// 每个运行中的同步会话持有一个 `CancellationToken`，取消后进行中的 WebDAV 请求
+/// 立即中止，会话以 cancelled 状态结束。
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::AppConfig;
+
+/// 暂停状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseState {
+    /// 是否全局暂停
+    pub global: bool,
+    /// 单独暂停的文件夹 ID
+    pub folders: BTreeSet<String>,
+}
+
+impl PauseState {
+    /// 从配置中读取暂停状态
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            global: config.sync_paused,
+            folders: config.paused_folders.iter().cloned().collect(),
+        }
+    }
+
+    /// 将暂停状态写回配置
+    pub fn apply_to(&self, config: &mut AppConfig) {
+        config.sync_paused = self.global;
+        config.paused_folders = self.folders.iter().cloned().collect();
+    }
+
+    /// 指定文件夹是否处于暂停状态（全局暂停或单独暂停）
+    pub fn is_paused(&self, folder_id: &str) -> bool {
+        self.global || self.folders.contains(folder_id)
+    }
+}
+
+/// 同步控制器
+///
+/// 作为 Tauri State 管理，克隆后共享同一个状态
+#[derive(Debug, Clone)]
+pub struct SyncControl {
+    state: Arc<watch::Sender<PauseState>>,
+    sessions: Arc<Mutex<HashMap<i64, CancellationToken>>>,
+}
+
+impl Default for SyncControl {
+    fn default() -> Self {
+        Self::new(PauseState::default())
+    }
+}
+
+impl SyncControl {
+    /// 使用初始暂停状态创建控制器
+    pub fn new(state: PauseState) -> Self {
+        let (sender, _) = watch::channel(state);
+        Self {
+            state: Arc::new(sender),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 当前暂停状态
+    pub fn state(&self) -> PauseState {
+        self.state.borrow().clone()
+    }
+
+    /// 暂停同步
+    ///
+    /// # 参数
+    /// - folder_id: 要暂停的文件夹 ID，None 表示全局暂停
+    ///
+    /// # 返回
+    /// 更新后的暂停状态
+    pub fn pause(&self, folder_id: Option<&str>) -> PauseState {
+        self.state.send_modify(|state| match folder_id {
+            Some(id) => {
+                state.folders.insert(id.to_string());
+            }
+            None => state.global = true,
+        });
+        self.state()
+    }
+
+    /// 恢复同步
+    ///
+    /// # 参数
+    /// - folder_id: 要恢复的文件夹 ID，None 表示解除全局暂停并恢复所有文件夹
+    ///
+    /// # 返回
+    /// 更新后的暂停状态（全局暂停时单独恢复某个文件夹不会使其继续同步）
+    pub fn resume(&self, folder_id: Option<&str>) -> PauseState {
+        self.state.send_modify(|state| match folder_id {
+            Some(id) => {
+                state.folders.remove(id);
+            }
+            None => {
+                state.global = false;
+                state.folders.clear();
+            }
+        });
+        self.state()
+    }
+
+    /// 替换暂停状态（配置被修改后使用）
+    ///
+    /// # 返回
+    /// 暂停状态是否发生变化
+    pub fn set_state(&self, state: PauseState) -> bool {
+        self.state.send_if_modified(|current| {
+            if *current == state {
+                return false;
+            }
+            *current = state;
+            true
+        })
+    }
+
+    /// 指定文件夹是否处于暂停状态
+    pub fn is_paused(&self, folder_id: &str) -> bool {
+        self.state.borrow().is_paused(folder_id)
+    }
+
+    /// 获取指定文件夹的暂停信号，供传输池在数据块之间检查
+    pub fn signal(&self, folder_id: &str) -> PauseSignal {
+        PauseSignal {
+            folder_id: folder_id.to_string(),
+            receiver: self.state.subscribe(),
+        }
+    }
+
+    /// 登记运行中的同步会话
+    ///
+    /// # 返回
+    /// 会话的取消令牌
+    pub fn register_session(&self, session_id: i64) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session_id, token.clone());
+        token
+    }
+
+    /// 会话结束后移除登记
+    pub fn finish_session(&self, session_id: i64) {
+        self.sessions.lock().unwrap().remove(&session_id);
+    }
+
+    /// 取消运行中的同步会话
+    ///
+    /// # 返回
+    /// 会话是否正在运行
+    pub fn cancel_session(&self, session_id: i64) -> bool {
+        match self.sessions.lock().unwrap().get(&session_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 单个文件夹的暂停信号
+#[derive(Debug, Clone)]
+pub struct PauseSignal {
+    folder_id: String,
+    receiver: watch::Receiver<PauseState>,
+}
+
+impl PauseSignal {
+    /// 文件夹当前是否处于暂停状态
+    pub fn is_paused(&self) -> bool {
+        self.receiver.borrow().is_paused(&self.folder_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_and_resume_folder() {
+        let control = SyncControl::default();
+        let signal = control.signal("folder-1");
+
+        control.pause(Some("folder-1"));
+        assert!(signal.is_paused());
+        assert!(!control.is_paused("folder-2"));
+
+        control.resume(Some("folder-1"));
+        assert!(!signal.is_paused());
+    }
+
+    #[test]
+    fn test_set_state() {
+        let control = SyncControl::default();
+        let signal = control.signal("folder-1");
+
+        let state = PauseState {
+            global: false,
+            folders: ["folder-1".to_string()].into_iter().collect(),
+        };
+        assert!(control.set_state(state.clone()));
+        assert!(signal.is_paused());
+        assert!(!control.set_state(state));
+    }
+
+    #[test]
+    fn test_global_pause_overrides_folder_resume() {
+        let control = SyncControl::default();
+        control.pause(None);
+        control.pause(Some("folder-1"));
+
+        let state = control.resume(Some("folder-1"));
+        assert!(state.global);
+        assert!(control.is_paused("folder-1"));
+
+        let state = control.resume(None);
+        assert_eq!(state, PauseState::default());
+    }
+
+    #[test]
+    fn test_cancel_session() {
+        let control = SyncControl::default();
+        let token = control.register_session(7);
+
+        assert!(!control.cancel_session(8));
+        assert!(control.cancel_session(7));
+        assert!(token.is_cancelled());
+
+        control.finish_session(7);
+        assert!(!control.cancel_session(7));
+    }
+}
@@ -0,0 +1,319 @@
+/// 远程变更获取
+///
+/// 优先使用 WebDAV sync-collection REPORT（RFC 6578）获取自上次同步以来的增量变更，
+/// 服务器未声明支持或令牌失效时回退到完整的递归 PROPFIND 列表
+use crate::database::{sync_tokens, Database};
+use crate::sync::filter::{folder_relative_path, SyncFilter};
+use crate::webdav::client::{FileInfo, RemoteListing, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 远程变更
+#[derive(Debug, Clone)]
+pub enum RemoteChanges {
+    /// 完整列表：不在列表中的远程文件视为已删除
+    Full(Vec<FileInfo>),
+
+    /// 增量变更：仅包含自上次同步以来新增、修改和删除的资源
+    Delta {
+        /// 新增或修改的资源
+        changed: Vec<FileInfo>,
+        /// 已删除资源的 href
+        removed: Vec<String>,
+    },
+}
+
+/// 获取同步文件夹的远程变更
+///
+/// # 流程
+/// 1. 已有同步令牌时发送增量 REPORT，成功则保存新令牌并返回 `Delta`
+/// 2. 令牌失效时清除令牌，重新发送不带令牌的 REPORT，返回 `Full`
+/// 3. 服务器不支持 sync-collection 时回退到递归 PROPFIND，返回 `Full`
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - client: WebDAV 客户端
+/// - folder_id: 同步文件夹 ID
+/// - remote_path: 远程根路径
+/// - filter: 同步过滤器，被排除的资源不会出现在结果中
+pub async fn fetch_remote_changes(
+    db: &Database,
+    client: &WebDavClient,
+    folder_id: &str,
+    remote_path: &str,
+    filter: &SyncFilter,
+) -> Result<RemoteChanges> {
+    let changes = fetch_unfiltered(db, client, folder_id, remote_path, filter).await?;
+
+    let keep = |href: &str| {
+        folder_relative_path(remote_path, &client.relative_path(href))
+            .map(|rel| !filter.is_excluded(&rel))
+            .unwrap_or(false)
+    };
+
+    Ok(match changes {
+        RemoteChanges::Full(files) => {
+            RemoteChanges::Full(files.into_iter().filter(|f| keep(&f.path)).collect())
+        }
+        RemoteChanges::Delta { changed, removed } => RemoteChanges::Delta {
+            changed: changed.into_iter().filter(|f| keep(&f.path)).collect(),
+            removed: removed.into_iter().filter(|href| keep(href)).collect(),
+        },
+    })
+}
+
+/// 获取远程变更（sync-collection 结果尚未按过滤器筛选）
+async fn fetch_unfiltered(
+    db: &Database,
+    client: &WebDavClient,
+    folder_id: &str,
+    remote_path: &str,
+    filter: &SyncFilter,
+) -> Result<RemoteChanges> {
+    let token = sync_tokens::get(db, folder_id).await?;
+
+    if let Some(token) = token {
+        match client.sync_collection(remote_path, Some(&token)).await {
+            Ok(Some(result)) => {
+                sync_tokens::save(db, folder_id, &result.sync_token).await?;
+                return Ok(RemoteChanges::Delta {
+                    changed: result.changed,
+                    removed: result.removed,
+                });
+            }
+            Ok(None) => {
+                tracing::info!(folder_id = %folder_id, "同步令牌已失效，执行完整同步");
+                sync_tokens::clear(db, folder_id).await?;
+            }
+            Err(SyncError::Network(msg)) => return Err(SyncError::Network(msg)),
+            Err(e) => {
+                tracing::warn!(folder_id = %folder_id, error = %e, "增量同步失败，回退到完整列表");
+                sync_tokens::clear(db, folder_id).await?;
+                let files = list_recursive(client, remote_path, filter).await?;
+                return Ok(RemoteChanges::Full(files));
+            }
+        }
+    } else if !client.supports_sync_collection(remote_path).await? {
+        tracing::debug!(folder_id = %folder_id, "服务器未声明 sync-collection，使用完整列表");
+        let files = list_recursive(client, remote_path, filter).await?;
+        return Ok(RemoteChanges::Full(files));
+    }
+
+    match client.sync_collection(remote_path, None).await {
+        Ok(Some(result)) => {
+            sync_tokens::save(db, folder_id, &result.sync_token).await?;
+            Ok(RemoteChanges::Full(result.changed))
+        }
+        Ok(None) => Ok(RemoteChanges::Full(
+            list_recursive(client, remote_path, filter).await?,
+        )),
+        Err(SyncError::Network(msg)) => Err(SyncError::Network(msg)),
+        Err(e) => {
+            tracing::warn!(folder_id = %folder_id, error = %e, "sync-collection 请求失败，回退到完整列表");
+            let files = list_recursive(client, remote_path, filter).await?;
+            Ok(RemoteChanges::Full(files))
+        }
+    }
+}
+
+/// 递归列出远程目录下的所有文件和文件夹
+///
+/// 被过滤器排除的子文件夹不会被继续遍历，读取失败的子项只记录日志
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_path: 远程根路径
+/// - filter: 同步过滤器
+pub async fn list_recursive(
+    client: &WebDavClient,
+    remote_path: &str,
+    filter: &SyncFilter,
+) -> Result<Vec<FileInfo>> {
+    let listing = list_recursive_detailed(client, remote_path, filter).await?;
+    for failure in &listing.failures {
+        tracing::warn!(path = %failure.path, status = failure.status, "远程子项读取失败，已跳过");
+    }
+    Ok(listing.files)
+}
+
+/// 递归列出远程目录下的所有文件和文件夹，同时返回读取失败的子项
+///
+/// 读取失败的子文件夹不会被继续遍历
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_path: 远程根路径
+/// - filter: 同步过滤器
+pub async fn list_recursive_detailed(
+    client: &WebDavClient,
+    remote_path: &str,
+    filter: &SyncFilter,
+) -> Result<RemoteListing> {
+    let mut listing = RemoteListing::default();
+    let mut pending = vec![remote_path.to_string()];
+
+    while let Some(dir) = pending.pop() {
+        let RemoteListing { files, failures } = client.list_detailed(&dir).await?;
+        for entry in files {
+            let path = client.relative_path(&entry.path);
+            let excluded = folder_relative_path(remote_path, &path)
+                .map(|rel| filter.is_excluded(&rel))
+                .unwrap_or(true);
+            if excluded {
+                continue;
+            }
+
+            if entry.is_directory {
+                pending.push(path);
+            }
+            listing.files.push(entry);
+        }
+        listing
+            .failures
+            .extend(failures.into_iter().filter(|failure| {
+                folder_relative_path(remote_path, &client.relative_path(&failure.path))
+                    .map(|rel| !filter.is_excluded(&rel))
+                    .unwrap_or(false)
+            }));
+    }
+
+    Ok(listing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webdav::mock_server_config;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn create_test_db() -> (PathBuf, Database) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/005_sync_tokens.sql"))
+            .expect("Failed to run migration 005");
+
+        (test_dir, db)
+    }
+
+    fn create_client(url: String) -> WebDavClient {
+        let config = mock_server_config(&url);
+        WebDavClient::new(&config, "password".to_string()).unwrap()
+    }
+
+    const REPORT_BODY: &str = r#"<?xml version="1.0"?>
+        <D:multistatus xmlns:D="DAV:">
+            <D:response>
+                <D:href>/docs/a.txt</D:href>
+                <D:propstat>
+                    <D:prop><D:resourcetype/><D:getcontentlength>10</D:getcontentlength></D:prop>
+                </D:propstat>
+            </D:response>
+            <D:sync-token>token-1</D:sync-token>
+        </D:multistatus>"#;
+
+    #[tokio::test]
+    async fn test_initial_report_saves_token() {
+        let (test_dir, db) = create_test_db();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("PROPFIND", "/docs")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_body("<D:multistatus><D:sync-collection/></D:multistatus>")
+            .create_async()
+            .await;
+        server
+            .mock("REPORT", "/docs")
+            .with_status(207)
+            .with_body(REPORT_BODY)
+            .create_async()
+            .await;
+
+        let client = create_client(server.url());
+        let changes =
+            fetch_remote_changes(&db, &client, "folder-1", "/docs", &SyncFilter::default())
+                .await
+                .unwrap();
+
+        assert!(matches!(changes, RemoteChanges::Full(ref files) if files.len() == 1));
+        assert_eq!(
+            sync_tokens::get(&db, "folder-1").await.unwrap().as_deref(),
+            Some("token-1")
+        );
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_existing_token_returns_delta() {
+        let (test_dir, db) = create_test_db();
+        sync_tokens::save(&db, "folder-1", "token-0").await.unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("REPORT", "/docs")
+            .with_status(207)
+            .with_body(REPORT_BODY)
+            .create_async()
+            .await;
+
+        let client = create_client(server.url());
+        let changes =
+            fetch_remote_changes(&db, &client, "folder-1", "/docs", &SyncFilter::default())
+                .await
+                .unwrap();
+
+        assert!(matches!(changes, RemoteChanges::Delta { ref changed, .. } if changed.len() == 1));
+        assert_eq!(
+            sync_tokens::get(&db, "folder-1").await.unwrap().as_deref(),
+            Some("token-1")
+        );
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_full_listing() {
+        let (test_dir, db) = create_test_db();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("PROPFIND", "/docs")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_body("<D:multistatus></D:multistatus>")
+            .create_async()
+            .await;
+        let listing = server
+            .mock("PROPFIND", "/docs")
+            .match_header("depth", "1")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus>
+                    <D:response><D:href>/docs/</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat></D:response>
+                    <D:response><D:href>/docs/a.txt</D:href><D:propstat><D:prop><D:resourcetype/></D:prop></D:propstat></D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_client(server.url());
+        let changes =
+            fetch_remote_changes(&db, &client, "folder-1", "/docs", &SyncFilter::default())
+                .await
+                .unwrap();
+
+        listing.assert_async().await;
+        assert!(matches!(changes, RemoteChanges::Full(ref files) if files.len() == 1));
+        assert_eq!(sync_tokens::get(&db, "folder-1").await.unwrap(), None);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,478 @@
+/// 重复文件检测
+///
+/// 扫描同步文件夹的本地文件，先按大小分组，只对大小相同的文件计算 SHA-256，
+/// 找出内容完全相同的文件组及其浪费的空间。
+///
+/// 文件哈希缓存在 file_metadata 表的 hash 列中：本地文件的大小和修改时间与快照一致时
+/// 直接使用缓存的哈希，否则重新计算，并在文件与快照一致时写回缓存。
+///
+/// 尚未上传的重复文件可以通过服务器端 COPY 从一个已同步的规范文件复制到远程，
+/// 避免逐个重新上传相同的内容
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::sync_direction;
+use crate::database::{file_metadata, folder_keys, Database, FileMetadata};
+use crate::sync::filter::SyncFilter;
+use crate::sync::scanner::{inode, scan_local, LocalEntry};
+use crate::sync::symlinks::SymlinkPolicy;
+use crate::sync::versions::hash_file;
+use crate::webdav::client::WebDavClient;
+use crate::{Result, SyncError};
+
+/// 一组内容相同的文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// 文件内容的 SHA-256 哈希
+    pub hash: String,
+    /// 单个文件的大小（字节）
+    pub size: u64,
+    /// 组内文件相对于同步根目录的路径（按路径排序）
+    pub paths: Vec<String>,
+    /// 只保留一份时可节省的空间（字节）
+    pub wasted_bytes: u64,
+}
+
+/// 重复文件检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateReport {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 扫描的文件数（不含空文件）
+    pub files_scanned: usize,
+    /// 本次重新计算哈希的文件数
+    pub files_hashed: usize,
+    /// 使用缓存哈希的文件数
+    pub cached_hashes: usize,
+    /// 所有重复文件组浪费的空间合计（字节）
+    pub total_wasted_bytes: u64,
+    /// 重复文件组（按浪费空间从大到小排序）
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// 未复制的重复文件的原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// 已经同步过，远程已有该文件
+    AlreadySynced,
+    /// 内容与规范文件不同
+    ContentDiffers,
+    /// 远程目标路径已存在文件
+    RemoteExists,
+}
+
+/// 未复制的重复文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedDuplicate {
+    /// 相对于同步根目录的路径
+    pub path: String,
+    /// 未复制的原因
+    pub reason: SkipReason,
+}
+
+/// 从规范文件复制重复文件的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyDuplicatesResult {
+    /// 已在服务器端复制的文件
+    pub copied: Vec<String>,
+    /// 未复制的文件
+    pub skipped: Vec<SkippedDuplicate>,
+    /// 免于上传的字节数
+    pub saved_bytes: u64,
+}
+
+/// 计算过哈希的文件
+#[derive(Debug, Clone)]
+struct HashedFile {
+    rel_path: String,
+    size: u64,
+    hash: String,
+}
+
+/// 检测同步文件夹中的重复文件
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - folder: 同步文件夹配置
+///
+/// # 返回
+/// - Ok(DuplicateReport): 检测结果
+/// - Err(SyncError): 扫描或读取文件失败
+pub async fn find_duplicates(db: &Database, folder: &SyncFolderConfig) -> Result<DuplicateReport> {
+    let root = folder.local_path.clone();
+    let filter = SyncFilter::from_folder(folder);
+    let policy = SymlinkPolicy::from_config(&folder.symlink_policy);
+    let files: Vec<LocalEntry> = run_blocking(move || scan_local(&root, &filter, policy))
+        .await?
+        .into_iter()
+        .filter(|entry| !entry.is_directory && entry.size > 0)
+        .collect();
+    let files_scanned = files.len();
+
+    // 大小不同的文件不可能重复，不需要计算哈希
+    let mut size_counts: HashMap<u64, usize> = HashMap::new();
+    for entry in &files {
+        *size_counts.entry(entry.size).or_default() += 1;
+    }
+    let candidates = files
+        .into_iter()
+        .filter(|entry| size_counts[&entry.size] > 1);
+
+    let sync_folder_id = folder_keys::resolve(db, &folder.id).await?;
+    let snapshot: HashMap<String, FileMetadata> = file_metadata::get_by_folder(db, sync_folder_id)
+        .await?
+        .into_iter()
+        .map(|metadata| (metadata.path.clone(), metadata))
+        .collect();
+
+    let mut hashed = Vec::new();
+    let mut pending = Vec::new();
+    for entry in candidates {
+        match cached_hash(&entry, snapshot.get(&entry.rel_path)) {
+            Some(hash) => hashed.push(HashedFile {
+                rel_path: entry.rel_path,
+                size: entry.size,
+                hash: hash.to_string(),
+            }),
+            None => pending.push(entry),
+        }
+    }
+    let cached_hashes = hashed.len();
+
+    let root = folder.local_path.clone();
+    let computed = run_blocking(move || {
+        let mut computed = Vec::new();
+        for entry in pending {
+            // 扫描后被删除的文件直接跳过
+            match hash_file(&local_path(&root, &entry.rel_path)) {
+                Ok(hash) => computed.push((entry, hash)),
+                Err(SyncError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(computed)
+    })
+    .await?;
+    let files_hashed = computed.len();
+
+    for (entry, hash) in computed {
+        let unchanged = snapshot
+            .get(&entry.rel_path)
+            .is_some_and(|metadata| matches_snapshot(&entry, metadata));
+        if unchanged {
+            file_metadata::update_hash(db, sync_folder_id, &entry.rel_path, &hash).await?;
+        }
+        hashed.push(HashedFile {
+            rel_path: entry.rel_path,
+            size: entry.size,
+            hash,
+        });
+    }
+
+    let groups = group_duplicates(hashed);
+    Ok(DuplicateReport {
+        folder_id: folder.id.clone(),
+        files_scanned,
+        files_hashed,
+        cached_hashes,
+        total_wasted_bytes: groups.iter().map(|group| group.wasted_bytes).sum(),
+        groups,
+    })
+}
+
+/// 通过服务器端复制把尚未上传的重复文件同步到远程
+///
+/// 规范文件必须已经同步且本地未修改，保证远程保存的是相同内容。
+/// 每个重复文件都会重新核对哈希，已同步过的文件和远程已存在的目标不会被覆盖。
+/// 复制成功后写入快照，下次同步不会再上传这些文件
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - client: WebDAV 客户端
+/// - folder: 同步文件夹配置
+/// - canonical: 规范文件的相对路径
+/// - duplicates: 要复制的重复文件的相对路径
+///
+/// # 返回
+/// - Ok(CopyDuplicatesResult): 复制和跳过的文件
+/// - Err(SyncError::ValidationError): 文件夹不支持服务器端复制，或规范文件尚未同步
+/// - Err(SyncError): 读取文件或复制失败
+pub async fn copy_from_canonical(
+    db: &Database,
+    client: &WebDavClient,
+    folder: &SyncFolderConfig,
+    canonical: &str,
+    duplicates: &[String],
+) -> Result<CopyDuplicatesResult> {
+    // 加密文件夹的远程文件名和内容由加密器管理，不能直接复制
+    if folder.encryption.enabled {
+        return Err(SyncError::ValidationError(
+            "Server-side copies are not supported for encrypted folders".to_string(),
+        ));
+    }
+    if folder.sync_direction == sync_direction::DOWNLOAD_ONLY {
+        return Err(SyncError::ValidationError(
+            "Server-side copies are not allowed for download-only folders".to_string(),
+        ));
+    }
+
+    let sync_folder_id = folder_keys::resolve(db, &folder.id).await?;
+    let canonical_snapshot = match file_metadata::get_by_path(db, sync_folder_id, canonical).await {
+        Ok(metadata) => metadata,
+        Err(SyncError::NotFound(_)) => {
+            return Err(SyncError::ValidationError(format!(
+                "Canonical file has not been synced: {}",
+                canonical
+            )))
+        }
+        Err(e) => return Err(e),
+    };
+    let canonical_entry = local_entry(&folder.local_path, canonical).await?;
+    if !matches_snapshot(&canonical_entry, &canonical_snapshot) {
+        return Err(SyncError::ValidationError(format!(
+            "Canonical file has changed since the last sync: {}",
+            canonical
+        )));
+    }
+    let canonical_hash = match cached_hash(&canonical_entry, Some(&canonical_snapshot)) {
+        Some(hash) => hash.to_string(),
+        None => hash_local(&folder.local_path, canonical).await?,
+    };
+
+    let mut result = CopyDuplicatesResult::default();
+    for path in duplicates.iter().filter(|path| path.as_str() != canonical) {
+        let skip = |reason| SkippedDuplicate {
+            path: path.clone(),
+            reason,
+        };
+
+        match file_metadata::get_by_path(db, sync_folder_id, path).await {
+            Ok(_) => {
+                result.skipped.push(skip(SkipReason::AlreadySynced));
+                continue;
+            }
+            Err(SyncError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let entry = local_entry(&folder.local_path, path).await?;
+        if hash_local(&folder.local_path, path).await? != canonical_hash {
+            result.skipped.push(skip(SkipReason::ContentDiffers));
+            continue;
+        }
+
+        let target = remote_path(folder, path);
+        if let Some((parent, _)) = target.rsplit_once('/') {
+            if !parent.is_empty() {
+                client.mkdir_all(parent).await?;
+            }
+        }
+        if !client
+            .copy(&remote_path(folder, canonical), &target, false)
+            .await?
+        {
+            result.skipped.push(skip(SkipReason::RemoteExists));
+            continue;
+        }
+
+        file_metadata::upsert(
+            db,
+            &FileMetadata {
+                id: None,
+                path: path.clone(),
+                hash: Some(canonical_hash.clone()),
+                size: entry.size as i64,
+                modified_at: entry.modified.unwrap_or(0),
+                synced_at: Some(chrono::Utc::now().timestamp()),
+                sync_folder_id,
+                is_directory: false,
+                status: "synced".to_string(),
+                created_at: None,
+                updated_at: None,
+                remote_size: canonical_snapshot.remote_size,
+                inode: entry.inode.map(|inode| inode as i64),
+            },
+        )
+        .await?;
+
+        tracing::info!(folder_id = %folder.id, path = %path, canonical, "已通过服务器端复制同步重复文件");
+        result.saved_bytes += entry.size;
+        result.copied.push(path.clone());
+    }
+
+    Ok(result)
+}
+
+/// 把计算过哈希的文件按内容分组，只保留包含多个文件的组
+fn group_duplicates(files: Vec<HashedFile>) -> Vec<DuplicateGroup> {
+    let mut by_hash: HashMap<(String, u64), Vec<String>> = HashMap::new();
+    for file in files {
+        by_hash
+            .entry((file.hash, file.size))
+            .or_default()
+            .push(file.rel_path);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((hash, size), mut paths)| {
+            paths.sort();
+            DuplicateGroup {
+                wasted_bytes: size * (paths.len() as u64 - 1),
+                hash,
+                size,
+                paths,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.wasted_bytes
+            .cmp(&a.wasted_bytes)
+            .then_with(|| a.paths[0].cmp(&b.paths[0]))
+    });
+    groups
+}
+
+/// 本地文件与快照记录的大小和修改时间是否一致
+fn matches_snapshot(entry: &LocalEntry, snapshot: &FileMetadata) -> bool {
+    !snapshot.is_directory
+        && snapshot.size == entry.size as i64
+        && entry.modified == Some(snapshot.modified_at)
+}
+
+/// 本地文件未变化时快照中缓存的哈希
+fn cached_hash<'a>(entry: &LocalEntry, snapshot: Option<&'a FileMetadata>) -> Option<&'a str> {
+    snapshot
+        .filter(|metadata| matches_snapshot(entry, metadata))
+        .and_then(|metadata| metadata.hash.as_deref())
+}
+
+/// 读取单个本地文件的大小和修改时间
+async fn local_entry(root: &Path, rel_path: &str) -> Result<LocalEntry> {
+    let metadata = tokio::fs::metadata(local_path(root, rel_path)).await?;
+    if !metadata.is_file() {
+        return Err(SyncError::ValidationError(format!(
+            "Not a regular file: {}",
+            rel_path
+        )));
+    }
+
+    Ok(LocalEntry {
+        rel_path: rel_path.to_string(),
+        is_directory: false,
+        size: metadata.len(),
+        modified: metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64),
+        inode: inode(&metadata),
+    })
+}
+
+/// 计算单个本地文件的哈希
+async fn hash_local(root: &Path, rel_path: &str) -> Result<String> {
+    let path = local_path(root, rel_path);
+    run_blocking(move || hash_file(&path)).await
+}
+
+fn local_path(root: &Path, rel_path: &str) -> PathBuf {
+    root.join(rel_path)
+}
+
+fn remote_path(folder: &SyncFolderConfig, rel_path: &str) -> String {
+    format!("{}/{}", folder.remote_path.trim_end_matches('/'), rel_path)
+}
+
+/// 在阻塞线程池中执行文件操作
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| SyncError::Unknown(format!("Duplicate scan task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashed(path: &str, size: u64, hash: &str) -> HashedFile {
+        HashedFile {
+            rel_path: path.to_string(),
+            size,
+            hash: hash.to_string(),
+        }
+    }
+
+    fn snapshot(path: &str, size: i64, modified: i64, hash: Option<&str>) -> FileMetadata {
+        FileMetadata {
+            id: None,
+            path: path.to_string(),
+            hash: hash.map(str::to_string),
+            size,
+            modified_at: modified,
+            synced_at: Some(modified),
+            sync_folder_id: 1,
+            is_directory: false,
+            status: "synced".to_string(),
+            created_at: None,
+            updated_at: None,
+            remote_size: None,
+            inode: None,
+        }
+    }
+
+    #[test]
+    fn test_group_duplicates() {
+        let groups = group_duplicates(vec![
+            hashed("b/copy.txt", 10, "aaa"),
+            hashed("a/original.txt", 10, "aaa"),
+            hashed("unique.txt", 10, "bbb"),
+            hashed("big-1.bin", 100, "ccc"),
+            hashed("big-2.bin", 100, "ccc"),
+            hashed("big-3.bin", 100, "ccc"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].hash, "ccc");
+        assert_eq!(groups[0].wasted_bytes, 200);
+        assert_eq!(groups[1].paths, vec!["a/original.txt", "b/copy.txt"]);
+        assert_eq!(groups[1].wasted_bytes, 10);
+    }
+
+    #[test]
+    fn test_cached_hash_requires_unchanged_file() {
+        let entry = LocalEntry {
+            rel_path: "a.txt".to_string(),
+            is_directory: false,
+            size: 10,
+            modified: Some(100),
+            inode: None,
+        };
+
+        let fresh = snapshot("a.txt", 10, 100, Some("aaa"));
+        assert_eq!(cached_hash(&entry, Some(&fresh)), Some("aaa"));
+
+        let modified = snapshot("a.txt", 10, 90, Some("aaa"));
+        assert_eq!(cached_hash(&entry, Some(&modified)), None);
+
+        let resized = snapshot("a.txt", 12, 100, Some("aaa"));
+        assert_eq!(cached_hash(&entry, Some(&resized)), None);
+
+        let uncached = snapshot("a.txt", 10, 100, None);
+        assert_eq!(cached_hash(&entry, Some(&uncached)), None);
+        assert_eq!(cached_hash(&entry, None), None);
+    }
+}
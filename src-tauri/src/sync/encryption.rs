@@ -0,0 +1,696 @@
+/// 端到端加密
+///
+/// 同步文件夹启用加密后，文件在上传前使用 XChaCha20-Poly1305 加密、下载后解密，服务器只保存密文。
+/// 密钥由用户口令经 Argon2id 派生，口令保存在系统 Keyring 中（条目名为 `encryption:<文件夹 ID>`）。
+///
+/// # 文件格式
+///
+/// `LSE1` 魔数 + 24 字节随机基础 nonce，之后是按 `ENCRYPTION_CHUNK_SIZE` 分块加密的数据，
+/// 每块附带 16 字节认证标签。第 i 块的 nonce 为基础 nonce 末 8 字节与 i 异或的结果，
+/// 最后一块的关联数据为 1、其余为 0，因此数据块被重排或文件被截断都会导致解密失败。
+///
+/// # 同步清单
+///
+/// 远程根目录下的 `.lightsync-manifest.json` 记录密钥派生盐、是否混淆文件名，
+/// 以及混淆路径到真实路径的映射（映射本身加密保存，口令错误时无法解密）。
+/// 混淆名由密钥与真实路径确定性生成，同一路径在所有客户端上得到相同的远程名，
+/// 清单只用于把其他客户端上传的混淆名还原为真实路径。
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::{ENCRYPTION_CHUNK_SIZE, ENCRYPTION_MANIFEST_FILE};
+use crate::sync::planner::RemoteEntry;
+use crate::webdav::client::WebDavClient;
+use crate::webdav::keyring::KeyringManager;
+use crate::{Result, SyncError};
+
+/// 加密文件魔数
+const MAGIC: &[u8; 4] = b"LSE1";
+
+/// nonce 长度（字节）
+const NONCE_LEN: usize = 24;
+
+/// 认证标签长度（字节）
+const TAG_LEN: usize = 16;
+
+/// 加密文件头长度（魔数 + 基础 nonce）
+const HEADER_LEN: usize = MAGIC.len() + NONCE_LEN;
+
+/// 密钥派生盐长度（字节）
+const SALT_LEN: usize = 16;
+
+/// 混淆文件名长度（十六进制字符）
+const OBFUSCATED_NAME_LEN: usize = 32;
+
+/// 同步清单格式版本
+const MANIFEST_VERSION: u32 = 1;
+
+/// 加密算法名称
+const ALGORITHM: &str = "xchacha20-poly1305";
+
+/// 密钥派生算法名称
+const KDF: &str = "argon2id";
+
+/// 远程同步清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    /// 格式版本
+    version: u32,
+    /// 加密算法
+    algorithm: String,
+    /// 密钥派生算法
+    kdf: String,
+    /// 密钥派生盐（Base64）
+    salt: String,
+    /// 是否混淆文件名
+    obfuscate_filenames: bool,
+    /// 加密的路径映射（Base64，nonce + 密文）
+    names: String,
+}
+
+/// 同步文件夹的加密器
+pub struct FolderCipher {
+    /// 数据加密器
+    cipher: XChaCha20Poly1305,
+    /// 名称混淆密钥（由数据密钥派生）
+    name_key: [u8; 32],
+    /// 密钥派生盐
+    salt: Vec<u8>,
+    /// 是否混淆文件名
+    obfuscate_filenames: bool,
+    /// 混淆路径到真实路径的映射
+    names: Mutex<BTreeMap<String, String>>,
+    /// 映射是否有未保存到远程清单的变更
+    dirty: AtomicBool,
+}
+
+impl FolderCipher {
+    /// 从口令派生密钥并创建加密器
+    ///
+    /// # 参数
+    /// - passphrase: 加密口令
+    /// - salt: 密钥派生盐
+    /// - obfuscate_filenames: 是否混淆文件名
+    pub fn new(passphrase: &str, salt: Vec<u8>, obfuscate_filenames: bool) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(SyncError::ValidationError(
+                "Encryption passphrase cannot be empty".to_string(),
+            ));
+        }
+
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| SyncError::ConfigError(format!("Failed to derive key: {}", e)))?;
+
+        let name_key: [u8; 32] = Sha256::new()
+            .chain_update(key)
+            .chain_update(b"lightsync-filenames")
+            .finalize()
+            .into();
+
+        Ok(Self {
+            cipher: XChaCha20Poly1305::new(&key.into()),
+            name_key,
+            salt,
+            obfuscate_filenames,
+            names: Mutex::new(BTreeMap::new()),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    /// 打开同步文件夹的加密器
+    ///
+    /// 从 Keyring 读取口令并下载远程同步清单；清单不存在时使用新的随机盐创建，
+    /// 在第一次同步结束时上传。已有清单时以清单中的文件名混淆设置为准
+    ///
+    /// # 返回
+    /// - Ok(FolderCipher): 加密器
+    /// - Err(SyncError::ValidationError): 未设置加密口令
+    /// - Err(SyncError::AuthError): 口令与远程清单不匹配
+    pub async fn open(client: &WebDavClient, folder: &SyncFolderConfig) -> Result<Self> {
+        let passphrase = load_passphrase(&folder.id)?;
+
+        let tmp = temp_path("manifest");
+        let downloaded = client
+            .download(&manifest_path(&folder.remote_path), &tmp)
+            .await;
+        let content = match downloaded {
+            Ok(()) => {
+                let content = tokio::fs::read_to_string(&tmp).await;
+                let _ = tokio::fs::remove_file(&tmp).await;
+                Some(content?)
+            }
+            Err(SyncError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        match content {
+            Some(content) => {
+                let manifest: Manifest = serde_json::from_str(&content).map_err(|e| {
+                    SyncError::ConfigError(format!("Invalid encryption manifest: {}", e))
+                })?;
+                Self::from_manifest(&manifest, &passphrase)
+            }
+            None => {
+                let mut salt = vec![0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                let cipher = Self::new(&passphrase, salt, folder.encryption.obfuscate_filenames)?;
+                cipher.dirty.store(true, Ordering::SeqCst);
+                Ok(cipher)
+            }
+        }
+    }
+
+    /// 从同步清单创建加密器
+    fn from_manifest(manifest: &Manifest, passphrase: &str) -> Result<Self> {
+        if manifest.version > MANIFEST_VERSION
+            || manifest.algorithm != ALGORITHM
+            || manifest.kdf != KDF
+        {
+            return Err(SyncError::ConfigError(format!(
+                "Unsupported encryption manifest: version {}, algorithm {}",
+                manifest.version, manifest.algorithm
+            )));
+        }
+
+        let salt = BASE64
+            .decode(&manifest.salt)
+            .map_err(|e| SyncError::ConfigError(format!("Invalid manifest salt: {}", e)))?;
+        let cipher = Self::new(passphrase, salt, manifest.obfuscate_filenames)?;
+
+        let sealed = BASE64
+            .decode(&manifest.names)
+            .map_err(|e| SyncError::ConfigError(format!("Invalid manifest names: {}", e)))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(SyncError::ConfigError(
+                "Invalid manifest names: too short".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| SyncError::AuthError("Wrong encryption passphrase".to_string()))?;
+        let names: BTreeMap<String, String> = serde_json::from_slice(&plaintext)?;
+
+        *cipher.names.lock().unwrap_or_else(|e| e.into_inner()) = names;
+        Ok(cipher)
+    }
+
+    /// 生成同步清单
+    fn to_manifest(&self) -> Result<Manifest> {
+        let names = serde_json::to_vec(&*self.names.lock().unwrap_or_else(|e| e.into_inner()))?;
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), names.as_slice())
+            .map_err(|e| SyncError::ConfigError(format!("Failed to encrypt manifest: {}", e)))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+
+        Ok(Manifest {
+            version: MANIFEST_VERSION,
+            algorithm: ALGORITHM.to_string(),
+            kdf: KDF.to_string(),
+            salt: BASE64.encode(&self.salt),
+            obfuscate_filenames: self.obfuscate_filenames,
+            names: BASE64.encode(sealed),
+        })
+    }
+
+    /// 清单有变更时上传到远程根目录
+    pub async fn save_manifest(&self, client: &WebDavClient, remote_root: &str) -> Result<()> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let tmp = temp_path("manifest");
+        let result = async {
+            let content = serde_json::to_string_pretty(&self.to_manifest()?)?;
+            tokio::fs::write(&tmp, content).await?;
+            client.upload(&tmp, &manifest_path(remote_root)).await
+        }
+        .await;
+        let _ = tokio::fs::remove_file(&tmp).await;
+
+        if result.is_err() {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+
+    /// 是否混淆文件名
+    pub fn obfuscates_filenames(&self) -> bool {
+        self.obfuscate_filenames
+    }
+
+    /// 真实相对路径对应的远程相对路径
+    ///
+    /// 未启用文件名混淆时原样返回；启用时逐级混淆并记录映射
+    pub fn remote_rel_path(&self, rel_path: &str) -> String {
+        if !self.obfuscate_filenames {
+            return rel_path.to_string();
+        }
+
+        let mut names = self.names.lock().unwrap_or_else(|e| e.into_inner());
+        let mut real = String::new();
+        let mut remote = String::new();
+        for segment in rel_path.split('/').filter(|s| !s.is_empty()) {
+            if !real.is_empty() {
+                real.push('/');
+                remote.push('/');
+            }
+            real.push_str(segment);
+            remote.push_str(&self.obfuscate(&real));
+
+            if names.get(&remote) != Some(&real) {
+                names.insert(remote.clone(), real.clone());
+                self.dirty.store(true, Ordering::SeqCst);
+            }
+        }
+
+        remote
+    }
+
+    /// 远程相对路径对应的真实相对路径
+    ///
+    /// 未启用文件名混淆时原样返回；无法识别的混淆名返回 None
+    pub fn real_rel_path(&self, remote_rel_path: &str) -> Option<String> {
+        if !self.obfuscate_filenames {
+            return Some(remote_rel_path.to_string());
+        }
+        self.names
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(remote_rel_path)
+            .cloned()
+    }
+
+    /// 预先登记已知的真实路径（本地文件与快照）
+    ///
+    /// 混淆名是确定性的，登记后即使上次同步没能上传清单，也能识别自己上传的文件
+    pub fn register<'p>(&self, paths: impl IntoIterator<Item = &'p str>) {
+        if self.obfuscate_filenames {
+            for path in paths {
+                self.remote_rel_path(path);
+            }
+        }
+    }
+
+    /// 从映射中移除已删除的路径及其子项
+    pub fn forget(&self, rel_path: &str) {
+        if !self.obfuscate_filenames {
+            return;
+        }
+
+        let prefix = format!("{}/", rel_path);
+        let mut names = self.names.lock().unwrap_or_else(|e| e.into_inner());
+        let before = names.len();
+        names.retain(|_, real| real != rel_path && !real.starts_with(&prefix));
+        if names.len() != before {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 将远程列表还原为真实路径和明文大小
+    ///
+    /// 同步清单本身、无法识别的混淆名以及大小不合法的密文会被跳过
+    pub fn decode_entries(&self, entries: Vec<RemoteEntry>) -> Vec<RemoteEntry> {
+        let names = self.names.lock().unwrap_or_else(|e| e.into_inner());
+
+        entries
+            .into_iter()
+            .filter(|entry| entry.rel_path != ENCRYPTION_MANIFEST_FILE)
+            .filter_map(|mut entry| {
+                if self.obfuscate_filenames {
+                    match names.get(&entry.rel_path) {
+                        Some(real) => entry.rel_path = real.clone(),
+                        None => {
+                            tracing::warn!(path = %entry.rel_path, "跳过无法识别的加密文件名");
+                            return None;
+                        }
+                    }
+                }
+
+                if !entry.is_directory {
+                    match plaintext_size(entry.size) {
+                        Some(size) => entry.size = size,
+                        None => {
+                            tracing::warn!(path = %entry.rel_path, "跳过大小不合法的加密文件");
+                            return None;
+                        }
+                    }
+                }
+                Some(entry)
+            })
+            .collect()
+    }
+
+    /// 加密文件
+    ///
+    /// # 返回
+    /// 密文大小（字节）
+    pub fn encrypt_file(&self, src: &Path, dst: &Path) -> Result<u64> {
+        let mut input = File::open(src)?;
+        let mut output = File::create(dst)?;
+
+        let mut base_nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut base_nonce);
+        output.write_all(MAGIC)?;
+        output.write_all(&base_nonce)?;
+        let mut written = HEADER_LEN as u64;
+
+        // 预读一块，以便判断当前块是否为最后一块
+        let mut current = read_chunk(&mut input, ENCRYPTION_CHUNK_SIZE)?;
+        let mut index = 0u64;
+        loop {
+            let next = if current.len() == ENCRYPTION_CHUNK_SIZE {
+                read_chunk(&mut input, ENCRYPTION_CHUNK_SIZE)?
+            } else {
+                Vec::new()
+            };
+            let last = next.is_empty();
+
+            let ciphertext = self
+                .cipher
+                .encrypt(
+                    &chunk_nonce(&base_nonce, index),
+                    Payload {
+                        msg: &current,
+                        aad: &[last as u8],
+                    },
+                )
+                .map_err(|e| SyncError::Unknown(format!("Failed to encrypt file: {}", e)))?;
+            output.write_all(&ciphertext)?;
+            written += ciphertext.len() as u64;
+
+            if last {
+                break;
+            }
+            current = next;
+            index += 1;
+        }
+
+        output.sync_all()?;
+        Ok(written)
+    }
+
+    /// 解密文件
+    ///
+    /// 先写入目标文件旁的临时文件，全部数据块通过认证后再重命名为目标文件
+    ///
+    /// # 返回
+    /// - Ok(u64): 明文大小（字节）
+    /// - Err(SyncError::IntegrityError): 文件格式不正确、被篡改或口令错误
+    pub fn decrypt_file(&self, src: &Path, dst: &Path) -> Result<u64> {
+        let mut input = File::open(src)?;
+        let header = read_chunk(&mut input, HEADER_LEN)?;
+        if header.len() != HEADER_LEN || &header[..MAGIC.len()] != MAGIC {
+            return Err(SyncError::IntegrityError(
+                "Not an encrypted LightSync file".to_string(),
+            ));
+        }
+        let base_nonce: [u8; NONCE_LEN] = header[MAGIC.len()..]
+            .try_into()
+            .expect("header length checked");
+
+        let partial = crate::webdav::client::partial_download_path(dst);
+        match self.decrypt_chunks(&mut input, &base_nonce, &partial) {
+            Ok(written) => {
+                std::fs::rename(&partial, dst)?;
+                Ok(written)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&partial);
+                Err(e)
+            }
+        }
+    }
+
+    /// 逐块解密并写入输出文件
+    fn decrypt_chunks(
+        &self,
+        input: &mut File,
+        base_nonce: &[u8; NONCE_LEN],
+        output: &Path,
+    ) -> Result<u64> {
+        let mut output = File::create(output)?;
+        let chunk_len = ENCRYPTION_CHUNK_SIZE + TAG_LEN;
+        let mut current = read_chunk(input, chunk_len)?;
+        let mut index = 0u64;
+        let mut written = 0u64;
+        loop {
+            let next = if current.len() == chunk_len {
+                read_chunk(input, chunk_len)?
+            } else {
+                Vec::new()
+            };
+            let last = next.is_empty();
+
+            let plaintext = self
+                .cipher
+                .decrypt(
+                    &chunk_nonce(base_nonce, index),
+                    Payload {
+                        msg: &current,
+                        aad: &[last as u8],
+                    },
+                )
+                .map_err(|_| {
+                    SyncError::IntegrityError(
+                        "Failed to decrypt file: wrong key or corrupted data".to_string(),
+                    )
+                })?;
+            output.write_all(&plaintext)?;
+            written += plaintext.len() as u64;
+
+            if last {
+                break;
+            }
+            current = next;
+            index += 1;
+        }
+
+        output.sync_all()?;
+        Ok(written)
+    }
+
+    /// 由密钥和真实路径生成混淆名
+    fn obfuscate(&self, real_path: &str) -> String {
+        Sha256::new()
+            .chain_update(self.name_key)
+            .chain_update(real_path.as_bytes())
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()[..OBFUSCATED_NAME_LEN]
+            .to_string()
+    }
+}
+
+/// 由密文大小计算明文大小
+///
+/// # 返回
+/// - Some(u64): 明文大小
+/// - None: 不是合法的密文大小
+pub fn plaintext_size(ciphertext_size: u64) -> Option<u64> {
+    let body = ciphertext_size.checked_sub(HEADER_LEN as u64)?;
+    let chunk_len = (ENCRYPTION_CHUNK_SIZE + TAG_LEN) as u64;
+    let chunks = body.div_ceil(chunk_len).max(1);
+    let last_len = body.checked_sub((chunks - 1) * chunk_len)?;
+    if last_len < TAG_LEN as u64 {
+        return None;
+    }
+    Some(body - chunks * TAG_LEN as u64)
+}
+
+/// 加密口令在 Keyring 中的条目名
+pub fn passphrase_entry(folder_id: &str) -> String {
+    format!("encryption:{}", folder_id)
+}
+
+/// 从 Keyring 读取同步文件夹的加密口令
+///
+/// # 返回
+/// - Ok(String): 加密口令
+/// - Err(SyncError::ValidationError): 未设置加密口令
+pub fn load_passphrase(folder_id: &str) -> Result<String> {
+    match KeyringManager::get_password(&passphrase_entry(folder_id)) {
+        Err(SyncError::NotFound(_)) => Err(SyncError::ValidationError(format!(
+            "Encryption passphrase is not set for folder: {}",
+            folder_id
+        ))),
+        result => result,
+    }
+}
+
+/// 远程同步清单路径
+fn manifest_path(remote_root: &str) -> String {
+    format!(
+        "{}/{}",
+        remote_root.trim_end_matches('/'),
+        ENCRYPTION_MANIFEST_FILE
+    )
+}
+
+/// 系统临时目录下的临时文件路径
+pub fn temp_path(purpose: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("lightsync-{}-{}", purpose, uuid::Uuid::new_v4()))
+}
+
+/// 第 index 块的 nonce
+fn chunk_nonce(base: &[u8; NONCE_LEN], index: u64) -> XNonce {
+    let mut nonce = *base;
+    for (byte, counter) in nonce[NONCE_LEN - 8..].iter_mut().zip(index.to_be_bytes()) {
+        *byte ^= counter;
+    }
+    XNonce::from(nonce)
+}
+
+/// 读取至多 len 字节（到达文件末尾时可能更短）
+fn read_chunk(reader: &mut impl Read, len: usize) -> Result<Vec<u8>> {
+    let mut buffer = Vec::with_capacity(len);
+    reader.take(len as u64).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn create_test_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn cipher(obfuscate: bool) -> FolderCipher {
+        FolderCipher::new("passphrase", vec![7u8; SALT_LEN], obfuscate).unwrap()
+    }
+
+    fn remote(rel_path: &str, is_directory: bool, size: u64) -> RemoteEntry {
+        RemoteEntry {
+            rel_path: rel_path.to_string(),
+            is_directory,
+            size,
+            modified: None,
+            etag: None,
+        }
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let dir = create_test_dir();
+        let cipher = cipher(false);
+
+        for size in [0, 1, ENCRYPTION_CHUNK_SIZE, ENCRYPTION_CHUNK_SIZE * 2 + 5] {
+            let content: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            fs::write(dir.join("plain"), &content).unwrap();
+
+            let encrypted = cipher
+                .encrypt_file(&dir.join("plain"), &dir.join("encrypted"))
+                .unwrap();
+            assert_eq!(
+                fs::metadata(dir.join("encrypted")).unwrap().len(),
+                encrypted
+            );
+            assert_eq!(plaintext_size(encrypted), Some(size as u64));
+
+            let decrypted = cipher
+                .decrypt_file(&dir.join("encrypted"), &dir.join("decrypted"))
+                .unwrap();
+            assert_eq!(decrypted, size as u64);
+            assert_eq!(fs::read(dir.join("decrypted")).unwrap(), content);
+        }
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_tampered_or_truncated_file_fails() {
+        let dir = create_test_dir();
+        let cipher = cipher(false);
+        let content = vec![1u8; ENCRYPTION_CHUNK_SIZE * 2];
+        fs::write(dir.join("plain"), &content).unwrap();
+        cipher
+            .encrypt_file(&dir.join("plain"), &dir.join("encrypted"))
+            .unwrap();
+        let encrypted = fs::read(dir.join("encrypted")).unwrap();
+
+        // 截断最后一块
+        let truncated = &encrypted[..HEADER_LEN + ENCRYPTION_CHUNK_SIZE + TAG_LEN];
+        fs::write(dir.join("truncated"), truncated).unwrap();
+        let result = cipher.decrypt_file(&dir.join("truncated"), &dir.join("out"));
+        assert!(matches!(result, Err(SyncError::IntegrityError(_))));
+        assert!(!dir.join("out").exists());
+
+        // 错误的口令
+        let other = FolderCipher::new("other", vec![7u8; SALT_LEN], false).unwrap();
+        let result = other.decrypt_file(&dir.join("encrypted"), &dir.join("out"));
+        assert!(matches!(result, Err(SyncError::IntegrityError(_))));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_obfuscated_names_and_manifest() {
+        let cipher = cipher(true);
+        let remote_path = cipher.remote_rel_path("docs/report.pdf");
+        let segments: Vec<&str> = remote_path.split('/').collect();
+        assert_eq!(segments.len(), 2);
+        assert!(segments.iter().all(|s| s.len() == OBFUSCATED_NAME_LEN));
+        assert_eq!(cipher.remote_rel_path("docs"), segments[0]);
+
+        // 清单往返后可以还原真实路径与明文大小
+        let manifest = cipher.to_manifest().unwrap();
+        let reopened = FolderCipher::from_manifest(&manifest, "passphrase").unwrap();
+        let entries = reopened.decode_entries(vec![
+            remote(segments[0], true, 0),
+            remote(&remote_path, false, HEADER_LEN as u64 + 10 + TAG_LEN as u64),
+            remote("ffffffffffffffffffffffffffffffff", false, 100),
+            remote(ENCRYPTION_MANIFEST_FILE, false, 100),
+        ]);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].rel_path, "docs");
+        assert_eq!(entries[1].rel_path, "docs/report.pdf");
+        assert_eq!(entries[1].size, 10);
+
+        reopened.forget("docs");
+        assert!(reopened.names.lock().unwrap().is_empty());
+
+        let result = FolderCipher::from_manifest(&manifest, "wrong");
+        assert!(matches!(result, Err(SyncError::AuthError(_))));
+    }
+
+    #[test]
+    fn test_plaintext_size() {
+        assert_eq!(plaintext_size(0), None);
+        assert_eq!(plaintext_size(HEADER_LEN as u64), None);
+        assert_eq!(plaintext_size((HEADER_LEN + TAG_LEN) as u64), Some(0));
+        assert_eq!(
+            plaintext_size((HEADER_LEN + ENCRYPTION_CHUNK_SIZE + TAG_LEN + 3) as u64),
+            None
+        );
+    }
+}
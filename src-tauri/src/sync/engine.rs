@@ -0,0 +1,1571 @@
+/// 同步引擎
+///
+/// 执行一次完整的同步会话：
+/// 1. 确认本地根目录可用（存在且带有标记文件，避免磁盘断开时删除远程文件），
+///    对比本地、远程和快照，生成同步计划（`planner`），本地不合法的远程文件名按策略重命名或跳过，
+///    仍在写入的本地文件推迟上传，静默期过后重新同步；
+///    删除过多（`safety`）或服务器、本地剩余空间不足（`space`）时在任何操作前中止
+/// 2. 创建本地和远程目录（本地写入不会经过不允许跟随的符号链接）
+/// 3. 通过 `TransferPool` 并发上传、下载（开启校验时比对远程校验和，损坏的文件重新传输；
+///    开启上传加锁时先 LOCK 远程文件，上传结束后 UNLOCK；远程已有相同内容的文件跳过上传）；
+///    传输期间按 `SYNC_PROGRESS_INTERVAL_MS` 推送已传输字节数、当前速度与预计剩余时间
+/// 4. 执行删除（本地删除移入回收站）；本地重命名的文件在传输前通过 MOVE 在远程移动
+/// 5. 写回快照、同步日志和会话统计；服务器支持自定义属性时在远程根目录写入同步标记（`marker`）
+///
+/// 启用端到端加密时，先打开文件夹的加密器（读取远程同步清单），文件在传输池中加解密，
+/// 远程路径按需混淆，传输前后把新的路径映射写回同步清单。
+///
+/// 会话期间文件夹状态依次转换为 Scanning、Transferring，结束后转换为 Idle、Paused 或 Error；
+/// 同一文件夹已在同步时不会开始新的会话。
+///
+/// 暂停时传输池在当前数据块完成后停止；取消会话时进行中的请求立即中止并清理临时文件。
+/// 未完成的条目不会写入快照，下次同步对比时会重新出现在计划中。
+///
+/// 传输任务同时写入传输队列（transfers 表），传输期间保存进度，完成后删除，
+/// 失败、暂停或取消的任务保留在队列中，应用重启后据此继续同步。未加密文件夹的下载
+/// 以远程文件的 ETag 作为续传凭据，中断后保留临时文件，下次从断点继续；上传总是从头开始
+/// （PUT 请求不能续传）。
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::{
+    log_status, session_status, sync_action, transfer_direction, transfer_status, APP_NAME,
+    APP_VERSION, MAX_CONCURRENT_DOWNLOADS, MAX_CONCURRENT_UPLOADS, MAX_VERIFY_RETRIES,
+    METADATA_BATCH_SIZE, METADATA_FLUSH_INTERVAL_MS, REMOTE_LOCK_TIMEOUT,
+    SYNC_PROGRESS_INTERVAL_MS, TRANSFER_SPEED_WINDOW_SECS,
+};
+use crate::database::{
+    file_metadata, folder_keys, log_writer::SyncLogWriter, remote_cache, remote_locks,
+    sync_sessions, transfers, Database, FileMetadata, RemoteCacheEntry, SyncLog, SyncSession,
+    Transfer,
+};
+use crate::sync::compression::CompressionPolicy;
+use crate::sync::control::{PauseSignal, SyncControl};
+use crate::sync::encryption::FolderCipher;
+use crate::sync::events::{
+    SyncCompletedEvent, SyncCounters, SyncErrorEvent, SyncEventEmitter, SyncPhase,
+    SyncProgressEvent,
+};
+use crate::sync::folders;
+use crate::sync::incremental::ScanCache;
+use crate::sync::marker::{self, ClientId};
+use crate::sync::paths::{CaseCollisionPolicy, PathNormalizer, SkippedPath};
+use crate::sync::planner::{self, PlanAction, PlanItem, SyncPlan};
+use crate::sync::safety::DeletionGuard;
+use crate::sync::sanitize::ReservedNamePolicy;
+use crate::sync::scanner;
+use crate::sync::space;
+use crate::sync::stability::StabilityQueue;
+use crate::sync::state::{FolderSyncState, SyncStateManager};
+use crate::sync::symlinks::{self, SymlinkPolicy};
+use crate::sync::throughput::{self, SpeedWindow, TransferStats};
+use crate::sync::transfer::{TransferJob, TransferKind, TransferOutcome, TransferPool};
+use crate::sync::trash::Trash;
+use crate::sync::verify::{self, Verification};
+use crate::sync::versions::VersionStore;
+use crate::webdav::client::{partial_download_path, RemoteLock, WebDavClient};
+use crate::{Result, SyncError};
+use tokio_util::sync::CancellationToken;
+
+/// 同步所需的共享资源
+pub struct SyncContext<'a> {
+    /// 共享数据库连接
+    pub db: &'a Database,
+    /// WebDAV 客户端（会话内使用绑定取消令牌的副本）
+    pub client: WebDavClient,
+    /// 同步日志批量写入器
+    pub logs: &'a SyncLogWriter,
+    /// 暂停控制器
+    pub control: &'a SyncControl,
+    /// 文件夹同步状态
+    pub states: &'a SyncStateManager,
+    /// 本地回收站
+    pub trash: &'a Trash,
+    /// 文件历史版本库
+    pub versions: &'a VersionStore,
+    /// 事件发送器
+    pub emitter: SyncEventEmitter,
+    /// 每个文件最多保留的历史版本数
+    pub max_versions: u32,
+    /// 传输完成后是否校验完整性
+    pub verify_transfers: bool,
+    /// 上传前是否锁定远程文件
+    pub lock_uploads: bool,
+    /// 不小于该大小（字节）的下载使用多个连接按范围并行下载（0 表示不启用）
+    pub parallel_download_threshold: u64,
+    /// 服务器上的文件名在本地不合法时的处理策略
+    pub reserved_name_policy: ReservedNamePolicy,
+    /// 仅大小写不同的远程文件在本地冲突时的处理策略
+    pub case_collision_policy: CaseCollisionPolicy,
+    /// 本地文件最后修改后需要保持不变的时间（秒）
+    pub file_quiet_period: u32,
+    /// 传输优先窗口（秒），窗口内修改过的文件先传输（0 表示不调整顺序）
+    pub priority_window: u32,
+    /// 因文件仍在写入而需要稍后重新同步的文件夹
+    pub stability: &'a StabilityQueue,
+    /// 上次扫描的结果与脏路径（增量扫描）
+    pub scans: &'a ScanCache,
+    /// 大量删除保护
+    pub deletion_guard: &'a DeletionGuard,
+    /// 客户端标识（写入远程同步标记）
+    pub client_id: &'a ClientId,
+    /// 传输速度统计
+    pub transfer_stats: &'a TransferStats,
+}
+
+/// 同步一个文件夹
+///
+/// # 参数
+/// - ctx: 同步所需的共享资源
+/// - folder: 同步文件夹配置
+///
+/// # 返回
+/// - Ok(SyncSession): 会话结束（状态为 completed、paused 或 cancelled）
+/// - Err(SyncError::Interrupted): 文件夹已暂停或正在同步，未开始同步
+/// - Err(SyncError): 会话失败（失败状态已写入数据库）
+pub async fn sync_folder(ctx: &SyncContext<'_>, folder: &SyncFolderConfig) -> Result<SyncSession> {
+    if ctx.control.is_paused(&folder.id) {
+        ctx.states.apply_pause(&folder.id, true);
+        return Err(SyncError::Interrupted(format!(
+            "Sync is paused for folder: {}",
+            folder.id
+        )));
+    }
+
+    ctx.states
+        .transition(&folder.id, FolderSyncState::Scanning)?;
+    let (sync_folder_id, session_id) = match open_session(ctx.db, &folder.id).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            set_state(ctx.states, &folder.id, FolderSyncState::error(&e));
+            return Err(e);
+        }
+    };
+    let started_at = chrono::Utc::now().timestamp();
+    let started = Instant::now();
+    let cancel = ctx.control.register_session(session_id);
+
+    let mut run = FolderRun::new(ctx, folder, sync_folder_id, session_id, cancel);
+    let mut result = run.execute().await;
+    // 会话以任何方式结束（包括暂停、取消和出错）时都写入剩余的快照
+    if let Err(e) = run.flush_synced().await {
+        tracing::error!(folder_id = %folder.id, error = %e, "写入同步快照失败");
+        if result.is_ok() {
+            result = Err(e);
+        }
+    }
+    // 日志写入失败不影响会话结果
+    if let Err(e) = ctx.logs.flush(sync_folder_id).await {
+        tracing::error!(folder_id = %folder.id, error = %e, "写入同步日志失败");
+    }
+    ctx.control.finish_session(session_id);
+
+    let (status, result) = match result {
+        Ok(status) => (status, Ok(())),
+        Err(SyncError::Cancelled(_)) => (session_status::CANCELLED, Ok(())),
+        Err(e) => (session_status::FAILED, Err(e)),
+    };
+    let state = match &result {
+        Err(e) => FolderSyncState::error(e),
+        Ok(()) if status == session_status::PAUSED => FolderSyncState::Paused,
+        Ok(()) => FolderSyncState::Idle,
+    };
+    set_state(ctx.states, &folder.id, state);
+
+    let mut session = SyncSession {
+        id: Some(session_id),
+        sync_folder_id,
+        status: status.to_string(),
+        started_at,
+        completed_at: Some(chrono::Utc::now().timestamp()),
+        files_uploaded: 0,
+        files_downloaded: 0,
+        files_deleted: 0,
+        files_conflict: 0,
+        errors_count: 0,
+        total_bytes: 0,
+        error_message: result.as_ref().err().map(|e| e.to_string()),
+    };
+    run.counters.apply_to(&mut session);
+    sync_sessions::finish(ctx.db, &session).await?;
+
+    if let Err(e) = &result {
+        ctx.emitter.error(&SyncErrorEvent {
+            folder_id: folder.id.clone(),
+            session_id: Some(session_id),
+            file_path: None,
+            error_code: e.code(),
+            message: e.to_string(),
+            fatal: true,
+        });
+    }
+    ctx.emitter.completed(&SyncCompletedEvent {
+        folder_id: folder.id.clone(),
+        session_id: Some(session_id),
+        status: status.to_string(),
+        duration_ms: started.elapsed().as_millis() as i64,
+        counters: run.counters.clone(),
+        skipped: std::mem::take(&mut run.skipped),
+    });
+
+    tracing::info!(folder_id = %folder.id, session_id, status, "同步会话结束");
+    result.map(|_| session)
+}
+
+/// 映射文件夹 ID 并创建同步会话
+///
+/// # 返回
+/// (sync_folder_id, session_id)
+async fn open_session(db: &Database, folder_id: &str) -> Result<(i64, i64)> {
+    let sync_folder_id = folder_keys::resolve(db, folder_id).await?;
+    let session_id = sync_sessions::create(db, sync_folder_id).await?;
+    Ok((sync_folder_id, session_id))
+}
+
+/// 转换文件夹同步状态，失败只记录日志，不影响同步流程
+fn set_state(states: &SyncStateManager, folder_id: &str, state: FolderSyncState) {
+    if let Err(e) = states.transition(folder_id, state) {
+        tracing::warn!(folder_id, error = %e, "更新同步状态失败");
+    }
+}
+
+/// 单次文件夹同步的执行状态
+struct FolderRun<'a> {
+    ctx: &'a SyncContext<'a>,
+    folder: &'a SyncFolderConfig,
+    sync_folder_id: i64,
+    client: Arc<WebDavClient>,
+    signal: PauseSignal,
+    cancel: CancellationToken,
+    counters: SyncCounters,
+    progress: SyncProgressEvent,
+    /// 本次会话持有的远程文件锁
+    locks: Vec<RemoteLock>,
+    /// 远程路径到本地路径的映射
+    paths: PathNormalizer,
+    /// 符号链接处理策略
+    symlinks: SymlinkPolicy,
+    /// 本次会话生成的符号链接占位文件（上传结束后删除）
+    placeholders: Vec<PathBuf>,
+    /// 端到端加密器（未启用加密时为 None）
+    cipher: Option<Arc<FolderCipher>>,
+    /// 上传压缩策略（未启用压缩时为 None）
+    compression: Option<Arc<CompressionPolicy>>,
+    /// 本次同步跳过的条目（会话结束时随完成事件推送）
+    skipped: Vec<SkippedPath>,
+    /// 之前各轮传输已传输的字节数
+    bytes_done: u64,
+    /// 传输速度的滑动窗口
+    rate: SpeedWindow,
+    /// 最近一次推送进度事件的时间
+    last_emit: Option<Instant>,
+    /// 之前会话遗留在传输队列中的记录（按本地路径）
+    previous_transfers: HashMap<String, Transfer>,
+    /// 本次会话的传输在传输队列中的记录 ID（按相对路径）
+    queued: HashMap<String, i64>,
+    /// 尚未写入快照的已同步条目（写入时机见 `record_synced`）
+    synced: Vec<FileMetadata>,
+    /// 缓存中第一条快照记录加入的时间
+    synced_since: Option<Instant>,
+}
+
+impl<'a> FolderRun<'a> {
+    fn new(
+        ctx: &'a SyncContext<'a>,
+        folder: &'a SyncFolderConfig,
+        sync_folder_id: i64,
+        session_id: i64,
+        cancel: CancellationToken,
+    ) -> Self {
+        let mut progress = SyncProgressEvent::new(&folder.id, SyncPhase::Scanning);
+        progress.session_id = Some(session_id);
+
+        Self {
+            ctx,
+            folder,
+            sync_folder_id,
+            client: Arc::new(ctx.client.with_cancellation(cancel.clone())),
+            signal: ctx.control.signal(&folder.id),
+            cancel,
+            counters: SyncCounters::default(),
+            progress,
+            locks: Vec::new(),
+            paths: PathNormalizer::new(ctx.reserved_name_policy)
+                .with_case_collisions(ctx.case_collision_policy),
+            symlinks: SymlinkPolicy::from_config(&folder.symlink_policy),
+            placeholders: Vec::new(),
+            cipher: None,
+            compression: None,
+            skipped: Vec::new(),
+            bytes_done: 0,
+            rate: SpeedWindow::new(
+                Duration::from_secs(TRANSFER_SPEED_WINDOW_SECS),
+                Instant::now(),
+            ),
+            last_emit: None,
+            previous_transfers: HashMap::new(),
+            queued: HashMap::new(),
+            synced: Vec::new(),
+            synced_since: None,
+        }
+    }
+
+    /// 执行同步
+    ///
+    /// # 返回
+    /// - Ok(status): 会话状态（completed，或因暂停、取消提前停止）
+    /// - Err(SyncError::Cancelled): 对比阶段被取消
+    async fn execute(&mut self) -> Result<&'static str> {
+        let started_at = chrono::Utc::now().timestamp();
+        self.emit_phase(SyncPhase::Scanning);
+        // 根目录不可用时本地文件会全部被判定为已删除，必须在对比前确认
+        let has_synced = file_metadata::has_synced(self.ctx.db, self.sync_folder_id).await?;
+        folders::check_sync_root(&self.folder.local_path, &self.folder.id, has_synced)?;
+        if self.folder.encryption.enabled {
+            let cipher = FolderCipher::open(&self.client, self.folder).await?;
+            self.cipher = Some(Arc::new(cipher));
+        }
+        self.compression = CompressionPolicy::from_config(&self.folder.compression)?.map(Arc::new);
+        let mut plan = planner::plan_folder(
+            self.ctx.db,
+            &self.client,
+            self.folder,
+            &mut self.paths,
+            self.cipher.as_deref(),
+            Some(self.ctx.scans),
+            true,
+            self.ctx.file_quiet_period,
+            self.ctx.priority_window,
+        )
+        .await?;
+        // 删除过多时在执行任何操作前中止，等待用户确认计划
+        self.ctx
+            .deletion_guard
+            .check(&self.folder.id, &plan, self.folder.max_delete_ratio)?;
+        space::check_remote(&self.client, &self.folder.remote_path, &plan).await?;
+        space::check_local(&self.folder.local_path, &plan).await?;
+        // 新建的同步清单（包含密钥派生盐）必须在上传任何密文之前写入远程
+        self.save_manifest().await?;
+
+        self.progress.files_total = plan.total_actions() as u32;
+        self.progress.bytes_total = plan.upload_bytes + plan.download_bytes;
+        self.emit_phase(SyncPhase::Comparing);
+        self.emit_phase(SyncPhase::Transferring);
+
+        for item in &plan.conflicts {
+            self.counters.record_conflict();
+            let mut log =
+                self.log_entry(&item.rel_path, sync_action::CONFLICT, log_status::CONFLICT);
+            log.file_size = item.local_size.map(|size| size as i64);
+            self.ctx.logs.write(log).await?;
+            self.advance(&item.rel_path);
+        }
+
+        for item in &plan.skipped {
+            let action = match item.action {
+                PlanAction::Upload => sync_action::UPLOAD,
+                PlanAction::KeepBoth => sync_action::KEEP_BOTH,
+                _ => sync_action::DOWNLOAD,
+            };
+            let mut log = self.log_entry(&item.rel_path, action, log_status::SKIPPED);
+            log.error_message = Some(item.reason.clone());
+            self.ctx.logs.write(log).await?;
+        }
+        self.skipped = plan.skipped.clone();
+
+        // 以副本保存的大小写冲突文件照常同步，但记为冲突提醒用户在服务器上重命名
+        for collision in &plan.case_collisions {
+            self.counters.record_conflict();
+            let mut log = self.log_entry(
+                &collision.rel_path,
+                sync_action::CONFLICT,
+                log_status::CONFLICT,
+            );
+            log.error_message = Some(format!(
+                "Name differs only in case from {}; saved locally as {}",
+                collision.collides_with, collision.local_rel_path
+            ));
+            self.ctx.logs.write(log).await?;
+        }
+
+        for item in &plan.deferred {
+            let mut log = self.log_entry(&item.rel_path, sync_action::UPLOAD, log_status::SKIPPED);
+            log.error_message = item.reason.clone();
+            log.file_size = Some(item.size as i64);
+            self.ctx.logs.write(log).await?;
+        }
+        if !plan.deferred.is_empty() {
+            let retry_at =
+                chrono::Utc::now().timestamp() + i64::from(self.ctx.file_quiet_period.max(1));
+            self.ctx.stability.requeue(&self.folder.id, retry_at);
+            tracing::info!(
+                folder_id = %self.folder.id,
+                files = plan.deferred.len(),
+                "文件仍在写入，稍后重新同步"
+            );
+        }
+
+        self.keep_both(&mut plan).await?;
+        self.create_directories(&plan.uploads, &plan.downloads)
+            .await?;
+        if let Some(status) = self.stop_status() {
+            return Ok(status);
+        }
+
+        // 移动失败的文件改为上传新路径并删除旧路径
+        let failed_moves = self.apply_moves(&plan.moves).await?;
+        self.flush_synced().await?;
+        if let Some(status) = self.stop_status() {
+            return Ok(status);
+        }
+        self.progress.files_total += failed_moves.len() as u32;
+        let fallback_deletions: Vec<PlanItem> = failed_moves
+            .iter()
+            .filter_map(|item| {
+                Some(PlanItem {
+                    rel_path: item.from_path.clone()?,
+                    action: PlanAction::DeleteRemote,
+                    from_path: None,
+                    ..item.clone()
+                })
+            })
+            .collect();
+
+        self.load_queue().await;
+        self.release_stale_locks().await?;
+        let pending_uploads = self.skip_identical_uploads(&plan.uploads).await?;
+        let mut uploads = self.jobs(&pending_uploads, TransferKind::Upload).await?;
+        uploads.extend(self.jobs(&failed_moves, TransferKind::Upload).await?);
+        if self.symlinks == SymlinkPolicy::Placeholder {
+            uploads = self.prepare_placeholders(uploads).await?;
+        }
+        if self.ctx.lock_uploads {
+            uploads = self.lock_jobs(uploads).await?;
+        }
+        let uploaded = self.transfer(uploads, MAX_CONCURRENT_UPLOADS).await;
+        // 写入快照失败时也先释放远程锁和占位文件，再返回错误
+        let flushed = self.flush_synced().await;
+        let released = self.release_locks().await;
+        self.remove_placeholders().await;
+        flushed?;
+        released?;
+        self.save_manifest().await?;
+        if !uploaded? {
+            return Ok(self.stop_status().unwrap_or(session_status::PAUSED));
+        }
+
+        let downloads = self.jobs(&plan.downloads, TransferKind::Download).await?;
+        let downloads = self.guard_downloads(downloads).await?;
+        for job in &downloads {
+            self.save_version(&job.local_path).await;
+        }
+        let downloaded = self.transfer(downloads, MAX_CONCURRENT_DOWNLOADS).await;
+        self.flush_synced().await?;
+        if !downloaded? {
+            return Ok(self.stop_status().unwrap_or(session_status::PAUSED));
+        }
+
+        for item in &plan.local_deletions {
+            if let Some(status) = self.stop_status() {
+                return Ok(status);
+            }
+            self.delete_local(item).await?;
+        }
+
+        for item in fallback_deletions.iter().chain(&plan.remote_deletions) {
+            if let Some(status) = self.stop_status() {
+                return Ok(status);
+            }
+            self.delete_remote(item).await?;
+        }
+        self.flush_synced().await?;
+        self.save_manifest().await?;
+
+        self.emit_phase(SyncPhase::Finalizing);
+        // 本次会话没有再用到的记录（远程或本地已不需要传输的文件）
+        if let Err(e) = transfers::delete_stale(self.ctx.db, &self.folder.id, started_at).await {
+            tracing::warn!(folder_id = %self.folder.id, error = %e, "清理传输队列失败");
+        }
+        marker::tag_remote_root(&self.client, &self.folder.remote_path, self.ctx.client_id).await;
+        Ok(session_status::COMPLETED)
+    }
+
+    /// 会话被取消或文件夹被暂停时返回对应的会话状态
+    fn stop_status(&self) -> Option<&'static str> {
+        if self.cancel.is_cancelled() {
+            Some(session_status::CANCELLED)
+        } else if self.signal.is_paused() {
+            Some(session_status::PAUSED)
+        } else {
+            None
+        }
+    }
+
+    fn pool(&self, concurrency: usize) -> TransferPool {
+        TransferPool::new(
+            self.client.clone(),
+            self.signal.clone(),
+            self.cancel.clone(),
+            concurrency,
+        )
+        .with_cipher(self.cipher.clone())
+        .with_compression(self.compression.clone())
+        .with_parallel_downloads(self.ctx.parallel_download_threshold)
+        .with_stats(
+            self.ctx.transfer_stats.clone(),
+            &self.folder.server_id,
+            &self.folder.id,
+        )
+    }
+
+    /// 启用加密时把新的路径映射写回远程同步清单
+    async fn save_manifest(&self) -> Result<()> {
+        match &self.cipher {
+            Some(cipher) => {
+                cipher
+                    .save_manifest(&self.client, &self.folder.remote_path)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 首次同步时两侧都保留的文件：本地文件改名为冲突副本，
+    /// 随后上传副本并下载远程文件到原路径
+    async fn keep_both(&mut self, plan: &mut SyncPlan) -> Result<()> {
+        let label = format!(
+            "conflicted copy {}",
+            chrono::Local::now().format("%Y-%m-%d %H%M%S")
+        );
+
+        for item in std::mem::take(&mut plan.keep_both) {
+            let mut copy = planner::conflict_copy_path(&item.rel_path, &label);
+            let mut n = 2;
+            while self.local_path(&copy).is_ok_and(|path| path.exists()) {
+                copy = planner::conflict_copy_path(&item.rel_path, &format!("{} {}", label, n));
+                n += 1;
+            }
+
+            let result: Result<()> = async {
+                self.check_local_write(&item.rel_path)?;
+                tokio::fs::rename(self.local_path(&item.rel_path)?, self.local_path(&copy)?)
+                    .await?;
+                Ok(())
+            }
+            .await;
+            if let Err(e) = result {
+                self.record_failure(&item.rel_path, sync_action::KEEP_BOTH, &e)
+                    .await?;
+                continue;
+            }
+
+            let mut log =
+                self.log_entry(&item.rel_path, sync_action::KEEP_BOTH, log_status::SUCCESS);
+            log.error_message = Some(format!("local copy kept as {}", copy));
+            self.ctx.logs.write(log).await?;
+
+            // 保留一项计入进度的操作，另一项追加到总数
+            self.progress.files_total += 1;
+            plan.uploads.push(PlanItem {
+                rel_path: copy,
+                action: PlanAction::Upload,
+                size: item.local_size.unwrap_or(0),
+                ..item.clone()
+            });
+            plan.downloads.push(PlanItem {
+                action: PlanAction::Download,
+                size: item.remote_size.unwrap_or(0),
+                ..item
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 创建计划中的目录（父目录在前）
+    async fn create_directories(
+        &mut self,
+        uploads: &[PlanItem],
+        downloads: &[PlanItem],
+    ) -> Result<()> {
+        for item in uploads.iter().filter(|item| item.is_directory) {
+            let result = self
+                .client
+                .mkdir_all(&self.remote_path(&item.rel_path))
+                .await;
+            if result.is_ok() {
+                self.cache_remote(&item.rel_path, true, 0).await?;
+            }
+            self.record_item(item, sync_action::MKDIR_REMOTE, result)
+                .await?;
+        }
+
+        for item in downloads.iter().filter(|item| item.is_directory) {
+            let result = match self
+                .check_local_write(&item.rel_path)
+                .and_then(|()| self.local_path(&item.rel_path))
+            {
+                Ok(path) => tokio::fs::create_dir_all(path)
+                    .await
+                    .map_err(SyncError::from),
+                Err(e) => Err(e),
+            };
+            self.record_item(item, sync_action::MKDIR_LOCAL, result)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// 在服务器端移动本地已重命名的文件（MOVE），避免重新上传
+    ///
+    /// # 返回
+    /// 移动失败（如目标已存在或服务器不支持 MOVE）、需要改为重新上传的条目
+    async fn apply_moves(&mut self, moves: &[PlanItem]) -> Result<Vec<PlanItem>> {
+        let mut failed = Vec::new();
+
+        for item in moves {
+            if self.stop_status().is_some() {
+                break;
+            }
+            let Some(from) = item.from_path.as_deref() else {
+                continue;
+            };
+
+            let started = Instant::now();
+            let moved = self
+                .client
+                .move_to(
+                    &self.remote_path(from),
+                    &self.remote_path(&item.rel_path),
+                    false,
+                )
+                .await;
+            match moved {
+                Ok(true) => {
+                    file_metadata::mark_deleted(self.ctx.db, self.sync_folder_id, from).await?;
+                    remote_cache::remove(self.ctx.db, &self.folder.id, from).await?;
+                    self.cache_remote(&item.rel_path, false, item.size).await?;
+                    let stored_size = item.remote_size.filter(|&size| size != item.size);
+                    self.record_synced(
+                        &item.rel_path,
+                        &self.local_path(&item.rel_path)?,
+                        false,
+                        stored_size,
+                    )
+                    .await?;
+
+                    let mut log =
+                        self.log_entry(&item.rel_path, sync_action::MOVE, log_status::SUCCESS);
+                    log.file_size = Some(item.size as i64);
+                    log.duration_ms = Some(started.elapsed().as_millis() as i64);
+                    self.ctx.logs.write(log).await?;
+                    self.advance(&item.rel_path);
+                }
+                Ok(false) => {
+                    tracing::warn!(from, to = %item.rel_path, "远程目标已存在，改为重新上传");
+                    failed.push(item.clone());
+                }
+                Err(e @ SyncError::Cancelled(_)) => return Err(e),
+                Err(e) => {
+                    tracing::warn!(from, to = %item.rel_path, error = %e, "远程移动失败，改为重新上传");
+                    failed.push(item.clone());
+                }
+            }
+        }
+
+        Ok(failed)
+    }
+
+    /// 构建传输任务（跳过目录），本地路径无效的条目计为失败
+    async fn jobs(&mut self, items: &[PlanItem], kind: TransferKind) -> Result<Vec<TransferJob>> {
+        let action = match kind {
+            TransferKind::Upload => sync_action::UPLOAD,
+            TransferKind::Download => sync_action::DOWNLOAD,
+        };
+        let mut jobs = Vec::with_capacity(items.len());
+
+        for item in items.iter().filter(|item| !item.is_directory) {
+            match self.local_path(&item.rel_path) {
+                Ok(local_path) => jobs.push(TransferJob {
+                    rel_path: item.rel_path.clone(),
+                    kind,
+                    local_path,
+                    remote_path: self.remote_path(&item.rel_path),
+                    size: item.size,
+                    lock_token: None,
+                    resume_token: None,
+                }),
+                Err(e) => self.record_failure(&item.rel_path, action, &e).await?,
+            }
+        }
+
+        Ok(jobs)
+    }
+
+    /// 跳过远程已存在相同内容的上传（常见于重新添加同步文件夹后）
+    ///
+    /// 只检查远程大小与本地大小相同的文件：读取远程校验信息，内容哈希与本地一致时不再上传，
+    /// 直接写入快照并记录为 `skipped-identical`。加密、压缩上传的文件和占位文件
+    /// 在远程保存的内容与本地文件不同，不参与比对；无法获取校验信息时照常上传
+    async fn skip_identical_uploads(&mut self, items: &[PlanItem]) -> Result<Vec<PlanItem>> {
+        if self.cipher.is_some() {
+            return Ok(items.to_vec());
+        }
+        let mut pending = Vec::with_capacity(items.len());
+
+        for item in items {
+            // 本地路径无效的条目在构建传输任务时计为失败
+            let Ok(local_path) = self.local_path(&item.rel_path) else {
+                pending.push(item.clone());
+                continue;
+            };
+            // 压缩上传和占位文件在远程保存的内容与本地文件不同
+            let compressed = self
+                .compression
+                .as_ref()
+                .is_some_and(|policy| policy.should_compress(&item.rel_path, item.size));
+            let placeholder =
+                self.symlinks == SymlinkPolicy::Placeholder && symlinks::is_symlink(&local_path);
+            let comparable = !item.is_directory
+                && item.remote_size == Some(item.size)
+                && !compressed
+                && !placeholder;
+            if !comparable {
+                pending.push(item.clone());
+                continue;
+            }
+
+            let remote_path = self.remote_path(&item.rel_path);
+            match verify::remote_identical(&self.client, &local_path, &remote_path).await {
+                Ok(true) => {
+                    self.record_synced(&item.rel_path, &local_path, false, None)
+                        .await?;
+                    self.cache_remote(&item.rel_path, false, item.size).await?;
+                    let mut log = self.log_entry(
+                        &item.rel_path,
+                        sync_action::SKIPPED_IDENTICAL,
+                        log_status::SUCCESS,
+                    );
+                    log.file_size = Some(item.size as i64);
+                    self.ctx.logs.write(log).await?;
+                    self.advance(&item.rel_path);
+                }
+                Ok(false) => pending.push(item.clone()),
+                Err(e @ SyncError::Cancelled(_)) => return Err(e),
+                Err(e) => {
+                    tracing::debug!(path = %item.rel_path, error = %e, "无法比对远程文件，照常上传");
+                    pending.push(item.clone());
+                }
+            }
+        }
+
+        Ok(pending)
+    }
+
+    /// 将符号链接的上传任务替换为占位文件（内容为链接目标路径）
+    ///
+    /// 占位文件写入临时目录，上传结束后由 `remove_placeholders` 删除；生成失败的任务计为失败
+    async fn prepare_placeholders(&mut self, jobs: Vec<TransferJob>) -> Result<Vec<TransferJob>> {
+        let mut prepared = Vec::with_capacity(jobs.len());
+
+        for mut job in jobs {
+            if !symlinks::is_symlink(&job.local_path) {
+                prepared.push(job);
+                continue;
+            }
+
+            let placeholder = std::env::temp_dir()
+                .join(format!("lightsync-placeholder-{}", uuid::Uuid::new_v4()));
+            let result = match symlinks::placeholder_content(&job.local_path) {
+                Ok(target) => tokio::fs::write(&placeholder, target)
+                    .await
+                    .map_err(SyncError::from),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    self.placeholders.push(placeholder.clone());
+                    job.local_path = placeholder;
+                    prepared.push(job);
+                }
+                Err(e) => {
+                    self.record_failure(&job.rel_path, sync_action::UPLOAD, &e)
+                        .await?
+                }
+            }
+        }
+
+        Ok(prepared)
+    }
+
+    /// 删除本次会话生成的占位文件
+    async fn remove_placeholders(&mut self) {
+        for placeholder in self.placeholders.drain(..) {
+            if let Err(e) = tokio::fs::remove_file(&placeholder).await {
+                tracing::warn!(path = %placeholder.display(), error = %e, "删除占位文件失败");
+            }
+        }
+    }
+
+    /// 过滤会经由符号链接写入本地的下载任务，被拒绝的任务计为失败
+    async fn guard_downloads(&mut self, jobs: Vec<TransferJob>) -> Result<Vec<TransferJob>> {
+        let mut allowed = Vec::with_capacity(jobs.len());
+
+        for job in jobs {
+            match self.check_local_write(&job.rel_path) {
+                Ok(()) => allowed.push(job),
+                Err(e) => {
+                    self.record_failure(&job.rel_path, sync_action::DOWNLOAD, &e)
+                        .await?
+                }
+            }
+        }
+
+        Ok(allowed)
+    }
+
+    /// 检查写入本地路径时经过的符号链接是否符合文件夹的符号链接策略
+    fn check_local_write(&self, rel_path: &str) -> Result<()> {
+        symlinks::check_write_target(
+            &self.folder.local_path,
+            &self.paths.local_rel_path(rel_path),
+            self.symlinks,
+        )
+    }
+
+    /// 上传前锁定远程文件
+    ///
+    /// 已被其他客户端锁定的文件计为失败并跳过；服务器不支持锁时其余文件不加锁直接上传
+    async fn lock_jobs(&mut self, jobs: Vec<TransferJob>) -> Result<Vec<TransferJob>> {
+        let owner = format!("{} {}", APP_NAME, APP_VERSION);
+        let timeout = Duration::from_secs(REMOTE_LOCK_TIMEOUT);
+        let mut locked = Vec::with_capacity(jobs.len());
+
+        let mut jobs = jobs.into_iter();
+        for mut job in jobs.by_ref() {
+            match self.client.lock(&job.remote_path, &owner, timeout).await {
+                Ok(Some(lock)) => {
+                    remote_locks::save(self.ctx.db, &self.folder.id, &lock).await?;
+                    job.lock_token = Some(lock.token.clone());
+                    self.locks.push(lock);
+                    locked.push(job);
+                }
+                Ok(None) => {
+                    tracing::info!(
+                        folder_id = %self.folder.id,
+                        "服务器不支持 WebDAV 锁，上传时不加锁"
+                    );
+                    locked.push(job);
+                    break;
+                }
+                Err(e @ SyncError::Cancelled(_)) => return Err(e),
+                Err(e) => {
+                    self.record_failure(&job.rel_path, sync_action::UPLOAD, &e)
+                        .await?
+                }
+            }
+        }
+        locked.extend(jobs);
+
+        Ok(locked)
+    }
+
+    /// 释放本次会话持有的锁
+    async fn release_locks(&mut self) -> Result<()> {
+        for lock in std::mem::take(&mut self.locks) {
+            self.release_lock(&lock).await?;
+        }
+        Ok(())
+    }
+
+    /// 释放上次会话异常退出时遗留的锁
+    async fn release_stale_locks(&self) -> Result<()> {
+        for lock in remote_locks::list_by_folder(self.ctx.db, &self.folder.id).await? {
+            self.release_lock(&lock).await?;
+        }
+        Ok(())
+    }
+
+    /// 释放锁并删除记录
+    ///
+    /// 已过期的锁直接删除记录；释放失败时保留记录，下次同步时重试
+    async fn release_lock(&self, lock: &RemoteLock) -> Result<()> {
+        if lock.expires_at > chrono::Utc::now().timestamp() {
+            if let Err(e) = self.client.unlock(lock).await {
+                tracing::warn!(path = %lock.path, error = %e, "释放远程文件锁失败");
+                return Ok(());
+            }
+        }
+        remote_locks::delete(self.ctx.db, &self.folder.id, &lock.path).await
+    }
+
+    /// 执行传输任务，校验失败的文件最多重新传输 `MAX_VERIFY_RETRIES` 次
+    ///
+    /// # 返回
+    /// - Ok(true): 所有任务均已执行（成功或失败）
+    /// - Ok(false): 有任务因暂停或取消未完成
+    async fn transfer(&mut self, mut jobs: Vec<TransferJob>, concurrency: usize) -> Result<bool> {
+        let mut retries_left = MAX_VERIFY_RETRIES;
+
+        loop {
+            self.enqueue(&mut jobs).await;
+            let pool = self.pool(concurrency);
+            let results = self.run_pool(&pool, jobs).await;
+            self.bytes_done += pool.bytes_transferred();
+            let (completed, corrupt) = self.record_transfers(results, retries_left > 0).await?;
+            if !completed || corrupt.is_empty() {
+                return Ok(completed);
+            }
+
+            retries_left -= 1;
+            jobs = corrupt;
+        }
+    }
+
+    /// 读取之前会话遗留在传输队列中的记录
+    async fn load_queue(&mut self) {
+        match transfers::list_for_folder(self.ctx.db, &self.folder.id).await {
+            Ok(rows) => {
+                self.previous_transfers = rows
+                    .into_iter()
+                    .map(|transfer| (transfer.local_path.clone(), transfer))
+                    .collect();
+            }
+            Err(e) => {
+                tracing::warn!(folder_id = %self.folder.id, error = %e, "读取传输队列失败")
+            }
+        }
+    }
+
+    /// 把传输任务写入传输队列，写入失败只记录日志，不影响同步
+    async fn enqueue(&mut self, jobs: &mut [TransferJob]) {
+        for job in jobs {
+            if let Err(e) = self.enqueue_job(job).await {
+                tracing::warn!(path = %job.rel_path, error = %e, "写入传输队列失败");
+            }
+        }
+    }
+
+    /// 把传输任务写入传输队列，替换同一文件之前的记录；可以续传的下载带上远程文件的 ETag
+    async fn enqueue_job(&mut self, job: &mut TransferJob) -> Result<()> {
+        let db = self.ctx.db;
+        let local_path = job.local_path.to_string_lossy().into_owned();
+        let previous = self.previous_transfers.remove(&local_path);
+        let replaced = self
+            .queued
+            .remove(&job.rel_path)
+            .into_iter()
+            .chain(previous.as_ref().map(|transfer| transfer.id));
+        for id in replaced {
+            transfers::delete(db, id).await?;
+        }
+
+        let (direction, resume) = match job.kind {
+            TransferKind::Upload => (transfer_direction::UPLOAD, None),
+            TransferKind::Download => (
+                transfer_direction::DOWNLOAD,
+                self.resume_point(job, previous.as_ref()).await?,
+            ),
+        };
+        let id = transfers::enqueue(
+            db,
+            Some(&self.folder.id),
+            &self.folder.server_id,
+            direction,
+            &local_path,
+            &job.remote_path,
+            Some(job.size as i64),
+        )
+        .await?;
+        self.queued.insert(job.rel_path.clone(), id);
+
+        if let Some((etag, offset)) = resume {
+            transfers::update_progress(db, id, offset as i64, Some(&etag)).await?;
+            job.resume_token = Some(etag);
+        }
+        Ok(())
+    }
+
+    /// 确定下载的续传位置
+    ///
+    /// 只有未加密的文件夹、远程文件有强 ETag 时才能续传。之前的记录 ETag 相同时保留临时文件，
+    /// 截断到记录的字节数（之后写入的内容可能尚未落盘）；否则删除临时文件，从头下载
+    ///
+    /// # 返回
+    /// (ETag, 续传位置)，不能续传时为 None
+    async fn resume_point(
+        &self,
+        job: &TransferJob,
+        previous: Option<&Transfer>,
+    ) -> Result<Option<(String, u64)>> {
+        let etag = match self.cipher {
+            Some(_) => None,
+            None => remote_cache::etag(self.ctx.db, &self.folder.id, &job.rel_path).await?,
+        }
+        .filter(|etag| !etag.starts_with("W/"));
+        let saved = match (&etag, previous) {
+            (Some(etag), Some(previous))
+                if previous.direction == transfer_direction::DOWNLOAD
+                    && previous.resume_token.as_ref() == Some(etag) =>
+            {
+                previous.bytes_done.max(0) as u64
+            }
+            _ => 0,
+        };
+
+        let offset = truncate_partial(&partial_download_path(&job.local_path), saved).await?;
+        Ok(etag.map(|etag| (etag, offset)))
+    }
+
+    /// 把进行中传输的已传输字节数写入传输队列
+    async fn save_progress(&self) {
+        let db = self.ctx.db;
+        let snapshot = self.ctx.transfer_stats.snapshot();
+        let active = snapshot
+            .transfers
+            .iter()
+            .filter(|transfer| transfer.folder_id.as_deref() == Some(self.folder.id.as_str()));
+
+        for transfer in active {
+            let Some(&id) = self.queued.get(&transfer.path) else {
+                continue;
+            };
+            let saved: Result<()> = async {
+                transfers::set_status(db, id, transfer_status::RUNNING, None).await?;
+                transfers::update_progress(db, id, transfer.bytes_transferred as i64, None).await
+            }
+            .await;
+            if let Err(e) = saved {
+                tracing::warn!(path = %transfer.path, error = %e, "保存传输进度失败");
+            }
+        }
+    }
+
+    /// 按传输结果更新传输队列
+    ///
+    /// 完成的记录删除；失败、暂停或取消的记录保留已保存的进度，下次同步时继续
+    async fn settle(&mut self, job: &TransferJob, outcome: &TransferOutcome) {
+        let db = self.ctx.db;
+        let Some(&id) = self.queued.get(&job.rel_path) else {
+            return;
+        };
+        let result = match outcome {
+            TransferOutcome::Completed { .. } => {
+                self.queued.remove(&job.rel_path);
+                transfers::delete(db, id).await
+            }
+            TransferOutcome::Paused => {
+                transfers::set_status(db, id, transfer_status::PAUSED, None).await
+            }
+            TransferOutcome::Cancelled => {
+                transfers::set_status(db, id, transfer_status::QUEUED, None).await
+            }
+            TransferOutcome::Failed(e) => {
+                transfers::set_status(db, id, transfer_status::FAILED, Some(&e.to_string())).await
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!(path = %job.rel_path, error = %e, "更新传输队列失败");
+        }
+    }
+
+    /// 执行传输池，传输期间按 `SYNC_PROGRESS_INTERVAL_MS` 推送字节进度与预计剩余时间
+    async fn run_pool(
+        &mut self,
+        pool: &TransferPool,
+        jobs: Vec<TransferJob>,
+    ) -> Vec<(TransferJob, TransferOutcome)> {
+        let run = pool.run(jobs);
+        tokio::pin!(run);
+        let mut ticker = tokio::time::interval(Duration::from_millis(SYNC_PROGRESS_INTERVAL_MS));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // 第一次 tick 立即完成，跳过
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                results = &mut run => return results,
+                _ = ticker.tick() => {
+                    self.update_bytes(self.bytes_done + pool.bytes_transferred());
+                    self.emit_progress(true);
+                    self.save_progress().await;
+                }
+            }
+        }
+    }
+
+    /// 记录传输结果
+    ///
+    /// # 参数
+    /// - results: 传输任务及结果
+    /// - retry_corrupt: 校验失败的任务是否重新传输
+    ///
+    /// # 返回
+    /// (所有任务是否均已执行, 需要重新传输的任务)
+    async fn record_transfers(
+        &mut self,
+        results: Vec<(TransferJob, TransferOutcome)>,
+        retry_corrupt: bool,
+    ) -> Result<(bool, Vec<TransferJob>)> {
+        let mut completed = true;
+        let mut corrupt = Vec::new();
+
+        for (job, outcome) in results {
+            self.settle(&job, &outcome).await;
+            let action = match job.kind {
+                TransferKind::Upload => sync_action::UPLOAD,
+                TransferKind::Download => sync_action::DOWNLOAD,
+            };
+
+            match outcome {
+                TransferOutcome::Completed {
+                    bytes,
+                    duration_ms,
+                    stored_size,
+                } => {
+                    let status = match self.verify(&job, stored_size).await? {
+                        Verification::Verified => log_status::VERIFIED,
+                        Verification::Unverifiable => log_status::SUCCESS,
+                        Verification::Corrupt(reason) => {
+                            self.record_corrupt(&job.rel_path, action, &reason, retry_corrupt)
+                                .await?;
+                            if retry_corrupt {
+                                corrupt.push(job);
+                            }
+                            continue;
+                        }
+                    };
+
+                    self.record_synced(
+                        &job.rel_path,
+                        &self.local_path(&job.rel_path)?,
+                        false,
+                        stored_size,
+                    )
+                    .await?;
+                    match job.kind {
+                        TransferKind::Upload => {
+                            self.cache_remote(&job.rel_path, false, job.size).await?;
+                            self.counters.record_upload(bytes);
+                        }
+                        TransferKind::Download => self.counters.record_download(bytes),
+                    }
+
+                    let mut log = self.log_entry(&job.rel_path, action, status);
+                    log.file_size = Some(bytes as i64);
+                    log.duration_ms = Some(duration_ms);
+                    log.bytes_per_sec = throughput::average_speed(bytes, duration_ms);
+                    self.ctx.logs.write(log).await?;
+                    self.advance(&job.rel_path);
+                }
+                TransferOutcome::Paused | TransferOutcome::Cancelled => completed = false,
+                TransferOutcome::Failed(e) => {
+                    self.record_failure(&job.rel_path, action, &e).await?
+                }
+            }
+        }
+
+        Ok((completed, corrupt))
+    }
+
+    /// 校验传输结果（未开启校验或无法获取远程校验信息时视为无法校验）
+    async fn verify(&self, job: &TransferJob, stored_size: Option<u64>) -> Result<Verification> {
+        // 远程保存的是密文或压缩数据，无法与本地文件比对；
+        // 密文的完整性由解密时的认证标签保证，压缩数据解压时校验原始大小
+        if !self.ctx.verify_transfers || self.cipher.is_some() || stored_size.is_some() {
+            return Ok(Verification::Unverifiable);
+        }
+
+        match verify::verify_transfer(&self.client, &job.local_path, &job.remote_path).await {
+            Ok(verification) => Ok(verification),
+            Err(e @ SyncError::Cancelled(_)) => Err(e),
+            Err(e) => {
+                tracing::warn!(path = %job.rel_path, error = %e, "无法获取校验信息，跳过校验");
+                Ok(Verification::Unverifiable)
+            }
+        }
+    }
+
+    /// 记录校验失败的传输，不再重试时计为错误
+    async fn record_corrupt(
+        &mut self,
+        rel_path: &str,
+        action: &str,
+        reason: &str,
+        retry: bool,
+    ) -> Result<()> {
+        tracing::warn!(path = %rel_path, action, reason, retry, "传输完整性校验失败");
+
+        let mut log = self.log_entry(rel_path, action, log_status::CORRUPT);
+        log.error_message = Some(reason.to_string());
+        self.ctx.logs.write(log).await?;
+
+        if !retry {
+            self.report_error(rel_path, &SyncError::IntegrityError(reason.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 删除本地文件（移入回收站）
+    ///
+    /// 计划中的子项先于目录删除；目录中剩余的未同步内容（如被忽略的文件）
+    /// 自下而上逐个移入回收站，不会被永久删除
+    async fn delete_local(&mut self, item: &PlanItem) -> Result<()> {
+        let trash = self.ctx.trash;
+        let trashed = match self.local_path(&item.rel_path) {
+            Ok(path) if item.is_directory => trash
+                .move_tree_to_trash(self.ctx.db, &self.folder.id, &path)
+                .await
+                .map(|_| ()),
+            Ok(path) => trash
+                .move_to_trash(self.ctx.db, &self.folder.id, &path)
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+        let result = match trashed {
+            Ok(_) | Err(SyncError::FileNotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        };
+        self.record_deletion(item, sync_action::DELETE_LOCAL, result)
+            .await
+    }
+
+    /// 删除远程文件
+    ///
+    /// 目录只在已为空时删除（计划中的子项先于目录删除），
+    /// 仍包含未同步内容（如被排除的子文件夹）的远程目录会作为失败记录，不会连同内容一起删除
+    async fn delete_remote(&mut self, item: &PlanItem) -> Result<()> {
+        let remote_path = self.remote_path(&item.rel_path);
+        let deleted = if item.is_directory {
+            self.client.delete_recursive(&remote_path, false).await
+        } else {
+            self.client.delete(&remote_path).await
+        };
+        let result = match deleted {
+            Ok(()) | Err(SyncError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let (Ok(()), Some(cipher)) = (&result, &self.cipher) {
+            cipher.forget(&item.rel_path);
+        }
+        if result.is_ok() {
+            remote_cache::remove(self.ctx.db, &self.folder.id, &item.rel_path).await?;
+        }
+        self.record_deletion(item, sync_action::DELETE_REMOTE, result)
+            .await
+    }
+
+    /// 记录删除结果，成功时移除快照
+    async fn record_deletion(
+        &mut self,
+        item: &PlanItem,
+        action: &str,
+        result: Result<()>,
+    ) -> Result<()> {
+        match result {
+            Ok(()) => {
+                file_metadata::mark_deleted(self.ctx.db, self.sync_folder_id, &item.rel_path)
+                    .await?;
+                self.counters.record_delete();
+                let log = self.log_entry(&item.rel_path, action, log_status::SUCCESS);
+                self.ctx.logs.write(log).await?;
+                self.advance(&item.rel_path);
+                Ok(())
+            }
+            Err(e) => self.record_failure(&item.rel_path, action, &e).await,
+        }
+    }
+
+    /// 记录目录创建结果，成功时写入快照
+    async fn record_item(
+        &mut self,
+        item: &PlanItem,
+        action: &str,
+        result: Result<()>,
+    ) -> Result<()> {
+        match result {
+            Ok(()) => {
+                self.record_synced(
+                    &item.rel_path,
+                    &self.local_path(&item.rel_path)?,
+                    true,
+                    None,
+                )
+                .await?;
+                let log = self.log_entry(&item.rel_path, action, log_status::SUCCESS);
+                self.ctx.logs.write(log).await?;
+                self.advance(&item.rel_path);
+                Ok(())
+            }
+            Err(e) => self.record_failure(&item.rel_path, action, &e).await,
+        }
+    }
+
+    /// 记录单个条目的失败（不会终止会话）
+    async fn record_failure(
+        &mut self,
+        rel_path: &str,
+        action: &str,
+        error: &SyncError,
+    ) -> Result<()> {
+        tracing::warn!(path = %rel_path, action, error = %error, "同步条目失败");
+
+        let mut log = self.log_entry(rel_path, action, log_status::FAILED);
+        log.error_message = Some(error.to_string());
+        self.ctx.logs.write(log).await?;
+
+        self.report_error(rel_path, error);
+        Ok(())
+    }
+
+    /// 计入错误统计并推送非致命错误事件
+    fn report_error(&mut self, rel_path: &str, error: &SyncError) {
+        self.counters.record_error();
+        self.ctx.emitter.error(&SyncErrorEvent {
+            folder_id: self.folder.id.clone(),
+            session_id: self.progress.session_id,
+            file_path: Some(rel_path.to_string()),
+            error_code: error.code(),
+            message: error.to_string(),
+            fatal: false,
+        });
+        self.advance(rel_path);
+    }
+
+    /// 将已在服务器上创建的条目写入远程目录树缓存（ETag 要到下次列出远程目录时才知道）
+    async fn cache_remote(&self, rel_path: &str, is_directory: bool, size: u64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let entry = RemoteCacheEntry {
+            path: rel_path.to_string(),
+            is_directory,
+            size,
+            modified: Some(now),
+            etag: None,
+            cached_at: now,
+        };
+        remote_cache::upsert(self.ctx.db, &self.folder.id, &entry).await
+    }
+
+    /// 以本地文件的当前状态写入快照
+    ///
+    /// 占位文件策略下记录符号链接本身的状态，与扫描结果保持一致。
+    /// 上传时压缩的文件同时记录远程大小，供下次对比判断远程文件是否变化。
+    /// 记录先缓存在会话中，累计 `METADATA_BATCH_SIZE` 条、最早的记录等待超过
+    /// `METADATA_FLUSH_INTERVAL_MS`、每个阶段结束或会话结束时批量写入
+    async fn record_synced(
+        &mut self,
+        rel_path: &str,
+        local_path: &Path,
+        is_directory: bool,
+        remote_size: Option<u64>,
+    ) -> Result<()> {
+        let local_rel_path = self.paths.local_rel_path(rel_path);
+        let metadata = match tokio::fs::symlink_metadata(local_path).await {
+            Ok(link_metadata)
+                if link_metadata.file_type().is_symlink()
+                    && self.symlinks == SymlinkPolicy::Placeholder =>
+            {
+                Ok(link_metadata)
+            }
+            Ok(_) => tokio::fs::metadata(local_path).await,
+            Err(e) => Err(e),
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            // 传输后文件已在本地被删除或重命名（常见于仍在编辑的文件），不写入快照，留给下次扫描
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::debug!(path = %rel_path, "文件传输后已不存在，跳过写入快照");
+                self.ctx.scans.mark_dirty(&self.folder.id, &local_rel_path);
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let size = if metadata.file_type().is_symlink() {
+            symlinks::placeholder_content(local_path)?.len() as u64
+        } else {
+            metadata.len()
+        };
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        // 下次同步时重新读取，扫描结果与写入快照的状态一致
+        self.ctx.scans.mark_dirty(&self.folder.id, &local_rel_path);
+        self.synced.push(FileMetadata {
+            id: None,
+            path: rel_path.to_string(),
+            hash: None,
+            size: if is_directory { 0 } else { size as i64 },
+            modified_at,
+            synced_at: Some(chrono::Utc::now().timestamp()),
+            sync_folder_id: self.sync_folder_id,
+            is_directory,
+            status: "synced".to_string(),
+            created_at: None,
+            updated_at: None,
+            remote_size: remote_size.map(|size| size as i64),
+            inode: scanner::inode(&metadata).map(|inode| inode as i64),
+        });
+        let since = *self.synced_since.get_or_insert_with(Instant::now);
+        if self.synced.len() >= METADATA_BATCH_SIZE
+            || since.elapsed() >= Duration::from_millis(METADATA_FLUSH_INTERVAL_MS)
+        {
+            self.flush_synced().await?;
+        }
+
+        Ok(())
+    }
+
+    /// 在一个事务中写入缓存的快照记录
+    async fn flush_synced(&mut self) -> Result<()> {
+        self.synced_since = None;
+        let rows = std::mem::take(&mut self.synced);
+        file_metadata::upsert_batch(self.ctx.db, &rows).await
+    }
+
+    /// 下载覆盖本地文件前保存历史版本（失败只记录日志）
+    async fn save_version(&self, local_path: &Path) {
+        if let Err(e) = self
+            .ctx
+            .versions
+            .save_version(
+                self.ctx.db,
+                &self.folder.id,
+                local_path,
+                self.ctx.max_versions,
+            )
+            .await
+        {
+            tracing::warn!(path = %local_path.display(), error = %e, "保存历史版本失败");
+        }
+    }
+
+    fn log_entry(&self, rel_path: &str, action: &str, status: &str) -> SyncLog {
+        SyncLog {
+            id: None,
+            sync_folder_id: self.sync_folder_id,
+            file_path: rel_path.to_string(),
+            action: action.to_string(),
+            status: status.to_string(),
+            error_message: None,
+            file_size: None,
+            duration_ms: None,
+            bytes_per_sec: None,
+            created_at: None,
+        }
+    }
+
+    fn advance(&mut self, rel_path: &str) {
+        self.progress.files_processed += 1;
+        self.progress.current_file = Some(rel_path.to_string());
+        self.progress.counters = self.counters.clone();
+        self.update_bytes(self.bytes_done);
+        // 最后一个条目总是推送，保证前端看到最终计数
+        let last = self.progress.files_processed >= self.progress.files_total;
+        self.emit_progress(last);
+        self.update_state();
+    }
+
+    fn emit_phase(&mut self, phase: SyncPhase) {
+        self.progress.phase = phase;
+        self.progress.current_file = None;
+        if phase != SyncPhase::Transferring {
+            self.progress.bytes_per_sec = 0;
+            self.progress.eta_secs = None;
+        }
+        self.emit_progress(true);
+        self.update_state();
+    }
+
+    /// 更新已传输字节数、当前速度与预计剩余时间
+    fn update_bytes(&mut self, completed: u64) {
+        let now = Instant::now();
+        let previous = self.progress.bytes_completed;
+        if completed > previous {
+            self.rate.record(now, completed - previous);
+            self.progress.bytes_completed = completed;
+        }
+        if self.progress.phase != SyncPhase::Transferring {
+            return;
+        }
+
+        self.progress.bytes_per_sec = self.rate.bytes_per_sec(now);
+        let remaining = self
+            .progress
+            .bytes_total
+            .saturating_sub(self.progress.bytes_completed);
+        self.progress.eta_secs =
+            throughput::estimate_remaining_secs(remaining, self.progress.bytes_per_sec);
+    }
+
+    /// 推送进度事件
+    ///
+    /// # 参数
+    /// - force: 为 false 时距上次推送不足 `SYNC_PROGRESS_INTERVAL_MS` 则跳过
+    fn emit_progress(&mut self, force: bool) {
+        let interval = Duration::from_millis(SYNC_PROGRESS_INTERVAL_MS);
+        if !force && self.last_emit.is_some_and(|at| at.elapsed() < interval) {
+            return;
+        }
+        self.last_emit = Some(Instant::now());
+        self.ctx.emitter.progress(&self.progress);
+    }
+
+    /// 传输阶段按已处理条目更新文件夹状态的进度
+    fn update_state(&self) {
+        if self.progress.phase == SyncPhase::Transferring {
+            set_state(
+                self.ctx.states,
+                &self.folder.id,
+                FolderSyncState::transferring(
+                    self.progress.files_processed,
+                    self.progress.files_total,
+                ),
+            );
+        }
+    }
+
+    fn local_path(&self, rel_path: &str) -> Result<PathBuf> {
+        self.paths.local_path(&self.folder.local_path, rel_path)
+    }
+
+    fn remote_path(&self, rel_path: &str) -> String {
+        let rel_path = self.paths.remote_rel_path(rel_path);
+        let rel_path = match &self.cipher {
+            Some(cipher) => cipher.remote_rel_path(&rel_path),
+            None => rel_path,
+        };
+        format!(
+            "{}/{}",
+            self.folder.remote_path.trim_end_matches('/'),
+            rel_path
+        )
+    }
+}
+
+/// 把下载临时文件截断到 len 字节（len 为 0 时删除临时文件）
+///
+/// # 返回
+/// 临时文件截断后的长度（不存在时为 0）
+async fn truncate_partial(path: &Path, len: u64) -> Result<u64> {
+    let Ok(metadata) = tokio::fs::metadata(path).await else {
+        return Ok(0);
+    };
+    let len = metadata.len().min(len);
+    if len == 0 {
+        tokio::fs::remove_file(path).await?;
+    } else {
+        let file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+        file.set_len(len).await?;
+    }
+    Ok(len)
+}
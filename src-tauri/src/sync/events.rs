@@ -0,0 +1,362 @@
+/// 同步事件定义
+///
+/// 同步引擎通过 Tauri 事件系统向前端推送实时进度，前端使用
+/// `listen('sync://progress', ...)` 订阅即可渲染每个文件、每个文件夹的同步状态。
+///
+/// # 事件列表
+///
+/// - `sync://progress`: 同步进度（阶段、当前文件、已处理数量、累计计数、字节进度与预计剩余时间）
+/// - `sync://completed`: 同步会话结束（最终计数、耗时与跳过的条目）
+/// - `sync://error`: 同步过程中的错误（单个文件失败或整个会话失败）
+/// - `sync://state-changed`: 文件夹同步状态变化（空闲、扫描、传输、暂停、出错）
+/// - `sync://triggered`: 批量触发同步时，一次性推送加入队列的文件夹
+/// - `sync://skipped`: 自动同步因同步策略被跳过（跳过原因变化时推送）
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::SyncSession;
+use crate::error::ErrorCode;
+use crate::sync::paths::SkippedPath;
+use crate::sync::policy::SkipReason;
+use crate::sync::state::FolderSyncState;
+
+/// 同步进度事件名称
+pub const SYNC_PROGRESS_EVENT: &str = "sync://progress";
+
+/// 同步完成事件名称
+pub const SYNC_COMPLETED_EVENT: &str = "sync://completed";
+
+/// 同步错误事件名称
+pub const SYNC_ERROR_EVENT: &str = "sync://error";
+
+/// 同步状态变化事件名称
+pub const SYNC_STATE_CHANGED_EVENT: &str = "sync://state-changed";
+
+/// 批量触发同步事件名称
+pub const SYNC_TRIGGERED_EVENT: &str = "sync://triggered";
+
+/// 自动同步被跳过事件名称
+pub const SYNC_SKIPPED_EVENT: &str = "sync://skipped";
+
+/// 同步阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncPhase {
+    /// 扫描本地和远程文件
+    Scanning,
+    /// 对比快照，生成同步计划
+    Comparing,
+    /// 传输文件（上传、下载、删除）
+    Transferring,
+    /// 写回元数据，结束会话
+    Finalizing,
+}
+
+/// 同步计数
+///
+/// 字段与 `SyncSession` 保持一致，会话结束时可直接写回数据库
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCounters {
+    /// 已上传文件数
+    pub files_uploaded: i32,
+    /// 已下载文件数
+    pub files_downloaded: i32,
+    /// 已删除文件数
+    pub files_deleted: i32,
+    /// 冲突文件数
+    pub files_conflict: i32,
+    /// 错误数
+    pub errors_count: i32,
+    /// 已传输总字节数
+    pub total_bytes: i64,
+}
+
+impl SyncCounters {
+    /// 记录一次上传
+    pub fn record_upload(&mut self, bytes: u64) {
+        self.files_uploaded += 1;
+        self.total_bytes += bytes as i64;
+    }
+
+    /// 记录一次下载
+    pub fn record_download(&mut self, bytes: u64) {
+        self.files_downloaded += 1;
+        self.total_bytes += bytes as i64;
+    }
+
+    /// 记录一次删除
+    pub fn record_delete(&mut self) {
+        self.files_deleted += 1;
+    }
+
+    /// 记录一次冲突
+    pub fn record_conflict(&mut self) {
+        self.files_conflict += 1;
+    }
+
+    /// 记录一次错误
+    pub fn record_error(&mut self) {
+        self.errors_count += 1;
+    }
+
+    /// 将计数写回同步会话
+    pub fn apply_to(&self, session: &mut SyncSession) {
+        session.files_uploaded = self.files_uploaded;
+        session.files_downloaded = self.files_downloaded;
+        session.files_deleted = self.files_deleted;
+        session.files_conflict = self.files_conflict;
+        session.errors_count = self.errors_count;
+        session.total_bytes = self.total_bytes;
+    }
+}
+
+/// 同步进度事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgressEvent {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 同步会话 ID（会话写入数据库后才有）
+    pub session_id: Option<i64>,
+    /// 当前阶段
+    pub phase: SyncPhase,
+    /// 当前处理的文件（相对于同步文件夹根目录）
+    pub current_file: Option<String>,
+    /// 当前文件已传输字节数
+    pub file_bytes_transferred: u64,
+    /// 当前文件总字节数
+    pub file_total_bytes: u64,
+    /// 已处理文件数
+    pub files_processed: u32,
+    /// 计划处理的文件总数
+    pub files_total: u32,
+    /// 计划传输的总字节数（上传与下载合计）
+    pub bytes_total: u64,
+    /// 已传输的字节数（包括进行中的传输）
+    pub bytes_completed: u64,
+    /// 当前传输速度（字节/秒，滑动窗口平均）
+    pub bytes_per_sec: u64,
+    /// 预计剩余时间（秒，只在传输阶段且速度已知时提供）
+    pub eta_secs: Option<u64>,
+    /// 累计计数
+    pub counters: SyncCounters,
+}
+
+impl SyncProgressEvent {
+    /// 创建指定阶段的进度事件
+    pub fn new(folder_id: impl Into<String>, phase: SyncPhase) -> Self {
+        Self {
+            folder_id: folder_id.into(),
+            session_id: None,
+            phase,
+            current_file: None,
+            file_bytes_transferred: 0,
+            file_total_bytes: 0,
+            files_processed: 0,
+            files_total: 0,
+            bytes_total: 0,
+            bytes_completed: 0,
+            bytes_per_sec: 0,
+            eta_secs: None,
+            counters: SyncCounters::default(),
+        }
+    }
+}
+
+/// 同步完成事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCompletedEvent {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 同步会话 ID
+    pub session_id: Option<i64>,
+    /// 会话最终状态（completed, paused, failed, cancelled）
+    pub status: String,
+    /// 会话耗时（毫秒）
+    pub duration_ms: i64,
+    /// 最终计数
+    pub counters: SyncCounters,
+    /// 本次同步跳过的条目（不合法的文件名、超出文件大小上限或扩展名过滤）
+    #[serde(default)]
+    pub skipped: Vec<SkippedPath>,
+}
+
+/// 同步错误事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncErrorEvent {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 同步会话 ID
+    pub session_id: Option<i64>,
+    /// 出错的文件（会话级错误时为 None）
+    pub file_path: Option<String>,
+    /// 错误码
+    pub error_code: ErrorCode,
+    /// 错误信息
+    pub message: String,
+    /// 是否为致命错误（致命错误会终止整个会话）
+    pub fatal: bool,
+}
+
+/// 同步状态变化事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStateChangedEvent {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 变化前的状态
+    pub previous: FolderSyncState,
+    /// 当前状态
+    pub state: FolderSyncState,
+}
+
+/// 批量触发同步事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTriggeredEvent {
+    /// 加入同步队列的文件夹 ID（按同步顺序）
+    pub folder_ids: Vec<String>,
+}
+
+/// 自动同步被跳过事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSkippedEvent {
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 跳过原因
+    pub reason: SkipReason,
+}
+
+/// 同步事件发送器
+///
+/// 封装 `AppHandle::emit`，事件发送失败只记录日志，不影响同步流程
+#[derive(Clone)]
+pub struct SyncEventEmitter {
+    app: AppHandle,
+}
+
+impl SyncEventEmitter {
+    /// 创建事件发送器
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// 发送进度事件
+    pub fn progress(&self, event: &SyncProgressEvent) {
+        self.emit(SYNC_PROGRESS_EVENT, event);
+    }
+
+    /// 发送完成事件
+    pub fn completed(&self, event: &SyncCompletedEvent) {
+        self.emit(SYNC_COMPLETED_EVENT, event);
+    }
+
+    /// 发送错误事件
+    pub fn error(&self, event: &SyncErrorEvent) {
+        self.emit(SYNC_ERROR_EVENT, event);
+    }
+
+    /// 发送状态变化事件
+    pub fn state_changed(&self, event: &SyncStateChangedEvent) {
+        self.emit(SYNC_STATE_CHANGED_EVENT, event);
+    }
+
+    /// 发送批量触发同步事件
+    pub fn triggered(&self, event: &SyncTriggeredEvent) {
+        self.emit(SYNC_TRIGGERED_EVENT, event);
+    }
+
+    /// 发送自动同步被跳过事件
+    pub fn skipped(&self, event: &SyncSkippedEvent) {
+        self.emit(SYNC_SKIPPED_EVENT, event);
+    }
+
+    fn emit<T: Serialize + Clone>(&self, name: &str, payload: &T) {
+        if let Err(e) = self.app.emit(name, payload.clone()) {
+            tracing::warn!(event = name, error = %e, "发送同步事件失败");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_record() {
+        let mut counters = SyncCounters::default();
+        counters.record_upload(100);
+        counters.record_download(50);
+        counters.record_delete();
+        counters.record_conflict();
+        counters.record_error();
+
+        assert_eq!(counters.files_uploaded, 1);
+        assert_eq!(counters.files_downloaded, 1);
+        assert_eq!(counters.files_deleted, 1);
+        assert_eq!(counters.files_conflict, 1);
+        assert_eq!(counters.errors_count, 1);
+        assert_eq!(counters.total_bytes, 150);
+    }
+
+    #[test]
+    fn test_counters_apply_to_session() {
+        let mut counters = SyncCounters::default();
+        counters.record_upload(1024);
+        counters.record_upload(1024);
+
+        let mut session = SyncSession {
+            id: Some(1),
+            sync_folder_id: 1,
+            status: "running".to_string(),
+            started_at: 0,
+            completed_at: None,
+            files_uploaded: 0,
+            files_downloaded: 0,
+            files_deleted: 0,
+            files_conflict: 0,
+            errors_count: 0,
+            total_bytes: 0,
+            error_message: None,
+        };
+        counters.apply_to(&mut session);
+
+        assert_eq!(session.files_uploaded, 2);
+        assert_eq!(session.total_bytes, 2048);
+    }
+
+    #[test]
+    fn test_progress_event_serialization() {
+        let mut event = SyncProgressEvent::new("folder-1", SyncPhase::Transferring);
+        event.current_file = Some("docs/a.txt".to_string());
+        event.counters.record_upload(10);
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"folderId\":\"folder-1\""));
+        assert!(json.contains("\"phase\":\"transferring\""));
+        assert!(json.contains("\"currentFile\":\"docs/a.txt\""));
+        assert!(json.contains("\"filesUploaded\":1"));
+        assert!(json.contains("\"totalBytes\":10"));
+    }
+
+    #[test]
+    fn test_error_event_serialization() {
+        let event = SyncErrorEvent {
+            folder_id: "folder-1".to_string(),
+            session_id: Some(3),
+            file_path: None,
+            error_code: ErrorCode::NetworkError,
+            message: "Network error".to_string(),
+            fatal: true,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"sessionId\":3"));
+        assert!(json.contains("\"errorCode\":\"NETWORK_ERROR\""));
+        assert!(json.contains("\"fatal\":true"));
+    }
+}
@@ -0,0 +1,335 @@
+/// 同步过滤规则
+///
+/// 合并默认忽略模式、文件夹的 `ignore_patterns`（glob）以及选择性同步排除的子文件夹，
+/// 本地扫描和远程列表都使用同一个过滤器，保证两个方向跳过的内容一致。
+///
+/// 所有路径均为相对于同步根目录、以 `/` 分隔且不带首尾 `/` 的形式，如 `photos/2019/a.jpg`
+///
+/// 开启 `ignore_system_files` 时还会忽略操作系统生成的元数据文件（`.DS_Store`、`Thumbs.db` 等），
+/// 以及 macOS 在不支持扩展属性的文件系统上为文件生成的 AppleDouble 附属文件（`._name`）。
+/// 后者需要结合同一目录下的其他条目判断，由同步计划按完整的本地和远程列表检测（见 `apple_double_files`）
+///
+/// 文件大小上限和扩展名过滤（`FileConstraints`）不从扫描结果中排除文件，
+/// 而是由同步计划把超出限制的上传、下载移入跳过列表，避免文件变大后被误判为已删除
+use std::collections::HashSet;
+
+use crate::config::SyncFolderConfig;
+use crate::constants::{DEFAULT_IGNORE_PATTERNS, SYSTEM_FILE_PATTERNS};
+
+/// AppleDouble 附属文件的文件名前缀
+const APPLE_DOUBLE_PREFIX: &str = "._";
+
+/// 同步过滤器
+#[derive(Debug, Clone, Default)]
+pub struct SyncFilter {
+    /// 忽略模式（匹配任一路径分段或完整相对路径）
+    ignore: Vec<glob::Pattern>,
+
+    /// 选择性同步排除的子文件夹
+    exclusions: Vec<String>,
+
+    /// 是否忽略 AppleDouble 附属文件
+    apple_double: bool,
+}
+
+impl SyncFilter {
+    /// 根据同步文件夹配置创建过滤器
+    ///
+    /// 无效的 glob 模式会被跳过并记录警告
+    pub fn from_folder(folder: &SyncFolderConfig) -> Self {
+        let system_files: &[&str] = if folder.ignore_system_files {
+            SYSTEM_FILE_PATTERNS
+        } else {
+            &[]
+        };
+        let patterns = DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .chain(system_files)
+            .map(|p| p.to_string())
+            .chain(folder.ignore_patterns.iter().cloned());
+
+        let mut filter = Self::new(patterns, folder.selective_exclusions.iter().cloned());
+        filter.apple_double = folder.ignore_system_files;
+        filter
+    }
+
+    /// 使用指定的忽略模式和排除子文件夹创建过滤器
+    pub fn new(
+        patterns: impl IntoIterator<Item = String>,
+        exclusions: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let ignore = patterns
+            .into_iter()
+            .filter_map(|p| match glob::Pattern::new(&p) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    tracing::warn!(pattern = %p, error = %e, "忽略无效的 glob 模式");
+                    None
+                }
+            })
+            .collect();
+
+        let exclusions = exclusions
+            .into_iter()
+            .map(|e| e.trim_matches('/').to_string())
+            .filter(|e| !e.is_empty())
+            .collect();
+
+        Self {
+            ignore,
+            exclusions,
+            apple_double: false,
+        }
+    }
+
+    /// 判断相对路径是否被排除
+    ///
+    /// 路径本身或其任一上级目录被排除时返回 true
+    pub fn is_excluded(&self, rel_path: &str) -> bool {
+        let rel_path = rel_path.trim_matches('/');
+        if rel_path.is_empty() {
+            return false;
+        }
+
+        if self.is_selectively_excluded(rel_path) {
+            return true;
+        }
+
+        rel_path
+            .split('/')
+            .any(|segment| self.ignore.iter().any(|p| p.matches(segment)))
+            || self.ignore.iter().any(|p| p.matches(rel_path))
+    }
+
+    /// 找出需要忽略的 AppleDouble 附属文件
+    ///
+    /// `._name` 且同一目录下存在 `name` 时视为 `name` 的附属文件，
+    /// 单独存在的 `._name` 可能是用户自己的文件，照常同步
+    ///
+    /// # 参数
+    /// - paths: 文件夹的所有相对路径（本地、远程和快照的并集）
+    ///
+    /// # 返回
+    /// 附属文件的相对路径（未开启 `ignore_system_files` 时为空）
+    pub fn apple_double_files<'a>(
+        &self,
+        paths: impl IntoIterator<Item = &'a str>,
+    ) -> HashSet<String> {
+        if !self.apple_double {
+            return HashSet::new();
+        }
+
+        let paths: HashSet<&str> = paths.into_iter().collect();
+        paths
+            .iter()
+            .filter(|path| {
+                let (dir, name) = match path.rsplit_once('/') {
+                    Some((dir, name)) => (Some(dir), name),
+                    None => (None, **path),
+                };
+                let Some(companion) = name
+                    .strip_prefix(APPLE_DOUBLE_PREFIX)
+                    .filter(|rest| !rest.is_empty())
+                else {
+                    return false;
+                };
+                let companion = match dir {
+                    Some(dir) => format!("{}/{}", dir, companion),
+                    None => companion.to_string(),
+                };
+                paths.contains(companion.as_str())
+            })
+            .map(|path| path.to_string())
+            .collect()
+    }
+
+    /// 判断相对路径是否位于选择性同步排除的子文件夹中
+    pub fn is_selectively_excluded(&self, rel_path: &str) -> bool {
+        let rel_path = rel_path.trim_matches('/');
+        self.exclusions.iter().any(|excluded| {
+            rel_path == excluded
+                || (rel_path.starts_with(excluded.as_str())
+                    && rel_path[excluded.len()..].starts_with('/'))
+        })
+    }
+}
+
+/// 同步文件夹的文件大小上限与扩展名过滤
+#[derive(Debug, Clone, Default)]
+pub struct FileConstraints {
+    /// 单个文件的大小上限（字节）
+    max_file_size: Option<u64>,
+    /// 只同步这些扩展名（小写、不含点，为空表示不限制）
+    include: Vec<String>,
+    /// 不同步这些扩展名（小写、不含点）
+    exclude: Vec<String>,
+}
+
+impl FileConstraints {
+    /// 根据同步文件夹配置创建
+    pub fn from_folder(folder: &SyncFolderConfig) -> Self {
+        let normalize = |extensions: &[String]| -> Vec<String> {
+            extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .collect()
+        };
+
+        Self {
+            max_file_size: folder.max_file_size,
+            include: normalize(&folder.include_extensions),
+            exclude: normalize(&folder.exclude_extensions),
+        }
+    }
+
+    /// 是否没有任何限制
+    pub fn is_unrestricted(&self) -> bool {
+        self.max_file_size.is_none() && self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// 检查文件是否超出限制
+    ///
+    /// # 参数
+    /// - rel_path: 文件的相对路径
+    /// - size: 文件大小（字节）
+    ///
+    /// # 返回
+    /// 超出限制时返回原因，否则返回 None
+    pub fn violation(&self, rel_path: &str, size: u64) -> Option<String> {
+        if let Some(max) = self.max_file_size.filter(|&max| size > max) {
+            return Some(format!(
+                "File size {} bytes exceeds limit of {} bytes",
+                size, max
+            ));
+        }
+
+        let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        let ext = name
+            .rsplit_once('.')
+            .filter(|(stem, _)| !stem.is_empty())
+            .map(|(_, ext)| ext.to_lowercase())
+            .unwrap_or_default();
+        if !self.include.is_empty() && !self.include.contains(&ext) {
+            return Some(format!("Extension '{}' is not in the include list", ext));
+        }
+        if self.exclude.contains(&ext) {
+            return Some(format!("Extension '{}' is excluded", ext));
+        }
+
+        None
+    }
+}
+
+/// 将远程路径转换为相对于同步文件夹远程根目录的路径
+///
+/// # 参数
+/// - remote_root: 同步文件夹的远程根路径（如 `/documents`）
+/// - path: 相对于服务器根路径的远程路径（如 `/documents/a/b.txt`）
+///
+/// # 返回
+/// - Some(String): 相对路径（如 `a/b.txt`，根目录本身为空字符串）
+/// - None: 路径不在远程根目录下
+pub fn folder_relative_path(remote_root: &str, path: &str) -> Option<String> {
+    let root = remote_root.trim_matches('/');
+    let path = path.trim_matches('/');
+
+    if root.is_empty() {
+        return Some(path.to_string());
+    }
+    if path == root {
+        return Some(String::new());
+    }
+
+    path.strip_prefix(root)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .map(|rest| rest.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> SyncFilter {
+        SyncFilter::new(
+            vec!["*.tmp".to_string(), ".git".to_string()],
+            vec!["/photos/2019/".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_selective_exclusion_covers_subtree() {
+        let filter = filter();
+        assert!(filter.is_excluded("photos/2019"));
+        assert!(filter.is_excluded("photos/2019/a.jpg"));
+        assert!(!filter.is_excluded("photos/2019-old/a.jpg"));
+        assert!(!filter.is_excluded("photos/2020/a.jpg"));
+        assert!(!filter.is_excluded(""));
+    }
+
+    #[test]
+    fn test_file_constraints() {
+        let constraints = FileConstraints {
+            max_file_size: Some(100),
+            include: vec![],
+            exclude: vec!["iso".to_string()],
+        };
+        assert!(constraints.violation("docs/a.txt", 100).is_none());
+        assert!(constraints.violation("docs/a.txt", 101).is_some());
+        assert!(constraints.violation("images/disk.ISO", 1).is_some());
+        assert!(constraints.violation("Makefile", 1).is_none());
+
+        let constraints = FileConstraints {
+            include: vec!["md".to_string()],
+            ..Default::default()
+        };
+        assert!(constraints.violation("notes/todo.md", 1).is_none());
+        assert!(constraints.violation("photo.jpg", 1).is_some());
+        // 没有扩展名的文件（包括 .bashrc 这类点文件）不在包含列表中
+        assert!(constraints.violation(".bashrc", 1).is_some());
+    }
+
+    #[test]
+    fn test_ignore_patterns_match_any_segment() {
+        let filter = filter();
+        assert!(filter.is_excluded("docs/a.tmp"));
+        assert!(filter.is_excluded("project/.git/config"));
+        assert!(!filter.is_excluded("docs/a.txt"));
+    }
+
+    #[test]
+    fn test_apple_double_files() {
+        let paths = [
+            "photo.jpg",
+            "._photo.jpg",
+            "docs",
+            "._docs",
+            "docs/a.txt",
+            "docs/._a.txt",
+            "docs/._b.txt",
+            "._",
+        ];
+
+        let mut filter = filter();
+        assert!(filter.apple_double_files(paths).is_empty());
+
+        filter.apple_double = true;
+        let mut found: Vec<String> = filter.apple_double_files(paths).into_iter().collect();
+        found.sort();
+        // 没有对应文件的 ._b.txt 照常同步
+        assert_eq!(found, vec!["._docs", "._photo.jpg", "docs/._a.txt"]);
+    }
+
+    #[test]
+    fn test_folder_relative_path() {
+        assert_eq!(
+            folder_relative_path("/documents", "/documents/a/b.txt").as_deref(),
+            Some("a/b.txt")
+        );
+        assert_eq!(
+            folder_relative_path("/documents/", "/documents/").as_deref(),
+            Some("")
+        );
+        assert_eq!(folder_relative_path("/documents", "/documents2/a"), None);
+        assert_eq!(folder_relative_path("/", "/a").as_deref(), Some("a"));
+    }
+}
@@ -0,0 +1,456 @@
+/// 同步文件夹校验
+///
+/// 添加或修改同步文件夹前的本地校验逻辑：
+/// - 本地路径必须存在、是目录且可写
+/// - 不允许与已有同步文件夹重叠或互相嵌套
+/// - 同步方向、冲突策略、压缩算法等枚举值必须合法
+///
+/// 同步前确认本地根目录可用（存在且带有 `.lightsync` 标记文件），
+/// 以及启动时清理上次异常退出遗留的下载临时文件
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::{
+    conflict_resolution, first_sync_strategy, symlink_policy, sync_direction,
+    PARTIAL_DOWNLOAD_SUFFIX, SYNC_ROOT_MARKER,
+};
+use crate::sync::{compression, policy};
+use crate::{Result, SyncError};
+
+/// 校验本地路径
+///
+/// # 参数
+/// - path: 本地路径
+///
+/// # 返回
+/// - Ok(PathBuf): 规范化后的绝对路径
+/// - Err(SyncError::ConfigError): 路径不存在、不是目录或不可写
+pub fn validate_local_path(path: &Path) -> Result<PathBuf> {
+    if path.as_os_str().is_empty() {
+        return Err(SyncError::ConfigError(
+            "Local path cannot be empty".to_string(),
+        ));
+    }
+
+    if !path.exists() {
+        return Err(SyncError::ConfigError(format!(
+            "Local path does not exist: {}",
+            path.display()
+        )));
+    }
+
+    if !path.is_dir() {
+        return Err(SyncError::ConfigError(format!(
+            "Local path is not a directory: {}",
+            path.display()
+        )));
+    }
+
+    // 通过创建临时文件检查写权限（只读属性无法反映 ACL 等权限）
+    let probe = path.join(format!(".lightsync-write-test-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&probe, b"").map_err(|e| {
+        SyncError::ConfigError(format!(
+            "Local path is not writable: {} ({})",
+            path.display(),
+            e
+        ))
+    })?;
+    let _ = std::fs::remove_file(&probe);
+
+    path.canonicalize().map_err(|e| {
+        SyncError::ConfigError(format!(
+            "Failed to resolve local path {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// 查找与候选路径重叠的已有同步文件夹
+///
+/// 两个路径相同、或一个是另一个的子目录都视为重叠
+///
+/// # 参数
+/// - folders: 已有同步文件夹
+/// - candidate: 候选本地路径（应为规范化路径）
+/// - exclude_id: 需要排除的文件夹 ID（更新时排除自身）
+pub fn find_overlap<'a>(
+    folders: &'a [SyncFolderConfig],
+    candidate: &Path,
+    exclude_id: Option<&str>,
+) -> Option<&'a SyncFolderConfig> {
+    folders
+        .iter()
+        .filter(|folder| Some(folder.id.as_str()) != exclude_id)
+        .find(|folder| {
+            // 已有文件夹可能已被移除，无法规范化时按原路径比较
+            let existing = folder
+                .local_path
+                .canonicalize()
+                .unwrap_or_else(|_| folder.local_path.clone());
+            existing.starts_with(candidate) || candidate.starts_with(&existing)
+        })
+}
+
+/// 校验同步文件夹的基础字段
+///
+/// # 返回
+/// - Ok(()): 所有字段有效
+/// - Err(SyncError::ConfigError): 任一字段无效
+pub fn validate_options(folder: &SyncFolderConfig) -> Result<()> {
+    if folder.name.trim().is_empty() {
+        return Err(SyncError::ConfigError(
+            "Sync folder name cannot be empty".to_string(),
+        ));
+    }
+
+    if !folder.remote_path.starts_with('/') {
+        return Err(SyncError::ConfigError(format!(
+            "Remote path must start with '/', got: {}",
+            folder.remote_path
+        )));
+    }
+
+    let directions = [
+        sync_direction::BIDIRECTIONAL,
+        sync_direction::UPLOAD_ONLY,
+        sync_direction::DOWNLOAD_ONLY,
+    ];
+    if !directions.contains(&folder.sync_direction.as_str()) {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid sync direction: {}",
+            folder.sync_direction
+        )));
+    }
+
+    let resolutions = [
+        conflict_resolution::ASK,
+        conflict_resolution::LOCAL_WINS,
+        conflict_resolution::REMOTE_WINS,
+        conflict_resolution::NEWER_WINS,
+    ];
+    if !resolutions.contains(&folder.conflict_resolution.as_str()) {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid conflict resolution: {}",
+            folder.conflict_resolution
+        )));
+    }
+
+    let symlink_policies = [
+        symlink_policy::SKIP,
+        symlink_policy::FOLLOW,
+        symlink_policy::PLACEHOLDER,
+    ];
+    if !symlink_policies.contains(&folder.symlink_policy.as_str()) {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid symlink policy: {}",
+            folder.symlink_policy
+        )));
+    }
+
+    let first_sync_strategies = [
+        first_sync_strategy::MERGE_KEEP_BOTH,
+        first_sync_strategy::PREFER_LOCAL,
+        first_sync_strategy::PREFER_REMOTE,
+        first_sync_strategy::MIRROR,
+    ];
+    if !first_sync_strategies.contains(&folder.first_sync_strategy.as_str()) {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid first sync strategy: {}",
+            folder.first_sync_strategy
+        )));
+    }
+
+    compression::validate(&folder.compression)?;
+    if let Some(sync_policy) = &folder.sync_policy {
+        policy::validate(sync_policy)?;
+    }
+
+    if folder.max_file_size == Some(0) {
+        return Err(SyncError::ConfigError(
+            "Max file size must be greater than 0".to_string(),
+        ));
+    }
+    if let Some(ext) = folder
+        .include_extensions
+        .iter()
+        .chain(&folder.exclude_extensions)
+        .find(|ext| {
+            let ext = ext.trim_start_matches('.');
+            ext.is_empty() || ext.contains(['/', '\\', '.'])
+        })
+    {
+        return Err(SyncError::ConfigError(format!(
+            "Invalid file extension: {}",
+            ext
+        )));
+    }
+
+    if !(0.0..=1.0).contains(&folder.max_delete_ratio) {
+        return Err(SyncError::ConfigError(format!(
+            "Max delete ratio must be between 0 and 1, got: {}",
+            folder.max_delete_ratio
+        )));
+    }
+
+    if folder.sync_interval == 0 {
+        return Err(SyncError::ConfigError(
+            "Sync interval must be at least 1 minute".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 在同步文件夹本地根目录写入标记文件
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - folder_id: 同步文件夹 ID（写入标记文件内容）
+pub fn write_root_marker(root: &Path, folder_id: &str) -> Result<()> {
+    std::fs::write(root.join(SYNC_ROOT_MARKER), folder_id)?;
+    Ok(())
+}
+
+/// 确认同步文件夹本地根目录可用
+///
+/// 可移动磁盘断开或网络驱动器未挂载时，根目录不存在或变成空的挂载点，
+/// 此时所有文件都会被误判为本地已删除。缺少标记文件时：
+/// - 尚未同步过或根目录非空（升级前添加的文件夹）：补写标记文件
+/// - 已同步过且根目录为空：视为根目录不可用
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - folder_id: 同步文件夹 ID
+/// - has_synced: 快照中是否已有同步过的文件
+///
+/// # 返回
+/// - Ok(()): 根目录可用
+/// - Err(SyncError::SyncRootUnavailable): 根目录不存在、不是目录，或缺少标记文件且为空
+pub fn check_sync_root(root: &Path, folder_id: &str, has_synced: bool) -> Result<()> {
+    if !root.is_dir() {
+        return Err(SyncError::SyncRootUnavailable(format!(
+            "Local folder is missing or not a directory (is the drive connected?): {}",
+            root.display()
+        )));
+    }
+
+    if root.join(SYNC_ROOT_MARKER).is_file() {
+        return Ok(());
+    }
+
+    let is_empty = std::fs::read_dir(root)?.next().is_none();
+    if has_synced && is_empty {
+        return Err(SyncError::SyncRootUnavailable(format!(
+            "Local folder is empty and its {} marker is missing (is the drive mounted?): {}",
+            SYNC_ROOT_MARKER,
+            root.display()
+        )));
+    }
+
+    write_root_marker(root, folder_id)
+}
+
+/// 删除同步文件夹中遗留的下载临时文件（`*.lightsync-part`）
+///
+/// 下载过程中进程退出会留下临时文件，目标文件本身不受影响。不跟随符号链接。
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - keep: 需要保留的临时文件（排队中、可以断点续传的下载）
+///
+/// # 返回
+/// 删除的临时文件数量
+pub fn remove_partial_downloads(root: &Path, keep: &HashSet<PathBuf>) -> Result<usize> {
+    let mut removed = 0;
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file()
+                && entry
+                    .file_name()
+                    .to_string_lossy()
+                    .ends_with(PARTIAL_DOWNLOAD_SUFFIX)
+                && !keep.contains(&entry.path())
+            {
+                std::fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn create_test_dir() -> PathBuf {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        test_dir.canonicalize().unwrap()
+    }
+
+    fn create_folder(id: &str, local_path: PathBuf) -> SyncFolderConfig {
+        SyncFolderConfig {
+            id: id.to_string(),
+            name: "Test".to_string(),
+            local_path,
+            remote_path: "/remote".to_string(),
+            server_id: "server-1".to_string(),
+            sync_direction: "bidirectional".to_string(),
+            sync_interval: 30,
+            auto_sync: true,
+            ignore_patterns: vec![],
+            conflict_resolution: "newer-wins".to_string(),
+            selective_exclusions: vec![],
+            symlink_policy: "skip".to_string(),
+            encryption: Default::default(),
+            compression: Default::default(),
+            max_delete_ratio: 0.2,
+            first_sync_strategy: "merge-keep-both".to_string(),
+            max_file_size: None,
+            include_extensions: vec![],
+            exclude_extensions: vec![],
+            ignore_system_files: true,
+            sync_policy: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_local_path() {
+        let test_dir = create_test_dir();
+
+        assert!(validate_local_path(&test_dir).is_ok());
+        assert!(validate_local_path(&test_dir.join("missing")).is_err());
+
+        let file = test_dir.join("file.txt");
+        fs::write(&file, "x").unwrap();
+        assert!(validate_local_path(&file).is_err());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_find_overlap() {
+        let test_dir = create_test_dir();
+        let folders = vec![create_folder("a", test_dir.join("docs"))];
+
+        // 相同路径
+        assert!(find_overlap(&folders, &test_dir.join("docs"), None).is_some());
+        // 嵌套在已有文件夹内
+        assert!(find_overlap(&folders, &test_dir.join("docs").join("sub"), None).is_some());
+        // 包含已有文件夹
+        assert!(find_overlap(&folders, &test_dir, None).is_some());
+        // 同级目录（前缀相同但不是子目录）
+        assert!(find_overlap(&folders, &test_dir.join("docs2"), None).is_none());
+        // 排除自身
+        assert!(find_overlap(&folders, &test_dir.join("docs"), Some("a")).is_none());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_remove_partial_downloads() {
+        let test_dir = create_test_dir();
+        fs::create_dir_all(test_dir.join("docs")).unwrap();
+        fs::write(test_dir.join("a.txt"), "a").unwrap();
+        fs::write(test_dir.join(".a.txt.lightsync-part"), "partial").unwrap();
+        fs::write(test_dir.join("docs/.b.txt.lightsync-part"), "partial").unwrap();
+
+        fs::write(test_dir.join("docs/.c.txt.lightsync-part"), "partial").unwrap();
+
+        let keep = HashSet::from([test_dir.join("docs/.c.txt.lightsync-part")]);
+        assert_eq!(remove_partial_downloads(&test_dir, &keep).unwrap(), 2);
+        assert!(test_dir.join("a.txt").exists());
+        assert!(!test_dir.join(".a.txt.lightsync-part").exists());
+        assert!(!test_dir.join("docs/.b.txt.lightsync-part").exists());
+        assert!(test_dir.join("docs/.c.txt.lightsync-part").exists());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let folder = create_folder("a", PathBuf::from("/tmp"));
+        assert!(validate_options(&folder).is_ok());
+
+        let mut invalid = folder.clone();
+        invalid.sync_direction = "sideways".to_string();
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.conflict_resolution = "random".to_string();
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.symlink_policy = "always".to_string();
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.compression.algorithm = "brotli".to_string();
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.first_sync_strategy = "overwrite".to_string();
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.max_file_size = Some(0);
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.exclude_extensions = vec!["tar.gz".to_string()];
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.max_delete_ratio = 1.5;
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder.clone();
+        invalid.remote_path = "relative".to_string();
+        assert!(validate_options(&invalid).is_err());
+
+        let mut invalid = folder;
+        invalid.sync_interval = 0;
+        assert!(validate_options(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_check_sync_root() {
+        let test_dir = create_test_dir();
+        let missing = test_dir.join("unplugged");
+
+        assert!(matches!(
+            check_sync_root(&missing, "folder-1", true),
+            Err(SyncError::SyncRootUnavailable(_))
+        ));
+
+        // 已同步过的空目录缺少标记文件（未挂载的挂载点）
+        let mount = test_dir.join("mount");
+        fs::create_dir_all(&mount).unwrap();
+        assert!(matches!(
+            check_sync_root(&mount, "folder-1", true),
+            Err(SyncError::SyncRootUnavailable(_))
+        ));
+
+        // 尚未同步过时补写标记文件
+        check_sync_root(&mount, "folder-1", false).unwrap();
+        assert_eq!(
+            fs::read_to_string(mount.join(SYNC_ROOT_MARKER)).unwrap(),
+            "folder-1"
+        );
+        check_sync_root(&mount, "folder-1", true).unwrap();
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+}
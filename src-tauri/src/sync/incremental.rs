@@ -0,0 +1,454 @@
+/// 增量扫描
+///
+/// 只有注册了文件监控器的文件夹（`FileWatcherManager` 启动监控时调用 `ScanCache::set_watched`）才会增量扫描，
+/// 其余文件夹以及手动同步（`ScanCache::request_full_scan`）总是完整扫描。
+///
+/// 监控中的文件夹首次同步（及距上次完整扫描超过 `LOCAL_FULL_SCAN_INTERVAL_SECS`）时完整扫描本地目录，
+/// 之后的同步只重新读取修改时间变化的目录和脏路径所在的目录（见 `scanner::scan_changed`），
+/// 其余文件沿用上次扫描的结果，大型文件夹不再每次读取所有文件的元数据。
+///
+/// 目录的修改时间不反映其中文件内容的变化，因此以下路径标记为脏：
+/// - 文件监控器报告的变更（`ScanCache::mark_event`，按批处理时见 `ScanCache::apply_batch`，
+///   积压过多的批次改为下次完整扫描）
+/// - 本次同步传输过或推迟上传的文件（下次同步时重新读取，快照与扫描结果保持一致）
+///
+/// 远程自上次同步后有变化（新增、修改或删除）的文件在对比前重新读取本地状态
+/// （`refresh_entries`），避免沿用的旧结果掩盖本地修改，把冲突误判为下载或删除。
+/// 其余只在本地原地修改的文件在监控器报告或下次完整扫描时发现。
+///
+/// 忽略规则、排除的子文件夹或符号链接策略变化时重新完整扫描；跟随符号链接时总是完整扫描
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::constants::LOCAL_FULL_SCAN_INTERVAL_SECS;
+use crate::file_watcher::{EventBatch, FileEvent};
+use crate::sync::filter::SyncFilter;
+use crate::sync::paths::PathNormalizer;
+use crate::sync::scanner::{self, scan_changed, scan_local, LocalEntry};
+use crate::sync::symlinks::SymlinkPolicy;
+use crate::{Result, SyncError};
+
+/// 文件夹上次扫描的结果
+#[derive(Debug)]
+struct FolderScan {
+    /// 扫描参数（根目录、过滤器、符号链接策略），变化时不能沿用
+    key: String,
+    /// 扫描开始的时间（Unix 时间戳，秒）
+    started_at: i64,
+    /// 上次完整扫描开始的时间（Unix 时间戳，秒）
+    full_scan_at: i64,
+    /// 扫描开始时根目录的修改时间
+    root_modified: Option<i64>,
+    /// 扫描到的条目
+    entries: Vec<LocalEntry>,
+}
+
+/// 本地扫描结果
+#[derive(Debug)]
+pub struct LocalScan {
+    /// 所有未被排除的文件和目录（按相对路径排序）
+    pub entries: Vec<LocalEntry>,
+    /// 是否为增量扫描（部分条目沿用上次扫描的结果）
+    pub incremental: bool,
+}
+
+/// 各文件夹上次扫描的结果与待重新读取的脏路径
+///
+/// 作为 Tauri State 管理，只保存在内存中，应用启动后的首次同步总是完整扫描
+#[derive(Debug, Default)]
+pub struct ScanCache {
+    /// 文件夹 ID -> 上次扫描的结果
+    scans: Mutex<HashMap<String, Arc<FolderScan>>>,
+    /// 文件夹 ID -> 脏路径（本地相对路径）
+    dirty: Mutex<HashMap<String, HashSet<String>>>,
+    /// 注册了文件监控器的文件夹 ID
+    watched: Mutex<HashSet<String>>,
+}
+
+impl ScanCache {
+    /// 标记本地路径需要在下次扫描时重新读取
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - rel_path: 本地相对路径（`/` 分隔）
+    pub fn mark_dirty(&self, folder_id: &str, rel_path: &str) {
+        self.dirty
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(folder_id.to_string())
+            .or_default()
+            .insert(rel_path.to_string());
+    }
+
+    /// 根据文件监控事件标记脏路径（重命名事件同时标记旧路径）
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - root: 同步文件夹本地根目录
+    /// - event: 文件监控事件
+    pub fn mark_event(&self, folder_id: &str, root: &Path, event: &FileEvent) {
+        for path in std::iter::once(&event.path).chain(&event.old_path) {
+            if let Some(rel_path) = relative_path(root, path) {
+                self.mark_dirty(folder_id, &rel_path);
+            }
+        }
+    }
+
+    /// 处理一批文件监控事件
+    ///
+    /// 批次因积压过多丢弃过事件时，丢弃上次扫描的结果，下次同步完整扫描
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - root: 同步文件夹本地根目录
+    /// - batch: 从 `EventBatcher` 取出的事件
+    pub fn apply_batch(&self, folder_id: &str, root: &Path, batch: &EventBatch) {
+        if batch.rescan {
+            self.invalidate(folder_id);
+            return;
+        }
+        for event in &batch.events {
+            self.mark_event(folder_id, root, event);
+        }
+    }
+
+    /// 注册或注销文件夹的文件监控器
+    ///
+    /// 没有监控器期间无法得知原地修改的文件，注册和注销时都丢弃上次扫描的结果，
+    /// 注册后的首次同步完整扫描，注销后总是完整扫描
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - watched: 监控器是否在运行
+    pub fn set_watched(&self, folder_id: &str, watched: bool) {
+        let mut folders = self.watched.lock().unwrap_or_else(|e| e.into_inner());
+        if watched {
+            folders.insert(folder_id.to_string());
+        } else {
+            folders.remove(folder_id);
+        }
+        drop(folders);
+        self.invalidate(folder_id);
+    }
+
+    /// 下次扫描改为完整扫描（手动同步前调用）
+    pub fn request_full_scan(&self, folder_id: &str) {
+        self.scans
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(folder_id);
+    }
+
+    /// 丢弃文件夹的扫描结果与脏路径（删除同步文件夹时调用）
+    pub fn invalidate(&self, folder_id: &str) {
+        self.scans
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(folder_id);
+        self.dirty
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(folder_id);
+    }
+
+    /// 取出文件夹的脏路径
+    fn take_dirty(&self, folder_id: &str) -> HashSet<String> {
+        self.dirty
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(folder_id)
+            .unwrap_or_default()
+    }
+
+    /// 扫描文件夹的本地目录
+    ///
+    /// 文件夹注册了监控器且有可沿用的上次结果时增量扫描，否则完整扫描。
+    /// 扫描失败时脏路径放回，下次扫描仍会重新读取
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - root: 同步文件夹本地根目录
+    /// - filter: 同步过滤器
+    /// - policy: 符号链接处理策略
+    pub async fn scan(
+        &self,
+        folder_id: &str,
+        root: &Path,
+        filter: &SyncFilter,
+        policy: SymlinkPolicy,
+    ) -> Result<LocalScan> {
+        let key = format!("{}|{:?}|{:?}", root.display(), filter, policy);
+        let started_at = chrono::Utc::now().timestamp();
+        let watched = self
+            .watched
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(folder_id);
+        let previous = self
+            .scans
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(folder_id)
+            .filter(|scan| {
+                watched
+                    && scan.key == key
+                    && policy != SymlinkPolicy::Follow
+                    && started_at - scan.full_scan_at < LOCAL_FULL_SCAN_INTERVAL_SECS
+            })
+            .cloned();
+        let incremental = previous.is_some();
+        let dirty = self.take_dirty(folder_id);
+
+        let root = root.to_path_buf();
+        let filter = filter.clone();
+        let dirty_paths = dirty.clone();
+        let result = tokio::task::spawn_blocking(move || -> Result<FolderScan> {
+            let root_modified = scanner::modified_secs(&std::fs::metadata(&root)?);
+            let (entries, full_scan_at) = match previous {
+                Some(previous) => {
+                    let dirty_dirs = dirty_dirs(&dirty_paths);
+                    let entries = scan_changed(
+                        &root,
+                        &filter,
+                        policy,
+                        &previous.entries,
+                        previous.root_modified,
+                        previous.started_at,
+                        &dirty_dirs,
+                    )?;
+                    (entries, previous.full_scan_at)
+                }
+                None => (scan_local(&root, &filter, policy)?, started_at),
+            };
+            Ok(FolderScan {
+                key,
+                started_at,
+                full_scan_at,
+                root_modified,
+                entries,
+            })
+        })
+        .await
+        .map_err(|e| SyncError::Unknown(format!("Local scan task failed: {}", e)))
+        .and_then(|result| result);
+
+        let scan = match result {
+            Ok(scan) => scan,
+            Err(e) => {
+                for rel_path in dirty {
+                    self.mark_dirty(folder_id, &rel_path);
+                }
+                return Err(e);
+            }
+        };
+        let entries = scan.entries.clone();
+        self.scans
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(folder_id.to_string(), Arc::new(scan));
+        Ok(LocalScan {
+            entries,
+            incremental,
+        })
+    }
+}
+
+/// 脏路径对应需要重新读取的目录（路径本身及其父目录）
+fn dirty_dirs(paths: &HashSet<String>) -> HashSet<String> {
+    paths
+        .iter()
+        .flat_map(|path| [path.clone(), scanner::parent_dir(path).to_string()])
+        .collect()
+}
+
+/// 将绝对路径转换为相对于同步根目录的路径（`/` 分隔），不在根目录下时返回 None
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let segments: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some(segments.join("/"))
+}
+
+/// 重新读取沿用的扫描结果中指定文件的本地状态
+///
+/// 文件已不存在或为符号链接时保持原样
+///
+/// # 参数
+/// - local: 本地扫描结果（路径已规范化为远程路径）
+/// - root: 同步文件夹本地根目录
+/// - normalizer: 本地路径规范化器
+/// - paths: 需要重新读取的路径
+pub async fn refresh_entries(
+    local: &mut [LocalEntry],
+    root: &Path,
+    normalizer: &PathNormalizer,
+    paths: &HashSet<String>,
+) {
+    for entry in local
+        .iter_mut()
+        .filter(|e| !e.is_directory && paths.contains(&e.rel_path))
+    {
+        let Ok(path) = normalizer.local_path(root, &entry.rel_path) else {
+            continue;
+        };
+        let Ok(metadata) = tokio::fs::symlink_metadata(path).await else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        entry.size = metadata.len();
+        entry.modified = scanner::modified_secs(&metadata);
+        entry.inode = scanner::inode(&metadata);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_watcher::FileEventType;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_mark_event() {
+        let cache = ScanCache::default();
+        let root = Path::new("/data/sync");
+        cache.mark_event(
+            "folder-1",
+            root,
+            &FileEvent::new(FileEventType::Modify, root.join("docs/a.txt")),
+        );
+        cache.mark_event(
+            "folder-1",
+            root,
+            &FileEvent::new_rename(root.join("b.txt"), root.join("docs/b.txt")),
+        );
+        cache.mark_event(
+            "folder-1",
+            root,
+            &FileEvent::new(FileEventType::Create, PathBuf::from("/elsewhere/c.txt")),
+        );
+
+        let dirty = cache.take_dirty("folder-1");
+        assert_eq!(
+            dirty,
+            HashSet::from([
+                "docs/a.txt".to_string(),
+                "docs/b.txt".to_string(),
+                "b.txt".to_string()
+            ])
+        );
+        assert_eq!(
+            dirty_dirs(&dirty),
+            HashSet::from([
+                "docs/a.txt".to_string(),
+                "docs/b.txt".to_string(),
+                "b.txt".to_string(),
+                "docs".to_string(),
+                String::new()
+            ])
+        );
+        assert!(cache.take_dirty("folder-1").is_empty());
+
+        let batch = EventBatch {
+            events: vec![FileEvent::new(FileEventType::Delete, root.join("c.txt"))],
+            rescan: false,
+        };
+        cache.apply_batch("folder-1", root, &batch);
+        assert_eq!(
+            cache.take_dirty("folder-1"),
+            HashSet::from(["c.txt".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_reuses_previous_result() {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/a.txt"), "a").unwrap();
+        let cache = ScanCache::default();
+        cache.set_watched("folder-1", true);
+        let filter = SyncFilter::default();
+
+        let first = cache
+            .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+            .await
+            .unwrap();
+        assert!(!first.incremental);
+
+        // 标记为脏的文件所在目录重新读取
+        std::fs::write(root.join("docs/a.txt"), "aaa").unwrap();
+        cache.mark_dirty("folder-1", "docs/a.txt");
+        let second = cache
+            .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+            .await
+            .unwrap();
+        assert!(second.incremental);
+        assert_eq!(second.entries[1].size, 3);
+
+        // 过滤器变化时重新完整扫描
+        let filter = SyncFilter::new(vec!["*.tmp".to_string()], Vec::new());
+        let third = cache
+            .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+            .await
+            .unwrap();
+        assert!(!third.incremental);
+
+        // 手动同步前要求完整扫描
+        cache.request_full_scan("folder-1");
+        let fourth = cache
+            .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+            .await
+            .unwrap();
+        assert!(!fourth.incremental);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_scan_without_watcher_is_always_full() {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.txt"), "a").unwrap();
+        let cache = ScanCache::default();
+        let filter = SyncFilter::default();
+
+        for _ in 0..2 {
+            let scan = cache
+                .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+                .await
+                .unwrap();
+            assert!(!scan.incremental);
+        }
+
+        // 注册监控器后首次扫描仍完整扫描，之后增量扫描；注销后恢复完整扫描
+        cache.set_watched("folder-1", true);
+        assert!(
+            !cache
+                .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+                .await
+                .unwrap()
+                .incremental
+        );
+        assert!(
+            cache
+                .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+                .await
+                .unwrap()
+                .incremental
+        );
+        cache.set_watched("folder-1", false);
+        assert!(
+            !cache
+                .scan("folder-1", &root, &filter, SymlinkPolicy::Skip)
+                .await
+                .unwrap()
+                .incremental
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
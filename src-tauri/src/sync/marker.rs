@@ -0,0 +1,141 @@
+/// 远程同步标记
+///
+/// 同步成功完成后，通过 PROPPATCH 在同步文件夹的远程根目录上写入 LightSync 命名空间下的自定义属性：
+/// - `managed-by`: 应用名称，表示该目录由 LightSync 管理
+/// - `client-id`: 最近一次完成同步的客户端标识（每个安装随机生成，保存在应用数据目录）
+/// - `last-sync`: 最近一次完成同步的时间（Unix 时间戳，秒）
+///
+/// 其他客户端可以据此判断目录是否正由其他设备同步。
+/// 服务器不支持或拒绝自定义属性时只记录日志，不影响同步结果
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::constants::{remote_marker, APP_NAME, CLIENT_ID_FILE};
+use crate::webdav::client::{PropertyName, PropertyUpdate, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 客户端标识
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientId(String);
+
+impl ClientId {
+    /// 读取客户端标识，文件不存在或内容为空时生成新的标识并保存
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Ok(id) = std::fs::read_to_string(&path) {
+            let id = id.trim();
+            if !id.is_empty() {
+                return Ok(Self(id.to_string()));
+            }
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        write_id(&path, &id)?;
+        Ok(Self(id))
+    }
+
+    /// 在应用数据目录中读取或生成客户端标识
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        Self::open(app_dir.join(CLIENT_ID_FILE))
+    }
+
+    /// 标识字符串
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// 保存客户端标识
+fn write_id(path: &Path, id: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, id)?;
+    Ok(())
+}
+
+/// 同步标记的属性修改
+///
+/// # 参数
+/// - client_id: 完成同步的客户端标识
+/// - now: 同步完成时间（Unix 时间戳，秒）
+pub fn marker_updates(client_id: &ClientId, now: i64) -> Vec<PropertyUpdate> {
+    let property = |name: &str| PropertyName::new(remote_marker::NAMESPACE, name);
+    vec![
+        PropertyUpdate::Set(property(remote_marker::MANAGED_BY), APP_NAME.to_string()),
+        PropertyUpdate::Set(
+            property(remote_marker::CLIENT_ID),
+            client_id.as_str().to_string(),
+        ),
+        PropertyUpdate::Set(property(remote_marker::LAST_SYNC), now.to_string()),
+    ]
+}
+
+/// 在远程根目录上写入同步标记
+///
+/// # 返回
+/// 标记是否已写入（服务器不支持、拒绝或请求失败时返回 false）
+pub async fn tag_remote_root(
+    client: &WebDavClient,
+    remote_root: &str,
+    client_id: &ClientId,
+) -> bool {
+    let updates = marker_updates(client_id, chrono::Utc::now().timestamp());
+    match client.proppatch(remote_root, &updates).await {
+        Ok(true) => true,
+        Ok(false) => {
+            tracing::debug!(remote_root, "服务器不支持自定义属性，跳过同步标记");
+            false
+        }
+        Err(e) => {
+            tracing::debug!(remote_root, error = %e, "写入同步标记失败");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_id_is_persisted() {
+        let dir =
+            std::env::temp_dir().join(format!("lightsync-client-id-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(CLIENT_ID_FILE);
+
+        let first = ClientId::open(&path).unwrap();
+        assert!(uuid::Uuid::parse_str(first.as_str()).is_ok());
+        assert_eq!(ClientId::open(&path).unwrap(), first);
+
+        std::fs::write(&path, "  \n").unwrap();
+        assert_ne!(ClientId::open(&path).unwrap(), first);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_marker_updates() {
+        let id = ClientId("client-1".to_string());
+        let updates = marker_updates(&id, 1_700_000_000);
+
+        assert_eq!(updates.len(), 3);
+        assert!(updates
+            .iter()
+            .all(|update| update.property().namespace == remote_marker::NAMESPACE));
+        assert!(updates.contains(&PropertyUpdate::Set(
+            PropertyName::new(remote_marker::NAMESPACE, remote_marker::CLIENT_ID),
+            "client-1".to_string()
+        )));
+        assert!(updates.contains(&PropertyUpdate::Set(
+            PropertyName::new(remote_marker::NAMESPACE, remote_marker::LAST_SYNC),
+            "1700000000".to_string()
+        )));
+    }
+}
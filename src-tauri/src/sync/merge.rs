@@ -0,0 +1,202 @@
+/// 有序归并
+///
+/// 对比阶段按路径同时遍历本地扫描结果、远程列表和快照三个有序序列（归并连接），
+/// 每次只取出三个序列中路径最小的当前条目，不再为整棵目录树建立路径索引（HashMap）
+/// 和全部路径的有序集合；按路径查找单个条目时在有序序列中二分查找。
+///
+/// 三个序列本身仍完整保存在内存中（本地扫描结果、远程列表和快照），
+/// 归并只省去了额外的索引，并没有把中间状态写入数据库。
+/// 序列在原处排序（`sort_by_path`），不另外建立引用数组。
+///
+/// 序列按路径的字节序排列（与 SQLite 的 `ORDER BY path` 一致），
+/// 同一序列中重复的路径保留最后一个（与按路径建立索引时的行为一致）
+use std::borrow::Cow;
+use std::iter::Peekable;
+
+use crate::database::FileMetadata;
+use crate::sync::planner::RemoteEntry;
+use crate::sync::scanner::LocalEntry;
+
+/// 可按路径归并的条目
+pub trait PathKeyed {
+    /// 相对于同步根目录的路径
+    fn path_key(&self) -> &str;
+}
+
+impl PathKeyed for LocalEntry {
+    fn path_key(&self) -> &str {
+        &self.rel_path
+    }
+}
+
+impl PathKeyed for RemoteEntry {
+    fn path_key(&self) -> &str {
+        &self.rel_path
+    }
+}
+
+impl PathKeyed for FileMetadata {
+    fn path_key(&self) -> &str {
+        &self.path
+    }
+}
+
+impl<T: PathKeyed + ?Sized> PathKeyed for &T {
+    fn path_key(&self) -> &str {
+        (**self).path_key()
+    }
+}
+
+/// 三路有序归并：按路径升序依次产出每个路径在三个序列中的条目
+pub struct MergeJoin<A: Iterator, B: Iterator, C: Iterator> {
+    a: Peekable<A>,
+    b: Peekable<B>,
+    c: Peekable<C>,
+}
+
+impl<A, B, C> MergeJoin<A, B, C>
+where
+    A: Iterator,
+    B: Iterator,
+    C: Iterator,
+{
+    /// 归并三个按路径升序排列的序列
+    pub fn new(
+        a: impl IntoIterator<IntoIter = A>,
+        b: impl IntoIterator<IntoIter = B>,
+        c: impl IntoIterator<IntoIter = C>,
+    ) -> Self {
+        Self {
+            a: a.into_iter().peekable(),
+            b: b.into_iter().peekable(),
+            c: c.into_iter().peekable(),
+        }
+    }
+}
+
+impl<A, B, C> Iterator for MergeJoin<A, B, C>
+where
+    A: Iterator,
+    B: Iterator,
+    C: Iterator,
+    A::Item: PathKeyed,
+    B::Item: PathKeyed,
+    C::Item: PathKeyed,
+{
+    type Item = (Option<A::Item>, Option<B::Item>, Option<C::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = [
+            self.a.peek().map(|item| item.path_key()),
+            self.b.peek().map(|item| item.path_key()),
+            self.c.peek().map(|item| item.path_key()),
+        ]
+        .into_iter()
+        .flatten()
+        .min()?
+        .to_string();
+
+        Some((
+            take_key(&mut self.a, &key),
+            take_key(&mut self.b, &key),
+            take_key(&mut self.c, &key),
+        ))
+    }
+}
+
+/// 取出序列开头路径等于 key 的条目（重复时保留最后一个）
+fn take_key<I>(iter: &mut Peekable<I>, key: &str) -> Option<I::Item>
+where
+    I: Iterator,
+    I::Item: PathKeyed,
+{
+    let mut item = iter.next_if(|item| item.path_key() == key)?;
+    while let Some(next) = iter.next_if(|item| item.path_key() == key) {
+        item = next;
+    }
+    Some(item)
+}
+
+/// 序列是否已按路径排序
+fn is_sorted_by_path<T: PathKeyed>(items: &[T]) -> bool {
+    items.is_sorted_by(|a, b| a.path_key() <= b.path_key())
+}
+
+/// 按路径原处排序（已有序时不重新排序，稳定排序保持重复路径的先后顺序）
+pub fn sort_by_path<T: PathKeyed>(items: &mut [T]) {
+    if !is_sorted_by_path(items) {
+        items.sort_by(|a, b| a.path_key().cmp(b.path_key()));
+    }
+}
+
+/// 按路径排序的序列：已有序时直接借用，否则复制一份排序（只在调用方未排序时发生）
+pub fn sorted<T: PathKeyed + Clone>(items: &[T]) -> Cow<'_, [T]> {
+    if is_sorted_by_path(items) {
+        return Cow::Borrowed(items);
+    }
+    let mut owned = items.to_vec();
+    sort_by_path(&mut owned);
+    Cow::Owned(owned)
+}
+
+/// 在按路径排序的序列中二分查找路径（重复时返回最后一个）
+pub fn find<'a, T: PathKeyed>(sorted: &'a [T], path: &str) -> Option<&'a T> {
+    let end = sorted.partition_point(|item| item.path_key() <= path);
+    sorted[..end].last().filter(|item| item.path_key() == path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(path: &str, size: u64) -> LocalEntry {
+        LocalEntry {
+            rel_path: path.to_string(),
+            is_directory: false,
+            size,
+            modified: None,
+            inode: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_join() {
+        let a = [local("a", 1), local("c", 1), local("c", 2)];
+        let b = [local("b", 1), local("c", 1)];
+        let c: [LocalEntry; 0] = [];
+
+        let merged: Vec<_> = MergeJoin::new(&a, &b, &c)
+            .map(|(a, b, c)| {
+                (
+                    a.map(|e| (e.rel_path.as_str(), e.size)),
+                    b.map(|e| e.rel_path.as_str()),
+                    c.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            merged,
+            vec![
+                (Some(("a", 1)), None, false),
+                (None, Some("b"), false),
+                // 重复的路径保留最后一个
+                (Some(("c", 2)), Some("c"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_and_find() {
+        let mut entries = vec![local("b/x", 1), local("a", 1), local("b", 1), local("a", 2)];
+        assert!(matches!(sorted(&entries), Cow::Owned(_)));
+        sort_by_path(&mut entries);
+        assert!(matches!(sorted(&entries), Cow::Borrowed(_)));
+        let paths: Vec<_> = entries.iter().map(|e| e.rel_path.as_str()).collect();
+
+        assert_eq!(paths, vec!["a", "a", "b", "b/x"]);
+        assert_eq!(find(&entries, "a").map(|e| e.size), Some(2));
+        assert_eq!(find(&entries, "b/x").map(|e| e.size), Some(1));
+        assert!(find(&entries, "c").is_none());
+        assert!(find(&entries, "").is_none());
+    }
+}
@@ -0,0 +1,76 @@
+/// 同步引擎模块
+///
+/// 负责本地文件夹与 WebDAV 服务器之间的同步流程，并向前端推送同步进度
+///
+/// 模块结构:
+/// - browse: 远程目录浏览（读取远程目录树缓存，过期时在后台刷新）
+/// - compression: 透明压缩（按扩展名分组压缩上传，下载时根据压缩标记自动解压）
+/// - connectivity: 网络连通性监控（离线模式与待同步队列）
+/// - control: 同步控制（全局与按文件夹暂停、会话取消）
+/// - delta: 远程变更获取（sync-collection 增量同步，回退到完整列表）
+/// - duplicates: 重复文件检测（哈希缓存与服务器端复制）
+/// - encryption: 端到端加密（文件加解密、文件名混淆与远程同步清单）
+/// - engine: 同步引擎（执行同步计划）
+/// - events: 同步事件定义与发送
+/// - filter: 同步过滤规则（忽略模式与选择性同步排除）
+/// - folders: 同步文件夹校验
+/// - incremental: 增量扫描（沿用上次扫描结果，只重新读取变化的目录和脏路径）
+/// - marker: 远程同步标记（在远程根目录上写入客户端标识等自定义属性）
+/// - merge: 有序归并（按路径同时遍历本地、远程与快照）
+/// - paths: 本地路径规范化（Windows 长路径、保留文件名、大小写冲突）
+/// - planner: 同步计划（本地、远程与快照对比）
+/// - priority: 传输优先级（最近修改的文件先传输）
+/// - policy: 自动同步策略（按流量计费的网络、低电量或静默时段时跳过自动同步）
+/// - remote_poll: 远程变更轮询（不支持 sync-collection 的只下载文件夹比较目录指纹）
+/// - recovery: 启动恢复（中断的会话与传输、遗留的下载临时文件）
+/// - sanitize: 文件名清理（Windows 上不合法的远程文件名按策略替换、编码或跳过）
+/// - safety: 大量删除保护（超过比例时需要用户确认同步计划）
+/// - scanner: 本地文件扫描
+/// - selective: 选择性同步（远程子文件夹排除）
+/// - space: 存储空间预检查（服务器配额与本地磁盘剩余空间）
+/// - stability: 写入中文件的稳定性检测（推迟上传并稍后重新同步）
+/// - state: 文件夹同步状态机（空闲、扫描、传输、暂停、出错）
+/// - symlinks: 符号链接处理策略（跳过、跟随、占位文件）
+/// - throughput: 传输速度统计（按传输与服务器的滑动窗口吞吐量）
+/// - transfer: 传输池（并发上传下载，支持暂停）
+/// - trash: 本地回收站
+/// - verify: 传输完整性校验
+/// - versions: 本地文件历史版本
+pub mod browse;
+pub mod compression;
+pub mod connectivity;
+pub mod control;
+pub mod delta;
+pub mod duplicates;
+pub mod encryption;
+pub mod engine;
+pub mod events;
+pub mod filter;
+pub mod folders;
+pub mod incremental;
+pub mod marker;
+pub mod merge;
+pub mod paths;
+pub mod planner;
+pub mod policy;
+pub mod priority;
+pub mod recovery;
+pub mod remote_poll;
+pub mod safety;
+pub mod sanitize;
+pub mod scanner;
+pub mod selective;
+pub mod space;
+pub mod stability;
+pub mod state;
+pub mod symlinks;
+pub mod throughput;
+pub mod transfer;
+pub mod trash;
+pub mod verify;
+pub mod versions;
+
+pub use events::{
+    SyncCompletedEvent, SyncCounters, SyncErrorEvent, SyncEventEmitter, SyncPhase,
+    SyncProgressEvent, SyncStateChangedEvent,
+};
@@ -0,0 +1,778 @@
+/// 本地路径规范化
+///
+/// 处理远程路径映射到本地文件系统时的平台差异（主要是 Windows）：
+/// - 超过 `MAX_PATH`（260 个字符）的绝对路径添加 `\\?\` 前缀
+/// - 服务器上的文件名在 Windows 上不合法时（保留设备名 `CON`、`NUL` 等，
+///   结尾的点或空格，`<>:"|?*` 等字符），按配置的策略替换、编码或跳过（见 `sanitize`）
+/// - 大小写不敏感的文件系统上，仅大小写不同的远程路径会映射到同一个本地文件，
+///   保留排序靠前的一个；其余的文件按 `CaseCollisionPolicy` 以重命名的副本保存
+///   （如 `Readme (case conflict).md`）或跳过，冲突的目录总是跳过
+/// - 本地和远程的文件名统一为 Unicode NFC 形式参与对比，本地或服务器上已有的其他形式
+///   （如 macOS 创建的 NFD 文件名）在访问文件时还原为原始名称；
+///   规范化后与其他条目重复的文件跳过，避免同一个文件以两种形式重复上传
+///
+/// 同步计划、快照和远程请求始终使用远程路径；重命名只影响本地文件名，
+/// 对比前会把本地扫描到的重命名文件映射回对应的远程路径。
+/// 本地路径与远程路径的对应关系保存在数据库中（`name_mappings`），
+/// 远程文件被删除或移走后，本地的重命名文件仍然以原来的远程文件名上传。
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{case_collision_policy, WINDOWS_MAX_PATH};
+use crate::sync::planner::{PlanAction, RemoteEntry};
+use crate::sync::sanitize::{is_valid_path, to_nfc, ReservedNamePolicy};
+use crate::sync::scanner::LocalEntry;
+use crate::{Result, SyncError};
+
+/// 大小写冲突副本文件名中的标记
+const CASE_CONFLICT_SUFFIX: &str = "case conflict";
+
+/// 仅大小写不同的远程文件在不区分大小写的本地文件系统上的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseCollisionPolicy {
+    /// 保留排序靠前的文件，其余文件以重命名的副本保存到本地
+    Rename,
+    /// 跳过其余文件，不同步到本地
+    Skip,
+}
+
+impl CaseCollisionPolicy {
+    /// 从配置值解析，未知的值按重命名处理
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            case_collision_policy::SKIP => Self::Skip,
+            _ => Self::Rename,
+        }
+    }
+}
+
+/// 以重命名副本保存到本地的大小写冲突文件
+///
+/// 两个远程文件都会同步，但需要用户处理（例如在服务器上重命名其中一个）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseCollision {
+    /// 以副本保存的远程路径
+    pub rel_path: String,
+    /// 占用原本地路径的远程路径
+    pub collides_with: String,
+    /// 副本的本地相对路径
+    pub local_rel_path: String,
+}
+
+/// 同步时跳过的路径
+///
+/// 本地不合法或大小写冲突的远程文件名（下载），以及超出文件大小上限或扩展名过滤的文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedPath {
+    /// 相对于同步根目录的路径
+    pub rel_path: String,
+    /// 被跳过的操作
+    pub action: PlanAction,
+    /// 跳过原因
+    pub reason: String,
+}
+
+impl SkippedPath {
+    /// 判断路径是否为跳过的条目本身或其子项
+    pub fn covers(&self, rel_path: &str) -> bool {
+        rel_path == self.rel_path || is_descendant(rel_path, &self.rel_path)
+    }
+}
+
+/// 路径规范化器
+#[derive(Debug, Clone)]
+pub struct PathNormalizer {
+    policy: ReservedNamePolicy,
+    /// 是否应用 Windows 文件名规则和长路径前缀
+    windows: bool,
+    /// 本地文件系统是否不区分大小写
+    case_insensitive: bool,
+    /// 大小写冲突的处理策略
+    case_policy: CaseCollisionPolicy,
+    /// 以重命名副本保存的大小写冲突文件（远程路径 -> 冲突信息），由 `normalize` 更新
+    collisions: HashMap<String, CaseCollision>,
+    /// 之前同步保存的名称映射（本地路径 -> 远程路径）
+    stored: HashMap<String, String>,
+    /// 上次 `normalize` 中生效的名称映射（远程路径 -> 本地路径）
+    mappings: HashMap<String, String>,
+    /// 服务器上不是 NFC 形式的路径（NFC 路径 -> 原始路径），由 `normalize` 更新
+    remote_names: HashMap<String, String>,
+    /// 本地不是 NFC 形式的路径（NFC 路径 -> 原始路径），由 `normalize` 更新
+    local_names: HashMap<String, String>,
+}
+
+impl PathNormalizer {
+    /// 按当前平台创建规范化器
+    pub fn new(policy: ReservedNamePolicy) -> Self {
+        Self::with_platform(
+            policy,
+            cfg!(windows),
+            cfg!(any(windows, target_os = "macos")),
+        )
+    }
+
+    /// 使用指定的平台特性创建规范化器
+    pub fn with_platform(
+        policy: ReservedNamePolicy,
+        windows: bool,
+        case_insensitive: bool,
+    ) -> Self {
+        Self {
+            policy,
+            windows,
+            case_insensitive,
+            case_policy: CaseCollisionPolicy::Skip,
+            collisions: HashMap::new(),
+            stored: HashMap::new(),
+            mappings: HashMap::new(),
+            remote_names: HashMap::new(),
+            local_names: HashMap::new(),
+        }
+    }
+
+    /// 设置大小写冲突的处理策略（默认跳过）
+    pub fn with_case_collisions(mut self, policy: CaseCollisionPolicy) -> Self {
+        self.case_policy = policy;
+        self
+    }
+
+    /// 上次 `normalize` 中以重命名副本保存的大小写冲突文件（按远程路径排序）
+    pub fn case_collisions(&self) -> Vec<CaseCollision> {
+        let mut collisions: Vec<CaseCollision> = self.collisions.values().cloned().collect();
+        collisions.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+        collisions
+    }
+
+    /// 载入之前保存的名称映射（本地路径, 远程路径）
+    ///
+    /// 远程列表中没有对应文件时，`normalize` 按这些映射把本地的重命名文件改回原来的远程路径
+    pub fn restore_mappings(&mut self, mappings: Vec<(String, String)>) {
+        self.stored = mappings.into_iter().collect();
+    }
+
+    /// 上次 `normalize` 中生效的名称映射（本地路径, 远程路径），按本地路径排序
+    ///
+    /// 包括本次重命名的远程条目和仍在使用的已保存映射，用于保存到数据库
+    pub fn mappings(&self) -> Vec<(String, String)> {
+        let mut mappings: Vec<(String, String)> = self
+            .mappings
+            .iter()
+            .map(|(remote, local)| (local.clone(), remote.clone()))
+            .collect();
+        mappings.sort();
+        mappings
+    }
+
+    /// 将远程相对路径转换为本地相对路径（重命名不合法的分段和大小写冲突的文件，
+    /// 已保存映射的文件使用映射中的本地路径）
+    pub fn local_rel_path(&self, rel_path: &str) -> String {
+        if let Some(local_rel) = self.mappings.get(rel_path) {
+            return local_rel.clone();
+        }
+        if let Some(collision) = self.collisions.get(rel_path) {
+            return collision.local_rel_path.clone();
+        }
+        if let Some(original) = restore_original(&self.local_names, rel_path) {
+            return original;
+        }
+        self.sanitized_rel_path(rel_path)
+    }
+
+    /// 将相对路径转换为服务器上的相对路径
+    ///
+    /// 服务器上已有的文件或其上级目录不是 NFC 形式时使用原始名称，新文件使用 NFC 形式
+    pub fn remote_rel_path(&self, rel_path: &str) -> String {
+        restore_original(&self.remote_names, rel_path).unwrap_or_else(|| rel_path.to_string())
+    }
+
+    /// 按策略转换远程相对路径中不合法的分段
+    fn sanitized_rel_path(&self, rel_path: &str) -> String {
+        if !self.windows || self.policy == ReservedNamePolicy::Skip {
+            return rel_path.to_string();
+        }
+
+        rel_path
+            .split('/')
+            .map(|segment| self.policy.map_name(segment))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// 获取远程相对路径对应的本地绝对路径
+    ///
+    /// 按分段拼接（`\\?\` 路径不会转换 `/`），超长时添加长路径前缀
+    ///
+    /// # 返回
+    /// - Err(SyncError::ValidationError): 分段为 `.`、`..` 或平台路径前缀等，拼接后可能不在根目录下
+    pub fn local_path(&self, root: &Path, rel_path: &str) -> Result<PathBuf> {
+        let mut path = root.to_path_buf();
+        for segment in self.local_rel_path(rel_path).split('/') {
+            if segment.is_empty() {
+                continue;
+            }
+            let mut components = Path::new(segment).components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(_)), None) => path.push(segment),
+                _ => {
+                    return Err(SyncError::ValidationError(format!(
+                        "Path is outside the sync root: {}",
+                        rel_path
+                    )))
+                }
+            }
+        }
+
+        if !self.windows {
+            return Ok(path);
+        }
+        Ok(match to_long_path(&path.to_string_lossy()) {
+            Some(long) => PathBuf::from(long),
+            None => path,
+        })
+    }
+
+    /// 对比前规范化本地和远程条目
+    ///
+    /// 0. 本地和远程路径转换为 NFC 形式，规范化后与其他条目重复的条目跳过
+    /// 1. 文件名不合法的远程条目按策略重命名或跳过
+    /// 2. 映射到同一个本地路径的远程条目只保留第一个（及其子项），
+    ///    其余文件按大小写冲突策略改用重命名的本地路径，或跳过
+    /// 3. 本地扫描到的重命名文件改回对应的远程路径；远程文件已不存在时
+    ///    按之前保存的映射改回原来的远程路径
+    ///
+    /// # 参数
+    /// - remote: 远程条目，跳过的条目会被移除
+    /// - local: 本地扫描结果，跳过的条目会被移除
+    ///
+    /// # 返回
+    /// 被跳过的路径
+    pub fn normalize(
+        &mut self,
+        remote: &mut Vec<RemoteEntry>,
+        local: &mut Vec<LocalEntry>,
+    ) -> Vec<SkippedPath> {
+        let mut skipped = Vec::new();
+        self.remote_names = to_nfc_paths(
+            remote,
+            |e| &mut e.rel_path,
+            PlanAction::Download,
+            &mut skipped,
+        );
+        self.local_names =
+            to_nfc_paths(local, |e| &mut e.rel_path, PlanAction::Upload, &mut skipped);
+        remote.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+        let mut skipped_dirs: Vec<String> = Vec::new();
+        // 本地路径（比较键） -> 占用它的远程路径
+        let mut claimed: HashMap<String, String> = HashMap::new();
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        let mut collisions: HashMap<String, CaseCollision> = HashMap::new();
+
+        remote.retain(|entry| {
+            if skipped_dirs
+                .iter()
+                .any(|dir| is_descendant(&entry.rel_path, dir))
+            {
+                return false;
+            }
+
+            let reason = if self.windows
+                && self.policy == ReservedNamePolicy::Skip
+                && !is_valid_path(&entry.rel_path)
+            {
+                Some("File name is not valid on Windows".to_string())
+            } else {
+                let local_rel = self.sanitized_rel_path(&entry.rel_path);
+                match claimed.get(&self.collision_key(&local_rel)).cloned() {
+                    Some(owner)
+                        if self.case_policy == CaseCollisionPolicy::Rename
+                            && !entry.is_directory =>
+                    {
+                        let copy = self.collision_copy(&local_rel, &claimed);
+                        tracing::warn!(
+                            path = %entry.rel_path,
+                            collides_with = %owner,
+                            local_path = %copy,
+                            "远程文件与其他文件仅大小写不同，以副本保存到本地"
+                        );
+                        claimed.insert(self.collision_key(&copy), entry.rel_path.clone());
+                        renamed.insert(copy.clone(), entry.rel_path.clone());
+                        collisions.insert(
+                            entry.rel_path.clone(),
+                            CaseCollision {
+                                rel_path: entry.rel_path.clone(),
+                                collides_with: owner,
+                                local_rel_path: copy,
+                            },
+                        );
+                        None
+                    }
+                    Some(owner) => Some(format!("Local path collides with: {}", owner)),
+                    None => {
+                        claimed.insert(self.collision_key(&local_rel), entry.rel_path.clone());
+                        if local_rel != entry.rel_path {
+                            renamed.insert(local_rel, entry.rel_path.clone());
+                        }
+                        None
+                    }
+                }
+            };
+
+            match reason {
+                Some(reason) => {
+                    tracing::warn!(path = %entry.rel_path, reason = %reason, "跳过远程条目");
+                    if entry.is_directory {
+                        skipped_dirs.push(entry.rel_path.clone());
+                    }
+                    skipped.push(SkippedPath {
+                        rel_path: entry.rel_path.clone(),
+                        action: PlanAction::Download,
+                        reason,
+                    });
+                    false
+                }
+                None => true,
+            }
+        });
+
+        self.collisions = collisions;
+        let skipped_paths: HashSet<&str> = skipped.iter().map(|s| s.rel_path.as_str()).collect();
+        let remote_paths: HashSet<&str> = remote.iter().map(|e| e.rel_path.as_str()).collect();
+        let mut mappings: HashMap<String, String> = renamed
+            .iter()
+            .map(|(local_rel, remote_rel)| (remote_rel.clone(), local_rel.clone()))
+            .collect();
+        for entry in local.iter_mut() {
+            if let Some(remote_rel) = renamed.get(&entry.rel_path) {
+                if !skipped_paths.contains(remote_rel.as_str()) {
+                    entry.rel_path = remote_rel.clone();
+                }
+                continue;
+            }
+
+            // 远程文件已不存在（且本地路径未被其他远程条目占用）时按保存的映射还原远程文件名
+            let Some(remote_rel) = self.stored.get(&entry.rel_path) else {
+                continue;
+            };
+            if remote_rel != &entry.rel_path
+                && !remote_paths.contains(remote_rel.as_str())
+                && !skipped_paths.contains(remote_rel.as_str())
+                && !claimed.contains_key(&self.collision_key(&entry.rel_path))
+            {
+                mappings.insert(remote_rel.clone(), entry.rel_path.clone());
+                entry.rel_path = remote_rel.clone();
+            }
+        }
+        self.mappings = mappings;
+
+        skipped
+    }
+
+    /// 为大小写冲突的文件生成未被占用的本地副本路径
+    ///
+    /// 在文件名的扩展名前追加 ` (case conflict)`，仍被占用时追加序号，
+    /// 如 `docs/Readme.md` -> `docs/Readme (case conflict).md`、`docs/Readme (case conflict 2).md`
+    fn collision_copy(&self, local_rel: &str, claimed: &HashMap<String, String>) -> String {
+        let (dir, name) = match local_rel.rsplit_once('/') {
+            Some((dir, name)) => (format!("{}/", dir), name),
+            None => (String::new(), local_rel),
+        };
+        let (stem, ext) = match name.rfind('.').filter(|&i| i > 0) {
+            Some(i) => name.split_at(i),
+            None => (name, ""),
+        };
+
+        (1..)
+            .map(|n| {
+                let suffix = if n == 1 {
+                    CASE_CONFLICT_SUFFIX.to_string()
+                } else {
+                    format!("{} {}", CASE_CONFLICT_SUFFIX, n)
+                };
+                format!("{}{} ({}){}", dir, stem, suffix, ext)
+            })
+            .find(|copy| !claimed.contains_key(&self.collision_key(copy)))
+            .expect("unbounded candidate names")
+    }
+
+    fn collision_key(&self, local_rel: &str) -> String {
+        if self.case_insensitive {
+            local_rel.to_lowercase()
+        } else {
+            local_rel.to_string()
+        }
+    }
+}
+
+/// 将条目的路径转换为 NFC 形式
+///
+/// 转换后与已有条目重复的条目（同一个名称的两种 Unicode 形式）移入跳过列表
+///
+/// # 返回
+/// 转换过的路径（NFC 路径 -> 原始路径）
+fn to_nfc_paths<T>(
+    entries: &mut Vec<T>,
+    path_of: impl Fn(&mut T) -> &mut String,
+    action: PlanAction,
+    skipped: &mut Vec<SkippedPath>,
+) -> HashMap<String, String> {
+    let mut seen: HashSet<String> = entries.iter_mut().map(|e| path_of(e).clone()).collect();
+    let mut originals = HashMap::new();
+
+    entries.retain_mut(|entry| {
+        let path = path_of(entry);
+        let nfc = to_nfc(path);
+        if nfc == *path {
+            return true;
+        }
+        if !seen.insert(nfc.clone()) {
+            let reason = format!("Duplicate of {} after Unicode normalization", nfc);
+            tracing::warn!(path = %path, reason = %reason, "跳过条目");
+            skipped.push(SkippedPath {
+                rel_path: path.clone(),
+                action,
+                reason,
+            });
+            return false;
+        }
+        originals.insert(nfc.clone(), std::mem::replace(path, nfc));
+        true
+    });
+
+    originals
+}
+
+/// 按路径或最近的已记录上级目录还原原始路径
+///
+/// # 返回
+/// 路径及其上级目录都没有记录时返回 None
+fn restore_original(originals: &HashMap<String, String>, rel_path: &str) -> Option<String> {
+    if originals.is_empty() {
+        return None;
+    }
+
+    let mut prefix = rel_path;
+    loop {
+        if let Some(original) = originals.get(prefix) {
+            return Some(format!("{}{}", original, &rel_path[prefix.len()..]));
+        }
+        prefix = prefix.rsplit_once('/')?.0;
+    }
+}
+
+/// 判断路径是否位于目录之下
+fn is_descendant(path: &str, dir: &str) -> bool {
+    path.strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 判断远程相对路径是否只包含普通分段（没有空分段、`.` 或 `..`）
+///
+/// 服务器返回的路径不可信，包含这些分段的路径拼接到本地根目录后可能指向根目录之外
+pub fn is_safe_rel_path(rel_path: &str) -> bool {
+    rel_path
+        .split('/')
+        .all(|segment| !matches!(segment, "" | "." | ".."))
+}
+
+/// 为超长的 Windows 绝对路径添加 `\\?\` 前缀
+///
+/// # 返回
+/// - Some(String): 带前缀的路径（UNC 路径 `\\server\share` 转换为 `\\?\UNC\server\share`）
+/// - None: 路径未超过 `MAX_PATH`、不是绝对路径或已带前缀，无需处理
+pub fn to_long_path(path: &str) -> Option<String> {
+    if path.chars().count() < WINDOWS_MAX_PATH || path.starts_with(r"\\?\") {
+        return None;
+    }
+
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{}", unc));
+    }
+
+    let bytes = path.as_bytes();
+    let is_absolute = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    is_absolute.then(|| format!(r"\\?\{}", path.replace('/', r"\")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(path: &str, is_directory: bool) -> RemoteEntry {
+        RemoteEntry {
+            rel_path: path.to_string(),
+            is_directory,
+            size: 0,
+            modified: None,
+            etag: None,
+        }
+    }
+
+    fn local(path: &str) -> LocalEntry {
+        LocalEntry {
+            rel_path: path.to_string(),
+            is_directory: false,
+            size: 0,
+            modified: None,
+            inode: None,
+        }
+    }
+
+    fn paths(entries: &[RemoteEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.rel_path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_to_long_path() {
+        let long = format!(r"C:\Users\me\{}", "a".repeat(260));
+        assert_eq!(to_long_path(&long), Some(format!(r"\\?\{}", long)));
+
+        let unc = format!(r"\\server\share\{}", "b".repeat(260));
+        assert_eq!(
+            to_long_path(&unc),
+            Some(format!(r"\\?\UNC\server\share\{}", "b".repeat(260)))
+        );
+
+        assert_eq!(to_long_path(r"C:\short\path.txt"), None);
+        assert_eq!(to_long_path(&format!(r"\\?\{}", long)), None);
+        assert_eq!(to_long_path(&format!("relative/{}", "c".repeat(260))), None);
+    }
+
+    #[test]
+    fn test_local_path() {
+        let normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Rename, true, true);
+        assert_eq!(
+            normalizer.local_rel_path("docs/CON/a?.txt"),
+            "docs/CON_/a_.txt"
+        );
+
+        let root = PathBuf::from("/sync");
+        assert_eq!(
+            normalizer.local_path(&root, "docs/aux.txt").unwrap(),
+            PathBuf::from("/sync").join("docs").join("aux_.txt")
+        );
+
+        // 非 Windows 平台保持原样
+        let normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Rename, false, false);
+        assert_eq!(normalizer.local_rel_path("docs/CON"), "docs/CON");
+    }
+
+    #[test]
+    fn test_local_path_stays_under_root() {
+        assert!(is_safe_rel_path("docs/a.txt"));
+        assert!(!is_safe_rel_path("../../.bashrc"));
+        assert!(!is_safe_rel_path("docs/./a.txt"));
+        assert!(!is_safe_rel_path("docs//a.txt"));
+
+        let normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Skip, false, false);
+        let root = Path::new("/sync");
+        assert!(normalizer.local_path(root, "../../.bashrc").is_err());
+        assert!(normalizer.local_path(root, "docs/../../a.txt").is_err());
+        assert!(normalizer.local_path(root, "docs/./a.txt").is_err());
+        assert_eq!(
+            normalizer.local_path(root, "docs/..a.txt").unwrap(),
+            PathBuf::from("/sync/docs/..a.txt")
+        );
+    }
+
+    #[test]
+    fn test_normalize_renames_reserved_names() {
+        let mut normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Rename, true, true);
+        let mut remote_entries = vec![remote("docs", true), remote("docs/nul.txt", false)];
+        let mut local_entries = vec![local("docs/nul_.txt")];
+
+        let skipped = normalizer.normalize(&mut remote_entries, &mut local_entries);
+
+        assert!(skipped.is_empty());
+        assert_eq!(paths(&remote_entries), vec!["docs", "docs/nul.txt"]);
+        // 本地的重命名文件映射回远程路径
+        assert_eq!(local_entries[0].rel_path, "docs/nul.txt");
+    }
+
+    #[test]
+    fn test_normalize_percent_encodes_names() {
+        let mut normalizer =
+            PathNormalizer::with_platform(ReservedNamePolicy::PercentEncode, true, true);
+        let mut remote_entries = vec![remote("a:b.txt", false), remote("ok.txt", false)];
+        let mut local_entries = vec![local("a%3Ab.txt"), local("ok.txt")];
+
+        assert!(normalizer
+            .normalize(&mut remote_entries, &mut local_entries)
+            .is_empty());
+        assert_eq!(local_entries[0].rel_path, "a:b.txt");
+        assert_eq!(normalizer.local_rel_path("a:b.txt"), "a%3Ab.txt");
+        assert_eq!(
+            normalizer.mappings(),
+            vec![("a%3Ab.txt".to_string(), "a:b.txt".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_normalize_restores_stored_mappings() {
+        let mut normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Rename, true, true);
+        normalizer.restore_mappings(vec![
+            ("a_b.txt".to_string(), "a:b.txt".to_string()),
+            ("c_d.txt".to_string(), "c?d.txt".to_string()),
+            ("gone_.txt".to_string(), "gone*.txt".to_string()),
+        ]);
+        // 远程的 a:b.txt 已删除；c_d.txt 现在被同名的远程文件占用
+        let mut remote_entries = vec![remote("c_d.txt", false)];
+        let mut local_entries = vec![local("a_b.txt"), local("c_d.txt")];
+
+        normalizer.normalize(&mut remote_entries, &mut local_entries);
+
+        // 本地文件按原来的远程文件名上传
+        assert_eq!(local_entries[0].rel_path, "a:b.txt");
+        assert_eq!(local_entries[1].rel_path, "c_d.txt");
+        assert_eq!(normalizer.local_rel_path("a:b.txt"), "a_b.txt");
+        // 只保留仍在使用的映射
+        assert_eq!(
+            normalizer.mappings(),
+            vec![("a_b.txt".to_string(), "a:b.txt".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_normalize_unicode_forms() {
+        let mut normalizer =
+            PathNormalizer::with_platform(ReservedNamePolicy::Rename, false, false);
+        // 服务器上的目录是 NFD 形式，本地 macOS 创建的文件也是 NFD 形式
+        let mut remote_entries = vec![
+            remote("Cafe\u{301}", true),
+            remote("Cafe\u{301}/menu.txt", false),
+            remote("re\u{301}sume\u{301}.txt", false),
+            remote("résumé.txt", false),
+        ];
+        let mut local_entries = vec![local("Café/menu.txt"), local("note\u{301}s.md")];
+
+        let skipped = normalizer.normalize(&mut remote_entries, &mut local_entries);
+
+        assert_eq!(
+            paths(&remote_entries),
+            vec!["Café", "Café/menu.txt", "résumé.txt"]
+        );
+        // 同一个名称的两种形式只保留 NFC 的条目
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].rel_path, "re\u{301}sume\u{301}.txt");
+        assert_eq!(local_entries[1].rel_path, "notés.md");
+
+        // 访问文件时还原为原始名称，已有目录下的新文件沿用目录的原始名称
+        assert_eq!(
+            normalizer.remote_rel_path("Café/menu.txt"),
+            "Cafe\u{301}/menu.txt"
+        );
+        assert_eq!(
+            normalizer.remote_rel_path("Café/new.txt"),
+            "Cafe\u{301}/new.txt"
+        );
+        assert_eq!(normalizer.remote_rel_path("notés.md"), "notés.md");
+        assert_eq!(normalizer.local_rel_path("notés.md"), "note\u{301}s.md");
+        assert_eq!(normalizer.local_rel_path("Café/menu.txt"), "Café/menu.txt");
+    }
+
+    #[test]
+    fn test_normalize_skips_reserved_names() {
+        let mut normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Skip, true, true);
+        let mut remote_entries = vec![
+            remote("CON", true),
+            remote("CON/a.txt", false),
+            remote("trailing.", false),
+            remote("ok.txt", false),
+        ];
+
+        let skipped = normalizer.normalize(&mut remote_entries, &mut Vec::new());
+
+        assert_eq!(paths(&remote_entries), vec!["ok.txt"]);
+        assert!(skipped[0].covers("CON/a.txt"));
+        assert!(!skipped[0].covers("CONFIG"));
+        let skipped: Vec<&str> = skipped.iter().map(|s| s.rel_path.as_str()).collect();
+        assert_eq!(skipped, vec!["CON", "trailing."]);
+    }
+
+    #[test]
+    fn test_normalize_detects_case_collisions() {
+        let mut normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Rename, false, true);
+        let mut remote_entries = vec![
+            remote("docs/readme.md", false),
+            remote("Docs", true),
+            remote("docs", true),
+            remote("Docs/README.md", false),
+            remote("docs/other.md", false),
+        ];
+
+        let skipped = normalizer.normalize(&mut remote_entries, &mut Vec::new());
+
+        assert_eq!(paths(&remote_entries), vec!["Docs", "Docs/README.md"]);
+        assert_eq!(
+            skipped,
+            vec![SkippedPath {
+                rel_path: "docs".to_string(),
+                action: PlanAction::Download,
+                reason: "Local path collides with: Docs".to_string(),
+            }]
+        );
+
+        // 区分大小写的文件系统上不视为冲突
+        let mut normalizer =
+            PathNormalizer::with_platform(ReservedNamePolicy::Rename, false, false);
+        let mut remote_entries = vec![remote("a.txt", false), remote("A.txt", false)];
+        assert!(normalizer
+            .normalize(&mut remote_entries, &mut Vec::new())
+            .is_empty());
+        assert_eq!(remote_entries.len(), 2);
+    }
+
+    #[test]
+    fn test_normalize_renames_case_collisions() {
+        let mut normalizer = PathNormalizer::with_platform(ReservedNamePolicy::Rename, false, true)
+            .with_case_collisions(CaseCollisionPolicy::Rename);
+        let mut remote_entries = vec![
+            remote("README.md", false),
+            remote("Readme.md", false),
+            remote("readme.md", false),
+            remote("Readme (case conflict).md", false),
+            remote("Docs", true),
+            remote("docs", true),
+        ];
+        let mut local_entries = vec![local("Readme (case conflict 2).md")];
+
+        let skipped = normalizer.normalize(&mut remote_entries, &mut local_entries);
+
+        // 冲突的目录仍然跳过，文件都保留
+        let skipped: Vec<&str> = skipped.iter().map(|s| s.rel_path.as_str()).collect();
+        assert_eq!(skipped, vec!["docs"]);
+        assert_eq!(remote_entries.len(), 5);
+
+        let collisions = normalizer.case_collisions();
+        assert_eq!(
+            collisions,
+            vec![
+                CaseCollision {
+                    rel_path: "Readme.md".to_string(),
+                    collides_with: "README.md".to_string(),
+                    local_rel_path: "Readme (case conflict 2).md".to_string(),
+                },
+                CaseCollision {
+                    rel_path: "readme.md".to_string(),
+                    collides_with: "README.md".to_string(),
+                    local_rel_path: "readme (case conflict 3).md".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            normalizer
+                .local_path(Path::new("/sync"), "Readme.md")
+                .unwrap(),
+            PathBuf::from("/sync").join("Readme (case conflict 2).md")
+        );
+        // 本地的副本映射回远程路径
+        assert_eq!(local_entries[0].rel_path, "Readme.md");
+    }
+}
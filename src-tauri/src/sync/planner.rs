@@ -0,0 +1,1280 @@
+/// 同步计划（对比阶段）
+///
+/// 对比本地扫描结果、远程列表和上次同步的快照（file_metadata），生成同步计划：
+/// 需要上传、下载、删除的文件以及冲突。计划本身不执行任何传输，
+/// 既用于同步引擎执行，也用于 `preview_sync` 预览。
+///
+/// # 判定规则
+///
+/// | 本地 | 远程 | 快照 | 结果 |
+/// |------|------|------|------|
+/// | 有 | 有 | 有 | 仅本地变化→上传；仅远程变化→下载；都变化→冲突 |
+/// | 有 | 有 | 无 | 大小相同视为一致，否则冲突 |
+/// | 有 | 无 | 有 | 本地有变化→上传，否则删除本地 |
+/// | 有 | 无 | 无 | 上传 |
+/// | 无 | 有 | 有 | 远程有变化→下载，否则删除远程 |
+/// | 无 | 有 | 无 | 下载 |
+///
+/// 文件夹首次同步（快照为空）时，只在一侧或两侧内容不同的条目按 `first_sync_strategy` 处理：
+///
+/// | 策略 | 两侧内容不同 | 只在本地 | 只在远程 |
+/// |------|------|------|------|
+/// | merge-keep-both | 保留两份（本地改名为冲突副本） | 上传 | 下载 |
+/// | prefer-local | 上传 | 上传 | 下载 |
+/// | prefer-remote | 下载 | 上传 | 下载 |
+/// | mirror（源端为本地） | 上传 | 上传 | 删除远程 |
+/// | mirror（源端为远程） | 下载 | 删除本地 | 下载 |
+///
+/// 同一周期内本地删除的文件与新建的文件是同一文件（大小和修改时间一致，inode 相同，
+/// 或内容哈希与快照一致）时，删除远程和上传合并为远程移动，避免重新上传整个文件
+///
+/// 用户为单个文件设置的同步状态（见 `set_file_sync_state`）优先于以上规则：
+/// ignored 的文件不做任何操作，记入跳过列表；pinned 的文件始终保留本地副本，
+/// 远程删除时改为重新上传（只下载的文件夹直接跳过删除）
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::{conflict_resolution, file_status, first_sync_strategy, sync_direction};
+use crate::database::{
+    file_metadata, folder_keys, name_mappings, remote_cache, Database, FileMetadata,
+    RemoteCacheEntry,
+};
+use crate::sync::delta::list_recursive_detailed;
+use crate::sync::encryption::FolderCipher;
+use crate::sync::filter::{folder_relative_path, FileConstraints, SyncFilter};
+use crate::sync::incremental::{self, ScanCache};
+use crate::sync::merge::{self, sort_by_path, sorted, MergeJoin};
+use crate::sync::paths::{is_safe_rel_path, CaseCollision, PathNormalizer, SkippedPath};
+use crate::sync::priority;
+use crate::sync::sanitize::to_nfc;
+use crate::sync::scanner::{scan_local, LocalEntry};
+use crate::sync::stability;
+use crate::sync::symlinks::SymlinkPolicy;
+use crate::sync::versions::hash_file;
+use crate::webdav::client::{FileInfo, RemoteItemError, RemoteListing, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 计划中的操作类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlanAction {
+    /// 上传到远程（目录为创建远程目录）
+    Upload,
+    /// 下载到本地（目录为创建本地目录）
+    Download,
+    /// 删除本地文件
+    DeleteLocal,
+    /// 删除远程文件
+    DeleteRemote,
+    /// 在远程移动本地已重命名的文件
+    Move,
+    /// 首次同步时两侧内容不同，本地文件改名为冲突副本后两份都同步
+    KeepBoth,
+    /// 冲突，需要用户决定
+    Conflict,
+}
+
+/// 计划条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanItem {
+    /// 相对于同步根目录的路径
+    pub rel_path: String,
+    /// 操作类型
+    pub action: PlanAction,
+    /// 是否为目录
+    pub is_directory: bool,
+    /// 涉及的字节数（上传为本地大小，下载为远程大小）
+    pub size: u64,
+    /// 本地大小（冲突时供用户比较）
+    pub local_size: Option<u64>,
+    /// 远程大小（冲突时供用户比较）
+    pub remote_size: Option<u64>,
+    /// 生成该操作的原因（如冲突已按策略自动解决）
+    pub reason: Option<String>,
+    /// 移动的源路径（仅 Move）
+    #[serde(default)]
+    pub from_path: Option<String>,
+}
+
+/// 同步计划
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPlan {
+    /// 计划 ID（大量删除保护时用于确认）
+    #[serde(default)]
+    pub plan_id: String,
+    /// 同步文件夹 ID
+    pub folder_id: String,
+    /// 需要上传的条目
+    pub uploads: Vec<PlanItem>,
+    /// 需要下载的条目
+    pub downloads: Vec<PlanItem>,
+    /// 需要删除的本地条目
+    pub local_deletions: Vec<PlanItem>,
+    /// 需要删除的远程条目
+    pub remote_deletions: Vec<PlanItem>,
+    /// 本地重命名、需要在远程移动的文件
+    #[serde(default)]
+    pub moves: Vec<PlanItem>,
+    /// 需要用户处理的冲突
+    pub conflicts: Vec<PlanItem>,
+    /// 首次同步时两侧都保留的文件
+    #[serde(default)]
+    pub keep_both: Vec<PlanItem>,
+    /// 跳过的条目（远程文件名在本地不合法或大小写冲突，或超出文件大小上限、扩展名过滤）
+    #[serde(default)]
+    pub skipped: Vec<SkippedPath>,
+    /// 仍在写入、推迟到下次同步上传的本地文件
+    #[serde(default)]
+    pub deferred: Vec<PlanItem>,
+    /// 与其他远程文件仅大小写不同、以重命名副本保存到本地的文件（需要用户处理）
+    #[serde(default)]
+    pub case_collisions: Vec<CaseCollision>,
+    /// 上传总字节数
+    pub upload_bytes: u64,
+    /// 下载总字节数
+    pub download_bytes: u64,
+    /// 上次同步时的文件数（大量删除保护的比较基数）
+    #[serde(default)]
+    pub synced_files: usize,
+    /// 计划生成时间（Unix 时间戳，秒）
+    pub generated_at: i64,
+}
+
+impl SyncPlan {
+    /// 计划中的操作总数
+    pub fn total_actions(&self) -> usize {
+        self.uploads.len()
+            + self.downloads.len()
+            + self.local_deletions.len()
+            + self.remote_deletions.len()
+            + self.moves.len()
+            + self.conflicts.len()
+            + self.keep_both.len()
+    }
+
+    /// 计划是否为空（本地与远程已一致）
+    pub fn is_empty(&self) -> bool {
+        self.total_actions() == 0
+    }
+
+    fn push(&mut self, item: PlanItem) {
+        match item.action {
+            PlanAction::Upload => {
+                self.upload_bytes += item.size;
+                self.uploads.push(item);
+            }
+            PlanAction::Download => {
+                self.download_bytes += item.size;
+                self.downloads.push(item);
+            }
+            PlanAction::DeleteLocal => self.local_deletions.push(item),
+            PlanAction::DeleteRemote => self.remote_deletions.push(item),
+            PlanAction::Move => self.moves.push(item),
+            PlanAction::Conflict => self.conflicts.push(item),
+            PlanAction::KeepBoth => {
+                self.upload_bytes += item.local_size.unwrap_or(0);
+                self.download_bytes += item.remote_size.unwrap_or(0);
+                self.keep_both.push(item);
+            }
+        }
+    }
+}
+
+/// 远程条目（已转换为相对路径）
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteEntry {
+    /// 相对于同步根目录的路径
+    pub rel_path: String,
+    /// 是否为目录
+    pub is_directory: bool,
+    /// 文件大小
+    pub size: u64,
+    /// 最后修改时间（Unix 时间戳，秒）
+    pub modified: Option<i64>,
+    /// ETag（服务器未返回时为 None）
+    pub etag: Option<String>,
+}
+
+impl RemoteEntry {
+    /// 从远程文件信息构建
+    ///
+    /// 路径不在同步根目录下，或包含空分段、`.`、`..`（可能指向同步根目录之外）时返回 None
+    pub fn from_file_info(
+        client: &WebDavClient,
+        remote_root: &str,
+        info: &FileInfo,
+    ) -> Option<Self> {
+        let rel_path = folder_relative_path(remote_root, &client.relative_path(&info.path))?;
+        if rel_path.is_empty() {
+            return None;
+        }
+        if !is_safe_rel_path(&rel_path) {
+            tracing::warn!(path = %info.path, "远程路径包含空分段、. 或 ..，已忽略");
+            return None;
+        }
+
+        Some(Self {
+            rel_path,
+            is_directory: info.is_directory,
+            size: info.size,
+            modified: info.modified,
+            etag: info.etag.clone(),
+        })
+    }
+
+    /// 转换为远程目录树缓存条目
+    pub fn to_cache_entry(&self, cached_at: i64) -> RemoteCacheEntry {
+        RemoteCacheEntry {
+            path: self.rel_path.clone(),
+            is_directory: self.is_directory,
+            size: self.size,
+            modified: self.modified,
+            etag: self.etag.clone(),
+            cached_at,
+        }
+    }
+}
+
+/// 对比本地、远程和快照，生成同步计划
+///
+/// 三者按路径有序归并（见 `merge`），逐个路径判定操作，路径按升序处理，计划中父目录在子项之前。
+/// 输入应已按路径排序（见 `merge::sort_by_path`），未排序的输入会先复制一份再排序
+///
+/// # 参数
+/// - folder: 同步文件夹配置（决定同步方向和冲突策略）
+/// - local: 本地扫描结果
+/// - remote: 远程列表
+/// - snapshot: 上次同步后的文件元数据
+pub fn compare(
+    folder: &SyncFolderConfig,
+    local: &[LocalEntry],
+    remote: &[RemoteEntry],
+    snapshot: &[FileMetadata],
+) -> SyncPlan {
+    let local = sorted(local);
+    let remote = sorted(remote);
+    let snapshot = sorted(snapshot);
+    let first_sync = !snapshot.iter().any(|m| m.synced_at.is_some());
+
+    let mut plan = SyncPlan {
+        plan_id: uuid::Uuid::new_v4().to_string(),
+        folder_id: folder.id.clone(),
+        generated_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+
+    let entries = MergeJoin::new(local.iter(), remote.iter(), snapshot.iter());
+    for (l, r, row) in entries {
+        let s = row.filter(|m| m.synced_at.is_some());
+        if s.is_some_and(|m| !m.is_directory) {
+            plan.synced_files += 1;
+        }
+        let Some(path) = l
+            .map(|e| e.rel_path.as_str())
+            .or(r.map(|e| e.rel_path.as_str()))
+            .or(s.map(|m| m.path.as_str()))
+        else {
+            continue;
+        };
+
+        let Some(item) = decide(folder, first_sync, path, l, r, s)
+            .filter(|item| allowed_by_direction(&folder.sync_direction, item.action))
+        else {
+            continue;
+        };
+        match row.map(|m| m.status.as_str()) {
+            Some(file_status::IGNORED) => plan.skipped.push(SkippedPath {
+                rel_path: item.rel_path,
+                action: item.action,
+                reason: "ignored by user".to_string(),
+            }),
+            Some(file_status::PINNED) if item.action == PlanAction::DeleteLocal => {
+                let restore =
+                    l.filter(|_| allowed_by_direction(&folder.sync_direction, PlanAction::Upload));
+                if let Some(l) = restore {
+                    plan.push(PlanItem {
+                        action: PlanAction::Upload,
+                        size: l.size,
+                        local_size: Some(l.size),
+                        remote_size: None,
+                        reason: Some("pinned locally, restoring remote copy".to_string()),
+                        ..item
+                    });
+                }
+            }
+            _ => plan.push(item),
+        }
+    }
+
+    // 加密文件夹的远程路径由同步清单映射，不做移动识别
+    if !folder.encryption.enabled {
+        pair_moves(&mut plan, &local, &snapshot, same_inode);
+    }
+
+    apply_constraints(&mut plan, &FileConstraints::from_folder(folder));
+
+    // 删除时子项在前，便于按顺序执行
+    plan.local_deletions.reverse();
+    plan.remote_deletions.reverse();
+
+    plan
+}
+
+/// 把超出文件大小上限或扩展名过滤的上传、下载移入跳过列表
+///
+/// 只过滤传输，删除和远程移动照常执行
+fn apply_constraints(plan: &mut SyncPlan, constraints: &FileConstraints) {
+    if constraints.is_unrestricted() {
+        return;
+    }
+
+    let mut skipped = Vec::new();
+    let mut allowed = |item: &PlanItem, size: u64| {
+        let reason = constraints
+            .violation(&item.rel_path, size)
+            .filter(|_| !item.is_directory);
+        match reason {
+            Some(reason) => {
+                skipped.push(SkippedPath {
+                    rel_path: item.rel_path.clone(),
+                    action: item.action,
+                    reason,
+                });
+                false
+            }
+            None => true,
+        }
+    };
+    plan.uploads.retain(|item| allowed(item, item.size));
+    plan.downloads.retain(|item| allowed(item, item.size));
+    plan.keep_both.retain(|item| {
+        let size = item.local_size.max(item.remote_size).unwrap_or(0);
+        allowed(item, size)
+    });
+
+    plan.upload_bytes = plan.uploads.iter().map(|item| item.size).sum::<u64>()
+        + plan
+            .keep_both
+            .iter()
+            .filter_map(|item| item.local_size)
+            .sum::<u64>();
+    plan.download_bytes = plan.downloads.iter().map(|item| item.size).sum::<u64>()
+        + plan
+            .keep_both
+            .iter()
+            .filter_map(|item| item.remote_size)
+            .sum::<u64>();
+    plan.skipped.extend(skipped);
+}
+
+/// 判定单个路径的操作
+///
+/// first_sync 为 true 时（文件夹尚未同步过）按首次同步策略处理
+fn decide(
+    folder: &SyncFolderConfig,
+    first_sync: bool,
+    path: &str,
+    local: Option<&LocalEntry>,
+    remote: Option<&RemoteEntry>,
+    snapshot: Option<&FileMetadata>,
+) -> Option<PlanItem> {
+    let item = |action: PlanAction, size: u64, is_directory: bool| PlanItem {
+        rel_path: path.to_string(),
+        action,
+        is_directory,
+        size,
+        local_size: local.map(|l| l.size),
+        remote_size: remote.map(|r| r.size),
+        reason: None,
+        from_path: None,
+    };
+    let conflict = || item(PlanAction::Conflict, 0, false);
+    let strategy = first_sync.then_some(folder.first_sync_strategy.as_str());
+    let mirror_local = folder.sync_direction != sync_direction::DOWNLOAD_ONLY;
+    let with_reason = |mut item: PlanItem| {
+        item.reason = strategy.map(|strategy| format!("first sync: {}", strategy));
+        item
+    };
+
+    match (local, remote, snapshot) {
+        (Some(l), Some(r), Some(s)) => {
+            if l.is_directory && r.is_directory {
+                return None;
+            }
+            match (local_changed(l, s), remote_changed(r, s)) {
+                (false, false) => None,
+                (true, false) => Some(item(PlanAction::Upload, l.size, l.is_directory)),
+                (false, true) => Some(item(PlanAction::Download, r.size, r.is_directory)),
+                (true, true) => Some(resolve_conflict(folder, conflict(), l, r)),
+            }
+        }
+        (Some(l), Some(r), None) => {
+            if l.is_directory && r.is_directory {
+                return None;
+            }
+            if l.is_directory == r.is_directory && l.size == r.size {
+                return None;
+            }
+            let upload = || with_reason(item(PlanAction::Upload, l.size, l.is_directory));
+            let download = || with_reason(item(PlanAction::Download, r.size, r.is_directory));
+            match strategy {
+                Some(first_sync_strategy::PREFER_LOCAL) => Some(upload()),
+                Some(first_sync_strategy::PREFER_REMOTE) => Some(download()),
+                Some(first_sync_strategy::MIRROR) if mirror_local => Some(upload()),
+                Some(first_sync_strategy::MIRROR) => Some(download()),
+                // 只有双向同步且两侧都是文件时才能改名保留
+                Some(_)
+                    if folder.sync_direction == sync_direction::BIDIRECTIONAL
+                        && !l.is_directory
+                        && !r.is_directory =>
+                {
+                    Some(with_reason(item(PlanAction::KeepBoth, 0, false)))
+                }
+                _ => Some(resolve_conflict(folder, conflict(), l, r)),
+            }
+        }
+        (Some(l), None, Some(s)) => {
+            if !l.is_directory && local_changed(l, s) {
+                let mut upload = item(PlanAction::Upload, l.size, false);
+                upload.reason = Some("modified locally but deleted remotely".to_string());
+                Some(upload)
+            } else {
+                Some(item(PlanAction::DeleteLocal, l.size, l.is_directory))
+            }
+        }
+        (Some(l), None, None) => match strategy {
+            Some(first_sync_strategy::MIRROR) if !mirror_local => Some(with_reason(item(
+                PlanAction::DeleteLocal,
+                l.size,
+                l.is_directory,
+            ))),
+            _ => Some(item(PlanAction::Upload, l.size, l.is_directory)),
+        },
+        (None, Some(r), Some(s)) => {
+            if !r.is_directory && remote_changed(r, s) {
+                let mut download = item(PlanAction::Download, r.size, false);
+                download.reason = Some("modified remotely but deleted locally".to_string());
+                Some(download)
+            } else {
+                Some(item(PlanAction::DeleteRemote, r.size, r.is_directory))
+            }
+        }
+        (None, Some(r), None) => match strategy {
+            Some(first_sync_strategy::MIRROR) if mirror_local => Some(with_reason(item(
+                PlanAction::DeleteRemote,
+                r.size,
+                r.is_directory,
+            ))),
+            _ => Some(item(PlanAction::Download, r.size, r.is_directory)),
+        },
+        (None, None, _) => None,
+    }
+}
+
+/// 本地文件自上次同步后是否有变化
+fn local_changed(local: &LocalEntry, snapshot: &FileMetadata) -> bool {
+    if local.is_directory {
+        return false;
+    }
+    local.size as i64 != snapshot.size
+        || local
+            .modified
+            .map(|m| m != snapshot.modified_at)
+            .unwrap_or(true)
+}
+
+/// 远程文件自上次同步后是否有变化
+fn remote_changed(remote: &RemoteEntry, snapshot: &FileMetadata) -> bool {
+    if remote.is_directory {
+        return false;
+    }
+    let synced_at = snapshot.synced_at.unwrap_or(0);
+    remote.size as i64 != snapshot.remote_size.unwrap_or(snapshot.size)
+        || remote.modified.map(|m| m > synced_at).unwrap_or(false)
+}
+
+/// 远程自上次同步后新增、修改或删除的文件路径
+fn remote_changes(remote: &[RemoteEntry], snapshot: &[FileMetadata]) -> HashSet<String> {
+    let remote = sorted(remote);
+    let snapshot = sorted(snapshot);
+
+    MergeJoin::new(remote.iter(), snapshot.iter(), [] as [&LocalEntry; 0])
+        .filter_map(|(r, s, _)| match (r, s) {
+            (Some(r), s) if !r.is_directory && s.is_none_or(|s| remote_changed(r, s)) => {
+                Some(r.rel_path.clone())
+            }
+            (None, Some(s)) if !s.is_directory => Some(s.path.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 按文件夹的冲突策略解决冲突
+///
+/// 策略为 ask 时保留冲突，交给用户处理
+fn resolve_conflict(
+    folder: &SyncFolderConfig,
+    mut conflict: PlanItem,
+    local: &LocalEntry,
+    remote: &RemoteEntry,
+) -> PlanItem {
+    let local_wins = match folder.conflict_resolution.as_str() {
+        conflict_resolution::LOCAL_WINS => Some(true),
+        conflict_resolution::REMOTE_WINS => Some(false),
+        conflict_resolution::NEWER_WINS => match (local.modified, remote.modified) {
+            (Some(l), Some(r)) => Some(l >= r),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match local_wins {
+        Some(true) => {
+            conflict.action = PlanAction::Upload;
+            conflict.size = local.size;
+            conflict.is_directory = local.is_directory;
+        }
+        Some(false) => {
+            conflict.action = PlanAction::Download;
+            conflict.size = remote.size;
+            conflict.is_directory = remote.is_directory;
+        }
+        None => return conflict,
+    }
+
+    conflict.reason = Some(format!(
+        "conflict resolved by {}",
+        folder.conflict_resolution
+    ));
+    conflict
+}
+
+/// 生成冲突副本的路径（在文件名和扩展名之间插入标签）
+///
+/// # 参数
+/// - rel_path: 原文件的相对路径
+/// - label: 插入的标签（如 `conflicted copy 2024-01-01 120000`）
+pub fn conflict_copy_path(rel_path: &str, label: &str) -> String {
+    let (dir, name) = match rel_path.rsplit_once('/') {
+        Some((dir, name)) => (Some(dir), name),
+        None => (None, rel_path),
+    };
+    // 以点开头且没有其他点的文件（如 .bashrc）视为没有扩展名
+    let (stem, ext) = match name.rfind('.') {
+        Some(i) if i > 0 => name.split_at(i),
+        _ => (name, ""),
+    };
+
+    let copy = format!("{} ({}){}", stem, label, ext);
+    match dir {
+        Some(dir) => format!("{}/{}", dir, copy),
+        None => copy,
+    }
+}
+
+/// 把远程删除与新文件的上传配对为远程移动
+///
+/// 只配对文件：删除路径的快照与新文件的大小、修改时间一致，并由 `same_file` 确认为同一文件。
+/// 每个删除和上传最多配对一次
+///
+/// # 参数
+/// - plan: 同步计划（配对的删除和上传被替换为移动）
+/// - local: 本地扫描结果（按路径排序）
+/// - snapshot: 上次同步后的文件元数据（按路径排序）
+/// - same_file: 判断新文件与删除路径的快照是否为同一文件
+fn pair_moves<F>(
+    plan: &mut SyncPlan,
+    local: &[LocalEntry],
+    snapshot: &[FileMetadata],
+    mut same_file: F,
+) where
+    F: FnMut(&LocalEntry, &FileMetadata) -> bool,
+{
+    let mut i = 0;
+    while i < plan.remote_deletions.len() {
+        let deletion = &plan.remote_deletions[i];
+        let Some(s) = merge::find(snapshot, &deletion.rel_path)
+            .filter(|s| s.synced_at.is_some() && !deletion.is_directory && !s.is_directory)
+        else {
+            i += 1;
+            continue;
+        };
+
+        let found = plan.uploads.iter().position(|upload| {
+            is_new_file(upload)
+                && merge::find(local, &upload.rel_path).is_some_and(|l| {
+                    l.size as i64 == s.size && l.modified == Some(s.modified_at) && same_file(l, s)
+                })
+        });
+        let Some(j) = found else {
+            i += 1;
+            continue;
+        };
+
+        let deletion = plan.remote_deletions.remove(i);
+        let upload = plan.uploads.remove(j);
+        plan.upload_bytes -= upload.size;
+        plan.moves.push(PlanItem {
+            action: PlanAction::Move,
+            remote_size: deletion.remote_size,
+            reason: Some("renamed locally".to_string()),
+            from_path: Some(deletion.rel_path),
+            ..upload
+        });
+    }
+}
+
+/// 上传条目是否为远程和快照中都不存在的新文件
+fn is_new_file(item: &PlanItem) -> bool {
+    !item.is_directory && item.remote_size.is_none() && item.reason.is_none()
+}
+
+/// 新文件与快照的 inode 相同（任一方未记录 inode 时视为无法判断）
+fn same_inode(local: &LocalEntry, snapshot: &FileMetadata) -> bool {
+    matches!((local.inode, snapshot.inode), (Some(l), Some(s)) if l as i64 == s)
+}
+
+/// 按内容哈希配对无法通过 inode 判断的移动
+///
+/// 只在删除路径的快照缓存了内容哈希（SHA-256）时计算新文件的哈希
+fn pair_moves_by_hash(
+    plan: &mut SyncPlan,
+    root: &Path,
+    normalizer: &PathNormalizer,
+    local: &[LocalEntry],
+    snapshot: &[FileMetadata],
+) {
+    let local = sorted(local);
+    let snapshot = sorted(snapshot);
+
+    pair_moves(plan, &local, &snapshot, |l, s| {
+        if l.inode.is_some() && s.inode.is_some() {
+            return false;
+        }
+        let Some(expected) = s.hash.as_deref() else {
+            return false;
+        };
+        normalizer
+            .local_path(root, &l.rel_path)
+            .and_then(|path| hash_file(&path))
+            .is_ok_and(|actual| actual == expected)
+    });
+}
+
+/// 按同步方向过滤操作
+fn allowed_by_direction(direction: &str, action: PlanAction) -> bool {
+    match direction {
+        sync_direction::UPLOAD_ONLY => {
+            matches!(action, PlanAction::Upload | PlanAction::DeleteRemote)
+        }
+        sync_direction::DOWNLOAD_ONLY => {
+            matches!(action, PlanAction::Download | PlanAction::DeleteLocal)
+        }
+        _ => true,
+    }
+}
+
+/// 为同步文件夹生成同步计划
+///
+/// 执行完整的对比阶段：扫描本地、递归列出远程、读取快照并对比。
+/// 传入 `scans` 时本地目录增量扫描（见 `incremental`），远程自上次同步后有变化的文件
+/// 在对比前重新读取本地状态，推迟上传的文件标记为脏路径。
+/// 远程列表使用完整 PROPFIND 而不是 sync-collection，避免预览时推进同步令牌。
+/// 对比前由 `PathNormalizer` 处理本地不合法的文件名、大小写冲突和文件名的 Unicode 形式（统一为 NFC），
+/// 以重命名副本保存的大小写冲突文件记入 `SyncPlan::case_collisions`，
+/// 本地文件名与远程路径的映射在对比前读取、对比后保存（见 `database::name_mappings`）。
+/// 映射与远程目录树缓存只在 `persist` 为 true（执行同步）时写入，预览计划不修改数据库。
+/// 启用端到端加密时，远程条目先还原为真实路径和明文大小再参与对比。
+/// 开启 `ignore_system_files` 时，本地、远程和快照中的 AppleDouble 附属文件都不参与对比。
+/// 执行同步时远程列表同时写入远程目录树缓存（见 `database::remote_cache`）。
+/// 服务器在多状态响应中报告读取失败的远程条目（如 423 Locked）记入跳过列表，
+/// 本次不对该路径做任何操作，避免把读取失败当作远程已删除。
+/// 对比后仍在写入的本地文件从上传中移出（见 `stability`），
+/// 最近修改过的文件排到其他传输之前（见 `priority`）。
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - client: WebDAV 客户端
+/// - folder: 同步文件夹配置
+/// - normalizer: 本地路径规范化器
+/// - cipher: 文件夹的加密器（未启用加密时为 None）
+/// - scans: 上次扫描的结果与脏路径（为 None 时完整扫描，如预览计划）
+/// - persist: 是否写入远程目录树缓存与文件名映射（预览计划时为 false）
+/// - quiet_period: 本地文件最后修改后需要保持不变的时间（秒）
+/// - priority_window: 传输优先窗口（秒，0 表示不调整顺序）
+#[allow(clippy::too_many_arguments)]
+pub async fn plan_folder(
+    db: &Database,
+    client: &WebDavClient,
+    folder: &SyncFolderConfig,
+    normalizer: &mut PathNormalizer,
+    cipher: Option<&FolderCipher>,
+    scans: Option<&ScanCache>,
+    persist: bool,
+    quiet_period: u32,
+    priority_window: u32,
+) -> Result<SyncPlan> {
+    let filter = SyncFilter::from_folder(folder);
+
+    let symlink_policy = SymlinkPolicy::from_config(&folder.symlink_policy);
+    let (mut local, incremental) = match scans {
+        Some(scans) => {
+            let scan = scans
+                .scan(&folder.id, &folder.local_path, &filter, symlink_policy)
+                .await?;
+            (scan.entries, scan.incremental)
+        }
+        None => {
+            let local_root = folder.local_path.clone();
+            let local_filter = filter.clone();
+            let entries = tokio::task::spawn_blocking(move || {
+                scan_local(&local_root, &local_filter, symlink_policy)
+            })
+            .await
+            .map_err(|e| SyncError::Unknown(format!("Local scan task failed: {}", e)))??;
+            (entries, false)
+        }
+    };
+
+    let sync_folder_id = folder_keys::resolve(db, &folder.id).await?;
+    let snapshot = file_metadata::get_by_folder(db, sync_folder_id).await?;
+
+    // 混淆后的文件名无法匹配忽略规则，还原为真实路径后再过滤
+    let obfuscated = cipher.is_some_and(|c| c.obfuscates_filenames());
+    let list_filter = if obfuscated {
+        SyncFilter::default()
+    } else {
+        filter.clone()
+    };
+    // 转换后不再保留服务器返回的原始列表，大型文件夹的远程条目只在内存中保存一份
+    let RemoteListing { files, failures } =
+        list_recursive_detailed(client, &folder.remote_path, &list_filter).await?;
+    let mut remote: Vec<RemoteEntry> = files
+        .into_iter()
+        .filter_map(|info| RemoteEntry::from_file_info(client, &folder.remote_path, &info))
+        .collect();
+    if let Some(cipher) = cipher {
+        cipher.register(
+            local
+                .iter()
+                .map(|e| e.rel_path.as_str())
+                .chain(snapshot.iter().map(|m| m.path.as_str())),
+        );
+        remote = cipher.decode_entries(remote);
+        remote.retain(|e| !filter.is_excluded(&e.rel_path));
+    }
+    let apple_double = filter.apple_double_files(
+        local
+            .iter()
+            .map(|e| e.rel_path.as_str())
+            .chain(remote.iter().map(|e| e.rel_path.as_str()))
+            .chain(snapshot.iter().map(|m| m.path.as_str())),
+    );
+    if !apple_double.is_empty() {
+        local.retain(|e| !apple_double.contains(&e.rel_path));
+        remote.retain(|e| !apple_double.contains(&e.rel_path));
+    }
+    if persist {
+        let cached_at = chrono::Utc::now().timestamp();
+        remote_cache::replace(
+            db,
+            &folder.id,
+            remote.iter().map(|entry| entry.to_cache_entry(cached_at)),
+        )
+        .await?;
+    }
+    let unreadable: Vec<SkippedPath> = failures
+        .iter()
+        .filter_map(|failure| unreadable_remote(client, &folder.remote_path, cipher, failure))
+        .filter(|skip| !filter.is_excluded(&skip.rel_path))
+        .collect();
+    local.retain(|entry| !unreadable.iter().any(|skip| skip.covers(&entry.rel_path)));
+    normalizer.restore_mappings(name_mappings::list(db, &folder.id).await?);
+    let mut skipped = normalizer.normalize(&mut remote, &mut local);
+    skipped.extend(unreadable);
+    if persist {
+        name_mappings::replace(db, &folder.id, &normalizer.mappings()).await?;
+    }
+
+    // 早期以其他 Unicode 形式记录的路径按 NFC 形式对比，两种形式都有记录时保留 NFC 的记录
+    let recorded: HashSet<String> = snapshot.iter().map(|m| m.path.clone()).collect();
+    let mut snapshot: Vec<FileMetadata> = snapshot
+        .into_iter()
+        .filter_map(|mut m| {
+            let nfc = to_nfc(&m.path);
+            if nfc != m.path {
+                if recorded.contains(&nfc) {
+                    return None;
+                }
+                m.path = nfc;
+            }
+            Some(m)
+        })
+        .filter(|m| !filter.is_excluded(&m.path) && !apple_double.contains(&m.path))
+        .filter(|m| !skipped.iter().any(|s| s.covers(&m.path)))
+        .collect();
+    // 规范化可能改变路径顺序，对比前在原处重新排序，归并和查找不再复制或建立引用数组
+    sort_by_path(&mut local);
+    sort_by_path(&mut remote);
+    sort_by_path(&mut snapshot);
+    if incremental {
+        let stale = remote_changes(&remote, &snapshot);
+        incremental::refresh_entries(&mut local, &folder.local_path, normalizer, &stale).await;
+    }
+
+    let mut plan = compare(folder, &local, &remote, &snapshot);
+    plan.case_collisions = normalizer.case_collisions();
+    stability::defer_unstable(
+        &mut plan,
+        &folder.local_path,
+        normalizer,
+        &local,
+        quiet_period,
+    )
+    .await;
+    if let Some(scans) = scans {
+        for item in &plan.deferred {
+            scans.mark_dirty(&folder.id, &normalizer.local_rel_path(&item.rel_path));
+        }
+    }
+    let now = chrono::Utc::now().timestamp();
+    priority::prioritize_recent(&mut plan, &local, &remote, priority_window, now);
+    if !folder.encryption.enabled && !plan.remote_deletions.is_empty() && !plan.uploads.is_empty() {
+        let root = folder.local_path.clone();
+        let normalizer = normalizer.clone();
+        plan = tokio::task::spawn_blocking(move || {
+            pair_moves_by_hash(&mut plan, &root, &normalizer, &local, &snapshot);
+            plan
+        })
+        .await
+        .map_err(|e| SyncError::Unknown(format!("Move detection task failed: {}", e)))?;
+    }
+    skipped.append(&mut plan.skipped);
+    plan.skipped = skipped;
+    Ok(plan)
+}
+
+/// 将远程读取失败的条目转换为跳过条目
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_root: 同步文件夹的远程根路径
+/// - cipher: 文件夹的加密器（用于还原混淆的文件名）
+/// - failure: 多状态响应中失败的条目
+///
+/// # 返回
+/// 路径不在同步根目录下或无法还原时返回 None
+fn unreadable_remote(
+    client: &WebDavClient,
+    remote_root: &str,
+    cipher: Option<&FolderCipher>,
+    failure: &RemoteItemError,
+) -> Option<SkippedPath> {
+    let rel_path = folder_relative_path(remote_root, &client.relative_path(&failure.path))?;
+    let rel_path = match cipher {
+        Some(cipher) => cipher.real_rel_path(&rel_path)?,
+        None => rel_path,
+    };
+    if rel_path.is_empty() {
+        return None;
+    }
+
+    Some(SkippedPath {
+        rel_path,
+        action: PlanAction::Download,
+        reason: format!(
+            "Remote server returned status {} for this item",
+            failure.status
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn folder(direction: &str, resolution: &str) -> SyncFolderConfig {
+        SyncFolderConfig {
+            id: "folder-1".to_string(),
+            name: "Docs".to_string(),
+            local_path: PathBuf::from("/tmp/docs"),
+            remote_path: "/docs".to_string(),
+            server_id: "server-1".to_string(),
+            sync_direction: direction.to_string(),
+            sync_interval: 30,
+            auto_sync: true,
+            ignore_patterns: vec![],
+            conflict_resolution: resolution.to_string(),
+            selective_exclusions: vec![],
+            symlink_policy: "skip".to_string(),
+            encryption: Default::default(),
+            compression: Default::default(),
+            max_delete_ratio: 0.2,
+            first_sync_strategy: "merge-keep-both".to_string(),
+            max_file_size: None,
+            include_extensions: vec![],
+            exclude_extensions: vec![],
+            ignore_system_files: true,
+            sync_policy: None,
+        }
+    }
+
+    fn local(path: &str, size: u64, modified: i64) -> LocalEntry {
+        LocalEntry {
+            rel_path: path.to_string(),
+            is_directory: false,
+            size,
+            modified: Some(modified),
+            inode: None,
+        }
+    }
+
+    fn remote(path: &str, size: u64, modified: i64) -> RemoteEntry {
+        RemoteEntry {
+            rel_path: path.to_string(),
+            is_directory: false,
+            size,
+            modified: Some(modified),
+            etag: None,
+        }
+    }
+
+    fn snapshot(path: &str, size: i64, modified: i64, synced_at: i64) -> FileMetadata {
+        FileMetadata {
+            id: None,
+            path: path.to_string(),
+            hash: None,
+            size,
+            modified_at: modified,
+            synced_at: Some(synced_at),
+            sync_folder_id: 1,
+            is_directory: false,
+            status: "synced".to_string(),
+            created_at: None,
+            updated_at: None,
+            remote_size: None,
+            inode: None,
+        }
+    }
+
+    #[test]
+    fn test_remote_entry_rejects_path_traversal() {
+        let config = crate::webdav::mock_server_config("http://localhost/dav");
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let info = |path: &str| FileInfo {
+            path: path.to_string(),
+            name: String::new(),
+            is_directory: false,
+            size: 1,
+            modified: None,
+            etag: None,
+        };
+
+        let entry = RemoteEntry::from_file_info(&client, "/root", &info("/dav/root/docs/a.txt"));
+        assert_eq!(entry.map(|e| e.rel_path), Some("docs/a.txt".to_string()));
+        for href in [
+            "/dav/root/../../.bashrc",
+            "/dav/root/docs/../../../etc/passwd",
+            "/dav/root/./a.txt",
+            "/dav/root/docs//a.txt",
+        ] {
+            assert!(
+                RemoteEntry::from_file_info(&client, "/root", &info(href)).is_none(),
+                "{}",
+                href
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_and_deleted_files() {
+        let folder = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        let plan = compare(
+            &folder,
+            &[local("new-local.txt", 10, 100), local("kept.txt", 5, 100)],
+            &[
+                remote("new-remote.txt", 20, 100),
+                remote("kept.txt", 5, 50),
+                remote("gone-local.txt", 7, 50),
+            ],
+            &[
+                snapshot("kept.txt", 5, 100, 100),
+                snapshot("gone-local.txt", 7, 50, 100),
+                snapshot("gone-remote.txt", 3, 50, 100),
+            ],
+        );
+
+        assert_eq!(plan.uploads.len(), 1);
+        assert_eq!(plan.uploads[0].rel_path, "new-local.txt");
+        assert_eq!(plan.upload_bytes, 10);
+        assert_eq!(plan.downloads.len(), 1);
+        assert_eq!(plan.download_bytes, 20);
+        assert_eq!(plan.remote_deletions[0].rel_path, "gone-local.txt");
+        // 本地已不存在且远程也已删除的文件不产生操作
+        assert!(plan.local_deletions.is_empty());
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_ignored_and_pinned_files() {
+        let bidirectional = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        let mut ignored = snapshot("ignored.txt", 5, 100, 150);
+        ignored.status = file_status::IGNORED.to_string();
+        let mut pinned = snapshot("pinned.txt", 5, 100, 150);
+        pinned.status = file_status::PINNED.to_string();
+        // 从未同步过、只记录了状态的文件
+        let mut ignored_new = snapshot("ignored-new.txt", 0, 0, 0);
+        ignored_new.synced_at = None;
+        ignored_new.status = file_status::IGNORED.to_string();
+
+        let plan = compare(
+            &bidirectional,
+            &[
+                local("ignored.txt", 6, 200),
+                local("pinned.txt", 5, 100),
+                local("ignored-new.txt", 3, 200),
+            ],
+            &[],
+            &[ignored, pinned, ignored_new],
+        );
+
+        // 远程已删除 pinned.txt，改为重新上传而不是删除本地文件
+        assert!(plan.local_deletions.is_empty());
+        assert_eq!(plan.uploads.len(), 1);
+        assert_eq!(plan.uploads[0].rel_path, "pinned.txt");
+        assert_eq!(plan.upload_bytes, 5);
+        let skipped: Vec<&str> = plan.skipped.iter().map(|s| s.rel_path.as_str()).collect();
+        assert_eq!(skipped, vec!["ignored-new.txt", "ignored.txt"]);
+
+        // 只下载的文件夹不会重新上传，也不会删除 pinned 文件
+        let download_only = folder(sync_direction::DOWNLOAD_ONLY, conflict_resolution::ASK);
+        let mut pinned = snapshot("pinned.txt", 5, 100, 150);
+        pinned.status = file_status::PINNED.to_string();
+        let plan = compare(
+            &download_only,
+            &[local("pinned.txt", 5, 100)],
+            &[],
+            &[pinned],
+        );
+        assert_eq!(plan.total_actions(), 0);
+    }
+
+    #[test]
+    fn test_both_modified_is_conflict() {
+        let folder = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        let plan = compare(
+            &folder,
+            &[local("a.txt", 11, 200)],
+            &[remote("a.txt", 12, 300)],
+            &[snapshot("a.txt", 10, 100, 150)],
+        );
+
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].local_size, Some(11));
+        assert_eq!(plan.conflicts[0].remote_size, Some(12));
+    }
+
+    #[test]
+    fn test_newer_wins_resolves_conflict() {
+        let folder = folder(
+            sync_direction::BIDIRECTIONAL,
+            conflict_resolution::NEWER_WINS,
+        );
+        let plan = compare(
+            &folder,
+            &[local("a.txt", 11, 200)],
+            &[remote("a.txt", 12, 300)],
+            &[snapshot("a.txt", 10, 100, 150)],
+        );
+
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.downloads.len(), 1);
+        assert!(plan.downloads[0].reason.is_some());
+    }
+
+    #[test]
+    fn test_compressed_remote_size_is_not_a_change() {
+        let folder = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        let mut compressed = snapshot("notes.txt", 4096, 100, 150);
+        compressed.remote_size = Some(900);
+
+        let plan = compare(
+            &folder,
+            &[local("notes.txt", 4096, 100)],
+            &[remote("notes.txt", 900, 140)],
+            std::slice::from_ref(&compressed),
+        );
+        assert!(plan.downloads.is_empty());
+        assert!(plan.uploads.is_empty());
+
+        let plan = compare(
+            &folder,
+            &[local("notes.txt", 4096, 100)],
+            &[remote("notes.txt", 950, 140)],
+            &[compressed],
+        );
+        assert_eq!(plan.downloads.len(), 1);
+    }
+
+    #[test]
+    fn test_local_rename_becomes_move() {
+        let folder = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        let mut renamed = local("disk-renamed.iso", 2048, 100);
+        renamed.inode = Some(7);
+        let mut other = local("other.iso", 2048, 100);
+        other.inode = Some(8);
+        let mut synced = snapshot("disk.iso", 2048, 100, 150);
+        synced.inode = Some(7);
+
+        let plan = compare(
+            &folder,
+            &[renamed.clone(), other],
+            &[remote("disk.iso", 2048, 140)],
+            std::slice::from_ref(&synced),
+        );
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].rel_path, "disk-renamed.iso");
+        assert_eq!(plan.moves[0].from_path.as_deref(), Some("disk.iso"));
+        assert!(plan.remote_deletions.is_empty());
+        assert_eq!(plan.uploads.len(), 1);
+        assert_eq!(plan.upload_bytes, 2048);
+
+        // inode 相同但内容已修改时不视为重命名
+        renamed.size = 4096;
+        let plan = compare(
+            &folder,
+            &[renamed],
+            &[remote("disk.iso", 2048, 140)],
+            &[synced],
+        );
+        assert!(plan.moves.is_empty());
+        assert_eq!(plan.remote_deletions.len(), 1);
+    }
+
+    #[test]
+    fn test_first_sync_strategies() {
+        let locals = [local("both.txt", 10, 100), local("local-only.txt", 1, 100)];
+        let remotes = [
+            remote("both.txt", 20, 200),
+            remote("remote-only.txt", 2, 100),
+        ];
+        let plan_with = |direction: &str, strategy: &str| {
+            let mut folder = folder(direction, conflict_resolution::ASK);
+            folder.first_sync_strategy = strategy.to_string();
+            compare(&folder, &locals, &remotes, &[])
+        };
+        let paths = |items: &[PlanItem]| -> Vec<String> {
+            items.iter().map(|item| item.rel_path.clone()).collect()
+        };
+
+        let plan = plan_with(
+            sync_direction::BIDIRECTIONAL,
+            first_sync_strategy::MERGE_KEEP_BOTH,
+        );
+        assert_eq!(paths(&plan.keep_both), vec!["both.txt"]);
+        assert_eq!(paths(&plan.uploads), vec!["local-only.txt"]);
+        assert_eq!(paths(&plan.downloads), vec!["remote-only.txt"]);
+        assert_eq!(plan.upload_bytes, 11);
+        assert_eq!(plan.download_bytes, 22);
+
+        let plan = plan_with(
+            sync_direction::BIDIRECTIONAL,
+            first_sync_strategy::PREFER_REMOTE,
+        );
+        assert!(plan.keep_both.is_empty());
+        assert_eq!(paths(&plan.downloads), vec!["both.txt", "remote-only.txt"]);
+
+        let plan = plan_with(sync_direction::BIDIRECTIONAL, first_sync_strategy::MIRROR);
+        assert_eq!(paths(&plan.uploads), vec!["both.txt", "local-only.txt"]);
+        assert_eq!(paths(&plan.remote_deletions), vec!["remote-only.txt"]);
+        assert!(plan.downloads.is_empty());
+
+        let plan = plan_with(sync_direction::DOWNLOAD_ONLY, first_sync_strategy::MIRROR);
+        assert_eq!(paths(&plan.downloads), vec!["both.txt", "remote-only.txt"]);
+        assert_eq!(paths(&plan.local_deletions), vec!["local-only.txt"]);
+
+        // 已同步过的文件夹按冲突策略处理
+        let mut folder = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        folder.first_sync_strategy = first_sync_strategy::MIRROR.to_string();
+        let plan = compare(
+            &folder,
+            &locals,
+            &remotes,
+            &[snapshot("other.txt", 1, 100, 100)],
+        );
+        assert_eq!(paths(&plan.conflicts), vec!["both.txt"]);
+        assert_eq!(paths(&plan.downloads), vec!["remote-only.txt"]);
+    }
+
+    #[test]
+    fn test_constraints_skip_transfers() {
+        let mut folder = folder(sync_direction::BIDIRECTIONAL, conflict_resolution::ASK);
+        folder.max_file_size = Some(100);
+        folder.exclude_extensions = vec!["iso".to_string()];
+        let plan = compare(
+            &folder,
+            &[local("big.bin", 500, 100), local("small.txt", 10, 100)],
+            &[
+                remote("disk.iso", 50, 100),
+                remote("gone-local.bin", 500, 50),
+            ],
+            &[snapshot("gone-local.bin", 500, 50, 100)],
+        );
+
+        assert_eq!(plan.uploads.len(), 1);
+        assert_eq!(plan.upload_bytes, 10);
+        assert!(plan.downloads.is_empty());
+        assert_eq!(plan.download_bytes, 0);
+        let skipped: Vec<(&str, PlanAction)> = plan
+            .skipped
+            .iter()
+            .map(|s| (s.rel_path.as_str(), s.action))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("big.bin", PlanAction::Upload),
+                ("disk.iso", PlanAction::Download)
+            ]
+        );
+        // 超出大小上限的文件删除照常同步
+        assert_eq!(plan.remote_deletions.len(), 1);
+    }
+
+    #[test]
+    fn test_conflict_copy_path() {
+        let label = "conflicted copy 2024-01-01 120000";
+        assert_eq!(
+            conflict_copy_path("docs/report.final.pdf", label),
+            "docs/report.final (conflicted copy 2024-01-01 120000).pdf"
+        );
+        assert_eq!(
+            conflict_copy_path("Makefile", label),
+            "Makefile (conflicted copy 2024-01-01 120000)"
+        );
+        assert_eq!(
+            conflict_copy_path("home/.bashrc", label),
+            "home/.bashrc (conflicted copy 2024-01-01 120000)"
+        );
+    }
+
+    #[test]
+    fn test_direction_filters_actions() {
+        let folder = folder(sync_direction::UPLOAD_ONLY, conflict_resolution::ASK);
+        let plan = compare(
+            &folder,
+            &[local("up.txt", 1, 100)],
+            &[remote("down.txt", 1, 100)],
+            &[],
+        );
+
+        assert_eq!(plan.uploads.len(), 1);
+        assert!(plan.downloads.is_empty());
+    }
+}
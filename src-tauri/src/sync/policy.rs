@@ -0,0 +1,287 @@
+/// 自动同步策略
+///
+/// 自动触发同步前，根据文件夹（未单独设置时使用全局）的同步策略检查当前状态：
+/// - 按流量计费的网络：`pause_on_metered` 开启且系统报告网络按流量计费
+/// - 低电量：使用电池供电且电量低于 `min_battery_percent`
+/// - 静默时段：本地时间处于 `quiet_hours` 内
+///
+/// 任一条件成立时跳过本次自动同步，下次调度时重新检查；无法获取网络或电源状态时视为满足策略。
+/// 跳过原因保存在 `PolicySkips` 中，原因变化时推送 `sync://skipped` 事件
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{QuietHours, SyncFolderConfig, SyncPolicyConfig};
+use crate::system::{network, power};
+use crate::{Result, SyncError};
+
+/// 跳过自动同步的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// 当前网络按流量计费
+    MeteredNetwork,
+    /// 使用电池供电且电量过低
+    LowBattery,
+    /// 处于静默时段
+    QuietHours,
+}
+
+/// 检查同步策略所需的系统状态
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PolicyConditions {
+    /// 网络是否按流量计费（未知时为 None）
+    pub metered: Option<bool>,
+    /// 使用电池供电时的电量百分比（外接电源或未知时为 None）
+    pub battery_percent: Option<u8>,
+    /// 当前本地时间
+    pub local_time: NaiveTime,
+}
+
+impl PolicyConditions {
+    /// 获取当前的系统状态，只查询策略需要的项目
+    pub async fn current(policy: &SyncPolicyConfig) -> Self {
+        let metered = if policy.pause_on_metered {
+            network::network_state().await.metered
+        } else {
+            None
+        };
+        let battery_percent = if policy.min_battery_percent.is_some() {
+            let state = power::power_state().await;
+            state
+                .battery_percent
+                .filter(|_| state.source == crate::constants::power_source::BATTERY)
+        } else {
+            None
+        };
+
+        Self {
+            metered,
+            battery_percent,
+            local_time: chrono::Local::now().time(),
+        }
+    }
+}
+
+/// 文件夹生效的同步策略（文件夹未单独设置时使用全局策略）
+pub fn effective<'a>(
+    global: &'a SyncPolicyConfig,
+    folder: &'a SyncFolderConfig,
+) -> &'a SyncPolicyConfig {
+    folder.sync_policy.as_ref().unwrap_or(global)
+}
+
+/// 校验同步策略
+///
+/// # 返回
+/// - Ok(()): 策略有效
+/// - Err(SyncError::ConfigError): 电量百分比超出范围或静默时段的时间格式无效
+pub fn validate(policy: &SyncPolicyConfig) -> Result<()> {
+    if let Some(percent) = policy.min_battery_percent {
+        if percent > 100 {
+            return Err(SyncError::ConfigError(format!(
+                "Minimum battery percent must be between 0 and 100, got: {}",
+                percent
+            )));
+        }
+    }
+    if let Some(quiet_hours) = &policy.quiet_hours {
+        parse_time(&quiet_hours.start)?;
+        parse_time(&quiet_hours.end)?;
+    }
+    Ok(())
+}
+
+/// 根据当前系统状态检查同步策略
+///
+/// # 返回
+/// 满足策略时返回 None，否则返回跳过自动同步的原因
+pub fn evaluate(policy: &SyncPolicyConfig, conditions: &PolicyConditions) -> Option<SkipReason> {
+    if policy.pause_on_metered && conditions.metered == Some(true) {
+        return Some(SkipReason::MeteredNetwork);
+    }
+    if let (Some(min), Some(percent)) = (policy.min_battery_percent, conditions.battery_percent) {
+        if percent < min {
+            return Some(SkipReason::LowBattery);
+        }
+    }
+    if policy
+        .quiet_hours
+        .as_ref()
+        .is_some_and(|quiet_hours| in_quiet_hours(quiet_hours, conditions.local_time))
+    {
+        return Some(SkipReason::QuietHours);
+    }
+    None
+}
+
+/// 时间是否处于静默时段（包含开始时间，不包含结束时间；时间格式无效时视为不在时段内）
+pub fn in_quiet_hours(quiet_hours: &QuietHours, time: NaiveTime) -> bool {
+    let (Ok(start), Ok(end)) = (parse_time(&quiet_hours.start), parse_time(&quiet_hours.end))
+    else {
+        return false;
+    };
+    if start <= end {
+        start <= time && time < end
+    } else {
+        // 跨越午夜
+        time >= start || time < end
+    }
+}
+
+/// 解析 HH:MM 格式的时间
+fn parse_time(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| {
+        SyncError::ConfigError(format!(
+            "Invalid quiet hours time (expected HH:MM): {}",
+            value
+        ))
+    })
+}
+
+/// 各文件夹最近一次因同步策略跳过自动同步的原因
+///
+/// 作为 Tauri State 管理，自动同步满足策略后清除对应文件夹的记录
+#[derive(Debug, Default)]
+pub struct PolicySkips {
+    /// 文件夹 ID -> 跳过原因
+    skips: Mutex<HashMap<String, SkipReason>>,
+}
+
+impl PolicySkips {
+    /// 记录文件夹的检查结果
+    ///
+    /// # 返回
+    /// 与上次记录的结果不同时返回 true
+    pub fn record(&self, folder_id: &str, reason: Option<SkipReason>) -> bool {
+        let mut skips = self.skips.lock().unwrap();
+        let previous = match reason {
+            Some(reason) => skips.insert(folder_id.to_string(), reason),
+            None => skips.remove(folder_id),
+        };
+        previous != reason
+    }
+
+    /// 清除所有记录（全局策略变化后使用）
+    pub fn clear(&self) {
+        self.skips.lock().unwrap().clear();
+    }
+
+    /// 当前因同步策略跳过自动同步的文件夹
+    pub fn snapshot(&self) -> BTreeMap<String, SkipReason> {
+        self.skips
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(folder_id, reason)| (folder_id.clone(), *reason))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    fn quiet(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_in_quiet_hours() {
+        let night = quiet("22:00", "07:00");
+        assert!(in_quiet_hours(&night, time("23:30")));
+        assert!(in_quiet_hours(&night, time("22:00")));
+        assert!(in_quiet_hours(&night, time("06:59")));
+        assert!(!in_quiet_hours(&night, time("07:00")));
+        assert!(!in_quiet_hours(&night, time("12:00")));
+
+        let lunch = quiet("12:00", "13:30");
+        assert!(in_quiet_hours(&lunch, time("12:45")));
+        assert!(!in_quiet_hours(&lunch, time("13:30")));
+
+        assert!(!in_quiet_hours(&quiet("late", "07:00"), time("23:00")));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let policy = SyncPolicyConfig {
+            pause_on_metered: true,
+            min_battery_percent: Some(20),
+            quiet_hours: Some(quiet("01:00", "05:00")),
+        };
+        let conditions = PolicyConditions {
+            metered: Some(false),
+            battery_percent: Some(50),
+            local_time: time("12:00"),
+        };
+        assert_eq!(evaluate(&policy, &conditions), None);
+
+        let metered = PolicyConditions {
+            metered: Some(true),
+            ..conditions.clone()
+        };
+        assert_eq!(
+            evaluate(&policy, &metered),
+            Some(SkipReason::MeteredNetwork)
+        );
+        assert_eq!(evaluate(&SyncPolicyConfig::default(), &metered), None);
+
+        let low_battery = PolicyConditions {
+            battery_percent: Some(15),
+            ..conditions.clone()
+        };
+        assert_eq!(
+            evaluate(&policy, &low_battery),
+            Some(SkipReason::LowBattery)
+        );
+
+        let night = PolicyConditions {
+            local_time: time("03:00"),
+            ..conditions.clone()
+        };
+        assert_eq!(evaluate(&policy, &night), Some(SkipReason::QuietHours));
+
+        // 无法获取状态时视为满足策略
+        assert_eq!(evaluate(&policy, &PolicyConditions::default()), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&SyncPolicyConfig::default()).is_ok());
+
+        let mut policy = SyncPolicyConfig {
+            min_battery_percent: Some(101),
+            ..Default::default()
+        };
+        assert!(validate(&policy).is_err());
+
+        policy.min_battery_percent = Some(30);
+        policy.quiet_hours = Some(quiet("22:00", "7am"));
+        assert!(validate(&policy).is_err());
+
+        policy.quiet_hours = Some(quiet("22:00", "07:00"));
+        assert!(validate(&policy).is_ok());
+    }
+
+    #[test]
+    fn test_policy_skips() {
+        let skips = PolicySkips::default();
+        assert!(skips.record("a", Some(SkipReason::QuietHours)));
+        assert!(!skips.record("a", Some(SkipReason::QuietHours)));
+        assert!(skips.record("a", Some(SkipReason::LowBattery)));
+        assert_eq!(skips.snapshot().get("a"), Some(&SkipReason::LowBattery));
+
+        assert!(skips.record("a", None));
+        assert!(!skips.record("a", None));
+        assert!(skips.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+/// 最近修改文件的传输优先级
+///
+/// 首次同步等大量积压的传输会持续很久，期间用户正在编辑的文档也要排在积压条目之后。
+/// 生成同步计划后，最后修改时间在优先窗口（`priority_window_mins`）内的文件排到其他条目之前：
+/// 上传按本地修改时间、下载按远程修改时间判断，越新的越靠前；其余条目保持原有顺序。
+/// 传输池按计划顺序取出任务，因此最近修改的文件最先传输
+use std::collections::HashMap;
+
+use crate::sync::planner::{PlanItem, RemoteEntry, SyncPlan};
+use crate::sync::scanner::LocalEntry;
+
+/// 把优先窗口内修改过的上传和下载移到计划最前面
+///
+/// # 参数
+/// - plan: 同步计划
+/// - local: 本地扫描结果
+/// - remote: 远程列表
+/// - window: 优先窗口（秒），0 表示不调整顺序
+/// - now: 当前时间（Unix 时间戳，秒）
+///
+/// # 返回
+/// 提前的条目数
+pub fn prioritize_recent(
+    plan: &mut SyncPlan,
+    local: &[LocalEntry],
+    remote: &[RemoteEntry],
+    window: u32,
+    now: i64,
+) -> usize {
+    if window == 0 {
+        return 0;
+    }
+    let since = now - i64::from(window);
+
+    let local: HashMap<&str, Option<i64>> = local
+        .iter()
+        .map(|e| (e.rel_path.as_str(), e.modified))
+        .collect();
+    let remote: HashMap<&str, Option<i64>> = remote
+        .iter()
+        .map(|e| (e.rel_path.as_str(), e.modified))
+        .collect();
+
+    reorder(&mut plan.uploads, &local, since) + reorder(&mut plan.downloads, &remote, since)
+}
+
+/// 按修改时间把 since 之后修改的文件移到最前面（越新越靠前），目录和其余条目保持原有顺序
+fn reorder(items: &mut Vec<PlanItem>, modified: &HashMap<&str, Option<i64>>, since: i64) -> usize {
+    let recent_at = |item: &PlanItem| {
+        modified
+            .get(item.rel_path.as_str())
+            .copied()
+            .flatten()
+            .filter(|&at| !item.is_directory && at >= since)
+    };
+
+    let (mut recent, backlog): (Vec<PlanItem>, Vec<PlanItem>) = std::mem::take(items)
+        .into_iter()
+        .partition(|item| recent_at(item).is_some());
+    recent.sort_by_key(|item| std::cmp::Reverse(recent_at(item)));
+
+    let moved = recent.len();
+    *items = recent;
+    items.extend(backlog);
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::planner::PlanAction;
+
+    fn item(path: &str, action: PlanAction) -> PlanItem {
+        PlanItem {
+            rel_path: path.to_string(),
+            action,
+            is_directory: false,
+            size: 1,
+            local_size: None,
+            remote_size: None,
+            reason: None,
+            from_path: None,
+        }
+    }
+
+    fn local(path: &str, modified: i64) -> LocalEntry {
+        LocalEntry {
+            rel_path: path.to_string(),
+            is_directory: false,
+            size: 1,
+            modified: Some(modified),
+            inode: None,
+        }
+    }
+
+    fn remote(path: &str, modified: i64) -> RemoteEntry {
+        RemoteEntry {
+            rel_path: path.to_string(),
+            is_directory: false,
+            size: 1,
+            modified: Some(modified),
+            etag: None,
+        }
+    }
+
+    fn paths(items: &[PlanItem]) -> Vec<&str> {
+        items.iter().map(|i| i.rel_path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_recent_items_move_to_front() {
+        let now = 10_000;
+        let mut plan = SyncPlan {
+            uploads: ["old-a", "new-1", "old-b", "new-2"]
+                .into_iter()
+                .map(|p| item(p, PlanAction::Upload))
+                .collect(),
+            downloads: ["old-c", "new-3"]
+                .into_iter()
+                .map(|p| item(p, PlanAction::Download))
+                .collect(),
+            ..Default::default()
+        };
+        let local = [
+            local("old-a", now - 3600),
+            local("new-1", now - 60),
+            local("old-b", now - 7200),
+            local("new-2", now - 10),
+        ];
+        let remote = [remote("old-c", now - 3600), remote("new-3", now - 30)];
+
+        assert_eq!(prioritize_recent(&mut plan, &local, &remote, 900, now), 3);
+        assert_eq!(paths(&plan.uploads), ["new-2", "new-1", "old-a", "old-b"]);
+        assert_eq!(paths(&plan.downloads), ["new-3", "old-c"]);
+    }
+
+    #[test]
+    fn test_directories_and_disabled_window_keep_order() {
+        let now = 10_000;
+        let mut dir = item("docs", PlanAction::Upload);
+        dir.is_directory = true;
+        let mut plan = SyncPlan {
+            uploads: vec![item("a", PlanAction::Upload), dir],
+            ..Default::default()
+        };
+        let mut dir_entry = local("docs", now);
+        dir_entry.is_directory = true;
+        let local = [local("a", now - 3600), dir_entry];
+
+        assert_eq!(prioritize_recent(&mut plan, &local, &[], 0, now), 0);
+        assert_eq!(prioritize_recent(&mut plan, &local, &[], 900, now), 0);
+        assert_eq!(paths(&plan.uploads), ["a", "docs"]);
+    }
+}
@@ -0,0 +1,254 @@
+/// 启动恢复
+///
+/// 应用在同步过程中崩溃或被强制退出后，数据库与文件系统会留下中间状态：
+/// - sync_sessions 中的会话一直处于 running
+/// - transfers 中的传输一直处于 running，不会再被调度
+/// - 同步文件夹中遗留 `*.lightsync-part` 下载临时文件
+///
+/// 启动时执行一次恢复，把这些状态整理回一致的状态。传输队列保存在 transfers 表中，
+/// 未完成的下载已有进度和 ETag 时保留临时文件，下次同步从断点继续；
+/// 分段并行下载的临时文件中未完成的部分为空洞，总是删除；
+/// 有排队中传输的文件夹由调用方立即重新同步
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::{transfer_direction, transfer_status};
+use crate::database::{sync_sessions, transfers, Database};
+use crate::sync::folders;
+use crate::webdav::client::partial_download_path;
+use crate::Result;
+
+/// 启动恢复结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    /// 标记为已中断的会话数
+    pub sessions_interrupted: usize,
+    /// 重新入队的传输数
+    pub transfers_requeued: usize,
+    /// 删除的下载临时文件数
+    pub partial_files_removed: usize,
+    /// 保留用于断点续传的下载临时文件数
+    pub partial_files_kept: usize,
+    /// 有排队中传输、需要继续同步的文件夹 ID
+    pub pending_folders: Vec<String>,
+}
+
+impl RecoveryReport {
+    /// 是否执行了任何恢复操作
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// 执行启动恢复
+///
+/// 清理单个文件夹的临时文件失败只记录日志，不影响其他文件夹
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - sync_folders: 所有同步文件夹配置
+/// - launched_at: 本次启动时间（Unix 时间戳，秒），之后开始的会话与传输不受影响
+///
+/// # 返回
+/// - Ok(RecoveryReport): 恢复结果
+/// - Err(SyncError::DatabaseError): 更新会话或传输状态失败
+pub async fn run(
+    db: &Database,
+    sync_folders: &[SyncFolderConfig],
+    launched_at: i64,
+) -> Result<RecoveryReport> {
+    let mut report = RecoveryReport {
+        sessions_interrupted: sync_sessions::mark_interrupted(db, launched_at).await?,
+        transfers_requeued: transfers::requeue_interrupted(db, launched_at).await?,
+        ..Default::default()
+    };
+
+    let keep = Arc::new(resumable_partials(db).await?);
+    for folder in sync_folders {
+        let root = folder.local_path.clone();
+        let keep = keep.clone();
+        match tokio::task::spawn_blocking(move || folders::remove_partial_downloads(&root, &keep))
+            .await
+        {
+            Ok(Ok(removed)) => report.partial_files_removed += removed,
+            Ok(Err(e)) => {
+                tracing::warn!(folder_id = %folder.id, error = %e, "清理下载临时文件失败")
+            }
+            Err(e) => {
+                tracing::warn!(folder_id = %folder.id, error = %e, "清理下载临时文件任务失败")
+            }
+        }
+    }
+    report.partial_files_kept = keep.iter().filter(|path| path.is_file()).count();
+    report.pending_folders = transfers::folders_with_queued(db).await?;
+
+    Ok(report)
+}
+
+/// 未完成、已有进度和 ETag 的下载对应的临时文件
+///
+/// 只包含单连接下载的临时文件（`partial_download_path`），分段下载的临时文件不会保留。
+/// 暂停和失败的下载同样保留，下次同步时由同步引擎确认 ETag 后继续或删除
+async fn resumable_partials(db: &Database) -> Result<HashSet<PathBuf>> {
+    Ok(transfers::list(db, None)
+        .await?
+        .into_iter()
+        .filter(|t| {
+            t.direction == transfer_direction::DOWNLOAD
+                && t.status != transfer_status::COMPLETED
+                && t.bytes_done > 0
+                && t.resume_token.is_some()
+        })
+        .map(|t| partial_download_path(Path::new(&t.local_path)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{session_status, transfer_direction, transfer_status};
+    use crate::database::folder_records;
+    use crate::database::folder_records::tests::{create_folder, create_test_db};
+    use crate::webdav::client::ranged_download_path;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_run_recovers_interrupted_state() {
+        let (test_dir, db) = create_test_db();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .unwrap();
+
+        let root = test_dir.join("folder");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("sub/a.txt.lightsync-part"), b"partial").unwrap();
+        fs::write(root.join("sub/a.txt"), b"complete").unwrap();
+
+        let mut folder = create_folder("folder-1");
+        folder.local_path = root.clone();
+        folder_records::upsert(&db, &folder).await.unwrap();
+
+        let session = sync_sessions::create(&db, 1).await.unwrap();
+        let transfer = transfers::enqueue(
+            &db,
+            Some("folder-1"),
+            "server-1",
+            transfer_direction::DOWNLOAD,
+            "a.txt",
+            "/a.txt",
+            Some(8),
+        )
+        .await
+        .unwrap();
+        transfers::set_status(&db, transfer, transfer_status::RUNNING, None)
+            .await
+            .unwrap();
+
+        // 已有进度和 ETag 的下载保留临时文件
+        let resumable_path = root.join("sub/b.txt");
+        let resumable_part = partial_download_path(&resumable_path);
+        fs::write(&resumable_part, b"part").unwrap();
+        let resumable = transfers::enqueue(
+            &db,
+            Some("folder-1"),
+            "server-1",
+            transfer_direction::DOWNLOAD,
+            &resumable_path.to_string_lossy(),
+            "/sub/b.txt",
+            Some(8),
+        )
+        .await
+        .unwrap();
+        transfers::set_status(&db, resumable, transfer_status::RUNNING, None)
+            .await
+            .unwrap();
+        transfers::update_progress(&db, resumable, 4, Some("\"v1\""))
+            .await
+            .unwrap();
+
+        let launched_at = chrono::Utc::now().timestamp() + 1;
+        let report = run(&db, &[folder.clone()], launched_at).await.unwrap();
+        assert_eq!(
+            report,
+            RecoveryReport {
+                sessions_interrupted: 1,
+                transfers_requeued: 2,
+                partial_files_removed: 1,
+                partial_files_kept: 1,
+                pending_folders: vec!["folder-1".to_string()],
+            }
+        );
+        assert_eq!(
+            sync_sessions::get_by_id(&db, session).await.unwrap().status,
+            session_status::INTERRUPTED
+        );
+        assert!(root.join("sub/a.txt").exists());
+        assert!(resumable_part.exists());
+
+        // 再次执行时只剩等待继续同步的传输
+        let report = run(&db, &[folder], launched_at).await.unwrap();
+        assert_eq!(report.sessions_interrupted + report.transfers_requeued, 0);
+        assert_eq!(report.partial_files_removed, 0);
+        assert_eq!(report.pending_folders, vec!["folder-1".to_string()]);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_removes_interrupted_ranged_download() {
+        let (test_dir, db) = create_test_db();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/001_initial.sql"))
+            .unwrap();
+
+        let root = test_dir.join("folder");
+        fs::create_dir_all(&root).unwrap();
+        let mut folder = create_folder("folder-1");
+        folder.local_path = root.clone();
+        folder_records::upsert(&db, &folder).await.unwrap();
+
+        // 分段下载先把临时文件扩展到完整长度，进度为各段已写入字节数之和
+        let local_path = root.join("large.bin");
+        let ranged_part = ranged_download_path(&local_path);
+        let mut content = vec![1u8; 4];
+        content.extend([0u8; 4]);
+        content.extend([2u8; 4]);
+        content.extend([0u8; 4]);
+        fs::write(&ranged_part, &content).unwrap();
+        let transfer = transfers::enqueue(
+            &db,
+            Some("folder-1"),
+            "server-1",
+            transfer_direction::DOWNLOAD,
+            &local_path.to_string_lossy(),
+            "/large.bin",
+            Some(16),
+        )
+        .await
+        .unwrap();
+        transfers::set_status(&db, transfer, transfer_status::RUNNING, None)
+            .await
+            .unwrap();
+        transfers::update_progress(&db, transfer, 8, Some("\"v1\""))
+            .await
+            .unwrap();
+
+        let launched_at = chrono::Utc::now().timestamp() + 1;
+        let report = run(&db, &[folder], launched_at).await.unwrap();
+        assert_eq!(report.partial_files_removed, 1);
+        assert_eq!(report.partial_files_kept, 0);
+        assert!(!ranged_part.exists());
+        assert!(!partial_download_path(&local_path).exists());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,269 @@
+/// 远程变更轮询
+///
+/// 服务器不支持 sync-collection REPORT 时，只下载文件夹无法低成本地获取增量变更。
+/// 轮询按文件夹的同步间隔递归列出远程目录，根据每个条目的路径、ETag、修改时间和大小
+/// 计算目录指纹，与上次同步成功时保存的指纹比较，只有发生变化时才触发同步，
+/// 避免没有变化时执行完整的本地扫描与对比
+use sha2::{Digest, Sha256};
+
+use crate::config::SyncFolderConfig;
+use crate::constants::sync_direction;
+use crate::database::{remote_snapshots, Database, RemoteSnapshot};
+use crate::sync::delta::list_recursive;
+use crate::sync::filter::SyncFilter;
+use crate::webdav::client::{FileInfo, WebDavClient};
+use crate::Result;
+
+/// 轮询结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PollOutcome {
+    /// 服务器支持 sync-collection，不需要轮询
+    SyncCollection,
+    /// 远程目录没有变化
+    Unchanged,
+    /// 远程目录发生变化（或尚未同步成功过），需要同步
+    Changed {
+        /// 新的目录指纹，同步成功后保存
+        fingerprint: String,
+        /// 指纹包含的远程条目数
+        entry_count: i64,
+    },
+}
+
+/// 文件夹是否使用远程变更轮询（启用自动同步的只下载文件夹）
+pub fn is_eligible(folder: &SyncFolderConfig) -> bool {
+    folder.auto_sync && folder.sync_direction == sync_direction::DOWNLOAD_ONLY
+}
+
+/// 距离上次轮询是否已超过文件夹的同步间隔
+///
+/// # 参数
+/// - snapshot: 已保存的快照（None 表示从未轮询过）
+/// - interval_minutes: 同步间隔（分钟）
+/// - now: 当前时间（Unix 时间戳，秒）
+pub fn is_due(snapshot: Option<&RemoteSnapshot>, interval_minutes: u32, now: i64) -> bool {
+    snapshot.is_none_or(|s| now - s.checked_at >= i64::from(interval_minutes) * 60)
+}
+
+/// 计算远程目录指纹
+///
+/// 条目按路径排序后对路径、ETag、修改时间和大小计算 SHA-256，与列表顺序无关
+pub fn fingerprint(entries: &[FileInfo]) -> String {
+    let mut sorted: Vec<&FileInfo> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut hasher = Sha256::new();
+    for entry in sorted {
+        hasher.update(entry.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(entry.etag.as_deref().unwrap_or("").as_bytes());
+        hasher.update([0]);
+        hasher.update(entry.modified.unwrap_or(0).to_le_bytes());
+        hasher.update(entry.size.to_le_bytes());
+        hasher.update([u8::from(entry.is_directory)]);
+    }
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 轮询文件夹的远程目录并与保存的指纹比较
+///
+/// 每次轮询都会记录检查时间；指纹只在同步成功后通过 `remote_snapshots::save` 更新
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - client: WebDAV 客户端
+/// - folder: 同步文件夹配置
+/// - now: 当前时间（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(PollOutcome): 轮询结果
+/// - Err(SyncError): 列出远程目录或访问数据库失败
+pub async fn poll_folder(
+    db: &Database,
+    client: &WebDavClient,
+    folder: &SyncFolderConfig,
+    now: i64,
+) -> Result<PollOutcome> {
+    let previous = remote_snapshots::get(db, &folder.id).await?;
+    remote_snapshots::touch(db, &folder.id, now).await?;
+
+    if client.supports_sync_collection(&folder.remote_path).await? {
+        return Ok(PollOutcome::SyncCollection);
+    }
+
+    // 加密文件夹的远程文件名可能已混淆，无法匹配忽略规则，列出全部条目
+    let filter = if folder.encryption.enabled {
+        SyncFilter::default()
+    } else {
+        SyncFilter::from_folder(folder)
+    };
+    let entries = list_recursive(client, &folder.remote_path, &filter).await?;
+    let current = fingerprint(&entries);
+
+    if previous.and_then(|s| s.fingerprint).as_deref() == Some(current.as_str()) {
+        return Ok(PollOutcome::Unchanged);
+    }
+
+    Ok(PollOutcome::Changed {
+        fingerprint: current,
+        entry_count: entries.len() as i64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webdav::mock_server_config;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn file(path: &str, etag: Option<&str>, modified: i64) -> FileInfo {
+        FileInfo {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap_or("").to_string(),
+            is_directory: false,
+            size: 10,
+            modified: Some(modified),
+            etag: etag.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = file("/docs/a.txt", Some("\"1\""), 100);
+        let b = file("/docs/b.txt", None, 200);
+
+        let base = fingerprint(&[a.clone(), b.clone()]);
+        assert_eq!(base, fingerprint(&[b.clone(), a.clone()]));
+
+        let mut changed_etag = a.clone();
+        changed_etag.etag = Some("\"2\"".to_string());
+        assert_ne!(base, fingerprint(&[changed_etag, b.clone()]));
+
+        let mut touched = b.clone();
+        touched.modified = Some(201);
+        assert_ne!(base, fingerprint(&[a.clone(), touched]));
+
+        assert_ne!(base, fingerprint(&[a]));
+    }
+
+    #[test]
+    fn test_is_due() {
+        let snapshot = RemoteSnapshot {
+            folder_id: "folder-1".to_string(),
+            fingerprint: None,
+            entry_count: 0,
+            checked_at: 1_000,
+        };
+
+        assert!(is_due(None, 30, 1_000));
+        assert!(!is_due(Some(&snapshot), 30, 1_000 + 29 * 60));
+        assert!(is_due(Some(&snapshot), 30, 1_000 + 30 * 60));
+    }
+
+    #[tokio::test]
+    async fn test_poll_folder() {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&test_dir).unwrap();
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/014_remote_snapshots.sql"))
+            .expect("Failed to run migration 014");
+
+        let mut server = mockito::Server::new_async().await;
+        let _report_set = server
+            .mock("PROPFIND", "/docs")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/docs/</D:href>
+                        <D:propstat><D:prop><D:supported-report-set/></D:prop></D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let _list = server
+            .mock("PROPFIND", "/docs")
+            .match_header("depth", "1")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/docs/a.txt</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype/>
+                                <D:getcontentlength>3</D:getcontentlength>
+                                <D:getetag>"abc"</D:getetag>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+
+        let config = mock_server_config(&server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let folder = SyncFolderConfig {
+            id: "folder-1".to_string(),
+            name: "Docs".to_string(),
+            local_path: test_dir.join("local"),
+            remote_path: "/docs".to_string(),
+            server_id: "test-id".to_string(),
+            sync_direction: sync_direction::DOWNLOAD_ONLY.to_string(),
+            sync_interval: 30,
+            auto_sync: true,
+            ignore_patterns: vec![],
+            conflict_resolution: "newer-wins".to_string(),
+            selective_exclusions: vec![],
+            symlink_policy: "skip".to_string(),
+            encryption: Default::default(),
+            compression: Default::default(),
+            max_delete_ratio: 0.2,
+            first_sync_strategy: "merge-keep-both".to_string(),
+            max_file_size: None,
+            include_extensions: vec![],
+            exclude_extensions: vec![],
+            ignore_system_files: true,
+            sync_policy: None,
+        };
+        assert!(is_eligible(&folder));
+
+        // 尚未同步成功过，视为有变化
+        let outcome = poll_folder(&db, &client, &folder, 100).await.unwrap();
+        let PollOutcome::Changed {
+            fingerprint,
+            entry_count,
+        } = outcome
+        else {
+            panic!("expected changed outcome");
+        };
+        assert_eq!(entry_count, 1);
+
+        remote_snapshots::save(&db, &folder.id, &fingerprint, entry_count, 100)
+            .await
+            .unwrap();
+        let outcome = poll_folder(&db, &client, &folder, 200).await.unwrap();
+        assert_eq!(outcome, PollOutcome::Unchanged);
+        let snapshot = remote_snapshots::get(&db, &folder.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.checked_at, 200);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,200 @@
+/// 大量删除保护
+///
+/// 同步计划在任一侧删除的文件超过文件夹设置的比例（`max_delete_ratio`，相对于上次同步的文件数）时，
+/// 同步在执行任何操作前中止并返回 `SyncError::MassDeletionProtection`，计划登记为待确认。
+/// 用户通过 `confirm_sync_plan` 确认后，下一次同步只要删除的文件都在已确认的范围内就会执行；
+/// 确认只生效一次。
+///
+/// 删除数量不超过 `MASS_DELETION_MIN_FILES` 时不检查，避免文件很少的文件夹频繁需要确认
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use crate::constants::MASS_DELETION_MIN_FILES;
+use crate::sync::planner::SyncPlan;
+use crate::{Result, SyncError};
+
+/// 待确认的同步计划
+#[derive(Debug, Clone)]
+struct PendingPlan {
+    folder_id: String,
+    deletions: BTreeSet<String>,
+}
+
+/// 大量删除保护（作为 Tauri State 管理）
+#[derive(Debug, Default)]
+pub struct DeletionGuard {
+    /// 计划 ID -> 待确认的计划
+    pending: Mutex<HashMap<String, PendingPlan>>,
+    /// 文件夹 ID -> 已确认的删除
+    approved: Mutex<HashMap<String, BTreeSet<String>>>,
+}
+
+impl DeletionGuard {
+    /// 检查同步计划的删除数量
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - plan: 同步计划
+    /// - max_ratio: 任一侧最多删除的文件比例（0~1，1 表示不限制）
+    ///
+    /// # 返回
+    /// - Ok(()): 未超过比例，或删除均已被确认
+    /// - Err(SyncError::MassDeletionProtection): 超过比例，计划已登记为待确认
+    pub fn check(&self, folder_id: &str, plan: &SyncPlan, max_ratio: f64) -> Result<()> {
+        let deletions = deletion_keys(plan);
+        let approved = self.approved.lock().unwrap().remove(folder_id);
+        if approved.is_some_and(|approved| deletions.is_subset(&approved)) {
+            return Ok(());
+        }
+
+        let Some(reason) = exceeds(plan, max_ratio) else {
+            return Ok(());
+        };
+
+        self.pending.lock().unwrap().insert(
+            plan.plan_id.clone(),
+            PendingPlan {
+                folder_id: folder_id.to_string(),
+                deletions,
+            },
+        );
+        Err(SyncError::MassDeletionProtection(format!(
+            "{} (confirm plan {} to continue)",
+            reason, plan.plan_id
+        ))
+        .with_context("planId", &plan.plan_id))
+    }
+
+    /// 确认待执行的计划
+    ///
+    /// # 返回
+    /// - Ok(String): 计划所属的同步文件夹 ID
+    /// - Err(SyncError::NotFound): 计划不存在或已确认
+    pub fn confirm(&self, plan_id: &str) -> Result<String> {
+        let plan = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(plan_id)
+            .ok_or_else(|| {
+                SyncError::NotFound(format!("Pending sync plan not found: {}", plan_id))
+            })?;
+
+        // 同一文件夹较早登记的计划不再有效
+        self.pending
+            .lock()
+            .unwrap()
+            .retain(|_, pending| pending.folder_id != plan.folder_id);
+        self.approved
+            .lock()
+            .unwrap()
+            .insert(plan.folder_id.clone(), plan.deletions);
+        Ok(plan.folder_id)
+    }
+}
+
+/// 计划在任一侧删除的文件比例是否超过限制
+///
+/// # 返回
+/// 超过时返回原因，否则返回 None
+pub fn exceeds(plan: &SyncPlan, max_ratio: f64) -> Option<String> {
+    if max_ratio >= 1.0 || plan.synced_files == 0 {
+        return None;
+    }
+
+    let sides = [
+        ("local", &plan.local_deletions),
+        ("remote", &plan.remote_deletions),
+    ];
+    sides.into_iter().find_map(|(side, items)| {
+        let files = items.iter().filter(|item| !item.is_directory).count();
+        let ratio = files as f64 / plan.synced_files as f64;
+        (files > MASS_DELETION_MIN_FILES && ratio > max_ratio).then(|| {
+            format!(
+                "Sync would delete {} of {} {} files ({:.0}%, limit {:.0}%)",
+                files,
+                plan.synced_files,
+                side,
+                ratio * 100.0,
+                max_ratio * 100.0
+            )
+        })
+    })
+}
+
+/// 计划中删除的条目（带上本地或远程前缀）
+fn deletion_keys(plan: &SyncPlan) -> BTreeSet<String> {
+    plan.local_deletions
+        .iter()
+        .map(|item| format!("local:{}", item.rel_path))
+        .chain(
+            plan.remote_deletions
+                .iter()
+                .map(|item| format!("remote:{}", item.rel_path)),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::planner::{PlanAction, PlanItem};
+
+    fn plan(plan_id: &str, remote_deletions: usize, synced_files: usize) -> SyncPlan {
+        SyncPlan {
+            plan_id: plan_id.to_string(),
+            folder_id: "folder-1".to_string(),
+            remote_deletions: (0..remote_deletions)
+                .map(|i| PlanItem {
+                    rel_path: format!("file-{}.txt", i),
+                    action: PlanAction::DeleteRemote,
+                    is_directory: false,
+                    size: 1,
+                    local_size: None,
+                    remote_size: Some(1),
+                    reason: None,
+                    from_path: None,
+                })
+                .collect(),
+            synced_files,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_exceeds() {
+        assert!(exceeds(&plan("p", 30, 100), 0.2).is_some());
+        assert!(exceeds(&plan("p", 20, 100), 0.2).is_none());
+        // 删除数量很少时不检查
+        assert!(exceeds(&plan("p", MASS_DELETION_MIN_FILES, 10), 0.2).is_none());
+        assert!(exceeds(&plan("p", 100, 100), 1.0).is_none());
+    }
+
+    #[test]
+    fn test_confirmation_is_single_use() {
+        let guard = DeletionGuard::default();
+        let blocked = plan("plan-1", 30, 100);
+
+        let err = guard.check("folder-1", &blocked, 0.2).unwrap_err();
+        assert!(matches!(err.root(), SyncError::MassDeletionProtection(_)));
+        assert!(matches!(
+            guard.confirm("unknown"),
+            Err(SyncError::NotFound(_))
+        ));
+        assert_eq!(guard.confirm("plan-1").unwrap(), "folder-1");
+
+        // 重新生成的计划只删除已确认的文件时执行
+        guard
+            .check("folder-1", &plan("plan-2", 25, 100), 0.2)
+            .unwrap();
+        assert!(guard
+            .check("folder-1", &plan("plan-3", 25, 100), 0.2)
+            .is_err());
+
+        // 删除了确认范围之外的文件时需要重新确认
+        guard.confirm("plan-3").unwrap();
+        assert!(guard
+            .check("folder-1", &plan("plan-4", 40, 100), 0.2)
+            .is_err());
+    }
+}
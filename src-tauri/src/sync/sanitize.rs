@@ -0,0 +1,200 @@
+/// 文件名清理
+///
+/// 服务器上的文件名可能包含 Windows 上不合法的部分：保留设备名（`CON`、`NUL` 等，
+/// 带扩展名同样保留），结尾的点或空格，`<>:"\|?*` 和控制字符。
+/// 按配置的策略（`reserved_name_policy`）映射为合法的本地文件名：
+/// - rename: 不合法的字符替换为 `_`（`a:b.txt` -> `a_b.txt`）
+/// - percent-encode: 不合法的字符编码为 `%XX`（`a:b.txt` -> `a%3Ab.txt`）
+/// - skip: 不同步该文件
+///
+/// 名称的 Unicode 形式也统一处理：macOS 上创建的文件名通常为 NFD 形式（`e` + 组合重音符），
+/// Linux 服务器上为 NFC 形式（`é`），两者字节不同但显示相同。对比和上传都使用 NFC 形式（`to_nfc`），
+/// 本地或服务器上已有的其他形式的文件名由 `PathNormalizer` 记录并在访问时还原
+///
+/// 映射不要求可逆：本地文件名与远程文件名的对应关系保存在 name_mappings 表中
+/// （见 `database::name_mappings`），远程文件不在列表中时上传仍然使用原来的远程文件名
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::constants::reserved_name_policy;
+
+/// Windows 保留的设备名（不区分大小写，带扩展名同样保留，如 `con.txt`）
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 不合法的文件名在本地的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservedNamePolicy {
+    /// 替换不合法的部分后保存（如 `CON.txt` -> `CON_.txt`）
+    Rename,
+    /// 将不合法的字符编码为 `%XX` 后保存（如 `CON.txt` -> `%43ON.txt`）
+    PercentEncode,
+    /// 跳过该文件，不同步到本地
+    Skip,
+}
+
+impl ReservedNamePolicy {
+    /// 从配置值解析，未知的值按重命名处理
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            reserved_name_policy::SKIP => Self::Skip,
+            reserved_name_policy::PERCENT_ENCODE => Self::PercentEncode,
+            _ => Self::Rename,
+        }
+    }
+
+    /// 按策略将文件名映射为 Windows 上合法的形式（跳过策略保持原样）
+    pub fn map_name(self, name: &str) -> String {
+        match self {
+            Self::Rename => sanitize_name(name),
+            Self::PercentEncode => percent_encode_name(name),
+            Self::Skip => name.to_string(),
+        }
+    }
+}
+
+/// 判断相对路径的所有分段在 Windows 上是否合法
+pub fn is_valid_path(rel_path: &str) -> bool {
+    rel_path.split('/').all(is_valid_name)
+}
+
+/// 判断文件名在 Windows 上是否合法
+pub fn is_valid_name(name: &str) -> bool {
+    sanitize_name(name) == name
+}
+
+/// 是否为 Windows 不允许出现在文件名中的字符
+fn is_invalid_char(c: char) -> bool {
+    matches!(c, '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*') || c.is_control()
+}
+
+/// 文件名主名（第一个 `.` 之前的部分）是否为保留设备名
+fn has_reserved_stem(name: &str) -> bool {
+    let stem = &name[..name.find('.').unwrap_or(name.len())];
+    RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem))
+}
+
+/// 将文件名转换为 Windows 上合法的形式
+///
+/// - `<>:"\|?*` 和控制字符替换为 `_`
+/// - 结尾的点和空格替换为 `_`
+/// - 保留设备名（含带扩展名的形式）在主名后追加 `_`，如 `CON.txt` -> `CON_.txt`
+pub fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if is_invalid_char(c) { '_' } else { c })
+        .collect();
+
+    let trimmed_len = sanitized.trim_end_matches(['.', ' ']).len();
+    let trailing = sanitized.len() - trimmed_len;
+    if trailing > 0 {
+        sanitized.truncate(trimmed_len);
+        sanitized.push_str(&"_".repeat(trailing));
+    }
+
+    if has_reserved_stem(&sanitized) {
+        let stem_len = sanitized.find('.').unwrap_or(sanitized.len());
+        sanitized.insert(stem_len, '_');
+    }
+
+    sanitized
+}
+
+/// 将文件名中 Windows 上不合法的部分编码为 `%XX`（UTF-8 字节的十六进制大写形式）
+///
+/// - `<>:"\|?*` 和控制字符编码
+/// - 结尾的点和空格编码
+/// - 保留设备名（含带扩展名的形式）编码第一个字符，如 `CON.txt` -> `%43ON.txt`
+pub fn percent_encode_name(name: &str) -> String {
+    let trimmed_len = name.trim_end_matches(['.', ' ']).len();
+    let reserved = has_reserved_stem(name);
+
+    let mut encoded = String::with_capacity(name.len());
+    for (i, c) in name.char_indices() {
+        if is_invalid_char(c) || i >= trimmed_len || (reserved && i == 0) {
+            let mut buf = [0u8; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+/// 将名称（或相对路径）转换为 Unicode NFC 形式
+pub fn to_nfc(name: &str) -> String {
+    if is_nfc(name) {
+        name.to_string()
+    } else {
+        name.nfc().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("report.pdf"), "report.pdf");
+        assert_eq!(sanitize_name("CON"), "CON_");
+        assert_eq!(sanitize_name("nul.txt"), "nul_.txt");
+        assert_eq!(sanitize_name("Com1.tar.gz"), "Com1_.tar.gz");
+        assert_eq!(sanitize_name("notes."), "notes_");
+        assert_eq!(sanitize_name("draft . "), "draft___");
+        assert_eq!(sanitize_name("a<b>:c?.txt"), "a_b__c_.txt");
+        // 仅主名完全匹配时才是保留名
+        assert_eq!(sanitize_name("console.log"), "console.log");
+        assert_eq!(sanitize_name("COM10"), "COM10");
+
+        assert!(is_valid_name("文档.docx"));
+        assert!(!is_valid_name("aux"));
+        assert!(!is_valid_path("docs/con/readme.md"));
+    }
+
+    #[test]
+    fn test_percent_encode_name() {
+        assert_eq!(percent_encode_name("report.pdf"), "report.pdf");
+        assert_eq!(percent_encode_name("a:b.txt"), "a%3Ab.txt");
+        assert_eq!(percent_encode_name("what?*.md"), "what%3F%2A.md");
+        assert_eq!(percent_encode_name("CON.txt"), "%43ON.txt");
+        assert_eq!(percent_encode_name("notes. "), "notes%2E%20");
+        assert_eq!(percent_encode_name("tab\there"), "tab%09here");
+        assert_eq!(percent_encode_name("100%.txt"), "100%.txt");
+
+        for name in ["a:b.txt", "CON.txt", "notes. ", "x|y"] {
+            assert!(is_valid_name(&percent_encode_name(name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_to_nfc() {
+        assert_eq!(
+            to_nfc("Cafe\u{301}/re\u{301}sume\u{301}.txt"),
+            "Café/résumé.txt"
+        );
+        assert_eq!(to_nfc("Café"), "Café");
+        assert_eq!(to_nfc("plain.txt"), "plain.txt");
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        assert_eq!(
+            ReservedNamePolicy::from_config("percent-encode"),
+            ReservedNamePolicy::PercentEncode
+        );
+        assert_eq!(
+            ReservedNamePolicy::from_config("skip"),
+            ReservedNamePolicy::Skip
+        );
+        assert_eq!(
+            ReservedNamePolicy::from_config("unknown"),
+            ReservedNamePolicy::Rename
+        );
+        assert_eq!(ReservedNamePolicy::Rename.map_name("a:b"), "a_b");
+        assert_eq!(ReservedNamePolicy::Skip.map_name("a:b"), "a:b");
+    }
+}
@@ -0,0 +1,636 @@
+/// 本地文件扫描
+///
+/// 遍历同步文件夹的本地目录（多个线程并行），跳过被 `SyncFilter` 排除的文件和子树，
+/// 符号链接按文件夹的 `symlink_policy` 跳过、跟随或作为占位文件
+///
+/// `scan_changed` 沿用上次扫描的结果，只重新读取发生变化的目录（见 `incremental`）
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{FOLDER_STATS_MAX_THREADS, LOCAL_SCAN_MAX_THREADS};
+use crate::sync::filter::SyncFilter;
+use crate::sync::symlinks::{placeholder_content, resolve_within_root, SymlinkPolicy};
+use crate::{Result, SyncError};
+
+/// 本地文件条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalEntry {
+    /// 相对于同步根目录的路径（`/` 分隔）
+    pub rel_path: String,
+    /// 是否为目录
+    pub is_directory: bool,
+    /// 文件大小（字节，目录为 0）
+    pub size: u64,
+    /// 最后修改时间（Unix 时间戳，秒）
+    pub modified: Option<i64>,
+    /// 文件的 inode（仅 Unix，用于识别重命名的文件）
+    #[serde(default)]
+    pub inode: Option<u64>,
+}
+
+/// 读取文件的 inode，非 Unix 平台返回 None
+pub fn inode(metadata: &fs::Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// 读取最后修改时间（Unix 时间戳，秒）
+pub fn modified_secs(metadata: &fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+/// 相对路径的父目录（根目录下的条目为空字符串）
+pub fn parent_dir(rel_path: &str) -> &str {
+    rel_path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// 待遍历的目录（相对路径，及从根目录开始的真实路径链，跟随链接时用于检测循环）
+type PendingDir = (String, Vec<PathBuf>);
+
+/// 并行扫描时各线程共享的待遍历目录队列
+struct DirQueue {
+    state: Mutex<DirQueueState>,
+    /// 有新的待遍历目录或遍历结束时通知等待的线程
+    ready: Condvar,
+}
+
+struct DirQueueState {
+    pending: Vec<PendingDir>,
+    /// 正在遍历的目录数（其子目录尚未加入队列）
+    active: usize,
+    /// 有线程遍历失败，其余线程不再取出新的目录
+    aborted: bool,
+}
+
+impl DirQueue {
+    fn new(root: PendingDir) -> Self {
+        Self {
+            state: Mutex::new(DirQueueState {
+                pending: vec![root],
+                active: 0,
+                aborted: false,
+            }),
+            ready: Condvar::new(),
+        }
+    }
+
+    /// 取出下一个待遍历的目录
+    ///
+    /// 队列暂时为空但仍有目录正在遍历时等待；全部遍历完或已失败时返回 None
+    fn next(&self) -> Option<PendingDir> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.aborted {
+                return None;
+            }
+            if let Some(dir) = state.pending.pop() {
+                state.active += 1;
+                return Some(dir);
+            }
+            if state.active == 0 {
+                return None;
+            }
+            state = self.ready.wait(state).unwrap();
+        }
+    }
+
+    /// 完成一个目录的遍历，把其子目录加入队列
+    fn finish(&self, subdirs: Vec<PendingDir>, failed: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.active -= 1;
+        state.pending.extend(subdirs);
+        state.aborted |= failed;
+        drop(state);
+        self.ready.notify_all();
+    }
+}
+
+/// 扫描本地同步目录
+///
+/// 目录由多个线程并行遍历：各线程从共享队列取出目录，读取其中的条目并把子目录放回队列，
+/// 大型文件夹（数十万个文件）的首次扫描不再受单个线程的系统调用延迟限制。
+/// 任一目录读取失败时停止扫描并返回错误
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - filter: 同步过滤器，被排除的目录不会被继续遍历
+/// - policy: 符号链接处理策略
+///
+/// # 返回
+/// 所有未被排除的文件和目录（按相对路径排序）
+pub fn scan_local(
+    root: &Path,
+    filter: &SyncFilter,
+    policy: SymlinkPolicy,
+) -> Result<Vec<LocalEntry>> {
+    let canonical_root = fs::canonicalize(root)?;
+    let queue = DirQueue::new((String::new(), vec![canonical_root.clone()]));
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, LOCAL_SCAN_MAX_THREADS);
+
+    let results = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| -> Result<Vec<LocalEntry>> {
+                    let mut entries = Vec::new();
+                    while let Some((rel_dir, chain)) = queue.next() {
+                        let mut subdirs = Vec::new();
+                        let result = read_entries(
+                            root,
+                            &canonical_root,
+                            &rel_dir,
+                            &chain,
+                            filter,
+                            policy,
+                            &mut entries,
+                            &mut subdirs,
+                        );
+                        queue.finish(subdirs, result.is_err());
+                        result?;
+                    }
+                    Ok(entries)
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker.join().unwrap_or_else(|_| {
+                    Err(SyncError::Unknown("Local scan worker panicked".to_string()))
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut entries = Vec::new();
+    for result in results {
+        entries.extend(result?);
+    }
+    entries.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    Ok(entries)
+}
+
+/// 增量扫描本地同步目录
+///
+/// 逐个读取目录的修改时间（不读取文件），只重新读取修改时间与上次扫描不同的目录
+/// （目录中新增、删除或重命名条目时修改时间会变化）和 `dirty_dirs` 中的目录，
+/// 其余目录中的条目沿用上次扫描的结果。修改时间不早于上次扫描开始时间的目录同样重新读取，
+/// 同一秒内的修改无法通过修改时间区分。
+/// 跟随符号链接时不能使用（目标目录的变化不反映在链接所在目录的修改时间上）
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - filter: 同步过滤器（须与上次扫描相同）
+/// - policy: 符号链接处理策略
+/// - previous: 上次扫描的结果
+/// - root_modified: 上次扫描时根目录的修改时间
+/// - since: 上次扫描开始的时间（Unix 时间戳，秒）
+/// - dirty_dirs: 需要重新读取的目录（相对路径，根目录为空字符串）
+///
+/// # 返回
+/// 所有未被排除的文件和目录（按相对路径排序）
+pub fn scan_changed(
+    root: &Path,
+    filter: &SyncFilter,
+    policy: SymlinkPolicy,
+    previous: &[LocalEntry],
+    root_modified: Option<i64>,
+    since: i64,
+    dirty_dirs: &HashSet<String>,
+) -> Result<Vec<LocalEntry>> {
+    let canonical_root = fs::canonicalize(root)?;
+    let mut children: HashMap<&str, Vec<&LocalEntry>> = HashMap::new();
+    let mut previous_dirs: HashMap<&str, Option<i64>> = HashMap::new();
+    previous_dirs.insert("", root_modified);
+    for entry in previous {
+        children
+            .entry(parent_dir(&entry.rel_path))
+            .or_default()
+            .push(entry);
+        if entry.is_directory {
+            previous_dirs.insert(&entry.rel_path, entry.modified);
+        }
+    }
+
+    let mut entries = Vec::new();
+    // 待遍历的目录，及沿用上次结果时该目录条目在 entries 中的位置（需要更新修改时间）
+    let mut pending: Vec<(String, Option<usize>)> = vec![(String::new(), None)];
+    while let Some((rel_dir, reused)) = pending.pop() {
+        let dir = if rel_dir.is_empty() {
+            root.to_path_buf()
+        } else {
+            root.join(&rel_dir)
+        };
+        let modified = modified_secs(&fs::metadata(&dir)?);
+        if let Some(i) = reused {
+            entries[i].modified = modified;
+        }
+
+        let unchanged = modified.is_some_and(|m| m < since)
+            && previous_dirs.get(rel_dir.as_str()) == Some(&modified)
+            && !dirty_dirs.contains(&rel_dir);
+        if !unchanged {
+            let mut subdirs = Vec::new();
+            read_entries(
+                root,
+                &canonical_root,
+                &rel_dir,
+                &[canonical_root.join(&rel_dir)],
+                filter,
+                policy,
+                &mut entries,
+                &mut subdirs,
+            )?;
+            pending.extend(subdirs.into_iter().map(|(rel_path, _)| (rel_path, None)));
+            continue;
+        }
+
+        for entry in children.get(rel_dir.as_str()).into_iter().flatten() {
+            if entry.is_directory {
+                pending.push((entry.rel_path.clone(), Some(entries.len())));
+            }
+            entries.push((*entry).clone());
+        }
+    }
+
+    entries.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    Ok(entries)
+}
+
+/// 读取单个目录中的条目，子目录加入待遍历列表
+#[allow(clippy::too_many_arguments)]
+fn read_entries(
+    root: &Path,
+    canonical_root: &Path,
+    rel_dir: &str,
+    chain: &[PathBuf],
+    filter: &SyncFilter,
+    policy: SymlinkPolicy,
+    entries: &mut Vec<LocalEntry>,
+    subdirs: &mut Vec<PendingDir>,
+) -> Result<()> {
+    let dir = if rel_dir.is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(rel_dir)
+    };
+
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let rel_path = if rel_dir.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", rel_dir, name)
+        };
+
+        if filter.is_excluded(&rel_path) {
+            continue;
+        }
+
+        // DirEntry::metadata 不跟随符号链接
+        let mut metadata = entry.metadata()?;
+        let mut real_path = chain[chain.len() - 1].join(&name);
+        let mut placeholder_size = None;
+
+        if metadata.file_type().is_symlink() {
+            match policy {
+                SymlinkPolicy::Skip => continue,
+                SymlinkPolicy::Placeholder => {
+                    placeholder_size = Some(placeholder_content(&entry.path())?.len() as u64);
+                }
+                SymlinkPolicy::Follow => {
+                    let Some(target) = resolve_within_root(canonical_root, &entry.path()) else {
+                        tracing::warn!(path = %rel_path, "符号链接已失效或指向同步目录之外，跳过");
+                        continue;
+                    };
+                    metadata = fs::metadata(&target)?;
+                    if metadata.is_dir() && chain.iter().any(|dir| dir.starts_with(&target)) {
+                        tracing::warn!(path = %rel_path, "符号链接形成循环，跳过");
+                        continue;
+                    }
+                    real_path = target;
+                }
+            }
+        }
+
+        let modified = modified_secs(&metadata);
+
+        let is_directory = placeholder_size.is_none() && metadata.is_dir();
+        if is_directory {
+            let mut chain = chain.to_vec();
+            chain.push(real_path);
+            subdirs.push((rel_path.clone(), chain));
+        }
+
+        entries.push(LocalEntry {
+            rel_path,
+            is_directory,
+            size: match placeholder_size {
+                Some(size) => size,
+                None if is_directory => 0,
+                None => metadata.len(),
+            },
+            modified,
+            inode: inode(&metadata),
+        });
+    }
+    Ok(())
+}
+
+/// 文件夹的文件数与总大小
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderUsage {
+    /// 文件数（不含目录）
+    pub file_count: u64,
+    /// 文件总大小（字节）
+    pub total_bytes: u64,
+}
+
+impl FolderUsage {
+    fn add(&mut self, other: FolderUsage) {
+        self.file_count += other.file_count;
+        self.total_bytes += other.total_bytes;
+    }
+}
+
+/// 统计本地同步目录的文件数与总大小
+///
+/// 根目录下的各个子目录由多个线程并行遍历。跳过被 `SyncFilter` 排除的条目和符号链接，
+/// 无法读取的子目录记录警告后跳过
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - filter: 同步过滤器
+pub fn local_usage(root: &Path, filter: &SyncFilter) -> Result<FolderUsage> {
+    let mut usage = FolderUsage::default();
+    let mut subdirs = Vec::new();
+    read_usage(root, "", filter, &mut usage, &mut subdirs)?;
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, FOLDER_STATS_MAX_THREADS)
+        .min(subdirs.len());
+    let next = AtomicUsize::new(0);
+
+    let totals = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut usage = FolderUsage::default();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(subdir) = subdirs.get(i) else {
+                            break;
+                        };
+                        let mut pending = vec![subdir.clone()];
+                        while let Some(rel_dir) = pending.pop() {
+                            let dir = root.join(&rel_dir);
+                            if let Err(e) = read_usage(&dir, &rel_dir, filter, &mut usage, &mut pending)
+                            {
+                                tracing::warn!(path = %dir.display(), error = %e, "无法读取目录，跳过统计");
+                            }
+                        }
+                    }
+                    usage
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap_or_default())
+            .collect::<Vec<_>>()
+    });
+
+    for total in totals {
+        usage.add(total);
+    }
+    Ok(usage)
+}
+
+/// 统计单个目录中的文件，子目录加入待遍历列表
+fn read_usage(
+    dir: &Path,
+    rel_dir: &str,
+    filter: &SyncFilter,
+    usage: &mut FolderUsage,
+    subdirs: &mut Vec<String>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let rel_path = if rel_dir.is_empty() {
+            name
+        } else {
+            format!("{}/{}", rel_dir, name)
+        };
+        if filter.is_excluded(&rel_path) {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            subdirs.push(rel_path);
+        } else if metadata.is_file() {
+            usage.file_count += 1;
+            usage.total_bytes += metadata.len();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_scan_skips_excluded_subtrees() {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("photos/2019")).unwrap();
+        fs::create_dir_all(root.join("photos/2020")).unwrap();
+        fs::write(root.join("photos/2019/a.jpg"), "a").unwrap();
+        fs::write(root.join("photos/2020/b.jpg"), "bb").unwrap();
+        fs::write(root.join("notes.tmp"), "tmp").unwrap();
+
+        let filter = SyncFilter::new(vec!["*.tmp".to_string()], vec!["photos/2019".to_string()]);
+        let entries = scan_local(&root, &filter, SymlinkPolicy::Skip).unwrap();
+        let paths: Vec<_> = entries.iter().map(|e| e.rel_path.as_str()).collect();
+
+        assert_eq!(paths, vec!["photos", "photos/2020", "photos/2020/b.jpg"]);
+        assert_eq!(entries[2].size, 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_scan_wide_tree_in_parallel() {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        for i in 0..40 {
+            let dir = root.join(format!("d{:02}/nested", i));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("a.txt"), "a").unwrap();
+        }
+
+        let entries = scan_local(&root, &SyncFilter::default(), SymlinkPolicy::Skip).unwrap();
+
+        // 每个分支：d、d/nested、d/nested/a.txt
+        assert_eq!(entries.len(), 40 * 3);
+        assert!(entries.windows(2).all(|w| w[0].rel_path < w[1].rel_path));
+        assert!(scan_local(
+            &root.join("missing"),
+            &SyncFilter::default(),
+            SymlinkPolicy::Skip
+        )
+        .is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_scan_changed_rereads_modified_dirs() {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("b")).unwrap();
+        fs::write(root.join("a/x.txt"), "x").unwrap();
+        fs::write(root.join("b/y.txt"), "y").unwrap();
+        let root_modified = modified_secs(&fs::metadata(&root).unwrap());
+        let mut previous = scan_local(&root, &SyncFilter::default(), SymlinkPolicy::Skip).unwrap();
+
+        // 原地修改文件不改变目录的修改时间；b 的修改时间模拟为与上次扫描不同
+        fs::write(root.join("a/x.txt"), "xxx").unwrap();
+        fs::write(root.join("b/z.txt"), "z").unwrap();
+        previous
+            .iter_mut()
+            .find(|e| e.rel_path == "b")
+            .unwrap()
+            .modified = Some(0);
+        let since = chrono::Utc::now().timestamp() + 60;
+
+        let scan = |dirty_dirs: &HashSet<String>| {
+            scan_changed(
+                &root,
+                &SyncFilter::default(),
+                SymlinkPolicy::Skip,
+                &previous,
+                root_modified,
+                since,
+                dirty_dirs,
+            )
+            .unwrap()
+        };
+        let entries = scan(&HashSet::new());
+        let paths: Vec<_> = entries.iter().map(|e| e.rel_path.as_str()).collect();
+        assert_eq!(paths, vec!["a", "a/x.txt", "b", "b/y.txt", "b/z.txt"]);
+        // 未变化的目录沿用上次结果，目录条目的修改时间更新为当前值
+        assert_eq!(entries[1].size, 1);
+        assert_ne!(entries[2].modified, Some(0));
+
+        let entries = scan(&HashSet::from(["a".to_string()]));
+        assert_eq!(entries[1].size, 3);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_local_usage() {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        for i in 0..5 {
+            let dir = root.join(format!("dir-{}/nested", i));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("a.txt"), "aaaa").unwrap();
+        }
+        fs::write(root.join("top.txt"), "12").unwrap();
+        fs::write(root.join("dir-0/skip.tmp"), "ignored").unwrap();
+
+        let filter = SyncFilter::new(vec!["*.tmp".to_string()], vec![]);
+        let usage = local_usage(&root, &filter).unwrap();
+        assert_eq!(
+            usage,
+            FolderUsage {
+                file_count: 6,
+                total_bytes: 5 * 4 + 2,
+            }
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_symlink_policies() {
+        use std::os::unix::fs::symlink;
+
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/a.txt"), "aaa").unwrap();
+        symlink(root.join("docs"), root.join("docs-link")).unwrap();
+        symlink(root.join("docs/a.txt"), root.join("a-link.txt")).unwrap();
+        // 指向上级目录形成循环
+        symlink(&root, root.join("docs/loop")).unwrap();
+        // 指向同步目录之外
+        symlink(std::env::temp_dir(), root.join("escape")).unwrap();
+
+        let filter = SyncFilter::default();
+        let scan = |policy| {
+            scan_local(&root, &filter, policy)
+                .unwrap()
+                .into_iter()
+                .map(|e| (e.rel_path, e.is_directory, e.size))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            scan(SymlinkPolicy::Skip),
+            vec![
+                ("docs".to_string(), true, 0),
+                ("docs/a.txt".to_string(), false, 3),
+            ]
+        );
+
+        assert_eq!(
+            scan(SymlinkPolicy::Follow),
+            vec![
+                ("a-link.txt".to_string(), false, 3),
+                ("docs".to_string(), true, 0),
+                ("docs-link".to_string(), true, 0),
+                ("docs-link/a.txt".to_string(), false, 3),
+                ("docs/a.txt".to_string(), false, 3),
+            ]
+        );
+
+        let placeholders = scan(SymlinkPolicy::Placeholder);
+        let link_size = root.join("docs").to_string_lossy().len() as u64;
+        assert!(placeholders.contains(&("docs-link".to_string(), false, link_size)));
+        assert!(placeholders.contains(&(
+            "docs/loop".to_string(),
+            false,
+            root.to_string_lossy().len() as u64
+        )));
+        assert_eq!(placeholders.len(), 6);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
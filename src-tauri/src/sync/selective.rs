@@ -0,0 +1,170 @@
+/// 选择性同步
+///
+/// 允许用户排除同步文件夹中的部分远程子文件夹。排除列表保存在
+/// `SyncFolderConfig.selective_exclusions` 中，同步时由 `SyncFilter` 在本地扫描和
+/// 远程列表两个方向同时跳过被排除的子树。
+use serde::{Deserialize, Serialize};
+
+use crate::sync::filter::{folder_relative_path, SyncFilter};
+use crate::webdav::client::WebDavClient;
+use crate::{Result, SyncError};
+
+/// 远程目录树节点（仅包含文件夹）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFolderNode {
+    /// 文件夹名称
+    pub name: String,
+    /// 相对于同步文件夹远程根目录的路径
+    pub path: String,
+    /// 是否已被排除
+    pub excluded: bool,
+    /// 子文件夹（超过最大深度时为空）
+    pub children: Vec<RemoteFolderNode>,
+}
+
+/// 规范化排除列表
+///
+/// 去除首尾 `/`、去重并排序；已被上级目录覆盖的子目录会被移除
+///
+/// # 返回
+/// - Ok(Vec<String>): 规范化后的列表
+/// - Err(SyncError::ConfigError): 包含 `.`、`..` 或空路径分段
+pub fn normalize_exclusions(exclusions: &[String]) -> Result<Vec<String>> {
+    let mut normalized = Vec::new();
+
+    for exclusion in exclusions {
+        let path = exclusion.trim().trim_matches('/');
+        if path.is_empty() {
+            continue;
+        }
+        if path
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+        {
+            return Err(SyncError::ConfigError(format!(
+                "Invalid selective sync exclusion: {}",
+                exclusion
+            )));
+        }
+        normalized.push(path.to_string());
+    }
+
+    normalized.sort();
+    normalized.dedup();
+
+    // 移除已被上级目录覆盖的条目
+    let filter = SyncFilter::new(Vec::new(), normalized.clone());
+    let covered: Vec<bool> = normalized
+        .iter()
+        .map(|path| match path.rsplit_once('/') {
+            Some((parent, _)) => filter.is_selectively_excluded(parent),
+            None => false,
+        })
+        .collect();
+
+    Ok(normalized
+        .into_iter()
+        .zip(covered)
+        .filter(|(_, covered)| !covered)
+        .map(|(path, _)| path)
+        .collect())
+}
+
+/// 获取远程文件夹树，供用户选择要排除的子文件夹
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_root: 同步文件夹的远程根路径
+/// - exclusions: 当前的排除列表（用于标记 `excluded`）
+/// - max_depth: 最大展开深度（1 表示只列出根目录下的子文件夹）
+pub async fn fetch_folder_tree(
+    client: &WebDavClient,
+    remote_root: &str,
+    exclusions: &[String],
+    max_depth: u32,
+) -> Result<Vec<RemoteFolderNode>> {
+    let filter = SyncFilter::new(Vec::new(), exclusions.iter().cloned());
+    fetch_children(client, remote_root, "", &filter, max_depth).await
+}
+
+/// 递归列出子文件夹
+///
+/// 使用 `Box::pin` 以支持 async 递归
+fn fetch_children<'a>(
+    client: &'a WebDavClient,
+    remote_root: &'a str,
+    rel_dir: &'a str,
+    filter: &'a SyncFilter,
+    depth: u32,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<RemoteFolderNode>>> + Send + 'a>>
+{
+    Box::pin(async move {
+        if depth == 0 {
+            return Ok(Vec::new());
+        }
+
+        let dir = if rel_dir.is_empty() {
+            remote_root.to_string()
+        } else {
+            format!("{}/{}", remote_root.trim_end_matches('/'), rel_dir)
+        };
+
+        let mut nodes = Vec::new();
+        for entry in client.list(&dir).await? {
+            if !entry.is_directory {
+                continue;
+            }
+
+            let remote_path = client.relative_path(&entry.path);
+            let Some(path) = folder_relative_path(remote_root, &remote_path) else {
+                continue;
+            };
+            if path.is_empty() {
+                continue;
+            }
+
+            let excluded = filter.is_selectively_excluded(&path);
+            let children = fetch_children(client, remote_root, &path, filter, depth - 1).await?;
+
+            nodes.push(RemoteFolderNode {
+                name: entry.name,
+                path,
+                excluded,
+                children,
+            });
+        }
+
+        nodes.sort_by_key(|node| node.name.to_lowercase());
+        Ok(nodes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_exclusions() {
+        let input = vec![
+            "/photos/2019/".to_string(),
+            "photos/2019/summer".to_string(),
+            "videos".to_string(),
+            "videos".to_string(),
+            "  ".to_string(),
+        ];
+        assert_eq!(
+            normalize_exclusions(&input).unwrap(),
+            vec!["photos/2019".to_string(), "videos".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_normalize_exclusions_rejects_parent_dir() {
+        let input = vec!["photos/../secret".to_string()];
+        assert!(matches!(
+            normalize_exclusions(&input),
+            Err(SyncError::ConfigError(_))
+        ));
+    }
+}
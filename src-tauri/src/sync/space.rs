@@ -0,0 +1,222 @@
+/// 存储空间预检查
+///
+/// 同步计划生成后、开始传输前检查两侧的剩余空间，避免传输到一半才失败：
+/// - 计划上传的字节数与服务器配额（RFC 4331）的剩余空间比较，不足时返回
+///   `SyncError::InsufficientRemoteStorage`；服务器不支持配额查询或未声明剩余空间时不检查
+/// - 计划下载的字节数加安全余量（`LOCAL_FREE_SPACE_MARGIN`）与本地磁盘的剩余空间比较，
+///   不足时返回 `SyncError::InsufficientLocalStorage`；无法获取剩余空间时不检查
+///
+/// 两种错误都包含缺少的字节数（上下文 shortfallBytes），并按存储空间不足发送桌面通知
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::LOCAL_FREE_SPACE_MARGIN;
+use crate::sync::planner::SyncPlan;
+use crate::webdav::client::{RemoteQuota, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 计算剩余空间与所需空间的差额
+///
+/// # 参数
+/// - available: 剩余空间（字节），未知时为 None
+/// - required: 需要的空间（字节）
+///
+/// # 返回
+/// 空间不足时返回缺少的字节数，否则返回 None
+pub fn shortfall(available: Option<u64>, required: u64) -> Option<u64> {
+    available
+        .filter(|&available| available < required)
+        .map(|available| required - available)
+}
+
+/// 检查服务器剩余空间是否足够完成计划中的上传
+///
+/// 压缩和加密会改变实际上传的大小，这里按计划中的原始大小估算；
+/// 覆盖的远程文件在许多服务器上会保留为历史版本，因此不从所需空间中扣除
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_root: 同步文件夹的远程根路径
+/// - plan: 同步计划
+///
+/// # 返回
+/// - Ok(()): 空间足够，或无法获得配额信息
+/// - Err(SyncError::InsufficientRemoteStorage): 空间不足
+pub async fn check_remote(client: &WebDavClient, remote_root: &str, plan: &SyncPlan) -> Result<()> {
+    if plan.upload_bytes == 0 {
+        return Ok(());
+    }
+
+    let quota = match client.quota(remote_root).await {
+        Ok(quota) => quota,
+        Err(e @ SyncError::Cancelled(_)) => return Err(e),
+        Err(e) => {
+            tracing::debug!(remote_root, error = %e, "查询服务器配额失败，跳过剩余空间检查");
+            RemoteQuota::default()
+        }
+    };
+
+    match shortfall(quota.available_bytes, plan.upload_bytes) {
+        Some(missing) => Err(SyncError::InsufficientRemoteStorage(format!(
+            "{} bytes to upload but only {} bytes available (short by {} bytes)",
+            plan.upload_bytes,
+            quota.available_bytes.unwrap_or(0),
+            missing
+        ))
+        .with_context("shortfallBytes", missing)),
+        None => Ok(()),
+    }
+}
+
+/// 检查本地磁盘剩余空间是否足够完成计划中的下载
+///
+/// # 参数
+/// - root: 同步文件夹本地根目录
+/// - plan: 同步计划
+///
+/// # 返回
+/// - Ok(()): 空间足够，或无法获取剩余空间
+/// - Err(SyncError::InsufficientLocalStorage): 空间不足
+pub async fn check_local(root: &Path, plan: &SyncPlan) -> Result<()> {
+    if plan.download_bytes == 0 {
+        return Ok(());
+    }
+
+    let path = root.to_path_buf();
+    let available = tokio::task::spawn_blocking(move || available_space(&path))
+        .await
+        .map_err(|e| SyncError::Unknown(e.to_string()))
+        .and_then(|result| result);
+    let available = match available {
+        Ok(available) => Some(available),
+        Err(e) => {
+            tracing::debug!(root = %root.display(), error = %e, "获取本地剩余空间失败，跳过检查");
+            None
+        }
+    };
+
+    let required = plan.download_bytes.saturating_add(LOCAL_FREE_SPACE_MARGIN);
+    match shortfall(available, required) {
+        Some(missing) => Err(SyncError::InsufficientLocalStorage(format!(
+            "{} bytes to download (plus {} bytes reserved) but only {} bytes available on {} (short by {} bytes)",
+            plan.download_bytes,
+            LOCAL_FREE_SPACE_MARGIN,
+            available.unwrap_or(0),
+            root.display(),
+            missing
+        ))
+        .with_context("shortfallBytes", missing)),
+        None => Ok(()),
+    }
+}
+
+/// 磁盘空间使用情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    /// 磁盘总容量（字节）
+    pub total_bytes: u64,
+    /// 对当前用户可用的剩余空间（字节）
+    pub available_bytes: u64,
+}
+
+impl DiskUsage {
+    /// 已使用的空间（字节，包括为系统保留的空间）
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.available_bytes)
+    }
+}
+
+/// 获取路径所在磁盘对当前用户可用的剩余空间（字节）
+pub fn available_space(path: &Path) -> Result<u64> {
+    disk_usage(path).map(|usage| usage.available_bytes)
+}
+
+/// 获取路径所在磁盘的空间使用情况
+#[cfg(unix)]
+pub fn disk_usage(path: &Path) -> Result<DiskUsage> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+        SyncError::ValidationError(format!("Path contains a NUL byte: {}", path.display()))
+    })?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: c_path 是以 NUL 结尾的有效路径，stat 指向足够大小的可写内存
+    if unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    // SAFETY: statvfs 返回 0 时已完整写入 stat
+    let stat = unsafe { stat.assume_init() };
+
+    // 各平台的字段类型不同（如 macOS 上为 u32）
+    #[allow(clippy::unnecessary_cast)]
+    Ok(DiskUsage {
+        total_bytes: (stat.f_blocks as u64).saturating_mul(stat.f_frsize as u64),
+        available_bytes: (stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64),
+    })
+}
+
+/// 获取路径所在磁盘的空间使用情况
+#[cfg(windows)]
+pub fn disk_usage(path: &Path) -> Result<DiskUsage> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    let mut total = 0u64;
+    // SAFETY: wide 以 NUL 结尾，available 与 total 为有效的可写指针，最后一个输出参数允许为空
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            &mut total,
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(DiskUsage {
+        total_bytes: total,
+        available_bytes: available,
+    })
+}
+
+/// 获取路径所在磁盘的空间使用情况（不支持的平台）
+#[cfg(not(any(unix, windows)))]
+pub fn disk_usage(path: &Path) -> Result<DiskUsage> {
+    Err(SyncError::Unknown(format!(
+        "Free space is not available on this platform: {}",
+        path.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfall() {
+        assert_eq!(shortfall(Some(100), 150), Some(50));
+        assert_eq!(shortfall(Some(150), 150), None);
+        assert_eq!(shortfall(None, 150), None);
+        assert_eq!(shortfall(Some(0), 0), None);
+    }
+
+    #[test]
+    fn test_available_space() {
+        let dir = std::env::temp_dir();
+        assert!(available_space(&dir).unwrap() > 0);
+        assert!(available_space(&dir.join("lightsync-missing-dir")).is_err());
+
+        let usage = disk_usage(&dir).unwrap();
+        assert!(usage.total_bytes >= usage.available_bytes);
+        assert_eq!(
+            usage.used_bytes(),
+            usage.total_bytes - usage.available_bytes
+        );
+    }
+}
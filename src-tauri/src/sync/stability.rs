@@ -0,0 +1,174 @@
+/// 写入中文件的稳定性检测
+///
+/// 下载器、Office 等程序写入文件时会持续修改文件，此时上传会得到不完整的内容。
+/// 生成同步计划后，对需要上传的本地文件做两项检查：
+/// - 最后修改时间距今不足静默期（`file_quiet_period_secs`）
+/// - 重新读取的大小或修改时间与扫描时不一致
+///
+/// 任一成立的文件视为仍在写入，本次同步跳过（记录为 skipped），
+/// 并通过 `StabilityQueue` 在静默期过后重新同步所在的文件夹。
+/// 常见的临时文件（`~$*`、`*.crdownload`、`*.part`）已由默认忽略模式排除
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use crate::sync::paths::PathNormalizer;
+use crate::sync::planner::{PlanItem, SyncPlan};
+use crate::sync::scanner::LocalEntry;
+
+/// 文件是否仍在写入
+///
+/// # 参数
+/// - entry: 扫描时的本地条目
+/// - current: 当前的 (大小, 修改时间)，文件已不存在时为 None
+/// - quiet_period: 静默期（秒），0 表示不检查修改时间
+/// - now: 当前时间（Unix 时间戳，秒）
+pub fn is_settling(
+    entry: &LocalEntry,
+    current: Option<(u64, Option<i64>)>,
+    quiet_period: u32,
+    now: i64,
+) -> bool {
+    let recently_modified = entry
+        .modified
+        .is_some_and(|modified| now - modified < i64::from(quiet_period));
+    let changed =
+        current.is_some_and(|(size, modified)| size != entry.size || modified != entry.modified);
+
+    recently_modified || changed
+}
+
+/// 从计划中移出仍在写入的上传条目
+///
+/// 被移出的条目放入 `SyncPlan::deferred`，上传字节数相应减少
+///
+/// # 参数
+/// - plan: 同步计划
+/// - root: 同步文件夹本地根目录
+/// - normalizer: 本地路径规范化器
+/// - local: 本地扫描结果
+/// - quiet_period: 静默期（秒）
+pub async fn defer_unstable(
+    plan: &mut SyncPlan,
+    root: &Path,
+    normalizer: &PathNormalizer,
+    local: &[LocalEntry],
+    quiet_period: u32,
+) {
+    let local: HashMap<&str, &LocalEntry> =
+        local.iter().map(|e| (e.rel_path.as_str(), e)).collect();
+    let now = chrono::Utc::now().timestamp();
+
+    let mut stable = Vec::with_capacity(plan.uploads.len());
+    for item in std::mem::take(&mut plan.uploads) {
+        let Some(entry) = local
+            .get(item.rel_path.as_str())
+            .filter(|_| !item.is_directory)
+        else {
+            stable.push(item);
+            continue;
+        };
+
+        // 符号链接（跟随或占位文件）只检查静默期
+        let current = match normalizer.local_path(root, &item.rel_path) {
+            Ok(path) => tokio::fs::symlink_metadata(path).await.ok(),
+            Err(_) => None,
+        }
+        .filter(|metadata| !metadata.file_type().is_symlink())
+        .map(|metadata| {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64);
+            (metadata.len(), modified)
+        });
+
+        if is_settling(entry, current, quiet_period, now) {
+            plan.upload_bytes -= item.size;
+            plan.deferred.push(PlanItem {
+                reason: Some("file is still being written".to_string()),
+                ..item
+            });
+        } else {
+            stable.push(item);
+        }
+    }
+    plan.uploads = stable;
+}
+
+/// 因文件仍在写入而需要稍后重新同步的文件夹
+///
+/// 作为 Tauri State 管理，后台任务定期取出到期的文件夹并同步
+#[derive(Debug, Default)]
+pub struct StabilityQueue {
+    /// 文件夹 ID -> 重新同步时间（Unix 时间戳，秒）
+    due: Mutex<HashMap<String, i64>>,
+}
+
+impl StabilityQueue {
+    /// 安排文件夹在指定时间重新同步（已安排时保留较早的时间）
+    pub fn requeue(&self, folder_id: &str, retry_at: i64) {
+        let mut due = self.due.lock().unwrap();
+        due.entry(folder_id.to_string())
+            .and_modify(|at| *at = (*at).min(retry_at))
+            .or_insert(retry_at);
+    }
+
+    /// 取出已到重新同步时间的文件夹
+    pub fn take_due(&self, now: i64) -> Vec<String> {
+        let mut due = self.due.lock().unwrap();
+        let ready: Vec<String> = due
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(folder_id, _)| folder_id.clone())
+            .collect();
+        for folder_id in &ready {
+            due.remove(folder_id);
+        }
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(size: u64, modified: i64) -> LocalEntry {
+        LocalEntry {
+            rel_path: "download.iso".to_string(),
+            is_directory: false,
+            size,
+            modified: Some(modified),
+            inode: None,
+        }
+    }
+
+    #[test]
+    fn test_is_settling() {
+        let scanned = entry(100, 1_000);
+
+        assert!(!is_settling(&scanned, Some((100, Some(1_000))), 10, 1_010));
+        // 静默期内修改过
+        assert!(is_settling(&scanned, Some((100, Some(1_000))), 10, 1_005));
+        // 扫描后大小或修改时间发生变化
+        assert!(is_settling(&scanned, Some((150, Some(1_000))), 10, 1_100));
+        assert!(is_settling(&scanned, Some((100, Some(1_050))), 10, 1_100));
+        // 静默期为 0 时只比较扫描前后的状态
+        assert!(!is_settling(&scanned, Some((100, Some(1_000))), 0, 1_000));
+    }
+
+    #[test]
+    fn test_requeue_keeps_earliest() {
+        let queue = StabilityQueue::default();
+        queue.requeue("folder-1", 200);
+        queue.requeue("folder-1", 150);
+        queue.requeue("folder-2", 300);
+
+        assert!(queue.take_due(100).is_empty());
+        assert_eq!(queue.take_due(200), vec!["folder-1".to_string()]);
+        assert!(queue.take_due(200).is_empty());
+        assert_eq!(queue.take_due(300), vec!["folder-2".to_string()]);
+    }
+}
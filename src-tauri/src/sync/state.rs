@@ -0,0 +1,305 @@
+/// 文件夹同步状态机
+///
+/// 按文件夹记录当前的同步状态，取代各处零散的状态字符串：
+///
+/// - Idle: 空闲，可以开始同步
+/// - Scanning: 扫描本地与远程文件、生成同步计划
+/// - Transferring: 传输文件，附带进度百分比
+/// - Paused: 已暂停（全局暂停或单独暂停）
+/// - Error: 上一次同步失败，附带错误码
+///
+/// 同步从 Scanning 开始，经过 Transferring 后回到 Idle、Paused 或 Error；
+/// 空闲或失败的文件夹可以被暂停，恢复后回到 Idle。
+///
+/// 状态转换在锁内完成：同一文件夹正在同步时无法再次进入 Scanning，避免重复同步。
+/// 状态变化通过 `sync://state-changed` 事件推送到前端。
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ErrorCode;
+use crate::sync::events::{SyncEventEmitter, SyncStateChangedEvent};
+use crate::{Result, SyncError};
+
+/// 文件夹同步状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum FolderSyncState {
+    /// 空闲
+    #[default]
+    Idle,
+    /// 扫描与对比
+    Scanning,
+    /// 传输文件
+    Transferring {
+        /// 已处理条目的百分比（0-100）
+        progress: u8,
+    },
+    /// 已暂停
+    Paused,
+    /// 上一次同步失败
+    Error {
+        /// 错误码
+        code: ErrorCode,
+        /// 错误信息
+        message: String,
+    },
+}
+
+impl FolderSyncState {
+    /// 根据同步错误创建 Error 状态
+    pub fn error(error: &SyncError) -> Self {
+        Self::Error {
+            code: error.code(),
+            message: error.to_string(),
+        }
+    }
+
+    /// 根据已处理条目数计算传输状态
+    pub fn transferring(processed: u32, total: u32) -> Self {
+        let progress = if total == 0 {
+            100
+        } else {
+            (u64::from(processed.min(total)) * 100 / u64::from(total)) as u8
+        };
+        Self::Transferring { progress }
+    }
+
+    /// 是否正在同步（Scanning 或 Transferring）
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Scanning | Self::Transferring { .. })
+    }
+
+    /// 状态名称，用于日志和错误信息
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Scanning => "scanning",
+            Self::Transferring { .. } => "transferring",
+            Self::Paused => "paused",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    /// 是否允许转换到目标状态
+    fn can_transition_to(&self, next: &Self) -> bool {
+        use FolderSyncState::*;
+
+        matches!(
+            (self, next),
+            (Idle | Error { .. }, Scanning | Paused)
+                | (Paused, Idle | Scanning)
+                | (Error { .. }, Idle | Error { .. })
+                | (Scanning, Transferring { .. })
+                | (Transferring { .. }, Transferring { .. })
+                | (Scanning | Transferring { .. }, Idle | Paused | Error { .. })
+        )
+    }
+}
+
+/// 同步状态管理器
+///
+/// 作为 Tauri State 管理，克隆后共享同一个状态。没有记录的文件夹视为 Idle
+#[derive(Clone, Default)]
+pub struct SyncStateManager {
+    states: Arc<Mutex<HashMap<String, FolderSyncState>>>,
+    emitter: Option<SyncEventEmitter>,
+}
+
+impl SyncStateManager {
+    /// 创建状态管理器，状态变化时通过事件发送器推送
+    pub fn new(emitter: SyncEventEmitter) -> Self {
+        Self {
+            states: Arc::default(),
+            emitter: Some(emitter),
+        }
+    }
+
+    /// 指定文件夹的当前状态
+    pub fn get(&self, folder_id: &str) -> FolderSyncState {
+        self.states
+            .lock()
+            .unwrap()
+            .get(folder_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 转换文件夹状态
+    ///
+    /// 状态未变化时不发送事件
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - next: 目标状态
+    ///
+    /// # 返回
+    /// - Ok(FolderSyncState): 转换前的状态
+    /// - Err(SyncError::Interrupted): 文件夹正在同步，不能再次开始
+    /// - Err(SyncError::ValidationError): 不允许的状态转换
+    pub fn transition(&self, folder_id: &str, next: FolderSyncState) -> Result<FolderSyncState> {
+        let mut states = self.states.lock().unwrap();
+        let previous = states.get(folder_id).cloned().unwrap_or_default();
+
+        if previous.is_active() && next == FolderSyncState::Scanning {
+            return Err(SyncError::Interrupted(format!(
+                "Sync is already running for folder: {}",
+                folder_id
+            )));
+        }
+        if previous != next && !previous.can_transition_to(&next) {
+            return Err(SyncError::ValidationError(format!(
+                "Invalid sync state transition for folder {}: {} -> {}",
+                folder_id,
+                previous.name(),
+                next.name()
+            )));
+        }
+
+        self.replace(&mut states, folder_id, &previous, next);
+        Ok(previous)
+    }
+
+    /// 根据暂停状态更新文件夹状态
+    ///
+    /// 空闲或失败的文件夹暂停后进入 Paused，Paused 的文件夹恢复后回到 Idle；
+    /// 正在同步的文件夹不受影响，由同步引擎在停止后转换到 Paused
+    ///
+    /// # 参数
+    /// - folder_id: 同步文件夹 ID
+    /// - paused: 文件夹当前是否处于暂停状态
+    pub fn apply_pause(&self, folder_id: &str, paused: bool) {
+        let mut states = self.states.lock().unwrap();
+        let previous = states.get(folder_id).cloned().unwrap_or_default();
+        let next = match (&previous, paused) {
+            (FolderSyncState::Idle | FolderSyncState::Error { .. }, true) => {
+                FolderSyncState::Paused
+            }
+            (FolderSyncState::Paused, false) => FolderSyncState::Idle,
+            _ => return,
+        };
+
+        self.replace(&mut states, folder_id, &previous, next);
+    }
+
+    /// 删除同步文件夹后移除其状态
+    pub fn remove(&self, folder_id: &str) {
+        self.states.lock().unwrap().remove(folder_id);
+    }
+
+    /// 写入新状态并发送变化事件（状态未变化时不发送）
+    ///
+    /// 在锁内调用，保证同一文件夹的事件顺序与状态转换顺序一致
+    fn replace(
+        &self,
+        states: &mut HashMap<String, FolderSyncState>,
+        folder_id: &str,
+        previous: &FolderSyncState,
+        next: FolderSyncState,
+    ) {
+        if *previous == next {
+            return;
+        }
+
+        states.insert(folder_id.to_string(), next.clone());
+        if let Some(emitter) = &self.emitter {
+            emitter.state_changed(&SyncStateChangedEvent {
+                folder_id: folder_id.to_string(),
+                previous: previous.clone(),
+                state: next,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_lifecycle() {
+        let states = SyncStateManager::default();
+        assert_eq!(states.get("f1"), FolderSyncState::Idle);
+
+        states.transition("f1", FolderSyncState::Scanning).unwrap();
+        states
+            .transition("f1", FolderSyncState::transferring(1, 4))
+            .unwrap();
+        let previous = states
+            .transition("f1", FolderSyncState::transferring(2, 4))
+            .unwrap();
+        assert_eq!(previous, FolderSyncState::Transferring { progress: 25 });
+        states.transition("f1", FolderSyncState::Idle).unwrap();
+        assert_eq!(states.get("f1"), FolderSyncState::Idle);
+
+        let error = SyncError::Network("connection reset".to_string());
+        states.transition("f1", FolderSyncState::Scanning).unwrap();
+        states
+            .transition("f1", FolderSyncState::error(&error))
+            .unwrap();
+        assert!(matches!(
+            states.get("f1"),
+            FolderSyncState::Error {
+                code: ErrorCode::NetworkError,
+                ..
+            }
+        ));
+        states.transition("f1", FolderSyncState::Scanning).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_transitions() {
+        let states = SyncStateManager::default();
+        states.transition("f1", FolderSyncState::Scanning).unwrap();
+
+        assert!(matches!(
+            states.transition("f1", FolderSyncState::Scanning),
+            Err(SyncError::Interrupted(_))
+        ));
+        states
+            .transition("f1", FolderSyncState::transferring(0, 1))
+            .unwrap();
+        assert!(matches!(
+            states.transition("f1", FolderSyncState::Scanning),
+            Err(SyncError::Interrupted(_))
+        ));
+        assert!(matches!(
+            states.transition("f2", FolderSyncState::transferring(0, 1)),
+            Err(SyncError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_pause() {
+        let states = SyncStateManager::default();
+
+        states.apply_pause("f1", true);
+        assert_eq!(states.get("f1"), FolderSyncState::Paused);
+        states.apply_pause("f1", false);
+        assert_eq!(states.get("f1"), FolderSyncState::Idle);
+
+        // 正在同步的文件夹由同步引擎在停止后转换
+        states.transition("f2", FolderSyncState::Scanning).unwrap();
+        states.apply_pause("f2", true);
+        assert_eq!(states.get("f2"), FolderSyncState::Scanning);
+        states.transition("f2", FolderSyncState::Paused).unwrap();
+        states.apply_pause("f2", false);
+        assert_eq!(states.get("f2"), FolderSyncState::Idle);
+    }
+
+    #[test]
+    fn test_state_serialization() {
+        let json = serde_json::to_value(FolderSyncState::Transferring { progress: 40 }).unwrap();
+        assert_eq!(json["state"], "transferring");
+        assert_eq!(json["progress"], 40);
+
+        let json = serde_json::to_value(FolderSyncState::Error {
+            code: ErrorCode::AuthFailed,
+            message: "unauthorized".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["state"], "error");
+        assert_eq!(json["code"], "AUTH_FAILED");
+    }
+}
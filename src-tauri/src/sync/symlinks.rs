@@ -0,0 +1,152 @@
+/// 符号链接处理
+///
+/// 同步文件夹中的符号链接（Windows 上包括目录联接 junction）按文件夹的 `symlink_policy` 处理：
+/// - skip: 扫描时跳过
+/// - follow: 跟随链接同步目标内容；目标位于同步根目录之外或形成循环时跳过
+/// - sync-as-placeholder: 不跟随链接，上传一个内容为链接目标路径的占位文件
+///
+/// 无论哪种策略，下载和创建目录都不会经由不允许跟随的链接写入，
+/// 避免远程文件通过本地链接写到同步根目录之外。
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::constants::symlink_policy;
+use crate::{Result, SyncError};
+
+/// 符号链接处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// 跳过符号链接
+    Skip,
+    /// 跟随符号链接（仅限同步根目录内的目标）
+    Follow,
+    /// 以占位文件同步链接本身
+    Placeholder,
+}
+
+impl SymlinkPolicy {
+    /// 从配置值解析，未知的值按跳过处理
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            symlink_policy::FOLLOW => Self::Follow,
+            symlink_policy::PLACEHOLDER => Self::Placeholder,
+            _ => Self::Skip,
+        }
+    }
+}
+
+/// 判断路径本身是否为符号链接（不跟随，路径不存在时返回 false）
+pub fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+/// 解析链接目标，目标必须位于同步根目录之内
+///
+/// # 参数
+/// - root: 规范化后的同步根目录（`fs::canonicalize`）
+/// - link: 符号链接路径
+///
+/// # 返回
+/// - Some(PathBuf): 规范化后的目标路径
+/// - None: 链接已失效或目标位于同步根目录之外
+pub fn resolve_within_root(root: &Path, link: &Path) -> Option<PathBuf> {
+    let target = fs::canonicalize(link).ok()?;
+    target.starts_with(root).then_some(target)
+}
+
+/// 生成链接的占位文件内容（链接目标路径）
+pub fn placeholder_content(link: &Path) -> Result<String> {
+    Ok(fs::read_link(link)?.to_string_lossy().into_owned())
+}
+
+/// 检查写入本地路径前经过的符号链接
+///
+/// 从同步根目录逐级检查本地相对路径的每一段（包括最后一段），
+/// 遇到符号链接时只有 follow 策略且目标在同步根目录内才允许写入
+///
+/// # 参数
+/// - root: 同步根目录
+/// - local_rel_path: 本地相对路径（`/` 分隔）
+/// - policy: 符号链接处理策略
+///
+/// # 返回
+/// - Ok(()): 可以写入
+/// - Err(SyncError::ValidationError): 路径经过不允许跟随的符号链接
+pub fn check_write_target(root: &Path, local_rel_path: &str, policy: SymlinkPolicy) -> Result<()> {
+    let canonical_root = fs::canonicalize(root)?;
+    let mut path = root.to_path_buf();
+
+    for segment in local_rel_path.split('/').filter(|s| !s.is_empty()) {
+        path.push(segment);
+        if !is_symlink(&path) {
+            continue;
+        }
+
+        let allowed = policy == SymlinkPolicy::Follow
+            && resolve_within_root(&canonical_root, &path).is_some();
+        if !allowed {
+            return Err(SyncError::ValidationError(format!(
+                "Refusing to write through symbolic link: {}",
+                path.display()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use uuid::Uuid;
+
+    fn create_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("inside")).unwrap();
+        fs::canonicalize(root).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_within_root() {
+        let root = create_root();
+        let outside = std::env::temp_dir();
+        symlink(root.join("inside"), root.join("ok")).unwrap();
+        symlink(&outside, root.join("escape")).unwrap();
+        symlink(root.join("missing"), root.join("broken")).unwrap();
+
+        assert_eq!(
+            resolve_within_root(&root, &root.join("ok")),
+            Some(root.join("inside"))
+        );
+        assert_eq!(resolve_within_root(&root, &root.join("escape")), None);
+        assert_eq!(resolve_within_root(&root, &root.join("broken")), None);
+        assert_eq!(
+            placeholder_content(&root.join("broken")).unwrap(),
+            root.join("missing").to_string_lossy()
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_check_write_target() {
+        let root = create_root();
+        symlink(root.join("inside"), root.join("ok")).unwrap();
+        symlink(std::env::temp_dir(), root.join("escape")).unwrap();
+
+        assert!(check_write_target(&root, "inside/a.txt", SymlinkPolicy::Skip).is_ok());
+        assert!(check_write_target(&root, "new/dir/a.txt", SymlinkPolicy::Skip).is_ok());
+
+        assert!(check_write_target(&root, "ok/a.txt", SymlinkPolicy::Skip).is_err());
+        assert!(check_write_target(&root, "ok/a.txt", SymlinkPolicy::Placeholder).is_err());
+        assert!(check_write_target(&root, "ok/a.txt", SymlinkPolicy::Follow).is_ok());
+
+        assert!(check_write_target(&root, "escape/a.txt", SymlinkPolicy::Follow).is_err());
+        assert!(check_write_target(&root, "escape", SymlinkPolicy::Follow).is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
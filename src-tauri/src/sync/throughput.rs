@@ -0,0 +1,432 @@
+/// 传输速度统计
+///
+/// 记录每个进行中的传输（文件夹同步与手动传输）已传输的字节数，按滑动窗口
+/// （`TRANSFER_SPEED_WINDOW_SECS`）计算每个传输与每个服务器的实时速度：
+/// - 传输开始时通过 `TransferStats::begin` 登记，得到的 `TransferMeter` 在每个数据块后更新进度
+/// - `TransferMeter` 被丢弃时传输从统计中移除，服务器窗口中的数据随时间自然过期
+/// - 前端通过 `get_transfer_stats` 命令读取当前快照
+///
+/// 传输完成后的平均速度由 `average_speed` 计算，写入同步日志；
+/// 同步进度事件中的预计剩余时间由 `estimate_remaining_secs` 计算
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::TRANSFER_SPEED_WINDOW_SECS;
+use crate::sync::transfer::TransferKind;
+
+/// 滑动窗口内的吞吐量
+#[derive(Debug, Clone)]
+pub struct SpeedWindow {
+    window: Duration,
+    /// (时间, 字节数) 样本，按时间递增
+    samples: VecDeque<(Instant, u64)>,
+    /// 本轮统计的开始时间（窗口清空后重新计时）
+    since: Instant,
+}
+
+impl SpeedWindow {
+    /// 创建滑动窗口
+    pub fn new(window: Duration, now: Instant) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+            since: now,
+        }
+    }
+
+    /// 记录一次传输的字节数
+    pub fn record(&mut self, now: Instant, bytes: u64) {
+        self.expire(now);
+        if self.samples.is_empty() {
+            self.since = now;
+        }
+        self.samples.push_back((now, bytes));
+    }
+
+    /// 窗口内的平均速度（字节/秒）
+    ///
+    /// 统计开始不足一个窗口时按实际经过的时间计算，避免刚开始的传输速度偏低
+    pub fn bytes_per_sec(&mut self, now: Instant) -> u64 {
+        self.expire(now);
+        let bytes: u64 = self.samples.iter().map(|(_, bytes)| bytes).sum();
+        let span = now.saturating_duration_since(self.since).min(self.window);
+        if bytes == 0 || span.is_zero() {
+            return 0;
+        }
+        (bytes as f64 / span.as_secs_f64()) as u64
+    }
+
+    /// 窗口内是否没有样本
+    pub fn is_idle(&mut self, now: Instant) -> bool {
+        self.expire(now);
+        self.samples.is_empty()
+    }
+
+    /// 移除窗口外的样本
+    fn expire(&mut self, now: Instant) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+/// 进行中的传输的速度
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveTransferStats {
+    /// 传输 ID（进程内递增）
+    pub id: u64,
+    /// 服务器 ID
+    pub server_id: String,
+    /// 同步文件夹 ID（手动传输为 None）
+    pub folder_id: Option<String>,
+    /// 文件路径（文件夹同步为相对路径，手动传输为远程路径）
+    pub path: String,
+    /// 传输方向
+    pub kind: TransferKind,
+    /// 已传输字节数
+    pub bytes_transferred: u64,
+    /// 总字节数（未知时为 None）
+    pub total_bytes: Option<u64>,
+    /// 当前速度（字节/秒）
+    pub bytes_per_sec: u64,
+    /// 已用时间（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 服务器的吞吐量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerThroughput {
+    /// 服务器 ID
+    pub server_id: String,
+    /// 当前速度（字节/秒，所有传输合计）
+    pub bytes_per_sec: u64,
+    /// 进行中的传输数量
+    pub active_transfers: usize,
+}
+
+/// 传输速度快照
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferStatsSnapshot {
+    /// 进行中的传输（按开始顺序）
+    pub transfers: Vec<ActiveTransferStats>,
+    /// 窗口内有数据传输或有进行中传输的服务器（按服务器 ID 排序）
+    pub servers: Vec<ServerThroughput>,
+}
+
+#[derive(Debug)]
+struct ActiveTransfer {
+    server_id: String,
+    folder_id: Option<String>,
+    path: String,
+    kind: TransferKind,
+    transferred: u64,
+    total: Option<u64>,
+    started: Instant,
+    window: SpeedWindow,
+}
+
+#[derive(Debug, Default)]
+struct StatsInner {
+    next_id: u64,
+    transfers: HashMap<u64, ActiveTransfer>,
+    servers: HashMap<String, SpeedWindow>,
+}
+
+/// 传输速度统计
+///
+/// 作为 Tauri State 管理，克隆后共享同一个状态
+#[derive(Debug, Clone)]
+pub struct TransferStats {
+    window: Duration,
+    inner: Arc<Mutex<StatsInner>>,
+}
+
+impl Default for TransferStats {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(TRANSFER_SPEED_WINDOW_SECS))
+    }
+}
+
+impl TransferStats {
+    /// 创建指定窗口长度的统计
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            inner: Arc::new(Mutex::new(StatsInner::default())),
+        }
+    }
+
+    /// 登记开始的传输
+    ///
+    /// # 参数
+    /// - server_id: 服务器 ID
+    /// - folder_id: 同步文件夹 ID（手动传输为 None）
+    /// - path: 文件路径
+    /// - kind: 传输方向
+    /// - total: 预计传输的字节数（未知时为 None）
+    ///
+    /// # 返回
+    /// 传输进度记录器，丢弃时传输从统计中移除
+    pub fn begin(
+        &self,
+        server_id: &str,
+        folder_id: Option<&str>,
+        path: &str,
+        kind: TransferKind,
+        total: Option<u64>,
+    ) -> TransferMeter {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        inner.next_id += 1;
+        let id = inner.next_id;
+        inner.transfers.insert(
+            id,
+            ActiveTransfer {
+                server_id: server_id.to_string(),
+                folder_id: folder_id.map(str::to_string),
+                path: path.to_string(),
+                kind,
+                transferred: 0,
+                total,
+                started: now,
+                window: SpeedWindow::new(self.window, now),
+            },
+        );
+        TransferMeter {
+            stats: self.clone(),
+            id,
+        }
+    }
+
+    /// 当前的传输速度快照
+    pub fn snapshot(&self) -> TransferStatsSnapshot {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+
+        let mut transfers: Vec<ActiveTransferStats> = inner
+            .transfers
+            .iter_mut()
+            .map(|(&id, transfer)| ActiveTransferStats {
+                id,
+                server_id: transfer.server_id.clone(),
+                folder_id: transfer.folder_id.clone(),
+                path: transfer.path.clone(),
+                kind: transfer.kind,
+                bytes_transferred: transfer.transferred,
+                total_bytes: transfer.total,
+                bytes_per_sec: transfer.window.bytes_per_sec(now),
+                elapsed_ms: now.saturating_duration_since(transfer.started).as_millis() as u64,
+            })
+            .collect();
+        transfers.sort_by_key(|transfer| transfer.id);
+
+        let mut servers: Vec<ServerThroughput> = inner
+            .servers
+            .iter_mut()
+            .filter_map(|(server_id, window)| {
+                let active_transfers = transfers
+                    .iter()
+                    .filter(|transfer| &transfer.server_id == server_id)
+                    .count();
+                if active_transfers == 0 && window.is_idle(now) {
+                    return None;
+                }
+                Some(ServerThroughput {
+                    server_id: server_id.clone(),
+                    bytes_per_sec: window.bytes_per_sec(now),
+                    active_transfers,
+                })
+            })
+            .collect();
+        servers.sort_by(|a, b| a.server_id.cmp(&b.server_id));
+
+        // 没有传输也没有近期数据的服务器不再保留窗口
+        let active: Vec<String> = servers.iter().map(|s| s.server_id.clone()).collect();
+        inner
+            .servers
+            .retain(|server_id, _| active.contains(server_id));
+
+        TransferStatsSnapshot { transfers, servers }
+    }
+
+    /// 更新传输进度
+    fn update(&self, id: u64, transferred: u64, total: Option<u64>) {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        let Some(transfer) = inner.transfers.get_mut(&id) else {
+            return;
+        };
+
+        // 重新发送（如创建父目录后重新上传）时进度从 0 开始，不计入速度
+        let delta = transferred.saturating_sub(transfer.transferred);
+        transfer.transferred = transferred;
+        if total.is_some() {
+            transfer.total = total;
+        }
+        if delta == 0 {
+            return;
+        }
+
+        transfer.window.record(now, delta);
+        inner
+            .servers
+            .entry(transfer.server_id.clone())
+            .or_insert_with(|| SpeedWindow::new(self.window, now))
+            .record(now, delta);
+    }
+
+    /// 移除结束的传输
+    fn finish(&self, id: u64) {
+        self.inner.lock().unwrap().transfers.remove(&id);
+    }
+}
+
+/// 单个传输的进度记录器
+///
+/// 丢弃时传输从统计中移除
+#[derive(Debug)]
+pub struct TransferMeter {
+    stats: TransferStats,
+    id: u64,
+}
+
+impl TransferMeter {
+    /// 更新已传输的字节数
+    ///
+    /// # 参数
+    /// - transferred: 本次传输累计已传输的字节数
+    /// - total: 总字节数（已知时更新）
+    pub fn update(&self, transferred: u64, total: Option<u64>) {
+        self.stats.update(self.id, transferred, total);
+    }
+}
+
+impl Drop for TransferMeter {
+    fn drop(&mut self) {
+        self.stats.finish(self.id);
+    }
+}
+
+/// 计算传输的平均速度（字节/秒）
+///
+/// # 返回
+/// 耗时为 0 时返回 None
+pub fn average_speed(bytes: u64, duration_ms: i64) -> Option<i64> {
+    if duration_ms <= 0 {
+        return None;
+    }
+    Some((bytes as f64 * 1000.0 / duration_ms as f64) as i64)
+}
+
+/// 按当前速度估算剩余时间（秒，向上取整）
+///
+/// # 参数
+/// - remaining: 剩余字节数
+/// - bytes_per_sec: 当前速度（字节/秒）
+///
+/// # 返回
+/// 速度为 0 时无法估算，返回 None
+pub fn estimate_remaining_secs(remaining: u64, bytes_per_sec: u64) -> Option<u64> {
+    if bytes_per_sec == 0 {
+        return None;
+    }
+    Some(remaining.div_ceil(bytes_per_sec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_window() {
+        let start = Instant::now();
+        let mut window = SpeedWindow::new(Duration::from_secs(5), start);
+
+        assert_eq!(window.bytes_per_sec(start), 0);
+        window.record(start, 1000);
+        window.record(start + Duration::from_secs(1), 1000);
+        // 统计开始 2 秒，共 2000 字节
+        assert_eq!(window.bytes_per_sec(start + Duration::from_secs(2)), 1000);
+        // 超过窗口长度后按窗口计算
+        window.record(start + Duration::from_secs(5), 3000);
+        assert_eq!(window.bytes_per_sec(start + Duration::from_secs(5)), 1000);
+        // 早期样本过期
+        assert_eq!(window.bytes_per_sec(start + Duration::from_secs(7)), 600);
+        assert!(window.is_idle(start + Duration::from_secs(11)));
+        assert_eq!(window.bytes_per_sec(start + Duration::from_secs(11)), 0);
+    }
+
+    #[test]
+    fn test_transfer_stats_snapshot() {
+        let stats = TransferStats::default();
+        let upload = stats.begin(
+            "server-1",
+            Some("folder-1"),
+            "a.txt",
+            TransferKind::Upload,
+            Some(100),
+        );
+        let download = stats.begin("server-1", None, "/b.txt", TransferKind::Download, None);
+        upload.update(40, None);
+        download.update(10, Some(50));
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.transfers.len(), 2);
+        assert_eq!(snapshot.transfers[0].path, "a.txt");
+        assert_eq!(snapshot.transfers[0].bytes_transferred, 40);
+        assert_eq!(snapshot.transfers[0].total_bytes, Some(100));
+        assert_eq!(snapshot.transfers[1].total_bytes, Some(50));
+        assert_eq!(snapshot.servers.len(), 1);
+        assert_eq!(snapshot.servers[0].active_transfers, 2);
+
+        // 进度回退（重新发送）时不计入速度
+        upload.update(0, None);
+        assert_eq!(stats.snapshot().transfers[0].bytes_transferred, 0);
+
+        drop(upload);
+        drop(download);
+        let snapshot = stats.snapshot();
+        assert!(snapshot.transfers.is_empty());
+        // 窗口内仍有数据的服务器继续显示速度
+        assert_eq!(snapshot.servers.len(), 1);
+        assert_eq!(snapshot.servers[0].active_transfers, 0);
+    }
+
+    #[test]
+    fn test_idle_servers_are_dropped() {
+        let stats = TransferStats::new(Duration::ZERO);
+        let meter = stats.begin("server-1", None, "/a", TransferKind::Upload, None);
+        meter.update(10, None);
+        drop(meter);
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(stats.snapshot(), TransferStatsSnapshot::default());
+    }
+
+    #[test]
+    fn test_average_speed() {
+        assert_eq!(average_speed(1000, 500), Some(2000));
+        assert_eq!(average_speed(1000, 0), None);
+        assert_eq!(average_speed(0, 10), Some(0));
+    }
+
+    #[test]
+    fn test_estimate_remaining_secs() {
+        assert_eq!(estimate_remaining_secs(1000, 100), Some(10));
+        assert_eq!(estimate_remaining_secs(1001, 100), Some(11));
+        assert_eq!(estimate_remaining_secs(0, 100), Some(0));
+        assert_eq!(estimate_remaining_secs(1000, 0), None);
+    }
+}
@@ -0,0 +1,680 @@
+/// 传输池
+///
+/// 以固定并发数执行上传和下载任务。每个工作任务在开始新文件前检查暂停信号，
+/// 传输过程中在每个数据块之间检查，暂停时在当前数据块完成后停止；
+/// 未开始或被中断的任务以 `TransferOutcome::Paused` 返回，留待恢复后重新执行。
+/// 会话被取消时进行中的请求立即中止，剩余任务以 `TransferOutcome::Cancelled` 返回。
+/// 文件夹启用端到端加密时，上传前先加密到临时文件，下载的密文解密后再写入本地。
+/// 文件夹启用压缩时，匹配的文件上传前先压缩；下载的文件带有压缩标记时自动解压。
+/// 不小于并行下载阈值的文件在服务器支持范围请求时使用多个连接分段下载。
+/// 带有 `resume_token` 的下载中断后保留临时文件，已有临时文件时通过 `If-Range` 从断点继续。
+/// 提供速度统计时，每个传输的进度登记到 `TransferStats`，供前端显示实时速度；
+/// 所有任务已传输的字节数通过 `TransferPool::bytes_transferred` 读取，用于估算剩余时间。
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::constants::PARALLEL_DOWNLOAD_CONNECTIONS;
+use crate::sync::compression::{self, CompressionPolicy};
+use crate::sync::control::PauseSignal;
+use crate::sync::encryption::{self, FolderCipher};
+use crate::sync::throughput::TransferStats;
+use crate::webdav::client::{partial_download_path, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 传输方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferKind {
+    /// 上传到远程
+    Upload,
+    /// 下载到本地
+    Download,
+}
+
+/// 传输任务
+#[derive(Debug, Clone)]
+pub struct TransferJob {
+    /// 相对于同步根目录的路径
+    pub rel_path: String,
+    /// 传输方向
+    pub kind: TransferKind,
+    /// 本地绝对路径
+    pub local_path: PathBuf,
+    /// 远程路径（相对于服务器根路径）
+    pub remote_path: String,
+    /// 预计传输的字节数
+    pub size: u64,
+    /// 上传时持有的远程文件锁令牌
+    pub lock_token: Option<String>,
+    /// 下载时远程文件的强 ETag（提供时中断后保留临时文件，下次从断点继续）
+    pub resume_token: Option<String>,
+}
+
+/// 传输结果
+#[derive(Debug)]
+pub enum TransferOutcome {
+    /// 传输完成
+    Completed {
+        /// 实际传输的字节数
+        bytes: u64,
+        /// 耗时（毫秒）
+        duration_ms: i64,
+        /// 远程保存的内容与本地文件不同（已压缩）时，远程内容的大小
+        stored_size: Option<u64>,
+    },
+    /// 因暂停未开始或被中断
+    Paused,
+    /// 因会话取消未开始或被中止
+    Cancelled,
+    /// 传输失败
+    Failed(SyncError),
+}
+
+/// 传输速度统计及传输所属的服务器与文件夹
+#[derive(Debug, Clone)]
+struct StatsTarget {
+    stats: TransferStats,
+    server_id: String,
+    folder_id: String,
+}
+
+/// 所有传输任务共用的设置
+#[derive(Clone, Default)]
+struct TransferOptions {
+    cipher: Option<Arc<FolderCipher>>,
+    compression: Option<Arc<CompressionPolicy>>,
+    parallel_download_threshold: u64,
+    stats: Option<StatsTarget>,
+}
+
+/// 传输池
+pub struct TransferPool {
+    client: Arc<WebDavClient>,
+    signal: PauseSignal,
+    cancel: CancellationToken,
+    concurrency: usize,
+    options: TransferOptions,
+    /// 所有任务已传输的字节数
+    transferred: Arc<AtomicU64>,
+}
+
+impl TransferPool {
+    /// 创建传输池
+    ///
+    /// # 参数
+    /// - client: WebDAV 客户端
+    /// - signal: 所属文件夹的暂停信号
+    /// - cancel: 所属会话的取消令牌
+    /// - concurrency: 最大并发传输数
+    pub fn new(
+        client: Arc<WebDavClient>,
+        signal: PauseSignal,
+        cancel: CancellationToken,
+        concurrency: usize,
+    ) -> Self {
+        Self {
+            client,
+            signal,
+            cancel,
+            concurrency: concurrency.max(1),
+            options: TransferOptions::default(),
+            transferred: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 使用加密器加解密传输的文件内容
+    pub fn with_cipher(mut self, cipher: Option<Arc<FolderCipher>>) -> Self {
+        self.options.cipher = cipher;
+        self
+    }
+
+    /// 使用压缩策略压缩上传的文件
+    pub fn with_compression(mut self, compression: Option<Arc<CompressionPolicy>>) -> Self {
+        self.options.compression = compression;
+        self
+    }
+
+    /// 不小于 threshold 字节的下载使用多个连接按范围并行下载（0 表示不启用）
+    pub fn with_parallel_downloads(mut self, threshold: u64) -> Self {
+        self.options.parallel_download_threshold = threshold;
+        self
+    }
+
+    /// 将传输进度登记到速度统计
+    ///
+    /// # 参数
+    /// - stats: 传输速度统计
+    /// - server_id: 服务器 ID
+    /// - folder_id: 同步文件夹 ID
+    pub fn with_stats(mut self, stats: TransferStats, server_id: &str, folder_id: &str) -> Self {
+        self.options.stats = Some(StatsTarget {
+            stats,
+            server_id: server_id.to_string(),
+            folder_id: folder_id.to_string(),
+        });
+        self
+    }
+
+    /// 所有任务已传输的字节数（包括进行中与未完成的传输，重新发送的部分不重复计算）
+    pub fn bytes_transferred(&self) -> u64 {
+        self.transferred.load(Ordering::Relaxed)
+    }
+
+    /// 执行传输任务
+    ///
+    /// # 返回
+    /// 每个任务及其结果（顺序与完成顺序一致，不保证与输入顺序相同）
+    pub async fn run(&self, jobs: Vec<TransferJob>) -> Vec<(TransferJob, TransferOutcome)> {
+        let workers = self.concurrency.min(jobs.len());
+        let queue = Arc::new(Mutex::new(VecDeque::from(jobs)));
+        let mut tasks = tokio::task::JoinSet::new();
+
+        for _ in 0..workers {
+            let queue = queue.clone();
+            let client = self.client.clone();
+            let signal = self.signal.clone();
+            let cancel = self.cancel.clone();
+            let options = self.options.clone();
+            let transferred = self.transferred.clone();
+            tasks.spawn(async move {
+                let mut results = Vec::new();
+                while !signal.is_paused() && !cancel.is_cancelled() {
+                    let Some(job) = queue.lock().unwrap().pop_front() else {
+                        break;
+                    };
+                    let outcome =
+                        transfer_transformed(&client, &options, &job, &signal, &transferred).await;
+                    results.push((job, outcome));
+                }
+                results
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(worker_results) => results.extend(worker_results),
+                Err(e) => tracing::error!(error = %e, "传输任务异常退出"),
+            }
+        }
+
+        // 暂停或取消后剩余的任务
+        let remaining: Vec<TransferJob> = queue.lock().unwrap().drain(..).collect();
+        results.extend(remaining.into_iter().map(|job| {
+            if self.cancel.is_cancelled() {
+                (job, TransferOutcome::Cancelled)
+            } else {
+                (job, TransferOutcome::Paused)
+            }
+        }));
+
+        results
+    }
+}
+
+/// 执行单个传输任务
+///
+/// # 参数
+/// - parallel_threshold: 不小于该大小的下载使用多个连接按范围并行下载（0 表示不启用）
+/// - on_progress: 每个数据块后调用，参数为（已传输字节数, 总字节数）
+async fn transfer<P>(
+    client: &WebDavClient,
+    job: &TransferJob,
+    signal: &PauseSignal,
+    parallel_threshold: u64,
+    on_progress: P,
+) -> TransferOutcome
+where
+    P: Fn(u64, Option<u64>) + Send + Sync + 'static,
+{
+    let started = Instant::now();
+
+    let result = match job.kind {
+        TransferKind::Upload => {
+            let signal = signal.clone();
+            let should_stop = move || signal.is_paused();
+            client
+                .upload_with_parents(
+                    &job.local_path,
+                    &job.remote_path,
+                    job.lock_token.as_deref(),
+                    should_stop,
+                    on_progress,
+                )
+                .await
+        }
+        TransferKind::Download => {
+            if let Some(parent) = job.local_path.parent() {
+                if let Err(e) = tokio::fs::create_dir_all(parent).await {
+                    return TransferOutcome::Failed(SyncError::Io(e));
+                }
+            }
+            // 已有可以续传的临时文件时优先从断点继续
+            let resuming =
+                job.resume_token.is_some() && partial_download_path(&job.local_path).exists();
+            if parallel_threshold > 0 && job.size >= parallel_threshold && !resuming {
+                client
+                    .download_ranged(
+                        &job.remote_path,
+                        &job.local_path,
+                        PARALLEL_DOWNLOAD_CONNECTIONS,
+                        || signal.is_paused(),
+                        on_progress,
+                    )
+                    .await
+            } else if let Some(validator) = &job.resume_token {
+                client
+                    .download_resumable(
+                        &job.remote_path,
+                        &job.local_path,
+                        validator,
+                        || signal.is_paused(),
+                        on_progress,
+                    )
+                    .await
+            } else {
+                client
+                    .download_interruptible(
+                        &job.remote_path,
+                        &job.local_path,
+                        || signal.is_paused(),
+                        on_progress,
+                    )
+                    .await
+            }
+        }
+    };
+
+    match result {
+        Ok(bytes) => TransferOutcome::Completed {
+            bytes,
+            duration_ms: started.elapsed().as_millis() as i64,
+            stored_size: None,
+        },
+        Err(SyncError::Interrupted(_)) => {
+            tracing::info!(path = %job.rel_path, "传输已暂停");
+            TransferOutcome::Paused
+        }
+        Err(SyncError::Cancelled(_)) => {
+            tracing::info!(path = %job.rel_path, "传输已取消");
+            TransferOutcome::Cancelled
+        }
+        Err(e) => TransferOutcome::Failed(e),
+    }
+}
+
+/// 执行单个需要变换内容的传输任务
+///
+/// 上传时按需把本地文件压缩、加密到系统临时目录再上传；下载时密文先下载到临时目录，
+/// 解密后写入本地路径，带压缩标记的内容再原地解压。临时文件在传输结束后删除
+async fn transfer_transformed(
+    client: &WebDavClient,
+    options: &TransferOptions,
+    job: &TransferJob,
+    signal: &PauseSignal,
+    transferred: &Arc<AtomicU64>,
+) -> TransferOutcome {
+    let mut temps = Vec::new();
+    let outcome =
+        match transform_and_transfer(client, options, job, signal, transferred, &mut temps).await {
+            Ok(outcome) => outcome,
+            Err(e) => TransferOutcome::Failed(e),
+        };
+
+    for tmp in temps {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
+    outcome
+}
+
+async fn transform_and_transfer(
+    client: &WebDavClient,
+    options: &TransferOptions,
+    job: &TransferJob,
+    signal: &PauseSignal,
+    transferred: &Arc<AtomicU64>,
+    temps: &mut Vec<PathBuf>,
+) -> Result<TransferOutcome> {
+    let cipher = options.cipher.as_ref();
+    let compression = options.compression.as_ref();
+    let mut staged = job.clone();
+    let mut stored_size = None;
+
+    match job.kind {
+        TransferKind::Upload => {
+            if let Some(policy) = compression.filter(|p| p.should_compress(&job.rel_path, job.size))
+            {
+                let tmp = encryption::temp_path("compressed");
+                temps.push(tmp.clone());
+                let policy = policy.clone();
+                let (src, dst) = (job.local_path.clone(), tmp.clone());
+                let size =
+                    run_blocking("Compression", move || policy.compress_file(&src, &dst)).await?;
+                // 压缩后没有变小的文件仍上传原文件
+                if size < job.size {
+                    staged.local_path = tmp;
+                    stored_size = Some(size);
+                }
+            }
+            if let Some(cipher) = cipher {
+                let tmp = encryption::temp_path("encrypted");
+                temps.push(tmp.clone());
+                let cipher = cipher.clone();
+                let (src, dst) = (staged.local_path.clone(), tmp.clone());
+                run_blocking("Encryption", move || cipher.encrypt_file(&src, &dst)).await?;
+                staged.local_path = tmp;
+            }
+        }
+        TransferKind::Download => {
+            if cipher.is_some() {
+                let tmp = encryption::temp_path("encrypted");
+                temps.push(tmp.clone());
+                staged.local_path = tmp;
+                // 密文下载到临时目录，传输结束后删除，不能续传
+                staged.resume_token = None;
+            }
+        }
+    }
+
+    // 解密、解压完成前传输仍显示为进行中
+    let meter = options.stats.as_ref().map(|target| {
+        Arc::new(target.stats.begin(
+            &target.server_id,
+            Some(&target.folder_id),
+            &job.rel_path,
+            job.kind,
+            Some(staged.size),
+        ))
+    });
+    let progress_meter = meter.clone();
+    let transferred = transferred.clone();
+    // 本任务已计入的字节数，重新发送时进度从 0 开始，只累计超出的部分
+    let counted = AtomicU64::new(0);
+    let on_progress = move |sent: u64, total: Option<u64>| {
+        if let Some(meter) = &progress_meter {
+            meter.update(sent, total);
+        }
+        let previous = counted.fetch_max(sent, Ordering::Relaxed);
+        transferred.fetch_add(sent.saturating_sub(previous), Ordering::Relaxed);
+    };
+    let outcome = transfer(
+        client,
+        &staged,
+        signal,
+        options.parallel_download_threshold,
+        on_progress,
+    )
+    .await;
+    let TransferOutcome::Completed {
+        bytes, duration_ms, ..
+    } = outcome
+    else {
+        return Ok(outcome);
+    };
+
+    if job.kind == TransferKind::Download {
+        if let Some(parent) = job.local_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        if let Some(cipher) = cipher {
+            let cipher = cipher.clone();
+            let (src, dst) = (staged.local_path.clone(), job.local_path.clone());
+            run_blocking("Decryption", move || cipher.decrypt_file(&src, &dst)).await?;
+        }
+        let path = job.local_path.clone();
+        stored_size = run_blocking("Decompression", move || {
+            compression::decompress_in_place(&path)
+        })
+        .await?;
+    }
+
+    Ok(TransferOutcome::Completed {
+        bytes,
+        duration_ms,
+        stored_size,
+    })
+}
+
+/// 在阻塞线程池中执行文件变换
+async fn run_blocking<T, F>(name: &str, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| SyncError::Unknown(format!("{} task failed: {}", name, e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::control::SyncControl;
+    use crate::webdav::mock_server_config;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn create_client(url: String) -> Arc<WebDavClient> {
+        let config = mock_server_config(&url);
+        Arc::new(WebDavClient::new(&config, "password".to_string()).unwrap())
+    }
+
+    fn download_job(dir: &std::path::Path, name: &str) -> TransferJob {
+        TransferJob {
+            rel_path: name.to_string(),
+            kind: TransferKind::Download,
+            local_path: dir.join(name),
+            remote_path: format!("/docs/{}", name),
+            size: 5,
+            lock_token: None,
+            resume_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_downloads_all_jobs() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", mockito::Matcher::Regex("^/docs/".to_string()))
+            .with_status(200)
+            .with_body("hello")
+            .expect(3)
+            .create_async()
+            .await;
+
+        let control = SyncControl::default();
+        let pool = TransferPool::new(
+            create_client(server.url()),
+            control.signal("folder-1"),
+            CancellationToken::new(),
+            2,
+        );
+        let jobs = ["a.txt", "b.txt", "sub/c.txt"]
+            .iter()
+            .map(|name| download_job(&dir, name))
+            .collect();
+
+        let results = pool.run(jobs).await;
+
+        mock.assert_async().await;
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|(_, outcome)| matches!(outcome, TransferOutcome::Completed { bytes: 5, .. })));
+        assert_eq!(fs::read_to_string(dir.join("sub/c.txt")).unwrap(), "hello");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_records_transfer_speed() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/docs/a.txt")
+            .with_status(200)
+            .with_body("hello")
+            .create_async()
+            .await;
+
+        let stats = TransferStats::default();
+        let control = SyncControl::default();
+        let pool = TransferPool::new(
+            create_client(server.url()),
+            control.signal("folder-1"),
+            CancellationToken::new(),
+            1,
+        )
+        .with_stats(stats.clone(), "server-1", "folder-1");
+
+        let results = pool.run(vec![download_job(&dir, "a.txt")]).await;
+
+        assert!(matches!(results[0].1, TransferOutcome::Completed { .. }));
+        let snapshot = stats.snapshot();
+        assert!(snapshot.transfers.is_empty());
+        assert_eq!(snapshot.servers.len(), 1);
+        assert_eq!(snapshot.servers[0].server_id, "server-1");
+        assert!(snapshot.servers[0].bytes_per_sec > 0);
+        assert_eq!(pool.bytes_transferred(), 5);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_paused_folder_returns_jobs_untouched() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        let server = mockito::Server::new_async().await;
+
+        let control = SyncControl::default();
+        control.pause(Some("folder-1"));
+        let pool = TransferPool::new(
+            create_client(server.url()),
+            control.signal("folder-1"),
+            CancellationToken::new(),
+            2,
+        );
+
+        let results = pool.run(vec![download_job(&dir, "a.txt")]).await;
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].1, TransferOutcome::Paused));
+        assert!(!dir.join("a.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_session_returns_jobs_cancelled() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        let server = mockito::Server::new_async().await;
+
+        let control = SyncControl::default();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let pool = TransferPool::new(
+            create_client(server.url()),
+            control.signal("folder-1"),
+            cancel,
+            2,
+        );
+
+        let results = pool
+            .run(vec![
+                download_job(&dir, "a.txt"),
+                download_job(&dir, "b.txt"),
+            ])
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|(_, outcome)| matches!(outcome, TransferOutcome::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_download_is_decrypted() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let cipher = Arc::new(FolderCipher::new("passphrase", vec![1u8; 16], false).unwrap());
+        fs::write(dir.join("plain"), "hello").unwrap();
+        cipher
+            .encrypt_file(&dir.join("plain"), &dir.join("encrypted"))
+            .unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/docs/a.txt")
+            .with_status(200)
+            .with_body(fs::read(dir.join("encrypted")).unwrap())
+            .create_async()
+            .await;
+
+        let control = SyncControl::default();
+        let pool = TransferPool::new(
+            create_client(server.url()),
+            control.signal("folder-1"),
+            CancellationToken::new(),
+            1,
+        )
+        .with_cipher(Some(cipher));
+
+        let results = pool.run(vec![download_job(&dir, "a.txt")]).await;
+
+        mock.assert_async().await;
+        assert!(matches!(results[0].1, TransferOutcome::Completed { .. }));
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "hello");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_download_is_decompressed() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let content = "hello compression\n".repeat(100);
+        fs::write(dir.join("plain.txt"), &content).unwrap();
+        let policy = CompressionPolicy::from_config(&crate::config::FolderCompressionConfig {
+            enabled: true,
+            min_size: 0,
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let compressed_size = policy
+            .compress_file(&dir.join("plain.txt"), &dir.join("compressed"))
+            .unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/docs/a.txt")
+            .with_status(200)
+            .with_body(fs::read(dir.join("compressed")).unwrap())
+            .create_async()
+            .await;
+
+        // 下载时不需要启用压缩，根据压缩标记自动解压
+        let control = SyncControl::default();
+        let pool = TransferPool::new(
+            create_client(server.url()),
+            control.signal("folder-1"),
+            CancellationToken::new(),
+            1,
+        );
+
+        let results = pool.run(vec![download_job(&dir, "a.txt")]).await;
+
+        mock.assert_async().await;
+        assert!(matches!(
+            results[0].1,
+            TransferOutcome::Completed { stored_size: Some(size), .. } if size == compressed_size
+        ));
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), content);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
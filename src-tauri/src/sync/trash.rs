@@ -0,0 +1,381 @@
+/// 本地回收站
+///
+/// 同步引擎因远程删除而删除本地文件时，不直接删除，而是移动到应用数据目录下的
+/// 回收站目录，并在数据库中记录原始路径、删除时间和所属文件夹，
+/// 以便用户恢复误删的文件。超过保留期限的条目会被自动清理。
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::constants::TRASH_DIR;
+use crate::database::{trash as trash_db, Database, TrashItem};
+use crate::{Result, SyncError};
+
+/// 回收站
+///
+/// 作为 Tauri State 管理，所有条目以 UUID 命名存放在 `root` 目录下
+#[derive(Debug, Clone)]
+pub struct Trash {
+    root: PathBuf,
+}
+
+impl Trash {
+    /// 使用指定目录创建回收站（目录不存在时自动创建）
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// 打开应用数据目录下的回收站
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        Self::new(app_dir.join(TRASH_DIR))
+    }
+
+    /// 回收站目录
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 将文件或目录移动到回收站
+    ///
+    /// # 参数
+    /// - db: 共享数据库连接
+    /// - folder_id: 所属同步文件夹 ID
+    /// - path: 要删除的本地绝对路径
+    ///
+    /// # 返回
+    /// - Ok(TrashItem): 新建的回收站条目
+    /// - Err(SyncError::FileNotFound): 路径不存在
+    pub async fn move_to_trash(
+        &self,
+        db: &Database,
+        folder_id: &str,
+        path: &Path,
+    ) -> Result<TrashItem> {
+        let metadata = fs::symlink_metadata(path)
+            .map_err(|_| SyncError::FileNotFound(path.display().to_string()))?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let trash_path = self.root.join(&id);
+        move_path(path, &trash_path)?;
+
+        let item = TrashItem {
+            id,
+            folder_id: folder_id.to_string(),
+            original_path: path.to_string_lossy().into_owned(),
+            trash_path: trash_path.to_string_lossy().into_owned(),
+            size: if metadata.is_dir() {
+                0
+            } else {
+                metadata.len() as i64
+            },
+            is_directory: metadata.is_dir(),
+            deleted_at: chrono::Utc::now().timestamp(),
+        };
+
+        if let Err(e) = trash_db::insert(db, &item).await {
+            // 记录写入失败时放回原处，避免文件"丢失"在回收站中
+            let _ = move_path(&trash_path, path);
+            return Err(e);
+        }
+
+        tracing::info!(folder_id = %folder_id, path = %path.display(), "已移动到回收站");
+        Ok(item)
+    }
+
+    /// 自下而上删除目录：子文件逐个移动到回收站，清空的子目录直接删除
+    ///
+    /// 与整体移动目录不同，每个文件都有独立的回收站条目，可以单独恢复；
+    /// 符号链接不会被跟随，作为普通条目移动到回收站
+    ///
+    /// # 参数
+    /// - db: 共享数据库连接
+    /// - folder_id: 所属同步文件夹 ID
+    /// - dir: 要删除的本地目录
+    ///
+    /// # 返回
+    /// - Ok(Vec<TrashItem>): 新建的回收站条目
+    /// - Err(SyncError::FileNotFound): 目录不存在
+    pub async fn move_tree_to_trash(
+        &self,
+        db: &Database,
+        folder_id: &str,
+        dir: &Path,
+    ) -> Result<Vec<TrashItem>> {
+        let metadata = fs::symlink_metadata(dir)
+            .map_err(|_| SyncError::FileNotFound(dir.display().to_string()))?;
+        if !metadata.is_dir() {
+            return Ok(vec![self.move_to_trash(db, folder_id, dir).await?]);
+        }
+
+        let (files, dirs) = collect_tree(dir)?;
+        let mut items = Vec::with_capacity(files.len());
+        for file in files {
+            items.push(self.move_to_trash(db, folder_id, &file).await?);
+        }
+        // 子目录在父目录之后收集，倒序删除保证先删除最深的目录
+        for sub in dirs.iter().rev() {
+            fs::remove_dir(sub)?;
+        }
+
+        tracing::info!(folder_id = %folder_id, path = %dir.display(), files = items.len(), "已删除目录");
+        Ok(items)
+    }
+
+    /// 恢复回收站条目到原始位置
+    ///
+    /// # 返回
+    /// - Ok(PathBuf): 恢复后的路径
+    /// - Err(SyncError::Conflict): 原始位置已存在同名文件
+    /// - Err(SyncError::NotFound): 条目不存在
+    pub async fn restore(&self, db: &Database, id: &str) -> Result<PathBuf> {
+        let item = trash_db::get_by_id(db, id).await?;
+        let original = PathBuf::from(&item.original_path);
+
+        if original.exists() {
+            return Err(SyncError::Conflict(format!(
+                "Cannot restore: path already exists: {}",
+                original.display()
+            )));
+        }
+
+        if let Some(parent) = original.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_path(Path::new(&item.trash_path), &original)?;
+        trash_db::delete(db, id).await?;
+
+        tracing::info!(path = %original.display(), "已从回收站恢复");
+        Ok(original)
+    }
+
+    /// 永久删除回收站条目
+    pub async fn purge(&self, db: &Database, id: &str) -> Result<()> {
+        let item = trash_db::get_by_id(db, id).await?;
+        remove_path(Path::new(&item.trash_path))?;
+        trash_db::delete(db, id).await
+    }
+
+    /// 清空回收站
+    ///
+    /// # 参数
+    /// - folder_id: 只清空指定文件夹的条目（None 表示全部）
+    ///
+    /// # 返回
+    /// 被删除的条目数量
+    pub async fn empty(&self, db: &Database, folder_id: Option<&str>) -> Result<usize> {
+        let items = trash_db::list(db, folder_id).await?;
+        for item in &items {
+            remove_path(Path::new(&item.trash_path))?;
+            trash_db::delete(db, &item.id).await?;
+        }
+        Ok(items.len())
+    }
+
+    /// 按保留策略清理过期条目
+    ///
+    /// # 参数
+    /// - retention_days: 保留天数，0 表示永久保留
+    ///
+    /// # 返回
+    /// 被清理的条目数量
+    pub async fn purge_expired(&self, db: &Database, retention_days: u32) -> Result<usize> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+
+        let cutoff = chrono::Utc::now().timestamp() - retention_days as i64 * 24 * 60 * 60;
+        let items = trash_db::list_expired(db, cutoff).await?;
+        for item in &items {
+            remove_path(Path::new(&item.trash_path))?;
+            trash_db::delete(db, &item.id).await?;
+        }
+
+        if !items.is_empty() {
+            tracing::info!(count = items.len(), "已清理过期的回收站条目");
+        }
+        Ok(items.len())
+    }
+}
+
+/// 移动文件或目录
+///
+/// 优先使用 rename；跨文件系统时回退到复制后删除
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    copy_recursive(from, to)?;
+    remove_path(from)
+}
+
+/// 收集目录树中的文件与目录（目录按先父后子的顺序，包含根目录本身）
+fn collect_tree(root: &Path) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    let mut index = 0;
+
+    while index < dirs.len() {
+        for entry in fs::read_dir(&dirs[index])? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+        index += 1;
+    }
+
+    Ok((files, dirs))
+}
+
+/// 递归复制文件或目录
+fn copy_recursive(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// 删除文件或目录（不存在时忽略）
+fn remove_path(path: &Path) -> Result<()> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn setup() -> (PathBuf, Database, Trash) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(test_dir.join("sync")).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/006_trash_items.sql"))
+            .expect("Failed to run migration 006");
+
+        let trash = Trash::new(test_dir.join("trash")).unwrap();
+        (test_dir, db, trash)
+    }
+
+    #[tokio::test]
+    async fn test_move_and_restore_file() {
+        let (test_dir, db, trash) = setup();
+        let file = test_dir.join("sync").join("a.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let item = trash.move_to_trash(&db, "folder-1", &file).await.unwrap();
+        assert!(!file.exists());
+        assert!(Path::new(&item.trash_path).exists());
+        assert_eq!(item.size, 5);
+
+        let restored = trash.restore(&db, &item.id).await.unwrap();
+        assert_eq!(restored, file);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello");
+        assert!(trash_db::list(&db, None).await.unwrap().is_empty());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_move_tree_to_trash() {
+        let (test_dir, db, trash) = setup();
+        let dir = test_dir.join("sync").join("photos");
+        fs::create_dir_all(dir.join("2019").join("empty")).unwrap();
+        fs::write(dir.join("a.jpg"), "a").unwrap();
+        fs::write(dir.join("2019").join("b.jpg"), "bb").unwrap();
+
+        let items = trash
+            .move_tree_to_trash(&db, "folder-1", &dir)
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|item| !item.is_directory));
+        assert!(!dir.exists());
+
+        // 每个文件可以单独恢复，父目录自动重建
+        let nested = items
+            .iter()
+            .find(|item| item.original_path.ends_with("b.jpg"))
+            .unwrap();
+        let restored = trash.restore(&db, &nested.id).await.unwrap();
+        assert_eq!(fs::read_to_string(restored).unwrap(), "bb");
+
+        assert!(matches!(
+            trash
+                .move_tree_to_trash(&db, "folder-1", &dir.join("missing"))
+                .await,
+            Err(SyncError::FileNotFound(_))
+        ));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_restore_conflict() {
+        let (test_dir, db, trash) = setup();
+        let file = test_dir.join("sync").join("a.txt");
+        fs::write(&file, "old").unwrap();
+
+        let item = trash.move_to_trash(&db, "folder-1", &file).await.unwrap();
+        fs::write(&file, "new").unwrap();
+
+        let result = trash.restore(&db, &item.id).await;
+        assert!(matches!(result, Err(SyncError::Conflict(_))));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_purge_and_retention() {
+        let (test_dir, db, trash) = setup();
+        let dir = test_dir.join("sync").join("dir");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested").join("b.txt"), "b").unwrap();
+
+        let item = trash.move_to_trash(&db, "folder-1", &dir).await.unwrap();
+        assert!(item.is_directory);
+
+        // 保留期内不会被清理
+        assert_eq!(trash.purge_expired(&db, 30).await.unwrap(), 0);
+        assert_eq!(trash.purge_expired(&db, 0).await.unwrap(), 0);
+
+        trash.purge(&db, &item.id).await.unwrap();
+        assert!(!Path::new(&item.trash_path).exists());
+        assert!(trash_db::list(&db, None).await.unwrap().is_empty());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
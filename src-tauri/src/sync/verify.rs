@@ -0,0 +1,310 @@
+/// 传输完整性校验
+///
+/// 传输完成后通过 HEAD 请求读取远程文件的 Content-Length、`OC-Checksum`
+/// （Nextcloud/ownCloud 支持）和 ETag，与本地文件比对：
+/// - 大小不一致，或任一可识别算法的校验和不一致：`Corrupt`
+/// - 至少有一项比对通过：`Verified`
+/// - 服务器未提供任何可比对的信息：`Unverifiable`
+///
+/// 多数服务器的 ETag 并非内容哈希，因此 ETag 只在与本地哈希一致时作为校验通过的依据，
+/// 不一致时不判定为损坏。
+///
+/// 上传前也用同样的信息判断远程文件是否已与本地文件相同（`is_identical`），
+/// 此时要求至少一项内容哈希一致，仅大小一致不视为相同。
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::sync::versions::digest_file;
+use crate::webdav::client::{RemoteChecksum, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 校验结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// 校验通过
+    Verified,
+    /// 校验失败（附失败原因）
+    Corrupt(String),
+    /// 服务器未提供可比对的信息
+    Unverifiable,
+}
+
+/// 校验已传输的文件
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - local_path: 本地文件路径
+/// - remote_path: 远程文件路径
+///
+/// # 返回
+/// - Ok(Verification): 校验结果
+/// - Err(SyncError): 获取远程校验信息或读取本地文件失败
+pub async fn verify_transfer(
+    client: &WebDavClient,
+    local_path: &Path,
+    remote_path: &str,
+) -> Result<Verification> {
+    let remote = client.checksum(remote_path).await?;
+    let local_path = local_path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || compare(&local_path, &remote))
+        .await
+        .map_err(|e| SyncError::Unknown(format!("Verification task failed: {}", e)))?
+}
+
+/// 比对本地文件与远程校验信息
+pub fn compare(local_path: &Path, remote: &RemoteChecksum) -> Result<Verification> {
+    let mut hasher = LocalHashes::new(local_path);
+    let mut verified = false;
+
+    if let Some(expected) = remote.size {
+        let actual = fs::metadata(local_path)?.len();
+        if actual != expected {
+            return Ok(Verification::Corrupt(format!(
+                "Size mismatch: local {} bytes, remote {} bytes",
+                actual, expected
+            )));
+        }
+        verified = true;
+    }
+
+    for (algorithm, expected) in &remote.checksums {
+        let Some(actual) = hasher.hash(algorithm)? else {
+            continue;
+        };
+        if actual != *expected {
+            return Ok(Verification::Corrupt(format!(
+                "{} checksum mismatch: local {}, remote {}",
+                algorithm, actual, expected
+            )));
+        }
+        verified = true;
+    }
+
+    if !verified {
+        verified = etag_matches(&mut hasher, remote)?;
+    }
+
+    Ok(if verified {
+        Verification::Verified
+    } else {
+        Verification::Unverifiable
+    })
+}
+
+/// 检查远程文件是否已与本地文件内容相同
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - local_path: 本地文件路径
+/// - remote_path: 远程文件路径
+///
+/// # 返回
+/// - Ok(true): 内容相同
+/// - Ok(false): 内容不同、无法证明相同或远程文件不存在
+/// - Err(SyncError): 获取远程校验信息或读取本地文件失败
+pub async fn remote_identical(
+    client: &WebDavClient,
+    local_path: &Path,
+    remote_path: &str,
+) -> Result<bool> {
+    let remote = match client.checksum(remote_path).await {
+        Ok(remote) => remote,
+        Err(SyncError::NotFound(_)) => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let local_path = local_path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || is_identical(&local_path, &remote))
+        .await
+        .map_err(|e| SyncError::Unknown(format!("Verification task failed: {}", e)))?
+}
+
+/// 本地文件与远程校验信息是否表示相同的内容
+///
+/// 大小必须一致，且至少有一项 `OC-Checksum` 或内容哈希形式的 ETag 与本地哈希一致
+pub fn is_identical(local_path: &Path, remote: &RemoteChecksum) -> Result<bool> {
+    if remote.size != Some(fs::metadata(local_path)?.len()) {
+        return Ok(false);
+    }
+
+    let mut hasher = LocalHashes::new(local_path);
+    let mut matched = false;
+    for (algorithm, expected) in &remote.checksums {
+        match hasher.hash(algorithm)? {
+            Some(actual) if actual != *expected => return Ok(false),
+            Some(_) => matched = true,
+            None => {}
+        }
+    }
+
+    if matched {
+        Ok(true)
+    } else {
+        etag_matches(&mut hasher, remote)
+    }
+}
+
+/// ETag 是否为与本地文件一致的内容哈希（按长度推断算法）
+fn etag_matches(hasher: &mut LocalHashes, remote: &RemoteChecksum) -> Result<bool> {
+    let Some(etag) = &remote.etag else {
+        return Ok(false);
+    };
+    let algorithm = match etag.len() {
+        32 => "MD5",
+        40 => "SHA1",
+        64 => "SHA256",
+        _ => "",
+    };
+    let etag = etag.to_lowercase();
+    Ok(hasher.hash(algorithm)?.is_some_and(|actual| actual == etag))
+}
+
+/// 按算法缓存的本地文件哈希
+struct LocalHashes {
+    path: PathBuf,
+    hashes: HashMap<&'static str, String>,
+}
+
+impl LocalHashes {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            hashes: HashMap::new(),
+        }
+    }
+
+    /// 计算指定算法的哈希，不支持的算法返回 None
+    fn hash(&mut self, algorithm: &str) -> Result<Option<String>> {
+        let algorithm = match algorithm {
+            "SHA256" | "SHA-256" => "SHA256",
+            "SHA1" | "SHA-1" => "SHA1",
+            "MD5" => "MD5",
+            _ => return Ok(None),
+        };
+
+        if let Some(hash) = self.hashes.get(algorithm) {
+            return Ok(Some(hash.clone()));
+        }
+
+        let hash = match algorithm {
+            "SHA256" => digest_file::<Sha256>(&self.path)?,
+            "SHA1" => digest_file::<Sha1>(&self.path)?,
+            _ => digest_file::<Md5>(&self.path)?,
+        };
+        self.hashes.insert(algorithm, hash.clone());
+        Ok(Some(hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn write_file(content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("lightsync_verify_{}.txt", Uuid::new_v4()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_compare_checksums() {
+        let path = write_file("hello");
+        let sha1 = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d".to_string();
+
+        let matching = RemoteChecksum {
+            size: Some(5),
+            etag: None,
+            checksums: vec![("SHA1".to_string(), sha1.clone())],
+        };
+        assert_eq!(compare(&path, &matching).unwrap(), Verification::Verified);
+
+        let wrong_size = RemoteChecksum {
+            size: Some(6),
+            ..matching.clone()
+        };
+        assert!(matches!(
+            compare(&path, &wrong_size).unwrap(),
+            Verification::Corrupt(_)
+        ));
+
+        let wrong_hash = RemoteChecksum {
+            size: None,
+            etag: None,
+            checksums: vec![("MD5".to_string(), "00".repeat(16))],
+        };
+        assert!(matches!(
+            compare(&path, &wrong_hash).unwrap(),
+            Verification::Corrupt(_)
+        ));
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_compare_etag_is_advisory() {
+        let path = write_file("hello");
+
+        let content_etag = RemoteChecksum {
+            etag: Some("5D41402ABC4B2A76B9719D911017C592".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            compare(&path, &content_etag).unwrap(),
+            Verification::Verified
+        );
+
+        let opaque_etag = RemoteChecksum {
+            etag: Some("00".repeat(16)),
+            ..Default::default()
+        };
+        assert_eq!(
+            compare(&path, &opaque_etag).unwrap(),
+            Verification::Unverifiable
+        );
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_is_identical_requires_content_hash() {
+        let path = write_file("hello");
+        let sha1 = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d".to_string();
+
+        let matching = RemoteChecksum {
+            size: Some(5),
+            etag: None,
+            checksums: vec![("SHA1".to_string(), sha1)],
+        };
+        assert!(is_identical(&path, &matching).unwrap());
+
+        // 仅大小一致不足以证明内容相同
+        let size_only = RemoteChecksum {
+            size: Some(5),
+            etag: Some("opaque-etag".to_string()),
+            checksums: vec![],
+        };
+        assert!(!is_identical(&path, &size_only).unwrap());
+
+        let content_etag = RemoteChecksum {
+            size: Some(5),
+            etag: Some("5d41402abc4b2a76b9719d911017c592".to_string()),
+            checksums: vec![],
+        };
+        assert!(is_identical(&path, &content_etag).unwrap());
+
+        let wrong_size = RemoteChecksum {
+            size: Some(6),
+            ..content_etag
+        };
+        assert!(!is_identical(&path, &wrong_size).unwrap());
+
+        let _ = fs::remove_file(path);
+    }
+}
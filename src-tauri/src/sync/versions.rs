@@ -0,0 +1,292 @@
+/// 本地文件历史版本
+///
+/// 下载覆盖本地已修改的文件前，先将旧内容保存到应用数据目录的版本库中。
+/// 版本内容按 SHA-256 寻址存放（`versions/<前两位>/<哈希>`），相同内容只保存一份；
+/// 每个文件最多保留 `max_versions` 个版本，超出时删除最旧的版本。
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::constants::VERSIONS_DIR;
+use crate::database::{file_versions, Database, FileVersion};
+use crate::webdav::client::partial_download_path;
+use crate::{Result, SyncError};
+
+/// 计算文件内容的 SHA-256（小写十六进制）
+pub fn hash_file(path: &Path) -> Result<String> {
+    digest_file::<Sha256>(path)
+}
+
+/// 使用指定摘要算法计算文件内容的哈希（小写十六进制）
+pub fn digest_file<D: Digest>(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// 版本库
+///
+/// 作为 Tauri State 管理
+#[derive(Debug, Clone)]
+pub struct VersionStore {
+    root: PathBuf,
+}
+
+impl VersionStore {
+    /// 使用指定目录创建版本库（目录不存在时自动创建）
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// 打开应用数据目录下的版本库
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        Self::new(app_dir.join(VERSIONS_DIR))
+    }
+
+    /// 内容文件路径
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(hash)
+    }
+
+    /// 保存文件的当前内容为一个新版本
+    ///
+    /// # 参数
+    /// - db: 共享数据库连接
+    /// - folder_id: 所属同步文件夹 ID
+    /// - path: 本地文件绝对路径
+    /// - max_versions: 每个文件最多保留的版本数，0 表示不保存版本
+    ///
+    /// # 返回
+    /// - Ok(Some(version)): 新保存的版本
+    /// - Ok(None): 文件不存在、版本功能关闭或内容与最新版本相同
+    pub async fn save_version(
+        &self,
+        db: &Database,
+        folder_id: &str,
+        path: &Path,
+        max_versions: u32,
+    ) -> Result<Option<FileVersion>> {
+        if max_versions == 0 || !path.is_file() {
+            return Ok(None);
+        }
+
+        let file_path = path.to_string_lossy();
+        let hash = hash_file(path)?;
+
+        let existing = file_versions::list(db, folder_id, &file_path).await?;
+        if existing.first().map(|v| v.hash == hash).unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let blob = self.blob_path(&hash);
+        if !blob.exists() {
+            fs::create_dir_all(blob.parent().unwrap_or(&self.root))?;
+            let tmp = blob.with_extension("tmp");
+            fs::copy(path, &tmp)?;
+            fs::rename(&tmp, &blob)?;
+        }
+
+        let size = fs::metadata(path)?.len() as i64;
+        let id = file_versions::insert(db, folder_id, &file_path, &hash, size).await?;
+        self.prune(db, folder_id, &file_path, max_versions).await?;
+
+        tracing::debug!(path = %file_path, hash = %hash, "已保存文件历史版本");
+        file_versions::get_by_id(db, id).await.map(Some)
+    }
+
+    /// 获取文件的历史版本（最新的在前）
+    pub async fn list(
+        &self,
+        db: &Database,
+        folder_id: &str,
+        path: &Path,
+    ) -> Result<Vec<FileVersion>> {
+        file_versions::list(db, folder_id, &path.to_string_lossy()).await
+    }
+
+    /// 将文件恢复到指定版本
+    ///
+    /// 恢复前会先保存文件的当前内容，因此恢复操作本身也可以撤销
+    ///
+    /// # 返回
+    /// - Ok(PathBuf): 被恢复的文件路径
+    /// - Err(SyncError::NotFound): 版本不存在或内容文件丢失
+    pub async fn restore(
+        &self,
+        db: &Database,
+        version_id: i64,
+        max_versions: u32,
+    ) -> Result<PathBuf> {
+        let version = file_versions::get_by_id(db, version_id).await?;
+        let blob = self.blob_path(&version.hash);
+        if !blob.exists() {
+            return Err(SyncError::NotFound(format!(
+                "Version content missing: {}",
+                version.hash
+            )));
+        }
+
+        let target = PathBuf::from(&version.file_path);
+        self.save_version(db, &version.folder_id, &target, max_versions)
+            .await?;
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = partial_download_path(&target);
+        fs::copy(&blob, &tmp)?;
+        fs::File::open(&tmp)?.sync_all()?;
+        fs::rename(&tmp, &target)?;
+
+        tracing::info!(path = %target.display(), version_id, "已恢复文件历史版本");
+        Ok(target)
+    }
+
+    /// 删除超出数量限制的旧版本，并清理不再被引用的内容文件
+    async fn prune(
+        &self,
+        db: &Database,
+        folder_id: &str,
+        file_path: &str,
+        max_versions: u32,
+    ) -> Result<()> {
+        let versions = file_versions::list(db, folder_id, file_path).await?;
+
+        for version in versions.into_iter().skip(max_versions as usize) {
+            file_versions::delete(db, version.id).await?;
+            if file_versions::count_by_hash(db, &version.hash).await? == 0 {
+                let blob = self.blob_path(&version.hash);
+                if let Err(e) = fs::remove_file(&blob) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn setup() -> (PathBuf, Database, VersionStore) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(test_dir.join("sync")).unwrap();
+
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+        db.conn()
+            .unwrap()
+            .execute_batch(include_str!("../../migrations/007_file_versions.sql"))
+            .expect("Failed to run migration 007");
+
+        let store = VersionStore::new(test_dir.join("versions")).unwrap();
+        (test_dir, db, store)
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.txt");
+        fs::write(&file, "hello").unwrap();
+
+        assert_eq!(
+            hash_file(&file).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_save_skips_duplicates_and_prunes() {
+        let (test_dir, db, store) = setup();
+        let file = test_dir.join("sync").join("a.txt");
+
+        for content in ["v1", "v1", "v2", "v3"] {
+            fs::write(&file, content).unwrap();
+            store.save_version(&db, "folder-1", &file, 2).await.unwrap();
+        }
+
+        let versions = store.list(&db, "folder-1", &file).await.unwrap();
+        assert_eq!(versions.len(), 2);
+
+        // v1 已被清理，其内容文件也应被删除
+        let v1_hash = {
+            fs::write(&file, "v1").unwrap();
+            hash_file(&file).unwrap()
+        };
+        assert!(!store.blob_path(&v1_hash).exists());
+        assert!(versions.iter().all(|v| store.blob_path(&v.hash).exists()));
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_restore_version() {
+        let (test_dir, db, store) = setup();
+        let file = test_dir.join("sync").join("a.txt");
+
+        fs::write(&file, "original").unwrap();
+        let saved = store
+            .save_version(&db, "folder-1", &file, 10)
+            .await
+            .unwrap()
+            .unwrap();
+
+        fs::write(&file, "overwritten").unwrap();
+        store.restore(&db, saved.id, 10).await.unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "original");
+        // 被覆盖的内容也作为版本保存
+        assert_eq!(store.list(&db, "folder-1", &file).await.unwrap().len(), 2);
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_versioning() {
+        let (test_dir, db, store) = setup();
+        let file = test_dir.join("sync").join("a.txt");
+        fs::write(&file, "content").unwrap();
+
+        assert!(store
+            .save_version(&db, "folder-1", &file, 0)
+            .await
+            .unwrap()
+            .is_none());
+
+        drop(db);
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,160 @@
+// 系统信息模块
+//
+// 运行环境与操作系统信息，以及供同步策略使用的磁盘、电源与网络状态
+
+pub mod network;
+pub mod power;
+
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::constants::SYSTEM_QUERY_TIMEOUT;
+use crate::sync::space::{self, DiskUsage};
+use network::NetworkState;
+use power::PowerState;
+
+/// 运行环境信息（用于诊断包）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeInfo {
+    /// 操作系统（windows, macos, linux 等）
+    pub os: String,
+    /// 操作系统家族（windows, unix）
+    pub os_family: String,
+    /// 系统架构
+    pub arch: String,
+    /// 应用版本
+    pub app_version: String,
+    /// 运行模式（development, production）
+    pub mode: String,
+    /// CPU 逻辑核心数
+    pub cpu_count: usize,
+}
+
+/// 获取操作系统类型（内部使用）
+fn get_os_type_internal() -> String {
+    std::env::consts::OS.to_string()
+}
+
+/// 获取系统架构
+pub fn get_arch() -> String {
+    std::env::consts::ARCH.to_string()
+}
+
+/// 获取系统信息
+pub fn get_system_info() -> String {
+    format!("{}-{}", get_os_type_internal(), get_arch())
+}
+
+/// 获取运行环境信息
+pub fn get_runtime_info() -> RuntimeInfo {
+    RuntimeInfo {
+        os: get_os_type_internal(),
+        os_family: std::env::consts::FAMILY.to_string(),
+        arch: get_arch(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        mode: if cfg!(debug_assertions) {
+            "development".to_string()
+        } else {
+            "production".to_string()
+        },
+        cpu_count: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+    }
+}
+
+/// 获取当前运行环境信息
+#[tauri::command]
+pub fn get_runtime_environment() -> crate::Result<String> {
+    let os_type = get_os_type_internal();
+    let arch = get_arch();
+    let app_version = env!("CARGO_PKG_VERSION");
+
+    let env_info = format!("OS: {}, Arch: {}, App: {}", os_type, arch, app_version);
+
+    Ok(env_info)
+}
+
+/// 获取当前运行模式（开发环境或生产环境）
+#[tauri::command]
+pub fn get_environment_mode() -> crate::Result<String> {
+    let mode = if cfg!(debug_assertions) {
+        "development".to_string()
+    } else {
+        "production".to_string()
+    };
+
+    Ok(mode)
+}
+
+/// 获取操作系统类型（用于 UI 适配）
+/// 返回值: "Windows", "Darwin", "Linux" 等
+#[tauri::command]
+pub fn get_os_type() -> crate::Result<String> {
+    let os = match std::env::consts::OS {
+        "windows" => "Windows",
+        "macos" => "Darwin",
+        "linux" => "Linux",
+        other => other,
+    };
+
+    Ok(os.to_string())
+}
+
+/// 获取路径所在磁盘的空间使用情况
+///
+/// # 参数
+/// - path: 磁盘上的任意路径（如同步文件夹的本地路径）
+///
+/// # 返回
+/// - 成功：磁盘总容量与可用空间（字节）
+/// - 失败：路径不存在或无法读取
+#[tauri::command]
+pub async fn get_disk_usage(path: String) -> crate::Result<DiskUsage> {
+    let path = PathBuf::from(path);
+    tokio::task::spawn_blocking(move || space::disk_usage(&path))
+        .await
+        .map_err(|e| crate::SyncError::Unknown(format!("Disk usage task failed: {}", e)))?
+}
+
+/// 获取电源状态（外接电源或电池供电、电池电量）
+///
+/// 无法获取时返回 source 为 "unknown" 的结果
+#[tauri::command]
+pub async fn get_power_state() -> crate::Result<PowerState> {
+    Ok(power::power_state().await)
+}
+
+/// 获取网络状态（连接类型与是否按流量计费）
+///
+/// 无法获取时返回 connectionType 为 "unknown" 的结果
+#[tauri::command]
+pub async fn get_network_type() -> crate::Result<NetworkState> {
+    Ok(network::network_state().await)
+}
+
+/// 执行外部命令并返回标准输出（命令不存在、失败或超时时返回 None）
+#[cfg_attr(
+    not(any(windows, target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = tokio::process::Command::new(program);
+    command.args(args).kill_on_drop(true);
+    // 不弹出控制台窗口
+    #[cfg(windows)]
+    command.creation_flags(0x0800_0000);
+
+    let output = tokio::time::timeout(Duration::from_secs(SYSTEM_QUERY_TIMEOUT), command.output())
+        .await
+        .ok()?
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,254 @@
+/// 网络状态
+///
+/// 获取当前默认网络连接的类型（有线、无线、蜂窝）以及是否按流量计费，
+/// 供同步策略在按流量计费的网络上暂停或限制传输：
+/// - Windows: 通过 PowerShell 读取 `NetworkInformation.GetInternetConnectionProfile()` 的连接类型与费用
+/// - macOS: 根据默认路由的网络接口判断类型；无法获取系统的计费标记，只把手机网络共享（iPhone USB、
+///   蓝牙 PAN）视为按流量计费
+/// - Linux: 根据默认路由的网络接口判断类型，按流量计费状态来自 NetworkManager（nmcli）
+///
+/// 无法获取时返回 `connection_type = "unknown"`、`metered = None`，不视为错误
+use serde::{Deserialize, Serialize};
+
+use crate::constants::network_type;
+
+/// 网络状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkState {
+    /// 连接类型（ethernet, wifi, cellular, none, unknown）
+    pub connection_type: String,
+    /// 是否按流量计费（无法判断时为 None）
+    pub metered: Option<bool>,
+}
+
+impl NetworkState {
+    fn new(connection_type: &str, metered: Option<bool>) -> Self {
+        Self {
+            connection_type: connection_type.to_string(),
+            metered,
+        }
+    }
+
+    /// 无法确定网络状态
+    pub fn unknown() -> Self {
+        Self::new(network_type::UNKNOWN, None)
+    }
+}
+
+/// 获取当前的网络状态
+#[cfg(windows)]
+pub async fn network_state() -> NetworkState {
+    const SCRIPT: &str = "$profile = [Windows.Networking.Connectivity.NetworkInformation, \
+        Windows.Networking.Connectivity, ContentType = WindowsRuntime]::GetInternetConnectionProfile(); \
+        if ($profile) { $profile.GetConnectionCost().NetworkCostType; \
+        $profile.IsWwanConnectionProfile; $profile.IsWlanConnectionProfile }";
+
+    match super::command_output(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", SCRIPT],
+    )
+    .await
+    {
+        Some(output) => parse_connection_profile(&output),
+        None => NetworkState::unknown(),
+    }
+}
+
+/// 获取当前的网络状态
+#[cfg(target_os = "macos")]
+pub async fn network_state() -> NetworkState {
+    let Some(route) = super::command_output("route", &["-n", "get", "default"]).await else {
+        // 没有默认路由
+        return NetworkState::new(network_type::NONE, None);
+    };
+    let Some(interface) = route_interface(&route) else {
+        return NetworkState::unknown();
+    };
+
+    match super::command_output("networksetup", &["-listallhardwareports"]).await {
+        Some(ports) => classify_hardware_port(hardware_port(&ports, &interface).as_deref()),
+        None => NetworkState::unknown(),
+    }
+}
+
+/// 获取当前的网络状态
+#[cfg(target_os = "linux")]
+pub async fn network_state() -> NetworkState {
+    let Ok(routes) = tokio::fs::read_to_string("/proc/net/route").await else {
+        return NetworkState::unknown();
+    };
+    let Some(interface) = default_interface(&routes) else {
+        return NetworkState::new(network_type::NONE, None);
+    };
+
+    let sys = std::path::Path::new("/sys/class/net").join(&interface);
+    let connection_type = if sys.join("wireless").exists() || sys.join("phy80211").exists() {
+        network_type::WIFI
+    } else if interface.starts_with("ww") {
+        network_type::CELLULAR
+    } else {
+        network_type::ETHERNET
+    };
+    let metered = super::command_output(
+        "nmcli",
+        &["-t", "-g", "GENERAL.METERED", "device", "show", &interface],
+    )
+    .await
+    .and_then(|output| parse_nm_metered(&output))
+    .or((connection_type == network_type::CELLULAR).then_some(true));
+
+    NetworkState::new(connection_type, metered)
+}
+
+/// 获取当前的网络状态（不支持的平台）
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub async fn network_state() -> NetworkState {
+    NetworkState::unknown()
+}
+
+/// 解析 PowerShell 输出的连接费用类型、是否为蜂窝连接、是否为无线连接（每行一项）
+///
+/// 没有连接到互联网时输出为空
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_connection_profile(output: &str) -> NetworkState {
+    let mut lines = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let Some(cost) = lines.next() else {
+        return NetworkState::new(network_type::NONE, None);
+    };
+    let wwan = lines.next() == Some("True");
+    let wlan = lines.next() == Some("True");
+
+    let metered = match cost {
+        "Unrestricted" => Some(false),
+        "Fixed" | "Variable" => Some(true),
+        _ => None,
+    };
+    let connection_type = if wwan {
+        network_type::CELLULAR
+    } else if wlan {
+        network_type::WIFI
+    } else {
+        network_type::ETHERNET
+    };
+    NetworkState::new(connection_type, metered)
+}
+
+/// 从 `route -n get default` 的输出中读取网络接口
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn route_interface(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("interface:"))
+        .map(|interface| interface.trim().to_string())
+}
+
+/// 从 `networksetup -listallhardwareports` 的输出中查找网络接口对应的硬件端口名称
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn hardware_port(output: &str, interface: &str) -> Option<String> {
+    let mut port = None;
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("Hardware Port:") {
+            port = Some(name.trim());
+        } else if line.strip_prefix("Device:").map(str::trim) == Some(interface) {
+            return port.map(str::to_string);
+        }
+    }
+    None
+}
+
+/// 根据 macOS 硬件端口名称判断连接类型
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn classify_hardware_port(port: Option<&str>) -> NetworkState {
+    match port {
+        Some(port) if port.contains("iPhone") || port.contains("Bluetooth PAN") => {
+            NetworkState::new(network_type::CELLULAR, Some(true))
+        }
+        Some(port) if port.contains("Wi-Fi") || port.contains("AirPort") => {
+            NetworkState::new(network_type::WIFI, None)
+        }
+        // VPN 等虚拟接口没有对应的硬件端口
+        Some(_) => NetworkState::new(network_type::ETHERNET, None),
+        None => NetworkState::unknown(),
+    }
+}
+
+/// 从 `/proc/net/route` 中读取默认路由的网络接口
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn default_interface(routes: &str) -> Option<String> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut columns = line.split_whitespace();
+        let interface = columns.next()?;
+        (columns.next()? == "00000000").then(|| interface.to_string())
+    })
+}
+
+/// 解析 `nmcli -g GENERAL.METERED` 的输出（yes、no、yes (guessed)、no (guessed)、unknown）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nm_metered(output: &str) -> Option<bool> {
+    match output.split_whitespace().next()? {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_connection_profile() {
+        assert_eq!(
+            parse_connection_profile("Unrestricted\r\nFalse\r\nTrue\r\n"),
+            NetworkState::new("wifi", Some(false))
+        );
+        assert_eq!(
+            parse_connection_profile("Variable\r\nTrue\r\nFalse\r\n"),
+            NetworkState::new("cellular", Some(true))
+        );
+        assert_eq!(
+            parse_connection_profile("Unknown\r\nFalse\r\nFalse\r\n"),
+            NetworkState::new("ethernet", None)
+        );
+        assert_eq!(
+            parse_connection_profile(""),
+            NetworkState::new("none", None)
+        );
+    }
+
+    #[test]
+    fn test_macos_interface_type() {
+        let route = "   route to: default\ndestination: default\n    gateway: 172.20.10.1\n  interface: en7\n";
+        let ports = "Hardware Port: Wi-Fi\nDevice: en0\nEthernet Address: aa:bb\n\n\
+                     Hardware Port: iPhone USB\nDevice: en7\nEthernet Address: cc:dd\n";
+
+        let interface = route_interface(route).unwrap();
+        assert_eq!(interface, "en7");
+        assert_eq!(
+            classify_hardware_port(hardware_port(ports, &interface).as_deref()),
+            NetworkState::new("cellular", Some(true))
+        );
+        assert_eq!(
+            classify_hardware_port(hardware_port(ports, "en0").as_deref()),
+            NetworkState::new("wifi", None)
+        );
+        assert_eq!(hardware_port(ports, "utun3"), None);
+    }
+
+    #[test]
+    fn test_linux_default_interface() {
+        let routes = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                      docker0\t000011AC\t00000000\t0001\t0\t0\t0\t0000FFFF\n\
+                      wlp2s0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\n";
+        assert_eq!(default_interface(routes), Some("wlp2s0".to_string()));
+        assert_eq!(default_interface("Iface\tDestination\n"), None);
+
+        assert_eq!(parse_nm_metered("yes (guessed)\n"), Some(true));
+        assert_eq!(parse_nm_metered("no\n"), Some(false));
+        assert_eq!(parse_nm_metered("unknown\n"), None);
+    }
+}
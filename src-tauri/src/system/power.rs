@@ -0,0 +1,252 @@
+/// 电源状态
+///
+/// 获取当前是否使用电池供电、电池电量与充电状态，供同步策略在电池供电时暂停或降低频率：
+/// - Windows: `GetSystemPowerStatus`
+/// - macOS: 解析 `pmset -g batt` 的输出
+/// - Linux: 读取 `/sys/class/power_supply`
+///
+/// 无法获取时返回 `source = "unknown"`，不视为错误
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::power_source;
+
+/// 电源状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerState {
+    /// 电源类型（ac, battery, unknown）
+    pub source: String,
+    /// 电池电量百分比（没有电池或无法获取时为 None）
+    pub battery_percent: Option<u8>,
+    /// 是否正在充电（没有电池或无法获取时为 None）
+    pub charging: Option<bool>,
+}
+
+impl PowerState {
+    /// 无法确定电源状态
+    pub fn unknown() -> Self {
+        Self {
+            source: power_source::UNKNOWN.to_string(),
+            battery_percent: None,
+            charging: None,
+        }
+    }
+}
+
+/// 获取当前的电源状态
+#[cfg(windows)]
+pub async fn power_state() -> PowerState {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = std::mem::MaybeUninit::<SYSTEM_POWER_STATUS>::uninit();
+    // SAFETY: status 指向足够大小的可写内存，调用成功时已完整写入
+    if unsafe { GetSystemPowerStatus(status.as_mut_ptr()) } == 0 {
+        return PowerState::unknown();
+    }
+    // SAFETY: GetSystemPowerStatus 返回非 0 时已完整写入 status
+    let status = unsafe { status.assume_init() };
+
+    // BatteryFlag: 128 表示没有电池，255 表示未知；8 表示正在充电
+    let has_battery = status.BatteryFlag != 128 && status.BatteryFlag != 255;
+    PowerState {
+        source: match status.ACLineStatus {
+            0 => power_source::BATTERY,
+            1 => power_source::AC,
+            _ => power_source::UNKNOWN,
+        }
+        .to_string(),
+        battery_percent: (has_battery && status.BatteryLifePercent <= 100)
+            .then_some(status.BatteryLifePercent),
+        charging: has_battery.then_some(status.BatteryFlag & 8 != 0),
+    }
+}
+
+/// 获取当前的电源状态
+#[cfg(target_os = "macos")]
+pub async fn power_state() -> PowerState {
+    match super::command_output("pmset", &["-g", "batt"]).await {
+        Some(output) => parse_pmset(&output),
+        None => PowerState::unknown(),
+    }
+}
+
+/// 获取当前的电源状态
+#[cfg(target_os = "linux")]
+pub async fn power_state() -> PowerState {
+    tokio::task::spawn_blocking(|| read_power_supply(Path::new("/sys/class/power_supply")))
+        .await
+        .unwrap_or_else(|_| PowerState::unknown())
+}
+
+/// 获取当前的电源状态（不支持的平台）
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub async fn power_state() -> PowerState {
+    PowerState::unknown()
+}
+
+/// 解析 `pmset -g batt` 的输出
+///
+/// 输出示例：
+/// ```text
+/// Now drawing from 'Battery Power'
+///  -InternalBattery-0 (id=4653155)    85%; discharging; 4:20 remaining present: true
+/// ```
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> PowerState {
+    let source = if output.contains("'AC Power'") {
+        power_source::AC
+    } else if output.contains("'Battery Power'") {
+        power_source::BATTERY
+    } else {
+        power_source::UNKNOWN
+    };
+
+    let battery = output
+        .lines()
+        .find(|line| line.contains("InternalBattery"))
+        .and_then(|line| line.split('\t').nth(1));
+    let mut fields = battery
+        .into_iter()
+        .flat_map(|line| line.split(';'))
+        .map(str::trim);
+    let battery_percent = fields
+        .next()
+        .and_then(|percent| percent.strip_suffix('%'))
+        .and_then(|percent| percent.parse().ok());
+    let charging = fields
+        .next()
+        .map(|status| matches!(status, "charging" | "finishing charge"));
+
+    PowerState {
+        source: source.to_string(),
+        battery_percent,
+        charging,
+    }
+}
+
+/// 读取 Linux `/sys/class/power_supply` 下的电源信息
+///
+/// 外接电源（Mains、USB）在线时视为外接电源；只有正在放电的系统电池时视为电池供电；
+/// 没有系统电池（台式机）时视为外接电源。外设电池（scope 为 Device，如无线鼠标）不参与判断
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn read_power_supply(dir: &Path) -> PowerState {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return PowerState::unknown();
+    };
+    let read = |path: &Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|value| value.trim().to_string())
+            .ok()
+    };
+
+    let mut ac_online = false;
+    let mut battery: Option<(Option<u8>, String)> = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match read(&path, "type").as_deref() {
+            Some("Mains") | Some("USB") => {
+                ac_online |= read(&path, "online").as_deref() == Some("1");
+            }
+            Some("Battery") if read(&path, "scope").as_deref() != Some("Device") => {
+                let capacity = read(&path, "capacity").and_then(|c| c.parse().ok());
+                let status = read(&path, "status").unwrap_or_default();
+                battery.get_or_insert((capacity, status));
+            }
+            _ => {}
+        }
+    }
+
+    match battery {
+        Some((battery_percent, status)) => PowerState {
+            source: if ac_online || status != "Discharging" {
+                power_source::AC
+            } else {
+                power_source::BATTERY
+            }
+            .to_string(),
+            battery_percent,
+            charging: Some(status == "Charging"),
+        },
+        None => PowerState {
+            source: power_source::AC.to_string(),
+            battery_percent: None,
+            charging: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset() {
+        let state = parse_pmset(
+            "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t85%; discharging; 4:20 remaining present: true\n",
+        );
+        assert_eq!(state.source, "battery");
+        assert_eq!(state.battery_percent, Some(85));
+        assert_eq!(state.charging, Some(false));
+
+        let state = parse_pmset(
+            "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t97%; charging; 0:12 remaining present: true\n",
+        );
+        assert_eq!(state.source, "ac");
+        assert_eq!(state.charging, Some(true));
+
+        // 没有电池的 Mac mini
+        let state = parse_pmset("Now drawing from 'AC Power'\n");
+        assert_eq!(state.source, "ac");
+        assert_eq!(state.battery_percent, None);
+        assert_eq!(state.charging, None);
+    }
+
+    #[test]
+    fn test_read_power_supply() {
+        let dir = std::env::temp_dir().join(format!("lightsync_power_{}", uuid::Uuid::new_v4()));
+        let supply = |name: &str, values: &[(&str, &str)]| {
+            let path = dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            for (file, value) in values {
+                std::fs::write(path.join(file), format!("{}\n", value)).unwrap();
+            }
+        };
+
+        // 没有电池的台式机
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        assert_eq!(read_power_supply(&dir).source, "ac");
+
+        // 外设电池不影响判断
+        supply(
+            "hid-mouse-battery",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("capacity", "20"),
+                ("status", "Discharging"),
+            ],
+        );
+        assert_eq!(read_power_supply(&dir).battery_percent, None);
+
+        supply(
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "64"),
+                ("status", "Discharging"),
+            ],
+        );
+        let state = read_power_supply(&dir);
+        assert_eq!(state.source, "battery");
+        assert_eq!(state.battery_percent, Some(64));
+        assert_eq!(state.charging, Some(false));
+
+        supply("AC", &[("online", "1")]);
+        assert_eq!(read_power_supply(&dir).source, "ac");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(read_power_supply(&dir), PowerState::unknown());
+    }
+}
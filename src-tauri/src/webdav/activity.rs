@@ -0,0 +1,275 @@
+/// 服务器活动流（Nextcloud Activity OCS API）
+///
+/// 读取 Nextcloud `activity` 应用记录的服务器端活动（谁在何时修改了哪个文件），
+/// 供界面在同步事件旁显示"由 X 在服务器上修改"。
+///
+/// 其他服务器类型、或服务器未启用 activity 应用时不报错，返回 `supported = false` 的空结果
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::webdav::client::{CloudLocation, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 活动接口路径（相对于 `/ocs/v2.php/`）
+const ACTIVITY_ENDPOINT: &str = "apps/activity/api/v2/activity/all";
+
+/// 单次请求返回的最大活动数
+const ACTIVITY_LIMIT: u32 = 50;
+
+/// 服务器类型是否支持 Activity 接口
+pub fn supports_activity(server_type: &str) -> bool {
+    server_type == "nextcloud"
+}
+
+/// 服务器活动
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerActivity {
+    /// 活动 ID（递增）
+    pub id: i64,
+    /// 产生活动的应用（如 files、files_sharing）
+    pub app: String,
+    /// 活动类型（如 file_created、file_changed、file_deleted）
+    pub activity_type: String,
+    /// 执行操作的用户
+    pub user: Option<String>,
+    /// 服务器生成的活动描述
+    pub subject: String,
+    /// 涉及的远程路径（相对于服务器根路径；不在服务器根路径下时为用户文件中的路径）
+    pub path: Option<String>,
+    /// 活动时间（Unix 时间戳，秒）
+    pub timestamp: Option<i64>,
+}
+
+impl ServerActivity {
+    /// 从 OCS 返回的活动数据解析
+    fn from_ocs(data: &Value, location: &CloudLocation) -> Option<Self> {
+        let path = (data["object_type"].as_str() == Some("files"))
+            .then(|| data["object_name"].as_str())
+            .flatten()
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                location
+                    .remote_path(name)
+                    .unwrap_or_else(|| name.to_string())
+            });
+
+        Some(Self {
+            id: data["activity_id"].as_i64()?,
+            app: data["app"].as_str().unwrap_or_default().to_string(),
+            activity_type: data["type"].as_str().unwrap_or_default().to_string(),
+            user: data["user"]
+                .as_str()
+                .filter(|user| !user.is_empty())
+                .map(str::to_string),
+            subject: data["subject"].as_str().unwrap_or_default().to_string(),
+            path,
+            // ISO 8601 格式，如 "2024-01-31T12:00:00+00:00"
+            timestamp: data["datetime"]
+                .as_str()
+                .and_then(|datetime| chrono::DateTime::parse_from_rfc3339(datetime).ok())
+                .map(|datetime| datetime.timestamp()),
+        })
+    }
+}
+
+/// 服务器活动查询结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerActivityFeed {
+    /// 服务器是否提供活动流（非 Nextcloud 或未启用 activity 应用时为 false）
+    pub supported: bool,
+    /// 活动列表（从新到旧）
+    pub activities: Vec<ServerActivity>,
+    /// 已读取的最新活动 ID，下次查询时作为 since 传入
+    pub last_activity_id: Option<i64>,
+}
+
+impl ServerActivityFeed {
+    /// 不支持活动流的服务器
+    fn unsupported() -> Self {
+        Self::default()
+    }
+}
+
+/// 获取服务器活动
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - server_type: 服务器类型
+/// - since: 只返回该活动 ID 之后的活动（None 表示最近的活动）
+///
+/// # 返回
+/// - Ok(ServerActivityFeed): 活动列表；服务器不支持时 `supported` 为 false
+/// - Err(SyncError): 请求失败（认证失败、网络错误等）
+pub async fn fetch(
+    client: &WebDavClient,
+    server_type: &str,
+    since: Option<i64>,
+) -> Result<ServerActivityFeed> {
+    if !supports_activity(server_type) {
+        return Ok(ServerActivityFeed::unsupported());
+    }
+    let Some(location) = CloudLocation::parse(client.url()) else {
+        return Ok(ServerActivityFeed::unsupported());
+    };
+
+    // 指定 since 时按时间正序读取其后的活动，否则倒序读取最近的活动
+    let mut params = vec![
+        ("limit", ACTIVITY_LIMIT.to_string()),
+        (
+            "sort",
+            if since.is_some() { "asc" } else { "desc" }.to_string(),
+        ),
+    ];
+    if let Some(since) = since {
+        params.push(("since", since.to_string()));
+    }
+
+    let data = match client
+        .ocs(reqwest::Method::GET, ACTIVITY_ENDPOINT, &params)
+        .await
+    {
+        Ok(data) => data,
+        // 未安装或未启用 activity 应用
+        Err(SyncError::NotFound(_)) => return Ok(ServerActivityFeed::unsupported()),
+        Err(e) => return Err(e),
+    };
+
+    // 304 Not Modified 时 data 为 Null，表示没有新的活动
+    let mut activities: Vec<ServerActivity> = data
+        .as_array()
+        .map(|activities| {
+            activities
+                .iter()
+                .filter_map(|activity| ServerActivity::from_ocs(activity, &location))
+                .collect()
+        })
+        .unwrap_or_default();
+    activities.sort_by_key(|activity| std::cmp::Reverse(activity.id));
+
+    Ok(ServerActivityFeed {
+        supported: true,
+        last_activity_id: activities.first().map(|activity| activity.id).or(since),
+        activities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::WebDavServerConfig;
+    use crate::webdav::mock_server_config;
+    use serde_json::json;
+
+    fn nextcloud_config(url: &str) -> WebDavServerConfig {
+        WebDavServerConfig {
+            server_type: "nextcloud".to_string(),
+            ..mock_server_config(url)
+        }
+    }
+
+    fn activity_json(id: i64, object_name: &str) -> Value {
+        json!({
+            "activity_id": id,
+            "app": "files",
+            "type": "file_changed",
+            "user": "bob",
+            "subject": "bob changed a.txt",
+            "object_type": "files",
+            "object_id": 12,
+            "object_name": object_name,
+            "datetime": "2024-01-31T12:00:00+00:00"
+        })
+    }
+
+    #[test]
+    fn test_activity_from_ocs() {
+        let location =
+            CloudLocation::parse("https://host/remote.php/dav/files/alice/Docs").unwrap();
+
+        let activity =
+            ServerActivity::from_ocs(&activity_json(5, "/Docs/a.txt"), &location).unwrap();
+        assert_eq!(activity.id, 5);
+        assert_eq!(activity.activity_type, "file_changed");
+        assert_eq!(activity.user.as_deref(), Some("bob"));
+        assert_eq!(activity.path.as_deref(), Some("/a.txt"));
+        assert_eq!(activity.timestamp, Some(1706702400));
+
+        // 非文件活动没有路径
+        let mut calendar = activity_json(6, "Personal");
+        calendar["object_type"] = json!("calendar");
+        let activity = ServerActivity::from_ocs(&calendar, &location).unwrap();
+        assert_eq!(activity.path, None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_since() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/ocs/v2.php/apps/activity/api/v2/activity/all")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("since".to_string(), "4".to_string()),
+                mockito::Matcher::UrlEncoded("sort".to_string(), "asc".to_string()),
+            ]))
+            .match_header("ocs-apirequest", "true")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ocs": {
+                        "meta": { "status": "ok", "statuscode": 200, "message": "OK" },
+                        "data": [activity_json(5, "/Docs/a.txt"), activity_json(6, "/Docs/b.txt")]
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let config = nextcloud_config(&format!("{}/remote.php/dav/files/alice/Docs", server.url()));
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let feed = fetch(&client, "nextcloud", Some(4)).await.unwrap();
+        assert!(feed.supported);
+        assert_eq!(
+            feed.activities.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![6, 5]
+        );
+        assert_eq!(feed.last_activity_id, Some(6));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_degrades_gracefully() {
+        let mut server = mockito::Server::new_async().await;
+        let config = nextcloud_config(&format!("{}/remote.php/webdav", server.url()));
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        // 通用 WebDAV 服务器不发起请求
+        let feed = fetch(&client, "generic", None).await.unwrap();
+        assert_eq!(feed, ServerActivityFeed::unsupported());
+
+        // 没有新活动
+        let not_modified = server
+            .mock("GET", "/ocs/v2.php/apps/activity/api/v2/activity/all")
+            .match_query(mockito::Matcher::Any)
+            .with_status(304)
+            .create_async()
+            .await;
+        let feed = fetch(&client, "nextcloud", Some(9)).await.unwrap();
+        assert!(feed.supported);
+        assert!(feed.activities.is_empty());
+        assert_eq!(feed.last_activity_id, Some(9));
+        not_modified.remove_async().await;
+
+        // 未启用 activity 应用
+        let _missing = server
+            .mock("GET", "/ocs/v2.php/apps/activity/api/v2/activity/all")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+        let feed = fetch(&client, "nextcloud", None).await.unwrap();
+        assert!(!feed.supported);
+    }
+}
@@ -13,13 +13,23 @@
 ///
 /// 配置信息存储在数据库中，密码存储在系统 Keyring 中，
 /// `WebDavClient` 本身不持久化。
+use crate::constants::{
+    ip_version, propfind_dialect, MAX_REDIRECTS, PARTIAL_DOWNLOAD_SUFFIX, TRANSFER_CHUNK_SIZE,
+};
 use crate::database::WebDavServerConfig;
+use crate::webdav::cookies::CookieJar;
+use crate::webdav::path::{decode_path, encode_path};
+use crate::webdav::trace;
 use crate::{Result, SyncError};
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use std::path::Path;
-use std::time::Duration;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 /// WebDAV 文件信息
 ///
@@ -41,12 +51,235 @@ pub struct FileInfo {
 
     /// 最后修改时间（Unix 时间戳，秒）
     pub modified: Option<i64>,
+
+    /// 实体标签（ETag，服务器未返回时为 None）
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+/// 多状态（207）响应中处理失败的资源
+///
+/// 例如删除目录时其中一个子项被锁定（423），或列出目录时某个子项的属性无法读取（403）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteItemError {
+    /// 资源路径（href，已解码）
+    pub path: String,
+
+    /// 资源的 HTTP 状态码
+    pub status: u16,
+}
+
+/// 目录列表
+///
+/// 属性全部读取失败的子项不会出现在 `files` 中，而是记录在 `failures` 中，
+/// 调用方不应把它们当作已删除
+#[derive(Debug, Clone, Default)]
+pub struct RemoteListing {
+    /// 成功读取的文件和文件夹
+    pub files: Vec<FileInfo>,
+
+    /// 处理失败的子项
+    pub failures: Vec<RemoteItemError>,
+}
+
+/// sync-collection REPORT 结果（RFC 6578）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCollectionResult {
+    /// 新的同步令牌，下次请求时携带
+    pub sync_token: String,
+
+    /// 新增或修改的资源
+    pub changed: Vec<FileInfo>,
+
+    /// 已删除资源的 href
+    pub removed: Vec<String>,
+}
+
+/// WebDAV 锁（RFC 4918 LOCK）
+///
+/// 排他写锁，持有期间其他客户端对该资源的写操作会返回 423 Locked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteLock {
+    /// 被锁定的远程路径（相对于服务器根路径）
+    pub path: String,
+
+    /// 锁令牌（如 `opaquelocktoken:...`，不含尖括号）
+    pub token: String,
+
+    /// 锁的所有者描述
+    pub owner: String,
+
+    /// 锁的过期时间（Unix 时间戳，秒）
+    pub expires_at: i64,
+}
+
+/// 自定义（dead）属性名
+///
+/// 由客户端写入、服务器原样保存的属性（RFC 4918 第 4.2 节），使用自己的命名空间避免与其他客户端冲突
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyName {
+    /// 命名空间 URI
+    pub namespace: String,
+
+    /// 属性名（XML 名称）
+    pub name: String,
+}
+
+impl PropertyName {
+    /// 创建属性名
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            name: name.into(),
+        }
+    }
+
+    /// 属性名是否为合法的 XML 名称（不含命名空间前缀）
+    fn is_valid(&self) -> bool {
+        let mut chars = self.name.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !self.namespace.is_empty()
+    }
+}
+
+/// PROPPATCH 中的单项属性修改
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyUpdate {
+    /// 设置属性值（已存在时覆盖）
+    Set(PropertyName, String),
+
+    /// 删除属性（属性不存在时不视为错误）
+    Remove(PropertyName),
+}
+
+impl PropertyUpdate {
+    /// 修改的属性
+    pub fn property(&self) -> &PropertyName {
+        match self {
+            PropertyUpdate::Set(property, _) | PropertyUpdate::Remove(property) => property,
+        }
+    }
+}
+
+/// PROPFIND 请求方式
+///
+/// 部分精简的 WebDAV 服务器（如某些路由器）无法处理列出具体属性的请求体，返回 400 或 422。
+/// 遇到这种情况时依次改用 `AllProp` 和 `Empty` 重试，成功的方式由同一服务器的后续请求直接使用
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PropfindDialect {
+    /// 请求体中列出需要的属性
+    #[default]
+    Standard,
+    /// 请求体为 `<D:allprop/>`
+    AllProp,
+    /// 不带请求体
+    Empty,
+}
+
+impl PropfindDialect {
+    /// 从保存的值解析，未知的值按 `Standard` 处理
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            propfind_dialect::ALLPROP => Self::AllProp,
+            propfind_dialect::EMPTY => Self::Empty,
+            _ => Self::Standard,
+        }
+    }
+
+    /// 保存的值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => propfind_dialect::STANDARD,
+            Self::AllProp => propfind_dialect::ALLPROP,
+            Self::Empty => propfind_dialect::EMPTY,
+        }
+    }
+
+    /// 当前方式被拒绝时尝试的下一种方式
+    fn fallback(&self) -> Option<Self> {
+        match self {
+            Self::Standard => Some(Self::AllProp),
+            Self::AllProp => Some(Self::Empty),
+            Self::Empty => None,
+        }
+    }
+}
+
+/// allprop 方式的 PROPFIND 请求体
+const ALLPROP_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propfind xmlns:D="DAV:"><D:allprop/></D:propfind>"#;
+
+/// 服务器存储配额（RFC 4331）
+///
+/// 服务器未声明或声明为负数（表示不限制）的字段为空
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteQuota {
+    /// 可用空间（字节）
+    pub available_bytes: Option<u64>,
+
+    /// 已用空间（字节）
+    pub used_bytes: Option<u64>,
+}
+
+/// 远程文件的校验信息
+///
+/// 来自 HEAD 响应头，用于传输完成后的完整性校验
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteChecksum {
+    /// 文件大小（Content-Length）
+    pub size: Option<u64>,
+
+    /// 强 ETag（已去除引号，弱 ETag 不参与校验）
+    pub etag: Option<String>,
+
+    /// `OC-Checksum` 声明的校验和：(算法名大写, 小写十六进制值)
+    pub checksums: Vec<(String, String)>,
+}
+
+impl RemoteChecksum {
+    /// 从响应头解析校验信息
+    ///
+    /// `OC-Checksum` 格式为 `SHA1:abc MD5:def`，多个校验和以空格或逗号分隔
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let size = header("content-length").and_then(|v| v.trim().parse().ok());
+        let etag = header("etag")
+            .filter(|v| !v.starts_with("W/"))
+            .map(|v| v.trim_matches('"').to_string())
+            .filter(|v| !v.is_empty());
+        let checksums = header("oc-checksum")
+            .map(|value| {
+                value
+                    .split([' ', ','])
+                    .filter_map(|entry| entry.split_once(':'))
+                    .map(|(algorithm, hash)| {
+                        (algorithm.trim().to_uppercase(), hash.trim().to_lowercase())
+                    })
+                    .filter(|(_, hash)| !hash.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            size,
+            etag,
+            checksums,
+        }
+    }
 }
 
 /// WebDAV 客户端
 ///
 /// 封装与 WebDAV 服务器的所有通信逻辑
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WebDavClient {
     /// WebDAV 服务器 URL (从 WebDavServerConfig.url 获取)
     url: String,
@@ -57,11 +290,38 @@ pub struct WebDavClient {
     /// 密码 (从 Keyring 读取，不持久化在配置中)
     password: String,
 
-    /// 连接超时时间 (从 WebDavServerConfig.timeout 获取)
+    /// 单个非流式请求的总超时时间 (从 WebDavServerConfig.timeout 获取)
     timeout: Duration,
 
+    /// 建立连接的超时时间 (从 WebDavServerConfig.connect_timeout 获取)
+    connect_timeout: Duration,
+
+    /// 流式传输连续没有收发数据的最长时间 (从 WebDavServerConfig.read_timeout 获取)
+    read_timeout: Duration,
+
+    /// 固定的证书指纹 (从 WebDavServerConfig.pinned_cert_fingerprint 获取)
+    pinned_cert_fingerprint: Option<String>,
+
     /// HTTP 客户端 (支持连接复用)
     client: reqwest::Client,
+
+    /// 取消令牌，触发后所有进行中的请求立即中止
+    cancel: CancellationToken,
+
+    /// 上传时是否通过 `X-OC-MTime` 保留本地修改时间（Nextcloud / ownCloud）
+    send_mtime: bool,
+
+    /// 服务器的 Cookie 罐（SSO 网关会话），未设置时不发送也不保存 Cookie
+    cookies: Option<Arc<CookieJar>>,
+
+    /// 安全请求最多跟随的重定向次数（0 表示不跟随）
+    max_redirects: usize,
+
+    /// 服务器永久重定向（301/308）后推断出的新服务器 URL，克隆的客户端共享
+    redirected_url: Arc<Mutex<Option<String>>>,
+
+    /// 服务器可用的 PROPFIND 请求方式，克隆的客户端共享
+    propfind_dialect: Arc<Mutex<PropfindDialect>>,
 }
 
 impl WebDavClient {
@@ -95,6 +355,12 @@ impl WebDavClient {
     ///     last_test_at: None,
     ///     last_test_status: "unknown".to_string(),
     ///     last_test_error: None,
+    ///     auth_type: "basic".to_string(),
+    ///     accept_invalid_certs: false,
+    ///     pinned_cert_fingerprint: None,
+    ///     custom_headers: Default::default(),
+    ///     ip_version: "any".to_string(),
+    ///     resolve_ip: None,
     ///     server_type: "generic".to_string(),
     ///     enabled: true,
     ///     created_at: 0,
@@ -113,11 +379,11 @@ impl WebDavClient {
         // 验证配置
         config
             .validate()
-            .map_err(|e| SyncError::ConfigError(format!("Invalid server config: {}", e)))?;
+            .map_err(|e| SyncError::ValidationError(format!("Invalid server config: {}", e)))?;
 
         // 验证密码不为空
         if password.trim().is_empty() {
-            return Err(SyncError::ConfigError(
+            return Err(SyncError::ValidationError(
                 "Password cannot be empty".to_string(),
             ));
         }
@@ -138,22 +404,116 @@ impl WebDavClient {
             })?,
         );
 
+        // 服务器要求的自定义请求头（值可能是 API 密钥，标记为敏感避免出现在调试输出中）
+        for (name, value) in config.custom_headers.iter() {
+            let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|e| {
+                SyncError::ConfigError(format!("Invalid custom header name '{}': {}", name, e))
+            })?;
+            let mut value = HeaderValue::from_str(value).map_err(|e| {
+                SyncError::ConfigError(format!("Invalid value for custom header '{}': {}", name, e))
+            })?;
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+
         // 创建 HTTP 客户端
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(config.timeout as u64))
+        // 固定指纹时每次 TLS 握手都校验服务器证书的指纹（见 tls::pinned_tls_config）；
+        // 重定向由 execute 处理，只对可以安全重放的请求跟随同一服务器的重定向
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(config.connect_timeout as u64))
             .default_headers(headers)
+            .redirect(reqwest::redirect::Policy::none())
+            .danger_accept_invalid_certs(config.accept_invalid_certs);
+        if let Some(pinned) = &config.pinned_cert_fingerprint {
+            builder = builder.use_preconfigured_tls(crate::webdav::tls::pinned_tls_config(pinned)?);
+        }
+        let client = apply_network_options(builder, config)?
             .build()
             .map_err(|e| SyncError::Network(format!("Failed to create HTTP client: {}", e)))?;
 
         Ok(Self {
-            url: config.url.clone(),
+            url: config.resolved_url(),
             username: config.username.clone(),
             password,
             timeout: Duration::from_secs(config.timeout as u64),
+            connect_timeout: Duration::from_secs(config.connect_timeout as u64),
+            read_timeout: Duration::from_secs(config.read_timeout as u64),
+            pinned_cert_fingerprint: config.pinned_cert_fingerprint.clone(),
             client,
+            cancel: CancellationToken::new(),
+            send_mtime: matches!(config.server_type.as_str(), "nextcloud" | "owncloud"),
+            cookies: None,
+            max_redirects: MAX_REDIRECTS,
+            redirected_url: Arc::new(Mutex::new(None)),
+            propfind_dialect: Arc::new(Mutex::new(PropfindDialect::Standard)),
         })
     }
 
+    /// 校验服务器证书是否与固定指纹一致
+    ///
+    /// 未设置固定指纹时直接返回成功。客户端的每次握手都会校验指纹，
+    /// 创建客户端时调用此方法提前检查，指纹不一致时返回明确的错误而不是连接失败。
+    ///
+    /// # 返回
+    /// - `Ok(())`: 未固定指纹或指纹一致
+    /// - `Err(SyncError::AuthError)`: 指纹不一致
+    pub async fn verify_certificate(&self) -> Result<()> {
+        match &self.pinned_cert_fingerprint {
+            Some(pinned) => {
+                crate::webdav::tls::verify_fingerprint(&self.url, pinned, self.timeout).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 是否固定了服务器证书指纹
+    pub fn has_pinned_certificate(&self) -> bool {
+        self.pinned_cert_fingerprint.is_some()
+    }
+
+    /// 创建绑定取消令牌的客户端副本（共享连接池）
+    ///
+    /// 令牌被取消后，该副本上进行中的和后续的请求都会返回 `SyncError::Cancelled`
+    pub fn with_cancellation(&self, cancel: CancellationToken) -> Self {
+        Self {
+            cancel,
+            ..self.clone()
+        }
+    }
+
+    /// 使用 Cookie 罐：请求附带匹配的 Cookie，响应中的 `Set-Cookie` 写回 Cookie 罐
+    pub fn with_cookies(mut self, jar: Arc<CookieJar>) -> Self {
+        self.cookies = Some(jar);
+        self
+    }
+
+    /// 设置安全请求最多跟随的重定向次数（0 表示不跟随，重定向作为错误返回）
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// 服务器永久重定向后的新服务器 URL
+    ///
+    /// 请求服务器 URL 下的路径时收到 301/308 重定向，且新地址可以推断出新的服务器 URL 时返回，
+    /// 可以提示用户保存到服务器配置，避免每次请求都被重定向（写请求无法跟随重定向）
+    pub fn redirected_url(&self) -> Option<String> {
+        self.redirected_url.lock().unwrap().clone()
+    }
+
+    /// 使用已知可用的 PROPFIND 请求方式（保存在服务器配置中）
+    pub fn with_propfind_dialect(self, dialect: PropfindDialect) -> Self {
+        *self.propfind_dialect.lock().unwrap() = dialect;
+        self
+    }
+
+    /// 当前使用的 PROPFIND 请求方式
+    ///
+    /// 服务器拒绝请求体后改用的兼容方式会记录在这里，可以保存到服务器配置
+    pub fn propfind_dialect(&self) -> PropfindDialect {
+        *self.propfind_dialect.lock().unwrap()
+    }
+
     /// 获取服务器 URL
     pub fn url(&self) -> &str {
         &self.url
@@ -164,11 +524,29 @@ impl WebDavClient {
         &self.username
     }
 
-    /// 获取超时时间
+    /// 获取请求超时时间（单个非流式请求的总时长）
     pub fn timeout(&self) -> Duration {
         self.timeout
     }
 
+    /// 获取读取超时时间（流式传输连续没有收发数据的最长时间）
+    pub fn read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    /// 发送轻量的 OPTIONS 请求保持连接
+    ///
+    /// 只用于让连接池中的空闲连接保持活跃（避免路由器 NAT 超时断开），不检查响应状态
+    ///
+    /// # 返回
+    /// - `Ok(())`: 收到服务器响应
+    /// - `Err(SyncError)`: 请求失败（网络错误、超时等）
+    pub async fn ping(&self) -> Result<()> {
+        self.send(self.client.request(reqwest::Method::OPTIONS, &self.url))
+            .await
+            .map(|_| ())
+    }
+
     /// 测试与服务器的连接
     ///
     /// 发送 PROPFIND 请求到服务器根路径，验证：
@@ -201,6 +579,9 @@ impl WebDavClient {
     /// #     last_test_at: None,
     /// #     last_test_status: "unknown".to_string(),
     /// #     last_test_error: None,
+    /// #     auth_type: "basic".to_string(),
+    /// #     accept_invalid_certs: false,
+    /// #     pinned_cert_fingerprint: None,
     /// #     server_type: "generic".to_string(),
     /// #     enabled: true,
     /// #     created_at: 0,
@@ -223,27 +604,25 @@ impl WebDavClient {
                 </D:prop>
             </D:propfind>"#;
 
-        // 发送 PROPFIND 请求到根路径
+        // 发送 PROPFIND 请求到根路径（服务器拒绝请求体时改用兼容方式）
         let response = self
-            .client
-            .request(reqwest::Method::from_bytes(b"PROPFIND").unwrap(), &self.url)
-            .header("Depth", "0")
-            .header("Content-Type", "application/xml; charset=utf-8")
-            .body(propfind_body)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    SyncError::Network(format!(
-                        "Connection timeout after {} seconds",
-                        self.timeout.as_secs()
-                    ))
-                } else if e.is_connect() {
-                    SyncError::Network(format!("Failed to connect to server: {}", e))
-                } else {
-                    SyncError::Network(format!("Network error: {}", e))
-                }
-            })?;
+            .propfind_with(&self.url, "0", propfind_body, |request| async move {
+                self.execute(request.timeout(self.timeout))
+                    .await
+                    .map_err(|e| {
+                        if e.is_timeout() {
+                            SyncError::Network(format!(
+                                "Connection timeout after {} seconds",
+                                self.timeout.as_secs()
+                            ))
+                        } else if e.is_connect() {
+                            SyncError::Network(format!("Failed to connect to server: {}", e))
+                        } else {
+                            SyncError::Network(format!("Network error: {}", e))
+                        }
+                    })
+            })
+            .await?;
 
         // 检查响应状态码
         let status = response.status();
@@ -285,63 +664,9 @@ impl WebDavClient {
 
     /// 检测服务器类型
     ///
-    /// 通过分析 HTTP 响应头来识别服务器类型
-    ///
-    /// # 参数
-    /// - `response`: HTTP 响应对象
-    ///
-    /// # 返回
-    /// 服务器类型字符串：
-    /// - "nextcloud": Nextcloud 服务器
-    /// - "owncloud": ownCloud 服务器
-    /// - "apache": Apache WebDAV
-    /// - "nginx": Nginx WebDAV
-    /// - "generic": 通用 WebDAV 服务器
+    /// 通过分析 HTTP 响应头来识别服务器类型，见 [`server_type_from_headers`]
     fn detect_server_type(&self, response: &reqwest::Response) -> String {
-        let headers = response.headers();
-
-        // 检查 Server 头
-        if let Some(server_header) = headers.get("server") {
-            if let Ok(server_str) = server_header.to_str() {
-                let server_lower = server_str.to_lowercase();
-
-                if server_lower.contains("nextcloud") {
-                    return "nextcloud".to_string();
-                }
-                if server_lower.contains("owncloud") {
-                    return "owncloud".to_string();
-                }
-                if server_lower.contains("apache") {
-                    return "apache".to_string();
-                }
-                if server_lower.contains("nginx") {
-                    return "nginx".to_string();
-                }
-            }
-        }
-
-        // 检查 X-Powered-By 头（某些服务器会提供）
-        if let Some(powered_by) = headers.get("x-powered-by") {
-            if let Ok(powered_str) = powered_by.to_str() {
-                let powered_lower = powered_str.to_lowercase();
-
-                if powered_lower.contains("nextcloud") {
-                    return "nextcloud".to_string();
-                }
-                if powered_lower.contains("owncloud") {
-                    return "owncloud".to_string();
-                }
-            }
-        }
-
-        // 检查 X-OC-Version 头（ownCloud/Nextcloud 特有）
-        if headers.contains_key("x-oc-version") {
-            // 如果有 X-OC-Version 但没有明确标识，默认为 ownCloud
-            return "owncloud".to_string();
-        }
-
-        // 默认返回通用类型
-        "generic".to_string()
+        server_type_from_headers(response.headers())
     }
 
     /// 列出指定路径下的文件和文件夹
@@ -371,6 +696,9 @@ impl WebDavClient {
     /// #     last_test_at: None,
     /// #     last_test_status: "unknown".to_string(),
     /// #     last_test_error: None,
+    /// #     auth_type: "basic".to_string(),
+    /// #     accept_invalid_certs: false,
+    /// #     pinned_cert_fingerprint: None,
     /// #     server_type: "generic".to_string(),
     /// #     enabled: true,
     /// #     created_at: 0,
@@ -386,6 +714,25 @@ impl WebDavClient {
     /// # }
     /// ```
     pub async fn list(&self, path: &str) -> Result<Vec<FileInfo>> {
+        let listing = self.list_detailed(path).await?;
+        for failure in &listing.failures {
+            tracing::warn!(path = %failure.path, status = failure.status, "远程子项读取失败，已跳过");
+        }
+        Ok(listing.files)
+    }
+
+    /// 列出远程目录内容，同时返回处理失败的子项
+    ///
+    /// 多状态响应中子项的状态全部不是 2xx 时（如 423 Locked、403 Forbidden），
+    /// 该子项记录在 `failures` 中而不是按空属性加入文件列表
+    ///
+    /// # 参数
+    /// - `path`: 远程路径（相对于服务器根路径）
+    ///
+    /// # 返回
+    /// - `Ok(RemoteListing)`: 文件列表与失败的子项
+    /// - `Err(SyncError)`: 请求失败
+    pub async fn list_detailed(&self, path: &str) -> Result<RemoteListing> {
         // 构建完整 URL
         let url = self.build_url(path);
 
@@ -396,20 +743,13 @@ impl WebDavClient {
                     <D:resourcetype/>
                     <D:getcontentlength/>
                     <D:getlastmodified/>
+                    <D:getetag/>
                     <D:displayname/>
                 </D:prop>
             </D:propfind>"#;
 
-        // 发送 PROPFIND 请求
-        let response = self
-            .client
-            .request(reqwest::Method::from_bytes(b"PROPFIND").unwrap(), &url)
-            .header("Depth", "1") // 只列出当前目录，不递归
-            .header("Content-Type", "application/xml; charset=utf-8")
-            .body(propfind_body)
-            .send()
-            .await
-            .map_err(|e| self.map_request_error(e))?;
+        // 发送 PROPFIND 请求（Depth 1：只列出当前目录，不递归）
+        let response = self.propfind(&url, "1", propfind_body).await?;
 
         // 检查响应状态
         self.check_response_status(&response)?;
@@ -453,6 +793,9 @@ impl WebDavClient {
     /// #     last_test_at: None,
     /// #     last_test_status: "unknown".to_string(),
     /// #     last_test_error: None,
+    /// #     auth_type: "basic".to_string(),
+    /// #     accept_invalid_certs: false,
+    /// #     pinned_cert_fingerprint: None,
     /// #     server_type: "generic".to_string(),
     /// #     enabled: true,
     /// #     created_at: 0,
@@ -469,18 +812,15 @@ impl WebDavClient {
         let content = tokio::fs::read(local_path)
             .await
             .map_err(|e| SyncError::Io(e))?;
+        let metadata = tokio::fs::metadata(local_path).await?;
 
         // 构建完整 URL
         let url = self.build_url(remote_path);
 
         // 发送 PUT 请求
-        let response = self
-            .client
-            .put(&url)
-            .body(content)
-            .send()
-            .await
-            .map_err(|e| self.map_request_error(e))?;
+        let request = self.client.put(&url).body(content);
+        let request = self.with_mtime(request, &metadata);
+        let response = self.send(request).await?;
 
         // 检查响应状态
         self.check_response_status(&response)?;
@@ -490,7 +830,7 @@ impl WebDavClient {
 
     /// 从远程路径下载文件到本地
     ///
-    /// 使用 GET 方法下载文件内容
+    /// 使用 GET 方法下载文件内容，先写入同目录下的临时文件，完成后重命名为目标文件
     ///
     /// # 参数
     /// - `remote_path`: 远程文件路径（相对于服务器根路径）
@@ -517,6 +857,9 @@ impl WebDavClient {
     /// #     last_test_at: None,
     /// #     last_test_status: "unknown".to_string(),
     /// #     last_test_error: None,
+    /// #     auth_type: "basic".to_string(),
+    /// #     accept_invalid_certs: false,
+    /// #     pinned_cert_fingerprint: None,
     /// #     server_type: "generic".to_string(),
     /// #     enabled: true,
     /// #     created_at: 0,
@@ -529,1219 +872,5015 @@ impl WebDavClient {
     /// # }
     /// ```
     pub async fn download(&self, remote_path: &str, local_path: &Path) -> Result<()> {
-        // 构建完整 URL
-        let url = self.build_url(remote_path);
-
-        // 发送 GET 请求
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| self.map_request_error(e))?;
-
-        // 检查响应状态
-        self.check_response_status(&response)?;
-
-        // 读取响应内容
-        let content = response
-            .bytes()
-            .await
-            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
-
-        // 写入本地文件
-        tokio::fs::write(local_path, content)
-            .await
-            .map_err(|e| SyncError::Io(e))?;
+        // 先写入同目录下的临时文件，完成后原子重命名，避免中断时留下不完整的目标文件
+        self.download_stream(remote_path, local_path, &|| false, &|_, _| {})
+            .await?;
 
         Ok(())
     }
 
-    /// 删除远程路径的文件或文件夹
+    /// 分块上传本地文件，支持在数据块之间中断
     ///
-    /// 使用 DELETE 方法删除资源
+    /// 文件以 `TRANSFER_CHUNK_SIZE` 为单位流式发送，每发送一个数据块前调用
+    /// `should_stop`，返回 true 时在当前数据块完成后中止请求
     ///
     /// # 参数
-    /// - `path`: 远程路径（相对于服务器根路径）
+    /// - `local_path`: 本地文件路径
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `lock_token`: 持有的锁令牌（远程文件已被本客户端锁定时需要提供）
+    /// - `should_stop`: 中断检查
     ///
     /// # 返回
-    /// - `Ok(())`: 删除成功
-    /// - `Err(SyncError)`: 删除失败
+    /// - `Ok(u64)`: 上传的字节数
+    /// - `Err(SyncError::Interrupted)`: 传输被中断
+    /// - `Err(SyncError)`: 上传失败
+    pub async fn upload_interruptible<F>(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        lock_token: Option<&str>,
+        should_stop: F,
+    ) -> Result<u64>
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.upload_stream(local_path, remote_path, lock_token, should_stop, |_, _| {})
+            .await
+    }
+
+    /// 分块上传本地文件，父目录不存在时自动创建后重试
     ///
-    /// # 示例
+    /// 与 `upload_interruptible` 相同，但服务器返回 409 Conflict 时，从文件的直接父目录开始
+    /// 向上查找已存在的祖先目录，再自上而下用 MKCOL 依次创建缺失的目录，然后重新上传一次。
+    /// 父目录都已存在时，409 按普通错误返回
     ///
-    /// ```rust,no_run
-    /// # use lightsync_lib::webdav::client::WebDavClient;
-    /// # use lightsync_lib::database::WebDavServerConfig;
-    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let config = WebDavServerConfig {
-    /// #     id: "test".to_string(),
-    /// #     name: "Test".to_string(),
-    /// #     url: "https://example.com/webdav".to_string(),
-    /// #     username: "user".to_string(),
-    /// #     use_https: true,
-    /// #     timeout: 30,
-    /// #     last_test_at: None,
-    /// #     last_test_status: "unknown".to_string(),
-    /// #     last_test_error: None,
-    /// #     server_type: "generic".to_string(),
-    /// #     enabled: true,
-    /// #     created_at: 0,
-    /// #     updated_at: 0,
-    /// # };
-    /// # let password = "password".to_string();
-    /// let client = WebDavClient::new(&config, password)?;
-    /// client.delete("/old_file.txt").await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn delete(&self, path: &str) -> Result<()> {
-        // 构建完整 URL
-        let url = self.build_url(path);
+    /// # 参数
+    /// - `local_path`: 本地文件路径
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `lock_token`: 持有的锁令牌（远程文件已被本客户端锁定时需要提供）
+    /// - `should_stop`: 中断检查
+    /// - `on_progress`: 每读取一个数据块后调用，参数为（已发送字节数, 总字节数），
+    ///   重新上传时从 0 开始
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 上传的字节数
+    /// - `Err(SyncError::Interrupted)`: 传输被中断
+    /// - `Err(SyncError)`: 上传或创建父目录失败
+    pub async fn upload_with_parents<F, P>(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        lock_token: Option<&str>,
+        should_stop: F,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+        P: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        let should_stop = std::sync::Arc::new(should_stop);
+        let on_progress = std::sync::Arc::new(on_progress);
+        let stop = should_stop.clone();
+        let progress = on_progress.clone();
+        let (response, size) = self
+            .put_stream(
+                local_path,
+                remote_path,
+                lock_token,
+                move || stop(),
+                move |sent, total| progress(sent, total),
+            )
+            .await?;
+        if response.status() != reqwest::StatusCode::CONFLICT {
+            self.check_response_status(&response)?;
+            return Ok(size);
+        }
 
-        // 发送 DELETE 请求
-        let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .map_err(|e| self.map_request_error(e))?;
+        let created = self.create_missing_parents(remote_path).await?;
+        if created == 0 {
+            self.check_response_status(&response)?;
+        }
+        tracing::debug!(path = %remote_path, created, "已创建缺失的远程父目录，重新上传");
+
+        self.upload_stream(
+            local_path,
+            remote_path,
+            lock_token,
+            move || should_stop(),
+            move |sent, total| on_progress(sent, total),
+        )
+        .await
+    }
 
-        // 检查响应状态
-        self.check_response_status(&response)?;
+    /// 创建远程路径缺失的祖先目录
+    ///
+    /// 从直接父目录开始向上查找第一个已存在的目录，再自上而下依次创建其下缺失的目录
+    ///
+    /// # 返回
+    /// - `Ok(usize)`: 创建的目录数量（祖先目录都已存在时为 0）
+    /// - `Err(SyncError)`: 查询或创建目录失败
+    async fn create_missing_parents(&self, remote_path: &str) -> Result<usize> {
+        let mut missing = Vec::new();
+        let mut current = remote_path.trim_end_matches('/');
+        while let Some((parent, _)) = current.rsplit_once('/') {
+            if parent.is_empty() || self.exists(parent).await? {
+                break;
+            }
+            missing.push(parent.to_string());
+            current = parent;
+        }
 
-        Ok(())
+        for dir in missing.iter().rev() {
+            self.mkdir(dir).await?;
+        }
+        Ok(missing.len())
     }
 
-    /// 在远程路径创建文件夹
-    ///
-    /// 使用 MKCOL 方法创建目录
+    /// 分块上传本地文件并报告进度
     ///
     /// # 参数
-    /// - `path`: 远程路径（相对于服务器根路径）
+    /// - `local_path`: 本地文件路径
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `on_progress`: 每读取一个数据块后调用，参数为（已发送字节数, 总字节数）
     ///
     /// # 返回
-    /// - `Ok(())`: 创建成功
-    /// - `Err(SyncError)`: 创建失败
-    ///
-    /// # 示例
-    ///
-    /// ```rust,no_run
-    /// # use lightsync_lib::webdav::client::WebDavClient;
-    /// # use lightsync_lib::database::WebDavServerConfig;
-    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let config = WebDavServerConfig {
-    /// #     id: "test".to_string(),
-    /// #     name: "Test".to_string(),
-    /// #     url: "https://example.com/webdav".to_string(),
-    /// #     username: "user".to_string(),
-    /// #     use_https: true,
-    /// #     timeout: 30,
-    /// #     last_test_at: None,
-    /// #     last_test_status: "unknown".to_string(),
-    /// #     last_test_error: None,
-    /// #     server_type: "generic".to_string(),
-    /// #     enabled: true,
-    /// #     created_at: 0,
-    /// #     updated_at: 0,
-    /// # };
-    /// # let password = "password".to_string();
-    /// let client = WebDavClient::new(&config, password)?;
-    /// client.mkdir("/new_folder").await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn mkdir(&self, path: &str) -> Result<()> {
-        // 构建完整 URL
-        let url = self.build_url(path);
-
-        // 发送 MKCOL 请求
-        let response = self
-            .client
-            .request(reqwest::Method::from_bytes(b"MKCOL").unwrap(), &url)
-            .send()
+    /// - `Ok(u64)`: 上传的字节数
+    /// - `Err(SyncError)`: 上传失败
+    pub async fn upload_with_progress<P>(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        P: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        self.upload_stream(local_path, remote_path, None, || false, on_progress)
             .await
-            .map_err(|e| self.map_request_error(e))?;
+    }
+
+    /// 以数据流方式 PUT 本地文件
+    async fn upload_stream<F, P>(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        lock_token: Option<&str>,
+        should_stop: F,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+        P: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        let (response, size) = self
+            .put_stream(
+                local_path,
+                remote_path,
+                lock_token,
+                should_stop,
+                on_progress,
+            )
+            .await?;
 
-        // 检查响应状态
         self.check_response_status(&response)?;
+        Ok(size)
+    }
 
-        Ok(())
+    /// 发送流式 PUT 请求，返回未检查状态的响应与文件大小
+    async fn put_stream<F, P>(
+        &self,
+        local_path: &Path,
+        remote_path: &str,
+        lock_token: Option<&str>,
+        should_stop: F,
+        on_progress: P,
+    ) -> Result<(reqwest::Response, u64)>
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+        P: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        use futures_util::StreamExt;
+
+        let file = tokio::fs::File::open(local_path).await?;
+        let metadata = file.metadata().await?;
+        let size = metadata.len();
+
+        let should_stop = std::sync::Arc::new(should_stop);
+        let stream_stop = should_stop.clone();
+        let activity = IdleWatch::new();
+        let stream_activity = activity.clone();
+        let mut sent = 0u64;
+        let stream = tokio_util::io::ReaderStream::with_capacity(file, TRANSFER_CHUNK_SIZE).map(
+            move |chunk| {
+                if stream_stop() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Interrupted,
+                        "transfer interrupted",
+                    ));
+                }
+                if let Ok(bytes) = &chunk {
+                    sent += bytes.len() as u64;
+                    stream_activity.touch();
+                    on_progress(sent, Some(size));
+                }
+                chunk
+            },
+        );
+
+        let url = self.build_url(remote_path);
+        let mut request = self
+            .client
+            .put(&url)
+            .header(reqwest::header::CONTENT_LENGTH, size);
+        if let Some(token) = lock_token {
+            request = request.header("If", format!("(<{}>)", token));
+        }
+        let request = self
+            .with_mtime(request, &metadata)
+            .body(reqwest::Body::wrap_stream(stream));
+
+        // 请求体发送完后，等待服务器处理与响应的时间同样受读取超时限制
+        let response = match self
+            .idle_guard(&activity, self.send_streaming(request))
+            .await
+        {
+            Ok(response) => response,
+            Err(e @ SyncError::Cancelled(_)) => return Err(e),
+            Err(_) if should_stop() => {
+                return Err(SyncError::Interrupted(format!(
+                    "Upload interrupted: {}",
+                    remote_path
+                )))
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok((response, size))
     }
 
-    // ========== 辅助方法 ==========
+    /// 为 PUT 请求添加 `X-OC-MTime` 头，让服务器保留本地文件的修改时间
+    fn with_mtime(
+        &self,
+        request: reqwest::RequestBuilder,
+        metadata: &std::fs::Metadata,
+    ) -> reqwest::RequestBuilder {
+        match metadata.modified().ok().and_then(unix_timestamp) {
+            Some(mtime) if self.send_mtime => request.header("X-OC-MTime", mtime),
+            _ => request,
+        }
+    }
 
-    /// 构建完整的 WebDAV URL
+    /// 分块下载远程文件，支持在数据块之间中断
+    ///
+    /// 内容先写入同目录下的临时文件，完成后重命名为目标文件；
+    /// 每写入一个数据块后调用 `should_stop`，返回 true 时停止下载。
+    /// 中断、取消或失败时删除临时文件，目标文件保持不变
     ///
     /// # 参数
-    /// - `path`: 相对路径
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `local_path`: 本地文件路径
+    /// - `should_stop`: 中断检查
+    /// - `on_progress`: 每写入一个数据块后调用，参数为（已写入字节数, 总字节数）
     ///
     /// # 返回
-    /// 完整的 URL 字符串
-    fn build_url(&self, path: &str) -> String {
-        let path = path.trim_start_matches('/');
-        format!("{}/{}", self.url.trim_end_matches('/'), path)
+    /// - `Ok(u64)`: 下载的字节数
+    /// - `Err(SyncError::Interrupted)`: 传输被中断
+    /// - `Err(SyncError::Cancelled)`: 传输被取消
+    /// - `Err(SyncError)`: 下载失败
+    pub async fn download_interruptible<F, P>(
+        &self,
+        remote_path: &str,
+        local_path: &Path,
+        should_stop: F,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        F: Fn() -> bool + Sync,
+        P: Fn(u64, Option<u64>) + Sync,
+    {
+        self.download_stream(remote_path, local_path, &should_stop, &on_progress)
+            .await
     }
 
-    /// 映射 reqwest 错误到 SyncError
+    /// 分块下载远程文件并报告进度
     ///
-    /// 将 HTTP 客户端错误转换为应用层的 SyncError，提供详细的错误信息
+    /// 与 `download_interruptible` 一样先写入临时文件，完成后重命名为目标文件
     ///
     /// # 参数
-    /// - `error`: reqwest 错误
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `local_path`: 本地文件路径
+    /// - `on_progress`: 每写入一个数据块后调用，参数为（已写入字节数, 总字节数，服务器未返回长度时为 None）
     ///
     /// # 返回
-    /// 对应的 SyncError，包含详细的错误类型和描述
+    /// - `Ok(u64)`: 下载的字节数
+    /// - `Err(SyncError)`: 下载失败
+    pub async fn download_with_progress<P>(
+        &self,
+        remote_path: &str,
+        local_path: &Path,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        P: Fn(u64, Option<u64>) + Sync,
+    {
+        self.download_stream(remote_path, local_path, &|| false, &on_progress)
+            .await
+    }
+
+    /// 可断点续传的分块下载
     ///
-    /// # 错误类型映射
-    /// - 超时错误 -> `Network` (包含超时时间)
-    /// - 连接错误 -> `Network` (包含连接失败原因)
-    /// - DNS 解析错误 -> `Network` (包含域名信息)
-    /// - TLS/SSL 错误 -> `Network` (包含证书错误信息)
-    /// - 其他网络错误 -> `Network` (包含具体错误描述)
-    fn map_request_error(&self, error: reqwest::Error) -> SyncError {
-        // 超时错误
-        if error.is_timeout() {
-            return SyncError::Network(format!(
-                "Connection timeout after {} seconds. Please check your network connection or increase the timeout setting.",
-                self.timeout.as_secs()
-            ));
+    /// 与 `download_interruptible` 一样先写入临时文件，但传输中断、取消或失败时保留临时文件。
+    /// 临时文件已有内容时发送 `Range: bytes=<已有长度>-` 与 `If-Range: <validator>`：
+    /// 服务器返回 206 时在临时文件末尾追加剩余内容；返回 200（远程文件已变化或不支持范围请求）
+    /// 时从头下载；返回 416（临时文件与远程文件长度不符）时删除临时文件后从头下载
+    ///
+    /// # 参数
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `local_path`: 本地文件路径
+    /// - `validator`: 远程文件的强 ETag（含引号），确认临时文件中的内容仍属于当前的远程文件
+    /// - `should_stop`: 中断检查
+    /// - `on_progress`: 每写入一个数据块后调用，参数为（包括续传前内容的已写入字节数, 文件总字节数）
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 文件的字节数（包括续传前已下载的部分）
+    /// - `Err(SyncError::Interrupted)`: 传输被中断
+    /// - `Err(SyncError::Cancelled)`: 传输被取消
+    /// - `Err(SyncError)`: 下载失败
+    pub async fn download_resumable<F, P>(
+        &self,
+        remote_path: &str,
+        local_path: &Path,
+        validator: &str,
+        should_stop: F,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        F: Fn() -> bool + Sync,
+        P: Fn(u64, Option<u64>) + Sync,
+    {
+        let part_path = partial_download_path(local_path);
+        let mut offset = tokio::fs::metadata(&part_path)
+            .await
+            .map_or(0, |metadata| metadata.len());
+        let mut response = self.resume_request(remote_path, offset, validator).await?;
+        if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            tracing::debug!(path = %remote_path, "临时文件与远程文件长度不符，重新下载");
+            let _ = tokio::fs::remove_file(&part_path).await;
+            offset = 0;
+            response = self.resume_request(remote_path, 0, validator).await?;
         }
 
-        // 连接错误
-        if error.is_connect() {
-            // 尝试提取更详细的错误信息
-            let error_msg = error.to_string();
-
-            // DNS 解析失败
-            if error_msg.contains("dns") || error_msg.contains("resolve") {
-                return SyncError::Network(format!(
-                    "Failed to resolve server address '{}'. Please check the server URL and your DNS settings.",
-                    self.url
-                ));
+        self.check_response_status(&response)?;
+        if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            if content_range_start(response.headers()) != Some(offset) {
+                return Err(SyncError::WebDav(format!(
+                    "Unexpected Content-Range when resuming {}",
+                    remote_path
+                )));
             }
+            tracing::debug!(path = %remote_path, offset, "续传下载");
+        } else {
+            offset = 0;
+        }
+        let remote_modified = response
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_http_date);
+
+        let written = self
+            .write_body(
+                &mut response,
+                &part_path,
+                offset,
+                remote_path,
+                &should_stop,
+                &on_progress,
+            )
+            .await?;
+        // 只有写完后才交给 finish_download，中断或失败时保留临时文件供下次续传
+        finish_download(&part_path, local_path, remote_modified, Ok(written)).await
+    }
 
-            // 连接被拒绝
-            if error_msg.contains("refused") {
-                return SyncError::Network(format!(
-                    "Connection refused by server '{}'. Please verify the server is running and accessible.",
-                    self.url
-                ));
-            }
+    /// 发送续传下载请求（offset 为 0 时不带范围）
+    async fn resume_request(
+        &self,
+        remote_path: &str,
+        offset: u64,
+        validator: &str,
+    ) -> Result<reqwest::Response> {
+        let mut request = self.client.get(self.build_url(remote_path));
+        if offset > 0 {
+            request = request
+                .header(reqwest::header::RANGE, format!("bytes={}-", offset))
+                .header(reqwest::header::IF_RANGE, validator);
+        }
+        self.within_read_timeout(self.send_streaming(request)).await
+    }
 
-            // TLS/SSL 错误
-            if error_msg.contains("ssl")
-                || error_msg.contains("tls")
-                || error_msg.contains("certificate")
-            {
-                return SyncError::Network(format!(
-                    "SSL/TLS connection error: {}. This may be caused by an invalid certificate or unsupported protocol.",
-                    error
-                ));
+    /// GET 远程文件并写入临时文件，落盘后重命名为目标文件
+    ///
+    /// 临时文件与目标文件位于同一目录，重命名是原子操作：
+    /// 进程在下载过程中退出时目标文件保持原样，只会遗留临时文件（启动时清理）。
+    /// 服务器返回 `Last-Modified` 时将本地文件的修改时间设为远程修改时间
+    async fn download_stream(
+        &self,
+        remote_path: &str,
+        local_path: &Path,
+        should_stop: &(dyn Fn() -> bool + Sync),
+        on_progress: &(dyn Fn(u64, Option<u64>) + Sync),
+    ) -> Result<u64> {
+        let url = self.build_url(remote_path);
+        let request = self.client.get(&url);
+        let mut response = self
+            .within_read_timeout(self.send_streaming(request))
+            .await?;
+
+        self.check_response_status(&response)?;
+        let remote_modified = response
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_http_date);
+
+        let part_path = partial_download_path(local_path);
+        let result = self
+            .write_body(
+                &mut response,
+                &part_path,
+                0,
+                remote_path,
+                should_stop,
+                on_progress,
+            )
+            .await;
+        finish_download(&part_path, local_path, remote_modified, result).await
+    }
+
+    /// 使用多个连接按范围（HTTP Range）并行下载远程文件
+    ///
+    /// 先用 HEAD 请求确认服务器声明了 `Accept-Ranges: bytes` 并获取文件长度和强 ETag，
+    /// 再将文件均分为最多 `connections` 段并发下载，各段直接写入临时文件中的对应位置，
+    /// 全部完成后重命名为目标文件。分段写入的临时文件中未完成的部分为空洞，不能续传，
+    /// 因此使用单独的临时文件（见 `ranged_download_path`），中断后总是删除。
+    ///
+    /// 每段请求都带 `If-Range`，远程文件在 HEAD 之后发生变化时服务器返回完整内容（200），
+    /// 避免把不同版本的分段拼成一个文件。服务器不支持范围请求（未声明 `Accept-Ranges`）、
+    /// 没有返回强 ETag、对范围请求返回 200 或分段的 ETag 与 HEAD 不一致时回退为单连接下载
+    ///
+    /// # 参数
+    /// - `remote_path`: 远程文件路径（相对于服务器根路径）
+    /// - `local_path`: 本地文件路径
+    /// - `connections`: 最大并发连接数
+    /// - `should_stop`: 中断检查
+    /// - `on_progress`: 每写入一个数据块后调用，参数为（所有分段合计已写入字节数, 总字节数）
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 下载的字节数
+    /// - `Err(SyncError::Interrupted)`: 传输被中断
+    /// - `Err(SyncError::Cancelled)`: 传输被取消
+    /// - `Err(SyncError)`: 下载失败
+    pub async fn download_ranged<F, P>(
+        &self,
+        remote_path: &str,
+        local_path: &Path,
+        connections: usize,
+        should_stop: F,
+        on_progress: P,
+    ) -> Result<u64>
+    where
+        F: Fn() -> bool + Sync,
+        P: Fn(u64, Option<u64>) + Sync,
+    {
+        let (ranges, support) = match self.range_support(remote_path).await? {
+            Some(support) => (split_ranges(support.length, connections), Some(support)),
+            None => (Vec::new(), None),
+        };
+        let Some(support) = support.filter(|_| ranges.len() >= 2) else {
+            return self
+                .download_stream(remote_path, local_path, &should_stop, &on_progress)
+                .await;
+        };
+
+        let part_path = ranged_download_path(local_path);
+        match self
+            .write_ranges(
+                remote_path,
+                &part_path,
+                &ranges,
+                &support.etag,
+                &should_stop,
+                &on_progress,
+            )
+            .await
+        {
+            Ok(Some(written)) => {
+                finish_download(&part_path, local_path, support.modified, Ok(written)).await
             }
+            Ok(None) => {
+                let _ = tokio::fs::remove_file(&part_path).await;
+                tracing::debug!(path = %remote_path, "服务器忽略了范围请求或远程文件已变化，改为单连接下载");
+                self.download_stream(remote_path, local_path, &should_stop, &on_progress)
+                    .await
+            }
+            Err(e) => finish_download(&part_path, local_path, None, Err(e)).await,
+        }
+    }
 
-            // 通用连接错误
-            return SyncError::Network(format!(
-                "Failed to connect to server '{}': {}. Please check the server URL and your network connection.",
-                self.url, error
-            ));
+    /// 查询远程文件是否支持范围请求
+    ///
+    /// # 返回
+    /// - `Ok(Some(RangeSupport))`: 服务器声明了 `Accept-Ranges: bytes`，且返回了文件长度和强 ETag
+    /// - `Ok(None)`: 不支持范围请求，或没有可用于 `If-Range` 的强 ETag
+    async fn range_support(&self, remote_path: &str) -> Result<Option<RangeSupport>> {
+        let request = self.client.head(self.build_url(remote_path));
+        let response = self.send(request).await?;
+        self.check_response_status(&response)?;
+
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        if !header(reqwest::header::ACCEPT_RANGES).is_some_and(|v| v.eq_ignore_ascii_case("bytes"))
+        {
+            return Ok(None);
         }
 
-        // 请求构建错误
-        if error.is_builder() {
-            return SyncError::ConfigError(format!(
-                "Failed to build HTTP request: {}. This may indicate an invalid configuration.",
-                error
-            ));
+        let length = header(reqwest::header::CONTENT_LENGTH)
+            .and_then(|length| length.trim().parse::<u64>().ok());
+        let etag = header(reqwest::header::ETAG).filter(|etag| is_strong_etag(etag));
+        Ok(length.zip(etag).map(|(length, etag)| RangeSupport {
+            length,
+            modified: header(reqwest::header::LAST_MODIFIED).and_then(|v| parse_http_date(&v)),
+            etag,
+        }))
+    }
+
+    /// 并发下载各段并写入临时文件
+    ///
+    /// # 返回
+    /// - `Ok(Some(u64))`: 下载的字节数
+    /// - `Ok(None)`: 服务器忽略了范围请求，或远程文件已不是 `etag` 对应的版本
+    async fn write_ranges(
+        &self,
+        remote_path: &str,
+        path: &Path,
+        ranges: &[(u64, u64)],
+        etag: &str,
+        should_stop: &(dyn Fn() -> bool + Sync),
+        on_progress: &(dyn Fn(u64, Option<u64>) + Sync),
+    ) -> Result<Option<u64>> {
+        let total = ranges.last().map_or(0, |(_, end)| end + 1);
+        let file = tokio::fs::File::create(path).await?;
+        file.set_len(total).await?;
+        drop(file);
+
+        let written = AtomicU64::new(0);
+        let on_chunk = |bytes: u64| {
+            let sum = written.fetch_add(bytes, Ordering::Relaxed) + bytes;
+            on_progress(sum, Some(total));
+        };
+        let supported = futures_util::future::try_join_all(ranges.iter().map(|&(start, end)| {
+            self.download_range(remote_path, path, start, end, etag, should_stop, &on_chunk)
+        }))
+        .await?;
+        if supported.contains(&false) {
+            return Ok(None);
         }
 
-        // 请求发送错误
-        if error.is_request() {
-            return SyncError::Network(format!(
-                "Failed to send request: {}. Please check your network connection.",
-                error
-            ));
+        let file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+        // 重命名前确保数据已落盘
+        file.sync_all().await?;
+        Ok(Some(total))
+    }
+
+    /// 下载 [start, end] 范围的内容并写入文件的对应位置
+    ///
+    /// 请求带 `If-Range: etag`，每写入一个数据块后以该块的字节数调用 `on_chunk`
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 下载完成
+    /// - `Ok(false)`: 服务器未返回 206（忽略了范围请求或远程文件已变化），
+    ///   或响应的 ETag 与 `etag` 不一致，未写入任何内容
+    #[allow(clippy::too_many_arguments)]
+    async fn download_range(
+        &self,
+        remote_path: &str,
+        path: &Path,
+        start: u64,
+        end: u64,
+        etag: &str,
+        should_stop: &(dyn Fn() -> bool + Sync),
+        on_chunk: &(dyn Fn(u64) + Sync),
+    ) -> Result<bool> {
+        use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+        let request = self
+            .client
+            .get(self.build_url(remote_path))
+            .header(reqwest::header::RANGE, format!("bytes={}-{}", start, end))
+            .header(reqwest::header::IF_RANGE, etag);
+        let mut response = self
+            .within_read_timeout(self.send_streaming(request))
+            .await?;
+        self.check_response_status(&response)?;
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            return Ok(false);
+        }
+        let changed = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value != etag);
+        if changed {
+            return Ok(false);
         }
 
-        // 响应体读取错误
-        if error.is_body() || error.is_decode() {
-            return SyncError::WebDav(format!(
-                "Failed to read server response: {}. The server may have sent invalid data.",
-                error
-            ));
+        let mut file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        let expected = end - start + 1;
+        let mut written = 0u64;
+
+        loop {
+            let chunk = self.read_chunk(&mut response).await?;
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            written += chunk.len() as u64;
+            if written > expected {
+                break;
+            }
+            file.write_all(&chunk).await?;
+            on_chunk(chunk.len() as u64);
+
+            if should_stop() {
+                return Err(SyncError::Interrupted(format!(
+                    "Download interrupted: {}",
+                    remote_path
+                )));
+            }
         }
 
-        // 重定向错误
-        if error.is_redirect() {
-            return SyncError::WebDav(format!(
-                "Too many redirects or invalid redirect: {}. Please check the server URL.",
-                error
-            ));
+        if written != expected {
+            return Err(SyncError::WebDav(format!(
+                "Range {}-{} of {} returned {} bytes, expected {}",
+                start, end, remote_path, written, expected
+            )));
         }
+        file.flush().await?;
+        Ok(true)
+    }
 
-        // HTTP 状态错误（如果有状态码）
-        if let Some(status) = error.status() {
-            return self.map_status_error(status, &error.to_string());
+    /// 将响应体逐块写入文件
+    ///
+    /// offset 大于 0 时响应体是文件从 offset 开始的剩余部分，追加到已有内容之后
+    ///
+    /// # 返回
+    /// 文件的字节数（包括 offset 之前的内容）
+    async fn write_body(
+        &self,
+        response: &mut reqwest::Response,
+        path: &Path,
+        offset: u64,
+        remote_path: &str,
+        should_stop: &(dyn Fn() -> bool + Sync),
+        on_progress: &(dyn Fn(u64, Option<u64>) + Sync),
+    ) -> Result<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        let total = response.content_length().map(|length| offset + length);
+        let mut file = if offset > 0 {
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(path)
+                .await?
+        } else {
+            tokio::fs::File::create(path).await?
+        };
+        let mut written = offset;
+
+        loop {
+            let chunk = self.read_chunk(response).await?;
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+            on_progress(written, total);
+
+            if should_stop() {
+                return Err(SyncError::Interrupted(format!(
+                    "Download interrupted: {}",
+                    remote_path
+                )));
+            }
         }
 
-        // 其他未分类的网络错误
+        file.flush().await?;
+        // 重命名前确保数据已落盘，避免系统崩溃后目标文件内容为空或不完整
+        file.sync_all().await?;
+        Ok(written)
+    }
+
+    /// 读取响应体的下一块
+    ///
+    /// # 返回
+    /// - `Ok(Some(chunk))`: 数据块
+    /// - `Ok(None)`: 响应体已读完
+    /// - `Err(SyncError::Cancelled)`: 传输被取消
+    /// - `Err(SyncError::Network)`: 超过 `read_timeout` 没有收到数据
+    async fn read_chunk(
+        &self,
+        response: &mut reqwest::Response,
+    ) -> Result<Option<impl std::ops::Deref<Target = [u8]>>> {
+        tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => Err(self.cancelled_error()),
+            chunk = self.within_read_timeout(async {
+                response.chunk().await.map_err(|e| {
+                    SyncError::WebDav(format!("Failed to read response body: {}", e))
+                })
+            }) => chunk,
+        }
+    }
+
+    /// 等待操作完成，超过 `read_timeout` 时返回网络错误
+    async fn within_read_timeout<T>(
+        &self,
+        operation: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        tokio::time::timeout(self.read_timeout, operation)
+            .await
+            .unwrap_or_else(|_| Err(self.read_timeout_error()))
+    }
+
+    /// 等待上传完成，连续 `read_timeout` 没有发送数据时中止
+    ///
+    /// 连接失效时请求体不再被读取，`activity` 停止更新
+    async fn idle_guard<T>(
+        &self,
+        activity: &IdleWatch,
+        operation: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        tokio::pin!(operation);
+        loop {
+            let remaining = self.read_timeout.saturating_sub(activity.idle());
+            if remaining.is_zero() {
+                return Err(self.read_timeout_error());
+            }
+            tokio::select! {
+                result = &mut operation => return result,
+                _ = tokio::time::sleep(remaining) => {}
+            }
+        }
+    }
+
+    /// 流式传输连续没有收发数据时的错误
+    fn read_timeout_error(&self) -> SyncError {
         SyncError::Network(format!(
-            "Network error: {}. Please check your connection and try again.",
-            error
+            "No data transferred for {} seconds, the connection appears to be stalled",
+            self.read_timeout.as_secs()
         ))
     }
 
-    /// 检查 HTTP 响应状态码
+    /// 删除远程路径的文件或文件夹
     ///
-    /// 将 HTTP 状态码转换为应用层错误，提供详细的错误信息
+    /// 使用 DELETE 方法删除资源
     ///
     /// # 参数
-    /// - `response`: HTTP 响应对象
+    /// - `path`: 远程路径（相对于服务器根路径）
     ///
     /// # 返回
-    /// - `Ok(())`: 状态码表示成功 (2xx 或 207 Multi-Status)
-    /// - `Err(SyncError)`: 状态码表示失败，包含详细的错误类型和描述
+    /// - `Ok(())`: 删除成功
+    /// - `Err(SyncError)`: 删除失败
     ///
-    /// # 错误分类
-    /// - 401 Unauthorized -> `AuthError` (认证失败)
-    /// - 403 Forbidden -> `AuthError` (权限不足)
-    /// - 404 Not Found -> `NotFound` (资源不存在)
-    /// - 其他 4xx -> `WebDav` (客户端错误)
-    /// - 5xx -> `WebDav` (服务器错误)
-    fn check_response_status(&self, response: &reqwest::Response) -> Result<()> {
-        let status = response.status();
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// # use lightsync_lib::webdav::client::WebDavClient;
+    /// # use lightsync_lib::database::WebDavServerConfig;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let config = WebDavServerConfig {
+    /// #     id: "test".to_string(),
+    /// #     name: "Test".to_string(),
+    /// #     url: "https://example.com/webdav".to_string(),
+    /// #     username: "user".to_string(),
+    /// #     use_https: true,
+    /// #     timeout: 30,
+    /// #     last_test_at: None,
+    /// #     last_test_status: "unknown".to_string(),
+    /// #     last_test_error: None,
+    /// #     auth_type: "basic".to_string(),
+    /// #     accept_invalid_certs: false,
+    /// #     pinned_cert_fingerprint: None,
+    /// #     server_type: "generic".to_string(),
+    /// #     enabled: true,
+    /// #     created_at: 0,
+    /// #     updated_at: 0,
+    /// # };
+    /// # let password = "password".to_string();
+    /// let client = WebDavClient::new(&config, password)?;
+    /// client.delete("/old_file.txt").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        // 构建完整 URL
+        let url = self.build_url(path);
 
-        // 成功状态码
-        if status.is_success() || status == reqwest::StatusCode::MULTI_STATUS {
-            return Ok(());
-        }
+        // 发送 DELETE 请求
+        let request = self.client.delete(&url);
+        let response = self.send(request).await?;
 
-        // 认证错误 (401)
-        if status == reqwest::StatusCode::UNAUTHORIZED {
-            return Err(SyncError::AuthError(
-                "Authentication failed: Invalid username or password. Please check your credentials.".to_string(),
-            ));
-        }
+        // 检查响应状态
+        self.check_response_status(&response)?;
+        self.check_multistatus(response).await
+    }
 
-        // 权限错误 (403)
-        if status == reqwest::StatusCode::FORBIDDEN {
-            return Err(SyncError::AuthError(
-                "Access forbidden: You do not have permission to access this resource. Please check your account permissions.".to_string(),
-            ));
+    /// 删除远程目录
+    ///
+    /// 先确认目标是目录（集合），再发送 `Depth: infinity` 的 DELETE。
+    /// 未指定 `recursive` 时只删除空目录，目录中仍有内容（如被忽略规则排除的文件）时拒绝删除
+    ///
+    /// # 参数
+    /// - `path`: 远程目录路径（相对于服务器根路径）
+    /// - `recursive`: 是否允许连同目录中的内容一起删除
+    ///
+    /// # 返回
+    /// - `Ok(())`: 删除成功
+    /// - `Err(SyncError::NotFound)`: 目录不存在
+    /// - `Err(SyncError::ValidationError)`: 目标不是目录
+    /// - `Err(SyncError::Conflict)`: 目录非空且未指定 `recursive`，或其中有被锁定的子项
+    /// - `Err(SyncError)`: 其他错误（包括部分子项删除失败）
+    pub async fn delete_recursive(&self, path: &str, recursive: bool) -> Result<()> {
+        if !self.is_collection(path).await? {
+            return Err(SyncError::ValidationError(format!(
+                "Remote path is not a directory: {}",
+                path
+            )));
         }
 
-        // 资源不存在 (404)
-        if status == reqwest::StatusCode::NOT_FOUND {
-            return Err(SyncError::NotFound(
-                "Resource not found: The requested file or folder does not exist on the server."
-                    .to_string(),
-            ));
+        if !recursive {
+            let entries = self.list(path).await?;
+            if !entries.is_empty() {
+                return Err(SyncError::Conflict(format!(
+                    "Remote directory is not empty ({} entries): {}",
+                    entries.len(),
+                    path
+                )));
+            }
         }
 
-        // 其他客户端错误 (4xx)
-        if status.is_client_error() {
-            let error_detail = match status.as_u16() {
-                400 => "Bad Request: The server could not understand the request. This may indicate a client bug.",
-                405 => "Method Not Allowed: The requested operation is not supported for this resource.",
-                409 => "Conflict: The request conflicts with the current state of the resource. The resource may already exist or be locked.",
-                411 => "Length Required: The request did not specify the length of its content.",
-                412 => "Precondition Failed: One or more conditions in the request header fields evaluated to false.",
-                413 => "Payload Too Large: The request entity is larger than the server is willing or able to process.",
-                415 => "Unsupported Media Type: The server does not support the media type of the request.",
-                423 => "Locked: The resource is locked and cannot be modified.",
-                424 => "Failed Dependency: The request failed due to failure of a previous request.",
-                507 => "Insufficient Storage: The server is unable to store the representation needed to complete the request.",
-                _ => "Client error occurred.",
-            };
+        let url = self.build_url(path);
+        let request = self.client.delete(&url).header("Depth", "infinity");
+        let response = self.send(request).await?;
+        self.check_response_status(&response)?;
+        self.check_multistatus(response).await
+    }
 
-            return Err(SyncError::WebDav(format!(
-                "HTTP {} {}: {}",
-                status.as_u16(),
-                status.canonical_reason().unwrap_or("Unknown"),
-                error_detail
-            )));
-        }
+    /// 检查远程路径是否为目录（集合）
+    async fn is_collection(&self, path: &str) -> Result<bool> {
+        let url = self.build_url(path);
 
-        // 服务器错误 (5xx)
-        if status.is_server_error() {
-            let error_detail = match status.as_u16() {
-                500 => "Internal Server Error: The server encountered an unexpected condition. Please try again later or contact the server administrator.",
-                501 => "Not Implemented: The server does not support the functionality required to fulfill the request.",
-                502 => "Bad Gateway: The server received an invalid response from an upstream server.",
-                503 => "Service Unavailable: The server is temporarily unable to handle the request. Please try again later.",
-                504 => "Gateway Timeout: The server did not receive a timely response from an upstream server.",
-                507 => "Insufficient Storage: The server is unable to store the representation needed to complete the request.",
-                _ => "Server error occurred. Please try again later or contact the server administrator.",
-            };
+        let propfind_body = r#"<?xml version="1.0" encoding="utf-8" ?>
+            <D:propfind xmlns:D="DAV:">
+                <D:prop>
+                    <D:resourcetype/>
+                </D:prop>
+            </D:propfind>"#;
 
-            return Err(SyncError::WebDav(format!(
-                "HTTP {} {}: {}",
-                status.as_u16(),
-                status.canonical_reason().unwrap_or("Unknown"),
-                error_detail
-            )));
-        }
+        let response = self.propfind(&url, "0", propfind_body).await?;
+        self.check_response_status(&response)?;
 
-        // 其他未知状态码
-        Err(SyncError::WebDav(format!(
-            "Unexpected HTTP status: {} {}",
-            status.as_u16(),
-            status.canonical_reason().unwrap_or("Unknown")
-        )))
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+
+        Ok(body.contains(":collection") || body.contains("<collection"))
     }
 
-    /// 映射 HTTP 状态码到 SyncError（用于 map_request_error）
+    /// 在服务器端复制文件
+    ///
+    /// 使用 COPY 方法，文件内容不经过本地传输
     ///
     /// # 参数
-    /// - `status`: HTTP 状态码
-    /// - `additional_info`: 额外的错误信息
+    /// - `from`: 源路径（相对于服务器根路径）
+    /// - `to`: 目标路径（相对于服务器根路径）
+    /// - `overwrite`: 目标已存在时是否覆盖
     ///
     /// # 返回
-    /// 对应的 SyncError
-    fn map_status_error(&self, status: reqwest::StatusCode, additional_info: &str) -> SyncError {
-        // 认证错误 (401)
-        if status == reqwest::StatusCode::UNAUTHORIZED {
-            return SyncError::AuthError(
-                "Authentication failed: Invalid username or password. Please check your credentials.".to_string(),
-            );
-        }
+    /// - `Ok(true)`: 复制成功
+    /// - `Ok(false)`: 目标已存在且未要求覆盖（412）
+    /// - `Err(SyncError)`: 复制失败（包括目录中部分子项复制失败）
+    pub async fn copy(&self, from: &str, to: &str, overwrite: bool) -> Result<bool> {
+        let url = self.build_url(from);
 
-        // 权限错误 (403)
-        if status == reqwest::StatusCode::FORBIDDEN {
-            return SyncError::AuthError(
-                "Access forbidden: You do not have permission to access this resource. Please check your account permissions.".to_string(),
-            );
-        }
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"COPY").unwrap(), &url)
+            .header("Destination", self.build_url(to))
+            .header("Overwrite", if overwrite { "T" } else { "F" });
+        let response = self.send(request).await?;
 
-        // 资源不存在 (404)
-        if status == reqwest::StatusCode::NOT_FOUND {
-            return SyncError::NotFound(
-                "Resource not found: The requested file or folder does not exist on the server."
-                    .to_string(),
-            );
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
         }
+        self.check_response_status(&response)?;
+        self.check_multistatus(response).await?;
 
-        // 其他客户端错误 (4xx)
-        if status.is_client_error() {
-            let error_detail = match status.as_u16() {
-                400 => "Bad Request: The server could not understand the request.",
-                405 => "Method Not Allowed: The requested operation is not supported.",
-                409 => "Conflict: The resource may already exist or be locked.",
-                411 => "Length Required: The request did not specify content length.",
-                412 => "Precondition Failed: Request conditions evaluated to false.",
-                413 => "Payload Too Large: The request entity is too large.",
-                415 => "Unsupported Media Type: The media type is not supported.",
-                423 => "Locked: The resource is locked.",
-                424 => "Failed Dependency: A previous request failed.",
-                507 => "Insufficient Storage: The server has insufficient storage.",
-                _ => "Client error occurred.",
-            };
+        Ok(true)
+    }
 
-            let msg = if additional_info.is_empty() {
-                format!(
-                    "HTTP {} {}: {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown"),
-                    error_detail
-                )
-            } else {
-                format!(
-                    "HTTP {} {}: {}. {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown"),
-                    error_detail,
-                    additional_info
-                )
-            };
+    /// 在服务器端移动（重命名）文件
+    ///
+    /// 使用 MOVE 方法，文件内容不经过本地传输
+    ///
+    /// # 参数
+    /// - `from`: 源路径（相对于服务器根路径）
+    /// - `to`: 目标路径（相对于服务器根路径）
+    /// - `overwrite`: 目标已存在时是否覆盖
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 移动成功
+    /// - `Ok(false)`: 目标已存在且未要求覆盖（412）
+    /// - `Err(SyncError)`: 移动失败（包括目录中部分子项移动失败）
+    pub async fn move_to(&self, from: &str, to: &str, overwrite: bool) -> Result<bool> {
+        let url = self.build_url(from);
 
-            return SyncError::WebDav(msg);
-        }
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"MOVE").unwrap(), &url)
+            .header("Destination", self.build_url(to))
+            .header("Overwrite", if overwrite { "T" } else { "F" });
+        let response = self.send(request).await?;
 
-        // 服务器错误 (5xx)
-        if status.is_server_error() {
-            let error_detail = match status.as_u16() {
-                500 => "Internal Server Error: Please try again later.",
-                501 => "Not Implemented: The server does not support this functionality.",
-                502 => "Bad Gateway: Invalid response from upstream server.",
-                503 => "Service Unavailable: Please try again later.",
-                504 => "Gateway Timeout: Upstream server timeout.",
-                507 => "Insufficient Storage: The server has insufficient storage.",
-                _ => "Server error occurred.",
-            };
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        self.check_response_status(&response)?;
+        self.check_multistatus(response).await?;
 
-            let msg = if additional_info.is_empty() {
-                format!(
-                    "HTTP {} {}: {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown"),
-                    error_detail
-                )
-            } else {
-                format!(
-                    "HTTP {} {}: {}. {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown"),
-                    error_detail,
-                    additional_info
-                )
-            };
+        Ok(true)
+    }
 
-            return SyncError::WebDav(msg);
+    /// 在远程路径创建文件夹
+    ///
+    /// 使用 MKCOL 方法创建目录
+    ///
+    /// # 参数
+    /// - `path`: 远程路径（相对于服务器根路径）
+    ///
+    /// # 返回
+    /// - `Ok(())`: 创建成功
+    /// - `Err(SyncError)`: 创建失败
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// # use lightsync_lib::webdav::client::WebDavClient;
+    /// # use lightsync_lib::database::WebDavServerConfig;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let config = WebDavServerConfig {
+    /// #     id: "test".to_string(),
+    /// #     name: "Test".to_string(),
+    /// #     url: "https://example.com/webdav".to_string(),
+    /// #     username: "user".to_string(),
+    /// #     use_https: true,
+    /// #     timeout: 30,
+    /// #     last_test_at: None,
+    /// #     last_test_status: "unknown".to_string(),
+    /// #     last_test_error: None,
+    /// #     auth_type: "basic".to_string(),
+    /// #     accept_invalid_certs: false,
+    /// #     pinned_cert_fingerprint: None,
+    /// #     server_type: "generic".to_string(),
+    /// #     enabled: true,
+    /// #     created_at: 0,
+    /// #     updated_at: 0,
+    /// # };
+    /// # let password = "password".to_string();
+    /// let client = WebDavClient::new(&config, password)?;
+    /// client.mkdir("/new_folder").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mkdir(&self, path: &str) -> Result<()> {
+        // 构建完整 URL
+        let url = self.build_url(path);
+
+        // 发送 MKCOL 请求
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"MKCOL").unwrap(), &url);
+        let response = self.send(request).await?;
+
+        // 检查响应状态
+        self.check_response_status(&response)?;
+
+        Ok(())
+    }
+
+    /// 检查远程路径是否存在
+    ///
+    /// 发送 Depth 为 0 的 PROPFIND 请求，只检查资源本身
+    ///
+    /// # 参数
+    /// - `path`: 远程路径（相对于服务器根路径）
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 资源存在
+    /// - `Ok(false)`: 资源不存在（404）
+    /// - `Err(SyncError)`: 其他错误（认证失败、网络错误等）
+    pub async fn exists(&self, path: &str) -> Result<bool> {
+        let url = self.build_url(path);
+
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"PROPFIND").unwrap(), &url)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8");
+        let response = self.send(request).await?;
+
+        match self.check_response_status(&response) {
+            Ok(()) => Ok(true),
+            Err(SyncError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 获取远程文件的校验信息
+    ///
+    /// 发送 HEAD 请求，读取 Content-Length、ETag 与 `OC-Checksum` 响应头
+    ///
+    /// # 参数
+    /// - `path`: 远程文件路径（相对于服务器根路径）
+    ///
+    /// # 返回
+    /// - `Ok(RemoteChecksum)`: 校验信息（服务器未提供的字段为空）
+    /// - `Err(SyncError)`: 请求失败
+    pub async fn checksum(&self, path: &str) -> Result<RemoteChecksum> {
+        let url = self.build_url(path);
+
+        let request = self.client.head(&url);
+        let response = self.send(request).await?;
+        self.check_response_status(&response)?;
+
+        Ok(RemoteChecksum::from_headers(response.headers()))
+    }
+
+    /// 递归创建远程文件夹
+    ///
+    /// 从根路径开始逐级检查，不存在的目录依次使用 MKCOL 创建，已存在的目录会被跳过
+    ///
+    /// # 参数
+    /// - `path`: 远程路径（相对于服务器根路径）
+    ///
+    /// # 返回
+    /// - `Ok(())`: 目录已存在或创建成功
+    /// - `Err(SyncError)`: 创建失败
+    pub async fn mkdir_all(&self, path: &str) -> Result<()> {
+        let mut current = String::new();
+
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            current.push('/');
+            current.push_str(segment);
+
+            if !self.exists(&current).await? {
+                self.mkdir(&current).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 检查集合是否支持 sync-collection REPORT
+    ///
+    /// 通过 PROPFIND 查询 `supported-report-set`，判断服务器是否声明了 `sync-collection`
+    ///
+    /// # 参数
+    /// - `path`: 远程集合路径
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 支持增量同步
+    /// - `Ok(false)`: 不支持或服务器未声明
+    pub async fn supports_sync_collection(&self, path: &str) -> Result<bool> {
+        // 无法处理指定属性请求体的服务器不会支持 REPORT
+        if self.propfind_dialect() != PropfindDialect::Standard {
+            return Ok(false);
+        }
+
+        let url = self.build_url(path);
+
+        let propfind_body = r#"<?xml version="1.0" encoding="utf-8" ?>
+            <D:propfind xmlns:D="DAV:">
+                <D:prop>
+                    <D:supported-report-set/>
+                </D:prop>
+            </D:propfind>"#;
+
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"PROPFIND").unwrap(), &url)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(propfind_body);
+        let response = self.send(request).await?;
+
+        self.check_response_status(&response)?;
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+
+        Ok(body.contains("sync-collection"))
+    }
+
+    /// 查询存储配额
+    ///
+    /// 通过 PROPFIND 查询 `quota-available-bytes` 与 `quota-used-bytes`
+    ///
+    /// # 参数
+    /// - `path`: 远程集合路径
+    ///
+    /// # 返回
+    /// - `Ok(RemoteQuota)`: 配额信息（服务器未提供的字段为空）
+    /// - `Err(SyncError)`: 请求失败
+    pub async fn quota(&self, path: &str) -> Result<RemoteQuota> {
+        // 配额属性不在 allprop 的结果中，无法处理指定属性请求体的服务器视为未提供配额
+        if self.propfind_dialect() != PropfindDialect::Standard {
+            return Ok(RemoteQuota::default());
+        }
+
+        let url = self.build_url(path);
+
+        let propfind_body = r#"<?xml version="1.0" encoding="utf-8" ?>
+            <D:propfind xmlns:D="DAV:">
+                <D:prop>
+                    <D:quota-available-bytes/>
+                    <D:quota-used-bytes/>
+                </D:prop>
+            </D:propfind>"#;
+
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"PROPFIND").unwrap(), &url)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(propfind_body);
+        let response = self.send(request).await?;
+
+        self.check_response_status(&response)?;
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+
+        // Nextcloud 等服务器使用小写的 `d:` 前缀
+        let value = |name: &str| {
+            ["D:", "d:"]
+                .iter()
+                .find_map(|prefix| {
+                    self.extract_xml_value(&body, &format!("{}{}", prefix, name))
+                        .ok()
+                })
+                .and_then(|s| s.trim().parse::<u64>().ok())
+        };
+
+        Ok(RemoteQuota {
+            available_bytes: value("quota-available-bytes"),
+            used_bytes: value("quota-used-bytes"),
+        })
+    }
+
+    /// 通过 Nextcloud 预览接口获取图片缩略图（PNG）
+    ///
+    /// 仅适用于 Nextcloud 服务器，服务器 URL 需包含 `/remote.php/` 路径
+    ///
+    /// # 参数
+    /// - `path`: 远程文件路径（相对于服务器根路径）
+    /// - `size`: 缩略图的最大边长（像素，保持宽高比）
+    ///
+    /// # 返回
+    /// - `Ok(Some(bytes))`: 缩略图内容
+    /// - `Ok(None)`: 服务器 URL 不是 Nextcloud 的 WebDAV 地址，或服务器无法生成预览
+    /// - `Err(SyncError)`: 请求失败
+    pub async fn preview(&self, path: &str, size: u32) -> Result<Option<Vec<u8>>> {
+        let Some(url) = nextcloud_preview_url(&self.url, path, size) else {
+            return Ok(None);
+        };
+
+        let response = self.send(self.client.get(&url)).await?;
+        match self.check_response_status(&response) {
+            Ok(()) => {}
+            // 文件类型不支持预览或预览功能已关闭
+            Err(SyncError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read preview: {}", e)))?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    /// 调用 Nextcloud / ownCloud 的 OCS API（v2，JSON 格式）
+    ///
+    /// GET 与 DELETE 请求的参数放在查询字符串中，其他请求以表单提交
+    ///
+    /// # 参数
+    /// - `method`: 请求方法
+    /// - `endpoint`: 相对于 `/ocs/v2.php/` 的接口路径
+    /// - `params`: 请求参数
+    ///
+    /// # 返回
+    /// - `Ok(serde_json::Value)`: 响应中的 `ocs.data`（服务器返回 304 Not Modified 时为 `Null`）
+    /// - `Err(SyncError::ValidationError)`: 服务器 URL 不是 Nextcloud / ownCloud 的 WebDAV 地址，或参数被拒绝
+    /// - `Err(SyncError)`: 请求失败或 OCS 返回错误
+    pub async fn ocs(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        params: &[(&str, String)],
+    ) -> Result<serde_json::Value> {
+        let location = CloudLocation::parse(&self.url).ok_or_else(|| {
+            SyncError::ValidationError(format!(
+                "Server URL is not a Nextcloud or ownCloud WebDAV address: {}",
+                self.url
+            ))
+        })?;
+        let url = format!(
+            "{}/ocs/v2.php/{}",
+            location.base_url,
+            endpoint.trim_start_matches('/')
+        );
+
+        let mut request = self
+            .client
+            .request(method.clone(), &url)
+            .header("OCS-APIRequest", "true")
+            .header("Accept", "application/json")
+            .query(&[("format", "json")]);
+        request = if matches!(method, reqwest::Method::GET | reqwest::Method::DELETE) {
+            request.query(params)
+        } else {
+            request.form(params)
+        };
+        let response = self.send(request).await?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(serde_json::Value::Null);
+        }
+
+        let status_error = self.check_response_status(&response).err();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+        let body: Option<serde_json::Value> = serde_json::from_str(&body).ok();
+        let Some(ocs) = body.as_ref().and_then(|body| body.get("ocs")) else {
+            return Err(status_error.unwrap_or_else(|| {
+                SyncError::WebDav("Invalid OCS response from server".to_string())
+            }));
+        };
+
+        let meta = &ocs["meta"];
+        let message = meta["message"].as_str().unwrap_or_default().to_string();
+        match meta["statuscode"].as_u64().unwrap_or_default() {
+            100 | 200 => Ok(ocs["data"].clone()),
+            400 => Err(SyncError::ValidationError(format!(
+                "OCS request rejected: {}",
+                message
+            ))),
+            401 | 997 => Err(SyncError::AuthError(format!(
+                "OCS authentication failed: {}",
+                message
+            ))),
+            404 => Err(SyncError::NotFound(format!(
+                "OCS resource not found: {}",
+                message
+            ))),
+            code => Err(SyncError::WebDav(format!(
+                "OCS request failed ({}): {}",
+                code, message
+            ))),
+        }
+    }
+
+    /// 执行 sync-collection REPORT（RFC 6578）
+    ///
+    /// # 参数
+    /// - `path`: 远程集合路径
+    /// - `sync_token`: 上次返回的同步令牌；为 `None` 时返回集合下的全部资源
+    ///
+    /// # 返回
+    /// - `Ok(Some(result))`: 变更列表与新的同步令牌
+    /// - `Ok(None)`: 服务器拒绝了同步令牌（已过期），需要重新执行完整同步
+    /// - `Err(SyncError)`: 请求失败（包括服务器不支持该 REPORT）
+    pub async fn sync_collection(
+        &self,
+        path: &str,
+        sync_token: Option<&str>,
+    ) -> Result<Option<SyncCollectionResult>> {
+        let url = self.build_url(path);
+
+        let report_body = format!(
+            r#"<?xml version="1.0" encoding="utf-8" ?>
+            <D:sync-collection xmlns:D="DAV:">
+                <D:sync-token>{}</D:sync-token>
+                <D:sync-level>infinite</D:sync-level>
+                <D:prop>
+                    <D:resourcetype/>
+                    <D:getcontentlength/>
+                    <D:getlastmodified/>
+                    <D:getetag/>
+                </D:prop>
+            </D:sync-collection>"#,
+            sync_token.unwrap_or("")
+        );
+
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"REPORT").unwrap(), &url)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(report_body);
+        let response = self.send(request).await?;
+
+        let status = response.status();
+        if sync_token.is_some()
+            && (status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::CONFLICT)
+        {
+            let body = response.text().await.unwrap_or_default();
+            if body.contains("valid-sync-token") {
+                return Ok(None);
+            }
+            return Err(self.map_status_error(status, &body));
+        }
+
+        self.check_response_status(&response)?;
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+
+        self.parse_sync_collection_response(&body, path).map(Some)
+    }
+
+    /// 锁定远程资源（排他写锁）
+    ///
+    /// 对不存在的路径加锁时，服务器会创建一个空资源（RFC 4918 第 7.3 节）
+    ///
+    /// # 参数
+    /// - `path`: 远程路径（相对于服务器根路径）
+    /// - `owner`: 锁的所有者描述，便于在服务器上识别持有锁的客户端
+    /// - `timeout`: 锁的有效期（服务器可能缩短）
+    ///
+    /// # 返回
+    /// - `Ok(Some(RemoteLock))`: 加锁成功
+    /// - `Ok(None)`: 服务器不支持锁（405 / 501）
+    /// - `Err(SyncError::Conflict)`: 资源已被其他客户端锁定（423）
+    /// - `Err(SyncError)`: 其他错误
+    pub async fn lock(
+        &self,
+        path: &str,
+        owner: &str,
+        timeout: Duration,
+    ) -> Result<Option<RemoteLock>> {
+        let url = self.build_url(path);
+
+        let lock_body = format!(
+            r#"<?xml version="1.0" encoding="utf-8" ?>
+            <D:lockinfo xmlns:D="DAV:">
+                <D:lockscope><D:exclusive/></D:lockscope>
+                <D:locktype><D:write/></D:locktype>
+                <D:owner>{}</D:owner>
+            </D:lockinfo>"#,
+            escape_xml(owner)
+        );
+
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"LOCK").unwrap(), &url)
+            .header("Depth", "0")
+            .header("Timeout", format!("Second-{}", timeout.as_secs()))
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(lock_body);
+        let response = self.send(request).await?;
+
+        match response.status().as_u16() {
+            405 | 501 => return Ok(None),
+            423 => {
+                return Err(SyncError::Conflict(format!(
+                    "Remote file is locked by another client: {}",
+                    path
+                )))
+            }
+            _ => self.check_response_status(&response)?,
+        }
+
+        let header_token = response
+            .headers()
+            .get("lock-token")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| {
+                v.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            });
+        let granted_timeout = response
+            .headers()
+            .get("timeout")
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_lock_timeout);
+
+        let token = match header_token.filter(|t| !t.is_empty()) {
+            Some(token) => token,
+            None => {
+                let body = response.text().await.map_err(|e| {
+                    SyncError::WebDav(format!("Failed to read response body: {}", e))
+                })?;
+                let locktoken = self.extract_xml_value(&body, "D:locktoken")?;
+                self.extract_xml_value(&locktoken, "D:href")?
+                    .trim()
+                    .to_string()
+            }
+        };
+
+        let timeout = granted_timeout.unwrap_or(timeout);
+        Ok(Some(RemoteLock {
+            path: path.to_string(),
+            token,
+            owner: owner.to_string(),
+            expires_at: chrono::Utc::now().timestamp() + timeout.as_secs() as i64,
+        }))
+    }
+
+    /// 释放远程资源的锁
+    ///
+    /// # 参数
+    /// - `lock`: 加锁时返回的锁
+    ///
+    /// # 返回
+    /// - `Ok(())`: 释放成功，或锁已过期、资源已不存在
+    /// - `Err(SyncError)`: 释放失败
+    pub async fn unlock(&self, lock: &RemoteLock) -> Result<()> {
+        let url = self.build_url(&lock.path);
+
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"UNLOCK").unwrap(), &url)
+            .header("Lock-Token", format!("<{}>", lock.token));
+        let response = self.send(request).await?;
+
+        // 409：锁令牌已失效（锁已过期或被管理员清除）
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Ok(());
+        }
+
+        match self.check_response_status(&response) {
+            Ok(()) | Err(SyncError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 设置或删除远程资源的自定义属性（RFC 4918 PROPPATCH）
+    ///
+    /// 同一请求中的修改由服务器按顺序原子地执行：任一修改失败时全部不生效
+    ///
+    /// # 参数
+    /// - `path`: 远程路径（相对于服务器根路径）
+    /// - `updates`: 属性修改（按顺序执行）
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 全部修改已生效
+    /// - `Ok(false)`: 服务器不支持 PROPPATCH（405 / 501）或拒绝修改这些属性（403 等）
+    /// - `Err(SyncError::ValidationError)`: 属性名不是合法的 XML 名称
+    /// - `Err(SyncError::NotFound)`: 资源不存在
+    /// - `Err(SyncError::Conflict)`: 资源已被其他客户端锁定（423）
+    /// - `Err(SyncError)`: 其他错误
+    pub async fn proppatch(&self, path: &str, updates: &[PropertyUpdate]) -> Result<bool> {
+        if updates.is_empty() {
+            return Ok(true);
+        }
+        if let Some(update) = updates.iter().find(|update| !update.property().is_valid()) {
+            return Err(SyncError::ValidationError(format!(
+                "Invalid property name: {{{}}}{}",
+                update.property().namespace,
+                update.property().name
+            )));
+        }
+
+        let url = self.build_url(path);
+        let request = self
+            .client
+            .request(reqwest::Method::from_bytes(b"PROPPATCH").unwrap(), &url)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(proppatch_body(updates));
+        let response = self.send(request).await?;
+
+        match response.status().as_u16() {
+            403 | 405 | 501 => return Ok(false),
+            423 => {
+                return Err(SyncError::Conflict(format!(
+                    "Remote resource is locked by another client: {}",
+                    path
+                )))
+            }
+            207 => {}
+            _ => {
+                self.check_response_status(&response)?;
+                return Ok(true);
+            }
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+
+        // 每个属性的结果在 propstat 的 status 中，全部为 2xx 才表示修改已生效
+        Ok(multistatus_codes(&body)
+            .iter()
+            .all(|code| (200..300).contains(code)))
+    }
+
+    // ========== 辅助方法 ==========
+
+    /// 将 PROPFIND / REPORT 返回的 href 转换为相对于服务器根路径的路径
+    ///
+    /// 例如服务器 URL 为 `https://host/remote.php/dav/files/alice`，
+    /// href `/remote.php/dav/files/alice/docs/a.txt` 会被转换为 `/docs/a.txt`
+    ///
+    /// href 应为解析响应时已解码的路径（见 `FileInfo.path`）
+    pub fn relative_path(&self, href: &str) -> String {
+        let base = url::Url::parse(&self.url)
+            .map(|u| decode_path(u.path().trim_end_matches('/')))
+            .unwrap_or_default();
+        let relative = href.strip_prefix(base.as_str()).unwrap_or(href);
+        format!("/{}", relative.trim_start_matches('/'))
+    }
+
+    /// 发送 PROPFIND 请求，服务器拒绝请求体时改用兼容方式重试（见 `propfind_with`）
+    async fn propfind(&self, url: &str, depth: &str, body: &str) -> Result<reqwest::Response> {
+        self.propfind_with(url, depth, body, |request| self.send(request))
+            .await
+    }
+
+    /// 按当前的请求方式发送 PROPFIND 请求
+    ///
+    /// 服务器返回 400 或 422 时依次改用 allprop 和空请求体重试，
+    /// 重试成功后记住该方式，同一服务器的后续请求直接使用
+    ///
+    /// # 参数
+    /// - `url`: 完整 URL
+    /// - `depth`: `Depth` 头
+    /// - `body`: 标准方式的请求体（列出需要的属性）
+    /// - `send`: 发送请求的函数
+    ///
+    /// # 返回
+    /// 最后一次请求的响应（所有方式都被拒绝时为最后一次的 400/422 响应）
+    async fn propfind_with<F, Fut>(
+        &self,
+        url: &str,
+        depth: &str,
+        body: &str,
+        send: F,
+    ) -> Result<reqwest::Response>
+    where
+        F: Fn(reqwest::RequestBuilder) -> Fut,
+        Fut: std::future::Future<Output = Result<reqwest::Response>>,
+    {
+        let initial = self.propfind_dialect();
+        let mut dialect = initial;
+        loop {
+            let request = self
+                .client
+                .request(reqwest::Method::from_bytes(b"PROPFIND").unwrap(), url)
+                .header("Depth", depth);
+            let request = match dialect {
+                PropfindDialect::Standard => request
+                    .header("Content-Type", "application/xml; charset=utf-8")
+                    .body(body.to_string()),
+                PropfindDialect::AllProp => request
+                    .header("Content-Type", "application/xml; charset=utf-8")
+                    .body(ALLPROP_BODY),
+                PropfindDialect::Empty => request,
+            };
+            let response = send(request).await?;
+
+            let rejected = matches!(
+                response.status(),
+                reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNPROCESSABLE_ENTITY
+            );
+            match dialect.fallback() {
+                Some(next) if rejected => {
+                    tracing::info!(
+                        url,
+                        status = response.status().as_u16(),
+                        dialect = next.as_str(),
+                        "服务器拒绝 PROPFIND 请求体，改用兼容方式重试"
+                    );
+                    dialect = next;
+                }
+                _ => {
+                    if dialect != initial && !rejected {
+                        *self.propfind_dialect.lock().unwrap() = dialect;
+                    }
+                    return Ok(response);
+                }
+            }
+        }
+    }
+
+    /// 构建完整的 WebDAV URL
+    ///
+    /// 路径逐段百分号编码（`#`、`?`、`%`、空格、非 ASCII 字符等），分隔符 `/` 保持不变
+    ///
+    /// # 参数
+    /// - `path`: 未编码的相对路径
+    ///
+    /// # 返回
+    /// 完整的 URL 字符串
+    fn build_url(&self, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        format!("{}/{}", self.url.trim_end_matches('/'), encode_path(path))
+    }
+
+    /// 发送请求，取消令牌触发时立即中止
+    ///
+    /// 请求总时长（包括读取响应体）受 `timeout` 限制。
+    /// 未被跟随的重定向（写请求、跨服务器或超过次数限制）作为错误返回
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        self.send_streaming(request.timeout(self.timeout)).await
+    }
+
+    /// 发送流式传输请求（上传或下载文件内容），不限制总时长
+    ///
+    /// 传输大文件可能需要数小时，调用方通过 `read_timeout` 检测失效的连接
+    /// （见 `read_chunk` 与 `put_stream`）
+    async fn send_streaming(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => return Err(self.cancelled_error()),
+            result = self.execute(request) => result.map_err(|e| self.map_request_error(e))?,
+        };
+
+        match redirect_location(&response) {
+            Some(location) => Err(SyncError::WebDav(format!(
+                "Server redirected '{}' to '{}'. Please update the server URL.",
+                response.url(),
+                location
+            ))),
+            None => Ok(response),
+        }
+    }
+
+    /// 执行请求，跟随安全请求在同一服务器内的重定向
+    ///
+    /// 只有 PROPFIND、GET 等不修改数据、可以重放的请求会跟随重定向（保持原请求方法），
+    /// 写请求的请求体无法重放，直接返回重定向响应。永久重定向会记录推断出的新服务器 URL
+    async fn execute(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut request = request.build()?;
+        let mut redirects = 0;
+        loop {
+            let retry = if redirects < self.max_redirects && follows_redirect(request.method()) {
+                request.try_clone()
+            } else {
+                None
+            };
+            let from = request.url().clone();
+            let response = self.execute_once(request).await?;
+
+            let Some(location) = redirect_location(&response) else {
+                return Ok(response);
+            };
+            if !same_server(&from, &location) {
+                return Ok(response);
+            }
+            if matches!(response.status().as_u16(), 301 | 308) {
+                self.record_redirect(&from, &location);
+            }
+            let Some(mut next) = retry else {
+                return Ok(response);
+            };
+
+            tracing::debug!(from = %from, to = %location, status = %response.status(), "跟随重定向");
+            *next.url_mut() = location;
+            request = next;
+            redirects += 1;
+        }
+    }
+
+    /// 记录永久重定向推断出的新服务器 URL（首次发现时记录警告日志）
+    fn record_redirect(&self, from: &reqwest::Url, location: &reqwest::Url) {
+        let Some(url) = redirected_base(&self.url, from, location) else {
+            return;
+        };
+        let mut redirected = self.redirected_url.lock().unwrap();
+        if redirected.as_deref() != Some(url.as_str()) {
+            tracing::warn!(url = %self.url, redirected_url = %url, "服务器地址已永久重定向，建议更新服务器 URL");
+            *redirected = Some(url);
+        }
+    }
+
+    /// 执行单个请求，开启请求跟踪时记录方法、URL、状态、耗时与脱敏后的请求头
+    ///
+    /// 设置了 Cookie 罐时附带匹配的 Cookie，并保存响应中的 `Set-Cookie`
+    async fn execute_once(
+        &self,
+        mut request: reqwest::Request,
+    ) -> reqwest::Result<reqwest::Response> {
+        if let Some(cookie) = self
+            .cookies
+            .as_ref()
+            .and_then(|jar| jar.header(request.url()))
+        {
+            request
+                .headers_mut()
+                .insert(reqwest::header::COOKIE, cookie);
+        }
+        let result = if trace::trace().is_enabled() {
+            self.execute_traced(request).await
+        } else {
+            self.client.execute(request).await
+        };
+
+        if let (Some(jar), Ok(response)) = (&self.cookies, &result) {
+            jar.store_response(response.url(), response.headers());
+        }
+        result
+    }
+
+    /// 执行请求并记录到请求跟踪
+    async fn execute_traced(
+        &self,
+        request: reqwest::Request,
+    ) -> reqwest::Result<reqwest::Response> {
+        let trace = trace::trace();
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let headers = request.headers().clone();
+        let started = Instant::now();
+
+        let result = self.client.execute(request).await;
+        let response = match &result {
+            Ok(response) => Ok((response.status().as_u16(), response.headers())),
+            Err(e) => Err(e.to_string()),
+        };
+        trace.push(trace::record(
+            &method,
+            &url,
+            &headers,
+            response,
+            started.elapsed(),
+        ));
+
+        result
+    }
+
+    /// 请求被取消时的错误
+    fn cancelled_error(&self) -> SyncError {
+        SyncError::Cancelled(format!("Request to '{}' was cancelled", self.url))
+    }
+
+    /// 映射 reqwest 错误到 SyncError
+    ///
+    /// 将 HTTP 客户端错误转换为应用层的 SyncError，提供详细的错误信息
+    ///
+    /// # 参数
+    /// - `error`: reqwest 错误
+    ///
+    /// # 返回
+    /// 对应的 SyncError，包含详细的错误类型和描述
+    ///
+    /// # 错误类型映射
+    /// - 超时错误 -> `Network` (包含超时时间)
+    /// - 连接错误 -> `Network` (包含连接失败原因)
+    /// - DNS 解析错误 -> `Network` (包含域名信息)
+    /// - TLS/SSL 错误 -> `Network` (包含证书错误信息)
+    /// - 其他网络错误 -> `Network` (包含具体错误描述)
+    fn map_request_error(&self, error: reqwest::Error) -> SyncError {
+        // 超时错误
+        if error.is_timeout() {
+            let seconds = if error.is_connect() {
+                self.connect_timeout
+            } else {
+                self.timeout
+            };
+            return SyncError::Network(format!(
+                "Connection timeout after {} seconds. Please check your network connection or increase the timeout setting.",
+                seconds.as_secs()
+            ));
+        }
+
+        // 连接错误
+        if error.is_connect() {
+            // 尝试提取更详细的错误信息
+            let error_msg = error.to_string();
+
+            // DNS 解析失败
+            if error_msg.contains("dns") || error_msg.contains("resolve") {
+                return SyncError::Network(format!(
+                    "Failed to resolve server address '{}'. Please check the server URL and your DNS settings.",
+                    self.url
+                ));
+            }
+
+            // 连接被拒绝
+            if error_msg.contains("refused") {
+                return SyncError::Network(format!(
+                    "Connection refused by server '{}'. Please verify the server is running and accessible.",
+                    self.url
+                ));
+            }
+
+            // TLS/SSL 错误
+            if error_msg.contains("ssl")
+                || error_msg.contains("tls")
+                || error_msg.contains("certificate")
+            {
+                return SyncError::Network(format!(
+                    "SSL/TLS connection error: {}. This may be caused by an invalid certificate or unsupported protocol.",
+                    error
+                ));
+            }
+
+            // 通用连接错误
+            return SyncError::Network(format!(
+                "Failed to connect to server '{}': {}. Please check the server URL and your network connection.",
+                self.url, error
+            ));
+        }
+
+        // 请求构建错误
+        if error.is_builder() {
+            return SyncError::ConfigError(format!(
+                "Failed to build HTTP request: {}. This may indicate an invalid configuration.",
+                error
+            ));
+        }
+
+        // 请求发送错误
+        if error.is_request() {
+            return SyncError::Network(format!(
+                "Failed to send request: {}. Please check your network connection.",
+                error
+            ));
+        }
+
+        // 响应体读取错误
+        if error.is_body() || error.is_decode() {
+            return SyncError::WebDav(format!(
+                "Failed to read server response: {}. The server may have sent invalid data.",
+                error
+            ));
+        }
+
+        // 重定向错误
+        if error.is_redirect() {
+            return SyncError::WebDav(format!(
+                "Too many redirects or invalid redirect: {}. Please check the server URL.",
+                error
+            ));
+        }
+
+        // HTTP 状态错误（如果有状态码）
+        if let Some(status) = error.status() {
+            return self.map_status_error(status, &error.to_string());
+        }
+
+        // 其他未分类的网络错误
+        SyncError::Network(format!(
+            "Network error: {}. Please check your connection and try again.",
+            error
+        ))
+    }
+
+    /// 检查 HTTP 响应状态码
+    ///
+    /// 将 HTTP 状态码转换为应用层错误，提供详细的错误信息
+    ///
+    /// # 参数
+    /// - `response`: HTTP 响应对象
+    ///
+    /// # 返回
+    /// - `Ok(())`: 状态码表示成功 (2xx 或 207 Multi-Status)
+    /// - `Err(SyncError)`: 状态码表示失败，包含详细的错误类型和描述
+    ///
+    /// # 错误分类
+    /// - 401 Unauthorized -> `AuthError` (认证失败)
+    /// - 403 Forbidden -> `AuthError` (权限不足)
+    /// - 404 Not Found -> `NotFound` (资源不存在)
+    /// - 其他 4xx -> `WebDav` (客户端错误)
+    /// - 5xx -> `WebDav` (服务器错误)
+    fn check_response_status(&self, response: &reqwest::Response) -> Result<()> {
+        let status = response.status();
+
+        // 成功状态码
+        if status.is_success() || status == reqwest::StatusCode::MULTI_STATUS {
+            return Ok(());
+        }
+
+        // 认证错误 (401)
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(SyncError::AuthError(
+                "Authentication failed: Invalid username or password. Please check your credentials.".to_string(),
+            ));
+        }
+
+        // 权限错误 (403)
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return Err(SyncError::AuthError(
+                "Access forbidden: You do not have permission to access this resource. Please check your account permissions.".to_string(),
+            ));
+        }
+
+        // 资源不存在 (404)
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(SyncError::NotFound(
+                "Resource not found: The requested file or folder does not exist on the server."
+                    .to_string(),
+            ));
+        }
+
+        // 其他客户端错误 (4xx)
+        if status.is_client_error() {
+            let error_detail = match status.as_u16() {
+                400 => "Bad Request: The server could not understand the request. This may indicate a client bug.",
+                405 => "Method Not Allowed: The requested operation is not supported for this resource.",
+                409 => "Conflict: The request conflicts with the current state of the resource. The resource may already exist or be locked.",
+                411 => "Length Required: The request did not specify the length of its content.",
+                412 => "Precondition Failed: One or more conditions in the request header fields evaluated to false.",
+                413 => "Payload Too Large: The request entity is larger than the server is willing or able to process.",
+                415 => "Unsupported Media Type: The server does not support the media type of the request.",
+                423 => "Locked: The resource is locked and cannot be modified.",
+                424 => "Failed Dependency: The request failed due to failure of a previous request.",
+                507 => "Insufficient Storage: The server is unable to store the representation needed to complete the request.",
+                _ => "Client error occurred.",
+            };
+
+            return Err(SyncError::WebDav(format!(
+                "HTTP {} {}: {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown"),
+                error_detail
+            )));
+        }
+
+        // 服务器错误 (5xx)
+        if status.is_server_error() {
+            let error_detail = match status.as_u16() {
+                500 => "Internal Server Error: The server encountered an unexpected condition. Please try again later or contact the server administrator.",
+                501 => "Not Implemented: The server does not support the functionality required to fulfill the request.",
+                502 => "Bad Gateway: The server received an invalid response from an upstream server.",
+                503 => "Service Unavailable: The server is temporarily unable to handle the request. Please try again later.",
+                504 => "Gateway Timeout: The server did not receive a timely response from an upstream server.",
+                507 => "Insufficient Storage: The server is unable to store the representation needed to complete the request.",
+                _ => "Server error occurred. Please try again later or contact the server administrator.",
+            };
+
+            return Err(SyncError::WebDav(format!(
+                "HTTP {} {}: {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown"),
+                error_detail
+            )));
+        }
+
+        // 其他未知状态码
+        Err(SyncError::WebDav(format!(
+            "Unexpected HTTP status: {} {}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Unknown")
+        )))
+    }
+
+    /// 映射 HTTP 状态码到 SyncError（用于 map_request_error）
+    ///
+    /// # 参数
+    /// - `status`: HTTP 状态码
+    /// - `additional_info`: 额外的错误信息
+    ///
+    /// # 返回
+    /// 对应的 SyncError
+    fn map_status_error(&self, status: reqwest::StatusCode, additional_info: &str) -> SyncError {
+        // 认证错误 (401)
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return SyncError::AuthError(
+                "Authentication failed: Invalid username or password. Please check your credentials.".to_string(),
+            );
+        }
+
+        // 权限错误 (403)
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return SyncError::AuthError(
+                "Access forbidden: You do not have permission to access this resource. Please check your account permissions.".to_string(),
+            );
+        }
+
+        // 资源不存在 (404)
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return SyncError::NotFound(
+                "Resource not found: The requested file or folder does not exist on the server."
+                    .to_string(),
+            );
+        }
+
+        // 其他客户端错误 (4xx)
+        if status.is_client_error() {
+            let error_detail = match status.as_u16() {
+                400 => "Bad Request: The server could not understand the request.",
+                405 => "Method Not Allowed: The requested operation is not supported.",
+                409 => "Conflict: The resource may already exist or be locked.",
+                411 => "Length Required: The request did not specify content length.",
+                412 => "Precondition Failed: Request conditions evaluated to false.",
+                413 => "Payload Too Large: The request entity is too large.",
+                415 => "Unsupported Media Type: The media type is not supported.",
+                423 => "Locked: The resource is locked.",
+                424 => "Failed Dependency: A previous request failed.",
+                507 => "Insufficient Storage: The server has insufficient storage.",
+                _ => "Client error occurred.",
+            };
+
+            let msg = if additional_info.is_empty() {
+                format!(
+                    "HTTP {} {}: {}",
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown"),
+                    error_detail
+                )
+            } else {
+                format!(
+                    "HTTP {} {}: {}. {}",
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown"),
+                    error_detail,
+                    additional_info
+                )
+            };
+
+            return SyncError::WebDav(msg);
+        }
+
+        // 服务器错误 (5xx)
+        if status.is_server_error() {
+            let error_detail = match status.as_u16() {
+                500 => "Internal Server Error: Please try again later.",
+                501 => "Not Implemented: The server does not support this functionality.",
+                502 => "Bad Gateway: Invalid response from upstream server.",
+                503 => "Service Unavailable: Please try again later.",
+                504 => "Gateway Timeout: Upstream server timeout.",
+                507 => "Insufficient Storage: The server has insufficient storage.",
+                _ => "Server error occurred.",
+            };
+
+            let msg = if additional_info.is_empty() {
+                format!(
+                    "HTTP {} {}: {}",
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown"),
+                    error_detail
+                )
+            } else {
+                format!(
+                    "HTTP {} {}: {}. {}",
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown"),
+                    error_detail,
+                    additional_info
+                )
+            };
+
+            return SyncError::WebDav(msg);
+        }
+
+        // 其他未知状态码
+        SyncError::WebDav(format!(
+            "Unexpected HTTP status: {} {}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Unknown")
+        ))
+    }
+
+    /// 解析 PROPFIND 响应
+    ///
+    /// 简单的 XML 解析实现，提取文件信息
+    ///
+    /// # 参数
+    /// - `xml`: XML 响应体
+    /// - `base_path`: 基础路径
+    ///
+    /// # 返回
+    /// 文件信息列表与处理失败的子项
+    fn parse_propfind_response(&self, xml: &str, base_path: &str) -> Result<RemoteListing> {
+        let mut listing = RemoteListing::default();
+
+        // 简单的 XML 解析（生产环境应使用专业的 XML 解析库如 quick-xml）
+        // 这里使用简单的字符串匹配来提取信息
+
+        // 分割响应为多个 <D:response> 块
+        for response_block in xml.split("<D:response>").skip(1) {
+            if let Some(end_pos) = response_block.find("</D:response>") {
+                let response_content = &response_block[..end_pos];
+
+                // 提取 href（路径，解码百分号编码）
+                let path = decode_path(&self.extract_xml_value(response_content, "D:href")?);
+
+                // 跳过当前目录本身
+                let normalized_base = base_path.trim_end_matches('/');
+                let normalized_path = path.trim_end_matches('/');
+                if normalized_path == normalized_base {
+                    continue;
+                }
+
+                match failed_status(response_content) {
+                    Some(status) => listing.failures.push(RemoteItemError { path, status }),
+                    None => listing
+                        .files
+                        .push(self.parse_file_info(response_content, path)),
+                }
+            }
+        }
+
+        Ok(listing)
+    }
+
+    /// 解析多状态响应中处理失败的资源
+    ///
+    /// # 参数
+    /// - `xml`: XML 响应体
+    fn multistatus_failures(&self, xml: &str) -> Vec<RemoteItemError> {
+        xml.split("<D:response>")
+            .skip(1)
+            .filter_map(|block| {
+                let content = &block[..block.find("</D:response>")?];
+                let status = failed_status(content)?;
+                let path = decode_path(&self.extract_xml_value(content, "D:href").ok()?);
+                Some(RemoteItemError { path, status })
+            })
+            .collect()
+    }
+
+    /// 检查 DELETE / COPY / MOVE 的多状态响应
+    ///
+    /// 对目录的操作部分失败时服务器返回 207，响应体中列出失败的子项
+    ///
+    /// # 返回
+    /// - `Ok(())`: 不是多状态响应，或所有子项都成功
+    /// - `Err(SyncError::Conflict)`: 有子项被锁定（423）
+    /// - `Err(SyncError::WebDav)`: 有子项处理失败
+    async fn check_multistatus(&self, response: reqwest::Response) -> Result<()> {
+        if response.status() != reqwest::StatusCode::MULTI_STATUS {
+            return Ok(());
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::WebDav(format!("Failed to read response body: {}", e)))?;
+        let failures = self.multistatus_failures(&body);
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        let details = failures
+            .iter()
+            .map(|f| format!("{} ({})", f.path, f.status))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if failures.iter().any(|f| f.status == 423) {
+            Err(SyncError::Conflict(format!(
+                "Remote resources are locked: {}",
+                details
+            )))
+        } else {
+            Err(SyncError::WebDav(format!(
+                "Operation failed for {} remote resources: {}",
+                failures.len(),
+                details
+            )))
+        }
+    }
+
+    /// 从单个 `<D:response>` 块构建文件信息
+    ///
+    /// # 参数
+    /// - `response_content`: `<D:response>` 块内容
+    /// - `path`: 已提取的 href
+    fn parse_file_info(&self, response_content: &str, path: String) -> FileInfo {
+        // 提取文件名
+        let name = path
+            .trim_end_matches('/')
+            .split('/')
+            .last()
+            .unwrap_or("")
+            .to_string();
+
+        // 检查是否为目录
+        let is_directory = response_content.contains("<D:collection/>");
+
+        // 提取文件大小
+        let size = if is_directory {
+            0
+        } else {
+            self.extract_xml_value(response_content, "D:getcontentlength")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+
+        // 提取修改时间（RFC 1123 格式，如 `Mon, 12 Jan 1998 09:25:56 GMT`）
+        let modified = self
+            .extract_xml_value(response_content, "D:getlastmodified")
+            .ok()
+            .and_then(|s| parse_http_date(&s));
+
+        // 提取 ETag（保留引号，只用于比较）
+        let etag = self
+            .extract_xml_value(response_content, "D:getetag")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        FileInfo {
+            path,
+            name,
+            is_directory,
+            size,
+            modified,
+            etag,
+        }
+    }
+
+    /// 解析 sync-collection REPORT 响应
+    ///
+    /// 带有 `<D:status>` 404 且没有 `<D:propstat>` 的响应表示该资源已被删除，
+    /// 其余响应为新增或修改的资源
+    ///
+    /// # 参数
+    /// - `xml`: XML 响应体
+    /// - `base_path`: 基础路径（集合本身会被跳过）
+    fn parse_sync_collection_response(
+        &self,
+        xml: &str,
+        base_path: &str,
+    ) -> Result<SyncCollectionResult> {
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+
+        for response_block in xml.split("<D:response>").skip(1) {
+            if let Some(end_pos) = response_block.find("</D:response>") {
+                let response_content = &response_block[..end_pos];
+                let path = decode_path(&self.extract_xml_value(response_content, "D:href")?);
+
+                if path.trim_end_matches('/') == base_path.trim_end_matches('/') {
+                    continue;
+                }
+
+                let is_removed = !response_content.contains("<D:propstat>")
+                    && self
+                        .extract_xml_value(response_content, "D:status")
+                        .map(|status| status.contains(" 404"))
+                        .unwrap_or(false);
+
+                if is_removed {
+                    removed.push(path);
+                } else {
+                    changed.push(self.parse_file_info(response_content, path));
+                }
+            }
+        }
+
+        // sync-token 位于 multistatus 末尾
+        let tail = match xml.rfind("</D:response>") {
+            Some(pos) => &xml[pos..],
+            None => xml,
+        };
+        let sync_token = self.extract_xml_value(tail, "D:sync-token")?;
+
+        Ok(SyncCollectionResult {
+            sync_token,
+            changed,
+            removed,
+        })
+    }
+
+    /// 从 XML 中提取标签值
+    ///
+    /// # 参数
+    /// - `xml`: XML 字符串
+    /// - `tag`: 标签名
+    ///
+    /// # 返回
+    /// 标签内容
+    fn extract_xml_value(&self, xml: &str, tag: &str) -> Result<String> {
+        let start_tag = format!("<{}>", tag);
+        let end_tag = format!("</{}>", tag);
+
+        if let Some(start_pos) = xml.find(&start_tag) {
+            let content_start = start_pos + start_tag.len();
+            if let Some(end_pos) = xml[content_start..].find(&end_tag) {
+                return Ok(xml[content_start..content_start + end_pos].to_string());
+            }
+        }
+
+        Err(SyncError::WebDav(format!(
+            "Failed to extract XML value for tag: {}",
+            tag
+        )))
+    }
+}
+
+impl Display for WebDavClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WebDAV Client for {}", self.url)
+    }
+}
+
+/// 根据 HTTP 响应头检测服务器类型
+///
+/// # 参数
+/// - `headers`: HTTP 响应头
+///
+/// # 返回
+/// 服务器类型字符串：
+/// - "nextcloud": Nextcloud 服务器
+/// - "owncloud": ownCloud 服务器
+/// - "apache": Apache WebDAV
+/// - "nginx": Nginx WebDAV
+/// - "generic": 通用 WebDAV 服务器
+pub fn server_type_from_headers(headers: &reqwest::header::HeaderMap) -> String {
+    // 检查 Server 头
+    if let Some(server_header) = headers.get("server") {
+        if let Ok(server_str) = server_header.to_str() {
+            let server_lower = server_str.to_lowercase();
+
+            if server_lower.contains("nextcloud") {
+                return "nextcloud".to_string();
+            }
+            if server_lower.contains("owncloud") {
+                return "owncloud".to_string();
+            }
+            if server_lower.contains("apache") {
+                return "apache".to_string();
+            }
+            if server_lower.contains("nginx") {
+                return "nginx".to_string();
+            }
+        }
+    }
+
+    // 检查 X-Powered-By 头（某些服务器会提供）
+    if let Some(powered_by) = headers.get("x-powered-by") {
+        if let Ok(powered_str) = powered_by.to_str() {
+            let powered_lower = powered_str.to_lowercase();
+
+            if powered_lower.contains("nextcloud") {
+                return "nextcloud".to_string();
+            }
+            if powered_lower.contains("owncloud") {
+                return "owncloud".to_string();
+            }
+        }
+    }
+
+    // 检查 X-OC-Version 头（ownCloud/Nextcloud 特有）
+    if headers.contains_key("x-oc-version") {
+        // 如果有 X-OC-Version 但没有明确标识，默认为 ownCloud
+        return "owncloud".to_string();
+    }
+
+    // 默认返回通用类型
+    "generic".to_string()
+}
+
+/// Nextcloud / ownCloud 的服务器根地址与用户文件目录
+///
+/// 从 WebDAV 地址（`.../remote.php/dav/files/<用户>/...` 或 `.../remote.php/webdav/...`）推导，
+/// 例如 `https://host/nc/remote.php/dav/files/alice/Photos` 的根地址为 `https://host/nc`，
+/// WebDAV 地址对应用户文件中的 `/Photos`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudLocation {
+    /// 服务器根地址（不含末尾的 `/`）
+    pub base_url: String,
+    /// WebDAV 地址在用户文件中对应的目录（以 `/` 开头；为用户根目录时为空字符串）
+    pub files_root: String,
+}
+
+impl CloudLocation {
+    /// 解析 WebDAV 地址，不是 Nextcloud / ownCloud 的格式时返回 None
+    pub fn parse(webdav_url: &str) -> Option<Self> {
+        let mut url = url::Url::parse(webdav_url).ok()?;
+        let (prefix, rest) = url.path().split_once("/remote.php/")?;
+        let (prefix, rest) = (prefix.to_string(), decode_path(rest));
+
+        let root = if let Some(files) = rest.strip_prefix("dav/files/") {
+            // 跳过用户名
+            files.split_once('/').map_or("", |(_, root)| root)
+        } else if rest.trim_end_matches('/') == "webdav" {
+            ""
+        } else {
+            rest.strip_prefix("webdav/")?
+        };
+        let root = root.trim_matches('/');
+
+        url.set_path(&prefix);
+        url.set_query(None);
+        url.set_fragment(None);
+        Some(Self {
+            base_url: url.to_string().trim_end_matches('/').to_string(),
+            files_root: if root.is_empty() {
+                String::new()
+            } else {
+                format!("/{}", root)
+            },
+        })
+    }
+
+    /// 远程路径（相对于 WebDAV 地址）在用户文件中的路径
+    pub fn user_path(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            return if self.files_root.is_empty() {
+                "/".to_string()
+            } else {
+                self.files_root.clone()
+            };
+        }
+        format!("{}/{}", self.files_root, path)
+    }
+
+    /// 用户文件中的路径转换为相对于 WebDAV 地址的远程路径，不在 WebDAV 地址下时返回 None
+    pub fn remote_path(&self, user_path: &str) -> Option<String> {
+        let user_path = format!("/{}", user_path.trim_matches('/'));
+        if user_path == self.files_root {
+            return Some("/".to_string());
+        }
+        if self.files_root.is_empty() {
+            return Some(user_path);
+        }
+        user_path
+            .strip_prefix(&self.files_root)
+            .filter(|rest| rest.starts_with('/'))
+            .map(str::to_string)
+    }
+}
+
+/// 构建 Nextcloud 预览接口的 URL
+///
+/// 例如 `https://host/remote.php/dav/files/alice/Photos` 与 `/a.jpg` 对应
+/// `https://host/index.php/core/preview.png?file=%2FPhotos%2Fa.jpg&x=256&y=256&a=1`
+///
+/// # 返回
+/// WebDAV 地址不是 Nextcloud 的格式时返回 None
+pub fn nextcloud_preview_url(webdav_url: &str, path: &str, size: u32) -> Option<String> {
+    let location = CloudLocation::parse(webdav_url)?;
+
+    let mut url =
+        url::Url::parse(&format!("{}/index.php/core/preview.png", location.base_url)).ok()?;
+    url.query_pairs_mut()
+        .append_pair("file", &location.user_path(path))
+        .append_pair("x", &size.to_string())
+        .append_pair("y", &size.to_string())
+        .append_pair("a", "1");
+    Some(url.to_string())
+}
+
+/// 应用服务器的网络选项
+///
+/// - 强制 IPv4/IPv6 时绑定对应协议族的本地地址，DNS 解析结果中只使用该协议族的地址
+/// - 设置了固定 IP 时，服务器主机名直接解析到该地址（端口沿用 URL 中的端口）
+fn apply_network_options(
+    mut builder: reqwest::ClientBuilder,
+    config: &WebDavServerConfig,
+) -> Result<reqwest::ClientBuilder> {
+    match config.ip_version.as_str() {
+        ip_version::IPV4 => builder = builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        ip_version::IPV6 => builder = builder.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        _ => {}
+    }
+
+    if let Some(ip) = &config.resolve_ip {
+        let ip: IpAddr = ip
+            .trim()
+            .parse()
+            .map_err(|_| SyncError::ValidationError(format!("Invalid IP address: {}", ip)))?;
+        let url = reqwest::Url::parse(&config.resolved_url())
+            .map_err(|e| SyncError::ValidationError(format!("Invalid server URL: {}", e)))?;
+        if let (Some(url::Host::Domain(host)), Some(port)) =
+            (url.host(), url.port_or_known_default())
+        {
+            builder = builder.resolve(host, SocketAddr::new(ip, port));
+        }
+    }
+
+    Ok(builder)
+}
+
+/// 响应是否为重定向，返回解析后的目标地址（相对地址基于请求 URL 解析）
+///
+/// 304 Not Modified 等不带跳转目标的 3xx 响应不视为重定向
+fn redirect_location(response: &reqwest::Response) -> Option<reqwest::Url> {
+    if !matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+    let location = response.headers().get(reqwest::header::LOCATION)?;
+    response.url().join(location.to_str().ok()?).ok()
+}
+
+/// 请求方法是否可以安全地跟随重定向（不修改数据、没有需要重放的请求体流）
+fn follows_redirect(method: &reqwest::Method) -> bool {
+    matches!(method.as_str(), "GET" | "HEAD" | "OPTIONS" | "PROPFIND")
+}
+
+/// 重定向目标是否仍是同一服务器（相同主机；允许从 HTTP 升级到默认端口的 HTTPS）
+///
+/// 认证信息随默认请求头发送，不跟随到其他服务器或从 HTTPS 降级到 HTTP 的重定向
+fn same_server(from: &reqwest::Url, to: &reqwest::Url) -> bool {
+    if from.host_str() != to.host_str() {
+        return false;
+    }
+    match (from.scheme(), to.scheme()) {
+        (a, b) if a == b => from.port_or_known_default() == to.port_or_known_default(),
+        ("http", "https") => to.port().is_none(),
+        _ => false,
+    }
+}
+
+/// 根据永久重定向推断新的服务器 URL
+///
+/// 请求 URL 位于服务器 URL 下时，从重定向目标中去掉相同的相对路径得到新的服务器 URL，
+/// 如 `/webdav` 重定向到 `/webdav/`，或 `http://host/dav/a.txt` 重定向到 `https://host/dav/a.txt`
+///
+/// # 参数
+/// - base: 当前的服务器 URL
+/// - request: 被重定向的请求 URL
+/// - location: 重定向目标
+///
+/// # 返回
+/// 新的服务器 URL；请求不在服务器 URL 下、无法推断或与当前服务器 URL 相同时返回 None
+pub fn redirected_base(
+    base: &str,
+    request: &reqwest::Url,
+    location: &reqwest::Url,
+) -> Option<String> {
+    let base = reqwest::Url::parse(base).ok()?;
+    let relative = request
+        .as_str()
+        .strip_prefix(base.as_str().trim_end_matches('/'))?
+        .trim_end_matches('/');
+    if !relative.is_empty() && !relative.starts_with('/') {
+        return None;
+    }
+
+    let redirected = if relative.is_empty() {
+        location.as_str().to_string()
+    } else {
+        location
+            .as_str()
+            .trim_end_matches('/')
+            .strip_suffix(relative)?
+            .to_string()
+    };
+    (redirected != base.as_str()).then_some(redirected)
+}
+
+/// 下载结束后处理临时文件
+///
+/// 成功时将临时文件重命名为目标文件，并在服务器返回了修改时间时设置本地文件的修改时间；
+/// 失败时删除临时文件，目标文件保持不变
+async fn finish_download(
+    part_path: &Path,
+    local_path: &Path,
+    remote_modified: Option<i64>,
+    result: Result<u64>,
+) -> Result<u64> {
+    let result = match result {
+        Ok(written) => tokio::fs::rename(part_path, local_path)
+            .await
+            .map(|_| written)
+            .map_err(SyncError::Io),
+        Err(e) => Err(e),
+    };
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(part_path).await;
+    } else if let Some(modified) = remote_modified {
+        let mtime = filetime::FileTime::from_unix_time(modified, 0);
+        if let Err(e) = filetime::set_file_mtime(local_path, mtime) {
+            tracing::warn!(path = %local_path.display(), error = %e, "设置文件修改时间失败");
+        }
+    }
+    result
+}
+
+/// 解析 `Content-Range: bytes <start>-<end>/<total>` 中的起始位置
+fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .trim()
+        .strip_prefix("bytes ")?
+        .split_once('-')?
+        .0
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 将长度为 total 的文件均分为最多 connections 段，返回每段的闭区间 [start, end]
+///
+/// 每段至少 `TRANSFER_CHUNK_SIZE` 字节，文件较小时段数相应减少
+pub fn split_ranges(total: u64, connections: usize) -> Vec<(u64, u64)> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let count = (connections as u64)
+        .min(total / TRANSFER_CHUNK_SIZE as u64)
+        .max(1);
+    let size = total.div_ceil(count);
+
+    (0..count)
+        .map(|i| i * size)
+        .take_while(|&start| start < total)
+        .map(|start| (start, (start + size).min(total) - 1))
+        .collect()
+}
+
+/// 下载过程中使用的临时文件路径（与目标文件同目录，便于原子重命名）
+///
+/// 如 `docs/a.txt` 对应 `docs/.a.txt.lightsync-part`
+pub fn partial_download_path(local_path: &Path) -> PathBuf {
+    let file_name = local_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    local_path.with_file_name(format!(".{}{}", file_name, PARTIAL_DOWNLOAD_SUFFIX))
+}
+
+/// 支持范围请求的远程文件（`range_support` 的结果）
+#[derive(Debug)]
+struct RangeSupport {
+    /// 文件长度
+    length: u64,
+    /// 远程修改时间
+    modified: Option<i64>,
+    /// 强 ETag（含引号），作为各段请求的 `If-Range`
+    etag: String,
+}
+
+/// 是否为强 ETag（弱 ETag 不能用于 `If-Range`）
+fn is_strong_etag(etag: &str) -> bool {
+    etag.len() > 2 && etag.starts_with('"') && etag.ends_with('"')
+}
+
+/// 分段并行下载使用的临时文件路径
+///
+/// 如 `docs/a.txt` 对应 `docs/.a.txt.ranged.lightsync-part`。与可以续传的临时文件
+/// （`partial_download_path`）分开，启动恢复和续传前的检查不会把带空洞的内容当作已下载的前缀
+pub fn ranged_download_path(local_path: &Path) -> PathBuf {
+    let file_name = local_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    local_path.with_file_name(format!(".{}.ranged{}", file_name, PARTIAL_DOWNLOAD_SUFFIX))
+}
+
+/// 解析 HTTP 日期（RFC 1123，如 `Mon, 12 Jan 1998 09:25:56 GMT`）为 Unix 时间戳（秒）
+pub fn parse_http_date(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|date| date.timestamp())
+}
+
+/// 流式上传的活动记录：最近一次发送数据的时间，克隆后共享
+#[derive(Debug, Clone)]
+struct IdleWatch {
+    started: Instant,
+    /// 最近一次活动距 `started` 的毫秒数
+    last: Arc<AtomicU64>,
+}
+
+impl IdleWatch {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 记录一次活动
+    fn touch(&self) {
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        self.last.store(elapsed, Ordering::Relaxed);
+    }
+
+    /// 距最近一次活动的时间
+    fn idle(&self) -> Duration {
+        let last = Duration::from_millis(self.last.load(Ordering::Relaxed));
+        self.started.elapsed().saturating_sub(last)
+    }
+}
+
+/// 将系统时间转换为 Unix 时间戳（秒）
+fn unix_timestamp(time: std::time::SystemTime) -> Option<i64> {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
+/// 转义 XML 文本与属性值中的特殊字符
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 构建 PROPPATCH 请求体
+///
+/// 每个属性单独声明命名空间（前缀 `L`），不同命名空间的属性可以在同一请求中修改
+fn proppatch_body(updates: &[PropertyUpdate]) -> String {
+    let mut body = String::from(
+        r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propertyupdate xmlns:D="DAV:">"#,
+    );
+    for update in updates {
+        let property = update.property();
+        let namespace = escape_xml(&property.namespace);
+        match update {
+            PropertyUpdate::Set(_, value) => body.push_str(&format!(
+                r#"<D:set><D:prop><L:{name} xmlns:L="{namespace}">{value}</L:{name}></D:prop></D:set>"#,
+                name = property.name,
+                namespace = namespace,
+                value = escape_xml(value)
+            )),
+            PropertyUpdate::Remove(_) => body.push_str(&format!(
+                r#"<D:remove><D:prop><L:{name} xmlns:L="{namespace}"/></D:prop></D:remove>"#,
+                name = property.name,
+                namespace = namespace
+            )),
+        }
+    }
+    body.push_str("</D:propertyupdate>");
+    body
+}
+
+/// 提取多状态（207）响应中的所有状态码
+///
+/// 状态行的格式为 `HTTP/1.1 200 OK`，不依赖命名空间前缀
+fn multistatus_codes(xml: &str) -> Vec<u16> {
+    xml.match_indices("HTTP/1.")
+        .filter_map(|(pos, _)| {
+            xml[pos..]
+                .split_whitespace()
+                .nth(1)
+                .and_then(|code| code.get(..3))
+                .and_then(|code| code.parse().ok())
+        })
+        .collect()
+}
+
+/// 判断多状态响应中的单个资源是否处理失败
+///
+/// 资源的所有状态（`<D:status>` 与各 `<D:propstat>` 的状态）都不是 2xx 时视为失败；
+/// 部分属性返回 404（服务器没有该属性）而其他属性为 200 是正常情况
+///
+/// # 返回
+/// 失败时返回第一个状态码，否则返回 None
+fn failed_status(response_content: &str) -> Option<u16> {
+    let codes = multistatus_codes(response_content);
+    if codes.iter().any(|code| (200..300).contains(code)) {
+        return None;
+    }
+    codes.first().copied()
+}
+
+/// 解析 LOCK 响应的 `Timeout` 头
+///
+/// 格式为 `Second-600` 或 `Infinite`（视为不过期，返回 None 使用请求的有效期）
+fn parse_lock_timeout(value: &str) -> Option<Duration> {
+    value
+        .split(',')
+        .find_map(|part| part.trim().strip_prefix("Second-"))
+        .and_then(|secs| secs.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::init_test_logging;
+    use crate::webdav::mock_server_config;
+
+    /// 创建测试用的服务器配置
+    fn create_test_config() -> WebDavServerConfig {
+        init_test_logging(); // 初始化日志系统
+        use tracing::debug;
+
+        let config = WebDavServerConfig {
+            use_https: true,
+            timeout: 30,
+            ..mock_server_config("https://example.com/webdav")
+        };
+        debug!(config = ?config, "创建测试配置");
+        config
+    }
+
+    /// 创建使用 mock 服务器 URL 的配置
+    fn create_mock_config(url: String) -> WebDavServerConfig {
+        init_test_logging(); // 初始化日志系统
+        mock_server_config(&url)
+    }
+
+    #[test]
+    fn test_create_client_success() {
+        let config = create_test_config();
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_ok());
+
+        let client = result.unwrap();
+        assert_eq!(client.url(), "https://example.com/webdav");
+        assert_eq!(client.username(), "testuser");
+        assert_eq!(client.timeout(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_create_client_with_http() {
+        let mut config = create_test_config();
+        config.url = "http://example.com/webdav".to_string();
+        config.use_https = false;
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_ok());
+
+        let client = result.unwrap();
+        assert_eq!(client.url(), "http://example.com/webdav");
+    }
+
+    #[test]
+    fn test_create_client_empty_password() {
+        let config = create_test_config();
+        let password = "".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Password cannot be empty"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_whitespace_password() {
+        let config = create_test_config();
+        let password = "   ".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Password cannot be empty"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_invalid_config_empty_name() {
+        let mut config = create_test_config();
+        config.name = "".to_string();
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Invalid server config"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_invalid_config_empty_url() {
+        let mut config = create_test_config();
+        config.url = "".to_string();
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Invalid server config"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_invalid_config_bad_url() {
+        let mut config = create_test_config();
+        config.url = "not-a-valid-url".to_string();
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Invalid server config"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_invalid_config_empty_username() {
+        let mut config = create_test_config();
+        config.username = "".to_string();
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Invalid server config"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_invalid_config_timeout_too_small() {
+        let mut config = create_test_config();
+        config.timeout = 0;
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Invalid server config"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_invalid_config_timeout_too_large() {
+        let mut config = create_test_config();
+        config.timeout = 301;
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::ValidationError(msg) => {
+                assert!(msg.contains("Invalid server config"));
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_create_client_custom_timeout() {
+        let mut config = create_test_config();
+        config.timeout = 60;
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_ok());
+
+        let client = result.unwrap();
+        assert_eq!(client.timeout(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_create_client_minimum_timeout() {
+        let mut config = create_test_config();
+        config.timeout = 1;
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_ok());
+
+        let client = result.unwrap();
+        assert_eq!(client.timeout(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_create_client_maximum_timeout() {
+        let mut config = create_test_config();
+        config.timeout = 300;
+        let password = "test_password".to_string();
+
+        let result = WebDavClient::new(&config, password);
+        assert!(result.is_ok());
+
+        let client = result.unwrap();
+        assert_eq!(client.timeout(), Duration::from_secs(300));
+    }
+
+    // ========== test_connection 方法测试 ==========
+
+    #[tokio::test]
+    async fn test_connection_success_generic() {
+        use tracing::info;
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .match_header("authorization", mockito::Matcher::Any)
+            .with_status(207) // Multi-Status
+            .with_header("content-type", "application/xml")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        info!(mock_server_url = %server.url(), "创建的mock服务器");
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        info!(result = ?result, "获取的返回结果");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "generic");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_sent() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("x-api-key", "secret-key")
+            .match_header("authorization", mockito::Matcher::Any)
+            .with_status(207)
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let mut config = create_mock_config(server.url());
+        config
+            .custom_headers
+            .0
+            .insert("X-API-Key".to_string(), "secret-key".to_string());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        assert!(client.test_connection().await.is_ok());
+        assert!(!format!("{:?}", client).contains("secret-key"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ip_override() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(207)
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        // 主机名无法通过 DNS 解析，固定解析到 mock 服务器的地址
+        let address = server.socket_address();
+        let mut config = create_mock_config(format!("http://nas.invalid:{}", address.port()));
+        config.resolve_ip = Some(address.ip().to_string());
+        config.ip_version = "ipv4".to_string();
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        assert_eq!(client.test_connection().await.unwrap(), "generic");
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_redirected_base() {
+        let url = |s: &str| reqwest::Url::parse(s).unwrap();
+
+        // 根路径缺少末尾的 /
+        assert_eq!(
+            redirected_base(
+                "https://host/webdav",
+                &url("https://host/webdav"),
+                &url("https://host/webdav/")
+            ),
+            Some("https://host/webdav/".to_string())
+        );
+        // 子路径重定向到新的服务器路径与协议
+        assert_eq!(
+            redirected_base(
+                "http://host/dav",
+                &url("http://host/dav/docs/a.txt"),
+                &url("https://host/remote.php/dav/docs/a.txt")
+            ),
+            Some("https://host/remote.php/dav".to_string())
+        );
+        // 目录补全末尾的 / 不改变服务器 URL
+        assert_eq!(
+            redirected_base(
+                "https://host/dav",
+                &url("https://host/dav/docs"),
+                &url("https://host/dav/docs/")
+            ),
+            None
+        );
+        // 请求不在服务器 URL 下
+        assert_eq!(
+            redirected_base(
+                "https://host/dav",
+                &url("https://host/ocs/v2.php/cloud/user"),
+                &url("https://host/ocs/v2.php/cloud/user/")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_same_server() {
+        let url = |s: &str| reqwest::Url::parse(s).unwrap();
+
+        assert!(same_server(&url("https://host/a"), &url("https://host/b")));
+        assert!(same_server(&url("http://host/a"), &url("https://host/a")));
+        assert!(!same_server(&url("https://host/a"), &url("http://host/a")));
+        assert!(!same_server(
+            &url("https://host/a"),
+            &url("https://other/a")
+        ));
+        assert!(!same_server(
+            &url("https://host/a"),
+            &url("https://host:8443/a")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_propfind_follows_permanent_redirect() {
+        let mut server = mockito::Server::new_async().await;
+        let redirect = server
+            .mock("PROPFIND", "/webdav")
+            .with_status(301)
+            .with_header("location", "/webdav/")
+            .create_async()
+            .await;
+        let target = server
+            .mock("PROPFIND", "/webdav/")
+            .match_header("authorization", mockito::Matcher::Any)
+            .with_status(207)
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(format!("{}/webdav", server.url()));
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        assert_eq!(client.test_connection().await.unwrap(), "generic");
+        assert_eq!(
+            client.redirected_url(),
+            Some(format!("{}/webdav/", server.url()))
+        );
+        redirect.assert_async().await;
+        target.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_put_redirect_not_followed() {
+        let mut server = mockito::Server::new_async().await;
+        let _redirect = server
+            .mock("PUT", "/dav/a.txt")
+            .with_status(308)
+            .with_header("location", "/remote.php/dav/a.txt")
+            .create_async()
+            .await;
+        let target = server
+            .mock("PUT", "/remote.php/dav/a.txt")
+            .with_status(201)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(format!("{}/dav", server.url()));
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("redirect_{}.txt", uuid::Uuid::new_v4()));
+        tokio::fs::write(&local, b"hello").await.unwrap();
+
+        let error = client.upload(&local, "/a.txt").await.unwrap_err();
+        tokio::fs::remove_file(&local).await.ok();
+        assert!(error.to_string().contains("redirected"));
+        assert_eq!(
+            client.redirected_url(),
+            Some(format!("{}/remote.php/dav", server.url()))
+        );
+        target.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_success_nextcloud() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_header("server", "Apache/2.4.41 (Ubuntu) Nextcloud")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "nextcloud");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_success_owncloud() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_header("server", "Apache/2.4.41 (Ubuntu) ownCloud")
+            .with_header("x-oc-version", "10.8.0")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "owncloud");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_success_apache() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_header("server", "Apache/2.4.41 (Ubuntu)")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "apache");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_success_nginx() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_header("server", "nginx/1.18.0")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "nginx");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_success_with_200_ok() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .with_status(200) // Some servers return 200 OK instead of 207
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_auth_failure_401() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(401)
+            .with_header("www-authenticate", "Basic realm=\"WebDAV\"")
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "wrong_password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::AuthError(msg) => {
+                assert!(msg.contains("Authentication failed"));
+            }
+            _ => panic!("Expected AuthError"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_forbidden_403() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(403)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::AuthError(msg) => {
+                assert!(msg.contains("Access forbidden"));
+            }
+            _ => panic!("Expected AuthError"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_not_found_404() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::WebDav(msg) => {
+                assert!(msg.contains("404"));
+            }
+            _ => panic!("Expected WebDav error"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_server_error_500() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::WebDav(msg) => {
+                assert!(msg.contains("500"));
+            }
+            _ => panic!("Expected WebDav error"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_connection_network_error() {
+        // 使用一个不存在的地址来模拟网络错误
+        let mut config = create_test_config();
+        config.url = "http://localhost:1".to_string(); // 不太可能有服务在这个端口
+        config.timeout = 1; // 短超时
+        config.use_https = false;
+
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::Network(_) => {
+                // 预期的网络错误
+            }
+            _ => panic!("Expected Network error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_server_type_with_x_powered_by() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(207)
+            .with_header("x-powered-by", "Nextcloud")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "nextcloud");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_detect_server_type_with_x_oc_version() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/")
+            .with_status(207)
+            .with_header("x-oc-version", "10.8.0")
+            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.test_connection().await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "owncloud");
+        mock.assert_async().await;
+    }
+
+    // ========== 文件操作方法测试 ==========
+
+    #[tokio::test]
+    async fn test_list_files_success() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/documents")
+            .match_header("depth", "1")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/documents/</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype><D:collection/></D:resourcetype>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                    <D:response>
+                        <D:href>/documents/file1.txt</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype/>
+                                <D:getcontentlength>1024</D:getcontentlength>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                    <D:response>
+                        <D:href>/documents/folder1/</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype><D:collection/></D:resourcetype>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.list("/documents").await;
+        assert!(result.is_ok());
+
+        let files = result.unwrap();
+        assert_eq!(files.len(), 2); // 不包括当前目录本身
+
+        // 检查文件
+        let file = files.iter().find(|f| f.name == "file1.txt").unwrap();
+        assert!(!file.is_directory);
+        assert_eq!(file.size, 1024);
+
+        // 检查文件夹
+        let folder = files.iter().find(|f| f.name == "folder1").unwrap();
+        assert!(folder.is_directory);
+        assert_eq!(folder.size, 0);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_decodes_encoded_hrefs() {
+        let mut server = mockito::Server::new_async().await;
+        // 请求路径按分段编码
+        let mock = server
+            .mock("PROPFIND", "/%E6%96%87%E6%A1%A3/my%20docs")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/%E6%96%87%E6%A1%A3/my%20docs/</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype><D:collection/></D:resourcetype>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                    <D:response>
+                        <D:href>/%E6%96%87%E6%A1%A3/my%20docs/%E6%8A%A5%E5%91%8A%20%231.txt</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype/>
+                                <D:getcontentlength>12</D:getcontentlength>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let files = client.list("/文档/my docs").await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "/文档/my docs/报告 #1.txt");
+        assert_eq!(files[0].name, "报告 #1.txt");
+        assert_eq!(
+            client.relative_path(&files[0].path),
+            "/文档/my docs/报告 #1.txt"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_parses_last_modified() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/docs")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/docs/a.txt</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype/>
+                                <D:getcontentlength>3</D:getcontentlength>
+                                <D:getlastmodified>Wed, 21 Oct 2015 07:28:00 GMT</D:getlastmodified>
+                                <D:getetag>"5f1e-3"</D:getetag>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                    <D:response>
+                        <D:href>/docs/b.txt</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype/>
+                                <D:getlastmodified>not a date</D:getlastmodified>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let files = client.list("/docs").await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(files[0].modified, Some(1445412480));
+        assert_eq!(files[0].etag.as_deref(), Some("\"5f1e-3\""));
+        assert_eq!(files[1].modified, None);
+        assert_eq!(files[1].etag, None);
+    }
+
+    #[tokio::test]
+    async fn test_list_files_empty_directory() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PROPFIND", "/empty")
+            .match_header("depth", "1")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/empty/</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype><D:collection/></D:resourcetype>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let result = client.list("/empty").await;
+        assert!(result.is_ok());
+
+        let files = result.unwrap();
+        assert_eq!(files.len(), 0);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_success() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/test.txt")
+            .with_status(201) // Created
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        // 创建临时测试文件
+        let temp_dir = std::env::temp_dir();
+        let test_file = temp_dir.join("test_upload.txt");
+        tokio::fs::write(&test_file, b"test content").await.unwrap();
+
+        let result = client.upload(&test_file, "/test.txt").await;
+        assert!(result.is_ok());
+
+        // 清理
+        tokio::fs::remove_file(&test_file).await.ok();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("PUT", "/test.txt")
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        // 尝试上传不存在的文件
+        let result = client
+            .upload(Path::new("/nonexistent/file.txt"), "/test.txt")
+            .await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::Io(_) => {
+                // 预期的 IO 错误
+            }
+            _ => panic!("Expected Io error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_file_success() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/test.txt")
+            .with_status(200)
+            .with_body("downloaded content")
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        // 创建临时下载路径
+        let temp_dir = std::env::temp_dir();
+        let download_file = temp_dir.join("test_download.txt");
+
+        let result = client.download("/test.txt", &download_file).await;
+        assert!(result.is_ok());
+
+        // 验证文件内容
+        let content = tokio::fs::read_to_string(&download_file).await.unwrap();
+        assert_eq!(content, "downloaded content");
+
+        // 清理
+        tokio::fs::remove_file(&download_file).await.ok();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_stalled_body_hits_read_timeout() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/stalled.txt")
+            .with_status(200)
+            .with_chunked_body(|w| {
+                w.write_all(b"partial")?;
+                std::thread::sleep(Duration::from_secs(7));
+                w.write_all(b" content")
+            })
+            .create_async()
+            .await;
+
+        let mut config = create_mock_config(server.url());
+        config.read_timeout = 5;
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        assert_eq!(client.read_timeout(), Duration::from_secs(5));
+
+        let download_file = std::env::temp_dir().join("test_download_stalled.txt");
+        let result = client.download("/stalled.txt", &download_file).await;
+        assert!(
+            matches!(result, Err(SyncError::Network(message)) if message.contains("5 seconds"))
+        );
+
+        tokio::fs::remove_file(&download_file).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_download_file_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/nonexistent.txt")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let temp_dir = std::env::temp_dir();
+        let download_file = temp_dir.join("test_download_404.txt");
+
+        let result = client.download("/nonexistent.txt", &download_file).await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::NotFound(_) => {
+                // 预期的 NotFound 错误
+            }
+            _ => panic!("Expected NotFound error"),
         }
 
-        // 其他未知状态码
-        SyncError::WebDav(format!(
-            "Unexpected HTTP status: {} {}",
-            status.as_u16(),
-            status.canonical_reason().unwrap_or("Unknown")
-        ))
+        mock.assert_async().await;
     }
 
-    /// 解析 PROPFIND 响应
-    ///
-    /// 简单的 XML 解析实现，提取文件信息
-    ///
-    /// # 参数
-    /// - `xml`: XML 响应体
-    /// - `base_path`: 基础路径
-    ///
-    /// # 返回
-    /// 文件信息列表
-    fn parse_propfind_response(&self, xml: &str, base_path: &str) -> Result<Vec<FileInfo>> {
-        let mut files = Vec::new();
-
-        // 简单的 XML 解析（生产环境应使用专业的 XML 解析库如 quick-xml）
-        // 这里使用简单的字符串匹配来提取信息
+    #[tokio::test]
+    async fn test_download_sets_remote_mtime() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/dated.txt")
+            .with_status(200)
+            .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_body("dated")
+            .create_async()
+            .await;
 
-        // 分割响应为多个 <D:response> 块
-        for response_block in xml.split("<D:response>").skip(1) {
-            if let Some(end_pos) = response_block.find("</D:response>") {
-                let response_content = &response_block[..end_pos];
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-                // 提取 href（路径）
-                let path = self.extract_xml_value(response_content, "D:href")?;
+        let download_file = std::env::temp_dir().join("test_download_mtime.txt");
+        client.download("/dated.txt", &download_file).await.unwrap();
 
-                // 跳过当前目录本身
-                let normalized_base = base_path.trim_end_matches('/');
-                let normalized_path = path.trim_end_matches('/');
-                if normalized_path == normalized_base {
-                    continue;
-                }
+        let metadata = tokio::fs::metadata(&download_file).await.unwrap();
+        assert_eq!(
+            unix_timestamp(metadata.modified().unwrap()),
+            Some(1445412480)
+        );
 
-                // 提取文件名
-                let name = path
-                    .trim_end_matches('/')
-                    .split('/')
-                    .last()
-                    .unwrap_or("")
-                    .to_string();
+        tokio::fs::remove_file(&download_file).await.ok();
+        mock.assert_async().await;
+    }
 
-                // 检查是否为目录
-                let is_directory = response_content.contains("<D:collection/>");
+    #[test]
+    fn test_split_ranges() {
+        let chunk = TRANSFER_CHUNK_SIZE as u64;
 
-                // 提取文件大小
-                let size = if is_directory {
-                    0
-                } else {
-                    self.extract_xml_value(response_content, "D:getcontentlength")
-                        .ok()
-                        .and_then(|s| s.parse::<u64>().ok())
-                        .unwrap_or(0)
-                };
-
-                // 提取修改时间（简化处理）
-                let modified = None; // TODO: 解析 D:getlastmodified
-
-                files.push(FileInfo {
-                    path: path.clone(),
-                    name,
-                    is_directory,
-                    size,
-                    modified,
-                });
-            }
-        }
+        assert_eq!(split_ranges(0, 4), vec![]);
+        // 小文件不拆分
+        assert_eq!(split_ranges(100, 4), vec![(0, 99)]);
+        assert_eq!(
+            split_ranges(2 * chunk + 1, 4),
+            vec![(0, chunk), (chunk + 1, 2 * chunk)]
+        );
 
-        Ok(files)
+        let ranges = split_ranges(10 * chunk, 4);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[3].1, 10 * chunk - 1);
+        assert!(ranges.windows(2).all(|pair| pair[0].1 + 1 == pair[1].0));
     }
 
-    /// 从 XML 中提取标签值
-    ///
-    /// # 参数
-    /// - `xml`: XML 字符串
-    /// - `tag`: 标签名
-    ///
-    /// # 返回
-    /// 标签内容
-    fn extract_xml_value(&self, xml: &str, tag: &str) -> Result<String> {
-        let start_tag = format!("<{}>", tag);
-        let end_tag = format!("</{}>", tag);
+    #[tokio::test]
+    async fn test_download_ranged() {
+        let content: Vec<u8> = (0..3 * TRANSFER_CHUNK_SIZE)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let ranges = split_ranges(content.len() as u64, 2);
+        assert_eq!(ranges.len(), 2);
 
-        if let Some(start_pos) = xml.find(&start_tag) {
-            let content_start = start_pos + start_tag.len();
-            if let Some(end_pos) = xml[content_start..].find(&end_tag) {
-                return Ok(xml[content_start..content_start + end_pos].to_string());
-            }
+        let mut server = mockito::Server::new_async().await;
+        let head = server
+            .mock("HEAD", "/large.bin")
+            .with_status(200)
+            .with_header("accept-ranges", "bytes")
+            .with_header("content-length", &content.len().to_string())
+            .with_header("etag", "\"v1\"")
+            .create_async()
+            .await;
+        let mut parts = Vec::new();
+        for (start, end) in ranges {
+            parts.push(
+                server
+                    .mock("GET", "/large.bin")
+                    .match_header("range", format!("bytes={}-{}", start, end).as_str())
+                    .match_header("if-range", "\"v1\"")
+                    .with_status(206)
+                    .with_body(&content[start as usize..=end as usize])
+                    .create_async()
+                    .await,
+            );
         }
 
-        Err(SyncError::WebDav(format!(
-            "Failed to extract XML value for tag: {}",
-            tag
-        )))
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_ranged_{}", uuid::Uuid::new_v4()));
+
+        let reported = AtomicU64::new(0);
+        let written = client
+            .download_ranged(
+                "/large.bin",
+                &local,
+                2,
+                || false,
+                |sum, total| {
+                    assert_eq!(total, Some(content.len() as u64));
+                    reported.fetch_max(sum, Ordering::Relaxed);
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(reported.load(Ordering::Relaxed), written);
+        assert_eq!(tokio::fs::read(&local).await.unwrap(), content);
+        assert!(!partial_download_path(&local).exists());
+        assert!(!ranged_download_path(&local).exists());
+
+        head.assert_async().await;
+        for part in parts {
+            part.assert_async().await;
+        }
+        tokio::fs::remove_file(&local).await.ok();
     }
-}
 
-impl Display for WebDavClient {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "WebDAV Client for {}", self.url)
-    }
-}
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::test_utils::init_test_logging;
+    #[tokio::test]
+    async fn test_download_ranged_falls_back_when_remote_changes() {
+        let content = vec![9u8; 3 * TRANSFER_CHUNK_SIZE];
+        let mut server = mockito::Server::new_async().await;
+        let _head = server
+            .mock("HEAD", "/large.bin")
+            .with_status(200)
+            .with_header("accept-ranges", "bytes")
+            .with_header("content-length", &content.len().to_string())
+            .with_header("etag", "\"v1\"")
+            .create_async()
+            .await;
+        // If-Range 不匹配时服务器返回新版本的完整内容
+        let ranged = server
+            .mock("GET", "/large.bin")
+            .match_header("range", mockito::Matcher::Regex("bytes=".to_string()))
+            .match_header("if-range", "\"v1\"")
+            .with_status(200)
+            .with_header("etag", "\"v2\"")
+            .with_body(&content)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        let full = server
+            .mock("GET", "/large.bin")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body(&content)
+            .expect(1)
+            .create_async()
+            .await;
 
-    /// 创建测试用的服务器配置
-    fn create_test_config() -> WebDavServerConfig {
-        init_test_logging(); // 初始化日志系统
-        use tracing::debug;
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_ranged_{}", uuid::Uuid::new_v4()));
 
-        let now = chrono::Utc::now().timestamp();
-        let config = WebDavServerConfig {
-            id: "test-id".to_string(),
-            name: "Test Server".to_string(),
-            url: "https://example.com/webdav".to_string(),
-            username: "testuser".to_string(),
-            use_https: true,
-            timeout: 30,
-            last_test_at: None,
-            last_test_status: "unknown".to_string(),
-            last_test_error: None,
-            server_type: "generic".to_string(),
-            enabled: true,
-            created_at: now,
-            updated_at: now,
-        };
-        debug!(config = ?config, "创建测试配置");
-        config
+        let written = client
+            .download_ranged("/large.bin", &local, 2, || false, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(tokio::fs::read(&local).await.unwrap(), content);
+        assert!(!ranged_download_path(&local).exists());
+
+        ranged.assert_async().await;
+        full.assert_async().await;
+        tokio::fs::remove_file(&local).await.ok();
     }
 
-    /// 创建使用 mock 服务器 URL 的配置
-    fn create_mock_config(url: String) -> WebDavServerConfig {
-        init_test_logging(); // 初始化日志系统
-        let now = chrono::Utc::now().timestamp();
-        WebDavServerConfig {
-            id: "test-id".to_string(),
-            name: "Test Server".to_string(),
-            url,
-            username: "testuser".to_string(),
-            use_https: false,
-            timeout: 5,
-            last_test_at: None,
-            last_test_status: "unknown".to_string(),
-            last_test_error: None,
-            server_type: "generic".to_string(),
-            enabled: true,
-            created_at: now,
-            updated_at: now,
-        }
+    #[tokio::test]
+    async fn test_download_ranged_requires_strong_etag() {
+        let content = vec![5u8; 3 * TRANSFER_CHUNK_SIZE];
+        let mut server = mockito::Server::new_async().await;
+        let _head = server
+            .mock("HEAD", "/large.bin")
+            .with_status(200)
+            .with_header("accept-ranges", "bytes")
+            .with_header("content-length", &content.len().to_string())
+            .with_header("etag", "W/\"weak\"")
+            .create_async()
+            .await;
+        let get = server
+            .mock("GET", "/large.bin")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body(&content)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_ranged_{}", uuid::Uuid::new_v4()));
+
+        client
+            .download_ranged("/large.bin", &local, 4, || false, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(tokio::fs::read(&local).await.unwrap(), content);
+
+        get.assert_async().await;
+        tokio::fs::remove_file(&local).await.ok();
     }
 
-    #[test]
-    fn test_create_client_success() {
-        let config = create_test_config();
-        let password = "test_password".to_string();
+    #[tokio::test]
+    async fn test_download_ranged_falls_back_without_range_support() {
+        let content = vec![7u8; 3 * TRANSFER_CHUNK_SIZE];
+        let mut server = mockito::Server::new_async().await;
+        let _head = server
+            .mock("HEAD", "/large.bin")
+            .with_status(200)
+            .with_header("content-length", &content.len().to_string())
+            .create_async()
+            .await;
+        let get = server
+            .mock("GET", "/large.bin")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body(&content)
+            .expect(1)
+            .create_async()
+            .await;
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_ok());
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_ranged_{}", uuid::Uuid::new_v4()));
 
-        let client = result.unwrap();
-        assert_eq!(client.url(), "https://example.com/webdav");
-        assert_eq!(client.username(), "testuser");
-        assert_eq!(client.timeout(), Duration::from_secs(30));
+        let written = client
+            .download_ranged("/large.bin", &local, 4, || false, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(tokio::fs::read(&local).await.unwrap(), content);
+
+        get.assert_async().await;
+        tokio::fs::remove_file(&local).await.ok();
     }
 
-    #[test]
-    fn test_create_client_with_http() {
-        let mut config = create_test_config();
-        config.url = "http://example.com/webdav".to_string();
-        config.use_https = false;
-        let password = "test_password".to_string();
+    #[tokio::test]
+    async fn test_upload_sends_oc_mtime_for_nextcloud() {
+        let test_file = std::env::temp_dir().join("test_upload_oc_mtime.txt");
+        tokio::fs::write(&test_file, "mtime").await.unwrap();
+        filetime::set_file_mtime(
+            &test_file,
+            filetime::FileTime::from_unix_time(1600000000, 0),
+        )
+        .unwrap();
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_ok());
+        let mut server = mockito::Server::new_async().await;
+        let nextcloud = server
+            .mock("PUT", "/nextcloud.txt")
+            .match_header("X-OC-MTime", "1600000000")
+            .with_status(201)
+            .create_async()
+            .await;
+        let generic = server
+            .mock("PUT", "/generic.txt")
+            .match_header("X-OC-MTime", mockito::Matcher::Missing)
+            .with_status(201)
+            .create_async()
+            .await;
 
-        let client = result.unwrap();
-        assert_eq!(client.url(), "http://example.com/webdav");
+        let mut config = create_mock_config(server.url());
+        config.server_type = "nextcloud".to_string();
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        client
+            .upload_interruptible(&test_file, "/nextcloud.txt", None, || false)
+            .await
+            .unwrap();
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        client.upload(&test_file, "/generic.txt").await.unwrap();
+
+        nextcloud.assert_async().await;
+        generic.assert_async().await;
+        tokio::fs::remove_file(&test_file).await.ok();
     }
 
     #[test]
-    fn test_create_client_empty_password() {
-        let config = create_test_config();
-        let password = "".to_string();
+    fn test_parse_http_date() {
+        assert_eq!(
+            parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(1445412480)
+        );
+        assert_eq!(
+            parse_http_date(" Thu, 01 Jan 1970 00:00:00 +0000 "),
+            Some(0)
+        );
+        assert_eq!(parse_http_date("2015-10-21T07:28:00Z"), None);
+    }
 
-        let result = WebDavClient::new(&config, password);
+    #[tokio::test]
+    async fn test_download_failure_keeps_existing_file() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/broken.txt")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let download_file = std::env::temp_dir().join("test_download_keeps_existing.txt");
+        tokio::fs::write(&download_file, "original").await.unwrap();
+
+        let result = client.download("/broken.txt", &download_file).await;
         assert!(result.is_err());
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Password cannot be empty"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+        // 失败的下载不会覆盖已有文件，也不会遗留临时文件
+        let content = tokio::fs::read_to_string(&download_file).await.unwrap();
+        assert_eq!(content, "original");
+        assert!(!partial_download_path(&download_file).exists());
+
+        tokio::fs::remove_file(&download_file).await.ok();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_interruptible_success() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/chunked.txt")
+            .match_body("chunked content")
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let test_file = std::env::temp_dir().join("test_upload_interruptible.txt");
+        tokio::fs::write(&test_file, b"chunked content")
+            .await
+            .unwrap();
+
+        let bytes = client
+            .upload_interruptible(&test_file, "/chunked.txt", None, || false)
+            .await
+            .unwrap();
+        assert_eq!(bytes, 15);
+
+        tokio::fs::remove_file(&test_file).await.ok();
+        mock.assert_async().await;
     }
 
-    #[test]
-    fn test_create_client_whitespace_password() {
-        let config = create_test_config();
-        let password = "   ".to_string();
+    #[tokio::test]
+    async fn test_upload_with_parents_creates_missing_dirs() {
+        let mut server = mockito::Server::new_async().await;
+        let conflict = server
+            .mock("PUT", "/a/b/c.txt")
+            .with_status(409)
+            .expect(1)
+            .create_async()
+            .await;
+        let created = server
+            .mock("PUT", "/a/b/c.txt")
+            .match_body("nested")
+            .with_status(201)
+            .expect(1)
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("PROPFIND", "/a/b")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _existing = server
+            .mock("PROPFIND", "/a")
+            .with_status(207)
+            .create_async()
+            .await;
+        let mkcol = server
+            .mock("MKCOL", "/a/b")
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let test_file =
+            std::env::temp_dir().join(format!("test_upload_parents_{}.txt", uuid::Uuid::new_v4()));
+        tokio::fs::write(&test_file, b"nested").await.unwrap();
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+        let bytes = client
+            .upload_with_parents(&test_file, "/a/b/c.txt", None, || false, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(bytes, 6);
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Password cannot be empty"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+        tokio::fs::remove_file(&test_file).await.ok();
+        conflict.assert_async().await;
+        mkcol.assert_async().await;
+        created.assert_async().await;
     }
 
-    #[test]
-    fn test_create_client_invalid_config_empty_name() {
-        let mut config = create_test_config();
-        config.name = "".to_string();
-        let password = "test_password".to_string();
+    #[tokio::test]
+    async fn test_upload_with_parents_returns_conflict_when_parents_exist() {
+        let mut server = mockito::Server::new_async().await;
+        let _conflict = server
+            .mock("PUT", "/a/c.txt")
+            .with_status(409)
+            .create_async()
+            .await;
+        let _existing = server
+            .mock("PROPFIND", "/a")
+            .with_status(207)
+            .create_async()
+            .await;
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Invalid server config"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+        let test_file =
+            std::env::temp_dir().join(format!("test_upload_parents_{}.txt", uuid::Uuid::new_v4()));
+        tokio::fs::write(&test_file, b"nested").await.unwrap();
+
+        let result = client
+            .upload_with_parents(&test_file, "/a/c.txt", None, || false, |_, _| {})
+            .await;
+        assert!(matches!(result, Err(SyncError::WebDav(ref msg)) if msg.contains("409")));
+
+        tokio::fs::remove_file(&test_file).await.ok();
     }
 
-    #[test]
-    fn test_create_client_invalid_config_empty_url() {
-        let mut config = create_test_config();
-        config.url = "".to_string();
-        let password = "test_password".to_string();
+    #[tokio::test]
+    async fn test_lock_and_locked_upload() {
+        let mut server = mockito::Server::new_async().await;
+        let lock_mock = server
+            .mock("LOCK", "/locked.txt")
+            .match_header("timeout", "Second-600")
+            .match_body(mockito::Matcher::Regex("LightSync".to_string()))
+            .with_status(200)
+            .with_header("Lock-Token", "<opaquelocktoken:abc-123>")
+            .with_header("Timeout", "Second-300")
+            .create_async()
+            .await;
+        let put_mock = server
+            .mock("PUT", "/locked.txt")
+            .match_header("if", "(<opaquelocktoken:abc-123>)")
+            .with_status(204)
+            .create_async()
+            .await;
+        let unlock_mock = server
+            .mock("UNLOCK", "/locked.txt")
+            .match_header("lock-token", "<opaquelocktoken:abc-123>")
+            .with_status(204)
+            .create_async()
+            .await;
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Invalid server config"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+        let before = chrono::Utc::now().timestamp();
+        let lock = client
+            .lock("/locked.txt", "LightSync", Duration::from_secs(600))
+            .await
+            .unwrap()
+            .expect("server supports locking");
+        assert_eq!(lock.token, "opaquelocktoken:abc-123");
+        // 使用服务器返回的有效期
+        assert!(lock.expires_at >= before + 300 && lock.expires_at < before + 600);
+
+        let test_file = std::env::temp_dir().join("test_locked_upload.txt");
+        tokio::fs::write(&test_file, b"locked").await.unwrap();
+        client
+            .upload_interruptible(&test_file, "/locked.txt", Some(&lock.token), || false)
+            .await
+            .unwrap();
+        client.unlock(&lock).await.unwrap();
+
+        tokio::fs::remove_file(&test_file).await.ok();
+        lock_mock.assert_async().await;
+        put_mock.assert_async().await;
+        unlock_mock.assert_async().await;
     }
 
-    #[test]
-    fn test_create_client_invalid_config_bad_url() {
-        let mut config = create_test_config();
-        config.url = "not-a-valid-url".to_string();
-        let password = "test_password".to_string();
+    #[tokio::test]
+    async fn test_lock_token_from_body() {
+        let mut server = mockito::Server::new_async().await;
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:prop xmlns:D="DAV:"><D:lockdiscovery><D:activelock>
+<D:locktoken><D:href>opaquelocktoken:from-body</D:href></D:locktoken>
+</D:activelock></D:lockdiscovery></D:prop>"#;
+        let _mock = server
+            .mock("LOCK", "/a.txt")
+            .with_status(201)
+            .with_body(body)
+            .create_async()
+            .await;
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Invalid server config"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+        let lock = client
+            .lock("/a.txt", "LightSync", Duration::from_secs(60))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lock.token, "opaquelocktoken:from-body");
     }
 
-    #[test]
-    fn test_create_client_invalid_config_empty_username() {
-        let mut config = create_test_config();
-        config.username = "".to_string();
-        let password = "test_password".to_string();
+    #[tokio::test]
+    async fn test_lock_conflict_and_unsupported() {
+        let mut server = mockito::Server::new_async().await;
+        let _locked = server
+            .mock("LOCK", "/busy.txt")
+            .with_status(423)
+            .create_async()
+            .await;
+        let _unsupported = server
+            .mock("LOCK", "/plain.txt")
+            .with_status(405)
+            .create_async()
+            .await;
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let timeout = Duration::from_secs(60);
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Invalid server config"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+        assert!(matches!(
+            client.lock("/busy.txt", "LightSync", timeout).await,
+            Err(SyncError::Conflict(_))
+        ));
+        assert_eq!(
+            client
+                .lock("/plain.txt", "LightSync", timeout)
+                .await
+                .unwrap(),
+            None
+        );
     }
 
     #[test]
-    fn test_create_client_invalid_config_timeout_too_small() {
-        let mut config = create_test_config();
-        config.timeout = 0;
-        let password = "test_password".to_string();
-
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+    fn test_parse_lock_timeout() {
+        assert_eq!(
+            parse_lock_timeout("Second-600"),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            parse_lock_timeout("Infinite, Second-4100000000"),
+            Some(Duration::from_secs(4_100_000_000))
+        );
+        assert_eq!(parse_lock_timeout("Infinite"), None);
+    }
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Invalid server config"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+    fn marker_property() -> PropertyName {
+        PropertyName::new("https://lightsync.example/ns", "client-id")
     }
 
     #[test]
-    fn test_create_client_invalid_config_timeout_too_large() {
-        let mut config = create_test_config();
-        config.timeout = 301;
-        let password = "test_password".to_string();
-
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_err());
+    fn test_proppatch_body() {
+        let body = proppatch_body(&[
+            PropertyUpdate::Set(marker_property(), "a<b".to_string()),
+            PropertyUpdate::Remove(PropertyName::new("urn:x", "old")),
+        ]);
+        assert!(body.contains(
+            r#"<D:set><D:prop><L:client-id xmlns:L="https://lightsync.example/ns">a&lt;b</L:client-id></D:prop></D:set>"#
+        ));
+        assert!(body.contains(r#"<D:remove><D:prop><L:old xmlns:L="urn:x"/></D:prop></D:remove>"#));
+        assert!(body.ends_with("</D:propertyupdate>"));
+    }
 
-        match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
-                assert!(msg.contains("Invalid server config"));
-            }
-            _ => panic!("Expected ConfigError"),
-        }
+    #[test]
+    fn test_multistatus_codes() {
+        let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/a</d:href>
+<d:propstat><d:prop><x:a/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+<d:propstat><d:prop><x:b/></d:prop><d:status>HTTP/1.1 403 Forbidden</d:status></d:propstat>
+</d:response></d:multistatus>"#;
+        assert_eq!(multistatus_codes(xml), vec![200, 403]);
+        assert!(multistatus_codes("<D:multistatus/>").is_empty());
     }
 
     #[test]
-    fn test_create_client_custom_timeout() {
-        let mut config = create_test_config();
-        config.timeout = 60;
-        let password = "test_password".to_string();
+    fn test_failed_status() {
+        let ok = r#"<D:href>/a</D:href>
+<D:propstat><D:prop/><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+<D:propstat><D:prop><D:getetag/></D:prop><D:status>HTTP/1.1 404 Not Found</D:status></D:propstat>"#;
+        assert_eq!(failed_status(ok), None);
+        assert_eq!(failed_status("<D:href>/a</D:href>"), None);
+        assert_eq!(
+            failed_status("<D:href>/a</D:href><D:status>HTTP/1.1 423 Locked</D:status>"),
+            Some(423)
+        );
+        assert_eq!(
+            failed_status(
+                "<d:propstat><d:prop/><d:status>HTTP/1.1 403 Forbidden</d:status></d:propstat>"
+            ),
+            Some(403)
+        );
+    }
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_ok());
+    #[tokio::test]
+    async fn test_list_detailed_reports_failed_children() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("PROPFIND", "/docs")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:">
+<D:response><D:href>/docs/</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>
+<D:response><D:href>/docs/a.txt</D:href><D:propstat><D:prop><D:getcontentlength>5</D:getcontentlength></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>
+<D:response><D:href>/docs/locked.txt</D:href><D:propstat><D:prop/><D:status>HTTP/1.1 423 Locked</D:status></D:propstat></D:response>
+</D:multistatus>"#,
+            )
+            .create_async()
+            .await;
 
-        let client = result.unwrap();
-        assert_eq!(client.timeout(), Duration::from_secs(60));
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let listing = client.list_detailed("/docs").await.unwrap();
+        assert_eq!(listing.files.len(), 1);
+        assert_eq!(listing.files[0].path, "/docs/a.txt");
+        assert_eq!(
+            listing.failures,
+            vec![RemoteItemError {
+                path: "/docs/locked.txt".to_string(),
+                status: 423
+            }]
+        );
+
+        assert_eq!(client.list("/docs").await.unwrap().len(), 1);
     }
 
     #[test]
-    fn test_create_client_minimum_timeout() {
-        let mut config = create_test_config();
-        config.timeout = 1;
-        let password = "test_password".to_string();
+    fn test_propfind_dialect_values() {
+        for dialect in [
+            PropfindDialect::Standard,
+            PropfindDialect::AllProp,
+            PropfindDialect::Empty,
+        ] {
+            assert_eq!(PropfindDialect::from_config(dialect.as_str()), dialect);
+        }
+        assert_eq!(
+            PropfindDialect::from_config("unknown"),
+            PropfindDialect::Standard
+        );
+        assert_eq!(PropfindDialect::Empty.fallback(), None);
+    }
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_ok());
+    #[tokio::test]
+    async fn test_propfind_dialect_fallback() {
+        let mut server = mockito::Server::new_async().await;
+        let listing = r#"<D:multistatus xmlns:D="DAV:">
+<D:response><D:href>/docs/</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>
+<D:response><D:href>/docs/a.txt</D:href><D:propstat><D:prop><D:getcontentlength>5</D:getcontentlength></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>
+</D:multistatus>"#;
+        let standard = server
+            .mock("PROPFIND", "/docs")
+            .match_body(mockito::Matcher::Regex("getcontentlength".to_string()))
+            .with_status(400)
+            .expect(1)
+            .create_async()
+            .await;
+        let allprop = server
+            .mock("PROPFIND", "/docs")
+            .match_body(mockito::Matcher::Regex("allprop".to_string()))
+            .with_status(422)
+            .expect(1)
+            .create_async()
+            .await;
+        let empty = server
+            .mock("PROPFIND", "/docs")
+            .match_body(mockito::Matcher::Exact(String::new()))
+            .with_status(207)
+            .with_body(listing)
+            .expect(2)
+            .create_async()
+            .await;
 
-        let client = result.unwrap();
-        assert_eq!(client.timeout(), Duration::from_secs(1));
-    }
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-    #[test]
-    fn test_create_client_maximum_timeout() {
-        let mut config = create_test_config();
-        config.timeout = 300;
-        let password = "test_password".to_string();
+        assert_eq!(client.list("/docs").await.unwrap().len(), 1);
+        assert_eq!(client.propfind_dialect(), PropfindDialect::Empty);
 
-        let result = WebDavClient::new(&config, password);
-        assert!(result.is_ok());
+        // 之后的请求直接使用记住的方式
+        assert_eq!(client.clone().list("/docs").await.unwrap().len(), 1);
+        standard.assert_async().await;
+        allprop.assert_async().await;
+        empty.assert_async().await;
 
-        let client = result.unwrap();
-        assert_eq!(client.timeout(), Duration::from_secs(300));
+        // 不支持指定属性的服务器不查询配额
+        assert_eq!(client.quota("/docs").await.unwrap(), RemoteQuota::default());
     }
 
-    // ========== test_connection 方法测试 ==========
-
     #[tokio::test]
-    async fn test_connection_success_generic() {
-        use tracing::info;
-
+    async fn test_propfind_dialect_not_saved_when_all_rejected() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .match_header("depth", "0")
-            .match_header("authorization", mockito::Matcher::Any)
-            .with_status(207) // Multi-Status
-            .with_header("content-type", "application/xml")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+        let _mock = server
+            .mock("PROPFIND", "/docs")
+            .with_status(400)
+            .expect(3)
             .create_async()
             .await;
 
-        info!(mock_server_url = %server.url(), "创建的mock服务器");
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
-        info!(result = ?result, "获取的返回结果");
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "generic");
-        mock.assert_async().await;
+        assert!(client.list("/docs").await.is_err());
+        assert_eq!(client.propfind_dialect(), PropfindDialect::Standard);
     }
 
     #[tokio::test]
-    async fn test_connection_success_nextcloud() {
+    async fn test_delete_multistatus_failures() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .match_header("depth", "0")
+        let _locked = server
+            .mock("DELETE", "/locked")
             .with_status(207)
-            .with_header("server", "Apache/2.4.41 (Ubuntu) Nextcloud")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/locked/a.txt</D:href>
+<D:status>HTTP/1.1 423 Locked</D:status></D:response></D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let _forbidden = server
+            .mock("DELETE", "/forbidden")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/forbidden/a.txt</D:href>
+<D:status>HTTP/1.1 403 Forbidden</D:status></D:response></D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let _ok = server
+            .mock("DELETE", "/ok")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/ok</D:href>
+<D:status>HTTP/1.1 204 No Content</D:status></D:response></D:multistatus>"#,
+            )
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "nextcloud");
-        mock.assert_async().await;
+        assert!(matches!(
+            client.delete("/locked").await,
+            Err(SyncError::Conflict(msg)) if msg.contains("/locked/a.txt")
+        ));
+        assert!(matches!(
+            client.delete("/forbidden").await,
+            Err(SyncError::WebDav(msg)) if msg.contains("/forbidden/a.txt (403)")
+        ));
+        assert!(client.delete("/ok").await.is_ok());
     }
 
     #[tokio::test]
-    async fn test_connection_success_owncloud() {
+    async fn test_proppatch() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .match_header("depth", "0")
+        let applied = server
+            .mock("PROPPATCH", "/folder")
+            .match_body(mockito::Matcher::Regex("client-id".to_string()))
             .with_status(207)
-            .with_header("server", "Apache/2.4.41 (Ubuntu) ownCloud")
-            .with_header("x-oc-version", "10.8.0")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/folder</D:href>
+<D:propstat><D:prop/><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response></D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let _rejected = server
+            .mock("PROPPATCH", "/readonly")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/readonly</D:href>
+<D:propstat><D:prop/><D:status>HTTP/1.1 403 Forbidden</D:status></D:propstat></D:response></D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let _unsupported = server
+            .mock("PROPPATCH", "/plain")
+            .with_status(405)
+            .create_async()
+            .await;
+        let _locked = server
+            .mock("PROPPATCH", "/busy")
+            .with_status(423)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
-
-        let result = client.test_connection().await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "owncloud");
-        mock.assert_async().await;
+        let updates = [PropertyUpdate::Set(marker_property(), "abc".to_string())];
+
+        assert!(client.proppatch("/folder", &updates).await.unwrap());
+        applied.assert_async().await;
+        assert!(!client.proppatch("/readonly", &updates).await.unwrap());
+        assert!(!client.proppatch("/plain", &updates).await.unwrap());
+        assert!(matches!(
+            client.proppatch("/busy", &updates).await,
+            Err(SyncError::Conflict(_))
+        ));
+        assert!(client.proppatch("/folder", &[]).await.unwrap());
+
+        let invalid = [PropertyUpdate::Remove(PropertyName::new("urn:x", "1bad"))];
+        assert!(matches!(
+            client.proppatch("/folder", &invalid).await,
+            Err(SyncError::ValidationError(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_connection_success_apache() {
+    async fn test_download_interruptible_removes_partial_file() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .match_header("depth", "0")
-            .with_status(207)
-            .with_header("server", "Apache/2.4.41 (Ubuntu)")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+        let _mock = server
+            .mock("GET", "/large.bin")
+            .with_status(200)
+            .with_body(vec![0u8; 1024])
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "apache");
-        mock.assert_async().await;
+        let download_file = std::env::temp_dir().join("test_download_interrupted.bin");
+        tokio::fs::write(&download_file, b"previous").await.unwrap();
+
+        let result = client
+            .download_interruptible("/large.bin", &download_file, || true, |_, _| {})
+            .await;
+
+        assert!(matches!(result, Err(SyncError::Interrupted(_))));
+        // 中断后临时文件被清理，原文件保持不变
+        assert!(!partial_download_path(&download_file).exists());
+        assert_eq!(tokio::fs::read(&download_file).await.unwrap(), b"previous");
+
+        tokio::fs::remove_file(&download_file).await.ok();
     }
 
     #[tokio::test]
-    async fn test_connection_success_nginx() {
+    async fn test_download_resumable_appends_to_partial_file() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .match_header("depth", "0")
-            .with_status(207)
-            .with_header("server", "nginx/1.18.0")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+        let resumed = server
+            .mock("GET", "/resume.txt")
+            .match_header("range", "bytes=6-")
+            .match_header("if-range", "\"v1\"")
+            .with_status(206)
+            .with_header("content-range", "bytes 6-10/11")
+            .with_body("world")
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_resume_{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(partial_download_path(&local), b"hello ")
+            .await
+            .unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "nginx");
-        mock.assert_async().await;
+        let reported = AtomicU64::new(0);
+        let written = client
+            .download_resumable(
+                "/resume.txt",
+                &local,
+                "\"v1\"",
+                || false,
+                |sum, total| {
+                    assert_eq!(total, Some(11));
+                    reported.store(sum, Ordering::Relaxed);
+                },
+            )
+            .await
+            .unwrap();
+
+        resumed.assert_async().await;
+        assert_eq!(written, 11);
+        assert_eq!(reported.load(Ordering::Relaxed), 11);
+        assert_eq!(tokio::fs::read(&local).await.unwrap(), b"hello world");
+        assert!(!partial_download_path(&local).exists());
+        tokio::fs::remove_file(&local).await.ok();
     }
 
     #[tokio::test]
-    async fn test_connection_success_with_200_ok() {
+    async fn test_download_resumable_restarts_when_remote_changed() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .match_header("depth", "0")
-            .with_status(200) // Some servers return 200 OK instead of 207
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+        // If-Range 不匹配时服务器返回完整内容
+        let _mock = server
+            .mock("GET", "/resume.txt")
+            .with_status(200)
+            .with_body("new content")
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_resume_{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(partial_download_path(&local), b"stale")
+            .await
+            .unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_ok());
-        mock.assert_async().await;
+        let written = client
+            .download_resumable("/resume.txt", &local, "\"v1\"", || false, |_, _| {})
+            .await
+            .unwrap();
+
+        assert_eq!(written, 11);
+        assert_eq!(tokio::fs::read(&local).await.unwrap(), b"new content");
+        tokio::fs::remove_file(&local).await.ok();
     }
 
     #[tokio::test]
-    async fn test_connection_auth_failure_401() {
+    async fn test_download_resumable_keeps_partial_file_when_interrupted() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
-            .with_status(401)
-            .with_header("www-authenticate", "Basic realm=\"WebDAV\"")
+        let _mock = server
+            .mock("GET", "/large.bin")
+            .with_status(200)
+            .with_body(vec![1u8; 1024])
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
-        let client = WebDavClient::new(&config, "wrong_password".to_string()).unwrap();
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let local = std::env::temp_dir().join(format!("lightsync_resume_{}", uuid::Uuid::new_v4()));
 
-        let result = client.test_connection().await;
-        assert!(result.is_err());
+        let result = client
+            .download_resumable("/large.bin", &local, "\"v1\"", || true, |_, _| {})
+            .await;
 
-        match result.unwrap_err() {
-            SyncError::AuthError(msg) => {
-                assert!(msg.contains("Authentication failed"));
-            }
-            _ => panic!("Expected AuthError"),
-        }
-        mock.assert_async().await;
+        assert!(matches!(result, Err(SyncError::Interrupted(_))));
+        assert!(!local.exists());
+        let part = partial_download_path(&local);
+        assert!(tokio::fs::metadata(&part).await.unwrap().len() > 0);
+        tokio::fs::remove_file(&part).await.ok();
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(content_range_start(&headers), None);
+        headers.insert(
+            "content-range",
+            HeaderValue::from_static("bytes 100-199/200"),
+        );
+        assert_eq!(content_range_start(&headers), Some(100));
+        headers.insert("content-range", HeaderValue::from_static("bytes */200"));
+        assert_eq!(content_range_start(&headers), None);
     }
 
     #[tokio::test]
-    async fn test_connection_forbidden_403() {
+    async fn test_cancelled_client_aborts_requests() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
-            .mock("PROPFIND", "/")
-            .with_status(403)
+            .mock("DELETE", "/test.txt")
+            .with_status(204)
+            .expect(0)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
-        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let token = CancellationToken::new();
+        let client = WebDavClient::new(&config, "password".to_string())
+            .unwrap()
+            .with_cancellation(token.clone());
+        token.cancel();
 
-        let result = client.test_connection().await;
-        assert!(result.is_err());
+        let result = client.delete("/test.txt").await;
 
-        match result.unwrap_err() {
-            SyncError::AuthError(msg) => {
-                assert!(msg.contains("Access forbidden"));
-            }
-            _ => panic!("Expected AuthError"),
-        }
+        assert!(matches!(result, Err(SyncError::Cancelled(_))));
         mock.assert_async().await;
     }
 
+    #[test]
+    fn test_remote_checksum_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-length", HeaderValue::from_static("42"));
+        headers.insert("etag", HeaderValue::from_static("\"abc123\""));
+        headers.insert(
+            "oc-checksum",
+            HeaderValue::from_static("SHA1:AA11 md5:bb22"),
+        );
+
+        let checksum = RemoteChecksum::from_headers(&headers);
+
+        assert_eq!(checksum.size, Some(42));
+        assert_eq!(checksum.etag.as_deref(), Some("abc123"));
+        assert_eq!(
+            checksum.checksums,
+            vec![
+                ("SHA1".to_string(), "aa11".to_string()),
+                ("MD5".to_string(), "bb22".to_string()),
+            ]
+        );
+
+        headers.insert("etag", HeaderValue::from_static("W/\"weak\""));
+        assert_eq!(RemoteChecksum::from_headers(&headers).etag, None);
+    }
+
+    #[test]
+    fn test_cloud_location() {
+        let location =
+            CloudLocation::parse("https://host/nc/remote.php/dav/files/alice/My%20Photos/")
+                .unwrap();
+        assert_eq!(location.base_url, "https://host/nc");
+        assert_eq!(location.files_root, "/My Photos");
+        assert_eq!(location.user_path("/"), "/My Photos");
+        assert_eq!(location.user_path("/2024/a.jpg"), "/My Photos/2024/a.jpg");
+        assert_eq!(
+            location.remote_path("/My Photos/2024/a.jpg").as_deref(),
+            Some("/2024/a.jpg")
+        );
+        assert_eq!(location.remote_path("/My Photos").as_deref(), Some("/"));
+        assert_eq!(location.remote_path("/My Photos 2/a.jpg"), None);
+
+        let location = CloudLocation::parse("https://host/remote.php/webdav").unwrap();
+        assert_eq!(location.base_url, "https://host");
+        assert_eq!(location.files_root, "");
+        assert_eq!(location.user_path("/"), "/");
+        assert_eq!(location.remote_path("/docs").as_deref(), Some("/docs"));
+
+        assert_eq!(CloudLocation::parse("https://host/dav/"), None);
+    }
+
+    #[test]
+    fn test_nextcloud_preview_url() {
+        assert_eq!(
+            nextcloud_preview_url(
+                "https://host/remote.php/dav/files/alice/Photos",
+                "/a b.jpg",
+                256
+            )
+            .as_deref(),
+            Some(
+                "https://host/index.php/core/preview.png?file=%2FPhotos%2Fa+b.jpg&x=256&y=256&a=1"
+            )
+        );
+        assert_eq!(
+            nextcloud_preview_url("https://host/nc/remote.php/webdav/", "/a.png", 64).as_deref(),
+            Some("https://host/nc/index.php/core/preview.png?file=%2Fa.png&x=64&y=64&a=1")
+        );
+        assert_eq!(
+            nextcloud_preview_url("https://host/dav", "/a.png", 64),
+            None
+        );
+    }
+
     #[tokio::test]
-    async fn test_connection_not_found_404() {
+    async fn test_preview_falls_back_when_unavailable() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/")
+        let found = server
+            .mock("GET", "/index.php/core/preview.png")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "file".to_string(),
+                "/a.jpg".to_string(),
+            ))
+            .with_status(200)
+            .with_body("png")
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/index.php/core/preview.png")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "file".to_string(),
+                "/b.txt".to_string(),
+            ))
             .with_status(404)
             .create_async()
             .await;
 
-        let config = create_mock_config(server.url());
+        let config = create_mock_config(format!("{}/remote.php/webdav", server.url()));
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_err());
-
-        match result.unwrap_err() {
-            SyncError::WebDav(msg) => {
-                assert!(msg.contains("404"));
-            }
-            _ => panic!("Expected WebDav error"),
-        }
-        mock.assert_async().await;
+        assert_eq!(
+            client.preview("/a.jpg", 128).await.unwrap().as_deref(),
+            Some(&b"png"[..])
+        );
+        assert_eq!(client.preview("/b.txt", 128).await.unwrap(), None);
+        found.assert_async().await;
+        missing.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_connection_server_error_500() {
+    async fn test_checksum_reads_head_headers() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
-            .mock("PROPFIND", "/")
-            .with_status(500)
+            .mock("HEAD", "/test.txt")
+            .with_status(200)
+            .with_header("etag", "\"etag-1\"")
+            .with_header("oc-checksum", "SHA256:ff00")
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_err());
+        let checksum = client.checksum("/test.txt").await.unwrap();
 
-        match result.unwrap_err() {
-            SyncError::WebDav(msg) => {
-                assert!(msg.contains("500"));
-            }
-            _ => panic!("Expected WebDav error"),
-        }
+        assert_eq!(checksum.etag.as_deref(), Some("etag-1"));
+        assert_eq!(
+            checksum.checksums,
+            vec![("SHA256".to_string(), "ff00".to_string())]
+        );
         mock.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_connection_network_error() {
-        // 使用一个不存在的地址来模拟网络错误
-        let mut config = create_test_config();
-        config.url = "http://localhost:1".to_string(); // 不太可能有服务在这个端口
-        config.timeout = 1; // 短超时
-        config.use_https = false;
-
-        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
-
-        let result = client.test_connection().await;
-        assert!(result.is_err());
-
-        match result.unwrap_err() {
-            SyncError::Network(_) => {
-                // 预期的网络错误
-            }
-            _ => panic!("Expected Network error"),
-        }
-    }
-
-    #[tokio::test]
-    async fn test_detect_server_type_with_x_powered_by() {
+    async fn test_delete_file_success() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
-            .mock("PROPFIND", "/")
-            .with_status(207)
-            .with_header("x-powered-by", "Nextcloud")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .mock("DELETE", "/test.txt")
+            .with_status(204) // No Content
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
+        let result = client.delete("/test.txt").await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "nextcloud");
+
         mock.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_detect_server_type_with_x_oc_version() {
+    async fn test_delete_file_not_found() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
-            .mock("PROPFIND", "/")
-            .with_status(207)
-            .with_header("x-oc-version", "10.8.0")
-            .with_body(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"></d:multistatus>"#)
+            .mock("DELETE", "/nonexistent.txt")
+            .with_status(404)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.test_connection().await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "owncloud");
+        let result = client.delete("/nonexistent.txt").await;
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            SyncError::NotFound(_) => {
+                // 预期的 NotFound 错误
+            }
+            _ => panic!("Expected NotFound error"),
+        }
+
         mock.assert_async().await;
     }
 
-    // ========== 文件操作方法测试 ==========
-
     #[tokio::test]
-    async fn test_list_files_success() {
-        let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("PROPFIND", "/documents")
-            .match_header("depth", "1")
-            .with_status(207)
-            .with_body(
-                r#"<?xml version="1.0"?>
-                <D:multistatus xmlns:D="DAV:">
-                    <D:response>
-                        <D:href>/documents/</D:href>
-                        <D:propstat>
-                            <D:prop>
-                                <D:resourcetype><D:collection/></D:resourcetype>
-                            </D:prop>
-                        </D:propstat>
-                    </D:response>
-                    <D:response>
-                        <D:href>/documents/file1.txt</D:href>
-                        <D:propstat>
-                            <D:prop>
-                                <D:resourcetype/>
-                                <D:getcontentlength>1024</D:getcontentlength>
-                            </D:prop>
-                        </D:propstat>
-                    </D:response>
-                    <D:response>
-                        <D:href>/documents/folder1/</D:href>
-                        <D:propstat>
-                            <D:prop>
-                                <D:resourcetype><D:collection/></D:resourcetype>
-                            </D:prop>
-                        </D:propstat>
-                    </D:response>
-                </D:multistatus>"#,
-            )
+    async fn test_copy_file() {
+        let mut server = mockito::Server::new_async().await;
+        let destination = format!("{}/backup/a%20b.txt", server.url());
+        let mock = server
+            .mock("COPY", "/docs/a.txt")
+            .match_header("destination", destination.as_str())
+            .match_header("overwrite", "F")
+            .with_status(201)
+            .create_async()
+            .await;
+        let exists = server
+            .mock("COPY", "/docs/b.txt")
+            .with_status(412)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.list("/documents").await;
-        assert!(result.is_ok());
-
-        let files = result.unwrap();
-        assert_eq!(files.len(), 2); // 不包括当前目录本身
-
-        // 检查文件
-        let file = files.iter().find(|f| f.name == "file1.txt").unwrap();
-        assert!(!file.is_directory);
-        assert_eq!(file.size, 1024);
-
-        // 检查文件夹
-        let folder = files.iter().find(|f| f.name == "folder1").unwrap();
-        assert!(folder.is_directory);
-        assert_eq!(folder.size, 0);
+        assert!(client
+            .copy("/docs/a.txt", "/backup/a b.txt", false)
+            .await
+            .unwrap());
+        assert!(!client
+            .copy("/docs/b.txt", "/backup/b.txt", false)
+            .await
+            .unwrap());
 
         mock.assert_async().await;
+        exists.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_list_files_empty_directory() {
+    async fn test_move_file() {
         let mut server = mockito::Server::new_async().await;
+        let destination = format!("{}/docs/renamed.iso", server.url());
         let mock = server
-            .mock("PROPFIND", "/empty")
-            .match_header("depth", "1")
-            .with_status(207)
-            .with_body(
-                r#"<?xml version="1.0"?>
-                <D:multistatus xmlns:D="DAV:">
-                    <D:response>
-                        <D:href>/empty/</D:href>
-                        <D:propstat>
-                            <D:prop>
-                                <D:resourcetype><D:collection/></D:resourcetype>
-                            </D:prop>
-                        </D:propstat>
-                    </D:response>
-                </D:multistatus>"#,
-            )
+            .mock("MOVE", "/docs/disk.iso")
+            .match_header("destination", destination.as_str())
+            .match_header("overwrite", "F")
+            .with_status(201)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.list("/empty").await;
-        assert!(result.is_ok());
-
-        let files = result.unwrap();
-        assert_eq!(files.len(), 0);
+        assert!(client
+            .move_to("/docs/disk.iso", "/docs/renamed.iso", false)
+            .await
+            .unwrap());
 
         mock.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_upload_file_success() {
+    async fn test_mkdir_success() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
-            .mock("PUT", "/test.txt")
+            .mock("MKCOL", "/new_folder")
             .with_status(201) // Created
             .create_async()
             .await;
@@ -1749,188 +5888,305 @@ mod tests {
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        // 创建临时测试文件
-        let temp_dir = std::env::temp_dir();
-        let test_file = temp_dir.join("test_upload.txt");
-        tokio::fs::write(&test_file, b"test content").await.unwrap();
-
-        let result = client.upload(&test_file, "/test.txt").await;
+        let result = client.mkdir("/new_folder").await;
         assert!(result.is_ok());
 
-        // 清理
-        tokio::fs::remove_file(&test_file).await.ok();
-
         mock.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_upload_file_not_found() {
+    async fn test_mkdir_already_exists() {
         let mut server = mockito::Server::new_async().await;
-        let _mock = server
-            .mock("PUT", "/test.txt")
-            .with_status(201)
+        let mock = server
+            .mock("MKCOL", "/existing_folder")
+            .with_status(405) // Method Not Allowed (folder already exists)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        // 尝试上传不存在的文件
-        let result = client
-            .upload(Path::new("/nonexistent/file.txt"), "/test.txt")
-            .await;
+        let result = client.mkdir("/existing_folder").await;
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            SyncError::Io(_) => {
-                // 预期的 IO 错误
+            SyncError::WebDav(_) => {
+                // 预期的 WebDav 错误
             }
-            _ => panic!("Expected Io error"),
+            _ => panic!("Expected WebDav error"),
         }
+
+        mock.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_download_file_success() {
+    async fn test_exists() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("GET", "/test.txt")
-            .with_status(200)
-            .with_body("downloaded content")
+        let found = server
+            .mock("PROPFIND", "/documents")
+            .match_header("depth", "0")
+            .with_status(207)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("PROPFIND", "/missing")
+            .with_status(404)
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        // 创建临时下载路径
-        let temp_dir = std::env::temp_dir();
-        let download_file = temp_dir.join("test_download.txt");
+        assert!(client.exists("/documents").await.unwrap());
+        assert!(!client.exists("/missing").await.unwrap());
 
-        let result = client.download("/test.txt", &download_file).await;
-        assert!(result.is_ok());
+        found.assert_async().await;
+        missing.assert_async().await;
+    }
 
-        // 验证文件内容
-        let content = tokio::fs::read_to_string(&download_file).await.unwrap();
-        assert_eq!(content, "downloaded content");
+    #[tokio::test]
+    async fn test_delete_recursive() {
+        let mut server = mockito::Server::new_async().await;
+        let _is_dir = server
+            .mock("PROPFIND", "/photos")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/photos/</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat></D:response></D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let _listing = server
+            .mock("PROPFIND", "/photos")
+            .match_header("depth", "1")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:">
+                    <D:response><D:href>/photos/</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat></D:response>
+                    <D:response><D:href>/photos/.DS_Store</D:href><D:propstat><D:prop><D:resourcetype/></D:prop></D:propstat></D:response>
+                </D:multistatus>"#,
+            )
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/photos")
+            .match_header("depth", "infinity")
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+        let _is_file = server
+            .mock("PROPFIND", "/a.txt")
+            .with_status(207)
+            .with_body(
+                r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/a.txt</D:href><D:propstat><D:prop><D:resourcetype/></D:prop></D:propstat></D:response></D:multistatus>"#,
+            )
+            .create_async()
+            .await;
 
-        // 清理
-        tokio::fs::remove_file(&download_file).await.ok();
+        let config = create_mock_config(server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        mock.assert_async().await;
+        assert!(matches!(
+            client.delete_recursive("/photos", false).await,
+            Err(SyncError::Conflict(_))
+        ));
+        assert!(matches!(
+            client.delete_recursive("/a.txt", true).await,
+            Err(SyncError::ValidationError(_))
+        ));
+        client.delete_recursive("/photos", true).await.unwrap();
+
+        delete.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_download_file_not_found() {
+    async fn test_mkdir_all_creates_missing_segments() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("GET", "/nonexistent.txt")
+        let existing = server
+            .mock("PROPFIND", "/a")
+            .with_status(207)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("PROPFIND", "/a/b")
             .with_status(404)
             .create_async()
             .await;
+        let mkcol = server
+            .mock("MKCOL", "/a/b")
+            .with_status(201)
+            .create_async()
+            .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let temp_dir = std::env::temp_dir();
-        let download_file = temp_dir.join("test_download_404.txt");
-
-        let result = client.download("/nonexistent.txt", &download_file).await;
-        assert!(result.is_err());
-
-        match result.unwrap_err() {
-            SyncError::NotFound(_) => {
-                // 预期的 NotFound 错误
-            }
-            _ => panic!("Expected NotFound error"),
-        }
+        client.mkdir_all("/a/b/").await.unwrap();
 
-        mock.assert_async().await;
+        existing.assert_async().await;
+        missing.assert_async().await;
+        mkcol.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_delete_file_success() {
+    async fn test_sync_collection_parses_changes() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
-            .mock("DELETE", "/test.txt")
-            .with_status(204) // No Content
+            .mock("REPORT", "/documents")
+            .match_body(mockito::Matcher::Regex(
+                "<D:sync-token>token-1</D:sync-token>".to_string(),
+            ))
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/documents/new.txt</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:resourcetype/>
+                                <D:getcontentlength>42</D:getcontentlength>
+                            </D:prop>
+                            <D:status>HTTP/1.1 200 OK</D:status>
+                        </D:propstat>
+                    </D:response>
+                    <D:response>
+                        <D:href>/documents/old.txt</D:href>
+                        <D:status>HTTP/1.1 404 Not Found</D:status>
+                    </D:response>
+                    <D:sync-token>token-2</D:sync-token>
+                </D:multistatus>"#,
+            )
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.delete("/test.txt").await;
-        assert!(result.is_ok());
+        let result = client
+            .sync_collection("/documents", Some("token-1"))
+            .await
+            .unwrap()
+            .unwrap();
 
         mock.assert_async().await;
+        assert_eq!(result.sync_token, "token-2");
+        assert_eq!(result.changed.len(), 1);
+        assert_eq!(result.changed[0].name, "new.txt");
+        assert_eq!(result.changed[0].size, 42);
+        assert_eq!(result.removed, vec!["/documents/old.txt".to_string()]);
     }
 
     #[tokio::test]
-    async fn test_delete_file_not_found() {
+    async fn test_sync_collection_invalid_token() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("DELETE", "/nonexistent.txt")
-            .with_status(404)
+        server
+            .mock("REPORT", "/documents")
+            .with_status(403)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:error xmlns:D="DAV:"><D:valid-sync-token/></D:error>"#,
+            )
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.delete("/nonexistent.txt").await;
-        assert!(result.is_err());
-
-        match result.unwrap_err() {
-            SyncError::NotFound(_) => {
-                // 预期的 NotFound 错误
-            }
-            _ => panic!("Expected NotFound error"),
-        }
-
-        mock.assert_async().await;
+        let result = client
+            .sync_collection("/documents", Some("expired"))
+            .await
+            .unwrap();
+        assert!(result.is_none());
     }
 
     #[tokio::test]
-    async fn test_mkdir_success() {
+    async fn test_supports_sync_collection() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("MKCOL", "/new_folder")
-            .with_status(201) // Created
+        server
+            .mock("PROPFIND", "/documents")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <D:multistatus xmlns:D="DAV:">
+                    <D:response>
+                        <D:href>/documents/</D:href>
+                        <D:propstat>
+                            <D:prop>
+                                <D:supported-report-set>
+                                    <D:supported-report><D:report><D:sync-collection/></D:report></D:supported-report>
+                                </D:supported-report-set>
+                            </D:prop>
+                        </D:propstat>
+                    </D:response>
+                </D:multistatus>"#,
+            )
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.mkdir("/new_folder").await;
-        assert!(result.is_ok());
-
-        mock.assert_async().await;
+        assert!(client.supports_sync_collection("/documents").await.unwrap());
     }
 
     #[tokio::test]
-    async fn test_mkdir_already_exists() {
+    async fn test_quota() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("MKCOL", "/existing_folder")
-            .with_status(405) // Method Not Allowed (folder already exists)
+        server
+            .mock("PROPFIND", "/")
+            .match_header("depth", "0")
+            .with_status(207)
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <d:multistatus xmlns:d="DAV:">
+                    <d:response>
+                        <d:href>/</d:href>
+                        <d:propstat>
+                            <d:prop>
+                                <d:quota-available-bytes>-3</d:quota-available-bytes>
+                                <d:quota-used-bytes>1024</d:quota-used-bytes>
+                            </d:prop>
+                        </d:propstat>
+                    </d:response>
+                </d:multistatus>"#,
+            )
             .create_async()
             .await;
 
         let config = create_mock_config(server.url());
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        let result = client.mkdir("/existing_folder").await;
-        assert!(result.is_err());
+        let quota = client.quota("").await.unwrap();
+        assert_eq!(quota.available_bytes, None);
+        assert_eq!(quota.used_bytes, Some(1024));
+    }
 
-        match result.unwrap_err() {
-            SyncError::WebDav(_) => {
-                // 预期的 WebDav 错误
-            }
-            _ => panic!("Expected WebDav error"),
-        }
+    #[test]
+    fn test_relative_path() {
+        let mut config = create_test_config();
+        config.url = "https://example.com/remote.php/dav/files/alice/".to_string();
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        mock.assert_async().await;
+        assert_eq!(
+            client.relative_path("/remote.php/dav/files/alice/docs/a.txt"),
+            "/docs/a.txt"
+        );
+        assert_eq!(client.relative_path("/other/path"), "/other/path");
+    }
+
+    #[test]
+    fn test_relative_path_with_encoded_base_url() {
+        let mut config = create_test_config();
+        config.url = "https://example.com/dav/%E5%BC%A0%E4%B8%89".to_string();
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        assert_eq!(
+            client.relative_path("/dav/张三/docs/a b.txt"),
+            "/docs/a b.txt"
+        );
     }
 
     #[tokio::test]
@@ -2740,11 +6996,18 @@ mod tests {
         let config = create_test_config();
         let client = WebDavClient::new(&config, "password".to_string()).unwrap();
 
-        // 注意：实际使用中，特殊字符应该被 URL 编码
-        // 这里只测试路径拼接逻辑
+        // 每个路径分段都会被百分号编码
         assert_eq!(
             client.build_url("/documents/file with spaces.txt"),
-            "https://example.com/webdav/documents/file with spaces.txt"
+            "https://example.com/webdav/documents/file%20with%20spaces.txt"
+        );
+        assert_eq!(
+            client.build_url("/docs/a#1?.txt"),
+            "https://example.com/webdav/docs/a%231%3F.txt"
+        );
+        assert_eq!(
+            client.build_url("/文档/报告.pdf"),
+            "https://example.com/webdav/%E6%96%87%E6%A1%A3/%E6%8A%A5%E5%91%8A.pdf"
         );
     }
 
@@ -2826,10 +7089,10 @@ mod tests {
         assert!(result.is_err(), "Should reject empty password");
 
         match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
+            SyncError::ValidationError(msg) => {
                 assert!(msg.contains("Password cannot be empty"));
             }
-            _ => panic!("Expected ConfigError"),
+            _ => panic!("Expected ValidationError"),
         }
     }
 
@@ -2904,10 +7167,10 @@ mod tests {
         assert!(result.is_err(), "Should reject zero timeout");
 
         match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
+            SyncError::ValidationError(msg) => {
                 assert!(msg.contains("Invalid server config"));
             }
-            _ => panic!("Expected ConfigError"),
+            _ => panic!("Expected ValidationError"),
         }
     }
 
@@ -2920,10 +7183,10 @@ mod tests {
         assert!(result.is_err(), "Should reject timeout > 300 seconds");
 
         match result.unwrap_err() {
-            SyncError::ConfigError(msg) => {
+            SyncError::ValidationError(msg) => {
                 assert!(msg.contains("Invalid server config"));
             }
-            _ => panic!("Expected ConfigError"),
+            _ => panic!("Expected ValidationError"),
         }
     }
 
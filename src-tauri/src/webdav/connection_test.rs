@@ -0,0 +1,449 @@
+/// 分阶段连接测试
+///
+/// 把连接测试拆成多个阶段依次执行，每个阶段结束后立即回调，供前端展示逐步的连接向导：
+/// 1. DNS 解析服务器地址
+/// 2. 建立 TCP 连接
+/// 3. TLS 握手（http 地址跳过；固定了证书指纹时校验指纹）
+/// 4. 认证（PROPFIND 返回 401 / 403 时失败）
+/// 5. 确认服务器支持 WebDAV
+/// 6. 检测服务器类型
+/// 7. 查询存储配额（可选，服务器不支持时标记为跳过，不影响测试结果）
+///
+/// 前 6 个阶段任一失败即停止，后续阶段不再执行
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ErrorCode;
+use crate::webdav::client::{RemoteQuota, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 连接测试阶段事件名称
+pub const CONNECTION_TEST_STAGE_EVENT: &str = "webdav://connection-test-stage";
+
+/// 连接测试阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionStage {
+    /// 已解析服务器地址
+    DnsResolved,
+    /// 已建立 TCP 连接
+    TcpConnected,
+    /// TLS 握手成功
+    TlsOk,
+    /// 认证成功
+    AuthOk,
+    /// 服务器支持 WebDAV
+    WebdavOk,
+    /// 已检测服务器类型
+    ServerTypeDetected,
+    /// 已获取存储配额
+    QuotaFetched,
+}
+
+/// 阶段结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageStatus {
+    /// 通过
+    Passed,
+    /// 失败
+    Failed,
+    /// 跳过
+    Skipped,
+}
+
+/// 连接测试阶段事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStageEvent {
+    /// 服务器 ID
+    pub server_id: String,
+    /// 阶段
+    pub stage: ConnectionStage,
+    /// 阶段结果
+    pub status: StageStatus,
+    /// 阶段说明（解析到的地址、服务器类型等），失败时为错误信息
+    pub detail: Option<String>,
+    /// 错误码（仅失败或因错误跳过时存在）
+    pub error_code: Option<ErrorCode>,
+    /// 阶段耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 分阶段连接测试结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StagedTestOutcome {
+    /// 检测到的服务器类型
+    pub server_type: String,
+    /// 存储配额（服务器不支持或查询失败时为 None）
+    pub quota: Option<RemoteQuota>,
+}
+
+/// 执行分阶段连接测试
+///
+/// # 参数
+/// - server_id: 服务器 ID（写入阶段事件）
+/// - client: 待测试服务器的 WebDAV 客户端
+/// - accept_invalid_certs: TLS 握手时是否接受无效证书
+/// - on_stage: 每个阶段结束后的回调
+///
+/// # 返回
+/// - Ok(StagedTestOutcome): 所有必需阶段均通过
+/// - Err(SyncError): 第一个失败阶段的错误
+pub async fn run<F>(
+    server_id: &str,
+    client: &WebDavClient,
+    accept_invalid_certs: bool,
+    on_stage: F,
+) -> Result<StagedTestOutcome>
+where
+    F: FnMut(ConnectionStageEvent),
+{
+    let mut reporter = StageReporter {
+        server_id: server_id.to_string(),
+        on_stage,
+    };
+
+    let url = url::Url::parse(client.url())
+        .map_err(|e| SyncError::ConfigError(format!("Invalid URL format: {}", e)))?;
+    let host = url
+        .host()
+        .ok_or_else(|| SyncError::ConfigError(format!("URL has no host: {}", url)))?
+        .to_owned();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let timeout = client.timeout();
+
+    let addrs = reporter
+        .stage(
+            ConnectionStage::DnsResolved,
+            resolve(host, port, timeout),
+            |addrs| {
+                Some(
+                    addrs
+                        .iter()
+                        .map(|addr| addr.ip().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            },
+        )
+        .await?;
+
+    reporter
+        .stage(
+            ConnectionStage::TcpConnected,
+            connect(&addrs, timeout),
+            |addr| Some(addr.to_string()),
+        )
+        .await?;
+
+    if url.scheme() == "https" {
+        reporter
+            .stage(
+                ConnectionStage::TlsOk,
+                handshake(client, accept_invalid_certs),
+                |_| None,
+            )
+            .await?;
+    } else {
+        reporter.skip(ConnectionStage::TlsOk, Some("Plain HTTP".to_string()), None);
+    }
+
+    let started = Instant::now();
+    let server_type = match client.test_connection().await {
+        Ok(server_type) => server_type,
+        Err(e @ SyncError::WebDav(_)) => {
+            reporter.passed(ConnectionStage::AuthOk, None, started);
+            reporter.failed(ConnectionStage::WebdavOk, &e, started);
+            return Err(e);
+        }
+        Err(e) => {
+            reporter.failed(ConnectionStage::AuthOk, &e, started);
+            return Err(e);
+        }
+    };
+    reporter.passed(ConnectionStage::AuthOk, None, started);
+    reporter.passed(ConnectionStage::WebdavOk, None, started);
+    reporter.passed(
+        ConnectionStage::ServerTypeDetected,
+        Some(server_type.clone()),
+        started,
+    );
+
+    let started = Instant::now();
+    let quota = match client.quota("").await {
+        Ok(quota) if quota == RemoteQuota::default() => {
+            reporter.skip(
+                ConnectionStage::QuotaFetched,
+                Some("Server does not report quota".to_string()),
+                None,
+            );
+            None
+        }
+        Ok(quota) => {
+            let detail = quota
+                .available_bytes
+                .map(|bytes| format!("{} bytes available", bytes));
+            reporter.passed(ConnectionStage::QuotaFetched, detail, started);
+            Some(quota)
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "查询存储配额失败");
+            reporter.skip(ConnectionStage::QuotaFetched, Some(e.to_string()), Some(&e));
+            None
+        }
+    };
+
+    Ok(StagedTestOutcome { server_type, quota })
+}
+
+/// 阶段事件回调封装
+struct StageReporter<F> {
+    server_id: String,
+    on_stage: F,
+}
+
+impl<F: FnMut(ConnectionStageEvent)> StageReporter<F> {
+    /// 执行一个阶段，结束后发送通过或失败事件
+    async fn stage<T>(
+        &mut self,
+        stage: ConnectionStage,
+        future: impl Future<Output = Result<T>>,
+        detail: impl FnOnce(&T) -> Option<String>,
+    ) -> Result<T> {
+        let started = Instant::now();
+        match future.await {
+            Ok(value) => {
+                self.passed(stage, detail(&value), started);
+                Ok(value)
+            }
+            Err(e) => {
+                self.failed(stage, &e, started);
+                Err(e)
+            }
+        }
+    }
+
+    fn passed(&mut self, stage: ConnectionStage, detail: Option<String>, started: Instant) {
+        self.emit(stage, StageStatus::Passed, detail, None, started);
+    }
+
+    fn failed(&mut self, stage: ConnectionStage, error: &SyncError, started: Instant) {
+        self.emit(
+            stage,
+            StageStatus::Failed,
+            Some(error.to_string()),
+            Some(error.code()),
+            started,
+        );
+    }
+
+    fn skip(&mut self, stage: ConnectionStage, detail: Option<String>, error: Option<&SyncError>) {
+        self.emit(
+            stage,
+            StageStatus::Skipped,
+            detail,
+            error.map(SyncError::code),
+            Instant::now(),
+        );
+    }
+
+    fn emit(
+        &mut self,
+        stage: ConnectionStage,
+        status: StageStatus,
+        detail: Option<String>,
+        error_code: Option<ErrorCode>,
+        started: Instant,
+    ) {
+        (self.on_stage)(ConnectionStageEvent {
+            server_id: self.server_id.clone(),
+            stage,
+            status,
+            detail,
+            error_code,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+/// 解析服务器地址（IP 地址直接返回）
+async fn resolve(host: url::Host, port: u16, timeout: Duration) -> Result<Vec<SocketAddr>> {
+    let domain = match host {
+        url::Host::Domain(domain) => domain,
+        url::Host::Ipv4(ip) => return Ok(vec![SocketAddr::new(ip.into(), port)]),
+        url::Host::Ipv6(ip) => return Ok(vec![SocketAddr::new(ip.into(), port)]),
+    };
+
+    let addrs = tokio::time::timeout(timeout, tokio::net::lookup_host((domain.as_str(), port)))
+        .await
+        .map_err(|_| {
+            SyncError::Network(format!(
+                "Timed out resolving server address '{}' after {} seconds",
+                domain,
+                timeout.as_secs()
+            ))
+        })?
+        .map_err(|e| {
+            SyncError::Network(format!(
+                "Failed to resolve server address '{}': {}",
+                domain, e
+            ))
+        })?
+        .collect::<Vec<_>>();
+
+    if addrs.is_empty() {
+        return Err(SyncError::Network(format!(
+            "Server address '{}' did not resolve to any IP address",
+            domain
+        )));
+    }
+    Ok(addrs)
+}
+
+/// 依次尝试解析到的地址，返回第一个连接成功的地址
+async fn connect(addrs: &[SocketAddr], timeout: Duration) -> Result<SocketAddr> {
+    let mut last_error = None;
+    for addr in addrs {
+        match tokio::time::timeout(timeout, tokio::net::TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => return Ok(*addr),
+            Ok(Err(e)) => last_error = Some(e.to_string()),
+            Err(_) => last_error = Some(format!("timed out after {} seconds", timeout.as_secs())),
+        }
+    }
+
+    Err(SyncError::Network(format!(
+        "Failed to connect to {}: {}",
+        addrs
+            .iter()
+            .map(SocketAddr::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+        last_error.unwrap_or_default()
+    )))
+}
+
+/// TLS 握手
+///
+/// 固定了证书指纹时校验指纹；否则发送不带认证信息的 HEAD 请求，
+/// 收到任何 HTTP 响应即说明握手成功
+async fn handshake(client: &WebDavClient, accept_invalid_certs: bool) -> Result<()> {
+    if client.has_pinned_certificate() {
+        return client.verify_certificate().await;
+    }
+
+    let http = reqwest::Client::builder()
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .timeout(client.timeout())
+        .build()
+        .map_err(|e| SyncError::Network(format!("Failed to create HTTP client: {}", e)))?;
+    http.head(client.url()).send().await.map_err(|e| {
+        SyncError::Network(format!(
+            "TLS handshake with '{}' failed: {}",
+            client.url(),
+            e
+        ))
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webdav::mock_server_config;
+
+    async fn run_stages(
+        server: &mockito::Server,
+    ) -> (Result<StagedTestOutcome>, Vec<ConnectionStageEvent>) {
+        let config = mock_server_config(&server.url());
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let mut events = Vec::new();
+        let result = run("test-id", &client, false, |event| events.push(event)).await;
+        (result, events)
+    }
+
+    #[tokio::test]
+    async fn test_all_stages_pass() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("PROPFIND", "/")
+            .with_status(207)
+            .with_header("server", "Nextcloud")
+            .with_body(
+                r#"<?xml version="1.0"?>
+                <d:multistatus xmlns:d="DAV:">
+                    <d:response>
+                        <d:href>/</d:href>
+                        <d:propstat>
+                            <d:prop>
+                                <d:quota-available-bytes>2048</d:quota-available-bytes>
+                                <d:quota-used-bytes>1024</d:quota-used-bytes>
+                            </d:prop>
+                        </d:propstat>
+                    </d:response>
+                </d:multistatus>"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let (result, events) = run_stages(&server).await;
+        let outcome = result.unwrap();
+        assert_eq!(outcome.server_type, "nextcloud");
+        assert_eq!(outcome.quota.unwrap().available_bytes, Some(2048));
+
+        let stages: Vec<_> = events.iter().map(|e| (e.stage, e.status)).collect();
+        assert_eq!(
+            stages,
+            vec![
+                (ConnectionStage::DnsResolved, StageStatus::Passed),
+                (ConnectionStage::TcpConnected, StageStatus::Passed),
+                (ConnectionStage::TlsOk, StageStatus::Skipped),
+                (ConnectionStage::AuthOk, StageStatus::Passed),
+                (ConnectionStage::WebdavOk, StageStatus::Passed),
+                (ConnectionStage::ServerTypeDetected, StageStatus::Passed),
+                (ConnectionStage::QuotaFetched, StageStatus::Passed),
+            ]
+        );
+        assert!(events.iter().all(|e| e.server_id == "test-id"));
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_auth_failure() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("PROPFIND", "/")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let (result, events) = run_stages(&server).await;
+        assert!(matches!(result, Err(SyncError::AuthError(_))));
+
+        let last = events.last().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(last.stage, ConnectionStage::AuthOk);
+        assert_eq!(last.status, StageStatus::Failed);
+        assert_eq!(last.error_code, Some(ErrorCode::AuthFailed));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_failure() {
+        let config = mock_server_config("http://127.0.0.1:1");
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let mut events = Vec::new();
+        let result = run("test-id", &client, false, |event| events.push(event)).await;
+
+        assert!(matches!(result, Err(SyncError::Network(_))));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].detail.as_deref(), Some("127.0.0.1"));
+        assert_eq!(events[1].stage, ConnectionStage::TcpConnected);
+        assert_eq!(events[1].status, StageStatus::Failed);
+    }
+}
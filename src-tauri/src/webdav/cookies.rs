@@ -0,0 +1,510 @@
+/// 服务器 Cookie 会话
+///
+/// 部分 WebDAV 服务部署在基于 Cookie 的 SSO 网关之后，未携带会话 Cookie 的请求会被网关拦截。
+/// 每个服务器有独立的 `CookieJar`：客户端发送请求时附带与 URL 匹配的 Cookie，
+/// 响应中的 `Set-Cookie` 写回 Cookie 罐，内容变化时加密保存到应用数据目录
+/// （与 `credential_store` 相同的加密方式，使用本机密钥），应用重启后继续使用同一会话。
+///
+/// 会话 Cookie 也可以通过内嵌浏览器窗口手动登录获得，见 `commands::sso`
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::constants::COOKIES_FILE;
+use crate::crypto::{self, EncryptedBlob};
+use crate::webdav::credential_store::default_passphrase;
+use crate::{Result, SyncError};
+
+/// 保存的 Cookie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCookie {
+    /// 名称
+    pub name: String,
+    /// 值
+    pub value: String,
+    /// 所属域名（小写，不带前导点）
+    pub domain: String,
+    /// 是否同时发送给子域名（Set-Cookie 指定了 Domain 属性）
+    pub include_subdomains: bool,
+    /// 路径
+    pub path: String,
+    /// 是否只通过 HTTPS 发送
+    pub secure: bool,
+    /// 过期时间（Unix 时间戳，秒；None 表示会话 Cookie）
+    pub expires: Option<i64>,
+}
+
+impl StoredCookie {
+    /// 从服务器返回的 Cookie 创建
+    ///
+    /// # 参数
+    /// - cookie: 解析后的 Cookie
+    /// - url: 返回该 Cookie 的请求 URL
+    /// - now: 当前时间（Unix 时间戳，秒）
+    ///
+    /// # 返回
+    /// Domain 属性与请求的主机不匹配时返回 None
+    pub fn from_cookie(cookie: &cookie::Cookie<'_>, url: &Url, now: i64) -> Option<Self> {
+        let host = url.host_str()?.to_lowercase();
+        let (domain, include_subdomains) = match cookie.domain() {
+            Some(domain) => {
+                let domain = domain.trim_start_matches('.').to_lowercase();
+                if !domain_matches(&host, &domain) {
+                    return None;
+                }
+                (domain, true)
+            }
+            None => (host, false),
+        };
+
+        let path = match cookie.path() {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => default_path(url),
+        };
+        let expires = match cookie.max_age() {
+            Some(max_age) => Some(now + max_age.whole_seconds()),
+            None => cookie
+                .expires_datetime()
+                .map(|expires| expires.unix_timestamp()),
+        };
+
+        Some(Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            include_subdomains,
+            path,
+            secure: cookie.secure().unwrap_or(false),
+            expires,
+        })
+    }
+
+    /// 是否已过期
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// 是否应随该 URL 的请求发送
+    fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_lowercase();
+        let host_matches = if self.include_subdomains {
+            domain_matches(&host, &self.domain)
+        } else {
+            host == self.domain
+        };
+
+        host_matches
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+    }
+
+    /// 名称、域名与路径相同的 Cookie 相互覆盖
+    fn same_identity(&self, other: &StoredCookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+}
+
+/// 主机是否属于 Cookie 的域名（相同或为其子域名）
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// 请求路径是否在 Cookie 的路径下
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// 未指定 Path 属性时的默认路径（请求路径的目录部分）
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => url.path()[..index].to_string(),
+    }
+}
+
+/// 加密的 Cookie 文件（所有服务器共用）
+struct CookieFile {
+    path: PathBuf,
+    passphrase: String,
+    /// 串行化读改写
+    lock: Mutex<()>,
+}
+
+impl std::fmt::Debug for CookieFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieFile")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CookieFile {
+    /// 读取并解密文件，文件不存在时返回空表
+    fn load(&self) -> Result<BTreeMap<String, Vec<StoredCookie>>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let blob: EncryptedBlob = serde_json::from_str(&content)?;
+        let plaintext = crypto::decrypt(&blob, &self.passphrase).map_err(|e| {
+            SyncError::ConfigError(format!("Failed to decrypt cookie store: {}", e))
+        })?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// 读取服务器的 Cookie
+    fn get(&self, server_id: &str) -> Result<Vec<StoredCookie>> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.load()?.remove(server_id).unwrap_or_default())
+    }
+
+    /// 替换服务器的 Cookie（为空时删除该服务器）并写回文件
+    ///
+    /// 先写入临时文件再重命名，避免写入中断导致文件损坏
+    fn set(&self, server_id: &str, cookies: &[StoredCookie]) -> Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let mut servers = self.load()?;
+        if cookies.is_empty() {
+            servers.remove(server_id);
+        } else {
+            servers.insert(server_id.to_string(), cookies.to_vec());
+        }
+
+        let plaintext = serde_json::to_vec(&servers)?;
+        let blob = crypto::encrypt(&plaintext, &self.passphrase).map_err(|e| {
+            SyncError::ConfigError(format!("Failed to encrypt cookie store: {}", e))
+        })?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(&blob)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&tmp_path, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// 单个服务器的 Cookie 罐
+///
+/// 同一服务器的所有客户端共享同一个实例
+#[derive(Debug)]
+pub struct CookieJar {
+    server_id: String,
+    cookies: Mutex<Vec<StoredCookie>>,
+    file: Option<Arc<CookieFile>>,
+}
+
+impl CookieJar {
+    /// 创建不持久化的 Cookie 罐
+    pub fn in_memory(server_id: &str) -> Self {
+        Self {
+            server_id: server_id.to_string(),
+            cookies: Mutex::new(Vec::new()),
+            file: None,
+        }
+    }
+
+    /// 生成请求 URL 对应的 `Cookie` 请求头（没有匹配的 Cookie 时返回 None）
+    pub fn header(&self, url: &Url) -> Option<HeaderValue> {
+        let now = chrono::Utc::now().timestamp();
+        let cookies = self.cookies.lock().unwrap();
+        let mut matching: Vec<&StoredCookie> = cookies
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now) && cookie.matches(url))
+            .collect();
+        // 路径更长的 Cookie 排在前面（RFC 6265 5.4）
+        matching.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+
+        let value = matching
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if value.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&value).ok()
+    }
+
+    /// 读取响应中的 `Set-Cookie`，有变化时保存
+    ///
+    /// # 参数
+    /// - url: 响应对应的请求 URL
+    /// - headers: 响应头
+    pub fn store_response(&self, url: &Url, headers: &HeaderMap) {
+        let now = chrono::Utc::now().timestamp();
+        let received: Vec<StoredCookie> = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| cookie::Cookie::parse(value).ok())
+            .filter_map(|cookie| StoredCookie::from_cookie(&cookie, url, now))
+            .collect();
+        if received.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.merge(received, now) {
+            tracing::warn!(server_id = %self.server_id, error = %e, "保存服务器 Cookie 失败");
+        }
+    }
+
+    /// 导入 Cookie（如内嵌浏览器登录后获得的会话），覆盖名称、域名与路径相同的 Cookie
+    ///
+    /// # 返回
+    /// 导入后有效的 Cookie 数
+    pub fn import(&self, cookies: Vec<StoredCookie>) -> Result<usize> {
+        self.merge(cookies, chrono::Utc::now().timestamp())?;
+        Ok(self.len())
+    }
+
+    /// 有效的 Cookie 数
+    pub fn len(&self) -> usize {
+        let now = chrono::Utc::now().timestamp();
+        let cookies = self.cookies.lock().unwrap();
+        cookies.iter().filter(|c| !c.is_expired(now)).count()
+    }
+
+    /// 是否没有有效的 Cookie
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 清除所有 Cookie
+    pub fn clear(&self) -> Result<()> {
+        self.cookies.lock().unwrap().clear();
+        self.persist(&[])
+    }
+
+    /// 合并 Cookie：相同身份的覆盖，已过期的（服务器删除 Cookie 时会返回过去的过期时间）移除
+    fn merge(&self, received: Vec<StoredCookie>, now: i64) -> Result<()> {
+        let snapshot = {
+            let mut cookies = self.cookies.lock().unwrap();
+            let before = cookies.clone();
+            for cookie in received {
+                cookies.retain(|existing| !existing.same_identity(&cookie));
+                cookies.push(cookie);
+            }
+            cookies.retain(|cookie| !cookie.is_expired(now));
+            if *cookies == before {
+                return Ok(());
+            }
+            cookies.clone()
+        };
+        self.persist(&snapshot)
+    }
+
+    fn persist(&self, cookies: &[StoredCookie]) -> Result<()> {
+        match &self.file {
+            Some(file) => file.set(&self.server_id, cookies),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 所有服务器的 Cookie 会话（作为 Tauri State 管理）
+///
+/// 克隆的实例共享同一份数据，`WebDavClientFactory` 持有一份用于为客户端附加 Cookie 罐
+#[derive(Debug, Clone)]
+pub struct CookieStore {
+    file: Arc<CookieFile>,
+    jars: Arc<Mutex<HashMap<String, Arc<CookieJar>>>>,
+}
+
+impl CookieStore {
+    /// 使用指定文件和口令创建
+    pub fn new(path: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+        Self {
+            file: Arc::new(CookieFile {
+                path: path.into(),
+                passphrase: passphrase.into(),
+                lock: Mutex::new(()),
+            }),
+            jars: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 打开应用数据目录下的 Cookie 文件（使用本机密钥加密）
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        Ok(Self::new(app_dir.join(COOKIES_FILE), default_passphrase()))
+    }
+
+    /// Cookie 文件路径
+    pub fn path(&self) -> &Path {
+        &self.file.path
+    }
+
+    /// 获取服务器的 Cookie 罐（首次获取时从文件加载）
+    pub fn jar(&self, server_id: &str) -> Result<Arc<CookieJar>> {
+        let mut jars = self.jars.lock().unwrap();
+        if let Some(jar) = jars.get(server_id) {
+            return Ok(jar.clone());
+        }
+
+        let jar = Arc::new(CookieJar {
+            server_id: server_id.to_string(),
+            cookies: Mutex::new(self.file.get(server_id)?),
+            file: Some(self.file.clone()),
+        });
+        jars.insert(server_id.to_string(), jar.clone());
+        Ok(jar)
+    }
+
+    /// 清除服务器的 Cookie
+    pub fn clear(&self, server_id: &str) -> Result<()> {
+        self.jar(server_id)?.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).unwrap()
+    }
+
+    fn parse(set_cookie: &str, request_url: &str) -> Option<StoredCookie> {
+        let cookie = cookie::Cookie::parse(set_cookie.to_string()).unwrap();
+        StoredCookie::from_cookie(&cookie, &url(request_url), 1_000)
+    }
+
+    fn headers(set_cookies: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in set_cookies {
+            headers.append(SET_COOKIE, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_from_cookie() {
+        let cookie = parse(
+            "session=abc; Domain=.Example.com; Path=/dav; Secure; Max-Age=60",
+            "https://sso.example.com/login",
+        )
+        .unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert!(cookie.include_subdomains);
+        assert_eq!(cookie.path, "/dav");
+        assert!(cookie.secure);
+        assert_eq!(cookie.expires, Some(1_060));
+
+        // 未指定 Domain 与 Path 时只属于请求的主机和目录
+        let cookie = parse("id=1", "https://dav.example.com/remote/webdav").unwrap();
+        assert_eq!(cookie.domain, "dav.example.com");
+        assert!(!cookie.include_subdomains);
+        assert_eq!(cookie.path, "/remote");
+        assert_eq!(cookie.expires, None);
+
+        // 不能为其他域名设置 Cookie
+        assert_eq!(
+            parse("id=1; Domain=other.com", "https://dav.example.com/"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_header_matches_url() {
+        let jar = CookieJar::in_memory("server-1");
+        jar.store_response(
+            &url("https://sso.example.com/login"),
+            &headers(&[
+                "gateway=g1; Domain=example.com; Path=/",
+                "dav=d1; Domain=example.com; Path=/remote.php; Secure",
+                "host=h1",
+            ]),
+        );
+
+        let header = jar
+            .header(&url("https://dav.example.com/remote.php/webdav/a.txt"))
+            .unwrap();
+        assert_eq!(header.to_str().unwrap(), "dav=d1; gateway=g1");
+
+        // Secure Cookie 不通过 HTTP 发送，主机 Cookie 不发送给其他主机
+        let header = jar
+            .header(&url("http://dav.example.com/remote.php/webdav"))
+            .unwrap();
+        assert_eq!(header.to_str().unwrap(), "gateway=g1");
+        assert!(jar.header(&url("https://example.org/")).is_none());
+        assert_eq!(
+            jar.header(&url("https://sso.example.com/other"))
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "gateway=g1; host=h1"
+        );
+    }
+
+    #[test]
+    fn test_expired_cookie_is_removed() {
+        let jar = CookieJar::in_memory("server-1");
+        let request_url = url("https://dav.example.com/");
+        jar.store_response(&request_url, &headers(&["session=abc"]));
+        assert_eq!(jar.len(), 1);
+
+        jar.store_response(
+            &request_url,
+            &headers(&["session=; Expires=Thu, 01 Jan 1970 00:00:00 GMT"]),
+        );
+        assert!(jar.is_empty());
+        assert!(jar.header(&request_url).is_none());
+    }
+
+    #[test]
+    fn test_store_persists_encrypted() {
+        let dir = std::env::temp_dir().join(format!("lightsync_cookies_{}", Uuid::new_v4()));
+        let store = CookieStore::new(dir.join(COOKIES_FILE), "passphrase");
+        let request_url = url("https://dav.example.com/");
+
+        store
+            .jar("server-1")
+            .unwrap()
+            .store_response(&request_url, &headers(&["session=secret-value"]));
+        let content = std::fs::read_to_string(store.path()).unwrap();
+        assert!(!content.contains("secret-value"));
+
+        // 重新打开后恢复会话
+        let reopened = CookieStore::new(dir.join(COOKIES_FILE), "passphrase");
+        let jar = reopened.jar("server-1").unwrap();
+        assert_eq!(
+            jar.header(&request_url).unwrap().to_str().unwrap(),
+            "session=secret-value"
+        );
+        assert!(reopened.jar("server-2").unwrap().is_empty());
+
+        reopened.clear("server-1").unwrap();
+        let cleared = CookieStore::new(dir.join(COOKIES_FILE), "passphrase");
+        assert!(cleared.jar("server-1").unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
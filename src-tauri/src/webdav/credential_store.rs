@@ -0,0 +1,314 @@
+/// 加密文件凭据存储
+///
+/// 部分 Linux 环境没有 Secret Service，系统 Keyring 完全不可用。
+/// 此时 `KeyringManager` 自动改用本模块：所有服务器的密码以 JSON 对象的形式，
+/// 使用 `crate::crypto`（PBKDF2 + AES-256-GCM）加密后保存在应用数据目录下的单个文件中。
+///
+/// # 密钥来源
+///
+/// - 设置了主密码时使用从主密码派生的密钥（见 `crate::app_lock`），应用锁定期间无法读取
+/// - 设置了 `LIGHTSYNC_CREDENTIAL_PASSPHRASE` 环境变量时使用用户口令
+/// - 否则使用与本机绑定的机器标识（Linux 的 machine-id，其他系统为主机名与用户目录），
+///   文件被复制到其他机器后无法解密
+///
+/// 解密后的密码缓存在内存中，避免每次读取都重新派生密钥
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::constants::CREDENTIAL_PASSPHRASE_ENV;
+use crate::crypto::{self, EncryptedBlob};
+use crate::{Result, SyncError};
+
+/// 加密文件凭据存储
+pub struct CredentialStore {
+    /// 加密文件路径
+    path: PathBuf,
+    /// 加密口令（None 表示已锁定）
+    passphrase: Mutex<Option<String>>,
+    /// 已解密的密码（首次访问时加载）
+    cache: Mutex<Option<BTreeMap<String, String>>>,
+}
+
+impl CredentialStore {
+    /// 使用指定口令创建凭据存储
+    ///
+    /// 文件不存在时视为空存储，首次写入时创建
+    pub fn new(path: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            passphrase: Mutex::new(Some(passphrase.into())),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// 使用环境变量中的口令或本机标识创建凭据存储
+    pub fn with_default_key(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, default_passphrase())
+    }
+
+    /// 创建已锁定的凭据存储，需要通过 `unlock` 提供口令后才能读写
+    pub fn locked(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            passphrase: Mutex::new(None),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// 提供口令解锁（口令错误时首次读写会失败）
+    pub fn unlock(&self, passphrase: impl Into<String>) {
+        if let Ok(mut current) = self.passphrase.lock() {
+            *current = Some(passphrase.into());
+        }
+    }
+
+    /// 锁定并清除内存中已解密的密码
+    pub fn lock(&self) {
+        if let Ok(mut current) = self.passphrase.lock() {
+            *current = None;
+        }
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = None;
+        }
+    }
+
+    /// 更换加密口令，已保存的密码使用新口令重新加密
+    ///
+    /// # 返回
+    /// - Ok(()): 已重新加密
+    /// - Err(SyncError::Locked): 当前已锁定
+    /// - Err(SyncError::KeyringError): 文件无法解密
+    pub fn rekey(&self, passphrase: impl Into<String>) -> Result<()> {
+        let passphrase = passphrase.into();
+        self.with_entries(|_| Ok(((), false)))?;
+
+        let mut cache = self
+            .cache
+            .lock()
+            .map_err(|_| SyncError::KeyringError("Credential store lock poisoned".to_string()))?;
+        let entries = cache.take().unwrap_or_default();
+        Self::save(&self.path, &passphrase, &entries)?;
+        *cache = Some(entries);
+        drop(cache);
+
+        self.unlock(passphrase);
+        Ok(())
+    }
+
+    /// 读取密码
+    ///
+    /// # 返回
+    /// - Ok(Some(String)): 找到密码
+    /// - Ok(None): 没有该服务器的密码
+    /// - Err(SyncError::Locked): 已锁定
+    /// - Err(SyncError::KeyringError): 文件无法读取或解密
+    pub fn get(&self, server_id: &str) -> Result<Option<String>> {
+        self.with_entries(|entries| Ok((entries.get(server_id).cloned(), false)))
+    }
+
+    /// 保存密码（覆盖已有的密码）
+    pub fn set(&self, server_id: &str, password: &str) -> Result<()> {
+        self.with_entries(|entries| {
+            entries.insert(server_id.to_string(), password.to_string());
+            Ok(((), true))
+        })
+    }
+
+    /// 删除密码
+    ///
+    /// # 返回
+    /// - Ok(true): 已删除
+    /// - Ok(false): 没有该服务器的密码
+    pub fn delete(&self, server_id: &str) -> Result<bool> {
+        self.with_entries(|entries| {
+            let removed = entries.remove(server_id).is_some();
+            Ok((removed, removed))
+        })
+    }
+
+    /// 在已解密的密码上执行操作，操作返回 true 时写回文件
+    fn with_entries<T>(
+        &self,
+        f: impl FnOnce(&mut BTreeMap<String, String>) -> Result<(T, bool)>,
+    ) -> Result<T> {
+        let mut cache = self
+            .cache
+            .lock()
+            .map_err(|_| SyncError::KeyringError("Credential store lock poisoned".to_string()))?;
+
+        let passphrase = self
+            .passphrase
+            .lock()
+            .map_err(|_| SyncError::KeyringError("Credential store lock poisoned".to_string()))?
+            .clone()
+            .ok_or_else(|| SyncError::Locked("Credential store is locked".to_string()))?;
+
+        if cache.is_none() {
+            *cache = Some(Self::load(&self.path, &passphrase)?);
+        }
+        let entries = cache.as_mut().expect("credential cache loaded");
+
+        let (value, changed) = f(entries)?;
+        if changed {
+            Self::save(&self.path, &passphrase, entries)?;
+        }
+        Ok(value)
+    }
+
+    /// 读取并解密文件
+    fn load(path: &Path, passphrase: &str) -> Result<BTreeMap<String, String>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => {
+                return Err(SyncError::KeyringError(format!(
+                    "Failed to read credential store: {}",
+                    e
+                )))
+            }
+        };
+
+        let blob: EncryptedBlob = serde_json::from_str(&content).map_err(|e| {
+            SyncError::KeyringError(format!("Invalid credential store file: {}", e))
+        })?;
+        let plaintext = crypto::decrypt(&blob, passphrase).map_err(|e| {
+            SyncError::KeyringError(format!("Failed to decrypt credential store: {}", e))
+        })?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| {
+            SyncError::KeyringError(format!("Invalid credential store content: {}", e))
+        })
+    }
+
+    /// 加密并写入文件
+    ///
+    /// 先写入临时文件再重命名，避免写入中断导致文件损坏
+    fn save(path: &Path, passphrase: &str, entries: &BTreeMap<String, String>) -> Result<()> {
+        let plaintext = serde_json::to_vec(entries)?;
+        let blob = crypto::encrypt(&plaintext, passphrase).map_err(|e| {
+            SyncError::KeyringError(format!("Failed to encrypt credentials: {}", e))
+        })?;
+        let content = serde_json::to_string_pretty(&blob)?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, content)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+}
+
+/// 未设置主密码时使用的口令（环境变量中的用户口令或本机标识）
+pub fn default_passphrase() -> String {
+    std::env::var(CREDENTIAL_PASSPHRASE_ENV)
+        .ok()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(machine_key)
+}
+
+/// 与本机绑定的密钥材料
+///
+/// Linux 使用 machine-id，读取失败或其他系统使用主机名与用户目录
+fn machine_key() -> String {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty());
+
+    let id = machine_id.unwrap_or_else(|| {
+        let host = std::env::var("COMPUTERNAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_default();
+        let home = dirs::home_dir().unwrap_or_default();
+        format!("{}:{}", host, home.display())
+    });
+
+    format!("LightSync:{}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn create_test_path() -> (PathBuf, PathBuf) {
+        let test_dir = std::env::temp_dir().join(format!("lightsync_test_{}", Uuid::new_v4()));
+        let path = test_dir.join("credentials.enc.json");
+        (test_dir, path)
+    }
+
+    #[test]
+    fn test_round_trip_persists_encrypted() {
+        let (test_dir, path) = create_test_path();
+
+        let store = CredentialStore::new(&path, "passphrase");
+        assert_eq!(store.get("server-1").unwrap(), None);
+        store.set("server-1", "secret-password").unwrap();
+        store.set("server-2", "other").unwrap();
+
+        // 文件中不包含明文密码
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret-password"));
+
+        // 重新打开后可以读取
+        let reopened = CredentialStore::new(&path, "passphrase");
+        assert_eq!(
+            reopened.get("server-1").unwrap().as_deref(),
+            Some("secret-password")
+        );
+
+        assert!(reopened.delete("server-1").unwrap());
+        assert!(!reopened.delete("server-1").unwrap());
+        let reopened = CredentialStore::new(&path, "passphrase");
+        assert_eq!(reopened.get("server-1").unwrap(), None);
+        assert_eq!(reopened.get("server-2").unwrap().as_deref(), Some("other"));
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_lock_and_rekey() {
+        let (test_dir, path) = create_test_path();
+
+        let store = CredentialStore::new(&path, "machine");
+        store.set("server-1", "secret").unwrap();
+        store.rekey("master").unwrap();
+        assert_eq!(store.get("server-1").unwrap().as_deref(), Some("secret"));
+
+        store.lock();
+        assert!(matches!(store.get("server-1"), Err(SyncError::Locked(_))));
+        store.unlock("master");
+        assert_eq!(store.get("server-1").unwrap().as_deref(), Some("secret"));
+
+        // 旧口令无法再解密
+        let result = CredentialStore::new(&path, "machine").get("server-1");
+        assert!(matches!(result, Err(SyncError::KeyringError(_))));
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let (test_dir, path) = create_test_path();
+
+        CredentialStore::new(&path, "passphrase")
+            .set("server-1", "secret")
+            .unwrap();
+
+        let result = CredentialStore::new(&path, "other-machine").get("server-1");
+        assert!(matches!(result, Err(SyncError::KeyringError(_))));
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -1,16 +1,96 @@
 /// WebDAV 服务器配置数据库操作模块
 ///
-/// 提供对 webdav_servers 表的 CRUD 操作
+/// 提供对 webdav_servers 表的 CRUD 操作、批量启用/禁用，以及服务器的归档（软删除）、恢复与过期清理
 ///
 /// 注意: 密码不存储在数据库中，而是存储在系统 Keyring 中
-use crate::database::WebDavServerConfig;
+use crate::constants::propfind_dialect;
+use crate::database::{ArchivedWebDavServer, Database, WebDavServerConfig};
 use crate::{Result, SyncError};
-use tauri::{AppHandle, Manager};
+
+/// webdav_servers 表的查询列（顺序与 `row_to_server` 对应）
+const SERVER_COLUMNS: &str =
+    "id, name, url, username, use_https, timeout, last_test_at, last_test_status,
+                last_test_error, server_type, enabled, created_at, updated_at, auth_type,
+                accept_invalid_certs, pinned_cert_fingerprint, custom_headers, ip_version,
+                resolve_ip, connect_timeout, read_timeout";
+
+/// 将查询结果行转换为服务器配置
+fn row_to_server(row: &rusqlite::Row<'_>) -> rusqlite::Result<WebDavServerConfig> {
+    Ok(WebDavServerConfig {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        username: row.get(3)?,
+        use_https: row.get::<_, i32>(4)? != 0,
+        timeout: row.get::<_, i64>(5)? as u32,
+        last_test_at: row.get(6)?,
+        last_test_status: row.get(7)?,
+        last_test_error: row.get(8)?,
+        server_type: row.get(9)?,
+        auth_type: row.get(13)?,
+        accept_invalid_certs: row.get::<_, i32>(14)? != 0,
+        pinned_cert_fingerprint: row.get(15)?,
+        custom_headers: serde_json::from_str(&row.get::<_, String>(16)?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(16, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        ip_version: row.get(17)?,
+        resolve_ip: row.get(18)?,
+        connect_timeout: row.get::<_, i64>(19)? as u32,
+        read_timeout: row.get::<_, i64>(20)? as u32,
+        enabled: row.get::<_, i32>(10)? != 0,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+/// 检查同一地址下是否已有相同用户名的账户
+///
+/// 地址按规范化后的 URL 比较（见 `WebDavServerConfig::normalized_url`），
+/// 同一主机上的不同账户可以共存；已归档的服务器不参与比较
+///
+/// # 参数
+/// - conn: 数据库连接
+/// - config: 待保存的服务器配置
+/// - exclude_id: 不参与比较的服务器 ID（更新时为服务器自身）
+///
+/// # 返回
+/// - Ok(()): 没有重复的账户
+/// - Err(SyncError::DuplicateServer): 已存在相同地址和用户名的服务器，
+///   内容为已有服务器的名称，上下文 `serverId` 为已有服务器的 ID
+fn ensure_unique_account(
+    conn: &rusqlite::Connection,
+    config: &WebDavServerConfig,
+    exclude_id: &str,
+) -> Result<()> {
+    let query = format!(
+        "SELECT {} FROM webdav_servers WHERE id != ?1 AND deleted_at IS NULL",
+        SERVER_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+    let servers = stmt
+        .query_map(rusqlite::params![exclude_id], row_to_server)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query webdav servers: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
+
+    let url = config.normalized_url();
+    let username = config.username.trim();
+    match servers
+        .iter()
+        .find(|server| server.normalized_url() == url && server.username.trim() == username)
+    {
+        Some(existing) => Err(SyncError::DuplicateServer(existing.name.clone())
+            .with_context("serverId", &existing.id)),
+        None => Ok(()),
+    }
+}
 
 /// 插入新的 WebDAV 服务器配置
 ///
 /// # 参数
-/// - app: Tauri 应用句柄
+/// - db: 共享数据库连接
 /// - config: 服务器配置（必须包含有效的 id）
 ///
 /// # 返回
@@ -20,37 +100,29 @@ use tauri::{AppHandle, Manager};
 /// # 验证
 /// - 在插入前会调用 config.validate() 验证所有字段
 /// - id 必须是唯一的（数据库主键约束）
+/// - 地址与用户名的组合必须是唯一的
 pub async fn insert_webdav_server(
-    app: AppHandle,
+    db: &Database,
     config: WebDavServerConfig,
 ) -> Result<WebDavServerConfig> {
     // 验证配置
     config
         .validate()
-        .map_err(|e| SyncError::ConfigError(format!("Invalid server config: {}", e)))?;
-
-    // 使用 rusqlite 直接操作数据库
-    use rusqlite::Connection;
+        .map_err(|e| SyncError::ValidationError(format!("Invalid server config: {}", e)))?;
 
-    // 获取数据库路径
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to get app data dir: {}", e)))?;
-
-    let db_path = app_dir.join("lightsync.db");
-
-    // 打开数据库连接
-    let conn = Connection::open(&db_path)
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to open database: {}", e)))?;
+    let conn = db.conn()?;
+    ensure_unique_account(&conn, &config, &config.id)?;
 
     // 插入数据
     conn.execute(
         "INSERT INTO webdav_servers (
             id, name, url, username, use_https, timeout,
             last_test_at, last_test_status, last_test_error,
-            server_type, enabled, created_at, updated_at
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            server_type, enabled, created_at, updated_at, auth_type,
+            accept_invalid_certs, pinned_cert_fingerprint, custom_headers, ip_version, resolve_ip,
+            connect_timeout, read_timeout
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                  ?18, ?19, ?20, ?21)",
         rusqlite::params![
             config.id,
             config.name,
@@ -65,6 +137,14 @@ pub async fn insert_webdav_server(
             config.enabled as i32,
             config.created_at,
             config.updated_at,
+            config.auth_type,
+            config.accept_invalid_certs as i32,
+            config.pinned_cert_fingerprint,
+            serde_json::to_string(&config.custom_headers)?,
+            config.ip_version,
+            config.resolve_ip,
+            config.connect_timeout as i64,
+            config.read_timeout as i64,
         ],
     )
     .map_err(|e| SyncError::DatabaseError(format!("Failed to insert webdav server: {}", e)))?;
@@ -72,10 +152,10 @@ pub async fn insert_webdav_server(
     Ok(config)
 }
 
-/// 查询 WebDAV 服务器配置列表
+/// 查询 WebDAV 服务器配置列表（不包含已归档的服务器）
 ///
 /// # 参数
-/// - app: Tauri 应用句柄
+/// - db: 共享数据库连接
 /// - enabled_only: 是否只返回启用的服务器
 ///   - true: 只返回 enabled=1 的服务器
 ///   - false: 返回所有服务器
@@ -84,57 +164,33 @@ pub async fn insert_webdav_server(
 /// - Ok(Vec<WebDavServerConfig>): 查询成功，返回服务器配置列表
 /// - Err(SyncError): 查询失败
 pub async fn get_webdav_servers(
-    app: AppHandle,
+    db: &Database,
     enabled_only: bool,
 ) -> Result<Vec<WebDavServerConfig>> {
-    use rusqlite::Connection;
-
-    // 获取数据库路径
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to get app data dir: {}", e)))?;
-
-    let db_path = app_dir.join("lightsync.db");
-
-    // 打开数据库连接
-    let conn = Connection::open(&db_path)
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to open database: {}", e)))?;
+    let conn = db.conn()?;
 
     // 构建查询
     let query = if enabled_only {
-        "SELECT id, name, url, username, use_https, timeout, last_test_at, last_test_status, 
-                last_test_error, server_type, enabled, created_at, updated_at 
-         FROM webdav_servers WHERE enabled = 1 ORDER BY created_at DESC"
+        format!(
+            "SELECT {} FROM webdav_servers
+             WHERE enabled = 1 AND deleted_at IS NULL
+             ORDER BY created_at DESC",
+            SERVER_COLUMNS
+        )
     } else {
-        "SELECT id, name, url, username, use_https, timeout, last_test_at, last_test_status, 
-                last_test_error, server_type, enabled, created_at, updated_at 
-         FROM webdav_servers ORDER BY created_at DESC"
+        format!(
+            "SELECT {} FROM webdav_servers WHERE deleted_at IS NULL ORDER BY created_at DESC",
+            SERVER_COLUMNS
+        )
     };
 
     // 执行查询
     let mut stmt = conn
-        .prepare(query)
+        .prepare(&query)
         .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
 
     let servers = stmt
-        .query_map([], |row| {
-            Ok(WebDavServerConfig {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                url: row.get(2)?,
-                username: row.get(3)?,
-                use_https: row.get::<_, i32>(4)? != 0,
-                timeout: row.get::<_, i64>(5)? as u32,
-                last_test_at: row.get(6)?,
-                last_test_status: row.get(7)?,
-                last_test_error: row.get(8)?,
-                server_type: row.get(9)?,
-                enabled: row.get::<_, i32>(10)? != 0,
-                created_at: row.get(11)?,
-                updated_at: row.get(12)?,
-            })
-        })
+        .query_map([], row_to_server)
         .map_err(|e| SyncError::DatabaseError(format!("Failed to query webdav servers: {}", e)))?
         .collect::<std::result::Result<Vec<_>, _>>()
         .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
@@ -144,56 +200,27 @@ pub async fn get_webdav_servers(
 
 /// 根据 ID 查询单个 WebDAV 服务器配置
 ///
+/// 已归档的服务器同样可以查询到
+///
 /// # 参数
-/// - app: Tauri 应用句柄
+/// - db: 共享数据库连接
 /// - server_id: 服务器 ID
 ///
 /// # 返回
 /// - Ok(WebDavServerConfig): 查询成功，返回服务器配置
 /// - Err(SyncError::NotFound): 服务器不存在
 /// - Err(SyncError::DatabaseError): 查询失败
-pub async fn get_webdav_server_by_id(
-    app: AppHandle,
-    server_id: &str,
-) -> Result<WebDavServerConfig> {
-    use rusqlite::Connection;
-
-    // 获取数据库路径
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to get app data dir: {}", e)))?;
-
-    let db_path = app_dir.join("lightsync.db");
-
-    // 打开数据库连接
-    let conn = Connection::open(&db_path)
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to open database: {}", e)))?;
+pub async fn get_webdav_server_by_id(db: &Database, server_id: &str) -> Result<WebDavServerConfig> {
+    let conn = db.conn()?;
 
     // 执行查询
-    let query =
-        "SELECT id, name, url, username, use_https, timeout, last_test_at, last_test_status, 
-                        last_test_error, server_type, enabled, created_at, updated_at 
-                 FROM webdav_servers WHERE id = ?1 LIMIT 1";
+    let query = format!(
+        "SELECT {} FROM webdav_servers WHERE id = ?1 LIMIT 1",
+        SERVER_COLUMNS
+    );
 
     let server = conn
-        .query_row(query, rusqlite::params![server_id], |row| {
-            Ok(WebDavServerConfig {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                url: row.get(2)?,
-                username: row.get(3)?,
-                use_https: row.get::<_, i32>(4)? != 0,
-                timeout: row.get::<_, i64>(5)? as u32,
-                last_test_at: row.get(6)?,
-                last_test_status: row.get(7)?,
-                last_test_error: row.get(8)?,
-                server_type: row.get(9)?,
-                enabled: row.get::<_, i32>(10)? != 0,
-                created_at: row.get(11)?,
-                updated_at: row.get(12)?,
-            })
-        })
+        .query_row(&query, rusqlite::params![server_id], row_to_server)
         .map_err(|e| match e {
             rusqlite::Error::QueryReturnedNoRows => {
                 SyncError::NotFound(format!("WebDAV server not found: {}", server_id))
@@ -207,7 +234,7 @@ pub async fn get_webdav_server_by_id(
 /// 更新 WebDAV 服务器配置
 ///
 /// # 参数
-/// - app: Tauri 应用句柄
+/// - db: 共享数据库连接
 /// - server_id: 要更新的服务器 ID
 /// - config: 新的服务器配置
 ///
@@ -220,32 +247,23 @@ pub async fn get_webdav_server_by_id(
 /// - 会自动更新 updated_at 字段为当前时间
 /// - 在更新前会调用 config.validate() 验证所有字段
 /// - server_id 必须存在于数据库中
+/// - 地址与用户名的组合不能与其他服务器重复
+/// - 地址变化时记住的 PROPFIND 请求方式恢复为默认（见 `set_propfind_dialect`）
 pub async fn update_webdav_server(
-    app: AppHandle,
+    db: &Database,
     server_id: &str,
     config: WebDavServerConfig,
 ) -> Result<WebDavServerConfig> {
     // 验证配置
     config
         .validate()
-        .map_err(|e| SyncError::ConfigError(format!("Invalid server config: {}", e)))?;
+        .map_err(|e| SyncError::ValidationError(format!("Invalid server config: {}", e)))?;
 
     // 检查服务器是否存在
-    get_webdav_server_by_id(app.clone(), server_id).await?;
+    get_webdav_server_by_id(db, server_id).await?;
 
-    use rusqlite::Connection;
-
-    // 获取数据库路径
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to get app data dir: {}", e)))?;
-
-    let db_path = app_dir.join("lightsync.db");
-
-    // 打开数据库连接
-    let conn = Connection::open(&db_path)
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to open database: {}", e)))?;
+    let conn = db.conn()?;
+    ensure_unique_account(&conn, &config, server_id)?;
 
     // 更新当前时间
     let now = chrono::Utc::now().timestamp();
@@ -255,8 +273,12 @@ pub async fn update_webdav_server(
         "UPDATE webdav_servers
          SET name = ?1, url = ?2, username = ?3, use_https = ?4, timeout = ?5,
              last_test_at = ?6, last_test_status = ?7, last_test_error = ?8,
-             server_type = ?9, enabled = ?10, updated_at = ?11
-         WHERE id = ?12",
+             server_type = ?9, enabled = ?10, updated_at = ?11, auth_type = ?12,
+             accept_invalid_certs = ?13, pinned_cert_fingerprint = ?14, custom_headers = ?15,
+             ip_version = ?16, resolve_ip = ?17,
+             propfind_dialect = CASE WHEN url = ?2 THEN propfind_dialect ELSE ?19 END,
+             connect_timeout = ?20, read_timeout = ?21
+         WHERE id = ?18",
         rusqlite::params![
             config.name,
             config.url,
@@ -269,7 +291,16 @@ pub async fn update_webdav_server(
             config.server_type,
             config.enabled as i32,
             now,
+            config.auth_type,
+            config.accept_invalid_certs as i32,
+            config.pinned_cert_fingerprint,
+            serde_json::to_string(&config.custom_headers)?,
+            config.ip_version,
+            config.resolve_ip,
             server_id,
+            propfind_dialect::STANDARD,
+            config.connect_timeout as i64,
+            config.read_timeout as i64,
         ],
     )
     .map_err(|e| SyncError::DatabaseError(format!("Failed to update webdav server: {}", e)))?;
@@ -280,10 +311,129 @@ pub async fn update_webdav_server(
     Ok(updated_config)
 }
 
+/// 获取服务器记住的 PROPFIND 请求方式
+///
+/// 请求方式由客户端在服务器拒绝请求体时自动确定（见 `webdav::client::PropfindDialect`），
+/// 不属于用户编辑的服务器配置，因此单独读写
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - Ok(String): 请求方式（`constants::propfind_dialect`）
+/// - Err(SyncError::NotFound): 服务器不存在
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn get_propfind_dialect(db: &Database, server_id: &str) -> Result<String> {
+    let conn = db.conn()?;
+    conn.query_row(
+        "SELECT propfind_dialect FROM webdav_servers WHERE id = ?1",
+        rusqlite::params![server_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            SyncError::NotFound(format!("WebDAV server not found: {}", server_id))
+        }
+        _ => SyncError::DatabaseError(format!("Failed to query propfind dialect: {}", e)),
+    })
+}
+
+/// 保存服务器可用的 PROPFIND 请求方式
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - server_id: 服务器 ID
+/// - dialect: 请求方式（`constants::propfind_dialect`）
+///
+/// # 返回
+/// - Ok(()): 保存成功
+/// - Err(SyncError::NotFound): 服务器不存在
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn set_propfind_dialect(db: &Database, server_id: &str, dialect: &str) -> Result<()> {
+    let conn = db.conn()?;
+    let updated = conn
+        .execute(
+            "UPDATE webdav_servers SET propfind_dialect = ?1 WHERE id = ?2",
+            rusqlite::params![dialect, server_id],
+        )
+        .map_err(|e| {
+            SyncError::DatabaseError(format!("Failed to update propfind dialect: {}", e))
+        })?;
+    if updated == 0 {
+        return Err(SyncError::NotFound(format!(
+            "WebDAV server not found: {}",
+            server_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// 批量启用或禁用 WebDAV 服务器
+///
+/// 所有更新在同一个事务中完成，任一服务器不存在（或已归档）时全部回滚
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - server_ids: 服务器 ID 列表
+/// - enabled: 是否启用
+///
+/// # 返回
+/// - Ok(Vec<WebDavServerConfig>): 更新后的服务器配置（顺序与 server_ids 一致）
+/// - Err(SyncError::NotFound): 服务器不存在或已归档
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn set_servers_enabled(
+    db: &Database,
+    server_ids: &[String],
+    enabled: bool,
+) -> Result<Vec<WebDavServerConfig>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut conn = db.conn()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+    let query = format!(
+        "SELECT {} FROM webdav_servers WHERE id = ?1 LIMIT 1",
+        SERVER_COLUMNS
+    );
+    let mut servers = Vec::with_capacity(server_ids.len());
+    for server_id in server_ids {
+        let updated = tx
+            .execute(
+                "UPDATE webdav_servers SET enabled = ?1, updated_at = ?2
+                 WHERE id = ?3 AND deleted_at IS NULL",
+                rusqlite::params![enabled as i32, now, server_id],
+            )
+            .map_err(|e| {
+                SyncError::DatabaseError(format!("Failed to update webdav server: {}", e))
+            })?;
+        if updated == 0 {
+            return Err(SyncError::NotFound(format!(
+                "WebDAV server not found: {}",
+                server_id
+            )));
+        }
+
+        let server = tx
+            .query_row(&query, rusqlite::params![server_id], row_to_server)
+            .map_err(|e| {
+                SyncError::DatabaseError(format!("Failed to query webdav server: {}", e))
+            })?;
+        servers.push(server);
+    }
+
+    tx.commit()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+    Ok(servers)
+}
+
 /// 删除 WebDAV 服务器配置
 ///
 /// # 参数
-/// - app: Tauri 应用句柄
+/// - db: 共享数据库连接
 /// - server_id: 要删除的服务器 ID
 ///
 /// # 返回
@@ -294,23 +444,11 @@ pub async fn update_webdav_server(
 /// # 注意
 /// - 如果服务器被 sync_folders 使用，删除会失败（外键约束）
 /// - 删除服务器后，应该同时删除 Keyring 中的密码
-pub async fn delete_webdav_server(app: AppHandle, server_id: &str) -> Result<()> {
+pub async fn delete_webdav_server(db: &Database, server_id: &str) -> Result<()> {
     // 检查服务器是否存在
-    get_webdav_server_by_id(app.clone(), server_id).await?;
-
-    use rusqlite::Connection;
+    get_webdav_server_by_id(db, server_id).await?;
 
-    // 获取数据库路径
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to get app data dir: {}", e)))?;
-
-    let db_path = app_dir.join("lightsync.db");
-
-    // 打开数据库连接
-    let conn = Connection::open(&db_path)
-        .map_err(|e| SyncError::DatabaseError(format!("Failed to open database: {}", e)))?;
+    let conn = db.conn()?;
 
     // 执行删除
     conn.execute(
@@ -333,9 +471,129 @@ pub async fn delete_webdav_server(app: AppHandle, server_id: &str) -> Result<()>
     Ok(())
 }
 
+/// 归档 WebDAV 服务器
+///
+/// 服务器被禁用并记录归档时间，配置与历史记录保留，可以通过 `restore_webdav_server` 恢复
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - server_id: 服务器 ID
+/// - deleted_at: 归档时间（Unix 时间戳，秒）
+///
+/// # 返回
+/// - Ok(WebDavServerConfig): 归档后的服务器配置
+/// - Err(SyncError::NotFound): 服务器不存在或已归档
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn archive_webdav_server(
+    db: &Database,
+    server_id: &str,
+    deleted_at: i64,
+) -> Result<WebDavServerConfig> {
+    let mut config = get_webdav_server_by_id(db, server_id).await?;
+
+    let conn = db.conn()?;
+    let updated = conn
+        .execute(
+            "UPDATE webdav_servers
+             SET enabled = 0, deleted_at = ?1, updated_at = ?1
+             WHERE id = ?2 AND deleted_at IS NULL",
+            rusqlite::params![deleted_at, server_id],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to archive webdav server: {}", e)))?;
+    if updated == 0 {
+        return Err(SyncError::NotFound(format!(
+            "WebDAV server is already archived: {}",
+            server_id
+        )));
+    }
+
+    config.enabled = false;
+    config.updated_at = deleted_at;
+    Ok(config)
+}
+
+/// 恢复已归档的 WebDAV 服务器
+///
+/// 恢复后服务器仍处于禁用状态，由用户确认配置后重新启用
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - server_id: 服务器 ID
+///
+/// # 返回
+/// - Ok(WebDavServerConfig): 恢复后的服务器配置
+/// - Err(SyncError::NotFound): 服务器不存在或未归档
+/// - Err(SyncError::DuplicateServer): 归档期间已添加了相同地址和用户名的服务器
+/// - Err(SyncError::DatabaseError): 更新失败
+pub async fn restore_webdav_server(db: &Database, server_id: &str) -> Result<WebDavServerConfig> {
+    let mut config = get_webdav_server_by_id(db, server_id).await?;
+
+    let conn = db.conn()?;
+    ensure_unique_account(&conn, &config, server_id)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let updated = conn
+        .execute(
+            "UPDATE webdav_servers
+             SET deleted_at = NULL, updated_at = ?1
+             WHERE id = ?2 AND deleted_at IS NOT NULL",
+            rusqlite::params![now, server_id],
+        )
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to restore webdav server: {}", e)))?;
+    if updated == 0 {
+        return Err(SyncError::NotFound(format!(
+            "Archived WebDAV server not found: {}",
+            server_id
+        )));
+    }
+
+    config.updated_at = now;
+    Ok(config)
+}
+
+/// 查询已归档的 WebDAV 服务器（最近归档的在前）
+///
+/// # 参数
+/// - db: 共享数据库连接
+/// - archived_before: 只返回在此时间之前归档的服务器（可选，用于清理过期的服务器）
+///
+/// # 返回
+/// - Ok(Vec<ArchivedWebDavServer>): 已归档的服务器列表
+/// - Err(SyncError::DatabaseError): 查询失败
+pub async fn get_archived_webdav_servers(
+    db: &Database,
+    archived_before: Option<i64>,
+) -> Result<Vec<ArchivedWebDavServer>> {
+    let conn = db.conn()?;
+
+    let query = format!(
+        "SELECT {}, deleted_at FROM webdav_servers
+         WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < ?1)
+         ORDER BY deleted_at DESC",
+        SERVER_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to prepare query: {}", e)))?;
+
+    let servers = stmt
+        .query_map(rusqlite::params![archived_before], |row| {
+            Ok(ArchivedWebDavServer {
+                server: row_to_server(row)?,
+                deleted_at: row.get(21)?,
+            })
+        })
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to query webdav servers: {}", e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| SyncError::DatabaseError(format!("Failed to parse query results: {}", e)))?;
+
+    Ok(servers)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::webdav::mock_server_config;
     use std::fs;
     use std::path::PathBuf;
     use uuid::Uuid;
@@ -355,6 +613,26 @@ mod tests {
         // 注意: 001 迁移使用 MySQL 语法，不兼容 SQLite
         conn.execute_batch(include_str!("../../migrations/002_webdav_servers.sql"))
             .expect("Failed to run migration 002");
+        conn.execute_batch(include_str!("../../migrations/003_server_auth_type.sql"))
+            .expect("Failed to run migration 003");
+        conn.execute_batch(include_str!("../../migrations/004_server_tls_trust.sql"))
+            .expect("Failed to run migration 004");
+        conn.execute_batch(include_str!("../../migrations/013_server_archive.sql"))
+            .expect("Failed to run migration 013");
+        conn.execute_batch(include_str!(
+            "../../migrations/017_server_custom_headers.sql"
+        ))
+        .expect("Failed to run migration 017");
+        conn.execute_batch(include_str!(
+            "../../migrations/018_server_network_options.sql"
+        ))
+        .expect("Failed to run migration 018");
+        conn.execute_batch(include_str!(
+            "../../migrations/019_server_propfind_dialect.sql"
+        ))
+        .expect("Failed to run migration 019");
+        conn.execute_batch(include_str!("../../migrations/020_server_timeouts.sql"))
+            .expect("Failed to run migration 020");
 
         (test_dir, conn)
     }
@@ -370,17 +648,11 @@ mod tests {
         WebDavServerConfig {
             id: id.to_string(),
             name: format!("Test Server {}", id),
-            url: "https://example.com/webdav".to_string(),
-            username: "testuser".to_string(),
             use_https: true,
             timeout: 30,
-            last_test_at: None,
-            last_test_status: "unknown".to_string(),
-            last_test_error: None,
-            server_type: "generic".to_string(),
-            enabled: true,
             created_at: now,
             updated_at: now,
+            ..mock_server_config("https://example.com/webdav")
         }
     }
 
@@ -439,6 +711,7 @@ mod tests {
                     enabled: row.get::<_, i32>(10)? != 0,
                     created_at: row.get(11)?,
                     updated_at: row.get(12)?,
+                    ..mock_server_config("")
                 })
             },
         )
@@ -749,6 +1022,177 @@ mod tests {
         cleanup_test_db(test_dir);
     }
 
+    #[tokio::test]
+    async fn test_crud_via_shared_database() {
+        let (test_dir, conn) = create_test_db();
+        drop(conn);
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let config = create_test_config("test-shared-db-1");
+        insert_webdav_server(&db, config.clone()).await.unwrap();
+
+        let fetched = get_webdav_server_by_id(&db, &config.id).await.unwrap();
+        assert_eq!(fetched.name, config.name);
+
+        let mut updated = fetched.clone();
+        updated.name = "Renamed".to_string();
+        update_webdav_server(&db, &config.id, updated)
+            .await
+            .unwrap();
+
+        let servers = get_webdav_servers(&db, false).await.unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "Renamed");
+
+        delete_webdav_server(&db, &config.id).await.unwrap();
+        let result = get_webdav_server_by_id(&db, &config.id).await;
+        assert!(matches!(result, Err(SyncError::NotFound(_))));
+
+        drop(db);
+        cleanup_test_db(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_unique_account_per_url() {
+        let (test_dir, conn) = create_test_db();
+        drop(conn);
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let mut alice = create_test_config("test-account-alice");
+        alice.url = "https://cloud.example.com/remote.php/dav/files/{username}/".to_string();
+        alice.username = "alice".to_string();
+        insert_webdav_server(&db, alice.clone()).await.unwrap();
+
+        // 同一主机上的另一个账户
+        let mut bob = alice.clone();
+        bob.id = "test-account-bob".to_string();
+        bob.username = "bob".to_string();
+        insert_webdav_server(&db, bob.clone()).await.unwrap();
+
+        // 模板与展开后的地址指向同一账户
+        let mut duplicate = alice.clone();
+        duplicate.id = "test-account-duplicate".to_string();
+        duplicate.url = "https://cloud.example.com/remote.php/dav/files/alice/".to_string();
+        let result = insert_webdav_server(&db, duplicate.clone()).await;
+        let error = result.unwrap_err();
+        assert!(matches!(error.root(), SyncError::DuplicateServer(name) if *name == alice.name));
+        assert_eq!(error.context().get("serverId"), Some(&alice.id));
+
+        // 协议与主机名大小写、默认端口、末尾斜杠不影响判断
+        duplicate.url = "HTTPS://Cloud.Example.com:443/remote.php/dav/files/alice".to_string();
+        let result = insert_webdav_server(&db, duplicate).await;
+        assert!(matches!(
+            result.as_ref().map_err(SyncError::root),
+            Err(SyncError::DuplicateServer(_))
+        ));
+
+        // 更新自身不算重复，改成其他账户的用户名则拒绝
+        update_webdav_server(&db, &alice.id, alice.clone())
+            .await
+            .unwrap();
+        bob.username = "alice".to_string();
+        let result = update_webdav_server(&db, "test-account-bob", bob).await;
+        assert!(matches!(
+            result.as_ref().map_err(SyncError::root),
+            Err(SyncError::DuplicateServer(_))
+        ));
+
+        drop(db);
+        cleanup_test_db(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_set_servers_enabled() {
+        let (test_dir, conn) = create_test_db();
+        drop(conn);
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let ids: Vec<String> = (1..=3).map(|i| format!("test-bulk-{}", i)).collect();
+        for (i, id) in ids.iter().enumerate() {
+            let mut config = create_test_config(id);
+            config.username = format!("user{}", i);
+            insert_webdav_server(&db, config).await.unwrap();
+        }
+
+        let servers = set_servers_enabled(&db, &ids[..2], false).await.unwrap();
+        assert_eq!(servers.len(), 2);
+        assert!(servers.iter().all(|server| !server.enabled));
+        assert_eq!(get_webdav_servers(&db, true).await.unwrap().len(), 1);
+
+        // 任一服务器不存在时全部回滚
+        let mut with_missing = ids.clone();
+        with_missing.push("test-bulk-missing".to_string());
+        let result = set_servers_enabled(&db, &with_missing, true).await;
+        assert!(matches!(result, Err(SyncError::NotFound(_))));
+        assert_eq!(get_webdav_servers(&db, true).await.unwrap().len(), 1);
+
+        set_servers_enabled(&db, &ids, true).await.unwrap();
+        assert_eq!(get_webdav_servers(&db, true).await.unwrap().len(), 3);
+
+        drop(db);
+        cleanup_test_db(test_dir);
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_restore_server() {
+        let (test_dir, conn) = create_test_db();
+        drop(conn);
+        let db = Database::open(test_dir.join("lightsync.db")).unwrap();
+
+        let config = create_test_config("test-archive");
+        insert_webdav_server(&db, config.clone()).await.unwrap();
+
+        let archived = archive_webdav_server(&db, &config.id, 1_000).await.unwrap();
+        assert!(!archived.enabled);
+        assert!(matches!(
+            archive_webdav_server(&db, &config.id, 2_000).await,
+            Err(SyncError::NotFound(_))
+        ));
+
+        // 归档的服务器不出现在列表中，但仍可按 ID 查询
+        assert!(get_webdav_servers(&db, false).await.unwrap().is_empty());
+        assert!(get_webdav_server_by_id(&db, &config.id).await.is_ok());
+        let list = get_archived_webdav_servers(&db, None).await.unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].deleted_at, 1_000);
+        assert_eq!(list[0].server.id, config.id);
+        assert!(get_archived_webdav_servers(&db, Some(1_000))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            get_archived_webdav_servers(&db, Some(1_001))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // 归档期间可以重新添加相同的账户，此时不能恢复旧服务器
+        let mut replacement = config.clone();
+        replacement.id = "test-archive-replacement".to_string();
+        insert_webdav_server(&db, replacement.clone())
+            .await
+            .unwrap();
+        let result = restore_webdav_server(&db, &config.id).await;
+        assert!(matches!(
+            result.as_ref().map_err(SyncError::root),
+            Err(SyncError::DuplicateServer(_))
+        ));
+
+        delete_webdav_server(&db, &replacement.id).await.unwrap();
+        let restored = restore_webdav_server(&db, &config.id).await.unwrap();
+        assert!(!restored.enabled);
+        assert_eq!(get_webdav_servers(&db, false).await.unwrap().len(), 1);
+        assert!(matches!(
+            restore_webdav_server(&db, &config.id).await,
+            Err(SyncError::NotFound(_))
+        ));
+
+        drop(db);
+        cleanup_test_db(test_dir);
+    }
+
     // 注意: 外键约束测试需要等 Phase 5 实现 sync_folders 表后才能测试
     // 届时将添加以下测试:
     // - test_delete_server_with_foreign_key_constraint
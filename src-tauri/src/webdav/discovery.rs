@@ -0,0 +1,355 @@
+/// WebDAV 地址自动发现
+///
+/// 用户只输入主机名（如 `cloud.example.com`）时，探测常见的 WebDAV 端点并给出最合适的地址：
+///
+/// 1. 未指定协议时先尝试 https，无法连接再尝试 http
+/// 2. 请求 `/.well-known/caldav`：Nextcloud / ownCloud 会重定向到 `/remote.php/dav/`，
+///    据此得到安装路径（支持子目录安装）
+/// 3. 请求 `/status.php` 识别 Nextcloud / ownCloud
+/// 4. 依次对候选端点发送 OPTIONS（不带认证）：
+///    `/remote.php/dav/files/<user>/`、`/remote.php/webdav/`、`/dav/`、`/webdav/`、`/`。
+///    响应带 `DAV` 头的端点优先，其次是要求认证（401）的端点
+use crate::webdav::client::server_type_from_headers;
+use crate::webdav::path::encode_segment;
+use crate::{Result, SyncError};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 发现结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryResult {
+    /// 推荐使用的 WebDAV 地址
+    pub url: String,
+    /// 服务器类型（nextcloud, owncloud, apache, nginx, generic）
+    pub server_type: String,
+    /// 端点是否通过 `DAV` 响应头确认支持 WebDAV（否则只是要求认证，需要连接测试确认）
+    pub confirmed: bool,
+}
+
+/// 候选端点的探测结果，越大越可信
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Evidence {
+    /// 不是 WebDAV 端点
+    None,
+    /// 要求认证，可能是 WebDAV 端点
+    AuthRequired,
+    /// 响应带 `DAV` 头
+    Dav,
+}
+
+/// 从主机名或地址发现 WebDAV 端点
+///
+/// # 参数
+/// - input: 用户输入的主机名或地址（如 `cloud.example.com`、`https://example.com/nextcloud`）
+/// - username: 用户名，用于拼接 Nextcloud / ownCloud 的 `/remote.php/dav/files/<user>/`
+/// - timeout: 单个请求的超时时间
+///
+/// # 返回
+/// - Ok(DiscoveryResult): 推荐的地址与服务器类型
+/// - Err(SyncError::ValidationError): 输入不是有效的主机名或 http(s) 地址
+/// - Err(SyncError::Network): 无法连接服务器
+/// - Err(SyncError::WebDav): 服务器可以连接，但没有找到 WebDAV 端点
+pub async fn discover(
+    input: &str,
+    username: Option<&str>,
+    timeout: Duration,
+) -> Result<DiscoveryResult> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| SyncError::Network(format!("Failed to create HTTP client: {}", e)))?;
+    let username = username.map(str::trim).filter(|name| !name.is_empty());
+
+    let mut last_error = None;
+    for base in base_urls(input)? {
+        match discover_at(&client, &base, username).await {
+            Err(SyncError::Network(message)) => {
+                tracing::debug!(base = %base, error = %message, "WebDAV 地址探测无法连接");
+                last_error = Some(SyncError::Network(message));
+            }
+            result => return result,
+        }
+    }
+
+    Err(last_error
+        .unwrap_or_else(|| SyncError::Network(format!("Failed to connect to server: {}", input))))
+}
+
+/// 在指定根地址下探测 WebDAV 端点
+async fn discover_at(
+    client: &reqwest::Client,
+    base: &str,
+    username: Option<&str>,
+) -> Result<DiscoveryResult> {
+    let install_base = well_known_base(client, base).await?;
+    let product = server_product(client, install_base.as_deref().unwrap_or(base)).await;
+    let hinted = install_base.is_some() || product.is_some();
+
+    let mut best: Option<(Evidence, String, String)> = None;
+    for url in candidate_urls(base, install_base.as_deref(), username, hinted) {
+        let response = match client.request(Method::OPTIONS, &url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::debug!(url = %url, error = %e, "WebDAV 候选端点探测失败");
+                continue;
+            }
+        };
+
+        let evidence = evidence(&response);
+        if evidence > best.as_ref().map_or(Evidence::None, |(e, _, _)| *e) {
+            let server_type = server_type_from_headers(response.headers());
+            best = Some((evidence, url, server_type));
+            if evidence == Evidence::Dav {
+                break;
+            }
+        }
+    }
+
+    let (evidence, url, header_type) =
+        best.ok_or_else(|| SyncError::WebDav(format!("No WebDAV endpoint found at {}", base)))?;
+
+    Ok(DiscoveryResult {
+        url,
+        // Nextcloud 通常部署在 nginx / Apache 之后，响应头只能识别出前端服务器
+        server_type: product.unwrap_or(header_type),
+        confirmed: evidence == Evidence::Dav,
+    })
+}
+
+/// 规范化用户输入，返回需要依次尝试的根地址（不以 `/` 结尾）
+fn base_urls(input: &str) -> Result<Vec<String>> {
+    let input = input.trim().trim_end_matches('/');
+    if input.is_empty() {
+        return Err(SyncError::ValidationError(
+            "Server address cannot be empty".to_string(),
+        ));
+    }
+
+    let candidates = match input.split_once("://") {
+        Some((scheme, rest)) => {
+            let scheme = scheme.to_lowercase();
+            if scheme != "http" && scheme != "https" {
+                return Err(SyncError::ValidationError(format!(
+                    "Unsupported URL scheme: {}",
+                    scheme
+                )));
+            }
+            vec![format!("{}://{}", scheme, rest)]
+        }
+        None => vec![format!("https://{}", input), format!("http://{}", input)],
+    };
+
+    for candidate in &candidates {
+        url::Url::parse(candidate).map_err(|e| {
+            SyncError::ValidationError(format!("Invalid server address {}: {}", input, e))
+        })?;
+    }
+
+    Ok(candidates)
+}
+
+/// 通过 `/.well-known/caldav` 重定向获取 Nextcloud / ownCloud 的安装地址
+///
+/// # 返回
+/// - Ok(Some(String)): 重定向到 `/remote.php/...`，返回 `remote.php` 之前的部分
+/// - Ok(None): 没有重定向到 `remote.php`
+/// - Err(SyncError::Network): 无法连接服务器
+async fn well_known_base(client: &reqwest::Client, base: &str) -> Result<Option<String>> {
+    let well_known = format!("{}/.well-known/caldav", base);
+    let response = client
+        .get(&well_known)
+        .send()
+        .await
+        .map_err(|e| SyncError::Network(format!("Failed to connect to {}: {}", base, e)))?;
+
+    if !response.status().is_redirection() {
+        return Ok(None);
+    }
+
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| response.url().join(value).ok());
+
+    Ok(location.and_then(|location| {
+        let location = location.as_str();
+        location
+            .find("/remote.php")
+            .map(|idx| location[..idx].to_string())
+    }))
+}
+
+/// 通过 `/status.php` 识别 Nextcloud / ownCloud
+async fn server_product(client: &reqwest::Client, base: &str) -> Option<String> {
+    let response = client
+        .get(format!("{}/status.php", base))
+        .send()
+        .await
+        .ok()
+        .filter(|response| response.status() == StatusCode::OK)?;
+    let status: serde_json::Value = response.json().await.ok()?;
+    let product = status.get("productname")?.as_str()?.to_lowercase();
+
+    if product.contains("nextcloud") {
+        Some("nextcloud".to_string())
+    } else if product.contains("owncloud") {
+        Some("owncloud".to_string())
+    } else {
+        None
+    }
+}
+
+/// 按优先级生成候选端点
+///
+/// 识别出 Nextcloud / ownCloud 时优先尝试 `remote.php` 端点，否则优先尝试通用路径
+fn candidate_urls(
+    base: &str,
+    install_base: Option<&str>,
+    username: Option<&str>,
+    hinted: bool,
+) -> Vec<String> {
+    let install_base = install_base.unwrap_or(base);
+    let mut remote = Vec::new();
+    if let Some(username) = username {
+        remote.push(format!(
+            "{}/remote.php/dav/files/{}/",
+            install_base,
+            encode_segment(username)
+        ));
+    }
+    remote.push(format!("{}/remote.php/webdav/", install_base));
+    let generic = vec![format!("{}/dav/", base), format!("{}/webdav/", base)];
+
+    let mut urls = if hinted {
+        [remote, generic].concat()
+    } else {
+        [generic, remote].concat()
+    };
+    urls.push(format!("{}/", base));
+    urls
+}
+
+/// 根据 OPTIONS 响应判断端点是否为 WebDAV 端点
+fn evidence(response: &reqwest::Response) -> Evidence {
+    let status = response.status();
+    let auth_required = status == StatusCode::UNAUTHORIZED;
+
+    if response.headers().contains_key("dav") && (status.is_success() || auth_required) {
+        Evidence::Dav
+    } else if auth_required {
+        Evidence::AuthRequired
+    } else {
+        Evidence::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_base_urls() {
+        assert_eq!(
+            base_urls(" cloud.example.com/ ").unwrap(),
+            vec!["https://cloud.example.com", "http://cloud.example.com"]
+        );
+        assert_eq!(
+            base_urls("HTTP://nas.local:5005/").unwrap(),
+            vec!["http://nas.local:5005"]
+        );
+        assert!(matches!(
+            base_urls("ftp://example.com"),
+            Err(SyncError::ValidationError(_))
+        ));
+        assert!(matches!(
+            base_urls("  "),
+            Err(SyncError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_discover_nextcloud() {
+        let mut server = mockito::Server::new_async().await;
+        let _well_known = server
+            .mock("GET", "/.well-known/caldav")
+            .with_status(301)
+            .with_header("location", "/cloud/remote.php/dav/")
+            .create_async()
+            .await;
+        let _status = server
+            .mock("GET", "/cloud/status.php")
+            .with_status(200)
+            .with_body(r#"{"installed":true,"productname":"Nextcloud","version":"28.0.1.1"}"#)
+            .create_async()
+            .await;
+        let options = server
+            .mock("OPTIONS", "/cloud/remote.php/dav/files/alice%20b/")
+            .with_status(401)
+            .with_header("server", "nginx")
+            .with_header("dav", "1, 3, extended-mkcol")
+            .create_async()
+            .await;
+
+        let result = discover(&server.url(), Some("alice b"), TIMEOUT)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.url,
+            format!("{}/cloud/remote.php/dav/files/alice%20b/", server.url())
+        );
+        assert_eq!(result.server_type, "nextcloud");
+        assert!(result.confirmed);
+        options.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_generic() {
+        let mut server = mockito::Server::new_async().await;
+        let _dav = server
+            .mock("OPTIONS", "/dav/")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _webdav = server
+            .mock("OPTIONS", "/webdav/")
+            .with_status(200)
+            .with_header("server", "Apache/2.4.57")
+            .with_header("dav", "1,2")
+            .create_async()
+            .await;
+
+        let result = discover(&server.url(), None, TIMEOUT).await.unwrap();
+
+        assert_eq!(result.url, format!("{}/webdav/", server.url()));
+        assert_eq!(result.server_type, "apache");
+        assert!(result.confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_discover_auth_required_and_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let _root = server
+            .mock("OPTIONS", "/")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let result = discover(&server.url(), None, TIMEOUT).await.unwrap();
+        assert_eq!(result.url, format!("{}/", server.url()));
+        assert_eq!(result.server_type, "generic");
+        assert!(!result.confirmed);
+
+        let empty = mockito::Server::new_async().await;
+        assert!(matches!(
+            discover(&empty.url(), None, TIMEOUT).await,
+            Err(SyncError::WebDav(_))
+        ));
+    }
+}
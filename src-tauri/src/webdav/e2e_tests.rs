@@ -13,6 +13,7 @@
 mod tests {
     use crate::database::WebDavServerConfig;
     use crate::webdav::keyring::KeyringManager;
+    use crate::webdav::mock_server_config;
     use rusqlite::Connection;
     use std::fs;
     use std::path::PathBuf;
@@ -131,17 +132,12 @@ mod tests {
             let config = WebDavServerConfig {
                 id: server_id.clone(),
                 name: name.to_string(),
-                url: url.to_string(),
                 username: username.to_string(),
                 use_https: *use_https,
                 timeout: *timeout,
-                last_test_at: None,
-                last_test_status: "unknown".to_string(),
-                last_test_error: None,
-                server_type: "generic".to_string(),
-                enabled: true,
                 created_at: now,
                 updated_at: now,
+                ..mock_server_config(url)
             };
 
             // 3. 插入数据库
@@ -201,6 +197,7 @@ mod tests {
                             enabled: row.get::<_, i32>(10)? != 0,
                             created_at: row.get(11)?,
                             updated_at: row.get(12)?,
+                            ..mock_server_config("")
                         })
                     },
                 )
@@ -246,6 +243,7 @@ mod tests {
                         enabled: row.get::<_, i32>(10)? != 0,
                         created_at: row.get(11)?,
                         updated_at: row.get(12)?,
+                        ..mock_server_config("")
                     })
                 })
                 .unwrap()
@@ -281,17 +279,12 @@ mod tests {
                 WebDavServerConfig {
                     id: Uuid::new_v4().to_string(),
                     name: "Test".to_string(),
-                    url: "".to_string(), // 空 URL
                     username: "user".to_string(),
                     use_https: true,
                     timeout: 30,
-                    last_test_at: None,
-                    last_test_status: "unknown".to_string(),
-                    last_test_error: None,
-                    server_type: "generic".to_string(),
-                    enabled: true,
                     created_at: now,
                     updated_at: now,
+                    ..mock_server_config("")
                 },
             ),
             (
@@ -299,17 +292,12 @@ mod tests {
                 WebDavServerConfig {
                     id: Uuid::new_v4().to_string(),
                     name: "Test".to_string(),
-                    url: "not-a-url".to_string(), // 无效 URL
                     username: "user".to_string(),
                     use_https: true,
                     timeout: 30,
-                    last_test_at: None,
-                    last_test_status: "unknown".to_string(),
-                    last_test_error: None,
-                    server_type: "generic".to_string(),
-                    enabled: true,
                     created_at: now,
                     updated_at: now,
+                    ..mock_server_config("not-a-url")
                 },
             ),
             (
@@ -317,17 +305,12 @@ mod tests {
                 WebDavServerConfig {
                     id: Uuid::new_v4().to_string(),
                     name: "Test".to_string(),
-                    url: "https://example.com".to_string(),
                     username: "".to_string(), // 空用户名
                     use_https: true,
                     timeout: 30,
-                    last_test_at: None,
-                    last_test_status: "unknown".to_string(),
-                    last_test_error: None,
-                    server_type: "generic".to_string(),
-                    enabled: true,
                     created_at: now,
                     updated_at: now,
+                    ..mock_server_config("https://example.com")
                 },
             ),
             (
@@ -335,17 +318,12 @@ mod tests {
                 WebDavServerConfig {
                     id: Uuid::new_v4().to_string(),
                     name: "Test".to_string(),
-                    url: "https://example.com".to_string(),
                     username: "user".to_string(),
                     use_https: true,
                     timeout: 0, // 超时时间太小
-                    last_test_at: None,
-                    last_test_status: "unknown".to_string(),
-                    last_test_error: None,
-                    server_type: "generic".to_string(),
-                    enabled: true,
                     created_at: now,
                     updated_at: now,
+                    ..mock_server_config("https://example.com")
                 },
             ),
             (
@@ -353,17 +331,12 @@ mod tests {
                 WebDavServerConfig {
                     id: Uuid::new_v4().to_string(),
                     name: "Test".to_string(),
-                    url: "https://example.com".to_string(),
                     username: "user".to_string(),
                     use_https: true,
                     timeout: 301, // 超时时间太大
-                    last_test_at: None,
-                    last_test_status: "unknown".to_string(),
-                    last_test_error: None,
-                    server_type: "generic".to_string(),
-                    enabled: true,
                     created_at: now,
                     updated_at: now,
+                    ..mock_server_config("https://example.com")
                 },
             ),
         ];
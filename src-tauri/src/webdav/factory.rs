@@ -0,0 +1,316 @@
+/// WebDAV 客户端工厂
+///
+/// 按服务器 ID 缓存 WebDavClient，同步、浏览和手动传输共享同一个 reqwest 连接池，
+/// 避免每次调用命令都重新建立连接和 TLS 握手。
+///
+/// 获取客户端时仍会读取数据库中的服务器配置和 Keyring 中的密码（应用锁定时无法读取），
+/// 与缓存客户端创建时的连接参数比较，任一变化都会重新创建客户端；
+/// 修改、删除或归档服务器时也会主动使缓存失效。
+///
+/// 设置了 Cookie 会话存储时，新客户端附加服务器的 Cookie 罐（同一服务器的客户端共享）
+///
+/// 新客户端使用数据库中记住的 PROPFIND 请求方式；缓存的客户端改用了兼容方式时，
+/// 下次获取时保存到数据库（见 `webdav::client::PropfindDialect`）
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::database::{CustomHeaders, Database, WebDavServerConfig};
+use crate::webdav::client::{PropfindDialect, WebDavClient};
+use crate::webdav::cookies::CookieStore;
+use crate::webdav::db;
+use crate::webdav::keyring::KeyringManager;
+use crate::{Result, SyncError};
+
+/// 创建客户端时使用的连接参数
+///
+/// 测试状态、名称等字段的变化不影响客户端，不会导致重新创建
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClientKey {
+    url: String,
+    username: String,
+    password: String,
+    timeout: u32,
+    connect_timeout: u32,
+    read_timeout: u32,
+    accept_invalid_certs: bool,
+    pinned_cert_fingerprint: Option<String>,
+    custom_headers: CustomHeaders,
+    ip_version: String,
+    resolve_ip: Option<String>,
+    server_type: String,
+    auth_type: String,
+}
+
+impl ClientKey {
+    fn new(config: &WebDavServerConfig, password: &str) -> Self {
+        Self {
+            url: config.resolved_url(),
+            username: config.username.clone(),
+            password: password.to_string(),
+            timeout: config.timeout,
+            accept_invalid_certs: config.accept_invalid_certs,
+            pinned_cert_fingerprint: config.pinned_cert_fingerprint.clone(),
+            custom_headers: config.custom_headers.clone(),
+            ip_version: config.ip_version.clone(),
+            resolve_ip: config.resolve_ip.clone(),
+            connect_timeout: config.connect_timeout,
+            read_timeout: config.read_timeout,
+            server_type: config.server_type.clone(),
+            auth_type: config.auth_type.clone(),
+        }
+    }
+}
+
+/// 缓存的客户端
+#[derive(Debug)]
+struct CachedClient {
+    key: ClientKey,
+    client: WebDavClient,
+    /// 数据库中保存的 PROPFIND 请求方式
+    saved_dialect: PropfindDialect,
+}
+
+/// WebDAV 客户端工厂（作为 Tauri State 管理）
+#[derive(Debug, Default)]
+pub struct WebDavClientFactory {
+    clients: Mutex<HashMap<String, CachedClient>>,
+    cookies: Option<CookieStore>,
+}
+
+impl WebDavClientFactory {
+    /// 创建使用 Cookie 会话存储的工厂
+    pub fn with_cookie_store(cookies: CookieStore) -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            cookies: Some(cookies),
+        }
+    }
+
+    /// 获取服务器的客户端，没有可用的缓存时创建新客户端
+    ///
+    /// 新客户端在缓存前会校验固定的证书指纹
+    ///
+    /// # 参数
+    /// - db: 共享数据库连接
+    /// - server_id: 服务器 ID
+    ///
+    /// # 返回
+    /// - Ok((WebDavServerConfig, WebDavClient)): 服务器配置和客户端（与缓存共享连接池）
+    /// - Err(SyncError): 服务器或密码不存在、证书校验失败等（附带 serverId 上下文）
+    pub async fn get(
+        &self,
+        db: &Database,
+        server_id: &str,
+    ) -> Result<(WebDavServerConfig, WebDavClient)> {
+        let with_server = |e: SyncError| e.with_context("serverId", server_id);
+
+        let config = db::get_webdav_server_by_id(db, server_id)
+            .await
+            .map_err(with_server)?;
+        let password = KeyringManager::get_account_password(&config).map_err(with_server)?;
+        let key = ClientKey::new(&config, &password);
+
+        if let Some(client) = self.cached(server_id, &key)? {
+            if let Some(dialect) = self.changed_dialect(server_id) {
+                if let Err(e) = db::set_propfind_dialect(db, server_id, dialect.as_str()).await {
+                    tracing::warn!(server_id = %server_id, error = %e, "保存 PROPFIND 请求方式失败");
+                }
+            }
+            return Ok((config, client));
+        }
+
+        let saved_dialect = PropfindDialect::from_config(
+            &db::get_propfind_dialect(db, server_id)
+                .await
+                .map_err(with_server)?,
+        );
+        let mut client = WebDavClient::new(&config, password)
+            .map_err(with_server)?
+            .with_propfind_dialect(saved_dialect);
+        client.verify_certificate().await.map_err(with_server)?;
+        if let Some(cookies) = &self.cookies {
+            client = client.with_cookies(cookies.jar(server_id).map_err(with_server)?);
+        }
+
+        self.lock()?.insert(
+            server_id.to_string(),
+            CachedClient {
+                key,
+                client: client.clone(),
+                saved_dialect,
+            },
+        );
+        tracing::debug!(server_id = %server_id, "已创建 WebDAV 客户端");
+
+        Ok((config, client))
+    }
+
+    /// 向所有缓存的客户端发送保持连接请求
+    ///
+    /// 只针对最近使用过的服务器（已缓存客户端），请求并发发送，失败只记录调试日志
+    ///
+    /// # 返回
+    /// 成功收到响应的服务器数量
+    pub async fn keep_alive(&self) -> usize {
+        let clients: Vec<(String, WebDavClient)> = match self.lock() {
+            Ok(clients) => clients
+                .iter()
+                .map(|(server_id, cached)| (server_id.clone(), cached.client.clone()))
+                .collect(),
+            Err(_) => return 0,
+        };
+
+        let results =
+            futures_util::future::join_all(clients.iter().map(|(server_id, client)| async move {
+                let result = client.ping().await;
+                if let Err(e) = &result {
+                    tracing::debug!(server_id = %server_id, error = %e, "保持连接请求失败");
+                }
+                result.is_ok()
+            }))
+            .await;
+        results.into_iter().filter(|ok| *ok).count()
+    }
+
+    /// 使服务器的缓存客户端失效
+    ///
+    /// 修改、删除或归档服务器后调用，下次获取时重新创建客户端
+    pub fn invalidate(&self, server_id: &str) {
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.remove(server_id);
+        }
+    }
+
+    /// 连接参数一致时返回缓存的客户端，不一致时移除缓存
+    fn cached(&self, server_id: &str, key: &ClientKey) -> Result<Option<WebDavClient>> {
+        let mut clients = self.lock()?;
+        match clients.get(server_id) {
+            Some(cached) if cached.key == *key => Ok(Some(cached.client.clone())),
+            Some(_) => {
+                clients.remove(server_id);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 缓存的客户端改用了新的 PROPFIND 请求方式时返回该方式，并标记为已保存
+    fn changed_dialect(&self, server_id: &str) -> Option<PropfindDialect> {
+        let mut clients = self.lock().ok()?;
+        let cached = clients.get_mut(server_id)?;
+        let dialect = cached.client.propfind_dialect();
+        if dialect == cached.saved_dialect {
+            return None;
+        }
+        cached.saved_dialect = dialect;
+        Some(dialect)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, CachedClient>>> {
+        self.clients
+            .lock()
+            .map_err(|e| SyncError::Unknown(format!("Client cache lock poisoned: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webdav::mock_server_config;
+
+    #[test]
+    fn test_cached_client_invalidation() {
+        let factory = WebDavClientFactory::default();
+        let server = mock_server_config("http://localhost/dav");
+        let key = ClientKey::new(&server, "password");
+        let client = WebDavClient::new(&server, "password".to_string()).unwrap();
+        factory.lock().unwrap().insert(
+            server.id.clone(),
+            CachedClient {
+                key: key.clone(),
+                client,
+                saved_dialect: PropfindDialect::Standard,
+            },
+        );
+
+        // 测试状态等无关字段变化时复用缓存
+        let mut tested = server.clone();
+        tested.last_test_status = "success".to_string();
+        tested.name = "Renamed".to_string();
+        assert!(factory
+            .cached(&server.id, &ClientKey::new(&tested, "password"))
+            .unwrap()
+            .is_some());
+
+        // 密码变化时移除缓存
+        assert!(factory
+            .cached(&server.id, &ClientKey::new(&server, "new-password"))
+            .unwrap()
+            .is_none());
+        assert!(factory.cached(&server.id, &key).unwrap().is_none());
+
+        factory.lock().unwrap().insert(
+            server.id.clone(),
+            CachedClient {
+                key: key.clone(),
+                client: WebDavClient::new(&server, "password".to_string()).unwrap(),
+                saved_dialect: PropfindDialect::Standard,
+            },
+        );
+        factory.invalidate(&server.id);
+        assert!(factory.cached(&server.id, &key).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_changed_dialect() {
+        let factory = WebDavClientFactory::default();
+        let server = mock_server_config("http://localhost/dav");
+        let client = WebDavClient::new(&server, "password".to_string()).unwrap();
+        factory.lock().unwrap().insert(
+            server.id.clone(),
+            CachedClient {
+                key: ClientKey::new(&server, "password"),
+                client: client.clone(),
+                saved_dialect: PropfindDialect::Standard,
+            },
+        );
+        assert_eq!(factory.changed_dialect(&server.id), None);
+
+        // 克隆的客户端共享请求方式
+        let _ = client.with_propfind_dialect(PropfindDialect::AllProp);
+        assert_eq!(
+            factory.changed_dialect(&server.id),
+            Some(PropfindDialect::AllProp)
+        );
+        assert_eq!(factory.changed_dialect(&server.id), None);
+        assert_eq!(factory.changed_dialect("missing"), None);
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_pings_cached_clients() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("OPTIONS", "/dav")
+            .with_status(200)
+            .with_header("dav", "1, 2")
+            .create_async()
+            .await;
+
+        let factory = WebDavClientFactory::default();
+        assert_eq!(factory.keep_alive().await, 0);
+
+        let server_config = mock_server_config(&format!("{}/dav", server.url()));
+        let client = WebDavClient::new(&server_config, "password".to_string()).unwrap();
+        factory.lock().unwrap().insert(
+            server_config.id.clone(),
+            CachedClient {
+                key: ClientKey::new(&server_config, "password"),
+                client,
+                saved_dialect: PropfindDialect::Standard,
+            },
+        );
+
+        assert_eq!(factory.keep_alive().await, 1);
+        mock.assert_async().await;
+    }
+}
@@ -6,9 +6,11 @@
 /// # 设计说明
 ///
 /// - 使用 `keyring` crate 与系统 Keyring 交互
-/// - 每个服务器的密码使用服务器 ID 作为 key
+/// - 每个服务器账户的密码使用 `{服务器 ID}:{用户名}` 作为 key（见 `account_entry`），
+///   同一主机上的多个账户互不影响；旧版本按服务器 ID 保存的密码在首次读取时迁移
 /// - 服务名称固定为 "LightSync"，便于识别
-/// - 处理 keyring 不可用的情况（某些系统或环境）
+/// - 处理 keyring 不可用的情况（某些系统或环境）：应用启动时调用 `init_fallback` 后，
+///   Keyring 操作失败时自动改用应用数据目录下的加密文件（见 `credential_store`）
 ///
 /// # 使用示例
 ///
@@ -22,8 +24,23 @@
 /// // 删除密码
 /// KeyringManager::delete_password("server-uuid-1")?;
 /// ```
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Manager};
+
+use crate::constants::CREDENTIALS_FILE;
+use crate::database::WebDavServerConfig;
+use crate::webdav::credential_store::CredentialStore;
 use crate::{Result, SyncError};
 
+/// Keyring 不可用时使用的加密文件存储（未初始化时不启用）
+static FALLBACK: OnceLock<CredentialStore> = OnceLock::new();
+
+/// 服务器账户密码在 Keyring 中的条目名
+pub fn account_entry(server_id: &str, username: &str) -> String {
+    format!("{}:{}", server_id, username.trim())
+}
+
 /// WebDAV 服务器密码管理器
 ///
 /// 提供安全的密码存储和检索功能
@@ -33,6 +50,41 @@ impl KeyringManager {
     /// Keyring 服务名称
     const SERVICE_NAME: &'static str = "LightSync";
 
+    /// 启用加密文件备用存储
+    ///
+    /// 在应用启动时调用，之后 Keyring 操作失败时自动改用应用数据目录下的加密文件
+    ///
+    /// # 参数
+    /// - locked: 是否设置了主密码（设置了主密码时备用存储在解锁应用后才能读写）
+    pub fn init_fallback(app: &AppHandle, locked: bool) -> Result<()> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        let path = app_dir.join(CREDENTIALS_FILE);
+        let store = if locked {
+            CredentialStore::locked(path)
+        } else {
+            CredentialStore::with_default_key(path)
+        };
+        let _ = FALLBACK.set(store);
+        Ok(())
+    }
+
+    /// 获取加密文件备用存储（未启用时返回 None）
+    pub fn fallback() -> Option<&'static CredentialStore> {
+        FALLBACK.get()
+    }
+
+    /// 获取备用存储（Keyring 出错且已启用备用存储时返回）
+    fn fallback_for(error: &SyncError) -> Option<&'static CredentialStore> {
+        match error.root() {
+            SyncError::KeyringError(_) => FALLBACK.get(),
+            _ => None,
+        }
+    }
+
     /// 保存密码到系统 Keyring
     ///
     /// # 参数
@@ -44,8 +96,8 @@ impl KeyringManager {
     /// - Err(SyncError): 保存失败
     ///
     /// # 错误处理
-    /// - 如果 Keyring 不可用，返回 ConfigError
-    /// - 如果密码为空，返回 ConfigError
+    /// - 如果 Keyring 不可用，返回 KeyringError（已启用备用存储时改为保存到加密文件）
+    /// - 如果 server_id 或密码为空，返回 ValidationError
     ///
     /// # 注意
     /// - 如果相同的 server_id 已存在密码，会覆盖旧密码
@@ -53,25 +105,41 @@ impl KeyringManager {
     pub fn save_password(server_id: &str, password: &str) -> Result<()> {
         // 验证输入
         if server_id.trim().is_empty() {
-            return Err(SyncError::ConfigError(
+            return Err(SyncError::ValidationError(
                 "Server ID cannot be empty".to_string(),
             ));
         }
 
         if password.is_empty() {
-            return Err(SyncError::ConfigError(
+            return Err(SyncError::ValidationError(
                 "Password cannot be empty".to_string(),
             ));
         }
 
+        match Self::keyring_save(server_id, password) {
+            Ok(()) => Ok(()),
+            Err(e) => match Self::fallback_for(&e) {
+                Some(store) => {
+                    tracing::warn!(server_id, error = %e, "系统 Keyring 不可用，密码保存到加密文件");
+                    store.set(server_id, password)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// 保存密码到系统 Keyring（不使用备用存储）
+    fn keyring_save(server_id: &str, password: &str) -> Result<()> {
         // 创建 Keyring 条目
         let entry = keyring::Entry::new(Self::SERVICE_NAME, server_id).map_err(|e| {
-            SyncError::ConfigError(format!("Failed to create keyring entry: {}", e))
+            SyncError::KeyringError(format!("Failed to create keyring entry: {}", e))
+                .with_context("serverId", server_id)
         })?;
 
         // 保存密码
         entry.set_password(password).map_err(|e| {
-            SyncError::ConfigError(format!("Failed to save password to keyring: {}", e))
+            SyncError::KeyringError(format!("Failed to save password to keyring: {}", e))
+                .with_context("serverId", server_id)
         })?;
 
         Ok(())
@@ -87,9 +155,9 @@ impl KeyringManager {
     /// - Err(SyncError): 读取失败
     ///
     /// # 错误处理
-    /// - 如果 Keyring 不可用，返回 ConfigError
+    /// - 如果 Keyring 不可用，返回 KeyringError（已启用备用存储时改为从加密文件读取）
     /// - 如果密码不存在，返回 NotFound
-    /// - 如果 server_id 为空，返回 ConfigError
+    /// - 如果 server_id 为空，返回 ValidationError
     ///
     /// # 注意
     /// - 返回的密码是明文，调用者需要妥善处理
@@ -97,14 +165,37 @@ impl KeyringManager {
     pub fn get_password(server_id: &str) -> Result<String> {
         // 验证输入
         if server_id.trim().is_empty() {
-            return Err(SyncError::ConfigError(
+            return Err(SyncError::ValidationError(
                 "Server ID cannot be empty".to_string(),
             ));
         }
 
+        match Self::keyring_get(server_id) {
+            Ok(password) => Ok(password),
+            // Keyring 中没有时，密码可能是在 Keyring 不可用时保存到了加密文件
+            Err(SyncError::NotFound(msg)) => match FALLBACK.get() {
+                Some(store) => store
+                    .get(server_id)
+                    .ok()
+                    .flatten()
+                    .ok_or(SyncError::NotFound(msg)),
+                None => Err(SyncError::NotFound(msg)),
+            },
+            Err(e) => match Self::fallback_for(&e) {
+                Some(store) => store.get(server_id)?.ok_or_else(|| {
+                    SyncError::NotFound(format!("Password not found for server: {}", server_id))
+                }),
+                None => Err(e),
+            },
+        }
+    }
+
+    /// 从系统 Keyring 读取密码（不使用备用存储）
+    fn keyring_get(server_id: &str) -> Result<String> {
         // 创建 Keyring 条目
         let entry = keyring::Entry::new(Self::SERVICE_NAME, server_id).map_err(|e| {
-            SyncError::ConfigError(format!("Failed to create keyring entry: {}", e))
+            SyncError::KeyringError(format!("Failed to create keyring entry: {}", e))
+                .with_context("serverId", server_id)
         })?;
 
         // 读取密码
@@ -114,7 +205,10 @@ impl KeyringManager {
                 keyring::Error::NoEntry => {
                     SyncError::NotFound(format!("Password not found for server: {}", server_id))
                 }
-                _ => SyncError::ConfigError(format!("Failed to read password from keyring: {}", e)),
+                _ => {
+                    SyncError::KeyringError(format!("Failed to read password from keyring: {}", e))
+                        .with_context("serverId", server_id)
+                }
             }
         })
     }
@@ -129,24 +223,103 @@ impl KeyringManager {
     /// - Err(SyncError): 删除失败
     ///
     /// # 错误处理
-    /// - 如果 Keyring 不可用，返回 ConfigError
+    /// - 如果 Keyring 不可用，返回 KeyringError
     /// - 如果密码不存在，返回 NotFound
-    /// - 如果 server_id 为空，返回 ConfigError
+    /// - 如果 server_id 为空，返回 ValidationError
     ///
     /// # 注意
+    /// - 已启用备用存储时，Keyring 和加密文件中的密码都会被删除
     /// - 删除不存在的密码会返回 NotFound 错误
     /// - 删除后无法恢复，请谨慎操作
     pub fn delete_password(server_id: &str) -> Result<()> {
         // 验证输入
         if server_id.trim().is_empty() {
-            return Err(SyncError::ConfigError(
+            return Err(SyncError::ValidationError(
                 "Server ID cannot be empty".to_string(),
             ));
         }
 
+        let result = Self::keyring_delete(server_id);
+        let Some(store) = FALLBACK.get() else {
+            return result;
+        };
+
+        // 两处存储中的密码都删除，任意一处删除成功即视为成功
+        match (result, store.delete(server_id)) {
+            (Ok(()), _) | (_, Ok(true)) => Ok(()),
+            (Err(SyncError::NotFound(msg)), _) => Err(SyncError::NotFound(msg)),
+            (Err(e), Ok(false)) if Self::fallback_for(&e).is_some() => Err(SyncError::NotFound(
+                format!("Password not found for server: {}", server_id),
+            )),
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// 保存服务器账户的密码
+    ///
+    /// # 参数
+    /// - config: 服务器配置（使用其 ID 与用户名定位账户）
+    /// - password: 要保存的密码
+    pub fn save_account_password(config: &WebDavServerConfig, password: &str) -> Result<()> {
+        Self::save_password(&account_entry(&config.id, &config.username), password)
+    }
+
+    /// 读取服务器账户的密码
+    ///
+    /// 账户条目不存在时读取旧版本按服务器 ID 保存的密码，并迁移到账户条目
+    ///
+    /// # 参数
+    /// - config: 服务器配置（使用其 ID 与用户名定位账户）
+    ///
+    /// # 返回
+    /// - Ok(String): 读取成功，返回密码
+    /// - Err(SyncError::NotFound): 账户没有保存密码
+    /// - Err(SyncError): 读取失败
+    pub fn get_account_password(config: &WebDavServerConfig) -> Result<String> {
+        let entry = account_entry(&config.id, &config.username);
+        let legacy = match Self::get_password(&entry) {
+            Err(SyncError::NotFound(msg)) => match Self::get_password(&config.id) {
+                Ok(password) => password,
+                Err(SyncError::NotFound(_)) => return Err(SyncError::NotFound(msg)),
+                Err(e) => return Err(e),
+            },
+            result => return result,
+        };
+
+        match Self::save_password(&entry, &legacy) {
+            Ok(()) => {
+                let _ = Self::delete_password(&config.id);
+            }
+            Err(e) => {
+                tracing::warn!(server_id = %config.id, error = %e, "迁移服务器密码到账户条目失败");
+            }
+        }
+        Ok(legacy)
+    }
+
+    /// 删除服务器账户的密码（同时删除旧版本按服务器 ID 保存的密码）
+    ///
+    /// # 参数
+    /// - config: 服务器配置（使用其 ID 与用户名定位账户）
+    ///
+    /// # 返回
+    /// - Ok(()): 删除成功
+    /// - Err(SyncError::NotFound): 账户没有保存密码
+    /// - Err(SyncError): 删除失败
+    pub fn delete_account_password(config: &WebDavServerConfig) -> Result<()> {
+        let result = Self::delete_password(&account_entry(&config.id, &config.username));
+        match (result, Self::delete_password(&config.id)) {
+            (Ok(()), _) | (Err(SyncError::NotFound(_)), Ok(())) => Ok(()),
+            (result, _) => result,
+        }
+    }
+
+    /// 从系统 Keyring 删除密码（不使用备用存储）
+    fn keyring_delete(server_id: &str) -> Result<()> {
         // 创建 Keyring 条目
         let entry = keyring::Entry::new(Self::SERVICE_NAME, server_id).map_err(|e| {
-            SyncError::ConfigError(format!("Failed to create keyring entry: {}", e))
+            SyncError::KeyringError(format!("Failed to create keyring entry: {}", e))
+                .with_context("serverId", server_id)
         })?;
 
         // 删除密码
@@ -156,9 +329,11 @@ impl KeyringManager {
                 keyring::Error::NoEntry => {
                     SyncError::NotFound(format!("Password not found for server: {}", server_id))
                 }
-                _ => {
-                    SyncError::ConfigError(format!("Failed to delete password from keyring: {}", e))
-                }
+                _ => SyncError::KeyringError(format!(
+                    "Failed to delete password from keyring: {}",
+                    e
+                ))
+                .with_context("serverId", server_id),
             }
         })?;
 
@@ -199,7 +374,7 @@ mod tests {
     fn test_save_password_empty_server_id() {
         let result = KeyringManager::save_password("", "password");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     #[test]
@@ -207,14 +382,14 @@ mod tests {
         let server_id = generate_test_server_id();
         let result = KeyringManager::save_password(&server_id, "");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     #[test]
     fn test_save_password_whitespace_server_id() {
         let result = KeyringManager::save_password("   ", "password");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     #[test]
@@ -248,14 +423,14 @@ mod tests {
     fn test_get_password_empty_server_id() {
         let result = KeyringManager::get_password("");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     #[test]
     fn test_get_password_whitespace_server_id() {
         let result = KeyringManager::get_password("   ");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     #[test]
@@ -298,14 +473,14 @@ mod tests {
     fn test_delete_password_empty_server_id() {
         let result = KeyringManager::delete_password("");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     #[test]
     fn test_delete_password_whitespace_server_id() {
         let result = KeyringManager::delete_password("   ");
         assert!(result.is_err());
-        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+        assert!(matches!(result, Err(SyncError::ValidationError(_))));
     }
 
     /// Property 3: 密码安全存储 Round-Trip
@@ -411,4 +586,56 @@ mod tests {
         // 清理
         cleanup_test_password(&server_id);
     }
+
+    #[test]
+    fn test_account_passwords_and_legacy_migration() {
+        let server_id = generate_test_server_id();
+        let mut config: WebDavServerConfig = serde_json::from_value(serde_json::json!({
+            "id": server_id,
+            "name": "Test Server",
+            "url": "https://cloud.example.com/remote.php/dav/files/{username}/",
+            "username": "alice",
+            "useHttps": true,
+            "timeout": 30,
+            "lastTestAt": null,
+            "lastTestStatus": "unknown",
+            "lastTestError": null,
+            "serverType": "nextcloud",
+            "enabled": true,
+            "createdAt": 0,
+            "updatedAt": 0
+        }))
+        .unwrap();
+
+        // 旧版本按服务器 ID 保存的密码在读取时迁移到账户条目
+        KeyringManager::save_password(&server_id, "legacy-password").unwrap();
+        assert_eq!(
+            KeyringManager::get_account_password(&config).unwrap(),
+            "legacy-password"
+        );
+        assert!(matches!(
+            KeyringManager::get_password(&server_id),
+            Err(SyncError::NotFound(_))
+        ));
+
+        // 不同用户名的账户互不影响
+        config.username = "bob".to_string();
+        assert!(matches!(
+            KeyringManager::get_account_password(&config),
+            Err(SyncError::NotFound(_))
+        ));
+        KeyringManager::save_account_password(&config, "bob-password").unwrap();
+        assert_eq!(
+            KeyringManager::get_password(&account_entry(&server_id, "bob")).unwrap(),
+            "bob-password"
+        );
+
+        KeyringManager::delete_account_password(&config).unwrap();
+        config.username = "alice".to_string();
+        KeyringManager::delete_account_password(&config).unwrap();
+        assert!(matches!(
+            KeyringManager::delete_account_password(&config),
+            Err(SyncError::NotFound(_))
+        ));
+    }
 }
@@ -0,0 +1,292 @@
+/// Nextcloud Login Flow v2
+///
+/// 通过浏览器授权获取应用密码（app password），避免直接使用账户密码进行 Basic 认证
+///
+/// # 流程
+///
+/// 1. `start`：向 `{base}/index.php/login/v2` 发起 POST，获得登录页 URL 与轮询 token
+/// 2. 前端在浏览器中打开登录页，用户完成授权
+/// 3. `poll` / `wait_for_credentials`：轮询 `poll.endpoint`，授权完成后返回
+///    `server`、`loginName`、`appPassword`
+///
+/// 参考：https://docs.nextcloud.com/server/latest/developer_manual/client_apis/LoginFlow/index.html
+use crate::{Result, SyncError};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 发起登录流程时使用的 User-Agent（会显示在 Nextcloud 的设备列表中）
+const LOGIN_FLOW_USER_AGENT: &str = "LightSync";
+
+/// 登录流程初始化结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginFlowInit {
+    /// 需要在浏览器中打开的登录页 URL
+    pub login_url: String,
+    /// 轮询 token
+    pub poll_token: String,
+    /// 轮询地址
+    pub poll_endpoint: String,
+}
+
+/// 登录流程完成后获得的凭据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginFlowCredentials {
+    /// 服务器根地址
+    pub server: String,
+    /// 登录用户名
+    pub login_name: String,
+    /// 应用密码
+    pub app_password: String,
+}
+
+/// `POST /index.php/login/v2` 的响应
+#[derive(Debug, Deserialize)]
+struct InitResponse {
+    poll: PollInfo,
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PollInfo {
+    token: String,
+    endpoint: String,
+}
+
+/// 轮询成功时的响应
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PollResponse {
+    server: String,
+    login_name: String,
+    app_password: String,
+}
+
+/// 从 WebDAV URL 推导 Nextcloud 根地址
+///
+/// 例如 `https://cloud.example.com/remote.php/dav/files/alice/` -> `https://cloud.example.com`
+pub fn nextcloud_base_url(url: &str) -> String {
+    let trimmed = url.trim().trim_end_matches('/');
+    match trimmed.find("/remote.php") {
+        Some(idx) => trimmed[..idx].to_string(),
+        None => trimmed.to_string(),
+    }
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(LOGIN_FLOW_USER_AGENT)
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| SyncError::Network(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// 发起 Login Flow v2
+///
+/// # 参数
+/// - server_url: 服务器地址（可以是根地址，也可以是 WebDAV 地址）
+///
+/// # 返回
+/// - 成功：返回登录页 URL 与轮询信息
+/// - 失败：网络错误或服务器不支持登录流程
+pub async fn start(server_url: &str) -> Result<LoginFlowInit> {
+    let endpoint = format!("{}/index.php/login/v2", nextcloud_base_url(server_url));
+
+    let response = http_client()?
+        .post(&endpoint)
+        .send()
+        .await
+        .map_err(|e| SyncError::Network(format!("Failed to start login flow: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(SyncError::WebDav(format!(
+            "Server does not support Nextcloud login flow (status {})",
+            response.status().as_u16()
+        )));
+    }
+
+    let body: InitResponse = response
+        .json()
+        .await
+        .map_err(|e| SyncError::WebDav(format!("Invalid login flow response: {}", e)))?;
+
+    Ok(LoginFlowInit {
+        login_url: body.login,
+        poll_token: body.poll.token,
+        poll_endpoint: body.poll.endpoint,
+    })
+}
+
+/// 轮询一次登录结果
+///
+/// # 返回
+/// - `Ok(Some(credentials))`: 用户已完成授权
+/// - `Ok(None)`: 用户尚未完成授权（服务器返回 404）
+/// - `Err(SyncError)`: 网络错误或响应格式错误
+pub async fn poll(init: &LoginFlowInit) -> Result<Option<LoginFlowCredentials>> {
+    let response = http_client()?
+        .post(&init.poll_endpoint)
+        .form(&[("token", init.poll_token.as_str())])
+        .send()
+        .await
+        .map_err(|e| SyncError::Network(format!("Failed to poll login flow: {}", e)))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !response.status().is_success() {
+        return Err(SyncError::WebDav(format!(
+            "Login flow poll failed (status {})",
+            response.status().as_u16()
+        )));
+    }
+
+    let body: PollResponse = response
+        .json()
+        .await
+        .map_err(|e| SyncError::WebDav(format!("Invalid login flow poll response: {}", e)))?;
+
+    Ok(Some(LoginFlowCredentials {
+        server: body.server,
+        login_name: body.login_name,
+        app_password: body.app_password,
+    }))
+}
+
+/// 持续轮询直到用户完成授权或超时
+///
+/// # 参数
+/// - init: `start` 返回的初始化信息
+/// - interval: 轮询间隔
+/// - timeout: 最长等待时间
+pub async fn wait_for_credentials(
+    init: &LoginFlowInit,
+    interval: Duration,
+    timeout: Duration,
+) -> Result<LoginFlowCredentials> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if let Some(credentials) = poll(init).await? {
+            return Ok(credentials);
+        }
+
+        if Instant::now() + interval > deadline {
+            return Err(SyncError::AuthError(
+                "Login flow timed out before the user granted access".to_string(),
+            ));
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nextcloud_base_url() {
+        assert_eq!(
+            nextcloud_base_url("https://cloud.example.com/remote.php/dav/files/alice/"),
+            "https://cloud.example.com"
+        );
+        assert_eq!(
+            nextcloud_base_url("https://example.com/nextcloud/remote.php/webdav"),
+            "https://example.com/nextcloud"
+        );
+        assert_eq!(
+            nextcloud_base_url("https://cloud.example.com/"),
+            "https://cloud.example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_login_flow() {
+        let mut server = mockito::Server::new_async().await;
+        let poll_endpoint = format!("{}/login/v2/poll", server.url());
+        let mock = server
+            .mock("POST", "/index.php/login/v2")
+            .match_header("user-agent", LOGIN_FLOW_USER_AGENT)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"poll":{{"token":"abc","endpoint":"{}"}},"login":"{}/login/v2/flow/xyz"}}"#,
+                poll_endpoint,
+                server.url()
+            ))
+            .create_async()
+            .await;
+
+        let init = start(&format!("{}/remote.php/dav", server.url()))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(init.poll_token, "abc");
+        assert_eq!(init.poll_endpoint, poll_endpoint);
+        assert_eq!(
+            init.login_url,
+            format!("{}/login/v2/flow/xyz", server.url())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poll_pending_then_granted() {
+        let mut server = mockito::Server::new_async().await;
+        let init = LoginFlowInit {
+            login_url: String::new(),
+            poll_token: "abc".to_string(),
+            poll_endpoint: format!("{}/login/v2/poll", server.url()),
+        };
+
+        let pending = server
+            .mock("POST", "/login/v2/poll")
+            .match_body("token=abc")
+            .with_status(404)
+            .create_async()
+            .await;
+        assert_eq!(poll(&init).await.unwrap(), None);
+        pending.assert_async().await;
+        pending.remove_async().await;
+
+        server
+            .mock("POST", "/login/v2/poll")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"server":"https://cloud.example.com","loginName":"alice","appPassword":"secret"}"#,
+            )
+            .create_async()
+            .await;
+
+        let credentials =
+            wait_for_credentials(&init, Duration::from_millis(10), Duration::from_secs(1))
+                .await
+                .unwrap();
+        assert_eq!(credentials.login_name, "alice");
+        assert_eq!(credentials.app_password, "secret");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_credentials_timeout() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/login/v2/poll")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let init = LoginFlowInit {
+            login_url: String::new(),
+            poll_token: "abc".to_string(),
+            poll_endpoint: format!("{}/login/v2/poll", server.url()),
+        };
+
+        let result =
+            wait_for_credentials(&init, Duration::from_millis(10), Duration::from_millis(30)).await;
+        assert!(matches!(result, Err(SyncError::AuthError(_))));
+    }
+}
@@ -3,13 +3,67 @@
 /// 提供 WebDAV 服务器配置管理和客户端功能
 ///
 /// 模块结构:
+/// - activity: 服务器活动流（Nextcloud Activity OCS API）
 /// - db: 数据库 CRUD 操作
 /// - keyring: 密码管理
+/// - credential_store: Keyring 不可用时的加密文件凭据存储
+/// - discovery: 从主机名自动发现 WebDAV 地址与服务器类型
 /// - client: WebDAV 客户端实现
+/// - factory: 按服务器缓存客户端，共享连接池
+/// - cookies: 服务器 Cookie 会话（SSO 网关），加密持久化
+/// - connection_test: 分阶段连接测试（DNS、TCP、TLS、认证、WebDAV、服务器类型、配额）
+/// - login_flow: Nextcloud Login Flow v2（获取应用密码）
+/// - path: 远程路径的百分号编码与解码
+/// - shares: 公开分享链接（Nextcloud / ownCloud OCS Share API）
+/// - thumbnails: 远程图片缩略图（Nextcloud 预览接口或本地缩小，带磁盘缓存）
+/// - tls: 服务器证书获取与指纹校验
+/// - trace: 可选的请求跟踪（方法、URL、状态、耗时与脱敏后的请求头）
 /// - e2e_tests: 端到端集成测试
+pub mod activity;
 pub mod client;
+pub mod connection_test;
+pub mod cookies;
+pub mod credential_store;
 pub mod db;
+pub mod discovery;
+pub mod factory;
 pub mod keyring;
+pub mod login_flow;
+pub mod path;
+pub mod shares;
+pub mod thumbnails;
+pub mod tls;
+pub mod trace;
 
 #[cfg(test)]
 mod e2e_tests;
+
+/// 测试用的服务器配置（generic 类型、basic 认证，用户名 testuser）
+///
+/// 需要其他字段时用结构体更新语法覆盖：`WebDavServerConfig { server_type, ..mock_server_config(url) }`
+#[cfg(test)]
+pub(crate) fn mock_server_config(url: &str) -> crate::database::WebDavServerConfig {
+    crate::database::WebDavServerConfig {
+        id: "test-id".to_string(),
+        name: "Test Server".to_string(),
+        url: url.to_string(),
+        username: "testuser".to_string(),
+        use_https: false,
+        timeout: 5,
+        last_test_at: None,
+        last_test_status: "unknown".to_string(),
+        last_test_error: None,
+        auth_type: "basic".to_string(),
+        accept_invalid_certs: false,
+        pinned_cert_fingerprint: None,
+        custom_headers: Default::default(),
+        ip_version: "any".to_string(),
+        resolve_ip: None,
+        connect_timeout: 10,
+        read_timeout: 60,
+        server_type: "generic".to_string(),
+        enabled: true,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
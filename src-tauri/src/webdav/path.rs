@@ -0,0 +1,112 @@
+//! 远程路径编码
+//!
+//! WebDAV 请求 URL 中的路径需要按 RFC 3986 百分号编码，服务器在 PROPFIND / REPORT
+//! 响应的 `<D:href>` 中返回的也是编码后的路径。
+//!
+//! 应用内部统一使用未编码的路径（如 `/文档/a b.txt`）：
+//! - 构建请求 URL 时由 `encode_path` 逐段编码，保留分隔符 `/`
+//! - 解析响应时由 `decode_path` 解码 href
+
+/// 路径分段中无需编码的字符（RFC 3986 unreserved）
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// 编码单个路径分段
+///
+/// 除 unreserved 字符外全部按 UTF-8 字节编码为 `%XX`（包括 `/`）
+pub fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if is_unreserved(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// 编码路径，保留分段之间的 `/`
+///
+/// # 示例
+/// `/文档/a b#1.txt` -> `/%E6%96%87%E6%A1%A3/a%20b%231.txt`
+pub fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 解码百分号编码的路径
+///
+/// 无效的转义序列保持原样；解码结果不是合法 UTF-8 时使用替换字符
+pub fn decode_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+            decoded.push(u8::from_str_radix(hex, 16).unwrap());
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("/docs/a.txt"), "/docs/a.txt");
+        assert_eq!(
+            encode_path("/docs/file with spaces.txt"),
+            "/docs/file%20with%20spaces.txt"
+        );
+        assert_eq!(encode_path("/a#1/b?c/100%.txt"), "/a%231/b%3Fc/100%25.txt");
+        assert_eq!(
+            encode_path("/文档/报告.pdf"),
+            "/%E6%96%87%E6%A1%A3/%E6%8A%A5%E5%91%8A.pdf"
+        );
+        assert_eq!(encode_segment("a/b"), "a%2Fb");
+    }
+
+    #[test]
+    fn test_decode_path() {
+        assert_eq!(
+            decode_path("/%E6%96%87%E6%A1%A3/a%20b%231.txt"),
+            "/文档/a b#1.txt"
+        );
+        // 小写十六进制、未编码的字符
+        assert_eq!(decode_path("/%e6%96%87%e6%a1%a3/a b"), "/文档/a b");
+        // 无效的转义保持原样
+        assert_eq!(decode_path("/100%.txt"), "/100%.txt");
+        assert_eq!(decode_path("/a%zz"), "/a%zz");
+        assert_eq!(decode_path("/a%+1"), "/a%+1");
+        assert_eq!(decode_path("/a%2"), "/a%2");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for path in [
+            "/文档/季度 报告 (最终).docx",
+            "/a+b/c&d=e;f",
+            "/émoji 🎉.txt",
+            "/",
+        ] {
+            assert_eq!(decode_path(&encode_path(path)), path);
+        }
+    }
+}
@@ -0,0 +1,357 @@
+/// 公开分享链接（Nextcloud / ownCloud OCS Share API）
+///
+/// 通过 `files_sharing` 的 OCS 接口为远程文件或文件夹创建公开链接（可设置密码和过期日期），
+/// 并列出、删除已有的公开链接。只有服务器类型为 Nextcloud 或 ownCloud 时可用。
+///
+/// OCS 接口使用用户文件中的路径，与 WebDAV 路径之间的转换见 `CloudLocation`
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::webdav::client::{CloudLocation, WebDavClient};
+use crate::{Result, SyncError};
+
+/// 分享接口路径（相对于 `/ocs/v2.php/`）
+const SHARES_ENDPOINT: &str = "apps/files_sharing/api/v1/shares";
+
+/// 公开链接的分享类型
+const SHARE_TYPE_PUBLIC_LINK: u64 = 3;
+
+/// 只读权限
+const PERMISSION_READ: u32 = 1;
+
+/// 服务器类型是否支持 OCS 分享接口
+pub fn supports_ocs(server_type: &str) -> bool {
+    matches!(server_type, "nextcloud" | "owncloud")
+}
+
+/// 创建公开链接的选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLinkOptions {
+    /// 访问密码（可选）
+    #[serde(default)]
+    pub password: Option<String>,
+    /// 过期日期（可选，格式 YYYY-MM-DD）
+    #[serde(default)]
+    pub expire_date: Option<String>,
+}
+
+impl ShareLinkOptions {
+    /// 校验选项
+    ///
+    /// # 参数
+    /// - today: 当前日期（过期日期必须晚于今天）
+    pub fn validate(&self, today: chrono::NaiveDate) -> Result<()> {
+        if self
+            .password
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            return Err(SyncError::ValidationError(
+                "Share password cannot be empty".to_string(),
+            ));
+        }
+
+        if let Some(expire_date) = &self.expire_date {
+            let date =
+                chrono::NaiveDate::parse_from_str(expire_date, "%Y-%m-%d").map_err(|_| {
+                    SyncError::ValidationError(format!(
+                        "Invalid expire date '{}', expected YYYY-MM-DD",
+                        expire_date
+                    ))
+                })?;
+            if date <= today {
+                return Err(SyncError::ValidationError(format!(
+                    "Expire date must be in the future: {}",
+                    expire_date
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 公开分享链接
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicShare {
+    /// 分享 ID
+    pub id: String,
+    /// 公开链接
+    pub url: String,
+    /// 被分享的远程路径（相对于服务器根路径；不在服务器根路径下时为用户文件中的路径）
+    pub path: String,
+    /// 分享令牌
+    pub token: String,
+    /// 过期日期（YYYY-MM-DD，未设置时为 None）
+    pub expire_date: Option<String>,
+    /// 创建时间（Unix 时间戳，秒）
+    pub created_at: Option<i64>,
+}
+
+impl PublicShare {
+    /// 从 OCS 返回的分享数据解析，不是公开链接时返回 None
+    fn from_ocs(data: &Value, location: &CloudLocation) -> Option<Self> {
+        if data["share_type"].as_u64() != Some(SHARE_TYPE_PUBLIC_LINK) {
+            return None;
+        }
+
+        // Nextcloud 返回字符串 ID，较早的 ownCloud 返回数字
+        let id = match &data["id"] {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => return None,
+        };
+        let user_path = data["path"].as_str().unwrap_or_default();
+
+        Some(Self {
+            id,
+            url: data["url"].as_str()?.to_string(),
+            path: location
+                .remote_path(user_path)
+                .unwrap_or_else(|| user_path.to_string()),
+            token: data["token"].as_str().unwrap_or_default().to_string(),
+            // 格式为 "YYYY-MM-DD HH:MM:SS"
+            expire_date: data["expiration"]
+                .as_str()
+                .and_then(|expiration| expiration.split_whitespace().next())
+                .map(str::to_string),
+            created_at: data["stime"].as_i64(),
+        })
+    }
+}
+
+/// 解析客户端的服务器地址
+fn location(client: &WebDavClient) -> Result<CloudLocation> {
+    CloudLocation::parse(client.url()).ok_or_else(|| {
+        SyncError::ValidationError(format!(
+            "Server URL is not a Nextcloud or ownCloud WebDAV address: {}",
+            client.url()
+        ))
+    })
+}
+
+/// 为远程文件或文件夹创建公开链接（只读）
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_path: 远程路径（相对于服务器根路径）
+/// - options: 密码与过期日期
+pub async fn create_link(
+    client: &WebDavClient,
+    remote_path: &str,
+    options: &ShareLinkOptions,
+) -> Result<PublicShare> {
+    options.validate(chrono::Local::now().date_naive())?;
+    let location = location(client)?;
+
+    let mut params = vec![
+        ("path", location.user_path(remote_path)),
+        ("shareType", SHARE_TYPE_PUBLIC_LINK.to_string()),
+        ("permissions", PERMISSION_READ.to_string()),
+    ];
+    if let Some(password) = &options.password {
+        params.push(("password", password.clone()));
+    }
+    if let Some(expire_date) = &options.expire_date {
+        params.push(("expireDate", expire_date.clone()));
+    }
+
+    let data = client
+        .ocs(reqwest::Method::POST, SHARES_ENDPOINT, &params)
+        .await?;
+    PublicShare::from_ocs(&data, &location)
+        .ok_or_else(|| SyncError::WebDav("Invalid share returned by server".to_string()))
+}
+
+/// 列出公开链接
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - remote_path: 只列出该路径的分享（None 表示当前用户的全部分享）
+pub async fn list_links(
+    client: &WebDavClient,
+    remote_path: Option<&str>,
+) -> Result<Vec<PublicShare>> {
+    let location = location(client)?;
+
+    let params: Vec<(&str, String)> = remote_path
+        .map(|path| vec![("path", location.user_path(path))])
+        .unwrap_or_default();
+    let data = client
+        .ocs(reqwest::Method::GET, SHARES_ENDPOINT, &params)
+        .await?;
+
+    Ok(data
+        .as_array()
+        .map(|shares| {
+            shares
+                .iter()
+                .filter_map(|share| PublicShare::from_ocs(share, &location))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// 删除分享
+///
+/// # 参数
+/// - client: WebDAV 客户端
+/// - share_id: 分享 ID
+pub async fn delete(client: &WebDavClient, share_id: &str) -> Result<()> {
+    if share_id.is_empty() || !share_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(SyncError::ValidationError(format!(
+            "Invalid share ID: {}",
+            share_id
+        )));
+    }
+
+    client
+        .ocs(
+            reqwest::Method::DELETE,
+            &format!("{}/{}", SHARES_ENDPOINT, share_id),
+            &[],
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::WebDavServerConfig;
+    use crate::webdav::mock_server_config;
+    use serde_json::json;
+
+    fn nextcloud_config(url: &str) -> WebDavServerConfig {
+        WebDavServerConfig {
+            server_type: "nextcloud".to_string(),
+            ..mock_server_config(url)
+        }
+    }
+
+    fn share_json(id: Value, path: &str) -> Value {
+        json!({
+            "id": id,
+            "share_type": 3,
+            "path": path,
+            "url": "https://host/s/abc",
+            "token": "abc",
+            "expiration": "2030-01-31 00:00:00",
+            "stime": 1700000000
+        })
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let today = chrono::NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        let options = |password: Option<&str>, expire_date: Option<&str>| ShareLinkOptions {
+            password: password.map(str::to_string),
+            expire_date: expire_date.map(str::to_string),
+        };
+
+        assert!(options(None, None).validate(today).is_ok());
+        assert!(options(Some("secret"), Some("2030-01-02"))
+            .validate(today)
+            .is_ok());
+        assert!(options(Some(" "), None).validate(today).is_err());
+        assert!(options(None, Some("2030-01-01")).validate(today).is_err());
+        assert!(options(None, Some("01/02/2030")).validate(today).is_err());
+    }
+
+    #[test]
+    fn test_public_share_from_ocs() {
+        let location =
+            CloudLocation::parse("https://host/remote.php/dav/files/alice/Docs").unwrap();
+
+        let share =
+            PublicShare::from_ocs(&share_json(json!("42"), "/Docs/a.txt"), &location).unwrap();
+        assert_eq!(share.id, "42");
+        assert_eq!(share.path, "/a.txt");
+        assert_eq!(share.expire_date.as_deref(), Some("2030-01-31"));
+        assert_eq!(share.created_at, Some(1700000000));
+
+        // 数字 ID 与服务器根路径之外的文件
+        let share =
+            PublicShare::from_ocs(&share_json(json!(7), "/Other/b.txt"), &location).unwrap();
+        assert_eq!(share.id, "7");
+        assert_eq!(share.path, "/Other/b.txt");
+
+        // 非公开链接的分享
+        let mut user_share = share_json(json!("1"), "/Docs/a.txt");
+        user_share["share_type"] = json!(0);
+        assert_eq!(PublicShare::from_ocs(&user_share, &location), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_link() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/ocs/v2.php/apps/files_sharing/api/v1/shares")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "format".to_string(),
+                "json".to_string(),
+            ))
+            .match_header("ocs-apirequest", "true")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("path".to_string(), "/Docs/a.txt".to_string()),
+                mockito::Matcher::UrlEncoded("shareType".to_string(), "3".to_string()),
+                mockito::Matcher::UrlEncoded("password".to_string(), "secret".to_string()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ocs": {
+                        "meta": { "status": "ok", "statuscode": 200, "message": "OK" },
+                        "data": share_json(json!("42"), "/Docs/a.txt")
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let config = nextcloud_config(&format!("{}/remote.php/dav/files/alice/Docs", server.url()));
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+        let options = ShareLinkOptions {
+            password: Some("secret".to_string()),
+            expire_date: None,
+        };
+
+        let share = create_link(&client, "/a.txt", &options).await.unwrap();
+        assert_eq!(share.url, "https://host/s/abc");
+        assert_eq!(share.path, "/a.txt");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_ocs_error_is_mapped() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("DELETE", "/ocs/v2.php/apps/files_sharing/api/v1/shares/42")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_body(
+                json!({
+                    "ocs": {
+                        "meta": { "status": "failure", "statuscode": 404, "message": "Wrong share ID" },
+                        "data": []
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let config = nextcloud_config(&format!("{}/remote.php/webdav", server.url()));
+        let client = WebDavClient::new(&config, "password".to_string()).unwrap();
+
+        let err = delete(&client, "42").await.unwrap_err();
+        assert!(matches!(err, SyncError::NotFound(ref msg) if msg.contains("Wrong share ID")));
+        assert!(matches!(
+            delete(&client, "../42").await,
+            Err(SyncError::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,341 @@
+/// 远程图片缩略图
+///
+/// Nextcloud 服务器通过预览接口由服务器生成缩略图；其他服务器或服务器无法生成预览时，
+/// 下载不超过 `THUMBNAIL_MAX_SOURCE_SIZE` 的常见格式图片（`THUMBNAIL_EXTENSIONS`）并在本地缩小。
+///
+/// 缩略图统一编码为 PNG，以 (服务器 ID, 路径, ETag, 尺寸) 的 SHA-256 为文件名缓存在应用数据目录的
+/// `thumbnails` 目录中。命中缓存时更新文件的修改时间，缓存总大小超过上限时按修改时间
+/// 淘汰最久未使用的缩略图
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use filetime::FileTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::constants::{
+    THUMBNAILS_DIR, THUMBNAIL_CACHE_MAX_BYTES, THUMBNAIL_EXTENSIONS, THUMBNAIL_MAX_SOURCE_SIZE,
+};
+use crate::database::WebDavServerConfig;
+use crate::webdav::client::WebDavClient;
+use crate::{Result, SyncError};
+
+/// 远程图片缩略图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteThumbnail {
+    /// 远程文件路径
+    pub path: String,
+    /// 请求的最大边长（像素）
+    pub size: u32,
+    /// 图片 MIME 类型（固定为 image/png）
+    pub mime_type: String,
+    /// Base64 编码的图片内容
+    pub data: String,
+    /// 是否来自磁盘缓存
+    pub cached: bool,
+}
+
+impl RemoteThumbnail {
+    fn new(path: &str, size: u32, data: &[u8], cached: bool) -> Self {
+        Self {
+            path: path.to_string(),
+            size,
+            mime_type: "image/png".to_string(),
+            data: base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data),
+            cached,
+        }
+    }
+}
+
+/// 缩略图磁盘缓存
+///
+/// 作为 Tauri State 管理
+#[derive(Debug, Clone)]
+pub struct ThumbnailCache {
+    root: PathBuf,
+    max_bytes: u64,
+}
+
+impl ThumbnailCache {
+    /// 使用指定目录创建缓存（目录不存在时自动创建）
+    pub fn new(root: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root, max_bytes })
+    }
+
+    /// 打开应用数据目录下的缓存
+    pub fn open_in_app_dir(app: &AppHandle) -> Result<Self> {
+        let app_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| SyncError::ConfigError(format!("Failed to get app data dir: {}", e)))?;
+
+        Self::new(app_dir.join(THUMBNAILS_DIR), THUMBNAIL_CACHE_MAX_BYTES)
+    }
+
+    /// 缓存键
+    ///
+    /// # 参数
+    /// - server_id: 服务器 ID
+    /// - path: 远程文件路径
+    /// - version: 远程文件版本（ETag，服务器未返回时为文件大小）
+    /// - size: 缩略图的最大边长
+    pub fn key(server_id: &str, path: &str, version: &str, size: u32) -> String {
+        let mut hasher = Sha256::new();
+        for part in [server_id, path, version, &size.to_string()] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// 缓存文件路径
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{}.png", key))
+    }
+
+    /// 读取缓存的缩略图，命中时更新修改时间
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(key);
+        let data = fs::read(&path).ok()?;
+        if let Err(e) = filetime::set_file_mtime(&path, FileTime::now()) {
+            tracing::debug!(path = %path.display(), error = %e, "更新缩略图访问时间失败");
+        }
+        Some(data)
+    }
+
+    /// 写入缩略图，超出缓存上限时淘汰最久未使用的缩略图
+    pub fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.entry_path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, &path)?;
+
+        self.evict()
+    }
+
+    /// 按修改时间从旧到新删除缩略图，直到总大小不超过上限
+    fn evict(&self) -> Result<()> {
+        let mut entries: Vec<(FileTime, u64, PathBuf)> = fs::read_dir(&self.root)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "png"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((
+                    FileTime::from_last_modification_time(&metadata),
+                    metadata.len(),
+                    entry.path(),
+                ))
+            })
+            .collect();
+        entries.sort_by_key(|(modified, _, _)| *modified);
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+        }
+
+        Ok(())
+    }
+}
+
+/// 是否可以在本地为该文件生成缩略图（按扩展名判断）
+pub fn is_supported_image(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            THUMBNAIL_EXTENSIONS
+                .iter()
+                .any(|supported| ext.eq_ignore_ascii_case(supported))
+        })
+}
+
+/// 将图片缩小到最大边长不超过 size（保持宽高比，不放大），编码为 PNG
+pub fn downscale(data: &[u8], size: u32) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(data)
+        .map_err(|e| SyncError::ValidationError(format!("Failed to decode image: {}", e)))?;
+    let thumbnail = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image
+    };
+
+    let mut output = Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut output, image::ImageFormat::Png)
+        .map_err(|e| SyncError::Unknown(format!("Failed to encode thumbnail: {}", e)))?;
+    Ok(output.into_inner())
+}
+
+/// 获取远程图片的缩略图（优先使用磁盘缓存）
+///
+/// # 参数
+/// - cache: 缩略图缓存
+/// - server: 服务器配置
+/// - client: 服务器的 WebDAV 客户端
+/// - path: 远程文件路径（相对于服务器根路径）
+/// - size: 缩略图的最大边长（像素）
+///
+/// # 返回
+/// - Ok(RemoteThumbnail): 缩略图
+/// - Err(SyncError::NotFound): 文件不存在，或服务器无法生成预览且文件不是支持的图片格式
+/// - Err(SyncError::ValidationError): 原图超过本地生成缩略图的大小限制或无法解码
+pub async fn fetch(
+    cache: &ThumbnailCache,
+    server: &WebDavServerConfig,
+    client: &WebDavClient,
+    path: &str,
+    size: u32,
+) -> Result<RemoteThumbnail> {
+    let remote = client.checksum(path).await?;
+    let version = remote
+        .etag
+        .clone()
+        .or_else(|| remote.size.map(|size| size.to_string()))
+        .unwrap_or_default();
+    let key = ThumbnailCache::key(&server.id, path, &version, size);
+    if let Some(data) = cache.get(&key) {
+        return Ok(RemoteThumbnail::new(path, size, &data, true));
+    }
+
+    let preview = if server.server_type == "nextcloud" {
+        client.preview(path, size).await?
+    } else {
+        None
+    };
+    let data = match preview {
+        Some(data) => data,
+        None => {
+            if !is_supported_image(path) {
+                return Err(SyncError::NotFound(format!(
+                    "No thumbnail available for {}",
+                    path
+                )));
+            }
+            if remote
+                .size
+                .is_some_and(|len| len > THUMBNAIL_MAX_SOURCE_SIZE)
+            {
+                return Err(SyncError::ValidationError(format!(
+                    "Image is too large to generate a thumbnail locally: {}",
+                    path
+                )));
+            }
+
+            let download = cache
+                .root
+                .join(format!(".download-{}", uuid::Uuid::new_v4()));
+            client.download(path, &download).await?;
+            tokio::task::spawn_blocking(move || {
+                let original = fs::read(&download);
+                let _ = fs::remove_file(&download);
+                downscale(&original?, size)
+            })
+            .await
+            .map_err(|e| SyncError::Unknown(format!("Thumbnail task failed: {}", e)))??
+        }
+    };
+
+    cache.put(&key, &data)?;
+    Ok(RemoteThumbnail::new(path, size, &data, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_cache(max_bytes: u64) -> (PathBuf, ThumbnailCache) {
+        let dir = std::env::temp_dir().join(format!("lightsync_thumbs_{}", Uuid::new_v4()));
+        let cache = ThumbnailCache::new(&dir, max_bytes).unwrap();
+        (dir, cache)
+    }
+
+    fn set_mtime(cache: &ThumbnailCache, key: &str, seconds: i64) {
+        filetime::set_file_mtime(cache.entry_path(key), FileTime::from_unix_time(seconds, 0))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_key() {
+        let key = ThumbnailCache::key("server-1", "/a.jpg", "etag-1", 256);
+        assert_eq!(key.len(), 64);
+        assert_eq!(
+            key,
+            ThumbnailCache::key("server-1", "/a.jpg", "etag-1", 256)
+        );
+        assert_ne!(
+            key,
+            ThumbnailCache::key("server-1", "/a.jpg", "etag-2", 256)
+        );
+        assert_ne!(
+            key,
+            ThumbnailCache::key("server-1", "/a.jpg", "etag-1", 128)
+        );
+    }
+
+    #[test]
+    fn test_is_supported_image() {
+        assert!(is_supported_image("/photos/a.JPG"));
+        assert!(is_supported_image("b.webp"));
+        assert!(!is_supported_image("/docs/report.pdf"));
+        assert!(!is_supported_image("/docs/png"));
+    }
+
+    #[test]
+    fn test_downscale_keeps_aspect_ratio() {
+        let mut original = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(400, 200)
+            .write_to(&mut original, image::ImageFormat::Png)
+            .unwrap();
+
+        let thumbnail =
+            image::load_from_memory(&downscale(original.get_ref(), 100).unwrap()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (100, 50));
+
+        // 小于目标尺寸的图片不放大
+        let small = image::load_from_memory(&downscale(original.get_ref(), 1000).unwrap()).unwrap();
+        assert_eq!((small.width(), small.height()), (400, 200));
+
+        assert!(matches!(
+            downscale(b"not an image", 100),
+            Err(SyncError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let (dir, cache) = temp_cache(30);
+
+        cache.put("a", &[0; 10]).unwrap();
+        cache.put("b", &[0; 10]).unwrap();
+        cache.put("c", &[0; 10]).unwrap();
+        set_mtime(&cache, "a", 100);
+        set_mtime(&cache, "b", 200);
+        set_mtime(&cache, "c", 300);
+
+        // 读取 a 后 b 成为最久未使用的缩略图
+        assert_eq!(cache.get("a").unwrap().len(), 10);
+        cache.put("d", &[0; 10]).unwrap();
+
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+        assert!(cache.get("d").is_some());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,268 @@
+/// 服务器证书获取与指纹校验
+///
+/// 用于支持自签名证书的 NAS 等设备：
+/// 1. `fetch_certificate` 在不校验证书链的情况下获取服务器证书，供用户确认
+/// 2. 用户确认后将 SHA-256 指纹保存到 `WebDavServerConfig.pinned_cert_fingerprint`
+/// 3. 之后客户端使用 `pinned_tls_config`，每次 TLS 握手都校验服务器证书与固定指纹一致，
+///    创建客户端时另外通过 `verify_fingerprint` 提前检查，指纹不一致时返回明确的错误
+use crate::{Result, SyncError};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// 服务器证书信息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    /// SHA-256 指纹（大写十六进制，冒号分隔）
+    pub fingerprint_sha256: String,
+    /// PEM 格式证书
+    pub pem: String,
+}
+
+impl CertificateInfo {
+    /// 从 DER 编码的证书构建
+    pub fn from_der(der: &[u8]) -> Self {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(der);
+        let body = encoded
+            .as_bytes()
+            .chunks(64)
+            .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Self {
+            fingerprint_sha256: fingerprint(der),
+            pem: format!(
+                "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+                body
+            ),
+        }
+    }
+}
+
+/// 计算 DER 证书的 SHA-256 指纹
+///
+/// 格式与浏览器显示一致，例如 `AB:CD:...`
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// 规范化用户输入的指纹
+///
+/// 接受冒号、空格分隔或连续的十六进制字符串（大小写均可）
+///
+/// # 返回
+/// - `Some(String)`: 规范化后的指纹（大写、冒号分隔）
+/// - `None`: 不是有效的 SHA-256 指纹
+pub fn normalize_fingerprint(input: &str) -> Option<String> {
+    let hex: String = input
+        .chars()
+        .filter(|c| !matches!(c, ':' | ' ' | '-'))
+        .collect();
+
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(
+        hex.to_uppercase()
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| String::from_utf8_lossy(pair).into_owned())
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
+
+/// 获取服务器证书
+///
+/// 建立 TLS 连接时不校验证书链，仅用于展示给用户确认
+///
+/// # 参数
+/// - url: 服务器地址（必须是 https）
+/// - timeout: 连接超时时间
+///
+/// # 返回
+/// - 成功：返回服务器叶子证书信息
+/// - 失败：非 https 地址、网络错误或服务器未提供证书
+pub async fn fetch_certificate(url: &str, timeout: Duration) -> Result<CertificateInfo> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| SyncError::ConfigError(format!("Invalid URL format: {}", e)))?;
+    if parsed.scheme() != "https" {
+        return Err(SyncError::ConfigError(format!(
+            "Certificate can only be fetched from https URLs: {}",
+            url
+        )));
+    }
+
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .timeout(timeout)
+        .build()
+        .map_err(|e| SyncError::Network(format!("Failed to create HTTP client: {}", e)))?;
+
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .map_err(|e| SyncError::Network(format!("Failed to connect to '{}': {}", url, e)))?;
+
+    let der = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .ok_or_else(|| {
+            SyncError::Network(format!("Server '{}' did not present a certificate", url))
+        })?;
+
+    Ok(CertificateInfo::from_der(der))
+}
+
+/// 校验服务器证书是否与固定指纹一致
+///
+/// # 返回
+/// - `Ok(())`: 指纹一致
+/// - `Err(SyncError::AuthError)`: 指纹不一致（证书可能已更换或存在中间人攻击）
+pub async fn verify_fingerprint(url: &str, pinned: &str, timeout: Duration) -> Result<()> {
+    let expected = normalize_fingerprint(pinned).ok_or_else(|| {
+        SyncError::ConfigError(format!(
+            "Invalid SHA-256 certificate fingerprint: {}",
+            pinned
+        ))
+    })?;
+
+    let actual = fetch_certificate(url, timeout).await?.fingerprint_sha256;
+    if actual != expected {
+        return Err(SyncError::AuthError(format!(
+            "Server certificate fingerprint mismatch: expected {}, got {}",
+            expected, actual
+        )));
+    }
+
+    Ok(())
+}
+
+/// 固定指纹的服务器证书校验器
+///
+/// 叶子证书的 SHA-256 指纹与固定指纹一致时信任该证书（可以是自签名证书），
+/// 握手签名仍由 rustls 按证书中的公钥校验
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    /// 规范化后的固定指纹
+    fingerprint: String,
+}
+
+impl rustls::client::ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> std::result::Result<rustls::client::ServerCertVerified, rustls::Error> {
+        if fingerprint(&end_entity.0) == self.fingerprint {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(
+                rustls::CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+}
+
+/// 创建只信任固定指纹证书的 TLS 配置（通过 `use_preconfigured_tls` 传给 reqwest）
+///
+/// # 返回
+/// - `Ok(rustls::ClientConfig)`: 每次握手校验证书指纹的配置
+/// - `Err(SyncError::ConfigError)`: 不是有效的 SHA-256 指纹
+pub fn pinned_tls_config(pinned: &str) -> Result<rustls::ClientConfig> {
+    let fingerprint = normalize_fingerprint(pinned).ok_or_else(|| {
+        SyncError::ConfigError(format!(
+            "Invalid SHA-256 certificate fingerprint: {}",
+            pinned
+        ))
+    })?;
+
+    Ok(rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(PinnedCertVerifier { fingerprint }))
+        .with_no_client_auth())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_format() {
+        let fp = fingerprint(b"certificate");
+        assert_eq!(fp.len(), 32 * 3 - 1);
+        assert!(fp.split(':').all(|b| b.len() == 2));
+        assert_eq!(fp, fp.to_uppercase());
+    }
+
+    #[test]
+    fn test_normalize_fingerprint() {
+        let fp = fingerprint(b"certificate");
+        let compact = fp.replace(':', "").to_lowercase();
+        assert_eq!(normalize_fingerprint(&compact), Some(fp.clone()));
+        assert_eq!(
+            normalize_fingerprint(&fp.replace(':', " ")),
+            Some(fp.clone())
+        );
+        assert_eq!(normalize_fingerprint("AB:CD"), None);
+        assert_eq!(normalize_fingerprint(&"ZZ".repeat(32)), None);
+    }
+
+    #[test]
+    fn test_certificate_info_pem() {
+        let info = CertificateInfo::from_der(&[0u8; 100]);
+        assert!(info.pem.starts_with("-----BEGIN CERTIFICATE-----\n"));
+        assert!(info.pem.ends_with("-----END CERTIFICATE-----\n"));
+        assert!(info.pem.lines().all(|line| line.len() <= 64));
+    }
+
+    #[test]
+    fn test_pinned_verifier_checks_fingerprint() {
+        use rustls::client::ServerCertVerifier;
+
+        let cert = rustls::Certificate(b"certificate".to_vec());
+        let server_name = rustls::ServerName::try_from("nas.local").unwrap();
+        let verify = |pinned: &str| {
+            PinnedCertVerifier {
+                fingerprint: normalize_fingerprint(pinned).unwrap(),
+            }
+            .verify_server_cert(
+                &cert,
+                &[],
+                &server_name,
+                &mut std::iter::empty(),
+                &[],
+                SystemTime::now(),
+            )
+        };
+
+        assert!(verify(&fingerprint(b"certificate")).is_ok());
+        assert!(verify(&fingerprint(b"other")).is_err());
+        assert!(matches!(
+            pinned_tls_config("AB:CD"),
+            Err(SyncError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_certificate_rejects_http() {
+        let result = fetch_certificate("http://example.com", Duration::from_secs(1)).await;
+        assert!(matches!(result, Err(SyncError::ConfigError(_))));
+    }
+}
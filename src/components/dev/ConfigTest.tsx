@@ -6,8 +6,9 @@
 
 import { useEffect, useState } from 'react'
 import { Button, Card, CardBody, CardHeader, Divider, Input, Spinner } from '@nextui-org/react'
-import { useConfig, useLanguage, useSyncFolders, useTheme, useWebDavServers } from '@/hooks/useConfig.ts'
-import type { SyncFolderConfig, WebDavServerConfig } from '@/types/config.ts'
+import { useConfig, useLanguage, useSyncFolders, useTheme } from '@/hooks/useConfig.ts'
+import { useWebDavServers } from '@/hooks/useWebDavServers.ts'
+import type { SyncFolderConfig } from '@/types/config.ts'
 import { invoke } from '@tauri-apps/api/core'
 
 export default function ConfigTest() {
@@ -15,7 +16,7 @@ export default function ConfigTest() {
   const [language, setLanguage, langLoading] = useLanguage()
   const [theme, setTheme, themeLoading] = useTheme()
   const { syncFolders, addSyncFolder, removeSyncFolder } = useSyncFolders()
-  const { webdavServers, addServer, removeServer } = useWebDavServers()
+  const { servers: webdavServers, addServer, removeServer } = useWebDavServers()
 
   const [testResult, setTestResult] = useState<string[]>([])
   const [watcherStarted, setWatcherStarted] = useState(false)
@@ -125,15 +126,14 @@ export default function ConfigTest() {
 
   const testAddWebDavServer = async () => {
     try {
-      const newServer: WebDavServerConfig = {
-        id: `server-${Date.now()}`,
+      await addServer({
         name: '测试服务器',
         url: 'https://webdav.example.com',
         username: 'testuser',
+        password: 'testpassword',
         useHttps: true,
         timeout: 30,
-      }
-      await addServer(newServer)
+      })
       addTestResult('✅ 添加 WebDAV 服务器成功')
     } catch (err) {
       addTestResult(`❌ 添加 WebDAV 服务器失败: ${err}`)
@@ -268,14 +268,14 @@ export const ServerConfigForm: React.FC<ServerConfigFormProps> = ({ mode, initia
 
       {/* 超时时间 */}
       <Input
-        label={t('servers.timeout', '连接超时（秒）')}
+        label={t('servers.timeout', '请求超时（秒）')}
         type='number'
         value={formData.timeout.toString()}
         onChange={e => handleFieldChange('timeout', parseInt(e.target.value) || 30)}
         onBlur={() => handleFieldBlur('timeout')}
         isInvalid={touched.timeout && !!errors.timeout}
         errorMessage={touched.timeout && errors.timeout}
-        description={!errors.timeout ? t('servers.timeoutDescription', '连接超时时间，默认30秒') : undefined}
+        description={!errors.timeout ? t('servers.timeoutDescription', '单个请求的最长时间，不限制文件传输，默认30秒') : undefined}
         min={1}
         max={300}
       />
@@ -15,14 +15,7 @@ import {
   updateConfig,
   watchConfig,
 } from '@/utils/store'
-import type {
-  AppConfig,
-  ConfigUpdate,
-  SyncFolderConfig,
-  SyncFolderUpdate,
-  WebDavServerConfig,
-  WebDavServerUpdate,
-} from '@/types/config'
+import type { AppConfig, ConfigUpdate, SyncFolderConfig, SyncFolderUpdate } from '@/types/config'
 
 /**
  * 配置管理 Hook 返回类型
@@ -326,72 +319,3 @@ export function useSyncFolders(): UseSyncFoldersReturn {
     loading,
   }
 }
-
-/**
- * WebDAV 服务器 Hook 返回类型
- */
-interface UseWebDavServersReturn {
-  webdavServers: WebDavServerConfig[]
-  addServer: (serverConfig: WebDavServerConfig) => Promise<void>
-  updateServer: (id: string, updates: WebDavServerUpdate) => Promise<void>
-  removeServer: (id: string) => Promise<void>
-  loading: boolean
-}
-
-/**
- * WebDAV 服务器配置 Hook
- */
-export function useWebDavServers(): UseWebDavServersReturn {
-  const { config, update, loading } = useConfig()
-  const webdavServers = config?.webdavServers || []
-
-  // 添加服务器
-  const addServer = useCallback(
-    async (serverConfig: WebDavServerConfig) => {
-      if (!config) return
-
-      const newConfig: AppConfig = {
-        ...config,
-        webdavServers: [...config.webdavServers, serverConfig],
-      }
-      await update(newConfig)
-    },
-    [config, update]
-  )
-
-  // 更新服务器
-  const updateServer = useCallback(
-    async (id: string, updates: WebDavServerUpdate) => {
-      if (!config) return
-
-      const newConfig: AppConfig = {
-        ...config,
-        webdavServers: config.webdavServers.map(server => (server.id === id ? { ...server, ...updates } : server)),
-      }
-      await update(newConfig)
-    },
-    [config, update]
-  )
-
-  // 删除服务器
-  const removeServer = useCallback(
-    async (id: string) => {
-      if (!config) return
-
-      const newConfig: AppConfig = {
-        ...config,
-        webdavServers: config.webdavServers.filter(server => server.id !== id),
-      }
-      await update(newConfig)
-    },
-    [config, update]
-  )
-
-  return {
-    webdavServers,
-    addServer,
-    updateServer,
-    removeServer,
-    loading,
-  }
-}
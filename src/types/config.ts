@@ -18,10 +18,62 @@ export interface AppConfig {
   autoStart: boolean
   /** 是否最小化到系统托盘 */
   minimizeToTray: boolean
+  /** 回收站保留天数（0 表示永久保留） */
+  trashRetentionDays: number
+  /** 每个文件最多保留的历史版本数（0 表示不保存历史版本） */
+  maxVersionsPerFile: number
+  /** 同步历史（日志与会话）保留天数（0 表示永久保留） */
+  logRetentionDays: number
+  /** 归档的服务器保留天数，超过后永久删除（0 表示永久保留） */
+  serverArchiveRetentionDays: number
+  /** 是否全局暂停同步 */
+  syncPaused: boolean
+  /** 已暂停同步的文件夹 ID */
+  pausedFolders: string[]
+  /** 传输完成后是否校验完整性 */
+  verifyTransfers: boolean
+  /** 上传前是否锁定远程文件 */
+  lockUploads: boolean
+  /** 并行分段下载阈值（MB，0 表示不启用） */
+  parallelDownloadThresholdMb: number
+  /** 是否定期发送请求保持与服务器的连接（避免 NAT 超时） */
+  keepAlive: boolean
+  /** 是否记录 WebDAV 请求跟踪 */
+  webdavTrace: boolean
+  /** 本地文件最后修改后需要保持不变的时间（秒） */
+  fileQuietPeriodSecs: number
+  /** 传输优先窗口（分钟），最后修改时间在窗口内的文件先传输（0 表示不调整顺序） */
+  priorityWindowMins: number
+  /** 服务器上的文件名在本地不合法时的处理策略 */
+  reservedNamePolicy: 'rename' | 'percent-encode' | 'skip'
+  /** 仅大小写不同的远程文件在本地冲突时的处理策略 */
+  caseCollisionPolicy: 'rename' | 'skip'
+  /** 桌面通知设置 */
+  notifications: NotificationConfig
+  /** 自动同步策略（未单独设置策略的同步文件夹使用） */
+  syncPolicy: SyncPolicyConfig
+  /** 设置主密码后无操作多久自动锁定（分钟，0 表示不自动锁定） */
+  autoLockMinutes: number
+  /** 是否加密保存配置文件（密钥保存在系统 Keyring） */
+  encryptConfig: boolean
   /** 同步文件夹配置列表 */
   syncFolders: SyncFolderConfig[]
-  /** WebDAV 服务器配置列表 */
-  webdavServers: WebDavServerConfig[]
+}
+
+/**
+ * 桌面通知设置
+ */
+export interface NotificationConfig {
+  /** 是否启用桌面通知 */
+  enabled: boolean
+  /** 同步完成时是否通知（无变更的同步不通知） */
+  syncCompleted: boolean
+  /** 同步失败时是否通知（认证失败、存储空间不足） */
+  errors: boolean
+  /** 检测到冲突时是否通知 */
+  conflicts: boolean
+  /** 不发送通知的同步文件夹 ID */
+  mutedFolders: string[]
 }
 
 /**
@@ -48,26 +100,154 @@ export interface SyncFolderConfig {
   ignorePatterns: string[]
   /** 冲突解决策略（ask, local-wins, remote-wins, newer-wins） */
   conflictResolution: 'ask' | 'local-wins' | 'remote-wins' | 'newer-wins'
+  /** 选择性同步：排除的远程子文件夹（相对于 remotePath） */
+  selectiveExclusions: string[]
+  /** 符号链接处理策略（skip, follow, sync-as-placeholder） */
+  symlinkPolicy: 'skip' | 'follow' | 'sync-as-placeholder'
+  /** 端到端加密设置 */
+  encryption: FolderEncryptionConfig
+  /** 上传压缩设置 */
+  compression: FolderCompressionConfig
+  /** 单次同步最多删除的文件比例（0~1，任一侧超过时需要确认同步计划） */
+  maxDeleteRatio: number
+  /** 首次同步策略（本地与远程都已有文件时如何合并） */
+  firstSyncStrategy: 'merge-keep-both' | 'prefer-local' | 'prefer-remote' | 'mirror'
+  /** 单个文件的大小上限（字节，超过的文件不上传也不下载，为空表示不限制） */
+  maxFileSize: number | null
+  /** 只同步这些扩展名的文件（不含点，为空表示不限制） */
+  includeExtensions: string[]
+  /** 不同步这些扩展名的文件（不含点） */
+  excludeExtensions: string[]
+  /** 是否忽略操作系统生成的元数据文件（.DS_Store、Thumbs.db、AppleDouble ._* 附属文件等） */
+  ignoreSystemFiles: boolean
+  /** 文件夹单独设置的自动同步策略（为空时使用全局策略） */
+  syncPolicy?: SyncPolicyConfig | null
 }
 
 /**
- * WebDAV 服务器配置
+ * 同步文件夹的端到端加密设置（口令保存在系统 Keyring 中）
  */
-export interface WebDavServerConfig {
-  /** 服务器 ID */
-  id: string
-  /** 服务器名称 */
-  name: string
-  /** 服务器 URL */
-  url: string
-  /** 用户名 */
-  username: string
-  /** 是否使用 HTTPS */
-  useHttps: boolean
-  /** 连接超时（秒） */
-  timeout: number
+export interface FolderEncryptionConfig {
+  /** 是否在上传前加密文件 */
+  enabled: boolean
+  /** 是否混淆远程文件名（仅在首次创建远程同步清单时生效） */
+  obfuscateFilenames: boolean
+}
+
+/**
+ * 同步文件夹的上传压缩设置（下载时根据压缩标记自动解压）
+ */
+export interface FolderCompressionConfig {
+  /** 是否在上传前压缩匹配的文件 */
+  enabled: boolean
+  /** 压缩算法 */
+  algorithm: 'zstd' | 'gzip'
+  /** 要压缩的扩展名分组 */
+  groups: Array<'text' | 'code' | 'data' | 'document'>
+  /** 分组之外额外要压缩的扩展名 */
+  extensions: string[]
+  /** 最小文件大小（字节），更小的文件不压缩 */
+  minSize: number
 }
 
+/**
+ * 保留期限设置
+ */
+export interface RetentionSettings {
+  /** 回收站保留天数（0 表示永久保留） */
+  trashRetentionDays: number
+  /** 每个文件最多保留的历史版本数（0 表示不保存历史版本） */
+  maxVersionsPerFile: number
+  /** 同步历史保留天数（0 表示永久保留） */
+  logRetentionDays: number
+  /** 归档的服务器保留天数（0 表示永久保留） */
+  serverArchiveRetentionDays: number
+}
+
+/**
+ * 传输设置
+ */
+export interface TransferSettings {
+  /** 传输完成后是否校验完整性 */
+  verifyTransfers: boolean
+  /** 上传前是否锁定远程文件 */
+  lockUploads: boolean
+  /** 并行分段下载阈值（MB，0 表示不启用） */
+  parallelDownloadThresholdMb: number
+  /** 是否定期保持与最近使用的服务器的连接 */
+  keepAlive: boolean
+  /** 是否记录 WebDAV 请求跟踪 */
+  webdavTrace: boolean
+  /** 本地文件最后修改后需要保持不变的时间（秒） */
+  fileQuietPeriodSecs: number
+  /** 传输优先窗口（分钟），最后修改时间在窗口内的文件先传输（0 表示不调整顺序） */
+  priorityWindowMins: number
+  /** 服务器上的文件名在本地不合法时的处理策略 */
+  reservedNamePolicy: 'rename' | 'percent-encode' | 'skip'
+  /** 仅大小写不同的远程文件在本地冲突时的处理策略 */
+  caseCollisionPolicy: 'rename' | 'skip'
+}
+
+/**
+ * 配置文件加密状态
+ */
+export interface ConfigEncryptionStatus {
+  /** 是否开启了配置文件加密 */
+  enabled: boolean
+  /** 配置文件当前是否已加密保存 */
+  encrypted: boolean
+}
+
+/**
+ * 同步文件夹设置的部分更新（未提供的字段保持不变，可为空的字段传入 null 表示清除）
+ */
+export type SyncFolderSettings = Partial<
+  Pick<
+    SyncFolderConfig,
+    | 'name'
+    | 'syncDirection'
+    | 'syncInterval'
+    | 'autoSync'
+    | 'ignorePatterns'
+    | 'conflictResolution'
+    | 'symlinkPolicy'
+    | 'compression'
+    | 'maxDeleteRatio'
+    | 'maxFileSize'
+    | 'includeExtensions'
+    | 'excludeExtensions'
+    | 'ignoreSystemFiles'
+    | 'syncPolicy'
+  >
+>
+
+/**
+ * 自动同步策略（只限制自动触发的同步，手动同步不受影响）
+ */
+export interface SyncPolicyConfig {
+  /** 按流量计费的网络上是否暂停自动同步 */
+  pauseOnMetered: boolean
+  /** 使用电池供电且电量低于该百分比时暂停自动同步（为空表示不限制） */
+  minBatteryPercent: number | null
+  /** 暂停自动同步的时段（本地时间，为空表示不限制） */
+  quietHours: QuietHours | null
+}
+
+/**
+ * 暂停自动同步的时段
+ */
+export interface QuietHours {
+  /** 开始时间（HH:MM） */
+  start: string
+  /** 结束时间（HH:MM，早于开始时间表示跨越午夜） */
+  end: string
+}
+
+/**
+ * 跳过自动同步的原因
+ */
+export type SyncSkipReason = 'metered-network' | 'low-battery' | 'quiet-hours'
+
 /**
  * 配置更新对象（部分更新）
  */
@@ -77,8 +257,3 @@ export type ConfigUpdate = Partial<AppConfig>
  * 同步文件夹更新对象（部分更新）
  */
 export type SyncFolderUpdate = Partial<SyncFolderConfig>
-
-/**
- * WebDAV 服务器更新对象（部分更新）
- */
-export type WebDavServerUpdate = Partial<WebDavServerConfig>
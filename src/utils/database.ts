@@ -5,6 +5,7 @@
  * 使用 @tauri-apps/plugin-sql 直接操作 SQLite 数据库
  */
 
+import { invoke } from '@tauri-apps/api/core'
 import Database from '@tauri-apps/plugin-sql'
 
 // 数据库连接单例
@@ -32,11 +33,14 @@ export interface FileMetadata {
   synced_at?: number
   sync_folder_id: number
   is_directory: boolean
-  status: 'pending' | 'synced' | 'conflict' | 'error'
+  status: 'pending' | 'synced' | 'conflict' | 'error' | 'ignored' | 'pinned'
   created_at?: number
   updated_at?: number
 }
 
+/** 单个文件的同步状态（ignored 不同步，pinned 始终保留本地，normal 恢复正常同步） */
+export type FileSyncState = 'ignored' | 'pinned' | 'normal'
+
 export interface SyncLog {
   id?: number
   sync_folder_id: number
@@ -46,13 +50,15 @@ export interface SyncLog {
   error_message?: string
   file_size?: number
   duration_ms?: number
+  /** 传输的平均速度（字节/秒），仅完成的上传、下载记录 */
+  bytes_per_sec?: number
   created_at?: number
 }
 
 export interface SyncSession {
   id?: number
   sync_folder_id: number
-  status: 'running' | 'completed' | 'failed' | 'cancelled'
+  status: 'running' | 'completed' | 'paused' | 'failed' | 'cancelled' | 'interrupted'
   started_at: number
   completed_at?: number
   files_uploaded: number
@@ -328,35 +334,23 @@ export async function cleanupOldLogs(daysToKeep: number): Promise<number> {
 
 /**
  * 获取数据库统计信息
+ * 由后端统计，包含数据库文件在磁盘上的大小
  */
 export async function getDatabaseStats(): Promise<DatabaseStats> {
-  const database = await getDatabase()
-
-  const [filesResult] = await database.select<[{ total: number }]>('SELECT COUNT(*) as total FROM file_metadata')
-
-  const [logsResult] = await database.select<[{ total: number }]>('SELECT COUNT(*) as total FROM sync_logs')
-
-  const [sessionsResult] = await database.select<[{ total: number }]>('SELECT COUNT(*) as total FROM sync_sessions')
-
-  const [pendingResult] = await database.select<[{ total: number }]>(
-    "SELECT COUNT(*) as total FROM file_metadata WHERE status = 'pending'"
-  )
-
-  const [syncedResult] = await database.select<[{ total: number }]>(
-    "SELECT COUNT(*) as total FROM file_metadata WHERE status = 'synced'"
-  )
+  return invoke<DatabaseStats>('get_database_stats')
+}
 
-  const [conflictResult] = await database.select<[{ total: number }]>(
-    "SELECT COUNT(*) as total FROM file_metadata WHERE status = 'conflict'"
-  )
+/**
+ * 设置单个文件的同步状态
+ * 下次同步时生效
+ */
+export async function setFileSyncState(folderId: string, relativePath: string, state: FileSyncState): Promise<void> {
+  await invoke('set_file_sync_state', { folderId, relativePath, state })
+}
 
-  return {
-    total_files: filesResult.total,
-    total_logs: logsResult.total,
-    total_sessions: sessionsResult.total,
-    pending_files: pendingResult.total,
-    synced_files: syncedResult.total,
-    conflict_files: conflictResult.total,
-    database_size_bytes: 0, // SQLite 不容易获取数据库大小
-  }
+/**
+ * 获取同步文件夹下设置了同步状态（ignored、pinned）的文件，用于显示文件标记
+ */
+export async function getFileSyncStates(folderId: string): Promise<FileMetadata[]> {
+  return invoke<FileMetadata[]>('get_file_sync_states', { folderId })
 }
@@ -0,0 +1,101 @@
+/**
+ * LightSync 后端错误工具模块
+ *
+ * 后端命令失败时返回结构化的错误对象（SyncError 序列化结果），
+ * 前端应根据 errorCode 判断错误类型，message 仅用于展示
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+
+// ==================== 类型定义 ====================
+
+/**
+ * 后端错误码
+ */
+export type ErrorCode =
+  | 'IO_ERROR'
+  | 'WEBDAV_ERROR'
+  | 'NETWORK_ERROR'
+  | 'SERIALIZATION_ERROR'
+  | 'TAURI_ERROR'
+  | 'SYNC_CONFLICT'
+  | 'AUTH_FAILED'
+  | 'FILE_NOT_FOUND'
+  | 'NOT_FOUND'
+  | 'CONFIG_ERROR'
+  | 'VALIDATION_ERROR'
+  | 'DATABASE_ERROR'
+  | 'KEYRING_ERROR'
+  | 'WATCHER_ERROR'
+  | 'INTEGRITY_ERROR'
+  | 'INTERRUPTED'
+  | 'CANCELLED'
+  | 'APP_LOCKED'
+  | 'DUPLICATE_SERVER'
+  | 'SYNC_ROOT_UNAVAILABLE'
+  | 'MASS_DELETION_PROTECTION'
+  | 'INSUFFICIENT_REMOTE_STORAGE'
+  | 'INSUFFICIENT_LOCAL_STORAGE'
+  | 'UNKNOWN'
+
+/**
+ * 后端错误结构
+ */
+export interface BackendError {
+  /** 错误码 */
+  errorCode: ErrorCode
+  /** 错误信息（英文） */
+  message: string
+  /** 上下文信息（如 serverId、path） */
+  context: Record<string, string>
+  /** 本地化消息键（如 errors.NOT_FOUND） */
+  messageKey: string
+  /** 本地化消息参数 */
+  params: Record<string, string>
+}
+
+// ==================== 工具函数 ====================
+
+/**
+ * 将后端错误翻译为界面语言
+ *
+ * @param error - 后端命令返回的错误
+ * @param language - 目标语言（可选，默认使用配置中的 language）
+ */
+export async function translateError(error: unknown, language?: string): Promise<string> {
+  if (!isBackendError(error)) {
+    return getErrorMessage(error)
+  }
+  try {
+    return await invoke<string>('translate_error_message', { payload: error, language })
+  } catch {
+    return error.message
+  }
+}
+
+/**
+ * 判断是否为后端返回的结构化错误
+ */
+export function isBackendError(error: unknown): error is BackendError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as BackendError).errorCode === 'string' &&
+    typeof (error as BackendError).message === 'string'
+  )
+}
+
+/**
+ * 获取错误的展示文本
+ *
+ * 兼容后端结构化错误、Error 对象和字符串
+ */
+export function getErrorMessage(error: unknown): string {
+  if (isBackendError(error)) {
+    return error.message
+  }
+  if (error instanceof Error) {
+    return error.message
+  }
+  return String(error)
+}
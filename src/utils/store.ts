@@ -6,7 +6,17 @@
 
 import { invoke } from '@tauri-apps/api/core'
 import { Store } from '@tauri-apps/plugin-store'
-import type { AppConfig, ConfigUpdate } from '@/types/config'
+import type {
+  AppConfig,
+  ConfigEncryptionStatus,
+  ConfigUpdate,
+  NotificationConfig,
+  RetentionSettings,
+  SyncFolderConfig,
+  SyncFolderSettings,
+  SyncPolicyConfig,
+  TransferSettings,
+} from '@/types/config'
 
 // 配置存储实例
 let storeInstance: Store | null = null
@@ -152,3 +162,162 @@ export async function importConfig(jsonString: string): Promise<void> {
     throw error
   }
 }
+
+/**
+ * 设置主题
+ */
+export async function setTheme(theme: AppConfig['theme']): Promise<void> {
+  try {
+    await invoke('set_theme', { theme })
+  } catch (error) {
+    console.error('Failed to set theme:', error)
+    throw error
+  }
+}
+
+/**
+ * 设置界面语言
+ */
+export async function setLanguage(language: string): Promise<void> {
+  try {
+    await invoke('set_language', { language })
+  } catch (error) {
+    console.error('Failed to set language:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取保留期限设置
+ */
+export async function getRetentionSettings(): Promise<RetentionSettings> {
+  try {
+    return await invoke<RetentionSettings>('get_retention_settings')
+  } catch (error) {
+    console.error('Failed to get retention settings:', error)
+    throw error
+  }
+}
+
+/**
+ * 修改保留期限设置
+ */
+export async function setRetentionSettings(settings: RetentionSettings): Promise<void> {
+  try {
+    await invoke('set_retention_settings', { settings })
+  } catch (error) {
+    console.error('Failed to set retention settings:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取传输设置
+ */
+export async function getTransferSettings(): Promise<TransferSettings> {
+  try {
+    return await invoke<TransferSettings>('get_transfer_settings')
+  } catch (error) {
+    console.error('Failed to get transfer settings:', error)
+    throw error
+  }
+}
+
+/**
+ * 修改传输设置
+ */
+export async function setTransferSettings(settings: TransferSettings): Promise<void> {
+  try {
+    await invoke('set_transfer_settings', { settings })
+  } catch (error) {
+    console.error('Failed to set transfer settings:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取桌面通知设置
+ */
+export async function getNotificationSettings(): Promise<NotificationConfig> {
+  try {
+    return await invoke<NotificationConfig>('get_notification_settings')
+  } catch (error) {
+    console.error('Failed to get notification settings:', error)
+    throw error
+  }
+}
+
+/**
+ * 修改桌面通知设置
+ */
+export async function setNotificationSettings(settings: NotificationConfig): Promise<void> {
+  try {
+    await invoke('set_notification_settings', { settings })
+  } catch (error) {
+    console.error('Failed to set notification settings:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取全局自动同步策略
+ */
+export async function getSyncPolicy(): Promise<SyncPolicyConfig> {
+  try {
+    return await invoke<SyncPolicyConfig>('get_sync_policy')
+  } catch (error) {
+    console.error('Failed to get sync policy:', error)
+    throw error
+  }
+}
+
+/**
+ * 修改全局自动同步策略
+ */
+export async function setSyncPolicy(policy: SyncPolicyConfig): Promise<void> {
+  try {
+    await invoke('set_sync_policy', { policy })
+  } catch (error) {
+    console.error('Failed to set sync policy:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取配置文件加密状态
+ */
+export async function getConfigEncryption(): Promise<ConfigEncryptionStatus> {
+  try {
+    return await invoke<ConfigEncryptionStatus>('get_config_encryption')
+  } catch (error) {
+    console.error('Failed to get config encryption status:', error)
+    throw error
+  }
+}
+
+/**
+ * 开启或关闭配置文件加密（开启时密钥保存在系统 Keyring）
+ */
+export async function setConfigEncryption(enabled: boolean): Promise<void> {
+  try {
+    await invoke('set_config_encryption', { enabled })
+  } catch (error) {
+    console.error('Failed to set config encryption:', error)
+    throw error
+  }
+}
+
+/**
+ * 修改同步文件夹的设置（未提供的字段保持不变）
+ */
+export async function updateSyncFolderSettings(
+  folderId: string,
+  settings: SyncFolderSettings
+): Promise<SyncFolderConfig> {
+  try {
+    return await invoke<SyncFolderConfig>('update_sync_folder_settings', { folderId, settings })
+  } catch (error) {
+    console.error('Failed to update sync folder settings:', error)
+    throw error
+  }
+}
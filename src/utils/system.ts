@@ -71,3 +71,73 @@ export async function getOsType(): Promise<string> {
     throw error
   }
 }
+
+/**
+ * 磁盘空间使用情况（字节）
+ */
+export interface DiskUsage {
+  totalBytes: number
+  availableBytes: number
+}
+
+/**
+ * 电源状态
+ */
+export interface PowerState {
+  /** 电源类型: "ac", "battery", "unknown" */
+  source: 'ac' | 'battery' | 'unknown'
+  /** 电池电量百分比（没有电池时为 null） */
+  batteryPercent: number | null
+  /** 是否正在充电（无法判断时为 null） */
+  charging: boolean | null
+}
+
+/**
+ * 网络状态
+ */
+export interface NetworkState {
+  /** 连接类型 */
+  connectionType: 'ethernet' | 'wifi' | 'cellular' | 'none' | 'unknown'
+  /** 是否按流量计费（无法判断时为 null） */
+  metered: boolean | null
+}
+
+/**
+ * 获取路径所在磁盘的空间使用情况
+ * @param path 磁盘上的任意路径
+ * @returns Promise<DiskUsage> 磁盘总容量与可用空间
+ */
+export async function getDiskUsage(path: string): Promise<DiskUsage> {
+  try {
+    return await invoke<DiskUsage>('get_disk_usage', { path })
+  } catch (error) {
+    console.error('获取磁盘空间失败:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取电源状态（外接电源或电池供电）
+ * @returns Promise<PowerState> 电源状态
+ */
+export async function getPowerState(): Promise<PowerState> {
+  try {
+    return await invoke<PowerState>('get_power_state')
+  } catch (error) {
+    console.error('获取电源状态失败:', error)
+    throw error
+  }
+}
+
+/**
+ * 获取网络状态（连接类型与是否按流量计费）
+ * @returns Promise<NetworkState> 网络状态
+ */
+export async function getNetworkType(): Promise<NetworkState> {
+  try {
+    return await invoke<NetworkState>('get_network_type')
+  } catch (error) {
+    console.error('获取网络状态失败:', error)
+    throw error
+  }
+}
@@ -6,6 +6,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core'
+import { getErrorMessage } from './error'
 
 // ==================== 类型定义 ====================
 
@@ -23,8 +24,12 @@ export interface WebDavServerConfig {
   username: string
   /** 是否使用 HTTPS */
   useHttps: boolean
-  /** 连接超时时间（秒） */
+  /** 请求超时时间（秒，单个非流式请求的总时长） */
   timeout: number
+  /** 建立连接的超时时间（秒） */
+  connectTimeout: number
+  /** 读取超时时间（秒，流式传输连续没有收发数据的最长时间） */
+  readTimeout: number
   /** 最后连接测试时间（Unix 时间戳，秒） */
   lastTestAt?: number
   /** 最后连接测试状态 */
@@ -33,6 +38,18 @@ export interface WebDavServerConfig {
   lastTestError?: string
   /** 服务器类型 */
   serverType: string
+  /** 认证方式（basic, app_password） */
+  authType: string
+  /** 是否接受无效证书（自签名证书等） */
+  acceptInvalidCerts: boolean
+  /** 固定的证书 SHA-256 指纹 */
+  pinnedCertFingerprint?: string
+  /** 每个请求附带的自定义请求头（名称 -> 值） */
+  customHeaders: Record<string, string>
+  /** 连接时使用的 IP 协议版本（any, ipv4, ipv6） */
+  ipVersion: string
+  /** 服务器主机名固定解析到的 IP 地址 */
+  resolveIp?: string
   /** 是否启用 */
   enabled: boolean
   /** 创建时间（Unix 时间戳，秒） */
@@ -51,6 +68,8 @@ export interface ConnectionTestResult {
   message: string
   /** 服务器信息（仅在成功时返回） */
   serverInfo?: ServerInfo
+  /** 服务器永久重定向到的新服务器 URL（可通过 apply_redirected_url 保存） */
+  redirectedUrl?: string
 }
 
 /**
@@ -77,8 +96,12 @@ export interface AddServerInput {
   password: string
   /** 是否使用 HTTPS */
   useHttps: boolean
-  /** 连接超时时间（秒） */
+  /** 请求超时时间（秒） */
   timeout: number
+  /** 建立连接的超时时间（秒），默认 10 */
+  connectTimeout?: number
+  /** 读取超时时间（秒），默认 60 */
+  readTimeout?: number
   /** 是否启用 */
   enabled?: boolean
 }
@@ -97,8 +120,12 @@ export interface UpdateServerInput {
   password?: string
   /** 是否使用 HTTPS */
   useHttps?: boolean
-  /** 连接超时时间（秒） */
+  /** 请求超时时间（秒） */
   timeout?: number
+  /** 建立连接的超时时间（秒） */
+  connectTimeout?: number
+  /** 读取超时时间（秒） */
+  readTimeout?: number
   /** 是否启用 */
   enabled?: boolean
 }
@@ -134,6 +161,8 @@ export async function addWebDavServer(serverData: AddServerInput): Promise<WebDa
       username: serverData.username,
       useHttps: serverData.useHttps,
       timeout: serverData.timeout,
+      connectTimeout: serverData.connectTimeout ?? 10,
+      readTimeout: serverData.readTimeout ?? 60,
       enabled: serverData.enabled ?? true,
       lastTestStatus: 'unknown',
       serverType: 'generic',
@@ -147,7 +176,7 @@ export async function addWebDavServer(serverData: AddServerInput): Promise<WebDa
     return result
   } catch (error) {
     console.error('Failed to add WebDAV server:', error)
-    throw new Error(`Failed to add WebDAV server: ${error}`)
+    throw new Error(`Failed to add WebDAV server: ${getErrorMessage(error)}`)
   }
 }
 
@@ -176,7 +205,7 @@ export async function getWebDavServers(enabledOnly: boolean = false): Promise<We
     return result
   } catch (error) {
     console.error('Failed to get WebDAV servers:', error)
-    throw new Error(`Failed to get WebDAV servers: ${error}`)
+    throw new Error(`Failed to get WebDAV servers: ${getErrorMessage(error)}`)
   }
 }
 
@@ -202,7 +231,7 @@ export async function getWebDavServer(serverId: string): Promise<WebDavServerCon
     return result
   } catch (error) {
     console.error(`Failed to get WebDAV server ${serverId}:`, error)
-    throw new Error(`Failed to get WebDAV server: ${error}`)
+    throw new Error(`Failed to get WebDAV server: ${getErrorMessage(error)}`)
   }
 }
 
@@ -247,6 +276,8 @@ export async function updateWebDavServer(serverId: string, updates: UpdateServer
       ...(updates.username !== undefined && { username: updates.username }),
       ...(updates.useHttps !== undefined && { useHttps: updates.useHttps }),
       ...(updates.timeout !== undefined && { timeout: updates.timeout }),
+      ...(updates.connectTimeout !== undefined && { connectTimeout: updates.connectTimeout }),
+      ...(updates.readTimeout !== undefined && { readTimeout: updates.readTimeout }),
       ...(updates.enabled !== undefined && { enabled: updates.enabled }),
     }
 
@@ -260,7 +291,7 @@ export async function updateWebDavServer(serverId: string, updates: UpdateServer
     return result
   } catch (error) {
     console.error(`Failed to update WebDAV server ${serverId}:`, error)
-    throw new Error(`Failed to update WebDAV server: ${error}`)
+    throw new Error(`Failed to update WebDAV server: ${getErrorMessage(error)}`)
   }
 }
 
@@ -289,7 +320,7 @@ export async function deleteWebDavServer(serverId: string): Promise<void> {
     })
   } catch (error) {
     console.error(`Failed to delete WebDAV server ${serverId}:`, error)
-    throw new Error(`Failed to delete WebDAV server: ${error}`)
+    throw new Error(`Failed to delete WebDAV server: ${getErrorMessage(error)}`)
   }
 }
 
@@ -319,7 +350,27 @@ export async function testWebDavConnection(serverId: string): Promise<Connection
     return result
   } catch (error) {
     console.error(`Failed to test WebDAV connection for ${serverId}:`, error)
-    throw new Error(`Failed to test WebDAV connection: ${error}`)
+    throw new Error(`Failed to test WebDAV connection: ${getErrorMessage(error)}`)
+  }
+}
+
+/**
+ * 将连接测试发现的重定向地址保存为服务器 URL
+ *
+ * @param serverId - 服务器 ID
+ * @param url - 新的服务器 URL（连接测试结果中的 redirectedUrl）
+ * @returns 更新后的服务器配置
+ * @throws 如果保存失败
+ */
+export async function applyRedirectedUrl(serverId: string, url: string): Promise<WebDavServerConfig> {
+  try {
+    return await invoke<WebDavServerConfig>('apply_redirected_url', {
+      serverId,
+      url,
+    })
+  } catch (error) {
+    console.error(`Failed to apply redirected URL for ${serverId}:`, error)
+    throw new Error(`Failed to apply redirected URL: ${getErrorMessage(error)}`)
   }
 }
 
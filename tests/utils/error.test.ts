@@ -0,0 +1,27 @@
+/**
+ * 后端错误工具函数测试
+ */
+
+import { describe, it, expect } from 'vitest'
+import { isBackendError, getErrorMessage } from '@/utils/error'
+
+describe('后端错误工具函数测试', () => {
+  const backendError = {
+    errorCode: 'NOT_FOUND',
+    message: 'Not found: server-1',
+    context: { serverId: 'server-1' },
+  }
+
+  it('应该识别结构化的后端错误', () => {
+    expect(isBackendError(backendError)).toBe(true)
+    expect(isBackendError('Network error')).toBe(false)
+    expect(isBackendError(null)).toBe(false)
+    expect(isBackendError(new Error('boom'))).toBe(false)
+  })
+
+  it('应该返回可展示的错误信息', () => {
+    expect(getErrorMessage(backendError)).toBe('Not found: server-1')
+    expect(getErrorMessage(new Error('boom'))).toBe('boom')
+    expect(getErrorMessage('plain string')).toBe('plain string')
+  })
+})